@@ -0,0 +1,21 @@
+//! Reads arbitrary input, checking that reading never panics and that anything that could be read is read the same way after being written.
+//!
+//! Comments are checked when reading, since by default `--` is allowed in comments that are read, which can't be written.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use xmlity::value::XmlElement;
+use xmlity_quick_xml::ReaderOptions;
+
+fuzz_target!(|input: &str| {
+    let options = ReaderOptions::new().with_check_comments(true);
+    let Ok(read) = xmlity_quick_xml::from_str_with_options::<XmlElement>(input, &options) else {
+        return;
+    };
+
+    let written = xmlity_quick_xml::to_string(&read).expect("read documents can be written");
+    let reread: XmlElement = xmlity_quick_xml::from_str(&written)
+        .unwrap_or_else(|error| panic!("failed to read {written:?}: {error}"));
+
+    assert_eq!(read, reread, "{input:?} was rewritten as {written:?}");
+});
@@ -0,0 +1,19 @@
+//! Generates documents, writes them and reads them back, checking that reading what was written gives the same document every time.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use xmlity::value::XmlElement;
+
+fuzz_target!(|element: XmlElement| {
+    let written =
+        xmlity_quick_xml::to_string(&element).expect("generated documents can be written");
+
+    // Adjacent text is merged when read, so the generated document itself may differ from what is read back.
+    let read: XmlElement = xmlity_quick_xml::from_str(&written)
+        .unwrap_or_else(|error| panic!("failed to read {written:?}: {error}"));
+    let rewritten = xmlity_quick_xml::to_string(&read).expect("read documents can be written");
+    let reread: XmlElement = xmlity_quick_xml::from_str(&rewritten)
+        .unwrap_or_else(|error| panic!("failed to read {rewritten:?}: {error}"));
+
+    assert_eq!(read, reread, "{written:?} was rewritten as {rewritten:?}");
+});
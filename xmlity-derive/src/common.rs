@@ -8,7 +8,13 @@ use syn::{parse_quote, Expr, Ident, Index, ItemStruct, Stmt, Type, Visibility};
 
 use crate::{
     derive::{DeriveError, DeriveResult},
-    options::{records::fields::FieldOpts, FieldWithOpts},
+    options::{
+        records::{
+            fields::{FieldOpts, GroupOpts},
+            roots::{RootElementOpts, RootValueOpts},
+        },
+        FieldWithOpts, XmlnsDeclarations,
+    },
 };
 
 #[derive(Clone)]
@@ -82,15 +88,30 @@ impl LocalName<'_> {
 
 impl FromMeta for LocalName<'_> {
     fn from_string(value: &str) -> darling::Result<Self> {
-        // TODO: Validate local name
+        // Qualified names are split into prefix and local name once the `#[xmlns]` declarations are known.
+        let valid = match value.split_once(':') {
+            Some((prefix, local_name)) => is_valid_name(prefix) && is_valid_name(local_name),
+            None => is_valid_name(value),
+        };
+        if !valid {
+            return Err(darling::Error::custom(format!(
+                "`{value}` is not a valid XML name"
+            )));
+        }
         Ok(LocalName(Cow::Owned(value.to_owned())))
     }
 }
 
+// Names are built in const items, so they are only created once. The const functions of `::xmlity::__private` validate them again, which can only fail for names the derive has not validated itself.
 impl ToTokens for LocalName<'_> {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         let name = &self.0;
-        tokens.extend(quote::quote! { ::xmlity::LocalName::new(#name).expect("XML name in derive macro is invalid. This is a bug in xmlity. Please report it.") })
+        tokens.extend(quote::quote! {
+            {
+                const __LOCAL_NAME: &::xmlity::LocalName = ::xmlity::__private::local_name(#name);
+                __LOCAL_NAME
+            }
+        })
     }
 }
 
@@ -105,7 +126,6 @@ impl XmlNamespace<'_> {
 
 impl FromMeta for XmlNamespace<'_> {
     fn from_string(value: &str) -> darling::Result<Self> {
-        // TODO: Validate namespace
         Ok(XmlNamespace(Cow::Owned(value.to_owned())))
     }
 }
@@ -113,7 +133,12 @@ impl FromMeta for XmlNamespace<'_> {
 impl ToTokens for XmlNamespace<'_> {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         let namespace = &self.0;
-        tokens.extend(quote::quote! { ::xmlity::XmlNamespace::new(#namespace).expect("XML namespace in derive macro is invalid. This is a bug in xmlity. Please report it.") })
+        tokens.extend(quote::quote! {
+            {
+                const __NAMESPACE: &::xmlity::XmlNamespace = ::xmlity::__private::xml_namespace(#namespace);
+                __NAMESPACE
+            }
+        })
     }
 }
 
@@ -122,7 +147,11 @@ pub struct Prefix<'a>(pub Cow<'a, str>);
 
 impl FromMeta for Prefix<'_> {
     fn from_string(value: &str) -> darling::Result<Self> {
-        // TODO: Validate prefix
+        if !is_valid_name(value) {
+            return Err(darling::Error::custom(format!(
+                "`{value}` is not a valid XML prefix"
+            )));
+        }
         Ok(Prefix(Cow::Owned(value.to_owned())))
     }
 }
@@ -130,10 +159,33 @@ impl FromMeta for Prefix<'_> {
 impl ToTokens for Prefix<'_> {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         let prefix = &self.0;
-        tokens.extend(quote::quote! { ::xmlity::Prefix::new(#prefix).expect("XML prefix in derive macro is invalid. This is a bug in xmlity. Please report it.") })
+        tokens.extend(quote::quote! {
+            {
+                const __PREFIX: &::xmlity::Prefix = ::xmlity::__private::prefix(#prefix);
+                __PREFIX
+            }
+        })
     }
 }
 
+// Reference: https://www.w3.org/TR/xml/#sec-common-syn, without ":" which separates prefixes from local names.
+fn is_name_start_char(c: char) -> bool {
+    matches!(
+        c, 'A'..='Z' | '_' | 'a'..='z' | '\u{00C0}'..='\u{00D6}' | '\u{00D8}'..='\u{00F6}' | '\u{00F8}'..='\u{02FF}' | '\u{0370}'..='\u{037D}' | '\u{037F}'..='\u{1FFF}' | '\u{200C}'..='\u{200D}' | '\u{2070}'..='\u{218F}' | '\u{2C00}'..='\u{2FEF}' | '\u{3001}'..='\u{D7FF}' | '\u{F900}'..='\u{FDCF}' | '\u{FDF0}'..='\u{FFFD}' | '\u{10000}'..='\u{EFFFF}'
+    )
+}
+
+fn is_name_char(c: char) -> bool {
+    is_name_start_char(c)
+        || matches!(c, '-' | '.' | '0'..='9' | '\u{00B7}' | '\u{0300}'..='\u{036F}' | '\u{203F}'..='\u{2040}')
+}
+
+/// Whether `name` is an XML name that `::xmlity::LocalName::new` and `::xmlity::Prefix::new` accept.
+pub(crate) fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(is_name_start_char) && chars.all(is_name_char)
+}
+
 #[derive(Debug, Clone)]
 pub struct ExpandedName<'a> {
     name: LocalName<'a>,
@@ -170,8 +222,17 @@ impl<'a> ExpandedName<'a> {
             None => parse_quote! { ::core::option::Option::None },
         };
 
-        parse_quote! {
-            ::xmlity::ExpandedName::new(#name, #xml_namespace)
+        let expanded_name = quote! { ::xmlity::ExpandedName::new(#name, #xml_namespace) };
+
+        // Names with static parts are built once at compile time instead of on every call.
+        match namespace {
+            Some(XmlNamespaceRef::Dynamic(_)) => parse_quote! { #expanded_name },
+            _ => parse_quote! {
+                {
+                    const __NAME: ::xmlity::ExpandedName<'static> = #expanded_name;
+                    __NAME
+                }
+            },
         }
     }
 }
@@ -182,19 +243,96 @@ impl quote::ToTokens for ExpandedName<'_> {
     }
 }
 
+/// The generics as arguments of a type, like `<'a, T, N>` for `<'a, T: Trait, const N: usize>`.
 pub fn non_bound_generics(generics: &syn::Generics) -> syn::Generics {
     let mut non_bound_generics = generics.to_owned();
     non_bound_generics.where_clause = None;
-    non_bound_generics
-        .lifetimes_mut()
-        .for_each(|a| a.bounds.clear());
-    non_bound_generics
-        .type_params_mut()
-        .for_each(|a| a.bounds.clear());
+    for param in non_bound_generics.params.iter_mut() {
+        match param {
+            syn::GenericParam::Lifetime(lifetime) => lifetime.bounds.clear(),
+            syn::GenericParam::Type(ty) => {
+                ty.bounds.clear();
+                ty.eq_token = None;
+                ty.default = None;
+            }
+            // Const arguments are written like type arguments.
+            syn::GenericParam::Const(constant) => {
+                *param = syn::GenericParam::Type(syn::TypeParam::from(constant.ident.clone()))
+            }
+        }
+    }
 
     non_bound_generics
 }
 
+/// Generics of a helper type defined inside the implementation for a type with `generics`, such as the wrapper of a field with its own name.
+///
+/// Nested items can't use the generics of the items around them, so the helper takes all of them itself. This way the type of the field and expressions in its options, where `Self` refers to the type, can be used inside of the helper. `lifetime` is put in front for helpers borrowing the field.
+pub fn nested_generics(
+    generics: &syn::Generics,
+    lifetime: Option<&syn::Lifetime>,
+) -> syn::Generics {
+    let mut nested_generics = generics.to_owned();
+    if let Some(lifetime) = lifetime {
+        nested_generics.params.insert(
+            0,
+            syn::GenericParam::Lifetime(syn::LifetimeParam::new(lifetime.clone())),
+        );
+    }
+    nested_generics
+}
+
+/// Puts the lifetime of the input being deserialized in front of `generics`, bounded to outlive all lifetimes of `generics`. This lets fields borrow from the input, such as fields of types like `&'a str` and `Cow<'a, str>`.
+pub fn insert_deserialize_lifetime(generics: &mut syn::Generics, lifetime: &syn::Lifetime) {
+    let mut param = syn::LifetimeParam::new(lifetime.clone());
+    param.bounds = generics
+        .lifetimes()
+        .map(|param| param.lifetime.clone())
+        .collect();
+    generics
+        .params
+        .insert(0, syn::GenericParam::Lifetime(param));
+}
+
+/// Generic arguments naming a helper type with [`nested_generics`], for expressions where they can't be inferred. A borrowed `lifetime` is left for the compiler to infer.
+pub fn nested_generics_turbofish(generics: &syn::Generics, lifetime: bool) -> TokenStream {
+    let lifetime = lifetime.then(|| quote!('_));
+    let params = generics.params.iter().map(|param| match param {
+        syn::GenericParam::Lifetime(param) => param.lifetime.to_token_stream(),
+        syn::GenericParam::Type(param) => param.ident.to_token_stream(),
+        syn::GenericParam::Const(param) => param.ident.to_token_stream(),
+    });
+    let args = lifetime.into_iter().chain(params).collect::<Vec<_>>();
+
+    if generics.params.is_empty() {
+        TokenStream::new()
+    } else {
+        quote!(::<#(#args),*>)
+    }
+}
+
+/// A field using all type and lifetime parameters of `generics`, since a helper with [`nested_generics`] has to use them even if the type of the field it wraps does not. It is a [`PhantomData`](::core::marker::PhantomData), which is an empty group.
+pub fn generics_marker_field(generics: &syn::Generics) -> Option<FieldWithOpts<Ident, FieldOpts>> {
+    let used = generics
+        .params
+        .iter()
+        .filter_map(|param| match param {
+            syn::GenericParam::Lifetime(param) => {
+                let lifetime = &param.lifetime;
+                Some(quote!(&#lifetime ()))
+            }
+            syn::GenericParam::Type(param) => Some(param.ident.to_token_stream()),
+            syn::GenericParam::Const(_) => None,
+        })
+        .collect::<Vec<_>>();
+
+    (!used.is_empty()).then(|| FieldWithOpts {
+        field_ident: Ident::new("__marker", Span::call_site()),
+        field_type: parse_quote!(::core::marker::PhantomData<fn() -> (#(#used,)*)>),
+        options: FieldOpts::Group(GroupOpts::default()),
+    })
+}
+
 #[derive(Clone, Copy, PartialEq)]
 pub enum StructType {
     Named,
@@ -226,30 +364,66 @@ pub struct RecordInput<'a, T: Fn(syn::Expr) -> syn::Expr> {
     pub fallable_deconstruction: bool,
 }
 
+impl<T: Fn(syn::Expr) -> syn::Expr> RecordInput<'_, T> {
+    /// The only field of the record and the kind of struct it is in, for `#[xvalue(transparent)]`.
+    pub fn transparent_field(
+        &self,
+    ) -> Result<(FieldWithOpts<FieldIdent, FieldOpts>, StructType), DeriveError> {
+        match &self.fields {
+            StructTypeWithFields::Named(fields) if fields.len() == 1 => Ok((
+                fields[0].clone().map_ident(FieldIdent::Named),
+                StructType::Named,
+            )),
+            StructTypeWithFields::Unnamed(fields) if fields.len() == 1 => Ok((
+                fields[0].clone().map_ident(FieldIdent::Indexed),
+                StructType::Unnamed,
+            )),
+            _ => Err(DeriveError::custom(
+                "transparent can only be used on structs with exactly one field",
+            )),
+        }
+    }
+}
+
 #[allow(clippy::type_complexity)]
 pub fn fields_with_opts(
     fields: &syn::Fields,
+    attrs: &[syn::Attribute],
 ) -> DeriveResult<
     StructTypeWithFields<
         Vec<FieldWithOpts<syn::Ident, FieldOpts>>,
         Vec<FieldWithOpts<syn::Index, FieldOpts>>,
     >,
 > {
-    match fields {
-        syn::Fields::Named(fields) => fields
-            .named
-            .iter()
-            .map(|f| {
-                let field_ident = f.ident.clone().expect("Named struct");
+    let xmlns = XmlnsDeclarations::parse(attrs)?;
+    let uses_with = RootValueOpts::parse_uses_with(attrs)?;
+    let rename_all = RootElementOpts::parse_rename_all(attrs)?;
 
-                DeriveResult::Ok(FieldWithOpts {
-                    field_ident,
-                    options: FieldOpts::from_field(f)?,
-                    field_type: f.ty.clone(),
+    match fields {
+        syn::Fields::Named(fields) => {
+            let fields = fields
+                .named
+                .iter()
+                .map(|f| {
+                    let field_ident = f.ident.clone().expect("Named struct");
+
+                    DeriveResult::Ok(FieldWithOpts {
+                        field_ident,
+                        options: FieldOpts::from_field(f, &xmlns, rename_all, uses_with)?,
+                        field_type: f.ty.clone(),
+                    })
                 })
-            })
-            .collect::<Result<Vec<_>, DeriveError>>()
-            .map(StructTypeWithFields::Named),
+                .collect::<Result<Vec<_>, DeriveError>>()?;
+
+            check_default_from_fields(fields.iter().map(|field| {
+                (
+                    Cow::Borrowed(&field.field_ident),
+                    field.options.default_from(),
+                )
+            }))?;
+
+            Ok(StructTypeWithFields::Named(fields))
+        }
         syn::Fields::Unnamed(fields) => fields
             .unnamed
             .iter()
@@ -257,12 +431,24 @@ pub fn fields_with_opts(
             .map(|(i, f)| {
                 DeriveResult::Ok(FieldWithOpts {
                     field_ident: syn::Index::from(i),
-                    options: FieldOpts::from_field(f)?,
+                    options: FieldOpts::from_field(f, &xmlns, rename_all, uses_with)?,
                     field_type: f.ty.clone(),
                 })
             })
             .collect::<Result<Vec<_>, DeriveError>>()
-            .map(StructTypeWithFields::Unnamed),
+            .and_then(|fields| {
+                check_default_from_fields(fields.iter().map(|field| {
+                    (
+                        Cow::Owned(
+                            FieldIdent::Indexed(field.field_ident.clone())
+                                .to_named_ident()
+                                .into_owned(),
+                        ),
+                        field.options.default_from(),
+                    )
+                }))?;
+                Ok(StructTypeWithFields::Unnamed(fields))
+            }),
         _ => Ok(StructTypeWithFields::Unit),
     }
 }
@@ -280,7 +466,7 @@ pub fn parse_struct_derive_input(
         record_path: Cow::Owned(parse_quote!(self)),
         wrapper_function: std::convert::identity,
         fields: match &input.data {
-            syn::Data::Struct(data_struct) => fields_with_opts(&data_struct.fields)?,
+            syn::Data::Struct(data_struct) => fields_with_opts(&data_struct.fields, &input.attrs)?,
             _ => panic!("Wrong options. Only structs can be used for xelement."),
         },
         fallable_deconstruction: false,
@@ -319,7 +505,7 @@ pub fn parse_enum_variant_derive_input<'a>(
         generics: Cow::Borrowed(enum_generics),
         wrapper_function,
         record_path: Cow::Owned(parse_quote!(self.#sub_value_ident2)),
-        fields: fields_with_opts(&variant.fields)?,
+        fields: fields_with_opts(&variant.fields, &variant.attrs)?,
         fallable_deconstruction: fallible_enum,
         sub_path_ident: Some(sub_value_ident2),
     })
@@ -379,6 +565,117 @@ pub fn constructor_expr<T: ToTokens>(
     }
 }
 
+/// The local the value of a field is bound to by [`ordered_constructor_expr`], which fields with `default_from` clone their default from.
+pub fn field_value_local(field: &Ident) -> Ident {
+    quote::format_ident!("__{}_value", field)
+}
+
+/// Like [`constructor_expr`], but if any field defaults from another field (`default_from`), the values are first bound to locals, with each field bound after the field it defaults from.
+///
+/// Fields that default from other fields must have been checked with [`check_default_from_fields`], which rules out unknown fields and cycles.
+pub fn ordered_constructor_expr<T: ToTokens>(
+    ident: &syn::Path,
+    fields: impl IntoIterator<Item = (FieldIdent, T)>,
+    default_from: impl Fn(&Ident) -> Option<Ident>,
+    constructor_type: &StructType,
+) -> Expr {
+    let fields = fields
+        .into_iter()
+        .map(|(field_ident, value)| {
+            let default_from = default_from(&field_ident.to_named_ident());
+            (field_ident, value, default_from)
+        })
+        .collect::<Vec<_>>();
+    if fields
+        .iter()
+        .all(|(_, _, default_from)| default_from.is_none())
+    {
+        return constructor_expr(
+            ident,
+            fields
+                .into_iter()
+                .map(|(field_ident, value, _)| (field_ident, value)),
+            constructor_type,
+        );
+    }
+
+    let mut bound: Vec<Ident> = Vec::new();
+    let mut bindings: Vec<Stmt> = Vec::new();
+    while bound.len() < fields.len() {
+        let bound_before = bound.len();
+        for (field_ident, value, default_from) in &fields {
+            let name = field_ident.to_named_ident().into_owned();
+            let ready = default_from
+                .as_ref()
+                .is_none_or(|default_from| bound.contains(default_from));
+            if bound.contains(&name) || !ready {
+                continue;
+            }
+
+            let local = field_value_local(&name);
+            bindings.push(parse_quote! {
+                let #local = #value;
+            });
+            bound.push(name);
+        }
+        assert!(
+            bound.len() > bound_before,
+            "`default_from` cycles should have been rejected"
+        );
+    }
+
+    let constructor = constructor_expr(
+        ident,
+        fields.iter().map(|(field_ident, _, _)| {
+            let local = field_value_local(&field_ident.to_named_ident());
+            (field_ident.clone(), local)
+        }),
+        constructor_type,
+    );
+
+    parse_quote! {
+        {
+            #(#bindings)*
+            #constructor
+        }
+    }
+}
+
+/// Checks that the fields referred to by `default_from` exist and that they do not default from each other in a cycle.
+pub fn check_default_from_fields<'a>(
+    fields: impl IntoIterator<Item = (Cow<'a, Ident>, Option<&'a Ident>)>,
+) -> DeriveResult<()> {
+    let fields = fields.into_iter().collect::<Vec<_>>();
+    let default_from = |field: &Ident| {
+        fields
+            .iter()
+            .find(|(name, _)| **name == *field)
+            .and_then(|(_, default_from)| *default_from)
+    };
+
+    for (name, mut next) in fields.iter().map(|(name, next)| (name, *next)) {
+        let mut steps = 0;
+        while let Some(field) = next {
+            if !fields.iter().any(|(name, _)| **name == *field) {
+                return Err(DeriveError::custom_with_span(
+                    format!("`default_from` refers to `{field}`, which is not a field"),
+                    field.span(),
+                ));
+            }
+            if *field == **name || steps == fields.len() {
+                return Err(DeriveError::custom_with_span(
+                    format!("`default_from` of `{name}` leads to a cycle"),
+                    field.span(),
+                ));
+            }
+            next = default_from(field);
+            steps += 1;
+        }
+    }
+
+    Ok(())
+}
+
 fn named_struct_definition_expr<I: ToTokens, K: ToTokens, V: ToTokens>(
     ident: I,
     generics: Option<&syn::Generics>,
@@ -393,8 +690,10 @@ fn named_struct_definition_expr<I: ToTokens, K: ToTokens, V: ToTokens>(
             }
         });
 
+    let where_clause = generics.and_then(|generics| generics.where_clause.as_ref());
+
     parse_quote! {
-        #visibility struct #ident #generics {
+        #visibility struct #ident #generics #where_clause {
             #(#field_tokens,)*
         }
     }
@@ -408,10 +707,12 @@ fn unnamed_struct_definition_expr<I: ToTokens, T: ToTokens>(
 ) -> ItemStruct {
     let fields = fields.into_iter();
 
+    let where_clause = generics.and_then(|generics| generics.where_clause.as_ref());
+
     parse_quote! {
         #visibility struct #ident #generics (
             #(#fields),*
-        );
+        ) #where_clause;
     }
 }
 
@@ -420,8 +721,10 @@ fn unit_struct_definition_expr<I: ToTokens>(
     generics: Option<&syn::Generics>,
     visibility: &Visibility,
 ) -> ItemStruct {
+    let where_clause = generics.and_then(|generics| generics.where_clause.as_ref());
+
     parse_quote! {
-        #visibility struct #ident #generics;
+        #visibility struct #ident #generics #where_clause;
     }
 }
 
@@ -497,3 +800,64 @@ pub fn value_deconstructor(
         let #path #fields = #value_expr #fallible;
     }
 }
+
+/// A field of a record that is (de)serialized as a pseudo-attribute of a processing instruction.
+pub struct PseudoAttributeField {
+    pub field_ident: Ident,
+    pub field_type: Type,
+    pub name: String,
+    pub optional: bool,
+    pub default_or_else: Option<Expr>,
+    pub skip_serializing_if: Option<syn::Path>,
+}
+
+pub fn pseudo_attribute_fields(
+    fields: &[FieldWithOpts<Ident, FieldOpts>],
+) -> DeriveResult<Vec<PseudoAttributeField>> {
+    use crate::options::records::fields::{AttributeOpts, ChildOpts};
+
+    fields
+        .iter()
+        .map(|field| {
+            let (name, optional, skip_serializing_if) = match &field.options {
+                FieldOpts::Value(ChildOpts::Value(opts)) => {
+                    (None, false, opts.skip_serializing_if.clone())
+                }
+                FieldOpts::Attribute(AttributeOpts::Declared(opts))
+                    if opts.namespace.is_none() && opts.namespace_expr.is_none() =>
+                {
+                    (
+                        opts.name.as_ref().map(|name| name.0.to_string()),
+                        opts.optional,
+                        opts.skip_serializing_if.clone(),
+                    )
+                }
+                FieldOpts::Attribute(AttributeOpts::Declared(_)) => {
+                    return Err(DeriveError::custom(
+                        "Pseudo-attributes of processing instructions cannot have a namespace.",
+                    ))
+                }
+                _ => {
+                    return Err(DeriveError::custom(
+                        "Fields of processing instructions must be pseudo-attributes, either without options or declared with `xattribute`.",
+                    ))
+                }
+            };
+
+            let default_or_else = match &field.options {
+                FieldOpts::Value(opts) => opts.default_or_else(),
+                FieldOpts::Attribute(opts) => opts.default_or_else(),
+                FieldOpts::Group(_) => None,
+            };
+
+            Ok(PseudoAttributeField {
+                field_ident: field.field_ident.clone(),
+                field_type: field.field_type.clone(),
+                name: name.unwrap_or_else(|| field.field_ident.to_string()),
+                optional,
+                default_or_else,
+                skip_serializing_if,
+            })
+        })
+        .collect()
+}
@@ -3,11 +3,13 @@ use std::{borrow::Cow, ops::Deref};
 use proc_macro2::Span;
 use quote::format_ident;
 use syn::{
-    parse_quote, Generics, Ident, ImplItemFn, Item, ItemImpl, ItemStruct, Lifetime, LifetimeParam,
-    Stmt, Type,
+    parse_quote, Generics, Ident, ImplItemFn, Item, ItemImpl, ItemStruct, Lifetime, Stmt, Type,
 };
 
-use crate::{common::non_bound_generics, DeriveError};
+use crate::{
+    common::{insert_deserialize_lifetime, non_bound_generics},
+    DeriveError,
+};
 
 pub trait VisitorBuilder {
     fn visit_text_fn_body(
@@ -330,9 +332,10 @@ impl<T: VisitorBuilder> VisitorBuilderExt for T {
         };
 
         Ok(Some(parse_quote! {
-            fn visit_pi<#error_type, #access_type: AsRef<[u8]>>(self, #value_ident: #access_type) -> Result<Self::Value, #error_type>
+            fn visit_pi<#error_type, #access_type>(self, #value_ident: #access_type) -> ::core::result::Result<Self::Value, #error_type>
             where
                 #error_type: ::xmlity::de::Error,
+                #access_type: ::xmlity::de::XmlProcessingInstruction,
             {
                 #(#body)*
             }
@@ -472,14 +475,13 @@ impl<T: VisitorBuilder> VisitorBuilderExt for T {
 
         let mut deserialize_generics = (*generics).to_owned();
 
-        deserialize_generics.params.insert(
-            0,
-            syn::GenericParam::Lifetime(LifetimeParam::new((visitor_lifetime).clone())),
-        );
+        insert_deserialize_lifetime(&mut deserialize_generics, &visitor_lifetime);
         let non_bound_deserialize_generics = non_bound_generics(&deserialize_generics);
 
+        let where_clause = &deserialize_generics.where_clause;
+
         Ok(parse_quote! {
-            impl #deserialize_generics ::xmlity::de::Visitor<#visitor_lifetime> for #visitor_ident #non_bound_deserialize_generics {
+            impl #deserialize_generics ::xmlity::de::Visitor<#visitor_lifetime> for #visitor_ident #non_bound_deserialize_generics #where_clause {
                 type Value = #ident #value_non_bound_generics;
                 fn expecting(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
                     ::core::fmt::Formatter::write_str(formatter, #formatter_expecting)
@@ -541,15 +543,14 @@ impl<T: DeserializeBuilder> DeserializeBuilderExt for T {
 
         let mut deserialize_generics = (*generics).to_owned();
 
-        deserialize_generics.params.insert(
-            0,
-            syn::GenericParam::Lifetime(LifetimeParam::new((deserialize_lifetime).clone())),
-        );
+        insert_deserialize_lifetime(&mut deserialize_generics, &deserialize_lifetime);
 
         let deserialize_fn = self.deserialize_fn(&deserialize_lifetime)?;
 
+        let where_clause = &deserialize_generics.where_clause;
+
         Ok(parse_quote! {
-            impl #deserialize_generics ::xmlity::Deserialize<#deserialize_lifetime> for #ident #non_bound_generics  {
+            impl #deserialize_generics ::xmlity::Deserialize<#deserialize_lifetime> for #ident #non_bound_generics #where_clause {
                 #deserialize_fn
             }
         })
@@ -581,6 +582,8 @@ pub trait DeserializationGroupBuilderBuilder {
         deserialize_lifetime: &Lifetime,
     ) -> Result<Option<Vec<Stmt>>, DeriveError>;
 
+    fn completed_fields_fn_body(&self) -> Result<Option<Vec<Stmt>>, DeriveError>;
+
     fn finish_fn_body(
         &self,
         ident: &Ident,
@@ -622,6 +625,8 @@ pub trait DeserializationGroupBuilderContentExt: DeserializationGroupBuilderBuil
         deserialize_lifetime: &Lifetime,
     ) -> Result<Option<ImplItemFn>, DeriveError>;
 
+    fn completed_fields_fn(&self) -> Result<Option<ImplItemFn>, DeriveError>;
+
     fn finish_fn(
         &self,
         builder_ident: &Ident,
@@ -728,6 +733,20 @@ impl<T: DeserializationGroupBuilderBuilder> DeserializationGroupBuilderContentEx
         }))
     }
 
+    fn completed_fields_fn(&self) -> Result<Option<ImplItemFn>, DeriveError> {
+        let content = self.completed_fields_fn_body()?;
+
+        let Some(content) = content else {
+            return Ok(None);
+        };
+
+        Ok(Some(parse_quote! {
+            fn completed_fields(&self) -> ::std::vec::Vec<&'static str> {
+                #(#content)*
+            }
+        }))
+    }
+
     fn finish_fn(
         &self,
         ident: &Ident,
@@ -760,10 +779,7 @@ impl<T: DeserializationGroupBuilderBuilder> DeserializationGroupBuilderContentEx
 
         let mut builder_generics = (*generics).to_owned();
 
-        builder_generics.params.insert(
-            0,
-            syn::GenericParam::Lifetime(LifetimeParam::new(deserialize_lifetime.clone())),
-        );
+        insert_deserialize_lifetime(&mut builder_generics, deserialize_lifetime);
         let non_bound_builder_generics = non_bound_generics(&builder_generics);
 
         let contribute_attributes_fn = self.contribute_attributes_fn(deserialize_lifetime)?;
@@ -774,10 +790,14 @@ impl<T: DeserializationGroupBuilderBuilder> DeserializationGroupBuilderContentEx
 
         let elements_done_fn = self.elements_done_fn(deserialize_lifetime)?;
 
+        let completed_fields_fn = self.completed_fields_fn()?;
+
         let finish_fn = self.finish_fn(&ident, deserialize_lifetime)?;
 
+        let where_clause = &builder_generics.where_clause;
+
         Ok(parse_quote! {
-        impl #builder_generics ::xmlity::de::DeserializationGroupBuilder<#deserialize_lifetime> for #builder_ident #non_bound_builder_generics {
+        impl #builder_generics ::xmlity::de::DeserializationGroupBuilder<#deserialize_lifetime> for #builder_ident #non_bound_builder_generics #where_clause {
           type Value = #ident #value_non_bound_generics;
 
             #contribute_attributes_fn
@@ -788,6 +808,8 @@ impl<T: DeserializationGroupBuilderBuilder> DeserializationGroupBuilderContentEx
 
             #elements_done_fn
 
+            #completed_fields_fn
+
             #finish_fn
         }
         })
@@ -805,16 +827,15 @@ impl<T: DeserializationGroupBuilderBuilder> DeserializationGroupBuilderContentEx
 
         let mut builder_generics = (*generics).to_owned();
 
-        builder_generics.params.insert(
-            0,
-            syn::GenericParam::Lifetime(LifetimeParam::new((deserialize_lifetime).clone())),
-        );
+        insert_deserialize_lifetime(&mut builder_generics, &deserialize_lifetime);
         let non_bound_builder_generics = non_bound_generics(&builder_generics);
 
         let builder_constructor = self.builder_constructor(&builder_ident)?;
 
+        let where_clause = &builder_generics.where_clause;
+
         Ok(parse_quote! {
-            impl #builder_generics ::xmlity::de::DeserializationGroup<#deserialize_lifetime> for #ident #group_non_bound_generics {
+            impl #builder_generics ::xmlity::de::DeserializationGroup<#deserialize_lifetime> for #ident #group_non_bound_generics #where_clause {
                 type Builder = #builder_ident #non_bound_builder_generics;
 
                 fn builder() -> Self::Builder {
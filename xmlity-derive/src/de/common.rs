@@ -2,9 +2,15 @@ use proc_macro2::Span;
 use syn::{parse_quote, Expr, ExprWhile, Generics, Ident, Stmt};
 
 use crate::{
-    common::FieldIdent,
+    common::{check_default_from_fields, nested_generics, ExpandedName, FieldIdent},
     de::builders::DeserializeBuilderExt,
-    options::{records::fields::FieldValueGroupOpts, FieldWithOpts},
+    options::{
+        records::{
+            fields::FieldValueGroupOpts,
+            roots::{RootElementOpts, RootValueOpts},
+        },
+        FieldWithOpts, XmlnsDeclarations,
+    },
     DeriveError, DeriveResult,
 };
 
@@ -14,7 +20,7 @@ use crate::{
             AttributeDeclaredOpts, AttributeOpts, ChildOpts, ElementOpts, FieldAttributeGroupOpts,
             FieldOpts, GroupOpts, ValueOpts,
         },
-        Extendable, WithExpandedNameExt,
+        AllowUnknown, Extendable, WithExpandedNameExt,
     },
     utils::{self},
 };
@@ -23,8 +29,10 @@ use quote::{quote, ToTokens};
 
 use super::deserialize::SimpleDeserializeAttributeBuilder;
 
+#[allow(clippy::too_many_arguments)]
 fn attribute_field_deserialize_impl(
     access_expr: &Expr,
+    generics: &Generics,
     ident_to_expr: impl FnOnce(&FieldIdent) -> Expr,
     FieldWithOpts {
         field_ident,
@@ -39,13 +47,14 @@ fn attribute_field_deserialize_impl(
     let builder_expr = ident_to_expr(&field_ident);
     let temporary_value_ident = Ident::new("__v", Span::call_site());
     let wrapper_ident = Ident::new("__W", Span::call_site());
-    let empty_generics: Generics = parse_quote!();
+    let wrapper_generics = nested_generics(generics, None);
+    let extendable = options.extendable();
 
     let wrapper_data = match &options {
         AttributeOpts::Declared(opts @ AttributeDeclaredOpts { .. }) => {
             let builder = SimpleDeserializeAttributeBuilder {
                 ident: &wrapper_ident,
-                generics: &empty_generics,
+                generics: &wrapper_generics,
                 required_expanded_name: Some(
                     opts.expanded_name(field_ident.to_named_ident().to_string().as_str())
                         .into_owned(),
@@ -66,8 +75,11 @@ fn attribute_field_deserialize_impl(
 
     let deserialize_type = wrapper_data
         .as_ref()
-        .map(|(a, _)| a.ident)
-        .map(|a| parse_quote!(#a))
+        .map(|(a, _)| {
+            let ident = a.ident;
+            let (_, ty_generics, _) = a.generics.split_for_impl();
+            parse_quote!(#ident #ty_generics)
+        })
         .unwrap_or(field_type.clone());
 
     let deserialize_wrapper_def: Vec<Stmt> = match wrapper_data.as_ref() {
@@ -86,12 +98,33 @@ fn attribute_field_deserialize_impl(
 
     let value_transformer = deserialize_unwrapper.map(|a| (a)(&temporary_value_ident));
 
+    // Repeated attributes of extendable fields are merged into the value of the first one.
+    let store_value: Stmt = match extendable {
+        Extendable::None => parse_quote! {
+            #builder_expr = ::core::option::Option::Some(#temporary_value_ident);
+        },
+        Extendable::Iterator | Extendable::Single => {
+            let extendable_value: Expr = if extendable == Extendable::Iterator {
+                parse_quote! { #temporary_value_ident }
+            } else {
+                parse_quote! { [#temporary_value_ident] }
+            };
+
+            parse_quote! {
+                match &mut #builder_expr {
+                    ::core::option::Option::Some(__existing) => ::core::iter::Extend::extend(__existing, #extendable_value),
+                    ::core::option::Option::None => #builder_expr = ::core::option::Option::Some(#temporary_value_ident),
+                }
+            }
+        }
+    };
+
     let inner = quote! {
         let ::core::option::Option::Some(#temporary_value_ident) = #temporary_value_ident else {
             #(#if_next_attribute_none)*
         };
         #value_transformer
-        #builder_expr = ::core::option::Option::Some(#temporary_value_ident);
+        #store_value
         #(#finished_attribute)*
     };
     let deserialize_expr: Expr = parse_quote!(
@@ -102,13 +135,26 @@ fn attribute_field_deserialize_impl(
 
     let after_attempt = if !pop_error { after_attempt } else { &[] };
 
-    Ok(parse_quote! {
-        if ::core::option::Option::is_none(&#builder_expr) {
-            #(#deserialize_wrapper_def)*
+    let attempt = quote! {
+        #(#deserialize_wrapper_def)*
 
-            #inner
+        #inner
 
-            #(#after_attempt)*
+        #(#after_attempt)*
+    };
+
+    // Extendable fields are attempted again after they have a value, to pick up repeated attributes.
+    Ok(if extendable == Extendable::None {
+        parse_quote! {
+            if ::core::option::Option::is_none(&#builder_expr) {
+                #attempt
+            }
+        }
+    } else {
+        parse_quote! {
+            {
+                #attempt
+            }
         }
     })
 }
@@ -157,6 +203,7 @@ pub fn builder_attribute_field_visitor<
     F: IntoIterator<Item = FieldWithOpts<FieldIdent, FieldAttributeGroupOpts>>,
 >(
     access_expr: &Expr,
+    generics: &Generics,
     ident_to_expr: impl Fn(&FieldIdent) -> Expr + Clone,
     fields: F,
     if_next_attribute_none: Vec<Stmt>,
@@ -167,6 +214,7 @@ pub fn builder_attribute_field_visitor<
 ) -> DeriveResult<Vec<Stmt>> {
     fields
         .into_iter()
+        .filter(|field| !is_ancestor_attribute(field))
         .zip(utils::repeat_clone((
             ident_to_expr,
             if_next_attribute_none,
@@ -187,6 +235,7 @@ pub fn builder_attribute_field_visitor<
             )| match &var_field.options {
                 FieldAttributeGroupOpts::Attribute(_) => attribute_field_deserialize_impl(
                     access_expr,
+                    generics,
                     ident_to_expr,
                     var_field.map_options(|opts| match opts {
                         FieldAttributeGroupOpts::Attribute(opts) => opts,
@@ -214,8 +263,48 @@ pub fn builder_attribute_field_visitor<
         .map(|v| v.into_iter().flatten().collect())
 }
 
+fn is_ancestor_attribute(field: &FieldWithOpts<FieldIdent, FieldAttributeGroupOpts>) -> bool {
+    matches!(&field.options, FieldAttributeGroupOpts::Attribute(opts) if opts.ancestor().is_some())
+}
+
+/// Looks up the attributes of fields with `from_ancestor`, which are not part of the attributes of the element itself and are therefore skipped by [`builder_attribute_field_visitor`].
+pub fn ancestor_attribute_lookups<
+    F: IntoIterator<Item = FieldWithOpts<FieldIdent, FieldAttributeGroupOpts>>,
+>(
+    access_expr: &Expr,
+    ident_to_expr: impl Fn(&FieldIdent) -> Expr,
+    fields: F,
+) -> Vec<Stmt> {
+    fields
+        .into_iter()
+        .filter_map(|field| {
+            let FieldAttributeGroupOpts::Attribute(AttributeOpts::Declared(opts)) = &field.options
+            else {
+                return None;
+            };
+            let ancestor = ExpandedName::new(
+                opts.from_ancestor.clone()?,
+                opts.ancestor_namespace.clone(),
+            );
+            let expanded_name = opts
+                .expanded_name(field.field_ident.to_named_ident().to_string().as_str())
+                .into_owned();
+            let builder_expr = ident_to_expr(&field.field_ident);
+            let field_type = &field.field_type;
+
+            Some(parse_quote! {
+                if ::core::option::Option::is_none(&#builder_expr) {
+                    #builder_expr = ::xmlity::__private::ancestor_attribute::<#field_type, _>(#access_expr, &#ancestor, &#expanded_name)?;
+                }
+            })
+        })
+        .collect()
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn element_field_deserialize_impl(
     access_expr: &Expr,
+    generics: &Generics,
     ident_to_expr: impl FnOnce(&FieldIdent) -> Expr,
     FieldWithOpts {
         field_ident,
@@ -233,12 +322,14 @@ pub fn element_field_deserialize_impl(
     let builder_field_expr = ident_to_expr(&field_ident);
     let temporary_value_ident = Ident::new("__v", Span::call_site());
     let wrapper_ident = Ident::new("__W", Span::call_site());
-    let empty_generics: Generics = parse_quote!();
+    let wrapper_generics = nested_generics(generics, None);
+    let pop_error =
+        pop_error || matches!(&options, ChildOpts::Value(opts) if opts.propagates_errors());
 
     let wrapper_data = match &options {
         ChildOpts::Element(opts) => {
             let builder =
-                opts.to_builder(&field_ident, &wrapper_ident, &empty_generics, &field_type);
+                opts.to_builder(&field_ident, &wrapper_ident, &wrapper_generics, &field_type);
 
             let unwrap_expr = builder.unwrap_expression();
 
@@ -254,14 +345,23 @@ pub fn element_field_deserialize_impl(
         _ => None,
     };
 
+    let value_wrapped_type = match &options {
+        ChildOpts::Value(opts) => opts.wrapped_deserialize_type(&field_type),
+        ChildOpts::Element(_) => None,
+    };
+
     let deserialize_type = wrapper_data
         .as_ref()
-        .map(|(a, _)| a.ident)
-        .map(|a| parse_quote!(#a))
+        .map(|(a, _)| {
+            let ident = a.ident;
+            let (_, ty_generics, _) = a.generics.split_for_impl();
+            parse_quote!(#ident #ty_generics)
+        })
+        .or(value_wrapped_type.clone())
         .unwrap_or(field_type.clone());
 
-    let deserialize_wrapper_def: Vec<Stmt> = match wrapper_data.as_ref() {
-        Some((a, _)) => {
+    let deserialize_wrapper_def: Vec<Stmt> = match (wrapper_data.as_ref(), &options) {
+        (Some((a, _)), _) => {
             let def = a.struct_definition();
             let trait_impl = a.deserialize_trait_impl()?;
             parse_quote!(
@@ -269,7 +369,8 @@ pub fn element_field_deserialize_impl(
                 #trait_impl
             )
         }
-        None => Vec::new(),
+        (None, ChildOpts::Value(opts)) => opts.wrapped_deserialize_definitions(),
+        (None, ChildOpts::Element(_)) => Vec::new(),
     };
 
     let deserialize_unwrapper = wrapper_data.as_ref().map(|(_, a)| a);
@@ -312,7 +413,15 @@ pub fn element_field_deserialize_impl(
         None
     };
 
-    let value_transformer = deserialize_unwrapper.map(|a| (a)(&temporary_value_ident));
+    let value_transformer = deserialize_unwrapper
+        .map(|a| (a)(&temporary_value_ident))
+        .or_else(|| {
+            value_wrapped_type.as_ref().map(|_| {
+                quote! {
+                    let mut #temporary_value_ident = #temporary_value_ident.0;
+                }
+            })
+        });
 
     let inner = quote!(
         let ::core::option::Option::Some(mut #temporary_value_ident) = #temporary_value_ident else {
@@ -344,6 +453,22 @@ pub fn element_field_deserialize_impl(
     })
 }
 
+/// Returns from the closure that visits the fields in the loops of `::xmlity::__private`, telling the loop what was done, like `End` or `Next`.
+pub fn visit_result(visit: proc_macro2::TokenStream) -> Vec<Stmt> {
+    parse_quote! {
+        return ::core::result::Result::Ok(::xmlity::__private::Visit::#visit);
+    }
+}
+
+/// The `::xmlity::__private::AllowUnknown` for an `allow_unknown_attributes` or `allow_unknown_children` option.
+pub fn allow_unknown_expr(allow_unknown: AllowUnknown) -> Expr {
+    match allow_unknown {
+        AllowUnknown::Any => parse_quote!(::xmlity::__private::AllowUnknown::Any),
+        AllowUnknown::AtEnd => parse_quote!(::xmlity::__private::AllowUnknown::AtEnd),
+        AllowUnknown::None => parse_quote!(::xmlity::__private::AllowUnknown::None),
+    }
+}
+
 pub fn pop_or_ignore_error(
     access_ident: &Ident,
     expr: &Expr,
@@ -407,25 +532,20 @@ pub fn group_field_contribute_elements(
 }
 
 pub fn one_stop_field_expression(
-    seq_access_ty: &syn::Type,
     seq_access: &syn::Expr,
-    visitor_lifetime: &syn::Lifetime,
     de_type: &syn::Type,
     missing_field: &str,
     default_or_else: Option<&Expr>,
     unwrap_function: Option<impl Fn(&Expr) -> Expr>,
+    propagate_errors: bool,
 ) -> syn::Expr {
-    let deserialize_expr: Expr = parse_quote!(
-        ::xmlity::de::SeqAccess::next_element_seq::<#de_type>(#seq_access)
-    );
-
-    let option_value: Expr = parse_quote!(
-        ::core::option::Option::flatten(
-            ::core::result::Result::ok(
-                #deserialize_expr
-            )
-        )
-    );
+    let option_value: Expr = if propagate_errors {
+        parse_quote!(::xmlity::de::SeqAccess::next_element_seq::<#de_type>(#seq_access)?)
+    } else if default_or_else.is_some() {
+        parse_quote!(::xmlity::__private::next_child::<#de_type, _>(#seq_access))
+    } else {
+        parse_quote!(::xmlity::__private::next_child_or_none::<#de_type, _>(#seq_access))
+    };
 
     let mapped_option_value = if let Some(unwrap_function) = unwrap_function.as_ref() {
         let unwrap_expr = unwrap_function(&parse_quote!(__v));
@@ -447,37 +567,8 @@ pub fn one_stop_field_expression(
             )
         )
     } else {
-        let deserialize_none_expr: syn::Expr = parse_quote!(
-            <#de_type as ::xmlity::Deserialize<#visitor_lifetime>>::deserialize_seq(
-                ::xmlity::types::utils::NoneDeserializer::<
-                    <#seq_access_ty as ::xmlity::de::SeqAccess<#visitor_lifetime>>::Error
-                >::new()
-            )
-        );
-
-        let deserialize_none_option_expr: syn::Expr = parse_quote!(
-            ::core::result::Result::ok(#deserialize_none_expr)
-        );
-
-        let deserialize_none_option_expr = if let Some(unwrap_function) = unwrap_function {
-            let unwrap_expr = unwrap_function(&parse_quote!(__v));
-            parse_quote!(
-                ::core::option::Option::map(
-                    #deserialize_none_option_expr,
-                    |__v| #unwrap_expr
-                )
-            )
-        } else {
-            deserialize_none_option_expr
-        };
-
         parse_quote!(::core::option::Option::ok_or_else(
-            ::core::option::Option::or_else(
-                #mapped_option_value,
-                || {
-                    #deserialize_none_option_expr
-                },
-            ),
+            #mapped_option_value,
             || ::xmlity::de::Error::missing_field(stringify!(#missing_field)),
         )?)
     }
@@ -488,6 +579,7 @@ pub fn builder_element_field_visitor<
     F: IntoIterator<Item = FieldWithOpts<FieldIdent, FieldValueGroupOpts>>,
 >(
     access_expr: &Expr,
+    generics: &Generics,
     ident_to_expr: impl Fn(&FieldIdent) -> Expr + Clone,
     fields: F,
     if_next_element_none: Vec<Stmt>,
@@ -518,6 +610,7 @@ pub fn builder_element_field_visitor<
             )| match &var_field.options {
                 FieldValueGroupOpts::Value(_) => element_field_deserialize_impl(
                     access_expr,
+                    generics,
                     ident_to_expr,
                     var_field.map_options(|opts| match opts {
                         FieldValueGroupOpts::Value(opts) => opts,
@@ -555,6 +648,10 @@ pub fn attribute_done_expr(
 ) -> Expr {
     let builder_field_expr = ident_to_expr(&field_ident);
     match options {
+        // Repeated attributes can still be merged into extendable fields.
+        FieldAttributeGroupOpts::Attribute(opts) if opts.extendable() != Extendable::None => {
+            parse_quote! { false }
+        }
         FieldAttributeGroupOpts::Attribute(_) => parse_quote! {
             ::core::option::Option::is_some( #builder_field_expr)
         },
@@ -623,13 +720,16 @@ pub fn all_elements_done_expr(
 
 pub fn fields(
     ast: &syn::DeriveInput,
-) -> Result<impl IntoIterator<Item = FieldWithOpts<FieldIdent, FieldOpts>>, DeriveError> {
+) -> Result<Vec<FieldWithOpts<FieldIdent, FieldOpts>>, DeriveError> {
     let data_struct = match ast.data {
         syn::Data::Struct(ref data_struct) => data_struct,
         _ => unreachable!(),
     };
+    let xmlns = XmlnsDeclarations::parse(&ast.attrs)?;
+    let uses_with = RootValueOpts::parse_uses_with(&ast.attrs)?;
+    let rename_all = RootElementOpts::parse_rename_all(&ast.attrs)?;
 
-    Ok(match &data_struct.fields {
+    let fields = match &data_struct.fields {
         syn::Fields::Named(fields) => fields
             .named
             .iter()
@@ -638,7 +738,7 @@ pub fn fields(
 
                 DeriveResult::Ok(FieldWithOpts {
                     field_ident: FieldIdent::Named(field_ident),
-                    options: FieldOpts::from_field(f)?,
+                    options: FieldOpts::from_field(f, &xmlns, rename_all, uses_with)?,
                     field_type: f.ty.clone(),
                 })
             })
@@ -650,13 +750,22 @@ pub fn fields(
             .map(|(i, f)| {
                 DeriveResult::Ok(FieldWithOpts {
                     field_ident: FieldIdent::Indexed(syn::Index::from(i)),
-                    options: FieldOpts::from_field(f)?,
+                    options: FieldOpts::from_field(f, &xmlns, rename_all, uses_with)?,
                     field_type: f.ty.clone(),
                 })
             })
             .collect::<Result<Vec<_>, _>>()?,
         _ => Vec::new(),
-    })
+    };
+
+    check_default_from_fields(fields.iter().map(|field| {
+        (
+            field.field_ident.to_named_ident(),
+            field.options.default_from(),
+        )
+    }))?;
+
+    Ok(fields)
 }
 
 pub fn element_fields(
@@ -738,15 +847,9 @@ pub fn deserialize_option_value_expr(
         }
     } else if should_try_none {
         parse_quote! {
-            ::core::result::Result::map_err(
-                ::core::option::Option::map_or_else(
-                    #field_ident,
-                    || <#field_type as ::xmlity::Deserialize<#visitor_lifetime>>::deserialize_seq(
-                        ::xmlity::types::utils::NoneDeserializer::<#error_type>::new(),
-                    ),
-                    |__v| ::core::result::Result::Ok(__v)
-                ),
-                |_|  ::xmlity::de::Error::missing_field(stringify!(#missing_field))
+            ::xmlity::__private::value_or_missing::<#visitor_lifetime, #field_type, #error_type>(
+                #field_ident,
+                stringify!(#missing_field),
             )?
         }
     } else {
@@ -1,11 +1,14 @@
 use proc_macro2::Span;
-use quote::format_ident;
-use syn::{parse_quote, Expr, Lifetime, Stmt, Type};
+use quote::quote;
+use syn::{parse_quote, Expr, Ident, Lifetime, Stmt, Type};
 
 use crate::{
     common::FieldIdent,
     de::{
-        common::{builder_attribute_field_visitor, deserialize_option_value_expr},
+        common::{
+            allow_unknown_expr, ancestor_attribute_lookups, builder_attribute_field_visitor,
+            deserialize_option_value_expr, visit_result,
+        },
         components::SeqLoopAccessor,
     },
     derive::DeriveResult,
@@ -23,6 +26,7 @@ pub struct ElementLoopAccessor {
 
 impl ElementLoopAccessor {
     pub fn new(
+        generics: &syn::Generics,
         allow_unknown_children: AllowUnknown,
         allow_unknown_attributes: AllowUnknown,
         children_order: ElementOrder,
@@ -32,6 +36,7 @@ impl ElementLoopAccessor {
     ) -> Self {
         Self {
             children_loop_accessor: SeqLoopAccessor::new(
+                generics,
                 allow_unknown_children,
                 children_order,
                 ignore_whitespace,
@@ -127,101 +132,87 @@ impl ElementLoopAccessor {
         access_expr: &Expr,
     ) -> DeriveResult<Vec<Stmt>> {
         let Self {
+            children_loop_accessor,
             allow_unknown_attributes,
             attribute_order: attributes_order,
-            ..
         } = self;
 
         let attribute_group_fields = fields
             .into_iter()
             .flat_map(|a| a.map_options_opt(|a| a.attribute_group()));
 
+        // The fields are visited in a closure that the loop in `visit_attributes` calls with the access.
+        let access_ident = Ident::new("__access", Span::call_site());
+        let access: Expr = parse_quote!(&mut *#access_ident);
+
         let field_visits = builder_attribute_field_visitor(
-            access_expr,
+            &access,
+            children_loop_accessor.generics(),
             |field| {
                 let ident = field.to_named_ident();
                 parse_quote! {#ident}
             },
             attribute_group_fields,
-            parse_quote! {break;},
+            visit_result(quote!(End)),
             match *attributes_order {
-                ElementOrder::Strict => parse_quote! {break;},
-                ElementOrder::None => parse_quote! {continue;},
+                ElementOrder::Strict => visit_result(quote!(End)),
+                ElementOrder::None => visit_result(quote!(Next)),
             },
-            parse_quote! {continue;},
+            visit_result(quote!(Next)),
             parse_quote! {},
             false,
         )?;
 
-        let skip_unknown: Vec<Stmt> = match allow_unknown_attributes {
-            AllowUnknown::Any => {
-                let skip_ident = format_ident!("__skip");
-                parse_quote! {
-                    let #skip_ident = ::xmlity::de::AttributesAccess::next_attribute::<::xmlity::types::utils::IgnoredAny>(#access_expr).unwrap_or(None);
-                    if ::core::option::Option::is_none(&#skip_ident) {
-                        break;
-                    }
-                    continue;
-                }
-            }
-            AllowUnknown::AtEnd => {
-                //Ignore whatever is left
-                parse_quote! {
-                    break;
-                }
-            }
-            AllowUnknown::None => {
-                //Check that nothing is left
-                let skip_ident = format_ident!("__skip");
-                parse_quote! {
-                    let #skip_ident = ::xmlity::de::AttributesAccess::next_attribute::<::xmlity::types::utils::IgnoredAny>(#access_expr).unwrap_or(None);
-                    if ::core::option::Option::is_none(&#skip_ident) {
-                        break;
-                    }
-
-                    return Err(::xmlity::de::Error::unknown_child());
-                }
+        let allow_unknown = allow_unknown_expr(*allow_unknown_attributes);
+        let visit_attributes = |field_visits: &[Stmt]| -> Stmt {
+            parse_quote! {
+                ::xmlity::__private::visit_attributes(#access_expr, #allow_unknown, |#access_ident| {
+                    #(#field_visits)*
+                    ::core::result::Result::Ok(::xmlity::__private::Visit::Unmatched)
+                })?;
             }
         };
 
-        match *attributes_order {
+        // In strict order, the attributes of each field are visited in turn.
+        Ok(match *attributes_order {
             ElementOrder::Strict => field_visits
-                .into_iter()
-                .map(|field_visit| {
-                    Ok(parse_quote! {
-                        loop {
-                            #field_visit
-                            #(#skip_unknown)*
-                        }
-                    })
-                })
+                .iter()
+                .map(|field_visit| visit_attributes(std::slice::from_ref(field_visit)))
                 .collect(),
-            ElementOrder::None => Ok(parse_quote! {
-                loop {
-                    #(#field_visits)*
-                    #(#skip_unknown)*
-                }
-            }),
-        }
+            ElementOrder::None => vec![visit_attributes(&field_visits)],
+        })
+    }
+
+    pub fn ancestor_attribute_lookups<
+        F: IntoIterator<Item = FieldWithOpts<FieldIdent, FieldOpts>>,
+    >(
+        &self,
+        fields: F,
+        access_expr: &Expr,
+    ) -> Vec<Stmt> {
+        ancestor_attribute_lookups(
+            access_expr,
+            |field| {
+                let ident = field.to_named_ident();
+                parse_quote! {#ident}
+            },
+            fields
+                .into_iter()
+                .flat_map(|a| a.map_options_opt(|a| a.attribute_group())),
+        )
     }
 
     pub fn children_access_loop<F: IntoIterator<Item = FieldWithOpts<FieldIdent, FieldOpts>>>(
         &self,
         fields: F,
         seq_access: &Expr,
-        seq_access_ty: &Type,
-        visitor_lifetime: &Lifetime,
     ) -> DeriveResult<Vec<Stmt>> {
         let child_group_fields = fields
             .into_iter()
             .flat_map(|a| a.map_options_opt(|a| a.value_group()));
 
-        self.children_loop_accessor.access_loop(
-            child_group_fields,
-            seq_access,
-            seq_access_ty,
-            visitor_lifetime,
-        )
+        self.children_loop_accessor
+            .access_loop(child_group_fields, seq_access)
     }
 
     pub fn value_expressions<F: IntoIterator<Item = FieldWithOpts<FieldIdent, FieldOpts>>>(
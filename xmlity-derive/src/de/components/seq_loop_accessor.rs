@@ -1,12 +1,14 @@
 use proc_macro2::Span;
-use syn::{parse_quote, spanned::Spanned, Expr, Generics, Ident, Lifetime, Stmt, Type};
+use quote::quote;
+use syn::{parse_quote, Expr, Ident, Stmt, Type};
 
 use crate::{
-    common::FieldIdent,
+    common::{nested_generics, FieldIdent},
     de::{
         builders::DeserializeBuilderExt,
         common::{
-            builder_element_field_visitor, deserialize_option_value_expr, one_stop_field_expression,
+            allow_unknown_expr, builder_element_field_visitor, deserialize_option_value_expr,
+            one_stop_field_expression, visit_result,
         },
     },
     derive::{DeriveError, DeriveResult},
@@ -16,7 +18,11 @@ use crate::{
     },
 };
 
+/// Unwraps the value a field was deserialized as into the value of the field.
+type UnwrapFunction = Box<dyn Fn(&syn::Expr) -> syn::Expr>;
+
 pub struct SeqLoopAccessor {
+    generics: syn::Generics,
     allow_unknown_children: AllowUnknown,
     order: ElementOrder,
     ignore_whitespace: IgnoreWhitespace,
@@ -25,12 +31,14 @@ pub struct SeqLoopAccessor {
 
 impl SeqLoopAccessor {
     pub fn new(
+        generics: &syn::Generics,
         allow_unknown_children: AllowUnknown,
         order: ElementOrder,
         ignore_whitespace: IgnoreWhitespace,
         ignore_comments: IgnoreComments,
     ) -> Self {
         Self {
+            generics: generics.to_owned(),
             allow_unknown_children,
             order,
             ignore_whitespace,
@@ -38,6 +46,10 @@ impl SeqLoopAccessor {
         }
     }
 
+    pub fn generics(&self) -> &syn::Generics {
+        &self.generics
+    }
+
     pub fn field_definitions<
         F: IntoIterator<Item = FieldWithOpts<FieldIdent, FieldValueGroupOpts>>,
     >(
@@ -76,83 +88,60 @@ impl SeqLoopAccessor {
         &self,
         fields: F,
         seq_access: &Expr,
-        seq_access_ty: &Type,
-        visitor_lifetime: &Lifetime,
     ) -> DeriveResult<Vec<Stmt>> {
         let Self {
             allow_unknown_children,
             order,
             ignore_whitespace,
             ignore_comments,
+            ..
         } = self;
 
-        let whitespace_ty: syn::Type = parse_quote! {::xmlity::types::utils::Whitespace};
-
-        let ignore_whitespace_expression: Vec<Stmt> = match ignore_whitespace {
-            IgnoreWhitespace::Any => {
-                parse_quote! {
-                    if let Ok(Some(_)) = ::xmlity::de::SeqAccess::next_element::<#whitespace_ty>(#seq_access) {
-                        continue;
-                    }
-                }
-            }
-            IgnoreWhitespace::None => {
-                vec![]
-            }
-        };
-
-        let comment_ty: syn::Type = parse_quote! {::xmlity::value::XmlComment};
-
-        let ignore_comments_expression: Vec<Stmt> = match ignore_comments {
-            IgnoreComments::Any => {
-                parse_quote! {
-                    if let Ok(Some(_)) = ::xmlity::de::SeqAccess::next_element::<#comment_ty>(#seq_access) {
-                        continue;
-                    }
-                }
-            }
-            IgnoreComments::None => {
-                vec![]
-            }
-        };
-
-        let ignored_any_ty: syn::Type = parse_quote! {::xmlity::types::utils::IgnoredAny};
+        // The fields are visited in a closure that the loop in `::xmlity::__private` calls with the access.
+        let access_ident = Ident::new("__access", Span::call_site());
+        let access: Expr = parse_quote!(&mut *#access_ident);
+        let ignore_whitespace = matches!(ignore_whitespace, IgnoreWhitespace::Any);
+        let ignore_comments = matches!(ignore_comments, IgnoreComments::Any);
+        let allow_unknown = allow_unknown_expr(*allow_unknown_children);
 
         match order {
             ElementOrder::Strict => {
-                let end_check: Vec<Stmt> = match allow_unknown_children {
-                    AllowUnknown::Any => {
-                        return Err(DeriveError::custom(
-                            "An unknown element in any position is not allowed in strict order",
-                        ))
-                    }
-                    AllowUnknown::AtEnd => {
-                        //Ignore whatever is left
-                        Vec::new()
-                    }
-                    AllowUnknown::None => {
-                        //Check that nothing is left
-                        parse_quote! {
-                            if let Ok(Some(_)) = ::xmlity::de::SeqAccess::next_element::<#ignored_any_ty>(#seq_access)  {
-                                return Err(::xmlity::de::Error::custom("Unexpected element at end of sequence."));
-                            }
-                        }
-                    }
-                };
+                if *allow_unknown_children == AllowUnknown::Any {
+                    return Err(DeriveError::custom(
+                        "An unknown element in any position is not allowed in strict order",
+                    ));
+                }
+
+                // Children are expected in the order they are serialized in.
+                let mut fields = fields.into_iter().collect::<Vec<_>>();
+                fields.sort_by_key(|field| field.options.serialize_order());
 
                 let field_visits = fields.into_iter().map::<DeriveResult<(_, syn::Expr, Vec<Stmt>)>, _>(|f| {
                     let (condition, deserialize_stmts) = match &f.options {
                         FieldValueGroupOpts::Value(child_opts) => {
-                            let wrapper_ident = Ident::new("__W", Span::call_site());
-                            let empty_generics: Generics = parse_quote!();
+                            if child_opts.default_from().is_some() {
+                                return Err(DeriveError::custom(
+                                    "`default_from` is not supported on children in strict order",
+                                ));
+                            }
 
-                            let (prefix, wrapped_de_type, unwrap_function) = match child_opts {
-                                ChildOpts::Value(_) => (Vec::new(), None, None),
+                            let wrapper_ident = Ident::new("__W", Span::call_site());
+                            let wrapper_generics = nested_generics(&self.generics, None);
+
+                            let (prefix, wrapped_de_type, unwrap_function): (_, _, Option<UnwrapFunction>) = match child_opts {
+                                ChildOpts::Value(value_opts) => match value_opts.wrapped_deserialize_type(&f.field_type) {
+                                    Some(wrapped_de_type) => (
+                                        value_opts.wrapped_deserialize_definitions(),
+                                        Some(wrapped_de_type),
+                                        Some(Box::new(|value_expr: &syn::Expr| parse_quote!(#value_expr.0))),
+                                    ),
+                                    None => (Vec::new(), None, None),
+                                },
                                 ChildOpts::Element(element_opts) => {
                                     let builder = element_opts.to_builder(
                                         &f.field_ident,
                                         &wrapper_ident,
-                                        &empty_generics,
+                                        &wrapper_generics,
                                         &f.field_type,
                                     );
 
@@ -166,25 +155,25 @@ impl SeqLoopAccessor {
                                         )
                                     };
 
-                                    let struct_type: Type = parse_quote!(#wrapper_ident);
+                                    let (_, ty_generics, _) = wrapper_generics.split_for_impl();
+                                    let struct_type: Type = parse_quote!(#wrapper_ident #ty_generics);
                                     let unwrap_function = builder.unwrap_expression();
 
                                     (
                                         deserialize_wrapper_def,
                                         Some(struct_type),
-                                        Some(unwrap_function),
+                                        Some(Box::new(unwrap_function)),
                                     )
                                 }
                             };
 
                             let value_expr = one_stop_field_expression(
-                                seq_access_ty,
-                                seq_access,
-                                visitor_lifetime,
+                                &access,
                                 wrapped_de_type.as_ref().unwrap_or(&f.field_type),
                                 f.field_ident.to_string().as_str(),
                                 child_opts.default_or_else().as_ref(),
                                 unwrap_function,
+                                matches!(child_opts, ChildOpts::Value(value_opts) if value_opts.propagates_errors()),
                             );
 
                             let builder_ident = f.field_ident.to_named_ident();
@@ -209,7 +198,7 @@ impl SeqLoopAccessor {
                             );
 
                             let deserialize_expr: Expr = parse_quote!(
-                                ::xmlity::de::DeserializationGroupBuilder::contribute_elements(&mut #builder_ident, ::xmlity::de::SeqAccess::sub_access(#seq_access)?)?
+                                ::xmlity::de::DeserializationGroupBuilder::contribute_elements(&mut #builder_ident, ::xmlity::de::SeqAccess::sub_access(#access)?)?
                             );
 
                             let deserialize_stmts = parse_quote! {
@@ -244,102 +233,46 @@ impl SeqLoopAccessor {
                         }
                     });
 
-                let end_statement: Vec<Stmt> = parse_quote!(
-                    #(#end_check)*
-
-                    break;
-                );
-
-                let if_statements = if let Some(if_statements) = if_statements {
-                    parse_quote! {
+                // Once every field has been visited, the loop checks what is left.
+                let visit: Vec<Stmt> = match if_statements {
+                    Some(if_statements) => parse_quote! {
                         #if_statements else {
-                            #(#end_statement)*
+                            return ::core::result::Result::Ok(::xmlity::__private::Visit::End);
                         }
-                    }
-                } else {
-                    end_statement
+                        ::core::result::Result::Ok(::xmlity::__private::Visit::Next)
+                    },
+                    None => parse_quote! {
+                        ::core::result::Result::Ok(::xmlity::__private::Visit::End)
+                    },
                 };
 
                 Ok(parse_quote! {
-                    loop {
-                        #(#ignore_whitespace_expression)*
-                        #(#ignore_comments_expression)*
-                        #(#if_statements)*
-                    }
+                    ::xmlity::__private::visit_children_in_order(#seq_access, #ignore_whitespace, #ignore_comments, #allow_unknown, |#access_ident| {
+                        #(#visit)*
+                    })?;
                 })
             }
             ElementOrder::None => {
                 let field_visits = builder_element_field_visitor(
-                    seq_access,
+                    &access,
+                    &self.generics,
                     |field| {
                         let field = field.to_named_ident();
                         parse_quote!(#field)
                     },
                     fields,
-                    parse_quote! {break;},
-                    match order {
-                        ElementOrder::Strict => parse_quote! {break;},
-                        ElementOrder::None => parse_quote! {continue;},
-                    },
-                    parse_quote! {continue;},
+                    visit_result(quote!(End)),
+                    visit_result(quote!(Next)),
+                    visit_result(quote!(Next)),
                     parse_quote! {},
                     false,
                 )?;
 
-                let skip_unknown: Vec<Stmt> = match allow_unknown_children {
-                    AllowUnknown::Any => {
-                        // Currently, allow any unknown is not supported with strict ordering.
-                        if matches!(order, ElementOrder::Strict) {
-                            return Err(DeriveError::custom(
-                                "An unknown element in any position is not allowed in strict order",
-                            ));
-                        }
-
-                        let skip_ident = Ident::new("__skip", seq_access.span());
-                        parse_quote! {
-                            let #skip_ident = ::core::result::Result::unwrap_or(
-                                ::xmlity::de::SeqAccess::next_element::<#ignored_any_ty>(#seq_access),
-                                None
-                            );
-
-                            if ::core::option::Option::is_none(&#skip_ident) {
-                                break;
-                            }
-
-                            continue;
-                        }
-                    }
-                    AllowUnknown::AtEnd => {
-                        //Ignore whatever is left
-                        parse_quote! {
-                            break;
-                        }
-                    }
-                    AllowUnknown::None => {
-                        //Check that nothing is left
-                        let skip_ident = Ident::new("__skip", seq_access.span());
-                        parse_quote! {
-                            let #skip_ident = ::core::result::Result::unwrap_or(
-                                ::xmlity::de::SeqAccess::next_element::<#ignored_any_ty>(#seq_access),
-                                None
-                            );
-
-                            if ::core::option::Option::is_none(&#skip_ident) {
-                                break;
-                            }
-
-                            return Err(::xmlity::de::Error::unknown_child());
-                        }
-                    }
-                };
-
                 Ok(parse_quote! {
-                    loop {
-                        #(#ignore_whitespace_expression)*
-                        #(#ignore_comments_expression)*
+                    ::xmlity::__private::visit_children(#seq_access, #ignore_whitespace, #ignore_comments, #allow_unknown, |#access_ident| {
                         #(#field_visits)*
-                        #(#skip_unknown)*
-                    }
+                        ::core::result::Result::Ok(::xmlity::__private::Visit::Unmatched)
+                    })?;
                 })
             }
         }
@@ -2,34 +2,38 @@ use std::{borrow::Cow, iter};
 
 use proc_macro2::Span;
 use quote::quote;
-use syn::{
-    parse_quote, DeriveInput, Expr, Ident, Index, ItemStruct, Lifetime, LifetimeParam, Stmt,
-};
+use syn::{parse_quote, DeriveInput, Expr, Ident, Index, ItemStruct, Lifetime, Stmt};
 
 use crate::{
-    common::{FieldIdent, StructType},
+    common::{insert_deserialize_lifetime, nested_generics, FieldIdent, StructType},
     de::common::deserialize_option_value_expr,
+    meta::MetaForm,
     options::{
+        enums::groups::{GroupVariant, GroupVariantKind},
         records::{
             fields::{AttributeOpts, ChildOpts, GroupOpts},
             roots::RootGroupOpts,
         },
-        FieldWithOpts, GroupOrder,
+        DeriveDirection, FieldWithOpts, GroupOrder,
     },
     DeriveError, DeriveMacro, DeriveResult,
 };
 
 use super::{
-    builders::{DeserializationGroupBuilderBuilder, DeserializationGroupBuilderContentExt},
+    builders::{
+        DeserializationGroupBuilderBuilder, DeserializationGroupBuilderContentExt,
+        DeserializeBuilderExt,
+    },
     common::{
-        all_attributes_done_expr, attribute_fields, attribute_group_fields,
-        builder_attribute_field_visitor, builder_element_field_visitor, element_fields,
-        element_group_fields, group_fields,
+        all_attributes_done_expr, ancestor_attribute_lookups, attribute_fields,
+        attribute_group_fields, builder_attribute_field_visitor, builder_element_field_visitor,
+        element_fields, element_group_fields, fields, group_fields,
     },
+    deserialize::SimpleDeserializeAttributeBuilder,
 };
 
 use super::common::all_elements_done_expr;
-use crate::common::{constructor_expr, struct_definition_expr};
+use crate::common::{constructor_expr, ordered_constructor_expr, struct_definition_expr};
 
 pub struct DeriveDeserializationGroupStruct<'a> {
     opts: &'a RootGroupOpts,
@@ -63,6 +67,7 @@ impl DeserializationGroupBuilderBuilder for DeriveDeserializationGroupStruct<'_>
     ) -> Result<Option<Vec<Stmt>>, DeriveError> {
         let attribute_visit = builder_attribute_field_visitor(
             &parse_quote!(&mut #attributes_access_ident),
+            &self.ast.generics,
             |field| parse_quote! {self.#field},
             attribute_group_fields(self.ast)?,
             parse_quote! {return ::core::result::Result::Ok(false);},
@@ -76,7 +81,15 @@ impl DeserializationGroupBuilderBuilder for DeriveDeserializationGroupStruct<'_>
             false,
         )?;
 
+        let ancestor_attribute_lookups = ancestor_attribute_lookups(
+            &parse_quote!(&#attributes_access_ident),
+            |field| parse_quote! {self.#field},
+            attribute_group_fields(self.ast)?,
+        );
+
         Ok(Some(parse_quote! {
+                #(#ancestor_attribute_lookups)*
+
                 #(#attribute_visit)*
 
                 Ok(false)
@@ -104,10 +117,19 @@ impl DeserializationGroupBuilderBuilder for DeriveDeserializationGroupStruct<'_>
         _elements_access_type: &syn::Type,
         _deserialize_lifetime: &Lifetime,
     ) -> DeriveResult<Option<Vec<Stmt>>> {
+        let mut element_group_fields = element_group_fields(self.ast)?
+            .into_iter()
+            .collect::<Vec<_>>();
+        // Ordered children are expected in the order they are serialized in.
+        if !matches!(self.opts.children_order, GroupOrder::None) {
+            element_group_fields.sort_by_key(|field| field.options.serialize_order());
+        }
+
         let element_visit = builder_element_field_visitor(
             &parse_quote!(&mut #elements_access_ident),
+            &self.ast.generics,
             |field| parse_quote! {self.#field},
-            element_group_fields(self.ast)?,
+            element_group_fields,
             parse_quote! {return ::core::result::Result::Ok(false);},
             parse_quote! {return ::core::result::Result::Ok(true);},
             parse_quote! {return ::core::result::Result::Ok(true);},
@@ -143,18 +165,72 @@ impl DeserializationGroupBuilderBuilder for DeriveDeserializationGroupStruct<'_>
         )))
     }
 
+    fn completed_fields_fn_body(&self) -> Result<Option<Vec<Stmt>>, DeriveError> {
+        let completed_ident = Ident::new("__completed", Span::mixed_site());
+
+        let local_fields = attribute_fields(self.ast)?
+            .into_iter()
+            .map(|field| field.field_ident)
+            .chain(
+                element_fields(self.ast)?
+                    .into_iter()
+                    .map(|field| field.field_ident),
+            )
+            .map::<Stmt, _>(|field_ident| {
+                let field_name = field_ident.to_string();
+                parse_quote! {
+                    if ::core::option::Option::is_some(&self.#field_ident) {
+                        #completed_ident.push(#field_name);
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let group_fields = group_fields(self.ast)?
+            .into_iter()
+            .map::<Stmt, _>(|FieldWithOpts { field_ident, .. }| {
+                parse_quote! {
+                    ::core::iter::Extend::extend(
+                        &mut #completed_ident,
+                        ::xmlity::de::DeserializationGroupBuilder::completed_fields(&self.#field_ident),
+                    );
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if local_fields.is_empty() && group_fields.is_empty() {
+            return Ok(Some(parse_quote! {
+                ::std::vec::Vec::new()
+            }));
+        }
+
+        Ok(Some(parse_quote! {
+            let mut #completed_ident = ::std::vec::Vec::new();
+            #(#local_fields)*
+            #(#group_fields)*
+            #completed_ident
+        }))
+    }
+
     fn finish_fn_body(
         &self,
         ident: &syn::Ident,
         error_type: &syn::Type,
         deserialize_lifetime: &Lifetime,
     ) -> Result<Vec<Stmt>, DeriveError> {
+        let fields = fields(self.ast)?;
         let finish_constructor = finish_constructor_expr(
             &parse_quote!(#ident),
             deserialize_lifetime,
             element_fields(self.ast)?,
             attribute_fields(self.ast)?,
             group_fields(self.ast)?,
+            |field| {
+                fields
+                    .iter()
+                    .find(|f| *f.field_ident.to_named_ident() == *field)
+                    .and_then(|f| f.options.default_from().cloned())
+            },
             &Self::constructor_type(self.ast),
             error_type,
         );
@@ -225,10 +301,7 @@ impl DeserializationGroupBuilderBuilder for DeriveDeserializationGroupStruct<'_>
             )));
 
         let mut generics = self.ast.generics.clone();
-        generics.params.insert(
-            0,
-            syn::GenericParam::Lifetime(LifetimeParam::new((*deserialize_lifetime).to_owned())),
-        );
+        insert_deserialize_lifetime(&mut generics, deserialize_lifetime);
 
         Ok(struct_definition_expr(
             builder_ident,
@@ -312,12 +385,226 @@ impl DeserializationGroupBuilderBuilder for DeriveDeserializationGroupStruct<'_>
     }
 }
 
+/// Deserializes an enum as a group, where each variant is either an attribute or a child of the element. The first attribute or child matching a variant decides the value.
+pub struct DeriveDeserializationGroupEnum<'a> {
+    ast: &'a DeriveInput,
+    variants: Vec<GroupVariant<'a>>,
+}
+
+impl<'a> DeriveDeserializationGroupEnum<'a> {
+    pub fn new(ast: &'a DeriveInput) -> Result<Self, DeriveError> {
+        Ok(Self {
+            ast,
+            variants: GroupVariant::parse_all(ast)?,
+        })
+    }
+
+    fn value_ident() -> Ident {
+        Ident::new("__value", Span::call_site())
+    }
+
+    fn variant_constructor(&self, variant: &GroupVariant<'_>, value: &Expr) -> Expr {
+        let ident = &self.ast.ident;
+        let variant_ident = &variant.variant.ident;
+        let member = &variant.member;
+
+        parse_quote!(#ident::#variant_ident { #member: #value })
+    }
+}
+
+impl DeserializationGroupBuilderBuilder for DeriveDeserializationGroupEnum<'_> {
+    fn contribute_attributes_fn_body(
+        &self,
+        attributes_access_ident: &Ident,
+        _attributes_access_type: &syn::Type,
+        _deserialize_lifetime: &Lifetime,
+    ) -> Result<Option<Vec<Stmt>>, DeriveError> {
+        let value_ident = Self::value_ident();
+        let temporary_value_ident = Ident::new("__v", Span::call_site());
+        let wrapper_ident = Ident::new("__W", Span::call_site());
+        let wrapper_generics = nested_generics(&self.ast.generics, None);
+
+        let attempts = self
+            .variants
+            .iter()
+            .filter_map(|variant| {
+                variant
+                    .attribute_name()
+                    .map(|(_, expanded_name)| (variant, expanded_name))
+            })
+            .map::<Result<Stmt, DeriveError>, _>(|(variant, expanded_name)| {
+                let wrapper = SimpleDeserializeAttributeBuilder {
+                    ident: &wrapper_ident,
+                    generics: &wrapper_generics,
+                    required_expanded_name: Some(expanded_name),
+                    item_type: variant.field_type,
+                };
+                let definition = wrapper.struct_definition();
+                let trait_impl = wrapper.to_builder().deserialize_trait_impl()?;
+                let constructor =
+                    self.variant_constructor(variant, &parse_quote!(#temporary_value_ident.__value));
+                let (_, ty_generics, _) = wrapper_generics.split_for_impl();
+
+                Ok(parse_quote! {
+                    {
+                        #definition
+                        #trait_impl
+
+                        if let ::core::result::Result::Ok(::core::option::Option::Some(#temporary_value_ident)) = ::xmlity::de::AttributesAccess::next_attribute::<#wrapper_ident #ty_generics>(&mut #attributes_access_ident) {
+                            self.#value_ident = ::core::option::Option::Some(#constructor);
+                            return ::core::result::Result::Ok(true);
+                        }
+                    }
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if attempts.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(parse_quote! {
+            if ::core::option::Option::is_some(&self.#value_ident) {
+                return ::core::result::Result::Ok(false);
+            }
+
+            #(#attempts)*
+
+            ::core::result::Result::Ok(false)
+        }))
+    }
+
+    fn attributes_done_fn_body(
+        &self,
+        _deserialize_lifetime: &Lifetime,
+    ) -> Result<Option<Vec<Stmt>>, DeriveError> {
+        let value_ident = Self::value_ident();
+
+        Ok(Some(parse_quote!(
+            ::core::option::Option::is_some(&self.#value_ident)
+        )))
+    }
+
+    fn contribute_elements_fn_body(
+        &self,
+        elements_access_ident: &Ident,
+        _elements_access_type: &syn::Type,
+        _deserialize_lifetime: &Lifetime,
+    ) -> DeriveResult<Option<Vec<Stmt>>> {
+        let value_ident = Self::value_ident();
+        let temporary_value_ident = Ident::new("__v", Span::call_site());
+
+        let attempts = self
+            .variants
+            .iter()
+            .filter(|variant| matches!(variant.kind, GroupVariantKind::Value))
+            .map::<Stmt, _>(|variant| {
+                let field_type = variant.field_type;
+                let constructor =
+                    self.variant_constructor(variant, &parse_quote!(#temporary_value_ident));
+
+                parse_quote! {
+                    if let ::core::result::Result::Ok(::core::option::Option::Some(#temporary_value_ident)) = ::xmlity::de::SeqAccess::next_element_seq::<#field_type>(&mut #elements_access_ident) {
+                        self.#value_ident = ::core::option::Option::Some(#constructor);
+                        return ::core::result::Result::Ok(true);
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if attempts.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(parse_quote! {
+            if ::core::option::Option::is_some(&self.#value_ident) {
+                return ::core::result::Result::Ok(false);
+            }
+
+            #(#attempts)*
+
+            ::core::result::Result::Ok(false)
+        }))
+    }
+
+    fn elements_done_fn_body(
+        &self,
+        deserialize_lifetime: &Lifetime,
+    ) -> Result<Option<Vec<Stmt>>, DeriveError> {
+        self.attributes_done_fn_body(deserialize_lifetime)
+    }
+
+    fn completed_fields_fn_body(&self) -> Result<Option<Vec<Stmt>>, DeriveError> {
+        Ok(None)
+    }
+
+    fn finish_fn_body(
+        &self,
+        _ident: &syn::Ident,
+        _error_type: &syn::Type,
+        _deserialize_lifetime: &Lifetime,
+    ) -> Result<Vec<Stmt>, DeriveError> {
+        let value_ident = Self::value_ident();
+        let ident_string = self.ast.ident.to_string();
+
+        Ok(parse_quote! {
+            ::core::option::Option::ok_or_else(self.#value_ident, || {
+                ::xmlity::de::Error::no_possible_variant(#ident_string)
+            })
+        })
+    }
+
+    fn builder_definition(
+        &self,
+        builder_ident: &Ident,
+        deserialize_lifetime: &Lifetime,
+    ) -> Result<ItemStruct, DeriveError> {
+        let ident = &self.ast.ident;
+        let vis = &self.ast.vis;
+        let value_ident = Self::value_ident();
+        let non_bound_generics = crate::common::non_bound_generics(&self.ast.generics);
+
+        let mut generics = self.ast.generics.clone();
+        insert_deserialize_lifetime(&mut generics, deserialize_lifetime);
+
+        let where_clause = &generics.where_clause;
+
+        Ok(parse_quote! {
+            #vis struct #builder_ident #generics #where_clause {
+                #value_ident: ::core::option::Option<#ident #non_bound_generics>,
+                __marker: ::core::marker::PhantomData<&#deserialize_lifetime ()>,
+            }
+        })
+    }
+
+    fn builder_constructor(&self, builder_ident: &Ident) -> Result<Vec<Stmt>, DeriveError> {
+        let value_ident = Self::value_ident();
+
+        Ok(parse_quote! {
+            #builder_ident {
+                #value_ident: ::core::option::Option::None,
+                __marker: ::core::marker::PhantomData,
+            }
+        })
+    }
+
+    fn ident(&self) -> std::borrow::Cow<'_, Ident> {
+        Cow::Borrowed(&self.ast.ident)
+    }
+
+    fn generics(&self) -> std::borrow::Cow<'_, syn::Generics> {
+        Cow::Borrowed(&self.ast.generics)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn finish_constructor_expr(
     ident: &syn::Path,
     visitor_lifetime: &syn::Lifetime,
     element_fields: impl IntoIterator<Item = FieldWithOpts<FieldIdent, ChildOpts>>,
     attribute_fields: impl IntoIterator<Item = FieldWithOpts<FieldIdent, AttributeOpts>>,
     group_fields: impl IntoIterator<Item = FieldWithOpts<FieldIdent, GroupOpts>>,
+    default_from: impl Fn(&Ident) -> Option<Ident>,
     constructor_type: &StructType,
     error_type: &syn::Type,
 ) -> Expr {
@@ -371,7 +658,12 @@ fn finish_constructor_expr(
     let value_expressions_constructors =
         local_value_expressions_constructors.chain(group_value_expressions_constructors);
 
-    constructor_expr(ident, value_expressions_constructors, constructor_type)
+    ordered_constructor_expr(
+        ident,
+        value_expressions_constructors,
+        default_from,
+        constructor_type,
+    )
 }
 
 enum DeserializationGroupOption {
@@ -389,6 +681,12 @@ impl DeserializationGroupOption {
 pub struct DeriveDeserializationGroup;
 
 impl DeriveMacro for DeriveDeserializationGroup {
+    const DIRECTION: DeriveDirection = DeriveDirection::Deserialize;
+
+    fn meta_form(_ast: &syn::DeriveInput) -> Option<MetaForm> {
+        Some(MetaForm::Group)
+    }
+
     fn input_to_derive(ast: &DeriveInput) -> Result<proc_macro2::TokenStream, DeriveError> {
         let DeserializationGroupOption::Group(opts) = DeserializationGroupOption::parse(ast)?;
 
@@ -396,9 +694,9 @@ impl DeriveMacro for DeriveDeserializationGroup {
             syn::Data::Struct(_) => DeriveDeserializationGroupStruct::new(ast, &opts)
                 .total_impl()
                 .map(|items| quote! { #(#items)* }),
-            syn::Data::Enum(_) => Err(DeriveError::custom(
-                "Enums are not supported for deserialization groups.",
-            )),
+            syn::Data::Enum(_) => DeriveDeserializationGroupEnum::new(ast)?
+                .total_impl()
+                .map(|items| quote! { #(#items)* }),
             syn::Data::Union(_) => Err(DeriveError::custom(
                 "Unions are not supported for deserialization groups.",
             )),
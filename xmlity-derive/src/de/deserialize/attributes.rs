@@ -4,10 +4,14 @@ use std::{
 };
 
 use proc_macro2::Span;
-use syn::{parse_quote, Ident, Lifetime, LifetimeParam, Stmt, Type};
+use quote::quote;
+use syn::{parse_quote, Ident, Lifetime, Stmt, Type};
 
 use crate::{
-    common::{non_bound_generics, ExpandedName, StructTypeWithFields},
+    common::{
+        generics_marker_field, insert_deserialize_lifetime, non_bound_generics, ExpandedName,
+        StructTypeWithFields,
+    },
     de::builders::{DeserializeBuilder, VisitorBuilder, VisitorBuilderExt},
     options::{
         records::{
@@ -78,6 +82,7 @@ impl<'a, T: Fn(syn::Expr) -> syn::Expr> RecordDeserializeAttributeBuilder<'a, T>
             generics,
             required_expanded_name,
             struct_type,
+            generics_marker: false,
         })
     }
 }
@@ -99,11 +104,22 @@ impl SimpleDeserializeAttributeBuilder<'_> {
             ident, item_type, ..
         } = self;
 
+        let generics = self.generics;
         let value_access_ident = self.value_access_ident();
+        let marker = generics_marker_field(generics).map(
+            |FieldWithOpts {
+                 field_ident,
+                 field_type,
+                 ..
+             }| quote!(#field_ident: #field_type,),
+        );
+
+        let where_clause = &generics.where_clause;
 
         parse_quote! {
-            struct #ident {
+            struct #ident #generics #where_clause {
                 #value_access_ident: #item_type,
+                #marker
             }
         }
     }
@@ -112,6 +128,7 @@ impl SimpleDeserializeAttributeBuilder<'_> {
         StructDeserializeAttributeBuilder {
             ident: self.ident,
             generics: self.generics,
+            generics_marker: generics_marker_field(self.generics).is_some(),
             required_expanded_name: self.required_expanded_name.clone(),
             struct_type: StructTypeWithFields::Named(FieldWithOpts {
                 field_ident: self.value_access_ident(),
@@ -132,6 +149,8 @@ pub struct StructDeserializeAttributeBuilder<'a> {
         FieldWithOpts<syn::Ident, FieldOpts>,
         FieldWithOpts<syn::Index, FieldOpts>,
     >,
+    /// Whether the struct has a field for its generics besides its named field, see [`generics_marker_field`].
+    pub generics_marker: bool,
 }
 
 impl VisitorBuilder for StructDeserializeAttributeBuilder<'_> {
@@ -143,9 +162,10 @@ impl VisitorBuilder for StructDeserializeAttributeBuilder<'_> {
     ) -> Result<Option<Vec<Stmt>>, DeriveError> {
         let Self {
             ident,
+            generics,
             required_expanded_name,
             struct_type,
-            ..
+            generics_marker,
         } = self;
 
         let xml_name_identification = required_expanded_name.as_ref().map::<Stmt, _>(|qname| {
@@ -160,9 +180,15 @@ impl VisitorBuilder for StructDeserializeAttributeBuilder<'_> {
                 field_type,
                 ..
             }) => {
+                let marker = generics_marker
+                    .then(|| generics_marker_field(generics))
+                    .flatten()
+                    .map(|FieldWithOpts { field_ident, .. }| {
+                        quote!(#field_ident: ::core::marker::PhantomData,)
+                    });
                 parse_quote! {
                     ::xmlity::de::AttributeAccess::value::<#field_type>(#attribute_access_ident)
-                    .map(|a| #ident {#field_ident: a})
+                    .map(|a| #ident {#field_ident: a, #marker})
                 }
             }
             StructTypeWithFields::Unnamed(FieldWithOpts { field_type, .. }) => {
@@ -196,13 +222,12 @@ impl VisitorBuilder for StructDeserializeAttributeBuilder<'_> {
         let visitor_ident = Ident::new("__Visitor", Span::mixed_site());
         let visitor_lifetime = Lifetime::new("'__visitor", Span::mixed_site());
 
-        deserialize_generics.params.insert(
-            0,
-            syn::GenericParam::Lifetime(LifetimeParam::new(visitor_lifetime.clone())),
-        );
+        insert_deserialize_lifetime(&mut deserialize_generics, &visitor_lifetime);
+
+        let where_clause = &deserialize_generics.where_clause;
 
         Ok(parse_quote! {
-            struct #visitor_ident #deserialize_generics {
+            struct #visitor_ident #deserialize_generics #where_clause {
                 marker: ::core::marker::PhantomData<#ident #non_bound_generics>,
                 lifetime: ::core::marker::PhantomData<&#visitor_lifetime ()>,
             }
@@ -251,3 +276,141 @@ impl DeserializeBuilder for StructDeserializeAttributeBuilder<'_> {
         Cow::Borrowed(self.generics)
     }
 }
+
+/// Deserializes an enum whose variants each wrap the value of a different attribute, picking the variant by the name of the attribute.
+pub struct EnumDeserializeAttributeBuilder<'a> {
+    ast: &'a syn::DeriveInput,
+    variants: Vec<(&'a syn::Variant, RootAttributeOpts)>,
+}
+
+impl<'a> EnumDeserializeAttributeBuilder<'a> {
+    pub fn new(
+        ast: &'a syn::DeriveInput,
+        variants: Vec<(&'a syn::Variant, RootAttributeOpts)>,
+    ) -> Self {
+        Self { ast, variants }
+    }
+}
+
+impl VisitorBuilder for EnumDeserializeAttributeBuilder<'_> {
+    fn visit_attribute_fn_body(
+        &self,
+        _visitor_lifetime: &Lifetime,
+        attribute_access_ident: &Ident,
+        _access_type: &Type,
+    ) -> Result<Option<Vec<Stmt>>, DeriveError> {
+        let ident = &self.ast.ident;
+        let value_ident = Ident::new("__v", Span::mixed_site());
+
+        let variants = self
+            .variants
+            .iter()
+            .map::<Result<Stmt, DeriveError>, _>(|(variant, opts)| {
+                let variant_ident = &variant.ident;
+                let (field_type, constructor): (&Type, syn::Expr) = match &variant.fields {
+                    syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => (
+                        &fields.unnamed[0].ty,
+                        parse_quote!(#ident::#variant_ident(#value_ident)),
+                    ),
+                    syn::Fields::Named(fields) if fields.named.len() == 1 => {
+                        let field_ident = &fields.named[0].ident;
+                        (
+                            &fields.named[0].ty,
+                            parse_quote!(#ident::#variant_ident { #field_ident: #value_ident }),
+                        )
+                    }
+                    _ => {
+                        return Err(DeriveError::custom(format!(
+                            "Attribute variant `{variant_ident}` must have exactly one field"
+                        )))
+                    }
+                };
+
+                let condition: syn::Expr = if opts.deserialize_any_name {
+                    parse_quote!(true)
+                } else {
+                    let expanded_name = opts.expanded_name(&variant_ident.to_string()).into_owned();
+                    parse_quote!(::xmlity::de::AttributeAccess::name(&#attribute_access_ident) == #expanded_name)
+                };
+
+                Ok(parse_quote! {
+                    if #condition {
+                        return ::xmlity::de::AttributeAccess::value::<#field_type>(#attribute_access_ident)
+                            .map(|#value_ident| #constructor);
+                    }
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let ident_string = ident.to_string();
+
+        Ok(Some(parse_quote! {
+            #(#variants)*
+
+            ::core::result::Result::Err(::xmlity::de::Error::no_possible_variant(#ident_string))
+        }))
+    }
+
+    fn visitor_definition(&self) -> Result<syn::ItemStruct, DeriveError> {
+        let ident = &self.ast.ident;
+        let non_bound_generics = non_bound_generics(&self.ast.generics);
+
+        let mut deserialize_generics = self.ast.generics.clone();
+
+        let visitor_ident = Ident::new("__Visitor", Span::mixed_site());
+        let visitor_lifetime = Lifetime::new("'__visitor", Span::mixed_site());
+
+        insert_deserialize_lifetime(&mut deserialize_generics, &visitor_lifetime);
+
+        let where_clause = &deserialize_generics.where_clause;
+
+        Ok(parse_quote! {
+            struct #visitor_ident #deserialize_generics #where_clause {
+                marker: ::core::marker::PhantomData<#ident #non_bound_generics>,
+                lifetime: ::core::marker::PhantomData<&#visitor_lifetime ()>,
+            }
+        })
+    }
+
+    fn visitor_ident(&self) -> Cow<'_, Ident> {
+        Cow::Borrowed(&self.ast.ident)
+    }
+
+    fn visitor_generics(&self) -> Cow<'_, syn::Generics> {
+        Cow::Borrowed(&self.ast.generics)
+    }
+}
+
+impl DeserializeBuilder for EnumDeserializeAttributeBuilder<'_> {
+    fn deserialize_fn_body(
+        &self,
+        deserializer_ident: &Ident,
+        _deserialize_lifetime: &Lifetime,
+    ) -> Result<Vec<Stmt>, DeriveError> {
+        let formatter_expecting = format!("enum {}", self.ast.ident);
+
+        let visitor_ident = Ident::new("__Visitor", Span::mixed_site());
+
+        let visitor_def = self.visitor_definition()?;
+        let visitor_trait_impl = self.visitor_trait_impl(&visitor_ident, &formatter_expecting)?;
+
+        Ok(parse_quote! {
+            #visitor_def
+
+            #visitor_trait_impl
+
+            ::xmlity::de::Deserializer::deserialize_any(#deserializer_ident, #visitor_ident {
+                lifetime: ::core::marker::PhantomData,
+                marker: ::core::marker::PhantomData,
+            })
+        })
+    }
+
+    fn ident(&self) -> Cow<'_, Ident> {
+        Cow::Borrowed(&self.ast.ident)
+    }
+
+    fn generics(&self) -> Cow<'_, syn::Generics> {
+        Cow::Borrowed(&self.ast.generics)
+    }
+}
@@ -3,12 +3,12 @@
 use std::borrow::Cow;
 
 use proc_macro2::Span;
-use syn::{parse_quote, Ident, Lifetime, LifetimeParam, Stmt, Type};
+use syn::{parse_quote, Ident, Lifetime, Stmt, Type};
 
 use crate::{
     common::{
-        constructor_expr, non_bound_generics, ExpandedName, FieldIdent, StructType,
-        StructTypeWithFields,
+        insert_deserialize_lifetime, non_bound_generics, ordered_constructor_expr, ExpandedName,
+        FieldIdent, StructType, StructTypeWithFields,
     },
     de::{
         builders::{DeserializeBuilder, VisitorBuilder, VisitorBuilderExt},
@@ -25,10 +25,14 @@ pub struct RecordDeserializeElementBuilder<'a, T: Fn(syn::Expr) -> syn::Expr> {
     pub ignore_whitespace: IgnoreWhitespace,
     pub ignore_comments: IgnoreComments,
     pub required_expanded_name: Option<ExpandedName<'static>>,
+    /// Other names that are accepted in place of `required_expanded_name`.
+    pub alias_expanded_names: Vec<ExpandedName<'static>>,
     pub allow_unknown_attributes: AllowUnknown,
     pub allow_unknown_children: AllowUnknown,
     pub children_order: ElementOrder,
     pub attribute_order: ElementOrder,
+    /// Whether the struct is deserialized as its default value if the element is missing.
+    pub default: bool,
 }
 
 impl<T: Fn(syn::Expr) -> syn::Expr> VisitorBuilder for RecordDeserializeElementBuilder<'_, T> {
@@ -41,14 +45,28 @@ impl<T: Fn(syn::Expr) -> syn::Expr> VisitorBuilder for RecordDeserializeElementB
         let Self {
             input,
             required_expanded_name,
+            alias_expanded_names,
             ..
         } = self;
 
         let xml_name_identification = required_expanded_name.as_ref().map::<Stmt, _>(|qname| {
-          parse_quote! {
-              ::xmlity::de::ElementAccessExt::ensure_name::<<#access_type as ::xmlity::de::AttributesAccess<#visitor_lifetime>>::Error>(&#element_access_ident, &#qname)?;
-          }
-      });
+            let ensure_name: Stmt = parse_quote! {
+                ::xmlity::de::ElementAccessExt::ensure_name::<<#access_type as ::xmlity::de::AttributesAccess<#visitor_lifetime>>::Error>(&#element_access_ident, &#qname)?;
+            };
+
+            if alias_expanded_names.is_empty() {
+                return ensure_name;
+            }
+
+            parse_quote! {
+                if ![#(#alias_expanded_names),*]
+                    .iter()
+                    .any(|alias| ::xmlity::de::ElementAccess::name(&#element_access_ident) == *alias)
+                {
+                    #ensure_name
+                }
+            }
+        });
 
         let (constructor_type, fields) = match &input.fields {
             StructTypeWithFields::Named(n) => (
@@ -68,6 +86,7 @@ impl<T: Fn(syn::Expr) -> syn::Expr> VisitorBuilder for RecordDeserializeElementB
 
         let element_loop_accessor = (!fields.is_empty()).then(|| {
             ElementLoopAccessor::new(
+                &self.input.generics,
                 self.allow_unknown_children,
                 self.allow_unknown_attributes,
                 self.children_order,
@@ -91,21 +110,19 @@ impl<T: Fn(syn::Expr) -> syn::Expr> VisitorBuilder for RecordDeserializeElementB
             .transpose()?
             .unwrap_or_default();
 
-        let children_access_ident = Ident::new("__children", element_access_ident.span());
+        let ancestor_attribute_lookups = element_loop_accessor
+            .as_ref()
+            .map(|a| {
+                a.ancestor_attribute_lookups(fields.clone(), &parse_quote!(&#element_access_ident))
+            })
+            .unwrap_or_default();
 
-        let children_access_ty: syn::Type = parse_quote!(
-            <#access_type as ::xmlity::de::ElementAccess<#visitor_lifetime>>::ChildrenAccess
-        );
+        let children_access_ident = Ident::new("__children", element_access_ident.span());
 
         let children_loop = element_loop_accessor
             .as_ref()
             .map(|a| {
-                a.children_access_loop(
-                    fields.clone(),
-                    &parse_quote!(&mut #children_access_ident),
-                    &children_access_ty,
-                    visitor_lifetime,
-                )
+                a.children_access_loop(fields.clone(), &parse_quote!(&mut #children_access_ident))
             })
             .transpose()?
             .unwrap_or_default();
@@ -116,13 +133,19 @@ impl<T: Fn(syn::Expr) -> syn::Expr> VisitorBuilder for RecordDeserializeElementB
 
         let constructor_exprs = element_loop_accessor
             .as_ref()
-            .map(|a| a.value_expressions(fields, visitor_lifetime, &error_type))
+            .map(|a| a.value_expressions(fields.clone(), visitor_lifetime, &error_type))
             .transpose()?
             .unwrap_or_default();
 
-        let constructor = (self.input.wrapper_function)(constructor_expr(
+        let constructor = (self.input.wrapper_function)(ordered_constructor_expr(
             &self.input.constructor_path,
             constructor_exprs,
+            |field| {
+                fields
+                    .iter()
+                    .find(|f| *f.field_ident.to_named_ident() == *field)
+                    .and_then(|f| f.options.default_from().cloned())
+            },
             &constructor_type,
         ));
 
@@ -131,6 +154,8 @@ impl<T: Fn(syn::Expr) -> syn::Expr> VisitorBuilder for RecordDeserializeElementB
 
             #(#getter_declarations)*
 
+            #(#ancestor_attribute_lookups)*
+
             #(#attribute_loop)*
 
             let mut #children_access_ident = ::xmlity::de::ElementAccess::children(#element_access_ident)?;
@@ -141,6 +166,18 @@ impl<T: Fn(syn::Expr) -> syn::Expr> VisitorBuilder for RecordDeserializeElementB
         }))
     }
 
+    fn visit_none_fn_body(
+        &self,
+        _visitor_lifetime: &Lifetime,
+        _error_type: &Type,
+    ) -> Result<Option<Vec<Stmt>>, DeriveError> {
+        Ok(self.default.then(|| {
+            parse_quote! {
+                ::core::result::Result::Ok(::core::default::Default::default())
+            }
+        }))
+    }
+
     fn visitor_definition(&self) -> Result<syn::ItemStruct, DeriveError> {
         let RecordInput {
             impl_for_ident: ident,
@@ -154,13 +191,12 @@ impl<T: Fn(syn::Expr) -> syn::Expr> VisitorBuilder for RecordDeserializeElementB
         let visitor_ident = Ident::new("__Visitor", Span::mixed_site());
         let visitor_lifetime = Lifetime::new("'__visitor", Span::mixed_site());
 
-        deserialize_generics.params.insert(
-            0,
-            syn::GenericParam::Lifetime(LifetimeParam::new(visitor_lifetime.clone())),
-        );
+        insert_deserialize_lifetime(&mut deserialize_generics, &visitor_lifetime);
+
+        let where_clause = &deserialize_generics.where_clause;
 
         Ok(parse_quote! {
-            struct #visitor_ident #deserialize_generics {
+            struct #visitor_ident #deserialize_generics #where_clause {
                 marker: ::core::marker::PhantomData<#ident #non_bound_generics>,
                 lifetime: ::core::marker::PhantomData<&#visitor_lifetime ()>,
             }
@@ -4,15 +4,18 @@ use std::{borrow::Cow, ops::Not};
 pub use attributes::SimpleDeserializeAttributeBuilder;
 mod elements;
 mod none;
+mod pi;
 mod single_child_element;
 mod variant;
-use attributes::RecordDeserializeAttributeBuilder;
+use attributes::{EnumDeserializeAttributeBuilder, RecordDeserializeAttributeBuilder};
 use elements::RecordDeserializeElementBuilder;
 use none::{EnumVisitorBuilder, RecordDeserializeValueBuilder};
+use pi::RecordDeserializePiBuilder;
 use quote::ToTokens;
 
 use crate::{
-    options::{enums, records, WithExpandedNameExt},
+    meta::{self, MetaForm},
+    options::{enums, records, DeriveDirection, WithExpandedNameExt},
     DeriveError, DeriveMacro,
 };
 
@@ -50,10 +53,20 @@ impl<T: Fn(syn::Expr) -> syn::Expr> DeserializeBuilder for RecordDeserializeBuil
                     opts.expanded_name(&deserializer_ident.to_string())
                         .into_owned()
                 }),
+                alias_expanded_names: opts
+                    .aliases
+                    .iter()
+                    .map(|alias| {
+                        alias
+                            .expanded_name(&deserializer_ident.to_string())
+                            .into_owned()
+                    })
+                    .collect(),
                 allow_unknown_attributes: opts.allow_unknown_attributes,
                 allow_unknown_children: opts.allow_unknown_children,
                 children_order: opts.children_order,
                 attribute_order: opts.attribute_order,
+                default: opts.default,
             }
             .deserialize_fn_body(deserializer_ident, deserialize_lifetime),
             DeserializeRootOpts::Attribute(opts) => {
@@ -63,14 +76,19 @@ impl<T: Fn(syn::Expr) -> syn::Expr> DeserializeBuilder for RecordDeserializeBuil
             }
             DeserializeRootOpts::Value(opts) => RecordDeserializeValueBuilder {
                 input: self.input,
-                value: opts.value.clone(),
+                value: opts.value_expr(),
                 ignore_whitespace: opts.ignore_whitespace,
                 ignore_comments: opts.ignore_comments,
                 allow_unknown_children: opts.allow_unknown,
                 children_order: opts.order,
                 deserialize_with: opts.deserialize_with(),
+                case_insensitive: opts.case_insensitive,
+                collapse_whitespace: opts.collapse_whitespace,
+                transparent: opts.transparent,
             }
             .deserialize_fn_body(deserializer_ident, deserialize_lifetime),
+            DeserializeRootOpts::Pi(opts) => RecordDeserializePiBuilder::new(self.input, opts)
+                .deserialize_fn_body(deserializer_ident, deserialize_lifetime),
             DeserializeRootOpts::None => RecordDeserializeValueBuilder {
                 input: self.input,
                 ignore_whitespace: Default::default(),
@@ -79,6 +97,9 @@ impl<T: Fn(syn::Expr) -> syn::Expr> DeserializeBuilder for RecordDeserializeBuil
                 children_order: Default::default(),
                 deserialize_with: None,
                 value: None,
+                case_insensitive: false,
+                collapse_whitespace: false,
+                transparent: false,
             }
             .deserialize_fn_body(deserializer_ident, deserialize_lifetime),
         }
@@ -96,6 +117,12 @@ impl<T: Fn(syn::Expr) -> syn::Expr> DeserializeBuilder for RecordDeserializeBuil
 pub struct DeriveDeserialize;
 
 impl DeriveMacro for DeriveDeserialize {
+    const DIRECTION: DeriveDirection = DeriveDirection::Deserialize;
+
+    fn meta_form(ast: &syn::DeriveInput) -> Option<MetaForm> {
+        (!meta::is_group(ast)).then_some(MetaForm::Item)
+    }
+
     fn input_to_derive(ast: &syn::DeriveInput) -> Result<proc_macro2::TokenStream, DeriveError> {
         match &ast.data {
             syn::Data::Struct(_) => {
@@ -106,9 +133,38 @@ impl DeriveMacro for DeriveDeserialize {
                     .deserialize_trait_impl()
                     .map(|a| a.to_token_stream())
             }
-            syn::Data::Enum(_) => {
+            syn::Data::Enum(data) => {
                 let opts = enums::roots::RootOpts::parse(ast)?;
 
+                let attribute_variants = data
+                    .variants
+                    .iter()
+                    .map(|variant| {
+                        Ok(
+                            match enums::variants::DeserializeRootOpts::parse(&variant.attrs)? {
+                                enums::variants::DeserializeRootOpts::Attribute(opts) => {
+                                    Some((variant, opts))
+                                }
+                                _ => None,
+                            },
+                        )
+                    })
+                    .collect::<Result<Vec<_>, DeriveError>>()?;
+
+                if attribute_variants.iter().any(Option::is_some) {
+                    let Some(attribute_variants) =
+                        attribute_variants.into_iter().collect::<Option<Vec<_>>>()
+                    else {
+                        return Err(DeriveError::custom(
+                            "Variants with `xattribute` can not be mixed with other kinds of variants.",
+                        ));
+                    };
+
+                    return EnumDeserializeAttributeBuilder::new(ast, attribute_variants)
+                        .deserialize_trait_impl()
+                        .map(|a| a.to_token_stream());
+                }
+
                 let value_opts = match &opts {
                     enums::roots::RootOpts::None => None,
                     enums::roots::RootOpts::Value(opts) => Some(opts),
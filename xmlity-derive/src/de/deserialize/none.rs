@@ -1,10 +1,13 @@
 use std::borrow::Cow;
 
 use proc_macro2::Span;
-use syn::{parse_quote, DeriveInput, Expr, Ident, ItemStruct, Lifetime, LifetimeParam, Stmt, Type};
+use syn::{parse_quote, DeriveInput, Expr, Ident, ItemStruct, Lifetime, Stmt, Type};
 
 use crate::{
-    common::{constructor_expr, non_bound_generics, FieldIdent, StructType, StructTypeWithFields},
+    common::{
+        constructor_expr, insert_deserialize_lifetime, non_bound_generics,
+        ordered_constructor_expr, FieldIdent, StructType, StructTypeWithFields,
+    },
     de::{
         builders::{DeserializeBuilder, DeserializeBuilderExt, VisitorBuilder, VisitorBuilderExt},
         components::SeqLoopAccessor,
@@ -16,6 +19,7 @@ use crate::{
             fields::{FieldOpts, FieldValueGroupOpts},
         },
         AllowUnknown, ElementOrder, FieldWithOpts, IgnoreComments, IgnoreWhitespace,
+        WithExpandedNameExt,
     },
     DeriveError,
 };
@@ -28,14 +32,17 @@ pub struct RecordDeserializeValueBuilder<'a, T: Fn(syn::Expr) -> syn::Expr> {
     pub ignore_comments: IgnoreComments,
     pub allow_unknown_children: AllowUnknown,
     pub children_order: ElementOrder,
-    pub value: Option<String>,
+    pub value: Option<Expr>,
     pub deserialize_with: Option<Expr>,
+    pub case_insensitive: bool,
+    pub collapse_whitespace: bool,
+    pub transparent: bool,
 }
 
 impl<'a, T: Fn(syn::Expr) -> syn::Expr> RecordDeserializeValueBuilder<'a, T> {
     fn str_value_body(
         &self,
-        value: &str,
+        value: &Expr,
         value_ident: &Ident,
         _visitor_lifetime: &Lifetime,
         _access_type: &Type,
@@ -47,8 +54,11 @@ impl<'a, T: Fn(syn::Expr) -> syn::Expr> RecordDeserializeValueBuilder<'a, T> {
             &StructType::Unit,
         ));
 
+        let case_insensitive = self.case_insensitive;
+        let collapse_whitespace = self.collapse_whitespace;
+
         Ok(parse_quote! {
-            if ::core::primitive::str::trim(::core::ops::Deref::deref(&#value_ident)) == #value {
+            if ::xmlity::__private::text_matches_value(::core::ops::Deref::deref(&#value_ident), #value, #case_insensitive, #collapse_whitespace) {
                 return ::core::result::Result::Ok(#constructor);
             }
 
@@ -56,9 +66,9 @@ impl<'a, T: Fn(syn::Expr) -> syn::Expr> RecordDeserializeValueBuilder<'a, T> {
         })
     }
 
-    fn should_deserialize_as_str(&self) -> Option<&str> {
+    fn should_deserialize_as_str(&self) -> Option<&Expr> {
         if matches!(self.input.fields, StructTypeWithFields::Unit) && self.value.is_some() {
-            self.value.as_deref()
+            self.value.as_ref()
         } else {
             None
         }
@@ -72,7 +82,15 @@ impl<'a, T: Fn(syn::Expr) -> syn::Expr> RecordDeserializeValueBuilder<'a, T> {
         fields: impl IntoIterator<Item = FieldWithOpts<FieldIdent, FieldValueGroupOpts>>,
         constructor_type: StructType,
     ) -> Option<syn::Expr> {
-        let value_expressions_constructors = fields.into_iter().map::<Option<(_, Expr)>, _>(
+        let fields = fields.into_iter().collect::<Vec<_>>();
+        let default_from = |field: &Ident| {
+            fields
+                .iter()
+                .find(|f| *f.field_ident.to_named_ident() == *field)
+                .and_then(|f| f.options.default_from().cloned())
+        };
+
+        let value_expressions_constructors = fields.iter().cloned().map::<Option<(_, Expr)>, _>(
             |FieldWithOpts {
                  field_ident,
                  options,
@@ -89,11 +107,8 @@ impl<'a, T: Fn(syn::Expr) -> syn::Expr> RecordDeserializeValueBuilder<'a, T> {
                             match options {
                                 records::fields::ChildOpts::Value(_) =>  {
                                     Some(parse_quote! {
-                                        ::core::result::Result::map_err(
-                                            <#field_type as ::xmlity::Deserialize<#visitor_lifetime>>::deserialize_seq(
-                                                ::xmlity::types::utils::NoneDeserializer::<#error_type>::new(),
-                                            ),
-                                            |_| <#error_type as ::xmlity::de::Error>::missing_field(stringify!(#field_ident)),
+                                        ::xmlity::__private::deserialize_missing::<#visitor_lifetime, #field_type, #error_type>(
+                                            stringify!(#field_ident),
                                         )?
                                     })
                                 }
@@ -115,9 +130,10 @@ impl<'a, T: Fn(syn::Expr) -> syn::Expr> RecordDeserializeValueBuilder<'a, T> {
             },
         ).collect::<Option<Vec<_>>>()?;
 
-        Some(constructor_expr(
+        Some(ordered_constructor_expr(
             path,
             value_expressions_constructors,
+            default_from,
             &constructor_type,
         ))
     }
@@ -211,6 +227,7 @@ impl<T: Fn(syn::Expr) -> syn::Expr> VisitorBuilder for RecordDeserializeValueBui
 
         let seq_loop_accessor = element_group_fields.clone().next().is_some().then(|| {
             SeqLoopAccessor::new(
+                &self.input.generics,
                 self.allow_unknown_children,
                 self.children_order,
                 self.ignore_whitespace,
@@ -230,8 +247,6 @@ impl<T: Fn(syn::Expr) -> syn::Expr> VisitorBuilder for RecordDeserializeValueBui
                 a.access_loop(
                     element_group_fields.clone(),
                     &parse_quote!(&mut #access_ident),
-                    access_type,
-                    visitor_lifetime,
                 )
             })
             .transpose()?
@@ -246,9 +261,15 @@ impl<T: Fn(syn::Expr) -> syn::Expr> VisitorBuilder for RecordDeserializeValueBui
             .transpose()?
             .unwrap_or_default();
 
-        let constructor = (self.input.wrapper_function)(constructor_expr(
+        let constructor = (self.input.wrapper_function)(ordered_constructor_expr(
             self.input.constructor_path.as_ref(),
             result_exprs,
+            |field| {
+                fields
+                    .iter()
+                    .find(|f| *f.field_ident.to_named_ident() == *field)
+                    .and_then(|f| f.options.default_from().cloned())
+            },
             &constructor_type,
         ));
 
@@ -331,13 +352,12 @@ impl<T: Fn(syn::Expr) -> syn::Expr> VisitorBuilder for RecordDeserializeValueBui
         let visitor_ident = Ident::new("__Visitor", Span::mixed_site());
         let visitor_lifetime = Lifetime::new("'__visitor", Span::mixed_site());
 
-        deserialize_generics.params.insert(
-            0,
-            syn::GenericParam::Lifetime(LifetimeParam::new(visitor_lifetime.clone())),
-        );
+        insert_deserialize_lifetime(&mut deserialize_generics, &visitor_lifetime);
+
+        let where_clause = &deserialize_generics.where_clause;
 
         Ok(parse_quote! {
-            struct #visitor_ident #deserialize_generics {
+            struct #visitor_ident #deserialize_generics #where_clause {
                 marker: ::core::marker::PhantomData<#ident #non_bound_generics>,
                 lifetime: ::core::marker::PhantomData<&#visitor_lifetime ()>,
             }
@@ -357,7 +377,7 @@ impl<T: Fn(syn::Expr) -> syn::Expr> DeserializeBuilder for RecordDeserializeValu
     fn deserialize_fn_body(
         &self,
         deserializer_ident: &Ident,
-        _deserialize_lifetime: &Lifetime,
+        deserialize_lifetime: &Lifetime,
     ) -> Result<Vec<Stmt>, DeriveError> {
         if let Some(deserialize_with) = &self.deserialize_with {
             return Ok(parse_quote! {
@@ -365,6 +385,23 @@ impl<T: Fn(syn::Expr) -> syn::Expr> DeserializeBuilder for RecordDeserializeValu
             });
         }
 
+        if self.transparent {
+            let (field, constructor_type) = self.input.transparent_field()?;
+            let field_type = &field.field_type;
+            let value_ident = Ident::new("__value", Span::mixed_site());
+
+            let constructor = (self.input.wrapper_function)(constructor_expr(
+                self.input.constructor_path.as_ref(),
+                [(field.field_ident, &value_ident)],
+                &constructor_type,
+            ));
+
+            return Ok(parse_quote! {
+                let #value_ident = <#field_type as ::xmlity::Deserialize<#deserialize_lifetime>>::deserialize(#deserializer_ident)?;
+                ::core::result::Result::Ok(#constructor)
+            });
+        }
+
         let formatter_expecting = format!("struct {}", self.input.impl_for_ident);
 
         let visitor_ident = Ident::new("__Visitor", Span::mixed_site());
@@ -422,6 +459,128 @@ impl<'a> EnumVisitorBuilder<'a> {
         Self { ast, value_opts }
     }
 
+    /// Parses the options of a variant, filling in the value of unit variants from the options of the enum.
+    fn variant_opts(
+        &self,
+        variant: &syn::Variant,
+    ) -> Result<enums::variants::DeserializeRootOpts, DeriveError> {
+        let mut variant_opts = enums::variants::DeserializeRootOpts::parse(&variant.attrs)?;
+        if let enums::variants::DeserializeRootOpts::None = variant_opts {
+            variant_opts = enums::variants::DeserializeRootOpts::Value(
+                enums::variants::RootValueOpts::default(),
+            );
+        }
+
+        if let enums::variants::DeserializeRootOpts::Value(opts) = &mut variant_opts {
+            let enum_opts = self.value_opts;
+            if opts.value.is_none() {
+                let (value, value_with) = enums::roots::RootValueOpts::variant_value(
+                    enum_opts,
+                    &variant.ident.to_string(),
+                );
+                opts.value = Some(value);
+                opts.value_with = value_with;
+            }
+            opts.case_insensitive |= enum_opts.is_some_and(|a| a.case_insensitive);
+            opts.collapse_whitespace |= enum_opts.is_some_and(|a| a.collapse_whitespace);
+        }
+
+        Ok(variant_opts)
+    }
+
+    /// Returns what a variant expects for the error of an exhaustive enum, as expressions of strings.
+    fn variant_expected_exprs(&self, variant: &syn::Variant) -> Result<Vec<Expr>, DeriveError> {
+        let any_name_error = || {
+            DeriveError::custom_with_span(
+                format!(
+                    "`exhaustive` can not be used with variant `{}`, which accepts any name",
+                    variant.ident
+                ),
+                variant.ident.span(),
+            )
+        };
+        let default_name = format!("__XmlityVariant__{}", variant.ident);
+
+        match self.variant_opts(variant)? {
+            enums::variants::DeserializeRootOpts::Element(opts) => {
+                if opts.deserialize_any_name {
+                    return Err(any_name_error());
+                }
+                let name = opts.expanded_name(&default_name);
+                let aliases = opts.aliases.iter().map(|alias| alias.expanded_name(&default_name));
+                Ok(std::iter::once(name)
+                    .chain(aliases)
+                    .map(|name| parse_quote! { ::std::format!("<{}>", #name) })
+                    .collect())
+            }
+            enums::variants::DeserializeRootOpts::Attribute(opts) => {
+                if opts.deserialize_any_name {
+                    return Err(any_name_error());
+                }
+                let name = opts.expanded_name(&default_name);
+                Ok(vec![parse_quote! { ::std::format!("@{}", #name) }])
+            }
+            enums::variants::DeserializeRootOpts::Pi(opts) => {
+                let expected = format!("<?{}?>", opts.target);
+                Ok(vec![parse_quote! { ::std::string::String::from(#expected) }])
+            }
+            enums::variants::DeserializeRootOpts::Value(opts)
+                if matches!(variant.fields, syn::Fields::Unit) =>
+            {
+                let value = opts.value.expect("Unit variants always have a value.");
+                match opts.value_with {
+                    Some(value_with) => Ok(vec![parse_quote! { ::std::format!("{:?}", #value_with(#value)) }]),
+                    None => {
+                        let expected = format!("{value:?}");
+                        Ok(vec![parse_quote! { ::std::string::String::from(#expected) }])
+                    }
+                }
+            }
+            _ => Err(DeriveError::custom_with_span(
+                format!(
+                    "`exhaustive` requires every variant to be named with `xelement`, `xattribute` or `xpi`, or to be a unit variant, which `{}` is not",
+                    variant.ident
+                ),
+                variant.ident.span(),
+            )),
+        }
+    }
+
+    /// Returns the statements making the error for when no variant matches.
+    fn no_variant_error(&self) -> Result<Vec<Stmt>, DeriveError> {
+        let DeriveInput {
+            ident,
+            data: syn::Data::Enum(data),
+            ..
+        } = &self.ast
+        else {
+            unreachable!("Should already have been checked.")
+        };
+        let ident_string = ident.to_string();
+
+        if !self.value_opts.is_some_and(|opts| opts.exhaustive) {
+            return Ok(parse_quote! {
+                ::core::result::Result::Err(::xmlity::de::Error::no_possible_variant(#ident_string))
+            });
+        }
+
+        let expected = data
+            .variants
+            .iter()
+            .map(|variant| self.variant_expected_exprs(variant))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        let expected_len = expected.len();
+
+        Ok(parse_quote! {
+            let __expected: [::std::string::String; #expected_len] = [#(#expected),*];
+            let __expected: ::std::vec::Vec<&str> = __expected.iter().map(::std::string::String::as_str).collect();
+            ::core::result::Result::Err(::xmlity::de::Error::no_matching_variant(#ident_string, &__expected))
+        })
+    }
+
     pub fn variant_deserialize_definition(
         &self,
         variant: &syn::Variant,
@@ -438,35 +597,7 @@ impl<'a> EnumVisitorBuilder<'a> {
 
         let fallible_enum = data.variants.len() > 1;
 
-        let mut variant_opts = enums::variants::DeserializeRootOpts::parse(&variant.attrs)?;
-        if let enums::variants::DeserializeRootOpts::Value(enums::variants::RootValueOpts {
-            value: value @ None,
-            ..
-        }) = &mut variant_opts
-        {
-            let ident_value = self
-                .value_opts
-                .as_ref()
-                .map(|a| a.rename_all)
-                .unwrap_or_default()
-                .apply_to_variant(&variant.ident.to_string());
-
-            *value = Some(ident_value);
-        }
-        if let enums::variants::DeserializeRootOpts::None = variant_opts {
-            let ident_value = self
-                .value_opts
-                .as_ref()
-                .map(|a| a.rename_all)
-                .unwrap_or_default()
-                .apply_to_variant(&variant.ident.to_string());
-
-            variant_opts =
-                enums::variants::DeserializeRootOpts::Value(enums::variants::RootValueOpts {
-                    value: Some(ident_value),
-                    ..Default::default()
-                });
-        }
+        let variant_opts = self.variant_opts(variant)?;
 
         let record = parse_enum_variant_derive_input(ident, generics, variant, fallible_enum)?;
 
@@ -495,35 +626,7 @@ impl<'a> EnumVisitorBuilder<'a> {
 
         let fallible_enum = data.variants.len() > 1;
 
-        let mut variant_opts = enums::variants::DeserializeRootOpts::parse(&variant.attrs)?;
-        if let enums::variants::DeserializeRootOpts::Value(enums::variants::RootValueOpts {
-            value: value @ None,
-            ..
-        }) = &mut variant_opts
-        {
-            let ident_value = self
-                .value_opts
-                .as_ref()
-                .map(|a| a.rename_all)
-                .unwrap_or_default()
-                .apply_to_variant(&variant.ident.to_string());
-
-            *value = Some(ident_value);
-        }
-        if let enums::variants::DeserializeRootOpts::None = variant_opts {
-            let ident_value = self
-                .value_opts
-                .as_ref()
-                .map(|a| a.rename_all)
-                .unwrap_or_default()
-                .apply_to_variant(&variant.ident.to_string());
-
-            variant_opts =
-                enums::variants::DeserializeRootOpts::Value(enums::variants::RootValueOpts {
-                    value: Some(ident_value),
-                    ..Default::default()
-                });
-        }
+        let variant_opts = self.variant_opts(variant)?;
 
         let record = parse_enum_variant_derive_input(ident, generics, variant, fallible_enum)?;
 
@@ -562,35 +665,7 @@ impl<'a> EnumVisitorBuilder<'a> {
 
         let fallible_enum = data.variants.len() > 1;
 
-        let mut variant_opts = enums::variants::DeserializeRootOpts::parse(&variant.attrs)?;
-        if let enums::variants::DeserializeRootOpts::Value(enums::variants::RootValueOpts {
-            value: value @ None,
-            ..
-        }) = &mut variant_opts
-        {
-            let ident_value = self
-                .value_opts
-                .as_ref()
-                .map(|a| a.rename_all)
-                .unwrap_or_default()
-                .apply_to_variant(&variant.ident.to_string());
-
-            *value = Some(ident_value);
-        }
-        if let enums::variants::DeserializeRootOpts::None = variant_opts {
-            let ident_value = self
-                .value_opts
-                .as_ref()
-                .map(|a| a.rename_all)
-                .unwrap_or_default()
-                .apply_to_variant(&variant.ident.to_string());
-
-            variant_opts =
-                enums::variants::DeserializeRootOpts::Value(enums::variants::RootValueOpts {
-                    value: Some(ident_value),
-                    ..Default::default()
-                });
-        }
+        let variant_opts = self.variant_opts(variant)?;
 
         let record = parse_enum_variant_derive_input(ident, generics, variant, fallible_enum)?;
 
@@ -622,7 +697,6 @@ impl VisitorBuilder for EnumVisitorBuilder<'_> {
         _access_type: &Type,
     ) -> Result<Option<Vec<Stmt>>, DeriveError> {
         let DeriveInput {
-            ident,
             data: syn::Data::Enum(data),
             ..
         } = &self.ast
@@ -637,12 +711,44 @@ impl VisitorBuilder for EnumVisitorBuilder<'_> {
                 self.variant_deserialize_expr(variant, access_ident)
             })
             .collect::<Result<Vec<_>, _>>()?;
-        let ident_string = ident.to_string();
+        let no_variant_error = self.no_variant_error()?;
 
         Ok(Some(parse_quote! {
             #(#variants)*
 
-            ::core::result::Result::Err(::xmlity::de::Error::no_possible_variant(#ident_string))
+            #(#no_variant_error)*
+        }))
+    }
+
+    fn visit_attribute_fn_body(
+        &self,
+        _visitor_lifetime: &Lifetime,
+        access_ident: &Ident,
+        _access_type: &Type,
+    ) -> Result<Option<Vec<Stmt>>, DeriveError> {
+        let DeriveInput {
+            ident,
+            generics,
+            data: syn::Data::Enum(data),
+            ..
+        } = &self.ast
+        else {
+            unreachable!("Should already have been checked.")
+        };
+
+        // Enums of only unit variants are matched against text, which the value of an attribute can be used as.
+        if !data
+            .variants
+            .iter()
+            .all(|variant| matches!(variant.fields, syn::Fields::Unit))
+        {
+            return Ok(None);
+        }
+
+        let non_bound_generics = non_bound_generics(generics);
+
+        Ok(Some(parse_quote! {
+            ::xmlity::de::AttributeAccess::value::<#ident #non_bound_generics>(#access_ident)
         }))
     }
 
@@ -653,7 +759,6 @@ impl VisitorBuilder for EnumVisitorBuilder<'_> {
         error_type: &Type,
     ) -> Result<Option<Vec<Stmt>>, DeriveError> {
         let DeriveInput {
-            ident,
             data: syn::Data::Enum(data),
             ..
         } = &self.ast
@@ -669,12 +774,12 @@ impl VisitorBuilder for EnumVisitorBuilder<'_> {
             })
             .collect::<Result<Vec<_>, _>>()?;
 
-        let ident_string = ident.to_string();
+        let no_variant_error = self.no_variant_error()?;
 
         Ok(Some(parse_quote! {
             #(#variants)*
 
-            ::core::result::Result::Err(::xmlity::de::Error::no_possible_variant(#ident_string))
+            #(#no_variant_error)*
         }))
     }
 
@@ -689,13 +794,12 @@ impl VisitorBuilder for EnumVisitorBuilder<'_> {
         let visitor_ident = Ident::new("__Visitor", Span::mixed_site());
         let visitor_lifetime = Lifetime::new("'__visitor", Span::mixed_site());
 
-        deserialize_generics.params.insert(
-            0,
-            syn::GenericParam::Lifetime(LifetimeParam::new(visitor_lifetime.clone())),
-        );
+        insert_deserialize_lifetime(&mut deserialize_generics, &visitor_lifetime);
+
+        let where_clause = &deserialize_generics.where_clause;
 
         Ok(parse_quote! {
-            struct #visitor_ident #deserialize_generics {
+            struct #visitor_ident #deserialize_generics #where_clause {
                 marker: ::core::marker::PhantomData<#ident #non_bound_generics>,
                 lifetime: ::core::marker::PhantomData<&#visitor_lifetime ()>,
             }
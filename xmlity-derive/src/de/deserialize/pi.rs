@@ -0,0 +1,235 @@
+use std::borrow::Cow;
+
+use proc_macro2::Span;
+use quote::format_ident;
+use syn::{parse_quote, Expr, Ident, ItemStruct, Lifetime, Stmt, Type};
+
+use crate::{
+    common::{
+        insert_deserialize_lifetime, non_bound_generics, ordered_constructor_expr,
+        pseudo_attribute_fields, FieldIdent, PseudoAttributeField, RecordInput, StructType,
+        StructTypeWithFields,
+    },
+    de::builders::{DeserializeBuilder, VisitorBuilder, VisitorBuilderExt},
+    options::records::roots::RootPiOpts,
+    DeriveError,
+};
+
+pub struct RecordDeserializePiBuilder<'a, T: Fn(syn::Expr) -> syn::Expr> {
+    input: &'a RecordInput<'a, T>,
+    opts: &'a RootPiOpts,
+}
+
+impl<'a, T: Fn(syn::Expr) -> syn::Expr> RecordDeserializePiBuilder<'a, T> {
+    pub fn new(input: &'a RecordInput<'a, T>, opts: &'a RootPiOpts) -> Self {
+        Self { input, opts }
+    }
+}
+
+impl<T: Fn(syn::Expr) -> syn::Expr> VisitorBuilder for RecordDeserializePiBuilder<'_, T> {
+    fn visit_pi_fn_body(
+        &self,
+        _visitor_lifetime: &Lifetime,
+        access_ident: &Ident,
+        _access_type: &Type,
+        error_type: &Type,
+    ) -> Result<Option<Vec<Stmt>>, DeriveError> {
+        let target = &self.opts.target;
+        let content_ident = Ident::new("__content", Span::mixed_site());
+
+        let (constructor_type, field_definitions, result_exprs): (
+            StructType,
+            Vec<Stmt>,
+            Vec<(FieldIdent, Expr)>,
+        ) = match &self.input.fields {
+            StructTypeWithFields::Named(fields) => {
+                let fields = pseudo_attribute_fields(fields)?;
+
+                let value_ident = |field: &PseudoAttributeField| {
+                    format_ident!("__xpi_{}", field.field_ident, span = Span::mixed_site())
+                };
+
+                let definitions = fields.iter().map::<Stmt, _>(|field| {
+                    let value_ident = value_ident(field);
+                    let field_type = &field.field_type;
+                    parse_quote! {
+                        let mut #value_ident: ::core::option::Option<#field_type> = ::core::option::Option::None;
+                    }
+                });
+
+                let arms = fields.iter().map::<syn::Arm, _>(|field| {
+                    let value_ident = value_ident(field);
+                    let name = &field.name;
+                    let invalid_value = format!("Invalid value for pseudo-attribute {name}");
+                    let parsed: Expr = parse_quote! {
+                        ::core::result::Result::map_err(
+                            ::core::str::FromStr::from_str(&__attribute_value),
+                            |_| <#error_type as ::xmlity::de::Error>::custom(#invalid_value),
+                        )?
+                    };
+                    let parsed: Expr = if field.optional {
+                        parse_quote!(::core::option::Option::Some(#parsed))
+                    } else {
+                        parsed
+                    };
+
+                    parse_quote! {
+                        #name => {
+                            #value_ident = ::core::option::Option::Some(#parsed);
+                        }
+                    }
+                });
+
+                let field_definitions = parse_quote! {
+                    #(#definitions)*
+
+                    for __attribute in ::xmlity::types::pi::pseudo_attributes(#content_ident) {
+                        let (__attribute_name, __attribute_value) = ::core::result::Result::map_err(
+                            __attribute,
+                            <#error_type as ::xmlity::de::Error>::custom,
+                        )?;
+
+                        match __attribute_name {
+                            #(#arms)*
+                            _ => {}
+                        }
+                    }
+                };
+
+                let result_exprs = fields
+                    .iter()
+                    .map(|field| {
+                        let value_ident = value_ident(field);
+                        let name = &field.name;
+                        let expr: Expr = match &field.default_or_else {
+                            Some(default_or_else) => parse_quote! {
+                                ::core::option::Option::unwrap_or_else(#value_ident, #default_or_else)
+                            },
+                            None => parse_quote! {
+                                ::core::option::Option::ok_or_else(#value_ident, || <#error_type as ::xmlity::de::Error>::missing_field(#name))?
+                            },
+                        };
+                        (FieldIdent::Named(field.field_ident.clone()), expr)
+                    })
+                    .collect();
+
+                (StructType::Named, field_definitions, result_exprs)
+            }
+            StructTypeWithFields::Unnamed(fields) if fields.len() == 1 => {
+                let field = &fields[0];
+                (
+                    StructType::Unnamed,
+                    Vec::new(),
+                    vec![(
+                        FieldIdent::Indexed(field.field_ident.clone()),
+                        parse_quote! {
+                            ::core::result::Result::map_err(
+                                ::core::str::FromStr::from_str(#content_ident),
+                                |_| <#error_type as ::xmlity::de::Error>::custom("Invalid processing instruction content"),
+                            )?
+                        },
+                    )],
+                )
+            }
+            StructTypeWithFields::Unnamed(fields) => {
+                return Err(DeriveError::custom(format!(
+                    "Expected a single field for processing instruction deserialization, found {}",
+                    fields.len()
+                )))
+            }
+            StructTypeWithFields::Unit => (StructType::Unit, Vec::new(), Vec::new()),
+        };
+
+        let constructor = (self.input.wrapper_function)(ordered_constructor_expr(
+            self.input.constructor_path.as_ref(),
+            result_exprs,
+            |field| match &self.input.fields {
+                StructTypeWithFields::Named(fields) => fields
+                    .iter()
+                    .find(|f| f.field_ident == *field)
+                    .and_then(|f| f.options.default_from().cloned()),
+                _ => None,
+            },
+            &constructor_type,
+        ));
+
+        Ok(Some(parse_quote! {
+            ::xmlity::types::pi::ensure_target::<#error_type, _>(&#access_ident, #target)?;
+            let #content_ident = ::xmlity::types::pi::content_str::<#error_type, _>(&#access_ident)?;
+
+            #(#field_definitions)*
+
+            ::core::result::Result::Ok(#constructor)
+        }))
+    }
+
+    fn visitor_definition(&self) -> Result<ItemStruct, DeriveError> {
+        let RecordInput {
+            impl_for_ident: ident,
+            generics,
+            ..
+        } = &self.input;
+        let non_bound_generics = non_bound_generics(generics);
+
+        let mut deserialize_generics = generics.as_ref().clone();
+
+        let visitor_ident = Ident::new("__Visitor", Span::mixed_site());
+        let visitor_lifetime = Lifetime::new("'__visitor", Span::mixed_site());
+
+        insert_deserialize_lifetime(&mut deserialize_generics, &visitor_lifetime);
+
+        let where_clause = &deserialize_generics.where_clause;
+
+        Ok(parse_quote! {
+            struct #visitor_ident #deserialize_generics #where_clause {
+                marker: ::core::marker::PhantomData<#ident #non_bound_generics>,
+                lifetime: ::core::marker::PhantomData<&#visitor_lifetime ()>,
+            }
+        })
+    }
+
+    fn visitor_ident(&self) -> Cow<'_, Ident> {
+        Cow::Borrowed(self.input.impl_for_ident.as_ref())
+    }
+
+    fn visitor_generics(&self) -> Cow<'_, syn::Generics> {
+        Cow::Borrowed(self.input.generics.as_ref())
+    }
+}
+
+impl<T: Fn(syn::Expr) -> syn::Expr> DeserializeBuilder for RecordDeserializePiBuilder<'_, T> {
+    fn deserialize_fn_body(
+        &self,
+        deserializer_ident: &Ident,
+        _deserialize_lifetime: &Lifetime,
+    ) -> Result<Vec<Stmt>, DeriveError> {
+        let formatter_expecting = format!(
+            "processing instruction {} with target {}",
+            self.input.impl_for_ident, self.opts.target
+        );
+
+        let visitor_ident = Ident::new("__Visitor", Span::mixed_site());
+
+        let visitor_def = self.visitor_definition()?;
+        let visitor_trait_impl = self.visitor_trait_impl(&visitor_ident, &formatter_expecting)?;
+
+        Ok(parse_quote! {
+            #visitor_def
+
+            #visitor_trait_impl
+
+            ::xmlity::de::Deserializer::deserialize_any(#deserializer_ident, #visitor_ident {
+                lifetime: ::core::marker::PhantomData,
+                marker: ::core::marker::PhantomData,
+            })
+        })
+    }
+
+    fn ident(&self) -> Cow<'_, Ident> {
+        Cow::Borrowed(self.input.impl_for_ident.as_ref())
+    }
+
+    fn generics(&self) -> Cow<'_, syn::Generics> {
+        Cow::Borrowed(self.input.generics.as_ref())
+    }
+}
@@ -3,10 +3,14 @@
 use std::borrow::Cow;
 
 use proc_macro2::Span;
-use syn::{parse_quote, Ident, Lifetime, LifetimeParam, Stmt, Type};
+use quote::quote;
+use syn::{parse_quote, Ident, Lifetime, Stmt, Type};
 
 use crate::{
-    common::{non_bound_generics, ExpandedName, FieldIdent, RecordInput, StructTypeWithFields},
+    common::{
+        generics_marker_field, insert_deserialize_lifetime, non_bound_generics, ExpandedName,
+        FieldIdent, RecordInput, StructTypeWithFields,
+    },
     de::builders::{DeserializeBuilder, VisitorBuilder, VisitorBuilderExt},
     options::{
         records::fields::{ChildOpts, ElementOpts, FieldOpts, GroupOpts, ValueOpts},
@@ -63,11 +67,22 @@ impl DeserializeSingleChildElementBuilder<'_> {
             ident, item_type, ..
         } = self;
 
+        let generics = self.generics;
         let value_access_ident = self.value_access_ident();
+        let marker = generics_marker_field(generics).map(
+            |FieldWithOpts {
+                 field_ident,
+                 field_type,
+                 ..
+             }| quote!(#field_ident: #field_type,),
+        );
+
+        let where_clause = &generics.where_clause;
 
         parse_quote! {
-            struct #ident {
+            struct #ident #generics #where_clause {
                 #value_access_ident: #item_type,
+                #marker
             }
         }
     }
@@ -96,23 +111,35 @@ impl VisitorBuilder for DeserializeSingleChildElementBuilder<'_> {
             impl_for_ident: Cow::Borrowed(self.ident),
             constructor_path: Cow::Owned(parse_quote!(#ident)),
             result_type: Cow::Borrowed(self.item_type),
-            generics: Cow::Owned(parse_quote!()),
+            generics: Cow::Borrowed(self.generics),
             wrapper_function: std::convert::identity,
             record_path: Cow::Owned(parse_quote!(self)),
-            fields: StructTypeWithFields::Named(vec![FieldWithOpts {
-                field_ident: self.value_access_ident(),
-                field_type: self.item_type.clone(),
-                options: if self.group {
-                    FieldOpts::Group(GroupOpts {})
-                } else {
-                    FieldOpts::Value(ChildOpts::Value(ValueOpts {
-                        default: self.default,
-                        default_with: self.default_with.clone(),
-                        extendable: self.extendable,
-                        skip_serializing_if: None,
-                    }))
-                },
-            }]),
+            fields: StructTypeWithFields::Named(
+                std::iter::once(FieldWithOpts {
+                    field_ident: self.value_access_ident(),
+                    field_type: self.item_type.clone(),
+                    options: if self.group {
+                        FieldOpts::Group(GroupOpts::default())
+                    } else {
+                        FieldOpts::Value(ChildOpts::Value(ValueOpts {
+                            default: self.default,
+                            default_with: self.default_with.clone(),
+                            default_from: None,
+                            extendable: self.extendable,
+                            skip_serializing_if: None,
+                            raw_text: false,
+                            serialize_order: None,
+                            bool_format: None,
+                            radix_prefixes: false,
+                            int_format: None,
+                            key_attribute: None,
+                            duplicate_keys: None,
+                        }))
+                    },
+                })
+                .chain(generics_marker_field(self.generics))
+                .collect(),
+            ),
             sub_path_ident: None,
             fallable_deconstruction: false,
         };
@@ -122,10 +149,12 @@ impl VisitorBuilder for DeserializeSingleChildElementBuilder<'_> {
             ignore_whitespace: IgnoreWhitespace::default(),
             ignore_comments: IgnoreComments::default(),
             required_expanded_name: self.required_expanded_name.clone(),
+            alias_expanded_names: Vec::new(),
             allow_unknown_attributes: AllowUnknown::default(),
             allow_unknown_children: AllowUnknown::default(),
             children_order: ElementOrder::None,
             attribute_order: ElementOrder::None,
+            default: false,
         };
 
         builder.visit_element_fn_body(visitor_lifetime, element_access_ident, access_type)
@@ -142,13 +171,12 @@ impl VisitorBuilder for DeserializeSingleChildElementBuilder<'_> {
         let visitor_ident = Ident::new("__Visitor", Span::mixed_site());
         let visitor_lifetime = Lifetime::new("'__visitor", Span::mixed_site());
 
-        deserialize_generics.params.insert(
-            0,
-            syn::GenericParam::Lifetime(LifetimeParam::new(visitor_lifetime.clone())),
-        );
+        insert_deserialize_lifetime(&mut deserialize_generics, &visitor_lifetime);
+
+        let where_clause = &deserialize_generics.where_clause;
 
         Ok(parse_quote! {
-            struct #visitor_ident #deserialize_generics {
+            struct #visitor_ident #deserialize_generics #where_clause {
                 marker: ::core::marker::PhantomData<#ident #non_bound_generics>,
                 lifetime: ::core::marker::PhantomData<&#visitor_lifetime ()>,
             }
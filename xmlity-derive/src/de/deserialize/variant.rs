@@ -38,9 +38,11 @@ impl<'a, T: Fn(syn::Expr) -> syn::Expr> DeserializeVariantBuilder<'a, T> {
 
         let value_access_ident = self.value_access_ident();
 
+        let where_clause = &generics.where_clause;
+
         parse_quote! {
             #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
-            struct #ident #generics {
+            struct #ident #generics #where_clause {
                 #value_access_ident: #enum_type,
             }
         }
@@ -66,10 +68,14 @@ impl<T: Fn(syn::Expr) -> syn::Expr> DeserializeBuilder for DeserializeVariantBui
                 enums::variants::DeserializeRootOpts::Attribute(opts) => {
                     records::roots::DeserializeRootOpts::Attribute(opts.clone())
                 }
+                enums::variants::DeserializeRootOpts::Pi(opts) => {
+                    records::roots::DeserializeRootOpts::Pi(opts.clone())
+                }
                 enums::variants::DeserializeRootOpts::Value(opts) => {
                     let opts = opts.clone();
                     records::roots::DeserializeRootOpts::Value(records::roots::RootValueOpts {
                         value: opts.value,
+                        value_with: opts.value_with,
                         ignore_whitespace: opts.ignore_whitespace,
                         ignore_comments: opts.ignore_comments,
                         allow_unknown: opts.allow_unknown,
@@ -77,6 +83,10 @@ impl<T: Fn(syn::Expr) -> syn::Expr> DeserializeBuilder for DeserializeVariantBui
                         with: None,
                         serialize_with: None,
                         deserialize_with: None,
+                        deserialize_with_context: None,
+                        case_insensitive: opts.case_insensitive,
+                        collapse_whitespace: opts.collapse_whitespace,
+                        transparent: false,
                     })
                 }
             },
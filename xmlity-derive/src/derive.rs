@@ -1,3 +1,8 @@
+use crate::{
+    meta::{self, MetaForm},
+    options::{DeriveDirection, XmlityOpts},
+};
+
 pub enum DeriveError {
     Darling(darling::Error),
     Custom {
@@ -46,9 +51,15 @@ impl DeriveError {
 }
 
 pub trait DeriveMacro {
+    /// Whether the macro generates serialization or deserialization impls, which decides if `#[xmlity(serialize_only)]` or `#[xmlity(deserialize_only)]` turns it off.
+    const DIRECTION: DeriveDirection;
+
     fn input_to_derive(ast: &syn::DeriveInput) -> Result<proc_macro2::TokenStream, DeriveError>
     where
         Self: Sized;
+
+    /// How the macro describes the type for `#[xmlity(meta)]`, or [`None`] if the `SCHEMA` constant is left to another derive macro of the type so that it is only generated once.
+    fn meta_form(ast: &syn::DeriveInput) -> Option<MetaForm>;
 }
 
 pub trait DeriveMacroExt {
@@ -57,9 +68,140 @@ pub trait DeriveMacroExt {
 
 impl<T: DeriveMacro> DeriveMacroExt for T {
     fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-        let ast = syn::parse_macro_input!(input as syn::DeriveInput);
-        T::input_to_derive(&ast)
+        let mut ast = syn::parse_macro_input!(input as syn::DeriveInput);
+        resolve_self_in_attributes(&mut ast);
+        XmlityOpts::parse(&ast.attrs)
+            .and_then(|opts| {
+                if !opts.generates(T::DIRECTION) {
+                    return Ok(proc_macro2::TokenStream::new());
+                }
+
+                let mut tokens = T::input_to_derive(&ast)?;
+                if let Some(form) = T::meta_form(&ast).filter(|_| opts.describes(T::DIRECTION)) {
+                    tokens.extend(meta::schema_impl(&ast, form)?);
+                }
+                Ok(tokens)
+            })
             .unwrap_or_else(|e| e.into_compile_error())
             .into()
     }
 }
+
+/// The attributes that can have name expressions.
+const EXPRESSION_ATTRIBUTES: &[&str] = &["xelement", "xattribute", "xgroup", "xvalue", "xpi"];
+
+/// The arguments that are expressions for names, which are evaluated inside generated code.
+const NAME_EXPRESSION_ARGUMENTS: &[&str] = &["namespace_expr"];
+
+/// Replaces `Self` in the name expressions of the attributes of the type, its variants and its fields with the type itself.
+///
+/// Expressions such as `namespace_expr = Self::NAMESPACE` end up inside the impls of visitors and other helper types, where `Self` would refer to the helper type instead.
+fn resolve_self_in_attributes(ast: &mut syn::DeriveInput) {
+    let ident = &ast.ident;
+    let (_, ty_generics, _) = ast.generics.split_for_impl();
+    let turbofish = ty_generics.as_turbofish();
+    let self_type = quote::quote!(#ident #turbofish);
+
+    let mut attrs = ast.attrs.iter_mut().collect::<Vec<_>>();
+    match &mut ast.data {
+        syn::Data::Struct(data) => attrs.extend(
+            data.fields
+                .iter_mut()
+                .flat_map(|field| field.attrs.iter_mut()),
+        ),
+        syn::Data::Enum(data) => {
+            for variant in data.variants.iter_mut() {
+                attrs.extend(variant.attrs.iter_mut());
+                attrs.extend(
+                    variant
+                        .fields
+                        .iter_mut()
+                        .flat_map(|field| field.attrs.iter_mut()),
+                );
+            }
+        }
+        syn::Data::Union(data) => attrs.extend(
+            data.fields
+                .named
+                .iter_mut()
+                .flat_map(|field| field.attrs.iter_mut()),
+        ),
+    }
+
+    for attr in attrs {
+        let is_expression_attribute = EXPRESSION_ATTRIBUTES
+            .iter()
+            .any(|name| attr.path().is_ident(name));
+        if let (true, syn::Meta::List(list)) = (is_expression_attribute, &mut attr.meta) {
+            list.tokens =
+                replace_self_in_name_expressions(std::mem::take(&mut list.tokens), &self_type);
+        }
+    }
+}
+
+/// Replaces `Self` in the values of the [`NAME_EXPRESSION_ARGUMENTS`] of an attribute, leaving other arguments as they are.
+fn replace_self_in_name_expressions(
+    tokens: proc_macro2::TokenStream,
+    self_type: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    use proc_macro2::TokenTree;
+
+    let mut argument = None;
+    let mut in_name_expression = false;
+    let mut replaced = proc_macro2::TokenStream::new();
+    for token in tokens {
+        match &token {
+            TokenTree::Punct(punct) if punct.as_char() == ',' => {
+                argument = None;
+                in_name_expression = false;
+            }
+            TokenTree::Punct(punct) if punct.as_char() == '=' && !in_name_expression => {
+                in_name_expression = argument.as_ref().is_some_and(|argument: &syn::Ident| {
+                    NAME_EXPRESSION_ARGUMENTS
+                        .iter()
+                        .any(|name| argument == name)
+                });
+            }
+            TokenTree::Ident(ident) if !in_name_expression => argument = Some(ident.clone()),
+            _ => {}
+        }
+
+        if in_name_expression {
+            replaced.extend(replace_self(token.into(), self_type));
+        } else {
+            replaced.extend([token]);
+        }
+    }
+    replaced
+}
+
+fn replace_self(
+    tokens: proc_macro2::TokenStream,
+    self_type: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    use proc_macro2::{Group, TokenTree};
+
+    tokens
+        .into_iter()
+        .flat_map(|token| match token {
+            TokenTree::Ident(ident) if ident == "Self" => {
+                let span = ident.span();
+                self_type
+                    .clone()
+                    .into_iter()
+                    .map(|mut token| {
+                        token.set_span(span);
+                        token
+                    })
+                    .collect::<Vec<_>>()
+            }
+            TokenTree::Group(group) => {
+                let mut replaced =
+                    Group::new(group.delimiter(), replace_self(group.stream(), self_type));
+                replaced.set_span(group.span());
+                vec![TokenTree::Group(replaced)]
+            }
+            token => vec![token],
+        })
+        .collect()
+}
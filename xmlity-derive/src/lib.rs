@@ -10,6 +10,10 @@
 //!
 //! There are some attributes only used by either serialization or deserialization. These are highlighted in the documentation for each macro.
 //!
+//! A type can keep deriving both directions while only implementing one of them with `#[xmlity(serialize_only)]` or `#[xmlity(deserialize_only)]`, which make the derive macros of the other direction generate nothing. Combined with `cfg_attr`, this lets the same type be write-only or read-only depending on the features it is built with.
+//!
+//! With `#[xmlity(meta)]`, the derive macros also generate a `SCHEMA` constant of type `xmlity::meta::TypeMeta` on the type, describing the names of its element or attributes and how its fields are represented. It is generated by [`Deserialize`], or [`DeserializationGroup`] for groups and types with `#[xgroup]`, and by the serialization derive macros for types with `#[xmlity(serialize_only)]`.
+//!
 //! ## Example
 //! ```ignore
 //! use xmlity_derive::{Deserialize, Serialize};
@@ -39,6 +43,7 @@ struct _ReadMeDocTests;
 pub(crate) mod common;
 mod de;
 mod derive;
+mod meta;
 mod options;
 mod ser;
 mod utils;
@@ -48,25 +53,34 @@ use derive::{DeriveError, DeriveMacro, DeriveMacroExt, DeriveResult};
 use ser::{DeriveSerializationGroup, DeriveSerialize, DeriveSerializeAttribute};
 
 #[doc = include_str!("./ser/Serialize.md")]
-#[proc_macro_derive(Serialize, attributes(xelement, xattribute, xgroup, xvalue))]
+#[proc_macro_derive(
+    Serialize,
+    attributes(xelement, xattribute, xgroup, xvalue, xpi, xmlns, xmlity)
+)]
 pub fn derive_serialize_fn(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     DeriveSerialize::derive(item)
 }
 
 #[doc = include_str!("./ser/SerializeAttribute.md")]
-#[proc_macro_derive(SerializeAttribute, attributes(xattribute))]
+#[proc_macro_derive(SerializeAttribute, attributes(xattribute, xmlns, xmlity))]
 pub fn derive_serialize_attribute_fn(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     DeriveSerializeAttribute::derive(item)
 }
 
 #[doc = include_str!("./de/Deserialize.md")]
-#[proc_macro_derive(Deserialize, attributes(xelement, xattribute, xgroup, xvalue))]
+#[proc_macro_derive(
+    Deserialize,
+    attributes(xelement, xattribute, xgroup, xvalue, xpi, xmlns, xmlity)
+)]
 pub fn derive_deserialize_fn(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     DeriveDeserialize::derive(item)
 }
 
 #[doc = include_str!("./ser/SerializationGroup.md")]
-#[proc_macro_derive(SerializationGroup, attributes(xelement, xattribute, xgroup, xvalue))]
+#[proc_macro_derive(
+    SerializationGroup,
+    attributes(xelement, xattribute, xgroup, xvalue, xmlns, xmlity)
+)]
 pub fn derive_serialization_group_attribute_fn(
     item: proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
@@ -74,7 +88,10 @@ pub fn derive_serialization_group_attribute_fn(
 }
 
 #[doc = include_str!("./de/DeserializationGroup.md")]
-#[proc_macro_derive(DeserializationGroup, attributes(xelement, xattribute, xgroup, xvalue))]
+#[proc_macro_derive(
+    DeserializationGroup,
+    attributes(xelement, xattribute, xgroup, xvalue, xmlns, xmlity)
+)]
 pub fn derive_deserialization_group_fn(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     DeriveDeserializationGroup::derive(item)
 }
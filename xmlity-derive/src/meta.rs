@@ -0,0 +1,310 @@
+//! The `SCHEMA` constant generated for types with `#[xmlity(meta)]`.
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{parse_quote, DeriveInput, Expr, Ident};
+
+use crate::{
+    common::{
+        parse_enum_variant_derive_input, parse_struct_derive_input, ExpandedName, FieldIdent,
+        RecordInput, StructTypeWithFields,
+    },
+    options::{
+        enums::{
+            self,
+            groups::{GroupVariant, GroupVariantKind},
+        },
+        records::{
+            self,
+            fields::{AttributeOpts, ChildOpts, FieldOpts},
+        },
+        FieldWithOpts, WithExpandedNameExt,
+    },
+    DeriveError, DeriveResult,
+};
+
+/// How a derive macro describes a type.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MetaForm {
+    /// The type on its own, as described by `Serialize`, `SerializeAttribute` and `Deserialize`.
+    Item,
+    /// The type as a group inside of other elements, as described by `SerializationGroup` and `DeserializationGroup`.
+    Group,
+}
+
+/// Whether the type has `#[xgroup]`, in which case it is described by the group derive macros instead of the others.
+pub fn is_group(ast: &DeriveInput) -> bool {
+    ast.attrs.iter().any(|attr| attr.path().is_ident("xgroup"))
+}
+
+/// Whether the type has `#[xattribute]`, in which case it is described by `SerializeAttribute` instead of `Serialize`.
+pub fn is_attribute(ast: &DeriveInput) -> bool {
+    ast.attrs
+        .iter()
+        .any(|attr| attr.path().is_ident("xattribute"))
+}
+
+/// Generates the inherent `SCHEMA` constant of the type.
+pub fn schema_impl(ast: &DeriveInput, form: MetaForm) -> DeriveResult<TokenStream> {
+    let ident = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    let rust_name = ident.to_string();
+    let kind = match (&ast.data, form) {
+        (syn::Data::Struct(_), MetaForm::Item) => {
+            let input = parse_struct_derive_input(ast)?;
+            record_kind(
+                &input,
+                records::roots::DeserializeRootOpts::parse(&ast.attrs)?,
+            )
+        }
+        (syn::Data::Struct(_), MetaForm::Group) => {
+            let fields = fields_meta(&parse_struct_derive_input(ast)?);
+            parse_quote! { ::xmlity::meta::TypeKind::Group { fields: &[#(#fields,)*] } }
+        }
+        (syn::Data::Enum(data), MetaForm::Item) => {
+            let value_opts = match enums::roots::RootOpts::parse(ast)? {
+                enums::roots::RootOpts::None => None,
+                enums::roots::RootOpts::Value(opts) => Some(opts),
+            };
+            let variants = data
+                .variants
+                .iter()
+                .map(|variant| variant_meta(ast, variant, value_opts.as_ref()))
+                .collect::<DeriveResult<Vec<_>>>()?;
+            parse_quote! { ::xmlity::meta::TypeKind::Choice { variants: &[#(#variants,)*] } }
+        }
+        (syn::Data::Enum(_), MetaForm::Group) => {
+            let variants = GroupVariant::parse_all(ast)?
+                .iter()
+                .map(group_variant_meta)
+                .collect::<Vec<_>>();
+            parse_quote! { ::xmlity::meta::TypeKind::Choice { variants: &[#(#variants,)*] } }
+        }
+        (syn::Data::Union(_), _) => {
+            return Err(DeriveError::custom(
+                "Unions can not be described with `meta`.",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// The structure of this type, generated with `#[xmlity(meta)]`.
+            pub const SCHEMA: ::xmlity::meta::TypeMeta = ::xmlity::meta::TypeMeta::new(#rust_name, #kind);
+        }
+    })
+}
+
+fn record_kind<T: Fn(Expr) -> Expr>(
+    input: &RecordInput<'_, T>,
+    opts: records::roots::DeserializeRootOpts,
+) -> Expr {
+    use records::roots::DeserializeRootOpts;
+
+    let default_name = input.impl_for_ident.to_string();
+    match opts {
+        DeserializeRootOpts::Element(opts) => {
+            element_kind(opts.expanded_name(&default_name), fields_meta(input))
+        }
+        DeserializeRootOpts::Attribute(opts) => attribute_kind(opts.expanded_name(&default_name)),
+        DeserializeRootOpts::Value(records::roots::RootValueOpts {
+            value: Some(value), ..
+        }) if matches!(input.fields, StructTypeWithFields::Unit) => text_kind(Some(&value)),
+        DeserializeRootOpts::Value(_) | DeserializeRootOpts::None => value_kind(fields_meta(input)),
+        DeserializeRootOpts::Pi(opts) => pi_kind(&opts.target),
+    }
+}
+
+fn variant_meta(
+    ast: &DeriveInput,
+    variant: &syn::Variant,
+    value_opts: Option<&enums::roots::RootValueOpts>,
+) -> DeriveResult<Expr> {
+    use enums::variants::DeserializeRootOpts;
+
+    let input = parse_enum_variant_derive_input(&ast.ident, &ast.generics, variant, false)?;
+    let variant_name = variant.ident.to_string();
+    let kind = match DeserializeRootOpts::parse(&variant.attrs)? {
+        DeserializeRootOpts::Element(opts) => element_kind(
+            opts.expanded_name(&input.impl_for_ident.to_string()),
+            fields_meta(&input),
+        ),
+        DeserializeRootOpts::Attribute(opts) => attribute_kind(opts.expanded_name(&variant_name)),
+        DeserializeRootOpts::Pi(opts) => pi_kind(&opts.target),
+        DeserializeRootOpts::Value(enums::variants::RootValueOpts {
+            value: Some(value), ..
+        }) if matches!(variant.fields, syn::Fields::Unit) => text_kind(Some(&value)),
+        DeserializeRootOpts::Value(_) | DeserializeRootOpts::None
+            if matches!(variant.fields, syn::Fields::Unit) =>
+        {
+            let (value, value_with) =
+                enums::roots::RootValueOpts::variant_value(value_opts, &variant_name);
+            text_kind(value_with.is_none().then_some(value.as_str()))
+        }
+        DeserializeRootOpts::Value(_) | DeserializeRootOpts::None => {
+            value_kind(fields_meta(&input))
+        }
+    };
+
+    Ok(type_meta(&variant_name, kind))
+}
+
+fn group_variant_meta(variant: &GroupVariant<'_>) -> Expr {
+    let variant_name = variant.variant.ident.to_string();
+    let kind = match &variant.kind {
+        GroupVariantKind::Attribute(opts) => attribute_kind(opts.expanded_name(&variant_name)),
+        GroupVariantKind::Value => {
+            let ident = match &variant.member {
+                syn::Member::Named(ident) => Some(ident.to_string()),
+                syn::Member::Unnamed(_) => None,
+            };
+            let field = field_meta(
+                ident,
+                variant.field_type,
+                quote! { ::xmlity::meta::FieldKind::Value },
+                false,
+            );
+            value_kind(vec![field])
+        }
+    };
+
+    type_meta(&variant_name, kind)
+}
+
+fn type_meta(rust_name: &str, kind: Expr) -> Expr {
+    parse_quote! { ::xmlity::meta::TypeMeta::new(#rust_name, #kind) }
+}
+
+fn element_kind(name: ExpandedName<'_>, fields: Vec<Expr>) -> Expr {
+    parse_quote! { ::xmlity::meta::TypeKind::Element { name: #name, fields: &[#(#fields,)*] } }
+}
+
+fn attribute_kind(name: ExpandedName<'_>) -> Expr {
+    parse_quote! { ::xmlity::meta::TypeKind::Attribute { name: #name } }
+}
+
+fn value_kind(fields: Vec<Expr>) -> Expr {
+    parse_quote! { ::xmlity::meta::TypeKind::Value { fields: &[#(#fields,)*] } }
+}
+
+fn text_kind(value: Option<&str>) -> Expr {
+    let value = option_expr(value);
+    parse_quote! { ::xmlity::meta::TypeKind::Text { value: #value } }
+}
+
+fn pi_kind(target: &str) -> Expr {
+    parse_quote! { ::xmlity::meta::TypeKind::Pi { target: #target } }
+}
+
+fn option_expr<T: ToTokens>(value: Option<T>) -> Expr {
+    match value {
+        Some(value) => parse_quote! { ::core::option::Option::Some(#value) },
+        None => parse_quote! { ::core::option::Option::None },
+    }
+}
+
+fn fields_meta<T: Fn(Expr) -> Expr>(input: &RecordInput<'_, T>) -> Vec<Expr> {
+    match &input.fields {
+        StructTypeWithFields::Named(fields) => fields
+            .iter()
+            .cloned()
+            .map(|field| field.map_ident(FieldIdent::Named))
+            .map(|field| record_field_meta(&field, true))
+            .collect(),
+        StructTypeWithFields::Unnamed(fields) => fields
+            .iter()
+            .cloned()
+            .map(|field| field.map_ident(FieldIdent::Indexed))
+            .map(|field| record_field_meta(&field, false))
+            .collect(),
+        StructTypeWithFields::Unit => Vec::new(),
+    }
+}
+
+fn record_field_meta(field: &FieldWithOpts<FieldIdent, FieldOpts>, named: bool) -> Expr {
+    let default_name = field.field_ident.to_named_ident().to_string();
+    let (kind, has_default) = match &field.options {
+        FieldOpts::Value(ChildOpts::Element(opts)) => {
+            let name = opts.expanded_name(&default_name);
+            (
+                quote! { ::xmlity::meta::FieldKind::Element { name: #name } },
+                opts.default_or_else().is_some(),
+            )
+        }
+        FieldOpts::Value(opts) => (
+            quote! { ::xmlity::meta::FieldKind::Value },
+            opts.default_or_else().is_some(),
+        ),
+        FieldOpts::Attribute(opts) => {
+            let name = option_expr(match opts {
+                AttributeOpts::Declared(opts) => Some(opts.expanded_name(&default_name)),
+                AttributeOpts::Deferred(_) => None,
+            });
+            (
+                quote! { ::xmlity::meta::FieldKind::Attribute { name: #name } },
+                opts.default_or_else().is_some(),
+            )
+        }
+        FieldOpts::Group(_) => (quote! { ::xmlity::meta::FieldKind::Group }, false),
+    };
+
+    field_meta(
+        named.then(|| field.field_ident.to_string()),
+        &field.field_type,
+        kind,
+        has_default,
+    )
+}
+
+/// Describes a field, which is optional if it has a default value or is an [`Option`] and occurs any number of times if it is a [`Vec`].
+fn field_meta(
+    ident: Option<String>,
+    field_type: &syn::Type,
+    kind: TokenStream,
+    has_default: bool,
+) -> Expr {
+    let ident = option_expr(ident);
+    let cardinality: Expr = match outer_type_ident(field_type) {
+        Some(outer) if outer == "Vec" => parse_quote! { ::xmlity::meta::Cardinality::Many },
+        Some(outer) if outer == "Option" => parse_quote! { ::xmlity::meta::Cardinality::Optional },
+        _ if has_default => parse_quote! { ::xmlity::meta::Cardinality::Optional },
+        _ => parse_quote! { ::xmlity::meta::Cardinality::Required },
+    };
+
+    let rust_type = type_name(field_type);
+
+    parse_quote! {
+        ::xmlity::meta::FieldMeta::new(#ident, #rust_type, #kind, #cardinality)
+    }
+}
+
+/// Writes a type the way it is usually written in the source, without the spaces that separate every token in the output of [`ToTokens`].
+fn type_name(ty: &syn::Type) -> String {
+    [
+        (" <", "<"),
+        ("< ", "<"),
+        (" >", ">"),
+        (" ,", ","),
+        (" ;", ";"),
+        (":: ", "::"),
+        (" ::", "::"),
+        ("& ", "&"),
+        ("( ", "("),
+        (" )", ")"),
+        ("[ ", "["),
+        (" ]", "]"),
+    ]
+    .into_iter()
+    .fold(ty.to_token_stream().to_string(), |name, (from, to)| {
+        name.replace(from, to)
+    })
+}
+
+/// The name of the outermost type of a path type with generic arguments, like `Vec` in `Vec<T>`.
+fn outer_type_ident(ty: &syn::Type) -> Option<&Ident> {
+    let syn::Type::Path(syn::TypePath { qself: None, path }) = ty else {
+        return None;
+    };
+    let segment = path.segments.last()?;
+    matches!(segment.arguments, syn::PathArguments::AngleBracketed(_)).then_some(&segment.ident)
+}
@@ -9,8 +9,17 @@ pub mod roots {
     #[darling(attributes(xvalue))]
     pub struct RootValueOpts {
         /// The text casing to use for unit variants when serializing and deserializing.
+        ///
+        /// This is exclusive with [`rename_all_with`].
+        #[darling(default)]
+        pub rename_all: Option<RenameRule>,
+        /// Use function to turn the names of unit variants into their text values, for conventions not covered by [`rename_all`].
+        ///
+        /// Should have signature like `pub fn rename(variant: &str) -> String`.
+        ///
+        /// This is exclusive with [`rename_all`].
         #[darling(default)]
-        pub rename_all: RenameRule,
+        pub rename_all_with: Option<Path>,
         /// The path to the module that provides the serialization and deserialization functions.
         ///
         /// `::serialize` and `::deserialize` will be appended to this path and used as the `serialize_with` and `deserialize_with` functions.
@@ -26,6 +35,30 @@ pub mod roots {
         /// Should have signature like `fn deserialize<'de, D: xmlity::Deserializer<'de>>(deserializer: D) -> Result<T, D::Error>`
         #[darling(default)]
         pub deserialize_with: Option<Expr>,
+        /// Use function to deserialize the value with a context supplied by the caller of the deserializer.
+        ///
+        /// Should have signature like `fn deserialize<'de, D: xmlity::Deserializer<'de>>(deserializer: D, context: Option<&C>) -> Result<T, D::Error>`, where the context is the external data of type `C` of the deserializer, if any. `C` must implement [`Clone`].
+        ///
+        /// This is exclusive with [`deserialize_with`].
+        #[darling(default)]
+        pub deserialize_with_context: Option<Expr>,
+        /// Match text against the values of unit variants without regard to case.
+        ///
+        /// *Deserialize only*
+        #[darling(default)]
+        pub case_insensitive: bool,
+        /// Treat any run of whitespace in text as a single space when matching it against the values of unit variants.
+        ///
+        /// *Deserialize only*
+        #[darling(default)]
+        pub collapse_whitespace: bool,
+        /// Treat the enum as a closed choice, failing with an error that lists the names of the elements, attributes and processing instructions and the text values of all variants if none of them matches.
+        ///
+        /// Every variant must either have a name given by `xelement`, `xattribute` or `xpi`, or be a unit variant.
+        ///
+        /// *Deserialize only*
+        #[darling(default)]
+        pub exhaustive: bool,
     }
 
     impl RootValueOpts {
@@ -35,9 +68,32 @@ pub mod roots {
             };
 
             let opts = Self::from_attributes(std::slice::from_ref(attr))?;
+            if opts.rename_all.is_some() && opts.rename_all_with.is_some() {
+                return Err(DeriveError::custom(
+                    "rename_all and rename_all_with can not both be set",
+                ));
+            }
+            if opts.deserialize_with.is_some() && opts.deserialize_with_context.is_some() {
+                return Err(DeriveError::custom(
+                    "deserialize_with and deserialize_with_context can not both be set",
+                ));
+            }
             Ok(Some(opts))
         }
 
+        /// Returns the text value of a unit variant without a value of its own, together with the function to apply to it if `rename_all_with` is set.
+        pub fn variant_value(opts: Option<&Self>, variant: &str) -> (String, Option<Path>) {
+            match opts.and_then(|opts| opts.rename_all_with.clone()) {
+                Some(rename_all_with) => (variant.to_owned(), Some(rename_all_with)),
+                None => (
+                    opts.and_then(|opts| opts.rename_all)
+                        .unwrap_or_default()
+                        .apply_to_variant(variant),
+                    None,
+                ),
+            }
+        }
+
         pub fn serialize_with(&self) -> Option<Expr> {
             self.serialize_with
                 .as_ref()
@@ -56,6 +112,12 @@ pub mod roots {
         }
 
         pub fn deserialize_with(&self) -> Option<Expr> {
+            if let Some(deserialize_with_context) = &self.deserialize_with_context {
+                return Some(parse_quote! {
+                    ::xmlity::__private::deserialize_with_context(#deserialize_with_context)
+                });
+            }
+
             self.deserialize_with
                 .as_ref()
                 .map(|deserialize_with| {
@@ -92,9 +154,9 @@ pub mod roots {
 }
 
 pub mod variants {
-    use syn::Attribute;
+    use syn::{Attribute, Path};
 
-    use crate::options::records::roots::{RootAttributeOpts, RootElementOpts};
+    use crate::options::records::roots::{RootAttributeOpts, RootElementOpts, RootPiOpts};
 
     use super::*;
 
@@ -103,6 +165,9 @@ pub mod variants {
     pub struct RootValueOpts {
         /// The text value to use for unit variants when serializing and deserializing.
         pub value: Option<String>,
+        /// The function that turns `value` into the text value, set from `rename_all_with` of the enum.
+        #[darling(skip)]
+        pub value_with: Option<Path>,
         /// Set if whitespace should be ignored when deserializing.
         /// - `Any` (*default*): Ignore any whitespace.
         /// - `None`: Do not ignore whitespace.
@@ -132,6 +197,16 @@ pub mod variants {
         /// *Deserialize only*
         #[darling(default)]
         pub order: ElementOrder,
+        /// Match text against the values of the value without regard to case.
+        ///
+        /// *Deserialize only*
+        #[darling(default)]
+        pub case_insensitive: bool,
+        /// Treat any run of whitespace in text as a single space when matching it against the values of the value.
+        ///
+        /// *Deserialize only*
+        #[darling(default)]
+        pub collapse_whitespace: bool,
     }
 
     impl RootValueOpts {
@@ -150,6 +225,7 @@ pub mod variants {
         Element(RootElementOpts),
         Attribute(RootAttributeOpts),
         Value(RootValueOpts),
+        Pi(RootPiOpts),
     }
 
     impl DeserializeRootOpts {
@@ -157,14 +233,107 @@ pub mod variants {
             let element_opts = RootElementOpts::parse(attrs)?;
             let attribute_opts = RootAttributeOpts::parse(attrs)?;
             let value_opts = RootValueOpts::parse(attrs)?;
+            let pi_opts = RootPiOpts::parse(attrs)?;
 
-            match (element_opts, attribute_opts, value_opts) {
-                    (Some(element_opts), None, None) => Ok(Self::Element(element_opts)),
-                    (None, Some(attribute_opts), None) => Ok(Self::Attribute(attribute_opts)),
-                    (None, None, Some(value_opts)) => Ok(Self::Value(value_opts)),
-                    (None, None, None) => Ok(Self::None),
-                    _ => Err(DeriveError::custom("Wrong options. Only one of `xelement`, `xattribute`, or `xvalue` can be used for root elements.")),
+            if element_opts.as_ref().is_some_and(|opts| opts.default) {
+                return Err(DeriveError::custom(
+                    "`default` can only be used on the `xelement` attribute of structs",
+                ));
+            }
+
+            match (element_opts, attribute_opts, value_opts, pi_opts) {
+                    (Some(element_opts), None, None, None) => Ok(Self::Element(element_opts)),
+                    (None, Some(attribute_opts), None, None) => Ok(Self::Attribute(attribute_opts)),
+                    (None, None, Some(value_opts), None) => Ok(Self::Value(value_opts)),
+                    (None, None, None, Some(pi_opts)) => Ok(Self::Pi(pi_opts)),
+                    (None, None, None, None) => Ok(Self::None),
+                    _ => Err(DeriveError::custom("Wrong options. Only one of `xelement`, `xattribute`, `xvalue`, or `xpi` can be used for root elements.")),
+                }
+        }
+    }
+}
+
+pub mod groups {
+    use syn::{Member, Type, Variant};
+
+    use crate::options::records::roots::RootAttributeOpts;
+
+    use super::*;
+
+    /// How a variant of an enum deriving `SerializationGroup` or `DeserializationGroup` contributes to the element of its parent.
+    #[allow(clippy::large_enum_variant)]
+    pub enum GroupVariantKind {
+        /// The field of the variant is an attribute with the given options.
+        Attribute(RootAttributeOpts),
+        /// The field of the variant is a child of the element.
+        Value,
+    }
+
+    pub struct GroupVariant<'a> {
+        pub variant: &'a Variant,
+        pub member: Member,
+        pub field_type: &'a Type,
+        pub kind: GroupVariantKind,
+    }
+
+    impl<'a> GroupVariant<'a> {
+        pub fn parse(variant: &'a Variant) -> Result<Self, DeriveError> {
+            let (member, field_type) = match &variant.fields {
+                syn::Fields::Named(fields) if fields.named.len() == 1 => {
+                    let field = &fields.named[0];
+                    (
+                        Member::Named(field.ident.clone().expect("Named fields have idents.")),
+                        &field.ty,
+                    )
+                }
+                syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                    (Member::Unnamed(0.into()), &fields.unnamed[0].ty)
+                }
+                _ => {
+                    return Err(DeriveError::custom(format!(
+                        "Variant `{}` of a group must have exactly one field.",
+                        variant.ident
+                    )))
+                }
+            };
+
+            let kind = match variants::DeserializeRootOpts::parse(&variant.attrs)? {
+                variants::DeserializeRootOpts::Attribute(opts) => GroupVariantKind::Attribute(opts),
+                variants::DeserializeRootOpts::None => GroupVariantKind::Value,
+                _ => {
+                    return Err(DeriveError::custom(format!(
+                        "Variant `{}` of a group can only use `xattribute`, or no options to be a child.",
+                        variant.ident
+                    )))
                 }
+            };
+
+            Ok(Self {
+                variant,
+                member,
+                field_type,
+                kind,
+            })
+        }
+
+        pub fn parse_all(ast: &'a syn::DeriveInput) -> Result<Vec<Self>, DeriveError> {
+            let syn::Data::Enum(data) = &ast.data else {
+                unreachable!("Should only be called for enums.")
+            };
+
+            data.variants.iter().map(Self::parse).collect()
+        }
+
+        /// The name used for the attribute of an attribute variant.
+        pub fn attribute_name(&self) -> Option<(&RootAttributeOpts, ExpandedName<'static>)> {
+            match &self.kind {
+                GroupVariantKind::Attribute(opts) => Some((
+                    opts,
+                    opts.expanded_name(&self.variant.ident.to_string())
+                        .into_owned(),
+                )),
+                GroupVariantKind::Value => None,
+            }
         }
     }
 }
@@ -2,10 +2,10 @@
 use std::borrow::Cow;
 
 use darling::{FromAttributes, FromMeta};
-use syn::{DeriveInput, Expr};
+use syn::{ext::IdentExt, parse::ParseStream, parse_quote, Attribute, DeriveInput, Expr};
 
 use crate::{
-    common::{ExpandedName, LocalName, XmlNamespace},
+    common::{is_valid_name, ExpandedName, LocalName, Prefix, XmlNamespace},
     DeriveError,
 };
 
@@ -29,6 +29,47 @@ pub enum ElementOrder {
     None,
 }
 
+#[derive(Debug, Clone, Copy, FromMeta, PartialEq)]
+#[darling(rename_all = "snake_case")]
+pub enum BoolFormat {
+    Text,
+    Numeric,
+}
+
+impl BoolFormat {
+    pub fn expr(self) -> Expr {
+        match self {
+            BoolFormat::Text => parse_quote!(::xmlity::ser::BoolFormat::Text),
+            BoolFormat::Numeric => parse_quote!(::xmlity::ser::BoolFormat::Numeric),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, FromMeta, PartialEq)]
+#[darling(rename_all = "snake_case")]
+pub enum DuplicateKeys {
+    Error,
+    First,
+    Last,
+}
+
+impl DuplicateKeys {
+    pub fn expr(self) -> Expr {
+        match self {
+            DuplicateKeys::Error => parse_quote!(::xmlity::types::keyed::DuplicateKeys::Error),
+            DuplicateKeys::First => parse_quote!(::xmlity::types::keyed::DuplicateKeys::First),
+            DuplicateKeys::Last => parse_quote!(::xmlity::types::keyed::DuplicateKeys::Last),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, FromMeta, PartialEq)]
+#[darling(rename_all = "snake_case")]
+pub enum IntFormat {
+    Decimal,
+    Hex,
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq)]
 #[allow(clippy::enum_variant_names)]
 pub enum RenameRule {
@@ -70,6 +111,30 @@ impl RenameRule {
                 .replace('_', "-"),
         }
     }
+
+    /// Apply a renaming rule to a struct field, which is expected to be written in snake case, returning the version expected in the source.
+    pub fn apply_to_field(self, field: &str) -> String {
+        match self {
+            Self::LowerCase | Self::SnakeCase => field.to_owned(),
+            Self::UpperCase | Self::ScreamingSnakeCase => field.to_ascii_uppercase(),
+            Self::PascalCase => field
+                .split('_')
+                .map(|word| {
+                    let mut chars = word.chars();
+                    chars
+                        .next()
+                        .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                        .unwrap_or_default()
+                })
+                .collect(),
+            Self::CamelCase => {
+                let pascal = Self::PascalCase.apply_to_field(field);
+                pascal[..1].to_ascii_lowercase() + &pascal[1..]
+            }
+            Self::KebabCase => field.replace('_', "-"),
+            Self::ScreamingKebabCase => field.to_ascii_uppercase().replace('_', "-"),
+        }
+    }
 }
 
 impl FromMeta for RenameRule {
@@ -125,6 +190,10 @@ impl FromMeta for Extendable {
         }
     }
 
+    fn from_word() -> darling::Result<Self> {
+        Ok(Self::Single)
+    }
+
     fn from_bool(value: bool) -> darling::Result<Self> {
         if value {
             Ok(Self::Single)
@@ -196,6 +265,162 @@ impl<T: WithExpandedName> WithExpandedNameExt for T {
     }
 }
 
+/// Options given with `#[xmlity(...)]` on a type, which apply to all of the derive macros.
+#[derive(FromAttributes, Default, Clone)]
+#[darling(attributes(xmlity))]
+pub struct XmlityOpts {
+    /// Only generate the serialization impls, making the deserialization derive macros generate nothing.
+    #[darling(default)]
+    pub serialize_only: bool,
+    /// Only generate the deserialization impls, making the serialization derive macros generate nothing.
+    #[darling(default)]
+    pub deserialize_only: bool,
+    /// Generate a `SCHEMA` constant describing the structure of the type.
+    #[darling(default)]
+    pub meta: bool,
+}
+
+impl XmlityOpts {
+    pub fn parse(attrs: &[Attribute]) -> Result<Self, DeriveError> {
+        let opts = Self::from_attributes(attrs)?;
+        if opts.serialize_only && opts.deserialize_only {
+            return Err(DeriveError::custom(
+                "Cannot specify both `serialize_only` and `deserialize_only`",
+            ));
+        }
+
+        Ok(opts)
+    }
+
+    /// Returns `true` if impls should be generated for the given direction.
+    pub fn generates(&self, direction: DeriveDirection) -> bool {
+        match direction {
+            DeriveDirection::Serialize => !self.deserialize_only,
+            DeriveDirection::Deserialize => !self.serialize_only,
+        }
+    }
+
+    /// Returns `true` if the `SCHEMA` constant of `meta` should be generated by the derive macros of the given direction, which are the deserialization ones unless the type is `serialize_only`.
+    pub fn describes(&self, direction: DeriveDirection) -> bool {
+        let describing_direction = if self.serialize_only {
+            DeriveDirection::Serialize
+        } else {
+            DeriveDirection::Deserialize
+        };
+        self.meta && direction == describing_direction
+    }
+}
+
+/// Whether a derive macro generates serialization or deserialization impls.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DeriveDirection {
+    Serialize,
+    Deserialize,
+}
+
+/// Namespace declarations given with `#[xmlns(prefix = "namespace")]` on a type, used to resolve qualified names like `name = "xs:element"` in the options of the type and its fields.
+#[derive(Default, Clone)]
+pub struct XmlnsDeclarations(pub Vec<(Prefix<'static>, XmlNamespace<'static>)>);
+
+impl XmlnsDeclarations {
+    const XML_NAMESPACE: &'static str = "http://www.w3.org/XML/1998/namespace";
+
+    /// Parses `#[xmlns(prefix = "namespace", ...)]` attributes. Prefixes that are not Rust identifiers, like `my-prefix`, can be given as string literals.
+    pub fn parse(attrs: &[Attribute]) -> Result<Self, DeriveError> {
+        let mut declarations: Vec<(Prefix<'static>, XmlNamespace<'static>)> = Vec::new();
+
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("xmlns")) {
+            attr.parse_args_with(|input: ParseStream| {
+                while !input.is_empty() {
+                    let (prefix, span) = if input.peek(syn::LitStr) {
+                        let prefix: syn::LitStr = input.parse()?;
+                        (prefix.value(), prefix.span())
+                    } else {
+                        let prefix = input.call(syn::Ident::parse_any)?.unraw();
+                        (prefix.to_string(), prefix.span())
+                    };
+                    input.parse::<syn::Token![=]>()?;
+                    let namespace: syn::LitStr = input.parse()?;
+
+                    if !is_valid_name(&prefix) {
+                        return Err(syn::Error::new(
+                            span,
+                            format!("`{prefix}` is not a valid XML prefix"),
+                        ));
+                    }
+                    if declarations
+                        .iter()
+                        .any(|(declared_prefix, _)| declared_prefix.0 == prefix)
+                    {
+                        return Err(syn::Error::new(
+                            span,
+                            format!("The prefix `{prefix}` is declared more than once"),
+                        ));
+                    }
+
+                    declarations.push((
+                        Prefix(Cow::Owned(prefix)),
+                        XmlNamespace(Cow::Owned(namespace.value())),
+                    ));
+
+                    if input.is_empty() {
+                        break;
+                    }
+                    input.parse::<syn::Token![,]>()?;
+                }
+                Ok(())
+            })?;
+        }
+
+        Ok(Self(declarations))
+    }
+
+    pub fn namespace(&self, prefix: &str) -> Option<XmlNamespace<'static>> {
+        self.0
+            .iter()
+            .find(|(declared_prefix, _)| declared_prefix.0 == prefix)
+            .map(|(_, namespace)| namespace.clone())
+            .or_else(|| {
+                (prefix == "xml").then_some(XmlNamespace(Cow::Borrowed(Self::XML_NAMESPACE)))
+            })
+    }
+
+    /// If `name` is a qualified name, splits it into its local name and sets the namespace bound to its prefix, using the prefix as the preferred prefix unless one is already set.
+    pub fn resolve(
+        &self,
+        name: &mut Option<LocalName<'static>>,
+        namespace: &mut Option<XmlNamespace<'static>>,
+        namespace_expr: &Option<Expr>,
+        preferred_prefix: &mut Option<Prefix<'static>>,
+    ) -> Result<(), DeriveError> {
+        let Some((prefix, local_name)) = name.as_ref().and_then(|name| name.0.split_once(':'))
+        else {
+            return Ok(());
+        };
+
+        if namespace.is_some() || namespace_expr.is_some() {
+            return Err(DeriveError::custom(format!(
+                "Cannot specify `namespace` or `namespace_expr` together with the qualified name `{prefix}:{local_name}`"
+            )));
+        }
+
+        let Some(resolved_namespace) = self.namespace(prefix) else {
+            return Err(DeriveError::custom(format!(
+                "The prefix `{prefix}` is not declared. Declare it using `#[xmlns({prefix} = \"...\")]`"
+            )));
+        };
+
+        let prefix = Prefix(Cow::Owned(prefix.to_owned()));
+        let local_name = LocalName(Cow::Owned(local_name.to_owned()));
+
+        *namespace = Some(resolved_namespace);
+        preferred_prefix.get_or_insert(prefix);
+        *name = Some(local_name);
+
+        Ok(())
+    }
+}
+
 #[derive(Clone)]
 pub struct FieldWithOpts<I, Opts> {
     // If the field is indexed, this is none.
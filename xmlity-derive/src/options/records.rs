@@ -63,6 +63,11 @@ pub mod roots {
         /// *Deserialize only*
         #[darling(default)]
         pub deserialize_any_name: bool,
+        /// Deserialize the struct as its [`Default`] value if the element is missing from the children of its parent, instead of failing with a missing field error.
+        ///
+        /// *Deserialize only*
+        #[darling(default)]
+        pub default: bool,
         /// Set if the order of attributes is important when serializing or deserializing.
         /// - `Strict`: The order of attributes must match the order in the struct or enum variant.
         /// - `None` (*default*): The order of attributes does not matter, but the attributes must be present.
@@ -91,22 +96,139 @@ pub mod roots {
         /// *Deserialize only*
         #[darling(default)]
         pub ignore_comments: IgnoreComments,
+        /// Pairs of namespaces and schema locations written as an `xsi:schemaLocation` attribute after the other attributes.
+        ///
+        /// *Serialize only*
+        #[darling(default)]
+        pub xsi_schema_location: Option<String>,
+        /// The location of a schema without a target namespace written as an `xsi:noNamespaceSchemaLocation` attribute after the other attributes.
+        ///
+        /// *Serialize only*
+        #[darling(default)]
+        pub no_namespace_schema_location: Option<String>,
+        /// The casing of the names of attributes and child elements of fields that do not have a name of their own, which are otherwise named after the field.
+        #[darling(default)]
+        pub rename_all: Option<RenameRule>,
+        /// Namespace declarations given with `#[xmlns(...)]` on the type. They are declared on the element when serializing, even if they are not used.
+        #[darling(skip)]
+        pub xmlns: XmlnsDeclarations,
+        /// Other names of the element, given with additional `#[xelement(...)]` attributes, which are accepted in place of the name when deserializing.
+        ///
+        /// *Deserialize only*
+        #[darling(skip)]
+        pub aliases: Vec<ElementAliasOpts>,
     }
 
     impl RootElementOpts {
         pub fn parse(attrs: &[Attribute]) -> Result<Option<Self>, DeriveError> {
-            let Some(attr) = attrs.iter().find(|attr| attr.path().is_ident("xelement")) else {
+            let mut xelement_attrs = attrs.iter().filter(|attr| attr.path().is_ident("xelement"));
+            let Some(attr) = xelement_attrs.next() else {
                 return Ok(None);
             };
 
-            let opts = Self::from_attributes(std::slice::from_ref(attr))?;
+            let mut opts = Self::from_attributes(std::slice::from_ref(attr))?;
             if opts.namespace_expr.is_some() && opts.namespace.is_some() {
                 return Err(DeriveError::custom(
                     "Cannot specify both `namespace` and `namespace_expr`",
                 ));
             }
+            if opts
+                .xsi_schema_location
+                .as_ref()
+                .is_some_and(|location| location.split_whitespace().count() % 2 != 0)
+            {
+                return Err(DeriveError::custom(
+                    "`xsi_schema_location` must consist of pairs of namespaces and schema locations",
+                ));
+            }
+            if opts
+                .no_namespace_schema_location
+                .as_ref()
+                .is_some_and(|location| location.trim().is_empty())
+            {
+                return Err(DeriveError::custom(
+                    "`no_namespace_schema_location` cannot be empty",
+                ));
+            }
+            opts.xmlns = XmlnsDeclarations::parse(attrs)?;
+            opts.xmlns.resolve(
+                &mut opts.name,
+                &mut opts.namespace,
+                &opts.namespace_expr,
+                &mut opts.preferred_prefix,
+            )?;
+            opts.aliases = xelement_attrs
+                .map(|attr| ElementAliasOpts::parse(attr, &opts.xmlns))
+                .collect::<Result<_, _>>()?;
+            if opts.deserialize_any_name && !opts.aliases.is_empty() {
+                return Err(DeriveError::custom(
+                    "Cannot have more than one `xelement` attribute together with `deserialize_any_name`",
+                ));
+            }
             Ok(Some(opts))
         }
+
+        /// Returns the `rename_all` of the `#[xelement(...)]` attribute among `attrs`, if any.
+        pub fn parse_rename_all(attrs: &[Attribute]) -> Result<Option<RenameRule>, DeriveError> {
+            Ok(Self::parse(attrs)?.and_then(|opts| opts.rename_all))
+        }
+    }
+
+    /// An additional `#[xelement(...)]` attribute, giving another name the element is deserialized from.
+    #[derive(FromAttributes, Clone)]
+    #[darling(attributes(xelement))]
+    pub struct ElementAliasOpts {
+        /// The other name of the element.
+        ///
+        /// If not specified, the name of the struct or enum variant is used.
+        #[darling(default)]
+        pub name: Option<LocalName<'static>>,
+        /// The namespace of the other name, defined as a string.
+        ///
+        /// This is exclusive with [`namespace_expr`].
+        ///
+        /// If none of these are specified, the absence of a namespace is assumed.
+        #[darling(default)]
+        pub namespace: Option<XmlNamespace<'static>>,
+        /// The namespace of the other name given as an expression to an [`xmlity::XmlNamespace`] value.
+        ///
+        /// This is exclusive with [`namespace`].
+        ///
+        /// If none of these are specified, the absence of a namespace is assumed.
+        #[darling(default)]
+        pub namespace_expr: Option<Expr>,
+    }
+
+    impl ElementAliasOpts {
+        fn parse(attr: &Attribute, xmlns: &XmlnsDeclarations) -> Result<Self, DeriveError> {
+            let mut opts = Self::from_attributes(std::slice::from_ref(attr))?;
+            if opts.namespace_expr.is_some() && opts.namespace.is_some() {
+                return Err(DeriveError::custom(
+                    "Cannot specify both `namespace` and `namespace_expr`",
+                ));
+            }
+            xmlns.resolve(
+                &mut opts.name,
+                &mut opts.namespace,
+                &opts.namespace_expr,
+                &mut None,
+            )?;
+            Ok(opts)
+        }
+    }
+
+    impl WithExpandedName for ElementAliasOpts {
+        fn name(&self) -> Option<LocalName<'_>> {
+            self.name.clone()
+        }
+
+        fn namespace(&self) -> Option<XmlNamespace<'_>> {
+            self.namespace.clone()
+        }
+
+        fn namespace_expr(&self) -> Option<Expr> {
+            self.namespace_expr.clone()
+        }
     }
 
     impl WithExpandedName for RootElementOpts {
@@ -172,7 +294,13 @@ pub mod roots {
                 return Ok(None);
             };
 
-            let opts = Self::from_attributes(std::slice::from_ref(attr))?;
+            let mut opts = Self::from_attributes(std::slice::from_ref(attr))?;
+            XmlnsDeclarations::parse(attrs)?.resolve(
+                &mut opts.name,
+                &mut opts.namespace,
+                &opts.namespace_expr,
+                &mut opts.preferred_prefix,
+            )?;
             Ok(Some(opts))
         }
     }
@@ -196,6 +324,9 @@ pub mod roots {
     pub struct RootValueOpts {
         /// The text value to serialize to and deserialize from.
         pub value: Option<String>,
+        /// The function that turns `value` into the text value, set from `rename_all_with` of an enum.
+        #[darling(skip)]
+        pub value_with: Option<Path>,
         #[darling(default)]
         /// Set if whitespace should be ignored when deserializing.
         /// - `Any` (*default*): Ignore any whitespace.
@@ -240,6 +371,26 @@ pub mod roots {
         /// Should have signature like `fn deserialize<'de, D: xmlity::Deserializer<'de>>(deserializer: D) -> Result<T, D::Error>`
         #[darling(default)]
         pub deserialize_with: Option<Expr>,
+        /// Use function to deserialize the value with a context supplied by the caller of the deserializer.
+        ///
+        /// Should have signature like `fn deserialize<'de, D: xmlity::Deserializer<'de>>(deserializer: D, context: Option<&C>) -> Result<T, D::Error>`, where the context is the external data of type `C` of the deserializer, if any. `C` must implement [`Clone`].
+        ///
+        /// This is exclusive with [`deserialize_with`].
+        #[darling(default)]
+        pub deserialize_with_context: Option<Expr>,
+        /// Match text against the value without regard to case.
+        ///
+        /// *Deserialize only*
+        #[darling(default)]
+        pub case_insensitive: bool,
+        /// Treat any run of whitespace in text as a single space when matching it against the value.
+        ///
+        /// *Deserialize only*
+        #[darling(default)]
+        pub collapse_whitespace: bool,
+        /// Serialize and deserialize the struct exactly like its only field, without anything around it.
+        #[darling(default)]
+        pub transparent: bool,
     }
 
     impl RootValueOpts {
@@ -249,9 +400,45 @@ pub mod roots {
             };
 
             let opts = Self::from_attributes(std::slice::from_ref(attr))?;
+            if opts.deserialize_with.is_some() && opts.deserialize_with_context.is_some() {
+                return Err(DeriveError::custom(
+                    "deserialize_with and deserialize_with_context can not both be set",
+                ));
+            }
+            if opts.transparent
+                && (opts.value.is_some()
+                    || opts.with.is_some()
+                    || opts.serialize_with.is_some()
+                    || opts.deserialize_with.is_some()
+                    || opts.deserialize_with_context.is_some())
+            {
+                return Err(DeriveError::custom(
+                    "transparent can not be combined with value, with, serialize_with, deserialize_with or deserialize_with_context",
+                ));
+            }
             Ok(Some(opts))
         }
 
+        /// Whether the `xvalue` attribute sets any of `with`, `serialize_with`, `deserialize_with` or `deserialize_with_context`, in which case those functions are used instead of the fields.
+        pub fn parse_uses_with(attrs: &[Attribute]) -> Result<bool, DeriveError> {
+            Ok(Self::parse(attrs)?.is_some_and(|opts| {
+                opts.with.is_some()
+                    || opts.serialize_with.is_some()
+                    || opts.deserialize_with.is_some()
+                    || opts.deserialize_with_context.is_some()
+            }))
+        }
+
+        /// The expression for the text value, which is a `&str` or a reference to a [`String`].
+        pub fn value_expr(&self) -> Option<Expr> {
+            let value = self.value.as_ref()?;
+
+            Some(match &self.value_with {
+                Some(value_with) => parse_quote! { &#value_with(#value) },
+                None => parse_quote! { #value },
+            })
+        }
+
         pub fn serialize_with(&self) -> Option<Expr> {
             self.serialize_with
                 .as_ref()
@@ -270,6 +457,12 @@ pub mod roots {
         }
 
         pub fn deserialize_with(&self) -> Option<Expr> {
+            if let Some(deserialize_with_context) = &self.deserialize_with_context {
+                return Some(parse_quote! {
+                    ::xmlity::__private::deserialize_with_context(#deserialize_with_context)
+                });
+            }
+
             self.deserialize_with
                 .as_ref()
                 .map(|deserialize_with| {
@@ -319,32 +512,55 @@ pub mod roots {
         }
     }
 
+    #[derive(FromAttributes, Clone)]
+    #[darling(attributes(xpi))]
+    pub struct RootPiOpts {
+        /// The target of the processing instruction.
+        pub target: String,
+    }
+
+    impl RootPiOpts {
+        pub fn parse(attrs: &[Attribute]) -> Result<Option<Self>, DeriveError> {
+            let Some(attr) = attrs.iter().find(|attr| attr.path().is_ident("xpi")) else {
+                return Ok(None);
+            };
+
+            let opts = Self::from_attributes(std::slice::from_ref(attr))?;
+            Ok(Some(opts))
+        }
+    }
+
     #[allow(clippy::large_enum_variant)]
     pub enum SerializeRootOpts {
         None,
         Element(RootElementOpts),
         Value(RootValueOpts),
+        Pi(RootPiOpts),
     }
 
     impl SerializeRootOpts {
         pub fn parse(attrs: &[Attribute]) -> Result<Self, DeriveError> {
             let element_opts = RootElementOpts::parse(attrs)?;
             let value_opts = RootValueOpts::parse(attrs)?;
+            let pi_opts = RootPiOpts::parse(attrs)?;
 
-            match (element_opts, value_opts) {
-                    (Some(element_opts), None) => Ok(Self::Element(element_opts)),
-                    (None, Some(value_opts)) => Ok(Self::Value(value_opts)),
-                    (None, None) => Ok(Self::None),
-                    _ => Err(DeriveError::custom("Wrong options. Only one of `xelement`, `xattribute`, or `xvalue` can be used for root elements.")),
+            match (element_opts, value_opts, pi_opts) {
+                    (Some(element_opts), None, None) => Ok(Self::Element(element_opts)),
+                    (None, Some(value_opts), None) => Ok(Self::Value(value_opts)),
+                    (None, None, Some(pi_opts)) => Ok(Self::Pi(pi_opts)),
+                    (None, None, None) => Ok(Self::None),
+                    _ => Err(DeriveError::custom("Wrong options. Only one of `xelement`, `xattribute`, `xvalue`, or `xpi` can be used for root elements.")),
                 }
         }
     }
 
+    #[allow(clippy::large_enum_variant)]
     pub enum DeserializeRootOpts {
         None,
         Element(RootElementOpts),
         Attribute(RootAttributeOpts),
         Value(RootValueOpts),
+        Pi(RootPiOpts),
     }
 
     impl DeserializeRootOpts {
@@ -352,13 +568,15 @@ pub mod roots {
             let element_opts = RootElementOpts::parse(attrs)?;
             let attribute_opts = RootAttributeOpts::parse(attrs)?;
             let value_opts = RootValueOpts::parse(attrs)?;
-
-            match (element_opts, attribute_opts, value_opts) {
-                    (Some(element_opts), None, None) => Ok(Self::Element(element_opts)),
-                    (None, Some(attribute_opts), None) => Ok(Self::Attribute(attribute_opts)),
-                    (None, None, Some(value_opts)) => Ok(Self::Value(value_opts)),
-                    (None, None, None) => Ok(Self::None),
-                    _ => Err(DeriveError::custom("Wrong options. Only one of `xelement`, `xattribute`, or `xvalue` can be used for root elements.")),
+            let pi_opts = RootPiOpts::parse(attrs)?;
+
+            match (element_opts, attribute_opts, value_opts, pi_opts) {
+                    (Some(element_opts), None, None, None) => Ok(Self::Element(element_opts)),
+                    (None, Some(attribute_opts), None, None) => Ok(Self::Attribute(attribute_opts)),
+                    (None, None, Some(value_opts), None) => Ok(Self::Value(value_opts)),
+                    (None, None, None, Some(pi_opts)) => Ok(Self::Pi(pi_opts)),
+                    (None, None, None, None) => Ok(Self::None),
+                    _ => Err(DeriveError::custom("Wrong options. Only one of `xelement`, `xattribute`, `xvalue`, or `xpi` can be used for root elements.")),
                 }
         }
     }
@@ -366,7 +584,7 @@ pub mod roots {
 
 pub mod fields {
     use quote::ToTokens;
-    use syn::{parse_quote, Path};
+    use syn::{ext::IdentExt, parse_quote, Ident, Path};
 
     use crate::common::Prefix;
 
@@ -383,6 +601,11 @@ pub mod fields {
         /// Should have signature like `pub fn default_value() -> T`.
         #[darling(default)]
         pub default_with: Option<Path>,
+        /// Default to a clone of the value of another field of the same type if the element is not present.
+        ///
+        /// *Deserialize only*
+        #[darling(default)]
+        pub default_from: Option<Ident>,
         #[darling(default)]
         pub extendable: Extendable,
         #[darling(default)]
@@ -397,15 +620,23 @@ pub mod fields {
         pub enforce_prefix: bool,
         #[darling(default)]
         pub optional: bool,
+        /// Treat the field as a group, serializing its attributes and children inside the element instead of the field itself.
+        ///
+        /// The type of the field should implement `SerializationGroup`/`DeserializationGroup`.
         #[darling(default)]
         pub group: bool,
         #[darling(default)]
         pub skip_serializing_if: Option<Path>,
+        /// The position of the field among the children of the element. See [`ChildOpts::serialize_order`].
+        #[darling(default)]
+        pub serialize_order: Option<i32>,
     }
 
     impl ElementOpts {
         pub fn default_or_else(&self) -> Option<Expr> {
-            if let Some(default_with) = self.default_with.as_ref() {
+            if let Some(default_from) = self.default_from.as_ref() {
+                Some(default_from_expr(default_from))
+            } else if let Some(default_with) = self.default_with.as_ref() {
                 Some(parse_quote! {
                     #default_with
                 })
@@ -459,19 +690,116 @@ pub mod fields {
         /// Should have signature like `pub fn default_value() -> T`.
         #[darling(default)]
         pub default_with: Option<Path>,
+        /// Default to a clone of the value of another field of the same type if the value is not present.
+        ///
+        /// *Deserialize only*
+        #[darling(default)]
+        pub default_from: Option<Ident>,
         #[darling(default)]
         pub extendable: Extendable,
         #[darling(default)]
         pub skip_serializing_if: Option<Path>,
+        /// Capture the entire text content of the element as a string, joining text and CDATA nodes and defaulting to an empty string if there is none.
+        #[darling(default)]
+        pub raw_text: bool,
+        /// The position of the field among the children of the element. See [`ChildOpts::serialize_order`].
+        #[darling(default)]
+        pub serialize_order: Option<i32>,
+        /// Write the [`bool`] field in the given format, regardless of the format of the serializer.
+        ///
+        /// *Serialize only*
+        #[darling(default)]
+        pub bool_format: Option<BoolFormat>,
+        /// Accept integers with a `0x`, `0o` or `0b` prefix in addition to decimal integers.
+        ///
+        /// *Deserialize only*
+        #[darling(default)]
+        pub radix_prefixes: bool,
+        /// Write the integer field in the given format.
+        ///
+        /// *Serialize only*
+        #[darling(default)]
+        pub int_format: Option<IntFormat>,
+        /// Collect the elements into a map keyed by the given attribute of each element.
+        #[darling(default)]
+        pub key_attribute: Option<LocalName<'static>>,
+        /// What to do when several elements have the same key. Defaults to failing.
+        ///
+        /// *Deserialize only*
+        #[darling(default)]
+        pub duplicate_keys: Option<DuplicateKeys>,
     }
 
     impl ValueOpts {
+        /// The type the field is deserialized as before it is unwrapped into the field type, if it is not the field type itself.
+        pub fn wrapped_deserialize_type(&self, field_type: &syn::Type) -> Option<syn::Type> {
+            if self.key_attribute.is_some() {
+                return Some(parse_quote!(::xmlity::types::keyed::KeyedBy<#field_type, __K>));
+            }
+
+            self.radix_prefixes
+                .then(|| parse_quote!(::xmlity::types::radix::RadixPrefixed<#field_type>))
+        }
+
+        /// Items that [`Self::wrapped_deserialize_type`] depends on, which must be in scope where it is used.
+        pub fn wrapped_deserialize_definitions(&self) -> Vec<syn::Stmt> {
+            let Some(key_attribute) = &self.key_attribute else {
+                return Vec::new();
+            };
+
+            let name = ExpandedName::new(key_attribute.clone(), None);
+            let duplicate_keys = self.duplicate_keys.unwrap_or(DuplicateKeys::Error).expr();
+
+            parse_quote! {
+                struct __K;
+
+                impl ::xmlity::types::keyed::KeyAttribute for __K {
+                    fn name() -> ::xmlity::ExpandedName<'static> {
+                        #name
+                    }
+
+                    const DUPLICATES: ::xmlity::types::keyed::DuplicateKeys = #duplicate_keys;
+                }
+            }
+        }
+
+        /// Whether an error deserializing the field fails deserialization, instead of the child being left for other fields. Maps of keyed elements already end at the first element they can't deserialize, so they only fail for duplicate keys.
+        pub fn propagates_errors(&self) -> bool {
+            self.key_attribute.is_some()
+        }
+
+        /// The expression serialized in place of the field, if it is not the field itself.
+        pub fn wrapped_serialize_expr(&self, value_expr: &Expr) -> Option<Expr> {
+            if self.key_attribute.is_some() {
+                return Some(parse_quote! {
+                    &::xmlity::types::keyed::KeyedValues(#value_expr)
+                });
+            }
+
+            if let Some(bool_format) = self.bool_format {
+                let bool_format = bool_format.expr();
+                return Some(parse_quote! {
+                    &::xmlity::types::utils::FormattedBool::new(*#value_expr, #bool_format)
+                });
+            }
+
+            match self.int_format? {
+                IntFormat::Decimal => None,
+                IntFormat::Hex => Some(parse_quote! {
+                    &::xmlity::types::radix::Hex(*#value_expr)
+                }),
+            }
+        }
+
         pub fn default_or_else(&self) -> Option<Expr> {
-            if let Some(default_with) = &self.default_with {
+            if let Some(default_from) = &self.default_from {
+                Some(default_from_expr(default_from))
+            } else if let Some(default_with) = &self.default_with {
                 Some(parse_quote! {
                     #default_with
                 })
-            } else if self.default {
+            } else if self.default || self.key_attribute.is_some() {
+                // Maps of keyed elements are empty when there are no elements, like sequences.
                 Some(parse_quote! {
                     ::core::default::Default::default
                 })
@@ -505,31 +833,30 @@ pub mod fields {
     }
 
     impl ChildOpts {
-        pub fn default_or_else(&self) -> Option<Expr> {
-            let (default, default_with) = match self {
+        /// Children are serialized in ascending order, with fields without an explicit `serialize_order` counting as `0`. Fields with the same order keep their declaration order. Children in strict order are expected in the same order when deserializing.
+        pub fn serialize_order(&self) -> i32 {
+            match self {
                 ChildOpts::Value(ValueOpts {
-                    default,
-                    default_with,
-                    ..
-                }) => (*default, default_with),
-                ChildOpts::Element(ElementOpts {
-                    default,
-                    default_with,
-                    optional,
-                    ..
-                }) => (*default || *optional, default_with),
-            };
-
-            if let Some(default_with) = default_with {
-                Some(parse_quote! {
-                    #default_with
-                })
-            } else if default {
-                Some(parse_quote! {
-                    ::core::default::Default::default
+                    serialize_order, ..
                 })
-            } else {
-                None
+                | ChildOpts::Element(ElementOpts {
+                    serialize_order, ..
+                }) => serialize_order.unwrap_or_default(),
+            }
+        }
+
+        pub fn default_or_else(&self) -> Option<Expr> {
+            match self {
+                ChildOpts::Value(opts) => opts.default_or_else(),
+                ChildOpts::Element(opts) => opts.default_or_else(),
+            }
+        }
+
+        /// The field this field defaults to a clone of if it is not present.
+        pub fn default_from(&self) -> Option<&Ident> {
+            match self {
+                ChildOpts::Value(opts) => opts.default_from.as_ref(),
+                ChildOpts::Element(opts) => opts.default_from.as_ref(),
             }
         }
 
@@ -560,7 +887,43 @@ pub mod fields {
         pub fn from_xvalue_attribute(
             xvalue_attribute: syn::Attribute,
         ) -> Result<Option<Self>, DeriveError> {
-            let opts = ValueOpts::from_attributes(&[xvalue_attribute])?;
+            let mut opts = ValueOpts::from_attributes(&[xvalue_attribute])?;
+            check_default_from(opts.default, &opts.default_with, &opts.default_from)?;
+            if opts.bool_format.is_some() && opts.int_format.is_some() {
+                return Err(DeriveError::custom(
+                    "Cannot specify `bool_format` together with `int_format`",
+                ));
+            }
+            if opts.radix_prefixes && (opts.raw_text || opts.extendable != Extendable::None) {
+                return Err(DeriveError::custom(
+                    "Cannot specify `radix_prefixes` together with `raw_text` or `extendable`",
+                ));
+            }
+            if opts.key_attribute.is_some()
+                && (opts.raw_text
+                    || opts.radix_prefixes
+                    || opts.bool_format.is_some()
+                    || opts.int_format.is_some()
+                    || opts.extendable != Extendable::None)
+            {
+                return Err(DeriveError::custom(
+                    "Cannot specify `key_attribute` together with `raw_text`, `radix_prefixes`, `bool_format`, `int_format` or `extendable`",
+                ));
+            }
+            if opts.duplicate_keys.is_some() && opts.key_attribute.is_none() {
+                return Err(DeriveError::custom(
+                    "`duplicate_keys` can only be used together with `key_attribute`",
+                ));
+            }
+            if opts.raw_text {
+                if opts.extendable != Extendable::None {
+                    return Err(DeriveError::custom(
+                        "Cannot specify `extendable` together with `raw_text`",
+                    ));
+                }
+                opts.extendable = Extendable::Single;
+                opts.default |= opts.default_with.is_none();
+            }
             Ok(Some(ChildOpts::Value(opts)))
         }
 
@@ -568,6 +931,7 @@ pub mod fields {
             xelement_attribute: syn::Attribute,
         ) -> Result<Option<Self>, DeriveError> {
             let opts = ElementOpts::from_attributes(&[xelement_attribute])?;
+            check_default_from(opts.default, &opts.default_with, &opts.default_from)?;
             Ok(Some(ChildOpts::Element(opts)))
         }
     }
@@ -580,12 +944,20 @@ pub mod fields {
         ///
         /// Should have signature like `pub fn default_value() -> T`.
         pub default_with: Option<Path>,
+        /// Default to a clone of the value of another field of the same type if the attribute is not present.
+        ///
+        /// *Deserialize only*
+        pub default_from: Option<Ident>,
         /// Use function to skip serializing the field if it is not set.
         ///
         /// Should have signature like `pub fn skip_serializing_if(value: &T) -> bool`.
         pub skip_serializing_if: Option<Path>,
         /// If the field is an [`Option<T>`], it will not be serialized if it is not set.
         pub optional: bool,
+        /// Merge repeated attributes into the field, which must implement [`Extend`].
+        ///
+        /// *Deserialize only*
+        pub extendable: Extendable,
     }
 
     #[derive(Clone)]
@@ -596,6 +968,10 @@ pub mod fields {
         ///
         /// Should have signature like `pub fn default_value() -> T`.
         pub default_with: Option<Path>,
+        /// Default to a clone of the value of another field of the same type if the attribute is not present.
+        ///
+        /// *Deserialize only*
+        pub default_from: Option<Ident>,
         /// The name to serialize to and deserialize from.
         ///
         /// If not specified, the name of the struct is used.
@@ -628,6 +1004,18 @@ pub mod fields {
         pub skip_serializing_if: Option<Path>,
         /// If the field is an [`Option<T>`], it will not be serialized if it is not set.
         pub optional: bool,
+        /// Merge repeated attributes with the same name into the field, which must implement [`Extend`].
+        ///
+        /// *Deserialize only*
+        pub extendable: Extendable,
+        /// Read the attribute from the closest enclosing element with this local name instead of from the element itself.
+        ///
+        /// *Deserialize only*, the attribute is not written when serializing.
+        pub from_ancestor: Option<LocalName<'static>>,
+        /// The namespace of the element given in [`from_ancestor`], defined as a string.
+        ///
+        /// If not specified, the absence of a namespace is assumed.
+        pub ancestor_namespace: Option<XmlNamespace<'static>>,
     }
 
     impl WithExpandedName for AttributeDeclaredOpts {
@@ -653,22 +1041,26 @@ pub mod fields {
 
     impl AttributeOpts {
         pub fn default_or_else(&self) -> Option<Expr> {
-            let (default, default_with, optional) = match self {
+            let (default, default_with, default_from, optional) = match self {
                 AttributeOpts::Deferred(AttributeDeferredOpts {
                     default,
                     default_with,
+                    default_from,
                     optional,
                     ..
-                }) => (default, default_with, optional),
+                }) => (default, default_with, default_from, optional),
                 AttributeOpts::Declared(AttributeDeclaredOpts {
                     default,
                     default_with,
+                    default_from,
                     optional,
                     ..
-                }) => (default, default_with, optional),
+                }) => (default, default_with, default_from, optional),
             };
 
-            if let Some(default_with) = default_with {
+            if let Some(default_from) = default_from {
+                Some(default_from_expr(default_from))
+            } else if let Some(default_with) = default_with {
                 Some(parse_quote! {
                     #default_with
                 })
@@ -709,6 +1101,28 @@ pub mod fields {
                 }))
         }
 
+        pub fn extendable(&self) -> Extendable {
+            match self {
+                AttributeOpts::Deferred(opts) => opts.extendable,
+                AttributeOpts::Declared(opts) => opts.extendable,
+            }
+        }
+
+        /// The field this field defaults to a clone of if it is not present.
+        pub fn default_from(&self) -> Option<&Ident> {
+            match self {
+                AttributeOpts::Deferred(opts) => opts.default_from.as_ref(),
+                AttributeOpts::Declared(opts) => opts.default_from.as_ref(),
+            }
+        }
+
+        pub fn ancestor(&self) -> Option<&LocalName<'static>> {
+            match self {
+                AttributeOpts::Deferred(_) => None,
+                AttributeOpts::Declared(opts) => opts.from_ancestor.as_ref(),
+            }
+        }
+
         pub fn from_field(field: &syn::Field) -> Result<Option<Self>, DeriveError> {
             let Some(attribute) = field
                 .attrs
@@ -727,6 +1141,8 @@ pub mod fields {
                 #[darling(default)]
                 pub default_with: Option<Path>,
                 #[darling(default)]
+                pub default_from: Option<Ident>,
+                #[darling(default)]
                 pub deferred: bool,
                 #[darling(default)]
                 pub name: Option<LocalName<'static>>,
@@ -742,6 +1158,12 @@ pub mod fields {
                 pub optional: bool,
                 #[darling(default)]
                 pub skip_serializing_if: Option<Path>,
+                #[darling(default)]
+                pub from_ancestor: Option<LocalName<'static>>,
+                #[darling(default)]
+                pub ancestor_namespace: Option<XmlNamespace<'static>>,
+                #[darling(default)]
+                pub extendable: Extendable,
             }
 
             let raw = FieldAttributeRawOpts::from_attributes(&[attribute])
@@ -752,6 +1174,20 @@ pub mod fields {
                 return Ok(None);
             };
 
+            check_default_from(raw.default, &raw.default_with, &raw.default_from)?;
+
+            if raw.from_ancestor.is_none() && raw.ancestor_namespace.is_some() {
+                return Err(DeriveError::custom(
+                    "Cannot specify `ancestor_namespace` without `from_ancestor`",
+                ));
+            }
+
+            if raw.from_ancestor.is_some() && raw.extendable != Extendable::None {
+                return Err(DeriveError::custom(
+                    "Cannot specify `extendable` together with `from_ancestor`",
+                ));
+            }
+
             if raw.deferred {
                 let unallowed_fields = [
                     (raw.name.is_some(), "name"),
@@ -759,6 +1195,7 @@ pub mod fields {
                     (raw.namespace_expr.is_some(), "namespace_expr"),
                     (raw.preferred_prefix.is_some(), "preferred_prefix"),
                     (raw.enforce_prefix.is_some(), "enforce_prefix"),
+                    (raw.from_ancestor.is_some(), "from_ancestor"),
                 ];
                 if let Some((true, field)) =
                     unallowed_fields.iter().find(|(unallowed, _)| *unallowed)
@@ -771,13 +1208,16 @@ pub mod fields {
                 Ok(Some(Self::Deferred(AttributeDeferredOpts {
                     default: raw.default,
                     default_with: raw.default_with,
+                    default_from: raw.default_from,
                     skip_serializing_if: raw.skip_serializing_if,
                     optional: raw.optional,
+                    extendable: raw.extendable,
                 })))
             } else {
                 Ok(Some(Self::Declared(AttributeDeclaredOpts {
                     default: raw.default,
                     default_with: raw.default_with,
+                    default_from: raw.default_from,
                     name: raw.name,
                     namespace: raw.namespace,
                     namespace_expr: raw.namespace_expr,
@@ -785,14 +1225,21 @@ pub mod fields {
                     enforce_prefix: raw.enforce_prefix.unwrap_or(false),
                     skip_serializing_if: raw.skip_serializing_if,
                     optional: raw.optional,
+                    extendable: raw.extendable,
+                    from_ancestor: raw.from_ancestor,
+                    ancestor_namespace: raw.ancestor_namespace,
                 })))
             }
         }
     }
 
-    #[derive(FromAttributes, Clone)]
+    #[derive(FromAttributes, Clone, Default)]
     #[darling(attributes(xgroup))]
-    pub struct GroupOpts {}
+    pub struct GroupOpts {
+        /// The position of the children of the group among the children of the element. See [`ChildOpts::serialize_order`].
+        #[darling(default)]
+        pub serialize_order: Option<i32>,
+    }
 
     impl GroupOpts {
         pub fn from_field(field: &syn::Field) -> Result<Option<Self>, DeriveError> {
@@ -818,6 +1265,15 @@ pub mod fields {
     }
 
     impl FieldOpts {
+        /// The field this field defaults to a clone of if it is not present.
+        pub fn default_from(&self) -> Option<&Ident> {
+            match self {
+                FieldOpts::Value(opts) => opts.default_from(),
+                FieldOpts::Attribute(opts) => opts.default_from(),
+                FieldOpts::Group(_) => None,
+            }
+        }
+
         pub fn value_group(self) -> Option<FieldValueGroupOpts> {
             match self {
                 FieldOpts::Value(child_opts) => Some(FieldValueGroupOpts::Value(child_opts)),
@@ -859,11 +1315,160 @@ pub mod fields {
         Group(GroupOpts),
     }
 
+    impl FieldValueGroupOpts {
+        /// The field this field defaults to a clone of if it is not present.
+        pub fn default_from(&self) -> Option<&Ident> {
+            match self {
+                FieldValueGroupOpts::Value(opts) => opts.default_from(),
+                FieldValueGroupOpts::Group(_) => None,
+            }
+        }
+
+        pub fn serialize_order(&self) -> i32 {
+            match self {
+                FieldValueGroupOpts::Value(opts) => opts.serialize_order(),
+                FieldValueGroupOpts::Group(GroupOpts { serialize_order }) => {
+                    serialize_order.unwrap_or_default()
+                }
+            }
+        }
+    }
+
+    /// The default of a field with `default_from`, which clones the value of the other field from the local it is bound to before the value is constructed.
+    fn default_from_expr(default_from: &Ident) -> Expr {
+        let local = crate::common::field_value_local(default_from);
+        parse_quote! {
+            || ::core::clone::Clone::clone(&#local)
+        }
+    }
+
+    /// Checks that `default_from` is not combined with the other ways of providing a default value.
+    fn check_default_from(
+        default: bool,
+        default_with: &Option<Path>,
+        default_from: &Option<Ident>,
+    ) -> Result<(), DeriveError> {
+        match (default_from, default, default_with) {
+            (Some(_), true, _) => Err(DeriveError::custom(
+                "Cannot specify `default_from` together with `default`",
+            )),
+            (Some(_), _, Some(_)) => Err(DeriveError::custom(
+                "Cannot specify `default_from` together with `default_with`",
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// The attributes that decide how a field is serialized and deserialized, of which a field can have at most one.
+    const FIELD_ATTRIBUTES: [&str; 4] = ["xvalue", "xelement", "xattribute", "xgroup"];
+
+    /// Rejects fields of type `Vec<u8>`, which would be written as a sequence of numbers that can't be read back.
+    fn check_not_byte_vec(ty: &syn::Type) -> Result<(), DeriveError> {
+        let syn::Type::Path(syn::TypePath { qself: None, path }) = ty else {
+            return Ok(());
+        };
+        let Some(segment) = path.segments.last() else {
+            return Ok(());
+        };
+        let syn::PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+            return Ok(());
+        };
+
+        let is_byte_vec = segment.ident == "Vec"
+            && arguments.args.len() == 1
+            && matches!(
+                arguments.args.first(),
+                Some(syn::GenericArgument::Type(syn::Type::Path(argument)))
+                    if argument.qself.is_none() && argument.path.is_ident("u8")
+            );
+
+        if !is_byte_vec {
+            return Ok(());
+        }
+
+        Err(DeriveError::custom_with_span(
+            "`Vec<u8>` has no single representation in XML. Use `xmlity::types::bytes::Bytes` to write the bytes as base64 text, or `xmlity::types::bytes::ByteSeq` to write them as a list of numbers.",
+            syn::spanned::Spanned::span(ty),
+        ))
+    }
+
+    /// Checks that a field has at most one of the [`FIELD_ATTRIBUTES`], pointing to the first one that conflicts if not.
+    fn check_single_field_attribute(field: &syn::Field) -> Result<(), DeriveError> {
+        let mut field_attributes = field.attrs.iter().filter_map(|attr| {
+            FIELD_ATTRIBUTES
+                .iter()
+                .find(|name| attr.path().is_ident(name))
+                .map(|name| (*name, attr))
+        });
+
+        let Some((first, _)) = field_attributes.next() else {
+            return Ok(());
+        };
+        let Some((second, second_attr)) = field_attributes.next() else {
+            return Ok(());
+        };
+
+        let message = if first == second {
+            format!("Duplicate `#[{second}]` attribute. A field can only have one `#[{first}]` attribute.")
+        } else {
+            format!("Cannot have both `#[{first}]` and `#[{second}]` on the same field. A field can only have one of `#[xvalue]`, `#[xelement]`, `#[xattribute]` and `#[xgroup]`.")
+        };
+
+        Err(DeriveError::custom_with_span(
+            message,
+            syn::spanned::Spanned::span(second_attr.path()),
+        ))
+    }
+
     impl FieldOpts {
-        pub fn from_field(field: &syn::Field) -> Result<Self, DeriveError> {
-            let element = ChildOpts::from_field(field)?;
-            let attribute = AttributeOpts::from_field(field)?;
+        /// Parses the options of a field.
+        ///
+        /// `uses_with` is set when the record is serialized and deserialized by its own functions, see [`RootValueOpts::parse_uses_with`](super::roots::RootValueOpts::parse_uses_with), in which case the type of the field is not checked.
+        pub fn from_field(
+            field: &syn::Field,
+            xmlns: &XmlnsDeclarations,
+            rename_all: Option<RenameRule>,
+            uses_with: bool,
+        ) -> Result<Self, DeriveError> {
+            check_single_field_attribute(field)?;
+            if !uses_with {
+                check_not_byte_vec(&field.ty)?;
+            }
+
+            let mut element = ChildOpts::from_field(field)?;
+            let mut attribute = AttributeOpts::from_field(field)?;
             let group = GroupOpts::from_field(field)?;
+
+            let renamed = rename_all.zip(field.ident.as_ref()).map(|(rule, ident)| {
+                LocalName(Cow::Owned(rule.apply_to_field(&ident.unraw().to_string())))
+            });
+            if let Some(renamed) = renamed {
+                match (&mut element, &mut attribute) {
+                    (Some(ChildOpts::Element(ElementOpts { name, .. })), _)
+                    | (_, Some(AttributeOpts::Declared(AttributeDeclaredOpts { name, .. }))) => {
+                        name.get_or_insert(renamed);
+                    }
+                    _ => {}
+                }
+            }
+
+            if let Some(ChildOpts::Element(opts)) = &mut element {
+                xmlns.resolve(
+                    &mut opts.name,
+                    &mut opts.namespace,
+                    &opts.namespace_expr,
+                    &mut opts.preferred_prefix,
+                )?;
+            }
+            if let Some(AttributeOpts::Declared(opts)) = &mut attribute {
+                xmlns.resolve(
+                    &mut opts.name,
+                    &mut opts.namespace,
+                    &opts.namespace_expr,
+                    &mut opts.preferred_prefix,
+                )?;
+            }
+
             Ok(match (element, attribute, group) {
                 (Some(element), None, None) => Self::Value(element),
                 (None, Some(attribute), None) => Self::Attribute(attribute),
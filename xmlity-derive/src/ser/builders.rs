@@ -43,8 +43,10 @@ impl<T: SerializeBuilder> SerializeBuilderExt for T {
 
         let non_bound_generics = crate::common::non_bound_generics(&generics);
 
+        let where_clause = &generics.where_clause;
+
         Ok(parse_quote! {
-            impl #generics ::xmlity::Serialize for #ident #non_bound_generics {
+            impl #generics ::xmlity::Serialize for #ident #non_bound_generics #where_clause {
                 #serialize_fn
             }
         })
@@ -89,8 +91,10 @@ impl<T: SerializeAttributeBuilder> SerializeAttributeBuilderExt for T {
 
         let non_bound_generics = non_bound_generics(&generics);
 
+        let where_clause = &generics.where_clause;
+
         Ok(parse_quote! {
-            impl #generics ::xmlity::SerializeAttribute for #ident #non_bound_generics {
+            impl #generics ::xmlity::SerializeAttribute for #ident #non_bound_generics #where_clause {
                 #serialize_attribute_fn
             }
         })
@@ -157,8 +161,10 @@ impl<T: SerializationGroupBuilder> SerializationGroupBuilderExt for T {
         let serialize_attributes_fn = self.serialize_attributes_fn()?;
         let serialize_children_fn = self.serialize_children_fn()?;
 
+        let where_clause = &generics.where_clause;
+
         Ok(parse_quote! {
-        impl #generics ::xmlity::ser::SerializationGroup for #ident #non_bound_generics {
+        impl #generics ::xmlity::ser::SerializationGroup for #ident #non_bound_generics #where_clause {
             #serialize_attributes_fn
 
             #serialize_children_fn
@@ -4,10 +4,13 @@ use syn::{parse_quote, Expr, Ident, Item, Stmt};
 use crate::{
     common::FieldIdent,
     options::{
-        records::fields::{
-            AttributeOpts, ChildOpts, FieldAttributeGroupOpts, FieldOpts, FieldValueGroupOpts,
+        records::{
+            fields::{
+                AttributeOpts, ChildOpts, FieldAttributeGroupOpts, FieldOpts, FieldValueGroupOpts,
+            },
+            roots::{RootElementOpts, RootValueOpts},
         },
-        FieldWithOpts, WithExpandedNameExt,
+        FieldWithOpts, WithExpandedNameExt, XmlnsDeclarations,
     },
     DeriveError, DeriveResult,
 };
@@ -21,6 +24,7 @@ use super::{
 
 pub fn attribute_field_serializer(
     access_ident: impl ToTokens,
+    generics: &syn::Generics,
     field_ident: &FieldIdent,
     item_type: &syn::Type,
     field_ident_to_expr: impl Fn(&FieldIdent) -> syn::Expr,
@@ -28,6 +32,13 @@ pub fn attribute_field_serializer(
 ) -> DeriveResult<proc_macro2::TokenStream> {
     let value_expr = field_ident_to_expr(field_ident);
 
+    // Attributes read from an ancestor belong to that ancestor, which writes them itself.
+    if opts.ancestor().is_some() {
+        return Ok(quote! {
+            let _ = #value_expr;
+        });
+    }
+
     let skip_serializing_if_expr = opts.skip_serializing_if(&value_expr);
 
     let (prefix, serialize_expr) = match opts {
@@ -36,7 +47,7 @@ pub fn attribute_field_serializer(
 
             let wrapper = SimpleSerializeAttributeBuilder {
                 ident: &wrapper_ident,
-                generics: &syn::Generics::default(),
+                generics,
                 expanded_name: opts
                     .expanded_name(&field_ident.to_named_ident().to_string())
                     .into_owned(),
@@ -84,6 +95,7 @@ pub fn attribute_field_serializer(
 
 pub fn attribute_group_fields_serializer(
     access_ident: impl ToTokens,
+    generics: &syn::Generics,
     fields: impl IntoIterator<Item = FieldWithOpts<FieldIdent, FieldAttributeGroupOpts>>,
     field_ident_to_expr: impl Fn(&FieldIdent) -> syn::Expr,
 ) -> DeriveResult<proc_macro2::TokenStream> {
@@ -96,6 +108,7 @@ pub fn attribute_group_fields_serializer(
             FieldAttributeGroupOpts::Attribute(opts) => {
                 attribute_field_serializer(
                     &access_ident,
+                    generics,
                     field_ident,
                     &var_field.field_type,
                     &field_ident_to_expr,
@@ -119,6 +132,7 @@ pub fn attribute_group_fields_serializer(
 
 pub fn element_field_serializer(
     access_ident: impl ToTokens,
+    generics: &syn::Generics,
     field_ident: &FieldIdent,
     item_type: &syn::Type,
     field_ident_to_expr: impl Fn(&FieldIdent) -> syn::Expr,
@@ -131,7 +145,11 @@ pub fn element_field_serializer(
             let skip_serializing_if_expr =
                 value_opts.skip_serializing_if::<Expr>(parse_quote!(&#value_expr));
 
-            (Vec::new(), value_expr, skip_serializing_if_expr)
+            let serialize_expr = value_opts
+                .wrapped_serialize_expr(&value_expr)
+                .unwrap_or(value_expr);
+
+            (Vec::new(), serialize_expr, skip_serializing_if_expr)
         }
         ChildOpts::Element(opts) => {
             let skip_serializing_if_expr =
@@ -141,6 +159,7 @@ pub fn element_field_serializer(
 
             let wrapper = SingleChildSerializeElementBuilder {
                 ident: &wrapper_ident,
+                generics,
                 expanded_name: opts
                     .expanded_name(field_ident.to_named_ident().to_string().as_str())
                     .into_owned(),
@@ -189,9 +208,13 @@ pub fn element_field_serializer(
 
 pub fn element_group_fields_serializer(
     access_ident: impl ToTokens,
+    generics: &syn::Generics,
     fields: impl IntoIterator<Item = FieldWithOpts<FieldIdent, FieldValueGroupOpts>>,
     field_ident_to_expr: impl Fn(&FieldIdent) -> syn::Expr,
 ) -> DeriveResult<proc_macro2::TokenStream> {
+    let mut fields = fields.into_iter().collect::<Vec<_>>();
+    fields.sort_by_key(|field| field.options.serialize_order());
+
     let fields = fields
     .into_iter()
     .map::<DeriveResult<_>, _>(|var_field| {
@@ -199,7 +222,7 @@ pub fn element_group_fields_serializer(
 
         match var_field.options {
             FieldValueGroupOpts::Value(opts) => {
-                element_field_serializer(&access_ident, field_ident, &var_field.field_type, |a| field_ident_to_expr(a), opts)
+                element_field_serializer(&access_ident, generics, field_ident, &var_field.field_type, |a| field_ident_to_expr(a), opts)
             },
             FieldValueGroupOpts::Group(_) => {
                 let ser_value = field_ident_to_expr(field_ident);
@@ -218,14 +241,19 @@ pub fn element_group_fields_serializer(
 
 pub fn element_fields_serializer(
     access_ident: impl ToTokens,
+    generics: &syn::Generics,
     fields: impl IntoIterator<Item = FieldWithOpts<FieldIdent, ChildOpts>>,
     field_ident_to_expr: impl Fn(&FieldIdent) -> syn::Expr,
 ) -> DeriveResult<proc_macro2::TokenStream> {
+    let mut fields = fields.into_iter().collect::<Vec<_>>();
+    fields.sort_by_key(|field| field.options.serialize_order());
+
     let fields = fields
         .into_iter()
         .map(|var_field| {
             element_field_serializer(
                 &access_ident,
+                generics,
                 &var_field.field_ident,
                 &var_field.field_type,
                 |a| field_ident_to_expr(a),
@@ -245,6 +273,9 @@ pub fn fields(
     let syn::Data::Struct(syn::DataStruct { fields, .. }) = &ast.data else {
         unreachable!()
     };
+    let xmlns = XmlnsDeclarations::parse(&ast.attrs)?;
+    let uses_with = RootValueOpts::parse_uses_with(&ast.attrs)?;
+    let rename_all = RootElementOpts::parse_rename_all(&ast.attrs)?;
 
     match fields {
         syn::Fields::Named(fields) => fields
@@ -253,7 +284,7 @@ pub fn fields(
             .map(|f| {
                 Ok(FieldWithOpts {
                     field_ident: FieldIdent::Named(f.ident.clone().expect("Named struct")),
-                    options: FieldOpts::from_field(f)?,
+                    options: FieldOpts::from_field(f, &xmlns, rename_all, uses_with)?,
                     field_type: f.ty.clone(),
                 })
             })
@@ -265,7 +296,7 @@ pub fn fields(
             .map(|(i, f)| {
                 Ok(FieldWithOpts {
                     field_ident: FieldIdent::Indexed(syn::Index::from(i)),
-                    options: FieldOpts::from_field(f)?,
+                    options: FieldOpts::from_field(f, &xmlns, rename_all, uses_with)?,
                     field_type: f.ty.clone(),
                 })
             })
@@ -1,16 +1,28 @@
 use std::borrow::Cow;
 
+use proc_macro2::Span;
 use quote::{quote, ToTokens};
-use syn::{parse_quote, DeriveInput, Generics, Ident, Stmt};
-
-use crate::{options::records::roots::RootGroupOpts, DeriveError, DeriveMacro};
+use syn::{parse_quote, Arm, DeriveInput, Generics, Ident, Stmt};
+
+use crate::{
+    meta::MetaForm,
+    options::{
+        enums::groups::{GroupVariant, GroupVariantKind},
+        records::roots::RootGroupOpts,
+        DeriveDirection,
+    },
+    DeriveError, DeriveMacro,
+};
 
 use super::{
-    builders::{SerializationGroupBuilder, SerializationGroupBuilderExt},
+    builders::{
+        SerializationGroupBuilder, SerializationGroupBuilderExt, SerializeAttributeBuilderExt,
+    },
     common::{
         attribute_group_fields, attribute_group_fields_serializer, element_group_fields,
         element_group_fields_serializer, fields,
     },
+    serialize_attribute::SimpleSerializeAttributeBuilder,
 };
 
 #[allow(unused)]
@@ -32,6 +44,7 @@ impl SerializationGroupBuilder for DeriveSerializationGroupStruct<'_> {
     ) -> Result<Vec<Stmt>, DeriveError> {
         let serialize_attributes_implementation = attribute_group_fields_serializer(
             quote! { #element_access_ident},
+            &self.ast.generics,
             attribute_group_fields(fields(self.ast)?)?,
             |field_ident| parse_quote!(&self.#field_ident),
         )?;
@@ -48,6 +61,7 @@ impl SerializationGroupBuilder for DeriveSerializationGroupStruct<'_> {
     ) -> Result<Vec<Stmt>, DeriveError> {
         let serialize_children_implementation = element_group_fields_serializer(
             quote! { #children_access_ident},
+            &self.ast.generics,
             element_group_fields(fields(self.ast)?)?,
             |field_ident| parse_quote!(&self.#field_ident),
         )?;
@@ -67,6 +81,115 @@ impl SerializationGroupBuilder for DeriveSerializationGroupStruct<'_> {
     }
 }
 
+/// Serializes an enum as a group, where each variant is either an attribute or a child of the element.
+pub struct DeriveSerializationGroupEnum<'a> {
+    ast: &'a syn::DeriveInput,
+    variants: Vec<GroupVariant<'a>>,
+}
+
+impl<'a> DeriveSerializationGroupEnum<'a> {
+    fn new(ast: &'a syn::DeriveInput) -> Result<Self, DeriveError> {
+        Ok(Self {
+            ast,
+            variants: GroupVariant::parse_all(ast)?,
+        })
+    }
+
+    fn arms(
+        &self,
+        arm: impl Fn(&GroupVariant<'_>, &Ident) -> Result<Option<Vec<Stmt>>, DeriveError>,
+    ) -> Result<Vec<Arm>, DeriveError> {
+        let ident = &self.ast.ident;
+        let value_ident = Ident::new("__v", Span::mixed_site());
+
+        self.variants
+            .iter()
+            .map(|variant| {
+                let variant_ident = &variant.variant.ident;
+                let member = &variant.member;
+                Ok(match arm(variant, &value_ident)? {
+                    Some(stmts) => parse_quote! {
+                        #ident::#variant_ident { #member: #value_ident } => {
+                            #(#stmts)*
+                        }
+                    },
+                    None => parse_quote! {
+                        #ident::#variant_ident { .. } => {}
+                    },
+                })
+            })
+            .collect()
+    }
+}
+
+impl SerializationGroupBuilder for DeriveSerializationGroupEnum<'_> {
+    fn serialize_attributes_fn_body(
+        &self,
+        element_access_ident: &Ident,
+    ) -> Result<Vec<Stmt>, DeriveError> {
+        let arms = self.arms(|variant, value_ident| {
+            let Some((opts, expanded_name)) = variant.attribute_name() else {
+                return Ok(None);
+            };
+
+            let wrapper_ident = Ident::new("__W", Span::call_site());
+            let wrapper = SimpleSerializeAttributeBuilder {
+                ident: &wrapper_ident,
+                generics: &self.ast.generics,
+                expanded_name,
+                preferred_prefix: opts.preferred_prefix.clone(),
+                enforce_prefix: opts.enforce_prefix,
+                item_type: variant.field_type,
+            };
+
+            let definition = wrapper.struct_definition();
+            let trait_impl = wrapper.serialize_attribute_trait_impl()?;
+            let serialize_expr = wrapper.value_expression(&parse_quote!(#value_ident));
+
+            Ok(Some(parse_quote! {
+                #definition
+                #trait_impl
+                ::xmlity::ser::SerializeAttributes::serialize_attribute(#element_access_ident, &#serialize_expr)?;
+            }))
+        })?;
+
+        Ok(parse_quote! {
+            match self {
+                #(#arms)*
+            }
+            ::core::result::Result::Ok(())
+        })
+    }
+
+    fn serialize_children_fn_body(
+        &self,
+        children_access_ident: &Ident,
+    ) -> Result<Vec<Stmt>, DeriveError> {
+        let arms = self.arms(|variant, value_ident| {
+            Ok(matches!(variant.kind, GroupVariantKind::Value).then(|| {
+                parse_quote! {
+                    ::xmlity::ser::SerializeSeq::serialize_element(#children_access_ident, #value_ident)?;
+                }
+            }))
+        })?;
+
+        Ok(parse_quote! {
+            match self {
+                #(#arms)*
+            }
+            ::core::result::Result::Ok(())
+        })
+    }
+
+    fn ident(&self) -> Cow<'_, Ident> {
+        Cow::Borrowed(&self.ast.ident)
+    }
+
+    fn generics(&self) -> Cow<'_, Generics> {
+        Cow::Borrowed(&self.ast.generics)
+    }
+}
+
 enum SerializationGroupOption {
     Group(RootGroupOpts),
 }
@@ -82,6 +205,12 @@ impl SerializationGroupOption {
 pub struct DeriveSerializationGroup;
 
 impl DeriveMacro for DeriveSerializationGroup {
+    const DIRECTION: DeriveDirection = DeriveDirection::Serialize;
+
+    fn meta_form(_ast: &syn::DeriveInput) -> Option<MetaForm> {
+        Some(MetaForm::Group)
+    }
+
     fn input_to_derive(ast: &DeriveInput) -> Result<proc_macro2::TokenStream, DeriveError> {
         let SerializationGroupOption::Group(opts) = SerializationGroupOption::parse(ast)?;
 
@@ -89,9 +218,9 @@ impl DeriveMacro for DeriveSerializationGroup {
             syn::Data::Struct(_) => DeriveSerializationGroupStruct::new(ast, &opts)
                 .serialization_group_trait_impl()
                 .map(|a| a.to_token_stream()),
-            syn::Data::Enum(_) => Err(DeriveError::custom(
-                "Enums are not supported for serialization groups.",
-            )),
+            syn::Data::Enum(_) => DeriveSerializationGroupEnum::new(ast)?
+                .serialization_group_trait_impl()
+                .map(|a| a.to_token_stream()),
             syn::Data::Union(_) => Err(DeriveError::custom(
                 "Unions are not supported for serialization groups.",
             )),
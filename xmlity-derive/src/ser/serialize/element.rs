@@ -8,11 +8,12 @@ use crate::common::value_deconstructor;
 use crate::common::Prefix;
 use crate::common::RecordInput;
 use crate::common::StructTypeWithFields;
+use crate::common::{generics_marker_field, nested_generics, nested_generics_turbofish};
 use crate::options::records;
 use crate::options::records::fields::GroupOpts;
 use crate::options::records::fields::{ChildOpts, FieldOpts, ValueOpts};
 use crate::options::WithExpandedNameExt;
-use crate::options::{Extendable, FieldWithOpts};
+use crate::options::{Extendable, FieldWithOpts, XmlnsDeclarations};
 use crate::ser::builders::SerializeBuilder;
 use crate::ser::common::attribute_group_fields;
 use crate::ser::common::attribute_group_fields_serializer;
@@ -26,6 +27,7 @@ use crate::{
 #[allow(clippy::type_complexity)]
 pub struct SingleChildSerializeElementBuilder<'a> {
     pub ident: &'a syn::Ident,
+    pub generics: &'a syn::Generics,
     pub expanded_name: ExpandedName<'static>,
     pub preferred_prefix: Option<Prefix<'static>>,
     pub enforce_prefix: bool,
@@ -51,10 +53,20 @@ impl SingleChildSerializeElementBuilder<'_> {
         let value_access_ident = self.value_access_ident();
         let generics = self.generics();
         let lifetime = self.value_lifetime();
+        let marker = generics_marker_field(self.generics).map(
+            |FieldWithOpts {
+                 field_ident,
+                 field_type,
+                 ..
+             }| quote!(#field_ident: #field_type,),
+        );
+
+        let where_clause = &generics.where_clause;
 
         parse_quote! {
-            struct #ident #generics {
+            struct #ident #generics #where_clause {
                 #value_access_ident: &#lifetime #item_type,
+                #marker
             }
         }
     }
@@ -62,9 +74,14 @@ impl SingleChildSerializeElementBuilder<'_> {
     pub fn value_expression(&self, value_expr: &Expr) -> syn::Expr {
         let Self { ident, .. } = self;
         let value_access_ident = self.value_access_ident();
+        let turbofish = nested_generics_turbofish(self.generics, true);
+        let marker = generics_marker_field(self.generics).map(
+            |FieldWithOpts { field_ident, .. }| quote!(#field_ident: ::core::marker::PhantomData,),
+        );
         parse_quote! {
-            #ident {
+            #ident #turbofish {
                 #value_access_ident: #value_expr,
+                #marker
             }
         }
     }
@@ -85,20 +102,32 @@ impl SerializeBuilder for SingleChildSerializeElementBuilder<'_> {
             generics: Cow::Owned(parse_quote!()),
             wrapper_function: std::convert::identity,
             record_path: Cow::Owned(parse_quote!(self)),
-            fields: StructTypeWithFields::Named(vec![FieldWithOpts {
-                field_ident: self.value_access_ident(),
-                field_type: self.item_type.clone(),
-                options: if self.group {
-                    FieldOpts::Group(GroupOpts {})
-                } else {
-                    FieldOpts::Value(ChildOpts::Value(ValueOpts {
-                        default: false,
-                        default_with: None,
-                        extendable: Extendable::None,
-                        skip_serializing_if: self.skip_serializing_if.clone(),
-                    }))
-                },
-            }]),
+            fields: StructTypeWithFields::Named(
+                std::iter::once(FieldWithOpts {
+                    field_ident: self.value_access_ident(),
+                    field_type: self.item_type.clone(),
+                    options: if self.group {
+                        FieldOpts::Group(GroupOpts::default())
+                    } else {
+                        FieldOpts::Value(ChildOpts::Value(ValueOpts {
+                            default: false,
+                            default_with: None,
+                            default_from: None,
+                            extendable: Extendable::None,
+                            skip_serializing_if: self.skip_serializing_if.clone(),
+                            raw_text: false,
+                            serialize_order: None,
+                            bool_format: None,
+                            radix_prefixes: false,
+                            int_format: None,
+                            key_attribute: None,
+                            duplicate_keys: None,
+                        }))
+                    },
+                })
+                .chain(generics_marker_field(self.generics))
+                .collect(),
+            ),
             sub_path_ident: None,
             fallable_deconstruction: false,
         };
@@ -108,6 +137,9 @@ impl SerializeBuilder for SingleChildSerializeElementBuilder<'_> {
             expanded_name: self.expanded_name.clone(),
             preferred_prefix: self.preferred_prefix.clone(),
             enforce_prefix: self.enforce_prefix,
+            xmlns: XmlnsDeclarations::default(),
+            xsi_schema_location: None,
+            no_namespace_schema_location: None,
         };
 
         builder.serialize_fn_body(serializer_access, serializer_type)
@@ -118,9 +150,7 @@ impl SerializeBuilder for SingleChildSerializeElementBuilder<'_> {
     }
 
     fn generics(&self) -> Cow<'_, syn::Generics> {
-        let lifetime = self.value_lifetime();
-        let generics = parse_quote!(<#lifetime>);
-        Cow::Owned(generics)
+        Cow::Owned(nested_generics(self.generics, Some(&self.value_lifetime())))
     }
 }
 
@@ -129,6 +159,9 @@ pub struct RecordSerializeElementBuilder<'a, T: Fn(syn::Expr) -> syn::Expr> {
     pub expanded_name: ExpandedName<'static>,
     pub preferred_prefix: Option<Prefix<'static>>,
     pub enforce_prefix: bool,
+    pub xmlns: XmlnsDeclarations,
+    pub xsi_schema_location: Option<String>,
+    pub no_namespace_schema_location: Option<String>,
     pub input: &'a RecordInput<'a, T>,
 }
 
@@ -141,6 +174,9 @@ impl<'a, T: Fn(syn::Expr) -> syn::Expr> RecordSerializeElementBuilder<'a, T> {
             input,
             preferred_prefix: opts.preferred_prefix.clone(),
             enforce_prefix: opts.enforce_prefix,
+            xmlns: opts.xmlns.clone(),
+            xsi_schema_location: opts.xsi_schema_location.clone(),
+            no_namespace_schema_location: opts.no_namespace_schema_location.clone(),
             expanded_name,
         }
     }
@@ -157,6 +193,9 @@ impl<T: Fn(syn::Expr) -> syn::Expr> SerializeBuilder for RecordSerializeElementB
             enforce_prefix,
             expanded_name,
             preferred_prefix,
+            xmlns,
+            xsi_schema_location,
+            no_namespace_schema_location,
             ..
         } = self;
 
@@ -192,6 +231,7 @@ impl<T: Fn(syn::Expr) -> syn::Expr> SerializeBuilder for RecordSerializeElementB
 
         let attribute_fields = attribute_group_fields_serializer(
             quote! {&mut #ser_attributes_ident},
+            &self.input.generics,
             attribute_fields,
             |field_ident| {
                 let ident_name = field_ident.to_named_ident();
@@ -206,6 +246,7 @@ impl<T: Fn(syn::Expr) -> syn::Expr> SerializeBuilder for RecordSerializeElementB
         } else {
             let element_fields = element_group_fields_serializer(
                 quote! {&mut #ser_children_ident},
+                &self.input.generics,
                 element_fields,
                 |field_ident| {
                     let ident_name = field_ident.to_named_ident();
@@ -228,15 +269,33 @@ impl<T: Fn(syn::Expr) -> syn::Expr> SerializeBuilder for RecordSerializeElementB
         )).map::<Stmt, _>(|enforce_prefix: syn::Expr| parse_quote! {
               ::xmlity::ser::SerializeElement::include_prefix(&mut #ser_element_ident, #enforce_prefix)?;
           });
+        let namespace_declarations = xmlns.0.iter().map::<Stmt, _>(|(prefix, namespace)| parse_quote! {
+              ::xmlity::ser::SerializeElement::declare_namespace(&mut #ser_element_ident, #prefix, #namespace)?;
+          });
+
+        let schema_locations = [
+            ("schemaLocation", xsi_schema_location),
+            ("noNamespaceSchemaLocation", no_namespace_schema_location),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| value.as_ref().map(|value| (name, value)))
+        .map::<Stmt, _>(|(name, value)| parse_quote! {
+              ::xmlity::ser::SerializeAttributes::serialize_attribute(&mut #ser_attributes_ident, &::xmlity::__private::XsiAttribute {
+                  name: { const __LOCAL_NAME: &::xmlity::LocalName = ::xmlity::__private::local_name(#name); __LOCAL_NAME },
+                  value: #value,
+              })?;
+          });
 
         Ok(parse_quote! {
             let #xml_name_temp_ident = #expanded_name;
             let mut #ser_element_ident = ::xmlity::Serializer::serialize_element(#serializer_access, &#xml_name_temp_ident)?;
             #(#value_deconstructor)*
+            #(#namespace_declarations)*
             #preferred_prefix_setting
             #enforce_prefix_setting
             let mut #ser_attributes_ident = ::xmlity::ser::SerializeElement::serialize_attributes(#ser_element_ident)?;
             #attribute_fields
+            #(#schema_locations)*
             #element_end
         })
     }
@@ -5,13 +5,16 @@ use std::borrow::Cow;
 pub use none::{DeriveEnum, RecordSerializeValueBuilder};
 mod element;
 pub use element::{RecordSerializeElementBuilder, SingleChildSerializeElementBuilder};
+mod pi;
+pub use pi::RecordSerializePiBuilder;
 mod variant;
 
 use quote::ToTokens;
 use syn::{DeriveInput, Ident};
 
 use crate::common::{self, RecordInput};
-use crate::options::{enums, records};
+use crate::meta::{self, MetaForm};
+use crate::options::{enums, records, DeriveDirection};
 use crate::{DeriveError, DeriveMacro};
 
 use super::builders::{SerializeBuilder, SerializeBuilderExt};
@@ -46,6 +49,8 @@ impl<T: Fn(syn::Expr) -> syn::Expr> SerializeBuilder for RecordSerializeBuilder<
                 RecordSerializeValueBuilder::new(self.input, Some(opts))
                     .serialize_fn_body(serializer_access, serializer_type)
             }
+            SerializeRootOpts::Pi(opts) => RecordSerializePiBuilder::new(self.input, opts)
+                .serialize_fn_body(serializer_access, serializer_type),
             SerializeRootOpts::None => RecordSerializeValueBuilder::new(self.input, None)
                 .serialize_fn_body(serializer_access, serializer_type),
         }
@@ -63,6 +68,12 @@ impl<T: Fn(syn::Expr) -> syn::Expr> SerializeBuilder for RecordSerializeBuilder<
 pub struct DeriveSerialize;
 
 impl DeriveMacro for DeriveSerialize {
+    const DIRECTION: DeriveDirection = DeriveDirection::Serialize;
+
+    fn meta_form(ast: &syn::DeriveInput) -> Option<MetaForm> {
+        (!meta::is_group(ast) && !meta::is_attribute(ast)).then_some(MetaForm::Item)
+    }
+
     fn input_to_derive(ast: &DeriveInput) -> Result<proc_macro2::TokenStream, DeriveError> {
         match &ast.data {
             syn::Data::Struct(_) => {
@@ -79,6 +90,11 @@ impl DeriveMacro for DeriveSerialize {
                             .serialize_trait_impl()
                             .map(|a| a.to_token_stream())
                     }
+                    records::roots::SerializeRootOpts::Pi(opts) => {
+                        RecordSerializePiBuilder::new(&record, &opts)
+                            .serialize_trait_impl()
+                            .map(|a| a.to_token_stream())
+                    }
                     records::roots::SerializeRootOpts::None => {
                         RecordSerializeValueBuilder::new(&record, None)
                             .serialize_trait_impl()
@@ -48,6 +48,25 @@ impl<T: Fn(syn::Expr) -> syn::Expr> SerializeBuilder for RecordSerializeValueBui
             });
         }
 
+        let record_path = self.input.record_path.as_ref();
+
+        if self.options.is_some_and(|opts| opts.transparent) {
+            let (field, _) = self.input.transparent_field()?;
+            let field_ident = field.field_ident.to_named_ident();
+
+            let value_deconstructor = value_deconstructor(
+                self.input.constructor_path.as_ref(),
+                &parse_quote!(&#record_path),
+                &self.input.fields,
+                self.input.fallable_deconstruction,
+            );
+
+            return Ok(parse_quote! {
+                #(#value_deconstructor)*
+                ::xmlity::Serialize::serialize(#field_ident, #serializer_access)
+            });
+        }
+
         let seq_access_ident = Ident::new("__seq_access", proc_macro2::Span::call_site());
 
         let fields: Vec<_> = match (&self.input.fields, &self.options) {
@@ -61,12 +80,8 @@ impl<T: Fn(syn::Expr) -> syn::Expr> SerializeBuilder for RecordSerializeValueBui
                 .cloned()
                 .map(|f| f.map_ident(FieldIdent::Indexed))
                 .collect(),
-            (
-                common::StructTypeWithFields::Unit,
-                Some(RecordRootValueOpts {
-                    value: Some(value), ..
-                }),
-            ) => {
+            (common::StructTypeWithFields::Unit, Some(opts)) if opts.value.is_some() => {
+                let value = opts.value_expr();
                 return Ok(parse_quote! {
                     ::xmlity::Serializer::serialize_text(#serializer_access, #value)
                 });
@@ -78,8 +93,6 @@ impl<T: Fn(syn::Expr) -> syn::Expr> SerializeBuilder for RecordSerializeValueBui
             }
         };
 
-        let record_path = self.input.record_path.as_ref();
-
         let value_deconstructor = value_deconstructor(
             self.input.constructor_path.as_ref(),
             &parse_quote!(&#record_path),
@@ -89,6 +102,7 @@ impl<T: Fn(syn::Expr) -> syn::Expr> SerializeBuilder for RecordSerializeValueBui
 
         let value_fields = element_fields_serializer(
             quote! {&mut #seq_access_ident},
+            &self.input.generics,
             element_fields(fields)?,
             |field_ident| {
                 let ident_name = field_ident.to_named_ident();
@@ -169,30 +183,34 @@ impl SerializeBuilder for DeriveEnum<'_> {
                 )?;
 
                 let mut variant_opts = records::roots::SerializeRootOpts::parse(&variant.attrs)?;
+                if let SerializeRootOpts::Value(records::roots::RootValueOpts {
+                    transparent: true,
+                    ..
+                }) = &variant_opts
+                {
+                    return Err(DeriveError::custom(
+                        "transparent can only be used on structs, not enum variants",
+                    ));
+                }
                 if let SerializeRootOpts::Value(records::roots::RootValueOpts {
                     value: value @ None,
+                    value_with,
                     ..
                 }) = &mut variant_opts
                 {
-                    let ident_value = self
-                        .opts
-                        .as_ref()
-                        .map(|a| a.rename_all)
-                        .unwrap_or_default()
-                        .apply_to_variant(&variant.ident.to_string());
+                    let (ident_value, ident_value_with) =
+                        EnumRootVolueOpts::variant_value(self.opts, &variant.ident.to_string());
 
                     *value = Some(ident_value);
+                    *value_with = ident_value_with;
                 }
                 if let SerializeRootOpts::None = variant_opts {
-                    let ident_value = self
-                        .opts
-                        .as_ref()
-                        .map(|a| a.rename_all)
-                        .unwrap_or_default()
-                        .apply_to_variant(&variant.ident.to_string());
+                    let (ident_value, ident_value_with) =
+                        EnumRootVolueOpts::variant_value(self.opts, &variant.ident.to_string());
 
                     variant_opts = SerializeRootOpts::Value(records::roots::RootValueOpts {
                         value: Some(ident_value),
+                        value_with: ident_value_with,
                         ..Default::default()
                     });
                 }
@@ -0,0 +1,120 @@
+use std::borrow::Cow;
+
+use proc_macro2::Span;
+use syn::{parse_quote, Generics, Ident, Stmt};
+
+use crate::{
+    common::{
+        pseudo_attribute_fields, value_deconstructor, PseudoAttributeField, RecordInput,
+        StructTypeWithFields,
+    },
+    options::records::roots::RootPiOpts,
+    ser::builders::SerializeBuilder,
+    DeriveError,
+};
+
+pub struct RecordSerializePiBuilder<'a, T: Fn(syn::Expr) -> syn::Expr> {
+    input: &'a RecordInput<'a, T>,
+    opts: &'a RootPiOpts,
+}
+
+impl<'a, T: Fn(syn::Expr) -> syn::Expr> RecordSerializePiBuilder<'a, T> {
+    pub fn new(input: &'a RecordInput<'a, T>, opts: &'a RootPiOpts) -> Self {
+        Self { input, opts }
+    }
+}
+
+impl<T: Fn(syn::Expr) -> syn::Expr> SerializeBuilder for RecordSerializePiBuilder<'_, T> {
+    fn serialize_fn_body(
+        &self,
+        serializer_access: &Ident,
+        _serializer_type: &syn::Type,
+    ) -> Result<Vec<Stmt>, DeriveError> {
+        let content_ident = Ident::new("__content", Span::mixed_site());
+        let target = &self.opts.target;
+
+        let content: Vec<Stmt> = match &self.input.fields {
+            StructTypeWithFields::Named(fields) => {
+                let pushes = pseudo_attribute_fields(fields)?.into_iter().map::<Stmt, _>(
+                    |PseudoAttributeField {
+                         field_ident,
+                         name,
+                         optional,
+                         skip_serializing_if,
+                         ..
+                     }| {
+                        let push_value = |value: &Ident| -> Stmt {
+                            parse_quote! {
+                                ::xmlity::types::pi::push_pseudo_attribute(&mut #content_ident, #name, &::std::string::ToString::to_string(#value));
+                            }
+                        };
+                        let push: Stmt = if optional {
+                            let value_ident = Ident::new("__v", Span::mixed_site());
+                            let push = push_value(&value_ident);
+                            parse_quote! {
+                                if let ::core::option::Option::Some(#value_ident) = #field_ident {
+                                    #push
+                                }
+                            }
+                        } else {
+                            push_value(&field_ident)
+                        };
+
+                        match skip_serializing_if {
+                            Some(skip_serializing_if) => parse_quote! {
+                                if !#skip_serializing_if(#field_ident) {
+                                    #push
+                                }
+                            },
+                            None => push,
+                        }
+                    },
+                );
+
+                parse_quote! {
+                    let mut #content_ident = ::std::string::String::new();
+                    #(#pushes)*
+                }
+            }
+            StructTypeWithFields::Unnamed(fields) if fields.len() == 1 => {
+                parse_quote! {
+                    let #content_ident = ::std::string::ToString::to_string(__0);
+                }
+            }
+            StructTypeWithFields::Unnamed(fields) => {
+                return Err(DeriveError::custom(format!(
+                    "Expected a single field for processing instruction serialization, found {}",
+                    fields.len()
+                )))
+            }
+            StructTypeWithFields::Unit => {
+                parse_quote! {
+                    let #content_ident = ::std::string::String::new();
+                }
+            }
+        };
+
+        let record_path = self.input.record_path.as_ref();
+
+        let value_deconstructor = value_deconstructor(
+            self.input.constructor_path.as_ref(),
+            &parse_quote!(&#record_path),
+            &self.input.fields,
+            self.input.fallable_deconstruction,
+        );
+
+        Ok(parse_quote! {
+            #(#value_deconstructor)*
+            #(#content)*
+            ::xmlity::Serializer::serialize_pi(#serializer_access, #target.as_bytes(), #content_ident.as_bytes())
+        })
+    }
+
+    fn ident(&self) -> Cow<'_, Ident> {
+        Cow::Borrowed(self.input.impl_for_ident.as_ref())
+    }
+
+    fn generics(&self) -> Cow<'_, Generics> {
+        Cow::Borrowed(self.input.generics.as_ref())
+    }
+}
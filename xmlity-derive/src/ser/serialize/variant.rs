@@ -48,9 +48,11 @@ impl<'a, T: Fn(syn::Expr) -> syn::Expr> SerializeVariantBuilder<'a, T> {
 
         let generics = self.generics_with_serialize_lifetime();
 
+        let where_clause = &generics.where_clause;
+
         parse_quote! {
             #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
-            struct #ident #generics {
+            struct #ident #generics #where_clause {
                 #value_access_ident: &'__xmlity #enum_type,
             }
         }
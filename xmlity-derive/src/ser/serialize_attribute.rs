@@ -5,9 +5,13 @@ use quote::{quote, ToTokens};
 use syn::{parse_quote, Arm, Data, Expr, Lifetime, Stmt};
 use syn::{DeriveInput, Ident};
 
-use crate::common::{ExpandedName, Prefix, StructTypeWithFields};
+use crate::common::{
+    generics_marker_field, nested_generics, nested_generics_turbofish, ExpandedName, Prefix,
+    StructTypeWithFields,
+};
+use crate::meta::MetaForm;
 use crate::options::records::roots::RootAttributeOpts;
-use crate::options::{FieldWithOpts, WithExpandedNameExt};
+use crate::options::{DeriveDirection, FieldWithOpts, WithExpandedNameExt};
 
 use crate::DeriveError;
 use crate::{DeriveMacro, DeriveResult};
@@ -114,10 +118,20 @@ impl SimpleSerializeAttributeBuilder<'_> {
         let value_access_ident = self.value_access_ident();
         let generics = self.generics();
         let lifetime = self.value_lifetime();
+        let marker = generics_marker_field(self.generics).map(
+            |FieldWithOpts {
+                 field_ident,
+                 field_type,
+                 ..
+             }| quote!(#field_ident: #field_type,),
+        );
+
+        let where_clause = &generics.where_clause;
 
         parse_quote! {
-            struct #ident #generics {
+            struct #ident #generics #where_clause {
                 #value_access_ident: &#lifetime #item_type,
+                #marker
             }
         }
     }
@@ -125,9 +139,14 @@ impl SimpleSerializeAttributeBuilder<'_> {
     pub fn value_expression(&self, value_expr: &Expr) -> syn::Expr {
         let Self { ident, .. } = self;
         let value_access_ident = self.value_access_ident();
+        let turbofish = nested_generics_turbofish(self.generics, true);
+        let marker = generics_marker_field(self.generics).map(
+            |FieldWithOpts { field_ident, .. }| quote!(#field_ident: ::core::marker::PhantomData,),
+        );
         parse_quote! {
-            #ident {
+            #ident #turbofish {
                 #value_access_ident: #value_expr,
+                #marker
             }
         }
     }
@@ -161,9 +180,38 @@ impl SerializeAttributeBuilder for SimpleSerializeAttributeBuilder<'_> {
     }
 
     fn generics(&self) -> Cow<'_, syn::Generics> {
-        let lifetime = self.value_lifetime();
-        let generics = parse_quote!(<#lifetime>);
-        Cow::Owned(generics)
+        Cow::Owned(nested_generics(self.generics, Some(&self.value_lifetime())))
+    }
+}
+
+fn serialize_attribute_stmts(
+    serializer_access: &Ident,
+    expanded_name: &ExpandedName<'static>,
+    preferred_prefix: Option<&Prefix<'static>>,
+    enforce_prefix: bool,
+    value_exepr: proc_macro2::TokenStream,
+) -> Vec<Stmt> {
+    let access_ident = Ident::new("__sa", proc_macro2::Span::call_site());
+    let xml_name_temp_ident = Ident::new("__xml_name", proc_macro2::Span::call_site());
+
+    let preferred_prefix_setting = preferred_prefix.as_ref().map::<Stmt, _>(|preferred_prefix| parse_quote! {
+            ::xmlity::ser::SerializeAttributeAccess::preferred_prefix(&mut #access_ident, ::core::option::Option::Some(#preferred_prefix))?;
+        });
+    let enforce_prefix_setting = enforce_prefix.then(|| {
+            parse_quote!(::xmlity::ser::IncludePrefix::WhenNecessaryForPreferredPrefix)
+        }).map::<Stmt, _>(|enforce_prefix: syn::Expr| parse_quote! {
+            ::xmlity::ser::SerializeAttributeAccess::include_prefix(&mut #access_ident, #enforce_prefix)?;
+        });
+
+    parse_quote! {
+        let #xml_name_temp_ident = #expanded_name;
+        let mut #access_ident = ::xmlity::AttributeSerializer::serialize_attribute(
+            &mut #serializer_access,
+            &#xml_name_temp_ident,
+        )?;
+        #preferred_prefix_setting
+        #enforce_prefix_setting
+        ::xmlity::ser::SerializeAttributeAccess::end(#access_ident, #value_exepr)
     }
 }
 
@@ -209,28 +257,13 @@ impl SerializeAttributeBuilder for StructSerializeAttributeBuilder<'_> {
             }
         };
 
-        let access_ident = Ident::new("__sa", proc_macro2::Span::call_site());
-        let xml_name_temp_ident = Ident::new("__xml_name", proc_macro2::Span::call_site());
-
-        let preferred_prefix_setting = preferred_prefix.as_ref().map::<Stmt, _>(|preferred_prefix| parse_quote! {
-            ::xmlity::ser::SerializeAttributeAccess::preferred_prefix(&mut #access_ident, ::core::option::Option::Some(#preferred_prefix))?;
-        });
-        let enforce_prefix_setting = enforce_prefix.then(|| {
-            parse_quote!(::xmlity::ser::IncludePrefix::WhenNecessaryForPreferredPrefix)
-        }).map::<Stmt, _>(|enforce_prefix: syn::Expr| parse_quote! {
-            ::xmlity::ser::SerializeAttributeAccess::include_prefix(&mut #access_ident, #enforce_prefix)?;
-        });
-
-        Ok(parse_quote! {
-            let #xml_name_temp_ident = #expanded_name;
-            let mut #access_ident = ::xmlity::AttributeSerializer::serialize_attribute(
-                &mut #serializer_access,
-                &#xml_name_temp_ident,
-            )?;
-            #preferred_prefix_setting
-            #enforce_prefix_setting
-            ::xmlity::ser::SerializeAttributeAccess::end(#access_ident, #value_exepr)
-        })
+        Ok(serialize_attribute_stmts(
+            serializer_access,
+            expanded_name,
+            preferred_prefix.as_ref(),
+            *enforce_prefix,
+            value_exepr,
+        ))
     }
 
     fn ident(&self) -> Cow<'_, Ident> {
@@ -263,30 +296,54 @@ impl SerializeAttributeBuilder for EnumSingleFieldAttributeSerializeBuilder<'_>
             unreachable!()
         };
 
+        let value_ident = Ident::new("__v", Span::mixed_site());
+
         let variants = variants
             .iter()
             .map::<Result<Arm, DeriveError>, _>(|variant| {
                 let variant_name = &variant.ident;
-                match &variant.fields {
-                    syn::Fields::Named(_fields) => {
-                        Err(DeriveError::custom("Named fields are not supported yet"))
+                let pattern: syn::Pat = match &variant.fields {
+                    syn::Fields::Named(fields) if fields.named.len() == 1 => {
+                        let field_ident = &fields.named[0].ident;
+                        parse_quote!(#ident::#variant_name { #field_ident: #value_ident })
                     }
-                    syn::Fields::Unnamed(fields) => {
-                        if fields.unnamed.len() == 1 {
-                            Ok(parse_quote! {
-                                #ident::#variant_name(val) => {
-                                    ::xmlity::Serialize::serialize(&val, &mut #serializer_access)
-                                },
-                            })
-                        } else {
-                            Err(DeriveError::custom(
-                                "Enum variants with more than one field are not supported",
-                            ))
-                        }
+                    syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                        parse_quote!(#ident::#variant_name(#value_ident))
                     }
                     syn::Fields::Unit => {
-                        Err(DeriveError::custom("Unit variants are not supported yet"))
+                        return Err(DeriveError::custom("Unit variants are not supported yet"))
                     }
+                    _ => {
+                        return Err(DeriveError::custom(
+                            "Enum variants with more than one field are not supported",
+                        ))
+                    }
+                };
+
+                // Variants with their own `xattribute` options are serialized as an attribute with that name, while other variants delegate to the `SerializeAttribute` implementation of their field.
+                match RootAttributeOpts::parse(&variant.attrs)? {
+                    Some(opts) => {
+                        let expanded_name = opts
+                            .expanded_name(variant_name.to_string().as_str())
+                            .into_owned();
+                        let stmts = serialize_attribute_stmts(
+                            serializer_access,
+                            &expanded_name,
+                            opts.preferred_prefix.as_ref(),
+                            opts.enforce_prefix,
+                            quote!(#value_ident),
+                        );
+                        Ok(parse_quote! {
+                            #pattern => {
+                                #(#stmts)*
+                            }
+                        })
+                    }
+                    None => Ok(parse_quote! {
+                        #pattern => {
+                            ::xmlity::SerializeAttribute::serialize_attribute(#value_ident, #serializer_access)
+                        }
+                    }),
                 }
             })
             .collect::<Result<Vec<_>, _>>()?;
@@ -324,27 +381,36 @@ impl SerializeAttributeOption {
 pub struct DeriveSerializeAttribute;
 
 impl DeriveMacro for DeriveSerializeAttribute {
-    fn input_to_derive(ast: &DeriveInput) -> Result<proc_macro2::TokenStream, DeriveError> {
-        let SerializeAttributeOption::Attribute(opts) = SerializeAttributeOption::parse(ast)?;
+    const DIRECTION: DeriveDirection = DeriveDirection::Serialize;
 
+    fn meta_form(_ast: &syn::DeriveInput) -> Option<MetaForm> {
+        Some(MetaForm::Item)
+    }
+
+    fn input_to_derive(ast: &DeriveInput) -> Result<proc_macro2::TokenStream, DeriveError> {
         match &ast.data {
-            syn::Data::Struct(syn::DataStruct { fields, .. }) => match fields {
-                syn::Fields::Unnamed(fields) if fields.unnamed.len() != 1 => Err(
-                    DeriveError::custom("Structs with more than one field are not supported."),
-                ),
-                syn::Fields::Unnamed(_) => {
-                    SerializeAttributeStructUnnamedSingleFieldBuilder::new(ast, &opts)
-                        .to_builder()?
-                        .serialize_attribute_trait_impl()
-                        .map(|x| x.to_token_stream())
-                }
-                syn::Fields::Named(_) => {
-                    Err(DeriveError::custom("Named fields are not supported yet."))
-                }
-                syn::Fields::Unit => {
-                    Err(DeriveError::custom("Unit structs are not supported yet."))
+            syn::Data::Struct(syn::DataStruct { fields, .. }) => {
+                let SerializeAttributeOption::Attribute(opts) =
+                    SerializeAttributeOption::parse(ast)?;
+
+                match fields {
+                    syn::Fields::Unnamed(fields) if fields.unnamed.len() != 1 => Err(
+                        DeriveError::custom("Structs with more than one field are not supported."),
+                    ),
+                    syn::Fields::Unnamed(_) => {
+                        SerializeAttributeStructUnnamedSingleFieldBuilder::new(ast, &opts)
+                            .to_builder()?
+                            .serialize_attribute_trait_impl()
+                            .map(|x| x.to_token_stream())
+                    }
+                    syn::Fields::Named(_) => {
+                        Err(DeriveError::custom("Named fields are not supported yet."))
+                    }
+                    syn::Fields::Unit => {
+                        Err(DeriveError::custom("Unit structs are not supported yet."))
+                    }
                 }
-            },
+            }
             syn::Data::Enum(_) => EnumSingleFieldAttributeSerializeBuilder::new(ast)
                 .serialize_attribute_trait_impl()
                 .map(|x| x.to_token_stream()),
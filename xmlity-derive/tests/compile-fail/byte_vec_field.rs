@@ -0,0 +1,9 @@
+use xmlity::Serialize;
+
+#[derive(Serialize)]
+#[xelement(name = "data")]
+struct Data {
+    bytes: Vec<u8>,
+}
+
+fn main() {}
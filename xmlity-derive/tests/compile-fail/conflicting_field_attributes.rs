@@ -0,0 +1,11 @@
+use xmlity::Deserialize;
+
+#[derive(Deserialize)]
+#[xelement(name = "note")]
+struct Note {
+    #[xattribute(name = "to")]
+    #[xelement(name = "to")]
+    to: String,
+}
+
+fn main() {}
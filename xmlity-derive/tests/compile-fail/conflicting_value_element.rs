@@ -0,0 +1,11 @@
+use xmlity::Serialize;
+
+#[derive(Serialize)]
+#[xelement(name = "note")]
+struct Note {
+    #[xvalue]
+    #[xelement(name = "body")]
+    body: String,
+}
+
+fn main() {}
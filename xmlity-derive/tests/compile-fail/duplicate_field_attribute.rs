@@ -0,0 +1,11 @@
+use xmlity::Serialize;
+
+#[derive(Serialize)]
+#[xelement(name = "note")]
+struct Note {
+    #[xattribute(name = "to")]
+    #[xattribute(name = "from")]
+    to: String,
+}
+
+fn main() {}
@@ -0,0 +1,22 @@
+use xmlity::Serialize;
+
+#[derive(Serialize)]
+#[xelement(name = "a b")]
+struct Note;
+
+#[derive(Serialize)]
+#[xelement(name = "note", preferred_prefix = "1p")]
+struct Prefixed;
+
+#[derive(Serialize)]
+#[xmlns("a b" = "http://example.com")]
+#[xelement(name = "note")]
+struct InvalidPrefix;
+
+#[derive(Serialize)]
+#[xmlns(ex = "http://example.com/a")]
+#[xmlns(ex = "http://example.com/b")]
+#[xelement(name = "ex:note")]
+struct DuplicatePrefix;
+
+fn main() {}
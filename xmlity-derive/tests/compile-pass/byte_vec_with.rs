@@ -0,0 +1,33 @@
+use xmlity::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[xvalue(with = hex)]
+struct Data(Vec<u8>);
+
+mod hex {
+    use super::Data;
+    use xmlity::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Data, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let text = String::deserialize(deserializer)?;
+        let bytes = (0..text.len())
+            .step_by(2)
+            .filter_map(|i| text.get(i..i + 2))
+            .filter_map(|byte| u8::from_str_radix(byte, 16).ok())
+            .collect();
+        Ok(Data(bytes))
+    }
+
+    pub fn serialize<S>(value: &Data, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let text: String = value.0.iter().map(|byte| format!("{byte:02x}")).collect();
+        text.serialize(serializer)
+    }
+}
+
+fn main() {}
@@ -0,0 +1,266 @@
+use std::collections::BTreeMap;
+use std::io::{BufReader, Read, Seek, SeekFrom, Write};
+
+use quick_xml::{
+    events::{BytesStart, Event},
+    name::PrefixDeclaration,
+    writer::Writer as QuickXmlWriter,
+    NsReader,
+};
+use xmlity::{
+    ser::Error as _, ExpandedName, Prefix, PrefixBuf, Serialize, XmlNamespace, XmlNamespaceBuf,
+};
+
+use crate::{ser::Error, xml_namespace_from_resolve_result, Serializer};
+
+/// Where and how children are appended to an element of an existing document.
+///
+/// The state is updated by every call to [`Appender::append`]. Keeping it around makes it possible to continue appending to the same document with [`Appender::from_state`] without reading it again, as long as the document has not been changed by anything else in the meantime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppendState {
+    offset: u64,
+    head: Vec<u8>,
+    tail: Vec<u8>,
+    namespaces: BTreeMap<PrefixBuf, XmlNamespaceBuf>,
+}
+
+impl AppendState {
+    /// The offset in the document at which the next children are written.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// The namespace bindings in scope of the element that children are appended to, with the default namespace bound to the blank prefix. Children in these namespaces are written without declaring them again.
+    pub fn namespaces(&self) -> &BTreeMap<PrefixBuf, XmlNamespaceBuf> {
+        &self.namespaces
+    }
+}
+
+/// Serializes values as the last children of an element of an existing document, without rewriting the rest of the document.
+///
+/// When opened, the document is read once to find the end of the element and the namespace bindings in its scope. Every call to [`Appender::append`] then writes the new children in front of the end tag, followed by the end tag and anything after it, so the document stays well-formed after each call. This is useful for long-running jobs writing records into one large XML file.
+///
+/// If the element is in the scope of a default namespace, children in it are written without a prefix, and children without a namespace undeclare it with `xmlns=""`.
+///
+/// ```
+/// use std::io::Cursor;
+///
+/// use xmlity::Serialize;
+/// use xmlity_quick_xml::append::Appender;
+///
+/// #[derive(Serialize)]
+/// #[xelement(name = "record")]
+/// struct Record(String);
+///
+/// let file = Cursor::new(b"<log><record>a</record></log>\n".to_vec());
+///
+/// let mut appender = Appender::open(file).unwrap();
+/// appender.append(&Record("b".to_string())).unwrap();
+/// appender.append(&Record("c".to_string())).unwrap();
+///
+/// let (file, _) = appender.into_inner();
+/// assert_eq!(
+///     String::from_utf8(file.into_inner()).unwrap(),
+///     "<log><record>a</record><record>b</record><record>c</record></log>\n"
+/// );
+/// ```
+pub struct Appender<F> {
+    file: F,
+    state: AppendState,
+}
+
+impl<F: Read + Write + Seek> Appender<F> {
+    /// Opens a document to append children to its root element.
+    pub fn open(file: F) -> Result<Self, Error> {
+        Self::open_target(file, Target::Root)
+    }
+
+    /// Opens a document to append children to the last element with the given name.
+    pub fn open_element(file: F, name: &ExpandedName<'_>) -> Result<Self, Error> {
+        Self::open_target(file, Target::Name(name))
+    }
+
+    fn open_target(mut file: F, target: Target<'_, '_>) -> Result<Self, Error> {
+        file.seek(SeekFrom::Start(0))?;
+        let found =
+            find_target(BufReader::new(&mut file), target)?.ok_or(Error::MissingAppendTarget)?;
+
+        // Everything after the point of insertion is written again after the new children.
+        file.seek(SeekFrom::Start(found.rest_offset))?;
+        let mut tail = found.closing;
+        file.read_to_end(&mut tail)?;
+
+        Ok(Self {
+            file,
+            state: AppendState {
+                offset: found.offset,
+                head: found.head,
+                tail,
+                namespaces: found.namespaces,
+            },
+        })
+    }
+}
+
+impl<F: Write + Seek> Appender<F> {
+    /// Continues appending to a document with a state from an earlier [`Appender`].
+    pub fn from_state(file: F, state: AppendState) -> Self {
+        Self { file, state }
+    }
+
+    /// Serializes a value as the last child of the element.
+    ///
+    /// The value is serialized completely before anything is written, so a value that fails to serialize leaves the document as it was.
+    pub fn append<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let mut serializer = Serializer::new(QuickXmlWriter::new(Vec::new()));
+        serializer.bind_namespaces(
+            self.state
+                .namespaces
+                .iter()
+                .map(|(prefix, namespace)| (&**prefix, &**namespace)),
+        );
+        value.serialize(&mut serializer)?;
+        let children = serializer.into_inner();
+
+        self.file.seek(SeekFrom::Start(self.state.offset))?;
+        self.file.write_all(&self.state.head)?;
+        self.file.write_all(&children)?;
+        self.file.write_all(&self.state.tail)?;
+        self.file.flush()?;
+
+        self.state.offset += (self.state.head.len() + children.len()) as u64;
+        self.state.head.clear();
+
+        Ok(())
+    }
+
+    /// Returns the state for appending to the document.
+    pub fn state(&self) -> &AppendState {
+        &self.state
+    }
+
+    /// Returns the underlying file and the state for appending to it.
+    pub fn into_inner(self) -> (F, AppendState) {
+        (self.file, self.state)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Target<'a, 'n> {
+    Root,
+    Name(&'a ExpandedName<'n>),
+}
+
+struct FoundTarget {
+    /// Where the new children are written.
+    offset: u64,
+    /// What is written before the first children, which closes the start tag of an empty element.
+    head: Vec<u8>,
+    /// What is written after the children in place of the document from `offset`, before the document from `rest_offset`.
+    closing: Vec<u8>,
+    rest_offset: u64,
+    namespaces: BTreeMap<PrefixBuf, XmlNamespaceBuf>,
+}
+
+/// Reads a document to find where children are appended to the target element. If several elements match, the last one is used.
+fn find_target<R: std::io::BufRead>(
+    reader: R,
+    target: Target<'_, '_>,
+) -> Result<Option<FoundTarget>, Error> {
+    let mut reader = NsReader::from_reader(reader);
+    let mut buf = Vec::new();
+    let mut scopes: Vec<Vec<(PrefixBuf, XmlNamespaceBuf)>> = Vec::new();
+    let mut found = None;
+
+    loop {
+        let start = reader.buffer_position();
+        let (resolve_result, event) = reader.read_resolved_event_into(&mut buf)?;
+        let namespace = xml_namespace_from_resolve_result(resolve_result).map(ToOwned::to_owned);
+        let end = reader.buffer_position();
+
+        match event {
+            Event::Start(bytes) => scopes.push(namespace_bindings(&bytes)?),
+            Event::Empty(bytes) => {
+                scopes.push(namespace_bindings(&bytes)?);
+                let name = bytes.name();
+                let local_name = std::str::from_utf8(name.local_name().into_inner())
+                    .map_err(|err| Error::Custom(err.to_string()))?;
+                if is_target(target, scopes.len(), local_name, namespace.as_deref()) {
+                    // `<name/>` becomes `<name>children</name>`.
+                    let mut closing = b"</".to_vec();
+                    closing.extend_from_slice(name.as_ref());
+                    closing.push(b'>');
+                    found = Some(FoundTarget {
+                        offset: end - 2,
+                        head: b">".to_vec(),
+                        closing,
+                        rest_offset: end,
+                        namespaces: in_scope(&scopes),
+                    });
+                }
+                scopes.pop();
+            }
+            Event::End(bytes) => {
+                let name = bytes.name();
+                let local_name = std::str::from_utf8(name.local_name().into_inner())
+                    .map_err(|err| Error::Custom(err.to_string()))?;
+                if is_target(target, scopes.len(), local_name, namespace.as_deref()) {
+                    found = Some(FoundTarget {
+                        offset: start,
+                        head: Vec::new(),
+                        closing: Vec::new(),
+                        rest_offset: start,
+                        namespaces: in_scope(&scopes),
+                    });
+                }
+                scopes.pop();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+
+        buf.clear();
+    }
+
+    Ok(found)
+}
+
+fn is_target(
+    target: Target<'_, '_>,
+    depth: usize,
+    local_name: &str,
+    namespace: Option<&XmlNamespace>,
+) -> bool {
+    match target {
+        Target::Root => depth == 1,
+        Target::Name(name) => {
+            name.local_name().as_str() == local_name && name.namespace().as_deref() == namespace
+        }
+    }
+}
+
+fn namespace_bindings(bytes: &BytesStart<'_>) -> Result<Vec<(PrefixBuf, XmlNamespaceBuf)>, Error> {
+    let mut bindings = Vec::new();
+    for attribute in bytes.attributes() {
+        let attribute = attribute?;
+        let prefix = match attribute.key.as_namespace_binding() {
+            Some(PrefixDeclaration::Named(prefix)) => {
+                let prefix =
+                    std::str::from_utf8(prefix).map_err(|err| Error::Custom(err.to_string()))?;
+                PrefixBuf::new(prefix.to_string()).map_err(Error::custom)?
+            }
+            Some(PrefixDeclaration::Default) => Prefix::BLANK.to_owned(),
+            None => continue,
+        };
+        let namespace = attribute.unescape_value()?;
+        bindings.push((
+            prefix,
+            XmlNamespaceBuf::new(namespace.into_owned()).map_err(Error::custom)?,
+        ));
+    }
+    Ok(bindings)
+}
+
+fn in_scope(scopes: &[Vec<(PrefixBuf, XmlNamespaceBuf)>]) -> BTreeMap<PrefixBuf, XmlNamespaceBuf> {
+    scopes.iter().flatten().cloned().collect()
+}
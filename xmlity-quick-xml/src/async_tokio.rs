@@ -0,0 +1,184 @@
+use std::fmt;
+
+use quick_xml::events::Event;
+use tokio::io::{AsyncBufRead, AsyncWrite, AsyncWriteExt};
+use xmlity::{DeserializeOwned, Serialize};
+
+use crate::de::{self, ItemCollector};
+use crate::ser;
+
+/// Deserialize a document read asynchronously from `reader`.
+///
+/// The events of the document are read without blocking until the root element is closed, after which the document is deserialized from memory. Anything after the root element is left unread, so this can be used on streams that stay open after a document, such as network connections.
+///
+/// ```
+/// # use xmlity::Deserialize;
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// #[xelement(name = "note")]
+/// struct Note(String);
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let note: Note = xmlity_quick_xml::from_reader_async(&b"<note>Hello</note>"[..])
+///     .await
+///     .unwrap();
+///
+/// assert_eq!(note, Note("Hello".to_string()));
+/// # });
+/// ```
+pub async fn from_reader_async<T, R>(reader: R) -> Result<T, de::Error>
+where
+    T: DeserializeOwned,
+    R: AsyncBufRead + Unpin,
+{
+    let mut reader = quick_xml::Reader::from_reader(reader);
+    let mut buffer = Vec::new();
+    let mut document = Vec::new();
+    let mut depth = 0usize;
+
+    loop {
+        buffer.clear();
+        let event = reader.read_event_into_async(&mut buffer).await?;
+        let is_root_end = match &event {
+            Event::Start(_) => {
+                depth += 1;
+                false
+            }
+            Event::End(_) => {
+                depth = depth.saturating_sub(1);
+                depth == 0
+            }
+            Event::Empty(_) => depth == 0,
+            Event::Eof => break,
+            _ => false,
+        };
+        quick_xml::Writer::new(&mut document).write_event(event)?;
+        if is_root_end {
+            break;
+        }
+    }
+
+    let mut deserializer = de::Deserializer::from(document.as_slice());
+    T::deserialize(&mut deserializer)
+}
+
+/// Deserialize the children of the root element of a document read asynchronously from `reader`, yielding one `T` per child element.
+///
+/// This is the asynchronous counterpart of [`from_reader_items`](crate::from_reader_items) and behaves the same way, but the children are read with [`AsyncReaderItems::next_item`].
+///
+/// ```
+/// # use xmlity::Deserialize;
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// #[xelement(name = "record")]
+/// struct Record(String);
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let input = "<export><record>a</record><record>b</record></export>";
+/// let mut items = xmlity_quick_xml::from_reader_items_async::<Record, _>(input.as_bytes());
+///
+/// let mut records = Vec::new();
+/// while let Some(record) = items.next_item().await {
+///     records.push(record.unwrap());
+/// }
+///
+/// assert_eq!(records, [Record("a".to_string()), Record("b".to_string())]);
+/// # });
+/// ```
+pub fn from_reader_items_async<T, R>(reader: R) -> AsyncReaderItems<R, T>
+where
+    T: DeserializeOwned,
+    R: AsyncBufRead + Unpin,
+{
+    AsyncReaderItems {
+        reader: quick_xml::Reader::from_reader(reader),
+        buffer: Vec::new(),
+        collector: ItemCollector::new(),
+        marker: std::marker::PhantomData,
+    }
+}
+
+/// The children of the root element of a document read asynchronously, created by [`from_reader_items_async`].
+pub struct AsyncReaderItems<R, T> {
+    reader: quick_xml::Reader<R>,
+    buffer: Vec<u8>,
+    collector: ItemCollector,
+    marker: std::marker::PhantomData<fn() -> T>,
+}
+
+impl<R, T> fmt::Debug for AsyncReaderItems<R, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AsyncReaderItems")
+            .field("position", &self.reader.buffer_position())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<R, T> AsyncReaderItems<R, T> {
+    /// The number of bytes read from the underlying reader so far.
+    pub fn position(&self) -> u64 {
+        self.reader.buffer_position()
+    }
+}
+
+impl<R, T> AsyncReaderItems<R, T>
+where
+    R: AsyncBufRead + Unpin,
+    T: DeserializeOwned,
+{
+    /// Reads the next child of the root element, returning `None` when the root element has ended.
+    pub async fn next_item(&mut self) -> Option<Result<T, de::Error>> {
+        while !self.collector.is_done() {
+            self.buffer.clear();
+            let event = self.reader.read_event_into_async(&mut self.buffer).await;
+            if let Some(item) = self.collector.feed(event) {
+                return item.transpose();
+            }
+        }
+        None
+    }
+}
+
+/// Serialize a value into a writer asynchronously.
+///
+/// The value is serialized into memory first, as serialization itself can't be suspended, and the document is then written to `writer` without blocking. The writer is flushed afterwards.
+///
+/// ```
+/// # use xmlity::Serialize;
+/// #[derive(Serialize)]
+/// #[xelement(name = "note")]
+/// struct Note(String);
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let mut output = Vec::new();
+/// xmlity_quick_xml::to_writer_async(&mut output, &Note("Hello".to_string()))
+///     .await
+///     .unwrap();
+///
+/// assert_eq!(output, b"<note>Hello</note>");
+/// # });
+/// ```
+pub async fn to_writer_async<W, T>(writer: W, value: &T) -> Result<(), ser::Error>
+where
+    W: AsyncWrite + Unpin,
+    T: Serialize,
+{
+    to_writer_async_with_config(writer, value, &ser::SerializerConfig::new()).await
+}
+
+/// Serialize a value into a writer asynchronously, using the given configuration.
+///
+/// See [`to_writer_async`] for how the document is written.
+pub async fn to_writer_async_with_config<W, T>(
+    mut writer: W,
+    value: &T,
+    config: &ser::SerializerConfig,
+) -> Result<(), ser::Error>
+where
+    W: AsyncWrite + Unpin,
+    T: Serialize,
+{
+    let mut document = Vec::new();
+    ser::to_writer_with_config(&mut document, value, config)?;
+    writer.write_all(&document).await?;
+    writer.flush().await?;
+    Ok(())
+}
@@ -1,26 +1,39 @@
 /// The [`xmlity::de::Deserializer`] implementation for the `quick-xml` crate.
 ///
 /// This deserializer is based upon the [`quick_xml::NsReader`] with the same limits as the underlying reader, including requiring a `[u8]` backing.
-use std::{borrow::Cow, collections::HashMap, ops::Deref, rc::Rc};
+use std::{
+    borrow::Cow,
+    cell::{Cell, OnceCell, RefCell},
+    collections::{HashMap, HashSet},
+    fmt,
+    ops::Deref,
+    rc::Rc,
+    sync::Arc,
+};
 
 use quick_xml::{
-    events::{attributes::Attribute, BytesCData, BytesDecl, BytesPI, BytesStart, BytesText, Event},
-    name::QName as QuickName,
+    events::{
+        attributes::{Attribute, Attributes},
+        BytesCData, BytesDecl, BytesPI, BytesRef, BytesStart, BytesText, Event,
+    },
+    name::{PrefixDeclaration, QName as QuickName},
     NsReader,
 };
 
 use xmlity::{
     de::{
-        self, DeserializeContext, Error as _, Visitor, XmlCData, XmlComment, XmlDeclaration,
-        XmlDoctype, XmlProcessingInstruction, XmlText,
+        self, DeserializeContext, DeserializeSeed, Error as _, NameResolver, Visitor, XmlCData,
+        XmlComment, XmlDeclaration, XmlDoctype, XmlProcessingInstruction, XmlText,
     },
-    Deserialize, ExpandedName, ExpandedNameBuf, LocalName, XmlNamespace,
+    value::XmlDoctype as XmlValueDoctype,
+    Deserialize, ExpandedName, ExpandedNameBuf, LocalName, Prefix, PrefixBuf, QName, XmlNamespace,
+    XmlNamespaceBuf,
 };
 
-use crate::{xml_namespace_from_resolve_result, HasQuickXmlAlternative};
+use crate::HasQuickXmlAlternative;
 
 /// Errors that can occur when using this crate.
-#[derive(Debug, thiserror::Error)]
+#[derive(Debug, Clone, thiserror::Error)]
 #[non_exhaustive]
 pub enum Error {
     /// Error from the `quick-xml` crate.
@@ -34,7 +47,7 @@ pub enum Error {
     AttrError(#[from] quick_xml::events::attributes::AttrError),
     /// IO errors.
     #[error("IO error: {0}")]
-    Io(#[from] std::io::Error),
+    Io(Arc<std::io::Error>),
     /// Unexpected segments that occurred when deserializing.
     #[error("Unexpected: {0}")]
     Unexpected(xmlity::de::Unexpected),
@@ -64,6 +77,14 @@ pub enum Error {
         /// The name of the enum.
         ident: String,
     },
+    /// None of the variants of a type matches.
+    #[error("No variant of {ident} matches, expected one of: {}", expected.join(", "))]
+    NoMatchingVariant {
+        /// The name of the type.
+        ident: String,
+        /// What the variants expect, such as the names of elements or the text values.
+        expected: Vec<String>,
+    },
     /// Missing data.
     #[error("Missing data")]
     MissingData,
@@ -81,11 +102,65 @@ pub enum Error {
         /// The name of the end element.
         end_name: String,
     },
+    /// The elements of the document are nested deeper than the recursion limit of the deserializer.
+    #[error("Recursion limit of {limit} nested elements exceeded")]
+    RecursionLimitExceeded {
+        /// The recursion limit that was exceeded.
+        limit: usize,
+    },
+    /// Entity references are nested deeper in the replacement text of each other than the entity depth limit of the deserializer.
+    #[error("Entity depth limit of {limit} nested references exceeded")]
+    EntityDepthLimitExceeded {
+        /// The entity depth limit that was exceeded.
+        limit: usize,
+    },
+    /// The entity references of the document expand to more replacement text than the entity expansion limit of the deserializer.
+    #[error("Entity expansion limit of {limit} bytes exceeded")]
+    EntityExpansionLimitExceeded {
+        /// The entity expansion limit that was exceeded.
+        limit: usize,
+    },
+    /// An entity refers to itself, directly or through other entities.
+    #[error("Entity `&{name};` refers to itself")]
+    RecursiveEntity {
+        /// The name of the entity.
+        name: String,
+    },
+    /// The replacement text of an entity contains markup, which is not supported.
+    #[error("Entity `&{name};` contains markup")]
+    EntityMarkup {
+        /// The name of the entity.
+        name: String,
+    },
+    /// Invalid entity or character reference, such as a reference to an entity that is not declared.
+    #[error("Unescape error: {0}")]
+    Unescape(#[from] xmlity::escape::UnescapeError),
+    /// Error from deserializing an [`XmlValue`](xmlity::XmlValue), as done by [`from_str_via_value`].
+    #[error("Value error: {0}")]
+    Value(#[from] xmlity::value::XmlValueDeserializerError),
+    /// A name of an element or attribute, or its prefix, is not a valid XML name.
+    #[error("Invalid name: `{name}`")]
+    InvalidName {
+        /// The name as it was written.
+        name: String,
+    },
     /// Custom errors occuring in [`Deserialize`] implementations.
     #[error("Custom: {0}")]
     Custom(String),
 }
 
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::Io(Arc::new(error))
+    }
+}
+
+impl From<Error> for xmlity::Error {
+    fn from(error: Error) -> Self {
+        xmlity::Error::deserialize(error)
+    }
+}
+
 impl xmlity::de::Error for Error {
     fn custom<T: ToString>(msg: T) -> Self {
         Error::Custom(msg.to_string())
@@ -114,6 +189,16 @@ impl xmlity::de::Error for Error {
         }
     }
 
+    fn no_matching_variant(ident: &str, expected: &[&str]) -> Self {
+        Error::NoMatchingVariant {
+            ident: ident.to_string(),
+            expected: expected
+                .iter()
+                .map(|expected| expected.to_string())
+                .collect(),
+        }
+    }
+
     fn missing_data() -> Self {
         Error::MissingData
     }
@@ -136,151 +221,1731 @@ where
     T::deserialize(&mut deserializer)
 }
 
-/// This reader wraps the `quick_xml::NsReader` and provides a way to peek and read events from the XML stream, as well as observe the depth, which are properties used when deserializing.
-#[derive(Debug, Clone)]
-struct Reader<'i> {
-    reader: NsReader<&'i [u8]>,
-    current_depth: i16,
-    peeked_event: Option<Event<'i>>,
+/// Deserialize from a string with a context value that is available to the type being deserialized as external data.
+///
+/// The context can be read with [`xmlity::Deserializer::external_data`] or [`DeserializeContext::external_data`], for example by functions used with `deserialize_with_context`, to resolve values that depend on the environment, such as URLs relative to a base URL.
+pub fn from_str_with_context<'a, T, C>(s: &'a str, context: C) -> Result<T, Error>
+where
+    T: Deserialize<'a>,
+    C: core::any::Any,
+{
+    let mut external_data = ExternalData::new();
+    external_data.insert(context);
+
+    let mut deserializer = Deserializer::from(s.as_bytes()).with_external_data(external_data);
+    T::deserialize(&mut deserializer)
 }
-impl<'i> Reader<'i> {
-    /// Create a new deserializer from a [`NsReader<&'i [u8]>`].
-    pub fn new(reader: NsReader<&'i [u8]>) -> Self {
+
+/// Deserialize from a string with the given reader options.
+pub fn from_str_with_options<'a, T>(s: &'a str, options: &ReaderOptions) -> Result<T, Error>
+where
+    T: Deserialize<'a>,
+{
+    let mut deserializer = Deserializer::with_reader_options(s.as_bytes(), options);
+    T::deserialize(&mut deserializer)
+}
+
+/// Options of how the underlying `quick-xml` reader of a [`Deserializer`] reads documents, see [`Deserializer::with_reader_options`].
+///
+/// The defaults are the same as those of [`quick_xml::reader::Config`], so a deserializer behaves like a plain `quick-xml` reader unless options are changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReaderOptions {
+    trim_text: bool,
+    expand_empty_elements: bool,
+    check_end_names: bool,
+    check_comments: bool,
+    allow_unmatched_ends: bool,
+    allow_dangling_amp: bool,
+    trim_markup_names_in_closing_tags: bool,
+}
+
+impl Default for ReaderOptions {
+    fn default() -> Self {
         Self {
-            reader,
-            current_depth: 0,
-            peeked_event: None,
+            trim_text: false,
+            expand_empty_elements: false,
+            check_end_names: true,
+            check_comments: false,
+            allow_unmatched_ends: false,
+            allow_dangling_amp: false,
+            trim_markup_names_in_closing_tags: true,
         }
     }
+}
 
-    fn read_event(&mut self) -> Result<Option<Event<'i>>, Error> {
-        match self.reader.read_event()? {
-            Event::Eof => Ok(None),
-            event => Ok(Some(event)),
-        }
+impl ReaderOptions {
+    /// Create options with the defaults of `quick-xml`.
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    pub fn peek_event(&mut self) -> Result<Option<&Event<'i>>, Error> {
-        if self.peeked_event.is_some() {
-            return Ok(self.peeked_event.as_ref());
-        }
+    /// Trim whitespace from the start and end of text, leaving out text that is only whitespace. See [`quick_xml::reader::Config::trim_text`].
+    ///
+    /// Unlike in `quick-xml`, text is trimmed after text around entity references has been joined, so whitespace next to references such as `&amp;` is kept.
+    pub fn with_trim_text(mut self, trim_text: bool) -> Self {
+        self.trim_text = trim_text;
+        self
+    }
 
-        self.peeked_event = self.read_event()?;
-        Ok(self.peeked_event.as_ref())
+    /// Read empty elements like `<a/>` as a start and an end tag. See [`quick_xml::reader::Config::expand_empty_elements`].
+    pub fn with_expand_empty_elements(mut self, expand_empty_elements: bool) -> Self {
+        self.expand_empty_elements = expand_empty_elements;
+        self
     }
 
-    pub fn next_event(&mut self) -> Result<Option<Event<'i>>, Error> {
-        let event = if self.peeked_event.is_some() {
-            self.peeked_event.take()
-        } else {
-            self.read_event()?
-        };
+    /// Check that end tags have the same name as their start tags. See [`quick_xml::reader::Config::check_end_names`].
+    pub fn with_check_end_names(mut self, check_end_names: bool) -> Self {
+        self.check_end_names = check_end_names;
+        self
+    }
 
-        if matches!(event, Some(Event::End(_))) {
-            self.current_depth -= 1;
-        }
-        if matches!(event, Some(Event::Start(_))) {
-            self.current_depth += 1;
-        }
+    /// Check that comments do not contain `--`. See [`quick_xml::reader::Config::check_comments`].
+    pub fn with_check_comments(mut self, check_comments: bool) -> Self {
+        self.check_comments = check_comments;
+        self
+    }
 
-        Ok(event)
+    /// Allow end tags without a matching start tag. See [`quick_xml::reader::Config::allow_unmatched_ends`].
+    pub fn with_allow_unmatched_ends(mut self, allow_unmatched_ends: bool) -> Self {
+        self.allow_unmatched_ends = allow_unmatched_ends;
+        self
     }
 
-    pub fn resolve_qname<'a>(&'a self, qname: QuickName<'a>, attribute: bool) -> ExpandedName<'a> {
-        let namespace = self.resolve_namespace(qname, attribute);
+    /// Allow `&` in text that does not start a reference. See [`quick_xml::reader::Config::allow_dangling_amp`].
+    pub fn with_allow_dangling_amp(mut self, allow_dangling_amp: bool) -> Self {
+        self.allow_dangling_amp = allow_dangling_amp;
+        self
+    }
 
-        ExpandedName::new(<&LocalName>::from_quick_xml(qname.local_name()), namespace)
+    /// Ignore whitespace after the name in end tags like `</a >`. See [`quick_xml::reader::Config::trim_markup_names_in_closing_tags`].
+    pub fn with_trim_markup_names_in_closing_tags(
+        mut self,
+        trim_markup_names_in_closing_tags: bool,
+    ) -> Self {
+        self.trim_markup_names_in_closing_tags = trim_markup_names_in_closing_tags;
+        self
     }
 
-    pub fn resolve_namespace<'a>(
-        &'a self,
-        qname: QuickName<'_>,
-        attribute: bool,
-    ) -> Option<&'a XmlNamespace> {
-        let (resolve_result, _) = self.reader.resolve(qname, attribute);
-        let namespace = xml_namespace_from_resolve_result(resolve_result);
+    /// Whether whitespace is trimmed from text.
+    pub fn trim_text(&self) -> bool {
+        self.trim_text
+    }
 
-        namespace
+    /// Whether empty elements are read as a start and an end tag.
+    pub fn expand_empty_elements(&self) -> bool {
+        self.expand_empty_elements
     }
 
-    pub fn current_depth(&self) -> i16 {
-        self.current_depth
+    /// Whether end tags are checked against their start tags.
+    pub fn check_end_names(&self) -> bool {
+        self.check_end_names
+    }
+
+    /// Whether comments are checked for `--`.
+    pub fn check_comments(&self) -> bool {
+        self.check_comments
+    }
+
+    /// Whether end tags without a matching start tag are allowed.
+    pub fn allow_unmatched_ends(&self) -> bool {
+        self.allow_unmatched_ends
+    }
+
+    /// Whether `&` that does not start a reference is allowed.
+    pub fn allow_dangling_amp(&self) -> bool {
+        self.allow_dangling_amp
+    }
+
+    /// Whether whitespace after the name in end tags is ignored.
+    pub fn trim_markup_names_in_closing_tags(&self) -> bool {
+        self.trim_markup_names_in_closing_tags
+    }
+
+    fn apply(&self, config: &mut quick_xml::reader::Config) {
+        config.trim_text(self.trim_text);
+        config.expand_empty_elements = self.expand_empty_elements;
+        config.check_end_names = self.check_end_names;
+        config.check_comments = self.check_comments;
+        config.allow_unmatched_ends = self.allow_unmatched_ends;
+        config.allow_dangling_amp = self.allow_dangling_amp;
+        config.trim_markup_names_in_closing_tags = self.trim_markup_names_in_closing_tags;
     }
 }
 
-/// A struct to hold external data that can be used during deserialization.
-#[derive(Debug)]
-pub struct ExternalData {
-    data: HashMap<core::any::TypeId, Box<dyn core::any::Any>>,
+/// Deserialize from a string into a type that does not borrow from it.
+///
+/// This is the same as [`from_str`], but the [`DeserializeOwned`](xmlity::DeserializeOwned) bound makes it clear to the compiler that the result is independent of `s`, which makes it easier to use in generic code and to move the result to other threads, for example from a worker pool. The owned value types of `xmlity`, like [`XmlValue`](xmlity::XmlValue), and this crate's [`Error`] are all [`Send`] and [`Sync`].
+pub fn from_str_owned<T>(s: &str) -> Result<T, Error>
+where
+    T: xmlity::DeserializeOwned,
+{
+    from_str(s)
 }
 
-impl ExternalData {
-    /// Creates a new [`ExternalData`] instance.
-    pub fn new() -> Self {
-        Self {
-            data: HashMap::new(),
-        }
+/// Deserialize from a string by first reading it into an [`XmlValue`](xmlity::XmlValue), and then deserializing `T` from that value.
+///
+/// This is slower than [`from_str`] and uses more memory, but errors in deserializing `T` are [`XmlValueDeserializerError`](xmlity::value::XmlValueDeserializerError)s that record where in the document they occurred. They also record the invalid attribute or child that made an element fail, which types usually treat as absent and would otherwise report as missing. This makes errors much easier to track down in large documents, so it can be useful in debug builds, or to retry a failed [`from_str`] to get a better error message.
+///
+/// ```
+/// # use xmlity::Deserialize;
+/// #[derive(Debug, Deserialize)]
+/// #[xelement(name = "book")]
+/// struct Book {
+///     #[xattribute(name = "id")]
+///     id: u32,
+/// }
+///
+/// #[derive(Debug, Deserialize)]
+/// #[xelement(name = "catalog")]
+/// struct Catalog {
+///     first: Book,
+///     second: Book,
+/// }
+///
+/// let err = xmlity_quick_xml::from_str_via_value::<Catalog>(
+///     r#"<catalog><book id="1"/><book id="two"/></catalog>"#,
+/// )
+/// .unwrap_err();
+///
+/// let xmlity_quick_xml::de::Error::Value(err) = err else { panic!() };
+/// assert_eq!(err.root_cause().path().unwrap().to_string(), "/catalog/book[2]/@id");
+/// ```
+pub fn from_str_via_value<T>(s: &str) -> Result<T, Error>
+where
+    T: xmlity::DeserializeOwned,
+{
+    let mut deserializer = Deserializer::from(s.as_bytes());
+    let value = xmlity::XmlValue::deserialize_seq(&mut deserializer)?;
+    Ok(xmlity::value::from_value(&value)?)
+}
+
+/// Deserialize a stream of concatenated XML documents or fragments, yielding one `T` per document.
+///
+/// A document ends when its root element is closed, so this works both for newline-delimited snippets, as is common in log pipelines, and for documents that span several lines. Comments, processing instructions and XML declarations between documents are skipped.
+///
+/// Errors don't end the iteration. If a document can't be deserialized into `T`, the error is yielded and iteration continues with the next document. If a document is malformed, iteration continues on the line after the error, or at the next XML declaration if a new document starts before the malformed one was closed.
+///
+/// ```
+/// # use xmlity::Deserialize;
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// #[xelement(name = "entry")]
+/// struct Entry(String);
+///
+/// let input = "<entry>a</entry>\n<entry>b</oops>\n<entry>c</entry>\n";
+/// let entries: Vec<_> = xmlity_quick_xml::from_str_fragments::<Entry>(input).collect();
+///
+/// assert_eq!(entries.len(), 3);
+/// assert_eq!(entries[0].as_ref().unwrap(), &Entry("a".to_string()));
+/// assert!(entries[1].is_err());
+/// assert_eq!(entries[2].as_ref().unwrap(), &Entry("c".to_string()));
+/// ```
+pub fn from_str_fragments<'a, T>(s: &'a str) -> Fragments<'a, T>
+where
+    T: Deserialize<'a>,
+{
+    Fragments {
+        input: s,
+        position: 0,
+        marker: std::marker::PhantomData,
     }
+}
 
-    /// Inserts data into the external data map.
-    pub fn insert<T: 'static>(&mut self, data: T) {
-        self.data.insert(
-            core::any::TypeId::of::<T>(),
-            Box::new(data) as Box<dyn core::any::Any>,
-        );
+/// An iterator over the documents in a string, created by [`from_str_fragments`].
+pub struct Fragments<'a, T> {
+    input: &'a str,
+    position: usize,
+    marker: std::marker::PhantomData<fn() -> T>,
+}
+
+impl<T> fmt::Debug for Fragments<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Fragments")
+            .field("position", &self.position)
+            .finish_non_exhaustive()
     }
+}
 
-    /// Retrieves data from the external data map.
-    pub fn get<T: 'static>(&self) -> Option<&T> {
-        self.data
-            .get(&core::any::TypeId::of::<T>())
-            .and_then(|data| data.downcast_ref::<T>())
+impl<T> Fragments<'_, T> {
+    /// The byte offset in the input where the next document starts.
+    pub fn position(&self) -> usize {
+        self.position
     }
 }
 
-impl Default for ExternalData {
-    fn default() -> Self {
-        Self::new()
+/// The result of scanning for the end of the next document.
+enum FragmentEnd {
+    /// The root element of the document spans the given range.
+    Complete(std::ops::Range<usize>),
+    /// The document is malformed, and the next document starts at the given offset.
+    Malformed(Error, usize),
+    /// There are no more documents.
+    Eof,
+}
+
+fn next_fragment_end(input: &str) -> FragmentEnd {
+    let mut reader = quick_xml::Reader::from_str(input);
+    let mut open_elements: Vec<String> = Vec::new();
+    let mut root_start = 0;
+
+    // Any error inside a document skips to the next line.
+    let next_line = |position: usize| {
+        input[position..]
+            .find('\n')
+            .map_or(input.len(), |offset| position + offset + 1)
+    };
+
+    loop {
+        let start = reader.buffer_position() as usize;
+        let event = match reader.read_event() {
+            Ok(event) => event,
+            Err(err) => {
+                let position = (reader.buffer_position() as usize).max(start + 1);
+                return FragmentEnd::Malformed(err.into(), next_line(position.min(input.len())));
+            }
+        };
+        let end = reader.buffer_position() as usize;
+
+        match event {
+            Event::Start(bytes) => {
+                if open_elements.is_empty() {
+                    root_start = start;
+                }
+                open_elements.push(String::from_utf8_lossy(bytes.name().0).into_owned());
+            }
+            Event::End(_) => {
+                open_elements.pop();
+                if open_elements.is_empty() {
+                    return FragmentEnd::Complete(root_start..end);
+                }
+            }
+            Event::Empty(_) if open_elements.is_empty() => {
+                return FragmentEnd::Complete(start..end);
+            }
+            Event::Decl(_) if !open_elements.is_empty() => {
+                return FragmentEnd::Malformed(
+                    Error::StartElementWithoutEnd {
+                        name: open_elements.swap_remove(0),
+                    },
+                    start,
+                );
+            }
+            Event::Eof => {
+                return match open_elements.into_iter().next() {
+                    Some(name) => {
+                        FragmentEnd::Malformed(Error::StartElementWithoutEnd { name }, input.len())
+                    }
+                    None => FragmentEnd::Eof,
+                };
+            }
+            _ => {}
+        }
     }
 }
 
-/// The [`xmlity::Deserializer`] for the `quick-xml` crate.
+impl<'a, T> Iterator for Fragments<'a, T>
+where
+    T: Deserialize<'a>,
+{
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let input = &self.input[self.position..];
+
+        match next_fragment_end(input) {
+            FragmentEnd::Complete(root) => {
+                self.position += root.end;
+                Some(from_str(&input[root]))
+            }
+            FragmentEnd::Malformed(err, next) => {
+                self.position += next;
+                Some(Err(err))
+            }
+            FragmentEnd::Eof => {
+                self.position = self.input.len();
+                None
+            }
+        }
+    }
+}
+
+/// Deserialize a document read from `reader`.
 ///
-/// This currently only supports an underlying reader of type `&[u8]` due to limitations in the `quick-xml` crate.
-#[derive(Debug, Clone)]
-pub struct Deserializer<'i> {
-    reader: Reader<'i>,
-    // Limit depth
-    limit_depth: i16,
-    external_data: Option<Rc<ExternalData>>,
+/// The events of the document are read until the root element is closed, after which the document is deserialized from memory, so the document is held in memory once instead of being read into a string first. Anything after the root element is not deserialized, though it may already have been read from `reader` into a buffer. To deserialize documents that are too large to be held in memory one child of the root element at a time, use [`from_reader_items`].
+///
+/// ```
+/// # use xmlity::Deserialize;
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// #[xelement(name = "note")]
+/// struct Note(String);
+///
+/// let note: Note = xmlity_quick_xml::from_reader(&b"<note>Hello</note>"[..]).unwrap();
+///
+/// assert_eq!(note, Note("Hello".to_string()));
+/// ```
+pub fn from_reader<T, R>(reader: R) -> Result<T, Error>
+where
+    T: xmlity::DeserializeOwned,
+    R: std::io::Read,
+{
+    let mut reader = quick_xml::Reader::from_reader(std::io::BufReader::new(reader));
+    let mut buffer = Vec::new();
+    let mut document = Vec::new();
+    let mut depth = 0usize;
+
+    loop {
+        buffer.clear();
+        let event = reader.read_event_into(&mut buffer)?;
+        let is_root_end = match &event {
+            Event::Start(_) => {
+                depth += 1;
+                false
+            }
+            Event::End(_) => {
+                depth = depth.saturating_sub(1);
+                depth == 0
+            }
+            Event::Empty(_) => depth == 0,
+            Event::Eof => break,
+            _ => false,
+        };
+        quick_xml::Writer::new(&mut document).write_event(event)?;
+        if is_root_end {
+            break;
+        }
+    }
+
+    let mut deserializer = Deserializer::from(document.as_slice());
+    T::deserialize(&mut deserializer)
 }
 
-impl<'i> From<NsReader<&'i [u8]>> for Deserializer<'i> {
-    fn from(reader: NsReader<&'i [u8]>) -> Self {
-        Self::new(reader)
+/// Deserialize the children of the root element of a document read from `reader`, yielding one `T` per child element.
+///
+/// Only one child is kept in memory at a time, so this can be used for documents that are too large to be read into a string, such as exports that wrap a huge number of records in a single root element. The namespaces declared on the root element stay in scope for each child, and so do the entities declared in the internal subset of the doctype, with the default entity limits of [`Deserializer`] applying to the whole document. Text, comments and processing instructions between the children are skipped.
+///
+/// If a child can't be deserialized into `T`, the error is yielded and iteration continues with the next child. Errors from reading the document end the iteration.
+///
+/// ```
+/// # use xmlity::Deserialize;
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// #[xelement(name = "record")]
+/// struct Record(String);
+///
+/// let input = "<export><record>a</record><record>b</record></export>";
+/// let records = xmlity_quick_xml::from_reader_items::<Record, _>(input.as_bytes())
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+///
+/// assert_eq!(records, [Record("a".to_string()), Record("b".to_string())]);
+/// ```
+pub fn from_reader_items<T, R>(reader: R) -> ReaderItems<R, T>
+where
+    T: xmlity::DeserializeOwned,
+    R: std::io::Read,
+{
+    ReaderItems {
+        reader: quick_xml::Reader::from_reader(std::io::BufReader::new(reader)),
+        buffer: Vec::new(),
+        collector: ItemCollector::new(),
+        marker: std::marker::PhantomData,
     }
 }
 
-impl<'i> From<&'i [u8]> for Deserializer<'i> {
-    fn from(buffer: &'i [u8]) -> Self {
-        Self::new(NsReader::from_reader(buffer))
+/// An iterator over the children of the root element of a document, created by [`from_reader_items`].
+pub struct ReaderItems<R, T> {
+    reader: quick_xml::Reader<std::io::BufReader<R>>,
+    buffer: Vec<u8>,
+    collector: ItemCollector,
+    marker: std::marker::PhantomData<fn() -> T>,
+}
+
+impl<R, T> fmt::Debug for ReaderItems<R, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReaderItems")
+            .field("position", &self.reader.buffer_position())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<R, T> ReaderItems<R, T> {
+    /// The number of bytes read from the underlying reader so far.
+    pub fn position(&self) -> u64 {
+        self.reader.buffer_position()
+    }
+}
+
+impl<R, T> Iterator for ReaderItems<R, T>
+where
+    R: std::io::Read,
+    T: xmlity::DeserializeOwned,
+{
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.collector.is_done() {
+            self.buffer.clear();
+            let event = self.reader.read_event_into(&mut self.buffer);
+            if let Some(item) = self.collector.feed(event) {
+                return item.transpose();
+            }
+        }
+        None
+    }
+}
+
+enum ItemCollectorState {
+    BeforeRoot,
+    InRoot,
+    InItem { name: String, depth: usize },
+    Done,
+}
+
+/// Collects the children of the root element of a document from its events, one child at a time.
+///
+/// This is shared by [`ReaderItems`] and its asynchronous counterpart, which only differ in how they read the events.
+pub(crate) struct ItemCollector {
+    state: ItemCollectorState,
+    root_name: String,
+    /// The raw keys and values of the namespace declarations of the root element.
+    namespaces: Vec<(Vec<u8>, Vec<u8>)>,
+    /// The entities declared in the internal subset of the doctype.
+    entities: Entities,
+    /// The number of bytes of replacement text that the entity references of the previous children have expanded to, so that the entity expansion limit applies to the whole document.
+    entities_expanded: usize,
+    item: Vec<u8>,
+}
+
+impl ItemCollector {
+    pub(crate) fn new() -> Self {
+        Self {
+            state: ItemCollectorState::BeforeRoot,
+            root_name: String::new(),
+            namespaces: Vec::new(),
+            entities: Entities::new(),
+            entities_expanded: 0,
+            item: Vec::new(),
+        }
+    }
+
+    pub(crate) fn is_done(&self) -> bool {
+        matches!(self.state, ItemCollectorState::Done)
+    }
+
+    /// Feeds the next event of the document to the collector.
+    ///
+    /// Returns `Some(Ok(Some(item)))` when a child is complete, `Some(Ok(None))` when the root element has ended, and `Some(Err(_))` on errors. Errors from reading the document end the collection.
+    pub(crate) fn feed<T: xmlity::DeserializeOwned>(
+        &mut self,
+        event: Result<Event<'_>, quick_xml::Error>,
+    ) -> Option<Result<Option<T>, Error>> {
+        let event = match event {
+            Ok(event) => event,
+            Err(err) => {
+                self.state = ItemCollectorState::Done;
+                return Some(Err(err.into()));
+            }
+        };
+
+        match self.feed_event(event) {
+            Ok(true) => {
+                let mut deserializer =
+                    Deserializer::from(self.item.as_slice()).with_entities(self.entities.clone());
+                deserializer
+                    .reader
+                    .entities
+                    .expanded
+                    .set(self.entities_expanded);
+                let item = T::deserialize(&mut deserializer);
+                self.entities_expanded = deserializer.reader.entities.expanded.get();
+                Some(item.map(Some))
+            }
+            Ok(false) if self.is_done() => Some(Ok(None)),
+            Ok(false) => None,
+            Err(err) => {
+                self.state = ItemCollectorState::Done;
+                Some(Err(err))
+            }
+        }
+    }
+
+    /// Returns whether a child is complete.
+    fn feed_event(&mut self, event: Event<'_>) -> Result<bool, Error> {
+        match (&mut self.state, event) {
+            (ItemCollectorState::Done, _) => Ok(false),
+            (ItemCollectorState::BeforeRoot, Event::Start(start)) => {
+                self.root_name = String::from_utf8_lossy(start.name().0).into_owned();
+                self.namespaces = start
+                    .attributes()
+                    .with_checks(false)
+                    .flatten()
+                    .filter(|attribute| attribute.key.as_namespace_binding().is_some())
+                    .map(|attribute| {
+                        (
+                            attribute.key.as_ref().to_vec(),
+                            attribute.value.into_owned(),
+                        )
+                    })
+                    .collect();
+                self.state = ItemCollectorState::InRoot;
+                Ok(false)
+            }
+            (ItemCollectorState::BeforeRoot, Event::DocType(doctype)) => {
+                let mut entities = EntityResolver::new();
+                entities.declare(&XmlValueDoctype::new(doctype.as_ref()))?;
+                self.entities = Rc::unwrap_or_clone(entities.entities);
+                Ok(false)
+            }
+            (ItemCollectorState::BeforeRoot, Event::Empty(_) | Event::Eof) => {
+                self.state = ItemCollectorState::Done;
+                Ok(false)
+            }
+            (ItemCollectorState::BeforeRoot, _) => Ok(false),
+            (ItemCollectorState::InRoot, Event::Start(start)) => {
+                let name = String::from_utf8_lossy(start.name().0).into_owned();
+                self.write_item_start(start, false)?;
+                self.state = ItemCollectorState::InItem { name, depth: 1 };
+                Ok(false)
+            }
+            (ItemCollectorState::InRoot, Event::Empty(start)) => {
+                self.write_item_start(start, true)?;
+                Ok(true)
+            }
+            (ItemCollectorState::InRoot, Event::End(_)) => {
+                self.state = ItemCollectorState::Done;
+                Ok(false)
+            }
+            (ItemCollectorState::InRoot, Event::Eof) => Err(Error::StartElementWithoutEnd {
+                name: std::mem::take(&mut self.root_name),
+            }),
+            (ItemCollectorState::InRoot, _) => Ok(false),
+            (ItemCollectorState::InItem { name, .. }, Event::Eof) => {
+                Err(Error::StartElementWithoutEnd {
+                    name: std::mem::take(name),
+                })
+            }
+            (ItemCollectorState::InItem { depth, .. }, event) => {
+                match &event {
+                    Event::Start(_) => *depth += 1,
+                    Event::End(_) => *depth -= 1,
+                    _ => {}
+                }
+                let complete = *depth == 0;
+                quick_xml::Writer::new(&mut self.item).write_event(event)?;
+                if complete {
+                    self.state = ItemCollectorState::InRoot;
+                }
+                Ok(complete)
+            }
+        }
+    }
+
+    /// Starts a new child in `self.item`, adding the namespace declarations of the root element that it doesn't override itself.
+    fn write_item_start(&mut self, mut start: BytesStart<'_>, is_empty: bool) -> Result<(), Error> {
+        let declared = start
+            .attributes()
+            .with_checks(false)
+            .flatten()
+            .map(|attribute| attribute.key.as_ref().to_vec())
+            .collect::<Vec<_>>();
+        for (key, value) in &self.namespaces {
+            if !declared.contains(key) {
+                start.push_attribute(Attribute {
+                    key: QuickName(key),
+                    value: Cow::Borrowed(value),
+                });
+            }
+        }
+
+        self.item.clear();
+        let mut writer = quick_xml::Writer::new(&mut self.item);
+        if is_empty {
+            writer.write_event(Event::Empty(start))?;
+        } else {
+            writer.write_event(Event::Start(start))?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the replacement text of an entity declared with a literal value. Character references in the value are resolved when the entity is declared, while references to other entities are kept until the entity is used.
+fn replacement_text(value: &str) -> Result<String, Error> {
+    let mut replacement_text = String::with_capacity(value.len());
+    let mut position = 0;
+    while let Some(offset) = value[position..].find("&#") {
+        let start = position + offset;
+        let end = value[start..]
+            .find(';')
+            .map(|end| start + end)
+            .ok_or(xmlity::escape::UnescapeError::UnterminatedEntity(start))?;
+
+        replacement_text.push_str(&value[position..start]);
+        replacement_text.push_str(&xmlity::escape::unescape(&value[start..=end])?);
+        position = end + 1;
+    }
+    replacement_text.push_str(&value[position..]);
+
+    Ok(replacement_text)
+}
+
+/// Expands entity references with the entities of a [`Deserializer`], keeping to its limits.
+#[derive(Debug, Clone)]
+struct EntityResolver {
+    entities: Rc<Entities>,
+    /// The limits are shared to keep the reader small, since deserializers are copied at every level of nesting.
+    limits: Rc<EntityLimits>,
+    /// The number of bytes of replacement text that has been expanded so far.
+    expanded: Cell<usize>,
+    /// The error of the first entity reference that could not be resolved, after which every read fails. It is shared between all clones, so that it can't be hidden by types that ignore failed children.
+    error: Rc<OnceCell<Error>>,
+}
+
+#[derive(Debug, Clone)]
+struct EntityLimits {
+    depth: Option<usize>,
+    expansion: Option<usize>,
+}
+
+impl EntityResolver {
+    fn new() -> Self {
+        Self {
+            entities: Rc::new(Entities::new()),
+            limits: Rc::new(EntityLimits {
+                depth: Some(Deserializer::DEFAULT_ENTITY_DEPTH_LIMIT),
+                expansion: Some(Deserializer::DEFAULT_ENTITY_EXPANSION_LIMIT),
+            }),
+            expanded: Cell::new(0),
+            error: Rc::new(OnceCell::new()),
+        }
+    }
+
+    /// Records the error of a reference that could not be resolved.
+    fn record<T>(&self, result: Result<T, Error>) -> Result<T, Error> {
+        if let Err(error) = &result {
+            let _ = self.error.set(error.clone());
+        }
+        result
+    }
+
+    /// Adds the entities declared in the internal subset of a doctype. Like in XML, the first declaration of an entity is used, and declarations of the predefined entities are ignored.
+    fn declare(&mut self, doctype: &XmlValueDoctype) -> Result<(), Error> {
+        let declarations = doctype.entity_declarations();
+        if declarations.is_empty() {
+            return Ok(());
+        }
+
+        let entities = Rc::make_mut(&mut self.entities);
+        let mut declared = HashSet::new();
+        for declaration in declarations {
+            let name = std::str::from_utf8(declaration.name).map_err(|_| Error::InvalidString)?;
+            if quick_xml::escape::resolve_predefined_entity(name).is_some()
+                || !declared.insert(name)
+            {
+                continue;
+            }
+
+            let value = std::str::from_utf8(declaration.value).map_err(|_| Error::InvalidString)?;
+            entities.insert(name, replacement_text(value)?);
+        }
+        Ok(())
+    }
+
+    fn is_declared(&self, name: &str) -> bool {
+        self.entities.get(name).is_some()
+    }
+
+    /// Resolves a reference in text to its text.
+    fn resolve<'a>(&self, bytes_ref: &BytesRef<'a>) -> Result<Cow<'a, str>, Error> {
+        if let Some(ch) = bytes_ref.resolve_char_ref()? {
+            return Ok(Cow::Owned(ch.to_string()));
+        }
+
+        let name = bytes_ref.decode()?;
+        match quick_xml::escape::resolve_predefined_entity(&name) {
+            Some(resolved) => Ok(Cow::Borrowed(resolved)),
+            None => self.expand(&name, false).map(Cow::Owned),
+        }
+    }
+
+    /// Expands a reference to a declared entity. If `normalize` is set, whitespace written literally in the replacement text is replaced by spaces, as in attribute value normalization.
+    fn expand(&self, name: &str, normalize: bool) -> Result<String, Error> {
+        let mut expanded = String::new();
+        self.expand_into(name, normalize, &mut Vec::new(), &mut expanded)?;
+        Ok(expanded)
+    }
+
+    fn expand_into<'e>(
+        &'e self,
+        name: &str,
+        normalize: bool,
+        open: &mut Vec<&'e str>,
+        expanded: &mut String,
+    ) -> Result<(), Error> {
+        let Some((name, replacement_text)) = self.entities.entities.get_key_value(name) else {
+            return Err(xmlity::escape::UnescapeError::UnknownEntity(name.to_owned()).into());
+        };
+        if open.contains(&name.as_str()) {
+            return Err(Error::RecursiveEntity { name: name.clone() });
+        }
+        if let Some(limit) = self.limits.depth.filter(|limit| open.len() >= *limit) {
+            return Err(Error::EntityDepthLimitExceeded { limit });
+        }
+        // Charging the replacement text instead of the expanded text also limits entities that expand to nothing.
+        let total = self.expanded.get() + replacement_text.len();
+        if let Some(limit) = self.limits.expansion.filter(|limit| total > *limit) {
+            return Err(Error::EntityExpansionLimitExceeded { limit });
+        }
+        self.expanded.set(total);
+        if replacement_text.contains('<') {
+            return Err(Error::EntityMarkup { name: name.clone() });
+        }
+
+        let push_text = |expanded: &mut String, text: &str| {
+            if normalize {
+                expanded.extend(text.chars().map(|c| match c {
+                    '\t' | '\n' | '\r' => ' ',
+                    c => c,
+                }));
+            } else {
+                expanded.push_str(text);
+            }
+        };
+
+        open.push(name);
+        let mut position = 0;
+        while let Some(offset) = replacement_text[position..].find('&') {
+            let start = position + offset;
+            let end = replacement_text[start..]
+                .find(';')
+                .map(|end| start + end)
+                .ok_or(xmlity::escape::UnescapeError::UnterminatedEntity(start))?;
+
+            push_text(expanded, &replacement_text[position..start]);
+            let reference = &replacement_text[start + 1..end];
+            if reference.starts_with('#')
+                || quick_xml::escape::resolve_predefined_entity(reference).is_some()
+            {
+                expanded.push_str(&xmlity::escape::unescape(&replacement_text[start..=end])?);
+            } else {
+                self.expand_into(reference, normalize, open, expanded)?;
+            }
+            position = end + 1;
+        }
+        push_text(expanded, &replacement_text[position..]);
+        open.pop();
+
+        Ok(())
+    }
+}
+
+/// An enclosing element of the current position of a [`Reader`] that declares namespaces or sets `xml:space`, which changes how its content is read. It is linked to the closest enclosing element that does the same.
+#[derive(Debug)]
+struct Scope {
+    /// The depth of the element, which is left at the end tag at that depth.
+    depth: i16,
+    /// Empty elements enclose nothing after themselves, so they are left as soon as the next event is read.
+    empty: bool,
+    parent: Option<Rc<Scope>>,
+    /// The namespaces declared on the element. The default namespace is declared with [`Prefix::BLANK`], and a missing namespace undeclares the prefix.
+    declarations: Vec<(PrefixBuf, Option<XmlNamespaceBuf>)>,
+    /// Whether whitespace in the element is preserved, as set by the closest `xml:space` attribute.
+    preserves_space: bool,
+}
+
+impl Scope {
+    fn new(start: &BytesStart<'_>, parent: Option<Rc<Scope>>) -> Self {
+        Self {
+            depth: 0,
+            empty: false,
+            declarations: namespace_declarations(start),
+            preserves_space: xml_space(start)
+                .unwrap_or_else(|| parent.as_ref().is_some_and(|parent| parent.preserves_space)),
+            parent,
+        }
+    }
+
+    /// Returns this scope and the scopes enclosing it, starting with this one.
+    fn scopes(&self) -> impl Iterator<Item = &Scope> {
+        std::iter::successors(Some(self), |scope| scope.parent.as_deref())
+    }
+
+    /// Resolves a name in the scope of this element.
+    fn resolve_qname<'a>(
+        &'a self,
+        qname: QuickName<'a>,
+        attribute: bool,
+    ) -> Result<ExpandedName<'a>, Error> {
+        let (_, local_name) = QName::from_quick_xml(qname)?.into_parts();
+
+        Ok(ExpandedName::new(
+            local_name,
+            resolve_namespace_in(Some(self), qname, attribute),
+        ))
+    }
+
+    /// Resolves a prefix in the scope of this element, where a missing prefix refers to the default namespace.
+    fn resolve_prefix(&self, prefix: &Prefix) -> Option<&XmlNamespace> {
+        self.scopes()
+            .find_map(|scope| {
+                scope
+                    .declarations
+                    .iter()
+                    .find(|(declared, _)| **declared == *prefix)
+            })
+            .map_or_else(
+                || predefined_namespace(prefix),
+                |(_, namespace)| namespace.as_deref(),
+            )
+    }
+}
+
+/// The input of a [`Reader`], shared between its clones.
+///
+/// The start tags of the elements enclosing a position are only needed to look up the attributes of ancestors, so instead of keeping them while reading, they are found by reading the input again up to that position when they are looked up.
+#[derive(Debug)]
+struct Input<'i> {
+    bytes: &'i [u8],
+    /// Where the elements of the input start, after any doctype declaration, which is read with more care than quick-xml gives it.
+    body_start: Cell<usize>,
+    /// How far the input has been read again. Lookups further along continue from there, so reading a document from start to end only reads it again once.
+    scanned: RefCell<ScannedInput>,
+}
+
+impl<'i> Input<'i> {
+    /// Returns the offset of a part of the input.
+    fn offset_of(&self, part: &[u8]) -> Option<usize> {
+        (part.as_ptr() as usize)
+            .checked_sub(self.bytes.as_ptr() as usize)
+            .filter(|offset| *offset <= self.bytes.len())
+    }
+
+    /// Reads the start tag at an offset again.
+    fn start_at(&self, offset: usize) -> Option<BytesStart<'i>> {
+        match quick_xml::Reader::from_reader(&self.bytes[offset..]).read_event() {
+            Ok(Event::Start(start) | Event::Empty(start)) => Some(start),
+            _ => None,
+        }
+    }
+}
+
+/// The offsets of tags are kept instead of the tags, which would tie the lifetime of the input to the cell.
+#[derive(Debug, Default)]
+struct ScannedInput {
+    position: usize,
+    /// The offsets of the start tags of the elements that are open at the position, starting with the outermost one.
+    open: Vec<usize>,
+    /// The offset of the empty element that ends at the position, if any.
+    empty: Option<usize>,
+}
+
+/// Resolves the namespace of a name in the scope of an element. Like quick-xml, names without a prefix are only in the default namespace if they are not attribute names.
+fn resolve_namespace_in<'a>(
+    scope: Option<&'a Scope>,
+    qname: QuickName<'_>,
+    attribute: bool,
+) -> Option<&'a XmlNamespace> {
+    let prefix = match qname.prefix() {
+        Some(prefix) => Prefix::new(std::str::from_utf8(prefix.into_inner()).ok()?).ok()?,
+        None if attribute => return None,
+        None => Prefix::BLANK,
+    };
+
+    match scope {
+        Some(scope) => scope.resolve_prefix(prefix),
+        None => predefined_namespace(prefix),
+    }
+}
+
+/// Returns the namespace of the prefixes that are bound without being declared.
+fn predefined_namespace(prefix: &Prefix) -> Option<&'static XmlNamespace> {
+    match prefix.as_str() {
+        "xml" => Some(XmlNamespace::XML),
+        "xmlns" => Some(XmlNamespace::XMLNS),
+        _ => None,
+    }
+}
+
+/// Returns whether an element preserves whitespace according to its `xml:space` attribute, or [`None`] if it inherits the setting of its parent. Values other than `preserve` and `default` are ignored.
+fn xml_space(start: &BytesStart<'_>) -> Option<bool> {
+    let attribute = start
+        .attributes()
+        .with_checks(false)
+        .filter_map(Result::ok)
+        .find(|attribute| attribute.key.as_ref() == b"xml:space")?;
+
+    match &*attribute.value {
+        b"preserve" => Some(true),
+        b"default" => Some(false),
+        _ => None,
+    }
+}
+
+/// Returns the namespaces declared on an element. Unlike quick-xml, which uses the values of the declarations as written, entity and character references in them are resolved, since declarations are written escaped like other attribute values.
+fn namespace_declarations(start: &BytesStart<'_>) -> Vec<(PrefixBuf, Option<XmlNamespaceBuf>)> {
+    start
+        .attributes()
+        .with_checks(false)
+        .filter_map(Result::ok)
+        .filter_map(|attribute| {
+            let prefix = match attribute.key.as_namespace_binding()? {
+                PrefixDeclaration::Default => Prefix::BLANK.to_owned(),
+                PrefixDeclaration::Named(prefix) => {
+                    PrefixBuf::new(std::str::from_utf8(prefix).ok()?.to_owned()).ok()?
+                }
+            };
+            let namespace = match &*attribute.value {
+                [] => None,
+                value => {
+                    let value = xmlity::escape::unescape_attr(std::str::from_utf8(value).ok()?);
+                    Some(XmlNamespaceBuf::new(value.ok()?.into_owned()).ok()?)
+                }
+            };
+            Some((prefix, namespace))
+        })
+        .collect()
+}
+
+/// This reader wraps the `quick_xml::NsReader` and provides a way to peek and read events from the XML stream, as well as observe the depth and the enclosing elements, which are properties used when deserializing.
+#[derive(Debug, Clone)]
+struct Reader<'i> {
+    reader: NsReader<&'i [u8]>,
+    current_depth: i16,
+    peeked_event: Option<Event<'i>>,
+    /// An event that was read to find the end of the event before it, which the next read returns. It is boxed to keep the reader small, since deserializers are copied at every level of nesting.
+    buffered_event: Option<Box<Event<'i>>>,
+    scopes: Option<Rc<Scope>>,
+    trim_text_start: bool,
+    trim_text_end: bool,
+    /// Whether the last tag that was read is self-closing, so the element it starts is still open although the position is past its tag.
+    in_self_closing_tag: bool,
+    entities: EntityResolver,
+    input: Rc<Input<'i>>,
+    /// The position in the input after the last tag that was read.
+    position: usize,
+}
+impl<'i> Reader<'i> {
+    /// Create a new deserializer from a [`NsReader<&'i [u8]>`].
+    pub fn new(mut reader: NsReader<&'i [u8]>) -> Self {
+        // Text is trimmed here instead of by quick-xml, which trims the pieces of text between entity references separately.
+        let config = reader.config_mut();
+        let (trim_text_start, trim_text_end) = (config.trim_text_start, config.trim_text_end);
+        config.trim_text(false);
+
+        let input = Rc::new(Input {
+            bytes: reader.get_ref(),
+            body_start: Cell::new(0),
+            scanned: RefCell::default(),
+        });
+
+        Self {
+            reader,
+            current_depth: 0,
+            peeked_event: None,
+            buffered_event: None,
+            scopes: None,
+            trim_text_start,
+            trim_text_end,
+            in_self_closing_tag: false,
+            entities: EntityResolver::new(),
+            input,
+            position: 0,
+        }
+    }
+
+    fn read_event(&mut self) -> Result<Option<Event<'i>>, Error> {
+        if let Some(error) = self.entities.error.get() {
+            return Err(error.clone());
+        }
+
+        loop {
+            let event = self.read_joined_event()?;
+            let Some(Event::Text(mut text)) = event else {
+                return Ok(event);
+            };
+
+            if self.preserves_space() {
+                return Ok(Some(Event::Text(text)));
+            }
+
+            if (self.trim_text_start && text.inplace_trim_start())
+                || (self.trim_text_end && text.inplace_trim_end())
+            {
+                continue;
+            }
+
+            return Ok(Some(Event::Text(text)));
+        }
+    }
+
+    fn check_end_names(&self) -> bool {
+        self.reader.config().check_end_names
+    }
+
+    /// Reads a doctype declaration if one is next. quick-xml ends a declaration at the first `>` that balances a `<`, even if it is in a literal, comment or conditional section of the internal subset, so the end of the declaration is found here instead. Since a doctype can only come before the root element, a new reader can then take over after it without losing any namespace bindings.
+    fn read_doctype(&mut self) -> Option<Event<'i>> {
+        if self.current_depth > 0 || self.in_self_closing_tag {
+            return None;
+        }
+
+        // The `<` has already been read if quick-xml has stopped at it after reading text.
+        let rest: &'i [u8] = self.reader.get_mut();
+        let markup = rest.strip_prefix(b"<").unwrap_or(rest);
+        if !markup
+            .get(..b"!DOCTYPE".len())
+            .is_some_and(|keyword| keyword.eq_ignore_ascii_case(b"!DOCTYPE"))
+        {
+            return None;
+        }
+
+        let declaration = &markup[b"!DOCTYPE".len()..];
+        let len = XmlValueDoctype::declaration_len(declaration)?;
+        let content = std::str::from_utf8(declaration[..len - 1].trim_ascii_start()).ok()?;
+        if content.is_empty() {
+            // quick-xml reports the missing name.
+            return None;
+        }
+
+        let body = &declaration[len..];
+        let mut reader = NsReader::from_reader(body);
+        *reader.config_mut() = self.reader.config().clone();
+        self.reader = reader;
+        if let Some(body_start) = self.input.offset_of(body) {
+            self.input.body_start.set(body_start);
+        }
+
+        let declared = self.entities.declare(&XmlValueDoctype::new(content));
+        let _ = self.entities.record(declared);
+
+        Some(Event::DocType(BytesText::from_escaped(content)))
+    }
+
+    /// Reads the next event from quick-xml, or the event that was read to find the end of the event before it.
+    fn read_raw_event(&mut self) -> Result<Event<'i>, Error> {
+        if let Some(event) = self.buffered_event.take() {
+            return Ok(*event);
+        }
+
+        if let Some(doctype) = self.read_doctype() {
+            return Ok(doctype);
+        }
+
+        Ok(self.reader.read_event()?)
+    }
+
+    fn read_joined_event(&mut self) -> Result<Option<Event<'i>>, Error> {
+        let event = match self.read_raw_event()? {
+            Event::Eof => return Ok(None),
+            event @ (Event::Text(_) | Event::GeneralRef(_)) => event,
+            Event::CData(cdata) => return self.join_cdata(cdata).map(Some),
+            event => return Ok(Some(event)),
+        };
+
+        // quick-xml splits text at entity references, so adjacent text and references are joined back into one text event.
+        let mut text: Option<String> = None;
+        loop {
+            let next = self.read_raw_event()?;
+            if !matches!(next, Event::Text(_) | Event::GeneralRef(_)) {
+                self.buffered_event = Some(Box::new(next));
+                break;
+            }
+
+            let text = match &mut text {
+                Some(text) => text,
+                None => text.insert(self.text_content(&event)?.into_owned()),
+            };
+            text.push_str(&self.text_content(&next)?);
+        }
+
+        Ok(Some(match (text, event) {
+            (Some(text), _) => Event::Text(BytesText::from_escaped(text)),
+            (None, Event::GeneralRef(bytes_ref)) => Event::Text(BytesText::from_escaped(
+                self.resolve_general_ref(&bytes_ref)?,
+            )),
+            (None, event) => event,
+        }))
+    }
+
+    /// Resolves a reference in text to its text. If a reference can't be resolved, reading fails from then on, so the error isn't lost when the failed read is taken as the end of the input.
+    fn resolve_general_ref<'a>(&self, bytes_ref: &BytesRef<'a>) -> Result<Cow<'a, str>, Error> {
+        self.entities.record(self.entities.resolve(bytes_ref))
+    }
+
+    fn text_content<'a>(&self, event: &Event<'a>) -> Result<Cow<'a, str>, Error> {
+        match event {
+            Event::Text(bytes_text) => std::str::from_utf8(bytes_text)
+                .map(|text| Cow::Owned(text.to_owned()))
+                .map_err(|_| Error::InvalidString),
+            Event::GeneralRef(bytes_ref) => self.resolve_general_ref(bytes_ref),
+            _ => unreachable!("Only text and references are joined"),
+        }
+    }
+
+    /// Joins CDATA sections that were split to escape a `]]>` in them, which serializers do by ending a section after the `]]` and starting the next with the `>`. Other adjacent sections stay separate.
+    fn join_cdata(&mut self, cdata: BytesCData<'i>) -> Result<Event<'i>, Error> {
+        let mut joined: Option<Vec<u8>> = None;
+        loop {
+            let content = joined.as_deref().unwrap_or(&cdata);
+            if !content.ends_with(b"]]") {
+                break;
+            }
+
+            let next = self.read_raw_event()?;
+            let Event::CData(next) = next else {
+                self.buffered_event = Some(Box::new(next));
+                break;
+            };
+            if !next.starts_with(b">") {
+                self.buffered_event = Some(Box::new(Event::CData(next)));
+                break;
+            }
+
+            joined
+                .get_or_insert_with(|| cdata.to_vec())
+                .extend_from_slice(&next);
+        }
+
+        Ok(Event::CData(match joined {
+            Some(joined) => {
+                BytesCData::new(String::from_utf8(joined).map_err(|_| Error::InvalidString)?)
+            }
+            None => cdata,
+        }))
+    }
+
+    pub fn peek_event(&mut self) -> Result<Option<&Event<'i>>, Error> {
+        if self.peeked_event.is_some() {
+            return Ok(self.peeked_event.as_ref());
+        }
+
+        self.peeked_event = self.read_event()?;
+        Ok(self.peeked_event.as_ref())
+    }
+
+    pub fn next_event(&mut self) -> Result<Option<Event<'i>>, Error> {
+        let event = if self.peeked_event.is_some() {
+            self.peeked_event.take()
+        } else {
+            self.read_event()?
+        };
+
+        if self.scopes.as_ref().is_some_and(|scope| scope.empty) {
+            self.leave_scope();
+        }
+        self.in_self_closing_tag = false;
+
+        match &event {
+            Some(Event::End(bytes)) => {
+                if self
+                    .scopes
+                    .as_ref()
+                    .is_some_and(|scope| scope.depth == self.current_depth)
+                {
+                    self.leave_scope();
+                }
+                self.current_depth -= 1;
+                self.move_past_tag(bytes);
+            }
+            Some(Event::Start(bytes)) => {
+                self.current_depth += 1;
+                self.enter_scope(bytes, false);
+                self.move_past_tag(bytes);
+            }
+            Some(Event::Empty(bytes)) => {
+                self.enter_scope(bytes, true);
+                self.move_past_tag(bytes);
+            }
+            _ => {}
+        }
+
+        Ok(event)
+    }
+
+    /// Enters an element, which is only kept as a scope if it declares namespaces or sets `xml:space`.
+    fn enter_scope(&mut self, start: &BytesStart<'_>, empty: bool) {
+        let declarations = namespace_declarations(start);
+        let preserves_space = xml_space(start);
+        if declarations.is_empty() && preserves_space.is_none() {
+            return;
+        }
+
+        let parent = self.scopes.take();
+        self.scopes = Some(Rc::new(Scope {
+            depth: self.current_depth + i16::from(empty),
+            empty,
+            preserves_space: preserves_space
+                .unwrap_or_else(|| parent.as_ref().is_some_and(|parent| parent.preserves_space)),
+            declarations,
+            parent,
+        }));
+    }
+
+    fn leave_scope(&mut self) {
+        self.scopes = self.scopes.take().and_then(|scope| scope.parent.clone());
+    }
+
+    /// Moves the position past a tag that has been read, given the part of the tag that quick-xml reports.
+    fn move_past_tag(&mut self, content: &[u8]) {
+        let Some(content_end) = self
+            .input
+            .offset_of(content)
+            .map(|offset| offset + content.len())
+        else {
+            return;
+        };
+        let Some(tag_len) = self.input.bytes[content_end..]
+            .iter()
+            .position(|byte| *byte == b'>')
+        else {
+            return;
+        };
+
+        self.position = content_end + tag_len + 1;
+        self.in_self_closing_tag = self.input.bytes[..self.position].ends_with(b"/>");
+    }
+
+    /// Returns whether whitespace is preserved at the current position. An empty element is still the closest scope while the event after it is peeked at, but does not enclose it.
+    pub fn preserves_space(&self) -> bool {
+        let mut scope = self.scopes.as_deref();
+        if scope.is_some_and(|scope| scope.empty) {
+            scope = scope.and_then(|scope| scope.parent.as_deref());
+        }
+
+        scope.is_some_and(|scope| scope.preserves_space)
+    }
+
+    /// Returns the start tags of the enclosing elements, starting with the outermost one.
+    pub fn ancestors(&self) -> Vec<BytesStart<'i>> {
+        let mut scanned = self.input.scanned.borrow_mut();
+        let body_start = self.input.body_start.get();
+        if self.position < body_start {
+            return Vec::new();
+        }
+        if scanned.position > self.position || scanned.position < body_start {
+            *scanned = ScannedInput {
+                position: body_start,
+                ..ScannedInput::default()
+            };
+        }
+
+        let mut reader =
+            quick_xml::Reader::from_reader(&self.input.bytes[scanned.position..self.position]);
+        reader.config_mut().check_end_names = false;
+        loop {
+            let event = match reader.read_event() {
+                Ok(Event::Eof) | Err(_) => break,
+                Ok(event) => event,
+            };
+
+            // The `<` comes right before the name that starts the content of a tag.
+            let tag_offset = |start: &BytesStart<'_>| Some(self.input.offset_of(start)? - 1);
+            scanned.empty = None;
+            match event {
+                Event::Start(start) => scanned.open.extend(tag_offset(&start)),
+                Event::End(_) => {
+                    scanned.open.pop();
+                }
+                Event::Empty(start) => scanned.empty = tag_offset(&start),
+                _ => {}
+            }
+        }
+        scanned.position = self.position;
+
+        let empty = scanned.empty.filter(|_| self.in_self_closing_tag);
+        scanned
+            .open
+            .iter()
+            .chain(&empty)
+            .filter_map(|offset| self.input.start_at(*offset))
+            .collect()
+    }
+
+    pub fn resolve_qname<'a>(
+        &'a self,
+        qname: QuickName<'a>,
+        attribute: bool,
+    ) -> Result<ExpandedName<'a>, Error> {
+        let (_, local_name) = QName::from_quick_xml(qname)?.into_parts();
+        let namespace = self.resolve_namespace(qname, attribute);
+
+        Ok(ExpandedName::new(local_name, namespace))
+    }
+
+    /// Resolves the namespace of a name in the scope of the enclosing elements.
+    ///
+    /// The scope is tracked alongside the enclosing elements instead of taken from quick-xml, whose scope already includes the elements that have only been peeked at.
+    pub fn resolve_namespace<'a>(
+        &'a self,
+        qname: QuickName<'_>,
+        attribute: bool,
+    ) -> Option<&'a XmlNamespace> {
+        resolve_namespace_in(self.scopes.as_deref(), qname, attribute)
+    }
+
+    /// Resolves the name of an element that has been peeked at, which is in the scope of its own declarations.
+    pub fn resolve_peeked_qname(&self, start: &BytesStart<'i>) -> Result<ExpandedNameBuf, Error> {
+        let scope = Scope::new(start, self.scopes.clone());
+        Ok(scope.resolve_qname(start.name(), false)?.into_owned())
+    }
+
+    /// Returns the namespaces in scope, starting with the ones declared on the closest enclosing element.
+    pub fn namespaces_in_scope(&self) -> Vec<(PrefixBuf, XmlNamespaceBuf)> {
+        let mut namespaces: Vec<(PrefixBuf, Option<XmlNamespaceBuf>)> = Vec::new();
+        for (prefix, namespace) in self
+            .scopes
+            .iter()
+            .flat_map(|scope| scope.scopes())
+            .flat_map(|scope| &scope.declarations)
+        {
+            if namespaces.iter().all(|(declared, _)| declared != prefix) {
+                namespaces.push((prefix.clone(), namespace.clone()));
+            }
+        }
+
+        namespaces
+            .into_iter()
+            .filter_map(|(prefix, namespace)| Some((prefix, namespace?)))
+            .collect()
+    }
+
+    pub fn current_depth(&self) -> i16 {
+        self.current_depth
+    }
+}
+
+/// A struct to hold external data that can be used during deserialization.
+#[derive(Debug)]
+pub struct ExternalData {
+    data: HashMap<core::any::TypeId, Box<dyn core::any::Any>>,
+}
+
+impl ExternalData {
+    /// Creates a new [`ExternalData`] instance.
+    pub fn new() -> Self {
+        Self {
+            data: HashMap::new(),
+        }
+    }
+
+    /// Inserts data into the external data map.
+    pub fn insert<T: 'static>(&mut self, data: T) {
+        self.data.insert(
+            core::any::TypeId::of::<T>(),
+            Box::new(data) as Box<dyn core::any::Any>,
+        );
+    }
+
+    /// Retrieves data from the external data map.
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        self.data
+            .get(&core::any::TypeId::of::<T>())
+            .and_then(|data| data.downcast_ref::<T>())
+    }
+}
+
+impl Default for ExternalData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A table of general entities and their replacement text, which references like `&name;` in text and attribute values are expanded to, see [`Deserializer::with_entities`].
+///
+/// The replacement text can refer to other entities, which are expanded in turn, and to characters with character references, but can't contain markup.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Entities {
+    entities: HashMap<String, String>,
+}
+
+impl Entities {
+    /// Creates an empty [`Entities`] table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an entity, replacing any entity with the same name.
+    pub fn with_entity(
+        mut self,
+        name: impl Into<String>,
+        replacement_text: impl Into<String>,
+    ) -> Self {
+        self.insert(name, replacement_text);
+        self
+    }
+
+    /// Inserts an entity into the table, replacing any entity with the same name.
+    pub fn insert(&mut self, name: impl Into<String>, replacement_text: impl Into<String>) {
+        self.entities.insert(name.into(), replacement_text.into());
+    }
+
+    /// Returns the replacement text of an entity.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.entities.get(name).map(String::as_str)
+    }
+}
+
+/// The [`xmlity::Deserializer`] for the `quick-xml` crate.
+///
+/// This currently only supports an underlying reader of type `&[u8]` due to limitations in the `quick-xml` crate.
+#[derive(Clone)]
+pub struct Deserializer<'i> {
+    reader: Reader<'i>,
+    // Limit depth
+    limit_depth: i16,
+    external_data: Option<Rc<ExternalData>>,
+    name_resolver: Option<Rc<dyn NameResolver>>,
+    preserve_prefixes: bool,
+    normalize_attribute_values: bool,
+    allow_duplicate_attributes: bool,
+    skip_prolog: bool,
+    recursion_limit: Option<usize>,
+    // Shared between all sub-deserializers, so that exceeding the limit can't be hidden by types that ignore failed children.
+    recursion_limit_exceeded: Rc<Cell<bool>>,
+}
+
+impl fmt::Debug for Deserializer<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Deserializer")
+            .field("reader", &self.reader)
+            .field("limit_depth", &self.limit_depth)
+            .field("external_data", &self.external_data)
+            .field("name_resolver", &self.name_resolver.is_some())
+            .field("preserve_prefixes", &self.preserve_prefixes)
+            .field(
+                "normalize_attribute_values",
+                &self.normalize_attribute_values,
+            )
+            .field(
+                "allow_duplicate_attributes",
+                &self.allow_duplicate_attributes,
+            )
+            .field("skip_prolog", &self.skip_prolog)
+            .field("recursion_limit", &self.recursion_limit)
+            .finish()
+    }
+}
+
+impl<'i> From<NsReader<&'i [u8]>> for Deserializer<'i> {
+    fn from(reader: NsReader<&'i [u8]>) -> Self {
+        Self::new(reader)
+    }
+}
+
+impl<'i> From<&'i [u8]> for Deserializer<'i> {
+    fn from(buffer: &'i [u8]) -> Self {
+        Self::new(NsReader::from_reader(buffer))
+    }
+}
+
+impl<'i> Deserializer<'i> {
+    /// The default maximum number of nested elements, see [`Deserializer::with_recursion_limit`].
+    pub const DEFAULT_RECURSION_LIMIT: usize = 128;
+
+    /// The default maximum number of entity references nested in the replacement text of each other, see [`Deserializer::with_entity_depth_limit`].
+    pub const DEFAULT_ENTITY_DEPTH_LIMIT: usize = 16;
+
+    /// The default maximum number of bytes of replacement text that the entity references of a document expand to, see [`Deserializer::with_entity_expansion_limit`].
+    pub const DEFAULT_ENTITY_EXPANSION_LIMIT: usize = 8 * 1024 * 1024;
+
+    /// Create a new deserializer from a [`NsReader<&'i [u8]>`].
+    pub fn new(reader: NsReader<&'i [u8]>) -> Self {
+        Self {
+            reader: Reader::new(reader),
+            limit_depth: 0,
+            external_data: None,
+            name_resolver: None,
+            preserve_prefixes: false,
+            normalize_attribute_values: true,
+            allow_duplicate_attributes: false,
+            skip_prolog: false,
+            recursion_limit: Some(Self::DEFAULT_RECURSION_LIMIT),
+            recursion_limit_exceeded: Rc::new(Cell::new(false)),
+        }
+    }
+
+    /// Create a new deserializer reading from a buffer with the given reader options.
+    pub fn with_reader_options(buffer: &'i [u8], options: &ReaderOptions) -> Self {
+        let mut reader = NsReader::from_reader(buffer);
+        options.apply(reader.config_mut());
+        Self::new(reader)
+    }
+
+    /// Create a new deserializer from an already configured [`NsReader<&'i [u8]>`].
+    ///
+    /// All configuration of the reader (such as [`quick_xml::reader::Config::trim_text`] or [`quick_xml::reader::Config::check_comments`]) is kept as-is, and any events that have already been read from it are not seen by the deserializer.
+    pub fn from_reader(reader: NsReader<&'i [u8]>) -> Self {
+        Self::new(reader)
+    }
+
+    /// Set the external data for the deserializer.
+    pub fn with_external_data(mut self, external_data: ExternalData) -> Self {
+        self.external_data = Some(Rc::new(external_data));
+        self
+    }
+
+    /// Set the [`NameResolver`] used to map the names of encountered elements and attributes to canonical names.
+    pub fn with_name_resolver<R: NameResolver + 'static>(mut self, name_resolver: R) -> Self {
+        self.name_resolver = Some(Rc::new(name_resolver));
+        self
+    }
+
+    /// Set if the prefixes of elements and attributes should be preserved as they were written.
+    ///
+    /// When enabled, [`ElementAccess::prefix`](de::ElementAccess::prefix) and [`AttributeAccess::prefix`](de::AttributeAccess::prefix) report the prefix of each name and [`ElementAccess::namespace_declarations`](de::ElementAccess::namespace_declarations) reports the namespace declarations of each element, which [`XmlElement`](xmlity::value::XmlElement) and [`XmlAttribute`](xmlity::value::XmlAttribute) record so that they are serialized again with the same prefixes and declarations. This is useful for documents that must round trip faithfully, such as signed documents.
+    pub fn with_preserve_prefixes(mut self, preserve_prefixes: bool) -> Self {
+        self.preserve_prefixes = preserve_prefixes;
+        self
+    }
+
+    /// Set if attribute values should be normalized as described in [section 3.3.3 of the XML 1.0 specification](https://www.w3.org/TR/xml/#AVNormalize). Enabled by default.
+    ///
+    /// When enabled, tabs and line breaks written literally in attribute values are read as spaces, while those written as character references are kept. When disabled, attribute values are only unescaped, keeping the raw whitespace of the document.
+    pub fn with_normalize_attribute_values(mut self, normalize_attribute_values: bool) -> Self {
+        self.normalize_attribute_values = normalize_attribute_values;
+        self
+    }
+
+    /// Set if elements may have several attributes with the same name, which XML does not allow but some tools produce anyway. Disabled by default, which makes reading the attributes of such elements fail.
+    ///
+    /// When enabled, repeated attributes are read one after another like any other attributes, so they can be merged into one field with `#[xattribute(extendable)]`.
+    pub fn with_allow_duplicate_attributes(mut self, allow_duplicate_attributes: bool) -> Self {
+        self.allow_duplicate_attributes = allow_duplicate_attributes;
+        self
+    }
+
+    /// Set if an XML declaration, doctype, comments, processing instructions and whitespace should be skipped when they come before an element at the top level of the input. Disabled by default, which makes them part of the input like any other node.
+    ///
+    /// This is useful for fragments handed over by protocols that embed XML, such as XMPP-like streams, which may start with a declaration or whitespace before the element that is deserialized. Nodes that are not followed by an element are kept, so values that are not elements are read as usual.
+    pub fn with_skip_prolog(mut self, skip_prolog: bool) -> Self {
+        self.skip_prolog = skip_prolog;
+        self
+    }
+
+    /// Set the maximum number of nested elements that can be deserialized, or `None` to not limit it. Defaults to [`Deserializer::DEFAULT_RECURSION_LIMIT`].
+    ///
+    /// Deserializing recursive types such as `struct Node { children: Vec<Node> }` uses more stack the deeper the document is nested, so without a limit, deeply nested documents can overflow the stack and abort the process. With a limit, such documents instead fail with [`Error::RecursionLimitExceeded`].
+    ///
+    /// Only disable the limit for trusted documents, or when deserializing on a thread with a large enough stack.
+    pub fn with_recursion_limit(mut self, recursion_limit: Option<usize>) -> Self {
+        self.recursion_limit = recursion_limit;
+        self
+    }
+
+    /// Set the entities that references like `&name;` in text and attribute values are expanded to, in addition to the predefined entities and character references.
+    ///
+    /// Entities declared in the internal subset of the doctype of a document, like `<!ENTITY name "replacement text">`, are added to these when the doctype is read, replacing any with the same name, since XML reads the internal subset before any external one. References to entities that are declared neither way fail with [`Error::Unescape`].
+    pub fn with_entities(mut self, entities: Entities) -> Self {
+        self.reader.entities.entities = Rc::new(entities);
+        self
+    }
+
+    /// Set the maximum number of entity references that can be nested in the replacement text of each other, or `None` to not limit it. Defaults to [`Deserializer::DEFAULT_ENTITY_DEPTH_LIMIT`].
+    ///
+    /// Deeper nesting fails with [`Error::EntityDepthLimitExceeded`]. Entities that refer to themselves fail with [`Error::RecursiveEntity`] regardless of the limit.
+    pub fn with_entity_depth_limit(mut self, entity_depth_limit: Option<usize>) -> Self {
+        Rc::make_mut(&mut self.reader.entities.limits).depth = entity_depth_limit;
+        self
+    }
+
+    /// Set the maximum number of bytes of replacement text that the entity references of a document can expand to in total, or `None` to not limit it. Defaults to [`Deserializer::DEFAULT_ENTITY_EXPANSION_LIMIT`].
+    ///
+    /// An entity that refers to another entity several times grows exponentially with each level of nesting, so a document of a few hundred bytes like the "billion laughs" attack can expand to gigabytes of text. With a limit, such documents instead fail with [`Error::EntityExpansionLimitExceeded`] before using much time or memory.
+    ///
+    /// Only disable the limit for trusted documents.
+    pub fn with_entity_expansion_limit(mut self, entity_expansion_limit: Option<usize>) -> Self {
+        Rc::make_mut(&mut self.reader.entities.limits).expansion = entity_expansion_limit;
+        self
+    }
+
+    /// Returns the error of an entity reference that could not be resolved. The reader fails every read after such a reference, which the deserializer takes as the end of the input, so this is checked wherever that end is reached.
+    fn check_entities(&self) -> Result<(), Error> {
+        match self.entity_error() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    fn entity_error(&self) -> Option<Error> {
+        self.reader.entities.error.get().cloned()
+    }
+
+    fn check_recursion_limit(&self) -> Result<(), Error> {
+        let Some(limit) = self.recursion_limit else {
+            return Ok(());
+        };
+
+        if self.recursion_limit_exceeded.get() || self.reader.current_depth() as usize > limit {
+            self.recursion_limit_exceeded.set(true);
+            return Err(Error::RecursionLimitExceeded { limit });
+        }
+
+        Ok(())
+    }
+
+    fn attribute_value(&self, value: &[u8]) -> Result<Vec<u8>, Error> {
+        let value = std::str::from_utf8(value).map_err(|_| Error::InvalidString)?;
+        let unescape = |value| {
+            if self.normalize_attribute_values {
+                xmlity::escape::unescape_attr(value)
+            } else {
+                xmlity::escape::unescape(value)
+            }
+        };
+
+        // References to declared entities are expanded here, and the text between them is unescaped as usual.
+        let mut unescaped = String::new();
+        let mut position = 0;
+        for (start, _) in value.match_indices('&') {
+            let Some(end) = value[start..].find(';').map(|end| start + end) else {
+                break;
+            };
+            let name = &value[start + 1..end];
+            if !self.reader.entities.is_declared(name) {
+                continue;
+            }
+
+            unescaped.push_str(&unescape(&value[position..start])?);
+            let entities = &self.reader.entities;
+            unescaped.push_str(
+                &entities.record(entities.expand(name, self.normalize_attribute_values))?,
+            );
+            position = end + 1;
+        }
+        if position == 0 {
+            return Ok(unescape(value)?.into_owned().into_bytes());
+        }
+        unescaped.push_str(&unescape(&value[position..])?);
+
+        Ok(unescaped.into_bytes())
+    }
+
+    /// Returns the attributes of an element, which are only checked for duplicates if those are not allowed.
+    fn attributes<'a>(&self, bytes_start: &'a BytesStart<'_>) -> Attributes<'a> {
+        let mut attributes = bytes_start.attributes();
+        attributes.with_checks(!self.allow_duplicate_attributes);
+        attributes
     }
-}
 
-impl<'i> Deserializer<'i> {
-    /// Create a new deserializer from a [`NsReader<&'i [u8]>`].
-    pub fn new(reader: NsReader<&'i [u8]>) -> Self {
-        Self {
-            reader: Reader::new(reader),
-            limit_depth: 0,
-            external_data: None,
+    /// Skips a declaration, doctype, comments, processing instructions and whitespace at the top level if they are followed by an element, see [`Deserializer::with_skip_prolog`].
+    fn skip_prolog(&mut self) {
+        if !self.skip_prolog || self.limit_depth != 0 || self.reader.current_depth() != 0 {
+            return;
         }
-    }
 
-    /// Set the external data for the deserializer.
-    pub fn with_external_data(mut self, external_data: ExternalData) -> Self {
-        self.external_data = Some(Rc::new(external_data));
-        self
+        let mut reader = self.reader.clone();
+        loop {
+            match reader.peek_event() {
+                Ok(Some(Event::Decl(_) | Event::DocType(_) | Event::Comment(_) | Event::PI(_))) => {
+                }
+                Ok(Some(Event::Text(text)))
+                    if text
+                        .iter()
+                        .all(|byte| matches!(byte, b' ' | b'\t' | b'\r' | b'\n')) => {}
+                Ok(Some(Event::Start(_) | Event::Empty(_))) => {
+                    self.reader = reader;
+                    return;
+                }
+                _ => return,
+            }
+            let _ = reader.next_event();
+        }
     }
 
     fn read_until_end(&mut self) -> Result<(), Error> {
@@ -332,13 +1997,164 @@ impl<'i> Deserializer<'i> {
             reader: self.reader.clone(),
             limit_depth,
             external_data: self.external_data.clone(),
+            name_resolver: self.name_resolver.clone(),
+            preserve_prefixes: self.preserve_prefixes,
+            normalize_attribute_values: self.normalize_attribute_values,
+            allow_duplicate_attributes: self.allow_duplicate_attributes,
+            skip_prolog: self.skip_prolog,
+            recursion_limit: self.recursion_limit,
+            recursion_limit_exceeded: self.recursion_limit_exceeded.clone(),
         }
     }
 
-    fn resolve_qname<'a>(&'a self, qname: QuickName<'a>, attribute: bool) -> ExpandedName<'a> {
+    fn resolve_qname<'a>(
+        &'a self,
+        qname: QuickName<'a>,
+        attribute: bool,
+    ) -> Result<ExpandedName<'a>, Error> {
         self.reader.resolve_qname(qname, attribute)
     }
 
+    /// Checks that the name of an element is valid before it is visited.
+    fn check_element_name(&self, bytes_start: &BytesStart<'_>) -> Result<(), Error> {
+        self.resolve_qname(bytes_start.name(), false).map(|_| ())
+    }
+
+    /// Visits an element that has been started, which is the only kind of event that visits nested elements.
+    ///
+    /// It is kept apart from [`Deserializer::visit_event`] so that the stack frames of `deserialize_any`, which are repeated for every level of nesting, only hold what visiting an element needs.
+    fn visit_start<V>(&mut self, bytes_start: BytesStart<'i>, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'i>,
+    {
+        self.check_recursion_limit()?;
+        self.check_element_name(&bytes_start)?;
+
+        let mut sub = self.sub_deserializer(self.reader.current_depth());
+
+        let element = ElementAccess {
+            bytes_start: Some(&bytes_start),
+            start_depth: self.reader.current_depth(),
+            deserializer: Some(&mut sub),
+            empty: false,
+            attribute_index: 0,
+        };
+
+        // A child may have failed at an entity reference, after which the visitor fails for the missing content instead.
+        let value = visitor
+            .visit_element(element)
+            .map_err(|error| self.entity_error().unwrap_or(error))?;
+
+        self.end_element(sub, &bytes_start)?;
+
+        Ok(value)
+    }
+
+    /// Visits an event other than the start of an element.
+    fn visit_event<V>(&mut self, event: Event<'i>, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'i>,
+    {
+        match event {
+            Event::Start(_) => unreachable!("start elements are visited by `visit_start`"),
+            Event::End(_bytes_end) => Err(Error::custom("Unexpected end element")),
+            Event::Empty(bytes_start) => {
+                self.check_element_name(&bytes_start)?;
+
+                let value = visitor.visit_element(ElementAccess {
+                    bytes_start: Some(&bytes_start),
+                    start_depth: self.reader.current_depth(),
+                    deserializer: Some(self),
+                    empty: true,
+                    attribute_index: 0,
+                });
+
+                // An attribute may have exceeded a limit without the visitor returning its error.
+                self.check_entities()?;
+                value
+            }
+            Event::Text(bytes_text) => visitor.visit_text(DataWithD::new(bytes_text, self)),
+            Event::CData(bytes_cdata) => visitor.visit_cdata(DataWithD::new(bytes_cdata, self)),
+            Event::Comment(bytes_text) => visitor.visit_comment(DataWithD::new(bytes_text, self)),
+            Event::Decl(bytes_decl) => visitor.visit_decl(DataWithD::new(
+                ClearedByteDecl::try_from(&bytes_decl)?,
+                self,
+            )),
+            Event::PI(bytes_pi) => visitor.visit_pi(DataWithD::new(bytes_pi, self)),
+            Event::DocType(bytes_text) => visitor.visit_doctype(DataWithD::new(bytes_text, self)),
+            Event::Eof => Err(Error::custom("Unexpected EOF")),
+            Event::GeneralRef(bytes_ref) => {
+                let text = self.reader.resolve_general_ref(&bytes_ref)?;
+                visitor.visit_text(DataWithD::new(text, self))
+            }
+        }
+    }
+
+    /// Reads the rest of a visited element using the sub-deserializer it was visited with, followed by its end element.
+    fn end_element(&mut self, mut sub: Self, bytes_start: &BytesStart<'_>) -> Result<(), Error> {
+        // A child may have exceeded a limit without the visitor returning its error.
+        self.check_recursion_limit()?;
+        self.check_entities()?;
+
+        sub.read_until_end()?;
+
+        self.reader = sub.reader;
+        self.check_entities()?;
+
+        let end_event = self
+            .next_event()
+            .ok_or_else(|| Error::StartElementWithoutEnd {
+                name: String::from_utf8_lossy(bytes_start.name().0).to_string(),
+            })?;
+
+        if let Event::End(bytes_end) = &end_event {
+            if !self.reader.check_end_names() || bytes_end.name() == bytes_start.name() {
+                Ok(())
+            } else {
+                Err(Error::NoMatchingEndElement {
+                    start_name: String::from_utf8_lossy(bytes_start.name().0).to_string(),
+                    end_name: String::from_utf8_lossy(bytes_end.name().0).to_string(),
+                })
+            }
+        } else {
+            Err(Error::StartElementWithoutEnd {
+                name: String::from_utf8_lossy(bytes_start.name().0).to_string(),
+            })
+        }
+    }
+
+    /// Resolves the name of an encountered element or attribute, passing it through the [`NameResolver`] if one is set.
+    fn resolve_node_name<'a>(
+        &'a self,
+        qname: QuickName<'a>,
+        attribute: bool,
+    ) -> Result<ExpandedName<'a>, Error> {
+        Ok(self.resolve_name(self.resolve_qname(qname, attribute)?, attribute))
+    }
+
+    /// Passes a resolved name through the [`NameResolver`] if one is set.
+    fn resolve_name<'a>(&'a self, name: ExpandedName<'a>, attribute: bool) -> ExpandedName<'a> {
+        match (self.name_resolver.as_deref(), attribute) {
+            (None, _) => name,
+            (Some(resolver), false) => resolver.resolve_element_name(name),
+            (Some(resolver), true) => resolver.resolve_attribute_name(name),
+        }
+    }
+
+    /// Returns the prefix of a name as it was written, if prefixes are preserved.
+    fn written_prefix<'a>(&self, qname: QuickName<'a>) -> Option<&'a Prefix> {
+        if !self.preserve_prefixes {
+            return None;
+        }
+
+        match qname.prefix() {
+            None => Some(Prefix::BLANK),
+            Some(prefix) => std::str::from_utf8(prefix.into_inner())
+                .ok()
+                .and_then(|prefix| Prefix::new(prefix).ok()),
+        }
+    }
+
     fn resolve_namespace<'a>(
         &'a self,
         qname: QuickName<'_>,
@@ -346,6 +2162,42 @@ impl<'i> Deserializer<'i> {
     ) -> Option<&'a XmlNamespace> {
         self.reader.resolve_namespace(qname, attribute)
     }
+
+    /// Finds an attribute of the closest enclosing element with the given name.
+    fn find_ancestor_attribute(
+        &self,
+        ancestor: &ExpandedName<'_>,
+        attribute: &ExpandedName<'_>,
+    ) -> Option<Cow<'_, str>> {
+        let starts = self.reader.ancestors();
+        let mut scopes: Vec<Rc<Scope>> = Vec::with_capacity(starts.len());
+        for start in &starts {
+            scopes.push(Rc::new(Scope::new(start, scopes.last().cloned())));
+        }
+
+        // The names are resolved in the scope of the ancestor, since descendants may have redeclared their prefixes.
+        let (start, scope) = starts.iter().zip(&scopes).rev().find(|(start, scope)| {
+            scope
+                .resolve_qname(start.name(), false)
+                .is_ok_and(|name| self.resolve_name(name, false) == *ancestor)
+        })?;
+
+        let value = self
+            .attributes(start)
+            .filter_map(Result::ok)
+            .filter(|attr| attr.key.as_namespace_binding().is_none())
+            .find(|attr| {
+                scope
+                    .resolve_qname(attr.key, true)
+                    .is_ok_and(|name| self.resolve_name(name, true) == *attribute)
+            })?
+            .value;
+
+        self.attribute_value(&value)
+            .ok()
+            .and_then(|value| String::from_utf8(value).ok())
+            .map(Cow::Owned)
+    }
 }
 
 struct ElementAccess<'a, 'de> {
@@ -368,11 +2220,7 @@ const PLACEHOLDER_ELEMENT_NAME: &str = "a";
 
 impl DeserializeContext for &Deserializer<'_> {
     fn default_namespace(&self) -> Option<&XmlNamespace> {
-        let (_, namespace) = self
-            .resolve_qname(QuickName(PLACEHOLDER_ELEMENT_NAME.as_bytes()), false)
-            .into_parts();
-
-        namespace
+        self.resolve_namespace(QuickName(PLACEHOLDER_ELEMENT_NAME.as_bytes()), false)
     }
 
     fn resolve_prefix(&self, prefix: &xmlity::Prefix) -> Option<&XmlNamespace> {
@@ -388,28 +2236,48 @@ impl DeserializeContext for &Deserializer<'_> {
     {
         self.external_data.as_ref().and_then(|data| data.get::<T>())
     }
+
+    fn ancestor_attribute(
+        &self,
+        ancestor: &ExpandedName<'_>,
+        attribute: &ExpandedName<'_>,
+    ) -> Option<Cow<'_, str>> {
+        self.find_ancestor_attribute(ancestor, attribute)
+    }
+
+    fn namespaces_in_scope(&self) -> Vec<(PrefixBuf, XmlNamespaceBuf)> {
+        self.reader.namespaces_in_scope()
+    }
 }
 
-struct AttributeAccess<'a, 'v> {
+struct AttributeAccess<'a> {
     name: ExpandedName<'a>,
-    value: Cow<'v, [u8]>,
+    prefix: Option<&'a Prefix>,
+    /// The value as written in the document. It is only unescaped once it is deserialized, so attributes that are skipped are never decoded.
+    raw_value: &'a [u8],
     deserializer: &'a Deserializer<'a>,
 }
 
-impl<'de> de::AttributeAccess<'de> for AttributeAccess<'_, 'de> {
+impl<'de> de::AttributeAccess<'de> for AttributeAccess<'_> {
     type Error = Error;
 
     fn name(&self) -> ExpandedName<'_> {
         self.name
     }
 
+    fn prefix(&self) -> Option<&Prefix> {
+        self.prefix
+    }
+
     /// Deserializes the value of the attribute.
     fn value<T>(self) -> Result<T, Self::Error>
     where
         T: Deserialize<'de>,
     {
+        let value = self.deserializer.attribute_value(self.raw_value)?;
+
         T::deserialize(TextDeserializer {
-            value: self.value,
+            value: Cow::Owned(value),
             deserializer: self.deserializer,
             used_up: false,
         })
@@ -460,15 +2328,15 @@ impl<'de> de::SeqAccess<'de> for TextDeserializer<'_, 'de> {
     where
         Self: 'g;
 
-    fn next_element<T>(&mut self) -> Result<Option<T>, Self::Error>
+    fn next_element_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
     where
-        T: Deserialize<'de>,
+        S: DeserializeSeed<'de>,
     {
         if self.used_up {
             return Ok(None);
         }
 
-        T::deserialize(TextDeserializer {
+        seed.deserialize(TextDeserializer {
             value: self.value.clone(),
             deserializer: self.deserializer,
             used_up: false,
@@ -479,15 +2347,15 @@ impl<'de> de::SeqAccess<'de> for TextDeserializer<'_, 'de> {
         })
     }
 
-    fn next_element_seq<T>(&mut self) -> Result<Option<T>, Self::Error>
+    fn next_element_seq_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
     where
-        T: Deserialize<'de>,
+        S: DeserializeSeed<'de>,
     {
         if self.used_up {
             return Ok(None);
         }
 
-        T::deserialize_seq(TextDeserializer {
+        seed.deserialize_seq(TextDeserializer {
             value: self.value.clone(),
             deserializer: self.deserializer,
             used_up: false,
@@ -505,6 +2373,14 @@ impl<'de> de::SeqAccess<'de> for TextDeserializer<'_, 'de> {
             used_up: self.used_up,
         })
     }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(usize::from(!self.used_up))
+    }
+
+    fn peek_element(&mut self) -> Result<Option<de::PeekedNode>, Self::Error> {
+        Ok((!self.used_up).then_some(de::PeekedNode::Text))
+    }
 }
 
 impl<'de> de::Deserializer<'de> for TextDeserializer<'_, 'de> {
@@ -523,15 +2399,26 @@ impl<'de> de::Deserializer<'de> for TextDeserializer<'_, 'de> {
     {
         visitor.visit_seq(self)
     }
+
+    fn external_data<T>(&self) -> Option<&T>
+    where
+        T: core::any::Any,
+    {
+        self.deserializer
+            .external_data
+            .as_ref()
+            .and_then(|data| data.get::<T>())
+    }
 }
 
-struct AttributeDeserializer<'a, 'v> {
+struct AttributeDeserializer<'a> {
     name: ExpandedName<'a>,
-    value: Cow<'v, [u8]>,
+    prefix: Option<&'a Prefix>,
+    raw_value: &'a [u8],
     deserializer: &'a Deserializer<'a>,
 }
 
-impl<'de> xmlity::Deserializer<'de> for AttributeDeserializer<'_, 'de> {
+impl<'de> xmlity::Deserializer<'de> for AttributeDeserializer<'_> {
     type Error = Error;
 
     fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
@@ -540,16 +2427,28 @@ impl<'de> xmlity::Deserializer<'de> for AttributeDeserializer<'_, 'de> {
     {
         visitor.visit_attribute(AttributeAccess {
             name: self.name,
-            value: self.value,
+            prefix: self.prefix,
+            raw_value: self.raw_value,
             deserializer: self.deserializer,
         })
     }
 
-    fn deserialize_seq<V>(self, _: V) -> Result<V::Value, Self::Error>
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        Err(Self::Error::Unexpected(de::Unexpected::Seq))
+        // An attribute is a sequence of one attribute, the same as in the `XmlAttribute` deserializer.
+        self.deserialize_any(visitor)
+    }
+
+    fn external_data<T>(&self) -> Option<&T>
+    where
+        T: core::any::Any,
+    {
+        self.deserializer
+            .external_data
+            .as_ref()
+            .and_then(|data| data.get::<T>())
     }
 }
 
@@ -571,28 +2470,30 @@ fn key_is_declaration(key: ExpandedName) -> bool {
         || (key.local_name() == LocalName::XMLNS && key.namespace().is_none())
 }
 
-fn next_attribute<'a, 'de, T: Deserialize<'de>>(
+fn next_attribute<'a, 'de, S: DeserializeSeed<'de>>(
     deserializer: &'a Deserializer<'de>,
     bytes_start: &'a BytesStart<'de>,
     attribute_index: &'a mut usize,
-) -> Result<Option<T>, Error> {
-    while let Some(attribute) = bytes_start.attributes().nth(*attribute_index) {
+    seed: S,
+) -> Result<Option<S::Value>, Error> {
+    while let Some(attribute) = deserializer.attributes(bytes_start).nth(*attribute_index) {
         let attribute: Attribute<'_> = attribute?;
 
-        let key: ExpandedName<'_> = deserializer.resolve_qname(attribute.key, true);
+        let key: ExpandedName<'_> = deserializer.resolve_node_name(attribute.key, true)?;
 
         if key_is_declaration(key) {
             *attribute_index += 1;
             continue;
         }
 
-        let deserializer: AttributeDeserializer<'_, 'de> = AttributeDeserializer {
+        let deserializer = AttributeDeserializer {
             name: key,
-            value: Cow::Owned(attribute.value.into_owned()),
+            prefix: deserializer.written_prefix(attribute.key),
+            raw_value: &attribute.value,
             deserializer,
         };
 
-        let res = T::deserialize(deserializer)?;
+        let res = seed.deserialize(deserializer)?;
 
         // Only increment the index if the deserialization was successful
         *attribute_index += 1;
@@ -603,6 +2504,52 @@ fn next_attribute<'a, 'de, T: Deserialize<'de>>(
     Ok(None)
 }
 
+fn peek_attribute_name(
+    deserializer: &Deserializer<'_>,
+    bytes_start: &BytesStart<'_>,
+    attribute_index: usize,
+) -> Result<Option<ExpandedNameBuf>, Error> {
+    for attribute in deserializer.attributes(bytes_start).skip(attribute_index) {
+        let attribute: Attribute<'_> = attribute?;
+
+        let key = deserializer.resolve_node_name(attribute.key, true)?;
+
+        if !key_is_declaration(key) {
+            return Ok(Some(key.into_owned()));
+        }
+    }
+
+    Ok(None)
+}
+
+fn find_attribute<'a, 'de, S: DeserializeSeed<'de>>(
+    deserializer: &'a Deserializer<'de>,
+    bytes_start: &'a BytesStart<'de>,
+    name: ExpandedName<'_>,
+    seed: S,
+) -> Result<Option<S::Value>, Error> {
+    for attribute in deserializer.attributes(bytes_start) {
+        let attribute: Attribute<'_> = attribute?;
+
+        let key: ExpandedName<'_> = deserializer.resolve_node_name(attribute.key, true)?;
+
+        if key != name || key_is_declaration(key) {
+            continue;
+        }
+
+        let deserializer = AttributeDeserializer {
+            name: key,
+            prefix: deserializer.written_prefix(attribute.key),
+            raw_value: &attribute.value,
+            deserializer,
+        };
+
+        return seed.deserialize(deserializer).map(Some);
+    }
+
+    Ok(None)
+}
+
 impl<'de> de::AttributesAccess<'de> for SubAttributesAccess<'_, 'de> {
     type Error = Error;
 
@@ -611,14 +2558,15 @@ impl<'de> de::AttributesAccess<'de> for SubAttributesAccess<'_, 'de> {
     where
         Self: 'a;
 
-    fn next_attribute<T>(&mut self) -> Result<Option<T>, Self::Error>
+    fn next_attribute_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
     where
-        T: Deserialize<'de>,
+        S: DeserializeSeed<'de>,
     {
         next_attribute(
             self.deserializer,
             self.bytes_start,
             &mut self.attribute_index,
+            seed,
         )
     }
 
@@ -630,6 +2578,30 @@ impl<'de> de::AttributesAccess<'de> for SubAttributesAccess<'_, 'de> {
             write_attribute_to: &mut self.attribute_index,
         })
     }
+
+    fn peek_name(&mut self) -> Result<Option<ExpandedNameBuf>, Self::Error> {
+        peek_attribute_name(self.deserializer, self.bytes_start, self.attribute_index)
+    }
+
+    fn find_attribute_seed<S>(
+        &mut self,
+        name: ExpandedName<'_>,
+        seed: S,
+    ) -> Result<Option<S::Value>, Self::Error>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        find_attribute(self.deserializer, self.bytes_start, name, seed)
+    }
+
+    fn ancestor_attribute(
+        &self,
+        ancestor: &ExpandedName<'_>,
+        attribute: &ExpandedName<'_>,
+    ) -> Option<Cow<'_, str>> {
+        self.deserializer
+            .find_ancestor_attribute(ancestor, attribute)
+    }
 }
 
 impl<'de> de::AttributesAccess<'de> for ElementAccess<'_, 'de> {
@@ -640,9 +2612,9 @@ impl<'de> de::AttributesAccess<'de> for ElementAccess<'_, 'de> {
     where
         Self: 'a;
 
-    fn next_attribute<T>(&mut self) -> Result<Option<T>, Self::Error>
+    fn next_attribute_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
     where
-        T: Deserialize<'de>,
+        S: DeserializeSeed<'de>,
     {
         next_attribute(
             self.deserializer
@@ -652,6 +2624,7 @@ impl<'de> de::AttributesAccess<'de> for ElementAccess<'_, 'de> {
                 .as_ref()
                 .expect("bytes_start should be set"),
             &mut self.attribute_index,
+            seed,
         )
     }
 
@@ -669,6 +2642,47 @@ impl<'de> de::AttributesAccess<'de> for ElementAccess<'_, 'de> {
                 .expect("Should not be called after ElementAccess has been consumed"),
         })
     }
+
+    fn peek_name(&mut self) -> Result<Option<ExpandedNameBuf>, Self::Error> {
+        peek_attribute_name(
+            self.deserializer
+                .as_ref()
+                .expect("deserializer should be set"),
+            self.bytes_start
+                .as_ref()
+                .expect("bytes_start should be set"),
+            self.attribute_index,
+        )
+    }
+
+    fn find_attribute_seed<S>(
+        &mut self,
+        name: ExpandedName<'_>,
+        seed: S,
+    ) -> Result<Option<S::Value>, Self::Error>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        find_attribute(
+            self.deserializer
+                .as_ref()
+                .expect("deserializer should be set"),
+            self.bytes_start
+                .as_ref()
+                .expect("bytes_start should be set"),
+            name,
+            seed,
+        )
+    }
+
+    fn ancestor_attribute(
+        &self,
+        ancestor: &ExpandedName<'_>,
+        attribute: &ExpandedName<'_>,
+    ) -> Option<Cow<'_, str>> {
+        self.deserializer()
+            .find_ancestor_attribute(ancestor, attribute)
+    }
 }
 
 impl<'a, 'de> de::ElementAccess<'de> for ElementAccess<'a, 'de> {
@@ -679,15 +2693,53 @@ impl<'a, 'de> de::ElementAccess<'de> for ElementAccess<'a, 'de> {
         Self: 'b;
 
     fn name(&self) -> ExpandedName<'_> {
-        self.deserializer().resolve_qname(
+        self.deserializer()
+            .resolve_node_name(
+                self.bytes_start
+                    .as_ref()
+                    .expect("bytes_start should be set")
+                    .name(),
+                false,
+            )
+            .expect("the name is checked before the element is visited")
+    }
+
+    fn prefix(&self) -> Option<&Prefix> {
+        self.deserializer().written_prefix(
             self.bytes_start
                 .as_ref()
                 .expect("bytes_start should be set")
                 .name(),
-            false,
         )
     }
 
+    fn namespace_declarations(&self) -> Vec<(PrefixBuf, XmlNamespaceBuf)> {
+        if !self.deserializer().preserve_prefixes {
+            return Vec::new();
+        }
+
+        let deserializer = self.deserializer();
+        deserializer
+            .attributes(
+                self.bytes_start
+                    .as_ref()
+                    .expect("bytes_start should be set"),
+            )
+            .filter_map(Result::ok)
+            .filter_map(|attribute| {
+                let prefix = match attribute.key.as_namespace_binding()? {
+                    PrefixDeclaration::Default => Prefix::BLANK.to_owned(),
+                    PrefixDeclaration::Named(prefix) => {
+                        PrefixBuf::new(std::str::from_utf8(prefix).ok()?.to_owned()).ok()?
+                    }
+                };
+                let namespace =
+                    XmlNamespaceBuf::new(attribute.unescape_value().ok()?.into_owned()).ok()?;
+                Some((prefix, namespace))
+            })
+            .collect()
+    }
+
     fn children(mut self) -> Result<Self::ChildrenAccess, Self::Error> {
         Ok(if self.empty {
             SeqAccess::Empty
@@ -752,9 +2804,9 @@ impl<'r> de::SeqAccess<'r> for SeqAccess<'_, 'r> {
     where
         Self: 's;
 
-    fn next_element<T>(&mut self) -> Result<Option<T>, Self::Error>
+    fn next_element_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
     where
-        T: Deserialize<'r>,
+        S: DeserializeSeed<'r>,
     {
         let Self::Filled { current, .. } = self else {
             return Ok(None);
@@ -771,13 +2823,13 @@ impl<'r> de::SeqAccess<'r> for SeqAccess<'_, 'r> {
         }
 
         deserializer
-            .try_deserialize(|deserializer| Deserialize::<'r>::deserialize(deserializer))
+            .try_deserialize(|deserializer| seed.deserialize(deserializer))
             .map(Some)
     }
 
-    fn next_element_seq<T>(&mut self) -> Result<Option<T>, Self::Error>
+    fn next_element_seq_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
     where
-        T: Deserialize<'r>,
+        S: DeserializeSeed<'r>,
     {
         let Self::Filled { current, .. } = self else {
             return Ok(None);
@@ -794,13 +2846,56 @@ impl<'r> de::SeqAccess<'r> for SeqAccess<'_, 'r> {
         }
 
         deserializer
-            .try_deserialize(|deserializer| Deserialize::<'r>::deserialize_seq(deserializer))
+            .try_deserialize(|deserializer| seed.deserialize_seq(deserializer))
             .map(Some)
     }
 
     fn sub_access(&mut self) -> Result<Self::SubAccess<'_>, Self::Error> {
         Ok(self.create_sub_seq_access())
     }
+
+    fn size_hint(&self) -> Option<usize> {
+        match self {
+            Self::Filled { .. } => None,
+            Self::Empty => Some(0),
+        }
+    }
+
+    fn peek_element(&mut self) -> Result<Option<de::PeekedNode>, Self::Error> {
+        let Self::Filled { current, .. } = self else {
+            return Ok(None);
+        };
+
+        let deserializer = current.as_mut().expect("SubSeqAccess used after drop");
+
+        let node = match deserializer.peek_event() {
+            None | Some(Event::End(_) | Event::Eof) => return Ok(None),
+            Some(Event::Start(bytes) | Event::Empty(bytes)) => {
+                let bytes = bytes.clone();
+                let name = deserializer.reader.resolve_peeked_qname(&bytes)?;
+                de::PeekedNode::Element(
+                    deserializer.resolve_name(name.as_ref(), false).into_owned(),
+                )
+            }
+            Some(Event::Text(_) | Event::GeneralRef(_)) => de::PeekedNode::Text,
+            Some(Event::CData(_)) => de::PeekedNode::CData,
+            Some(Event::Comment(_)) => de::PeekedNode::Comment,
+            Some(Event::PI(_)) => de::PeekedNode::PI,
+            Some(Event::Decl(_)) => de::PeekedNode::Decl,
+            Some(Event::DocType(_)) => de::PeekedNode::DocType,
+        };
+
+        Ok(Some(node))
+    }
+
+    fn preserves_whitespace(&self) -> bool {
+        match self {
+            Self::Filled { current, .. } => current
+                .as_ref()
+                .is_some_and(|deserializer| deserializer.reader.preserves_space()),
+            Self::Empty => false,
+        }
+    }
 }
 
 struct DataWithD<'a, T> {
@@ -983,7 +3078,8 @@ impl XmlProcessingInstruction for DataWithD<'_, BytesPI<'_>> {
     }
 
     fn content(&self) -> &[u8] {
-        self.data.content()
+        // The whitespace separating the content from the target is not part of the content.
+        self.data.content().trim_ascii_start()
     }
 
     fn context(&self) -> Self::DeserializeContext<'_> {
@@ -1017,70 +3113,16 @@ impl<'r> xmlity::Deserializer<'r> for &mut Deserializer<'r> {
     where
         V: de::Visitor<'r>,
     {
+        self.skip_prolog();
+
         let Some(event) = self.next_event() else {
+            self.check_entities()?;
             return visitor.visit_none();
         };
 
         match event {
-            Event::Start(bytes_start) => {
-                let mut sub = self.sub_deserializer(self.reader.current_depth());
-
-                let element = ElementAccess {
-                    bytes_start: Some(&bytes_start),
-                    start_depth: self.reader.current_depth(),
-                    deserializer: Some(&mut sub),
-                    empty: false,
-                    attribute_index: 0,
-                };
-
-                let value = visitor.visit_element(element)?;
-
-                sub.read_until_end()?;
-
-                self.reader = sub.reader;
-
-                let end_event = self
-                    .next_event()
-                    .ok_or_else(|| Error::StartElementWithoutEnd {
-                        name: String::from_utf8_lossy(bytes_start.name().0).to_string(),
-                    })?;
-
-                if let Event::End(bytes_end) = &end_event {
-                    if bytes_end.name() == bytes_start.name() {
-                        Ok(value)
-                    } else {
-                        Err(Error::NoMatchingEndElement {
-                            start_name: String::from_utf8_lossy(bytes_start.name().0).to_string(),
-                            end_name: String::from_utf8_lossy(bytes_end.name().0).to_string(),
-                        })
-                    }
-                } else {
-                    Err(Error::StartElementWithoutEnd {
-                        name: String::from_utf8_lossy(bytes_start.name().0).to_string(),
-                    })
-                }
-            }
-            Event::End(_bytes_end) => Err(Error::custom("Unexpected end element")),
-            Event::Empty(bytes_start) => visitor.visit_element(ElementAccess {
-                bytes_start: Some(&bytes_start),
-                start_depth: self.reader.current_depth(),
-                deserializer: Some(self),
-                empty: true,
-                attribute_index: 0,
-            }),
-            Event::Text(bytes_text) => visitor.visit_text(DataWithD::new(bytes_text, self)),
-            Event::CData(bytes_cdata) => visitor.visit_cdata(DataWithD::new(bytes_cdata, self)),
-            Event::Comment(bytes_text) => visitor.visit_comment(DataWithD::new(bytes_text, self)),
-            Event::Decl(bytes_decl) => visitor.visit_decl(DataWithD::new(
-                ClearedByteDecl::try_from(&bytes_decl)?,
-                self,
-            )),
-            Event::PI(bytes_pi) => visitor.visit_pi(DataWithD::new(bytes_pi, self)),
-            Event::DocType(bytes_text) => visitor.visit_doctype(DataWithD::new(bytes_text, self)),
-            Event::Eof => Err(Error::custom("Unexpected EOF")),
-            Event::GeneralRef(bytes_ref) => {
-                visitor.visit_text(DataWithD::new(bytes_ref.decode()?, self))
-            }
+            Event::Start(bytes_start) => self.visit_start(bytes_start, visitor),
+            event => self.visit_event(event, visitor),
         }
     }
 
@@ -1088,15 +3130,28 @@ impl<'r> xmlity::Deserializer<'r> for &mut Deserializer<'r> {
     where
         V: de::Visitor<'r>,
     {
+        self.skip_prolog();
+
         if self.peek_event().is_some() {
-            visitor.visit_seq(SeqAccess::Filled {
-                current: Some(self.clone()),
-                parent: self,
-            })
+            // An item may have failed at an entity reference without the visitor returning its error.
+            visitor
+                .visit_seq(SeqAccess::Filled {
+                    current: Some(self.clone()),
+                    parent: self,
+                })
+                .and_then(|value| self.check_entities().map(|()| value))
         } else {
+            self.check_entities()?;
             visitor.visit_none()
         }
     }
+
+    fn external_data<T>(&self) -> Option<&T>
+    where
+        T: core::any::Any,
+    {
+        self.external_data.as_ref().and_then(|data| data.get::<T>())
+    }
 }
 
 impl<'r> xmlity::Deserializer<'r> for Deserializer<'r> {
@@ -1115,4 +3170,11 @@ impl<'r> xmlity::Deserializer<'r> for Deserializer<'r> {
     {
         (&mut self).deserialize_seq(visitor)
     }
+
+    fn external_data<T>(&self) -> Option<&T>
+    where
+        T: core::any::Any,
+    {
+        self.external_data.as_ref().and_then(|data| data.get::<T>())
+    }
 }
@@ -15,47 +15,83 @@ use core::str;
 
 use xmlity::{LocalName, Prefix, QName, XmlNamespace};
 
+/// Includes appending children to elements of existing documents.
+pub mod append;
+/// Includes asynchronous reading and writing of documents with `tokio`.
+#[cfg(feature = "async-tokio")]
+pub mod async_tokio;
 /// Includes the deserializer for the `quick-xml` crate.
 pub mod de;
+/// Includes lenient parsing of documents that may be malformed.
+#[cfg(feature = "recovery")]
+pub mod recovery;
 /// Includes the serializer for the `quick-xml` crate.
 pub mod ser;
-
-pub use de::{from_str, Deserializer};
+/// Includes helpers for testing types against every backend.
+#[cfg(feature = "testing")]
+pub mod testing;
+
+#[cfg(feature = "async-tokio")]
+pub use async_tokio::{
+    from_reader_async, from_reader_items_async, to_writer_async, to_writer_async_with_config,
+    AsyncReaderItems,
+};
+pub use de::{
+    from_reader, from_reader_items, from_str, from_str_fragments, from_str_owned,
+    from_str_via_value, from_str_with_context, from_str_with_options, Deserializer, ReaderOptions,
+};
 use quick_xml::name::{LocalName as QuickLocalName, Prefix as QuickPrefix, QName as QuickName};
-pub use ser::{to_string, to_string_pretty, Serializer};
-
-trait HasQuickXmlAlternative {
+#[cfg(feature = "recovery")]
+pub use recovery::from_str_lenient;
+pub use ser::{
+    to_string, to_string_pretty, to_string_with_config, to_writer, to_writer_with_config,
+    Serializer, SerializerConfig,
+};
+
+trait HasQuickXmlAlternative: Sized {
     type QuickXmlAlternative;
 
-    fn from_quick_xml(quick_xml: Self::QuickXmlAlternative) -> Self;
+    /// Converts a name read by quick-xml, which only checks names as far as it needs to find their ends, failing if it is not a valid XML name.
+    fn from_quick_xml(quick_xml: Self::QuickXmlAlternative) -> Result<Self, de::Error>;
 }
 
 impl<'a> HasQuickXmlAlternative for QName<'a> {
     type QuickXmlAlternative = QuickName<'a>;
 
-    fn from_quick_xml(quick_xml: Self::QuickXmlAlternative) -> Self {
-        QName::new(
-            quick_xml.prefix().map(<&Prefix>::from_quick_xml),
-            <&LocalName>::from_quick_xml(quick_xml.local_name()),
-        )
+    fn from_quick_xml(quick_xml: Self::QuickXmlAlternative) -> Result<Self, de::Error> {
+        Ok(QName::new(
+            quick_xml
+                .prefix()
+                .map(<&Prefix>::from_quick_xml)
+                .transpose()?,
+            <&LocalName>::from_quick_xml(quick_xml.local_name())?,
+        ))
     }
 }
 
 impl<'a> HasQuickXmlAlternative for &'a Prefix {
     type QuickXmlAlternative = QuickPrefix<'a>;
-    fn from_quick_xml(quick_xml: Self::QuickXmlAlternative) -> Self {
-        Prefix::new(str::from_utf8(quick_xml.into_inner()).expect("prefix should be valid utf8"))
-            .expect("A quick xml prefix should be valid")
+    fn from_quick_xml(quick_xml: Self::QuickXmlAlternative) -> Result<Self, de::Error> {
+        let prefix = quick_xml.into_inner();
+        str::from_utf8(prefix)
+            .ok()
+            .and_then(|prefix| Prefix::new(prefix).ok())
+            .ok_or_else(|| de::Error::InvalidName {
+                name: String::from_utf8_lossy(prefix).into_owned(),
+            })
     }
 }
 
 impl<'a> HasQuickXmlAlternative for &'a LocalName {
     type QuickXmlAlternative = QuickLocalName<'a>;
-    fn from_quick_xml(quick_xml: Self::QuickXmlAlternative) -> Self {
-        LocalName::new(
-            str::from_utf8(quick_xml.into_inner()).expect("local name should be valid utf8"),
-        )
-        .expect("A quick xml local name should be valid")
+    fn from_quick_xml(quick_xml: Self::QuickXmlAlternative) -> Result<Self, de::Error> {
+        let local_name = quick_xml.into_inner();
+        str::from_utf8(local_name)
+            .ok()
+            .and_then(|local_name| LocalName::new(local_name).ok())
+            .ok_or_else(|| de::Error::InvalidName {
+                name: String::from_utf8_lossy(local_name).into_owned(),
+            })
     }
 }
 
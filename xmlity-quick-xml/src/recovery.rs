@@ -0,0 +1,263 @@
+use std::ops::Range;
+
+use quick_xml::{
+    errors::IllFormedError,
+    events::{BytesStart, Event},
+    name::QName as QuickName,
+    NsReader,
+};
+use xmlity::{
+    value::{
+        XmlAttribute, XmlCData, XmlChild, XmlComment, XmlDecl, XmlDoctype, XmlElement, XmlError,
+        XmlProcessingInstruction, XmlSeq, XmlText,
+    },
+    ExpandedName, LocalName, XmlNamespace, XmlValue,
+};
+
+use crate::xml_namespace_from_resolve_result;
+
+/// Parses a document into an [`XmlValue`] without ever failing.
+///
+/// Sections of the document that are malformed become [`XmlValue::Error`] and [`XmlChild::Error`] nodes, which record the byte range of the section and what is wrong with it. Everything else is parsed as usual, so partially broken documents can still be inspected, which is useful for editors and other tooling that report on documents as they are written.
+///
+/// Elements that are not closed are closed where their parent is closed, or at the end of the document, and an error node is added as their last child. End tags that don't match any open element are replaced by an error node.
+///
+/// ```
+/// use xmlity::value::{XmlChild, XmlValue};
+///
+/// let value = xmlity_quick_xml::from_str_lenient("<list><item>a</item><item>b</list>");
+///
+/// let XmlValue::Element(list) = value else { panic!() };
+/// let XmlChild::Element(item) = list.children.into_iter().nth(1).unwrap() else { panic!() };
+/// let error = item.children.into_iter().find_map(|child| match child {
+///     XmlChild::Error(error) => Some(error),
+///     _ => None,
+/// });
+///
+/// assert_eq!(error.unwrap().message, "element `item` is not closed");
+/// ```
+pub fn from_str_lenient(s: &str) -> XmlValue {
+    let mut parser = LenientParser {
+        reader: NsReader::from_str(s),
+        open_elements: Vec::new(),
+        values: Vec::new(),
+    };
+    parser.reader.config_mut().check_end_names = false;
+
+    parser.parse(s.len());
+
+    let mut values = parser.values;
+    match values.len() {
+        0 => XmlValue::None,
+        1 => values.pop().expect("Just checked."),
+        _ => XmlValue::Seq(values.into_iter().collect::<XmlSeq<_>>()),
+    }
+}
+
+struct OpenElement {
+    element: XmlElement,
+    children: Vec<XmlChild>,
+    raw_name: Vec<u8>,
+    start: usize,
+}
+
+struct LenientParser<'i> {
+    reader: NsReader<&'i [u8]>,
+    open_elements: Vec<OpenElement>,
+    values: Vec<XmlValue>,
+}
+
+impl LenientParser<'_> {
+    fn parse(&mut self, len: usize) {
+        loop {
+            let start = self.reader.buffer_position() as usize;
+            let event = match self.reader.read_event() {
+                Ok(event) => event.into_owned(),
+                // With end name checks disabled, the reader only fails on end tags if it has no open elements of its own, which can be out of sync with ours after a mismatched end tag.
+                Err(quick_xml::Error::IllFormed(IllFormedError::UnmatchedEndTag(name))) => {
+                    let span = start..self.reader.buffer_position() as usize;
+                    self.close_element(QuickName(name.as_bytes()), span);
+                    continue;
+                }
+                Err(err) => {
+                    let end = (self.reader.buffer_position() as usize).max(start + 1);
+                    self.push_error(start..end.min(len), err.to_string());
+                    if end >= len {
+                        break;
+                    }
+                    continue;
+                }
+            };
+            let span = start..self.reader.buffer_position() as usize;
+
+            match event {
+                Event::Start(bytes) => match self.element(&bytes) {
+                    Ok(element) => self.open_elements.push(OpenElement {
+                        element,
+                        children: Vec::new(),
+                        raw_name: bytes.name().0.to_vec(),
+                        start: span.start,
+                    }),
+                    Err(message) => self.push_error(span, message),
+                },
+                Event::Empty(bytes) => match self.element(&bytes) {
+                    Ok(element) => self.push_child(element),
+                    Err(message) => self.push_error(span, message),
+                },
+                Event::End(bytes) => self.close_element(bytes.name(), span),
+                Event::Text(bytes) => match std::str::from_utf8(&bytes) {
+                    Ok(text) => self.push_text(text),
+                    Err(err) => self.push_error(span, err.to_string()),
+                },
+                Event::GeneralRef(bytes) => match bytes.resolve_char_ref() {
+                    Ok(Some(ch)) => self.push_text(&ch.to_string()),
+                    Ok(None) => {
+                        let resolved = bytes
+                            .decode()
+                            .ok()
+                            .and_then(|name| quick_xml::escape::resolve_predefined_entity(&name));
+                        match resolved {
+                            Some(resolved) => self.push_text(resolved),
+                            None => self.push_error(
+                                span,
+                                format!("unknown entity `&{};`", String::from_utf8_lossy(&bytes)),
+                            ),
+                        }
+                    }
+                    Err(err) => self.push_error(span, err.to_string()),
+                },
+                Event::CData(bytes) => self.push_child(XmlCData::new(bytes.into_inner())),
+                Event::Comment(bytes) => self.push_child(XmlComment::new(bytes.into_inner())),
+                Event::PI(bytes) => self.push_child(XmlProcessingInstruction::new(
+                    bytes.target(),
+                    bytes.content(),
+                )),
+                Event::Decl(bytes) if self.open_elements.is_empty() => {
+                    let decl = bytes.version().and_then(|version| {
+                        let encoding = bytes.encoding().transpose()?;
+                        let standalone = bytes.standalone().transpose()?;
+                        let lossy = |value: &[u8]| String::from_utf8_lossy(value).into_owned();
+                        Ok(XmlDecl::new(
+                            lossy(&version),
+                            encoding.as_deref().map(lossy),
+                            standalone.as_deref().map(lossy),
+                        ))
+                    });
+                    match decl {
+                        Ok(decl) => self.values.push(XmlValue::Decl(decl)),
+                        Err(err) => self.push_error(span, err.to_string()),
+                    }
+                }
+                Event::DocType(bytes) if self.open_elements.is_empty() => {
+                    self.values
+                        .push(XmlValue::Doctype(XmlDoctype::new(bytes.into_inner())));
+                }
+                Event::Decl(_) => self.push_error(span, "XML declaration inside an element"),
+                Event::DocType(_) => self.push_error(span, "doctype inside an element"),
+                Event::Eof => break,
+            }
+        }
+
+        while let Some(open) = self.open_elements.pop() {
+            self.close_unclosed(open, len);
+        }
+    }
+
+    fn element(&self, bytes: &BytesStart<'_>) -> Result<XmlElement, String> {
+        let mut element = XmlElement::new(self.resolve(bytes.name(), false)?);
+
+        for attribute in bytes.attributes() {
+            let attribute = attribute.map_err(|err| err.to_string())?;
+            let name = self.resolve(attribute.key, true)?;
+            if *name.namespace() == Some(XmlNamespace::XMLNS)
+                || (name.local_name() == LocalName::XMLNS && name.namespace().is_none())
+            {
+                continue;
+            }
+
+            let value = std::str::from_utf8(&attribute.value).map_err(|err| err.to_string())?;
+            let value = xmlity::escape::unescape_attr(value).map_err(|err| err.to_string())?;
+            element
+                .attributes
+                .push_back(XmlAttribute::new(name, value.into_owned()));
+        }
+
+        Ok(element)
+    }
+
+    fn resolve<'a>(
+        &'a self,
+        name: QuickName<'a>,
+        attribute: bool,
+    ) -> Result<ExpandedName<'a>, String> {
+        let local_name = std::str::from_utf8(name.local_name().into_inner())
+            .ok()
+            .and_then(|local_name| LocalName::new(local_name).ok())
+            .ok_or_else(|| format!("invalid name `{}`", String::from_utf8_lossy(name.0)))?;
+        let (resolve_result, _) = self.reader.resolve(name, attribute);
+
+        Ok(ExpandedName::new(
+            local_name,
+            xml_namespace_from_resolve_result(resolve_result),
+        ))
+    }
+
+    fn close_element(&mut self, name: QuickName<'_>, span: Range<usize>) {
+        let Some(index) = self
+            .open_elements
+            .iter()
+            .rposition(|open| open.raw_name == name.0)
+        else {
+            let name = String::from_utf8_lossy(name.0);
+            self.push_error(
+                span,
+                format!("end tag `{name}` does not match any open element"),
+            );
+            return;
+        };
+
+        while self.open_elements.len() > index + 1 {
+            let open = self.open_elements.pop().expect("Just checked.");
+            self.close_unclosed(open, span.start);
+        }
+
+        let open = self.open_elements.pop().expect("Just checked.");
+        self.push_child(open.element.with_children(open.children));
+    }
+
+    fn close_unclosed(&mut self, mut open: OpenElement, end: usize) {
+        let name = String::from_utf8_lossy(&open.raw_name);
+        let error = XmlError::new(open.start..end, format!("element `{name}` is not closed"));
+        open.children.push(XmlChild::Error(error));
+        self.push_child(open.element.with_children(open.children));
+    }
+
+    fn push_text(&mut self, text: &str) {
+        let last = match self.open_elements.last_mut() {
+            Some(open) => match open.children.last_mut() {
+                Some(XmlChild::Text(last)) => Some(&mut last.0),
+                _ => None,
+            },
+            None => match self.values.last_mut() {
+                Some(XmlValue::Text(last)) => Some(&mut last.0),
+                _ => None,
+            },
+        };
+
+        match last {
+            Some(last) => last.extend_from_slice(text.as_bytes()),
+            None => self.push_child(XmlText::new(text)),
+        }
+    }
+
+    fn push_error<T: Into<String>>(&mut self, span: Range<usize>, message: T) {
+        self.push_child(XmlError::new(span, message));
+    }
+
+    fn push_child<T: Into<XmlChild> + Into<XmlValue>>(&mut self, child: T) {
+        match self.open_elements.last_mut() {
+            Some(open) => open.children.push(child.into()),
+            None => self.values.push(child.into()),
+        }
+    }
+}
@@ -3,12 +3,14 @@ use std::borrow::{Borrow, Cow};
 use std::collections::BTreeMap;
 use std::io::Write;
 use std::ops::DerefMut;
+use std::sync::Arc;
 
 use quick_xml::events::{BytesCData, BytesDecl, BytesEnd, BytesPI, BytesStart, BytesText, Event};
 use quick_xml::writer::Writer as QuickXmlWriter;
 
 use xmlity::{
-    ser::{self, Error as _, IncludePrefix, Unexpected},
+    escape::{escape_attr, escape_text},
+    ser::{self, BoolFormat, Error as _, IncludePrefix, UndeclaredAttributeNamespaces, Unexpected},
     ExpandedName, Prefix, QName, Serialize, XmlNamespace,
 };
 use xmlity::{ExpandedNameBuf, NoopDeSerializer, PrefixBuf, QNameBuf, XmlNamespaceBuf};
@@ -16,7 +18,7 @@ use xmlity::{ExpandedNameBuf, NoopDeSerializer, PrefixBuf, QNameBuf, XmlNamespac
 use crate::{OwnedQuickName, XmlnsDeclaration};
 
 /// Errors that can occur when using this crate.
-#[derive(Debug, thiserror::Error)]
+#[derive(Debug, Clone, thiserror::Error)]
 pub enum Error {
     /// Error from the `quick-xml` crate.
     #[error("Quick XML error: {0}")]
@@ -26,18 +28,72 @@ pub enum Error {
     AttrError(#[from] quick_xml::events::attributes::AttrError),
     /// IO errors.
     #[error("IO error: {0}")]
-    Io(#[from] std::io::Error),
+    Io(Arc<std::io::Error>),
     /// Custom errors from [`Serialize`] implementations.
     #[error("Custom: {0}")]
     Custom(String),
+    /// A node that can't be written where it is serialized, such as an element in the value of an attribute.
+    #[error("Unexpected: {0}")]
+    Unexpected(ser::Unexpected),
     /// Invalid UTF-8 when serializing.
     #[error("Invalid UTF-8: {0}")]
     InvalidUtf8(#[from] std::string::FromUtf8Error),
+    /// A prefix that has to be used is reserved or already bound to another namespace on the same element.
+    #[error(
+        "Prefix {prefix} cannot be bound to {namespace} as it is bound to {existing_namespace}"
+    )]
+    PrefixConflict {
+        /// The prefix that could not be bound.
+        prefix: PrefixBuf,
+        /// The namespace the prefix should have been bound to.
+        namespace: XmlNamespaceBuf,
+        /// The namespace the prefix is already bound to.
+        existing_namespace: XmlNamespaceBuf,
+    },
+    /// A namespace that cannot be bound to any prefix, such as the reserved `xmlns` namespace, or the namespace of an attribute that is not bound in scope when [`IncludePrefix::Never`] is set for the attribute, which rules out declaring it.
+    #[error("Namespace {namespace} cannot be bound to a prefix")]
+    UnboundNamespace {
+        /// The namespace that could not be bound.
+        namespace: XmlNamespaceBuf,
+    },
+    /// Content that can't be written as the node it is meant for, such as a comment containing `--`.
+    #[error("Invalid content: {0}")]
+    InvalidContent(#[from] xmlity::value::InvalidContentError),
+    /// The element to append to could not be found in the document.
+    #[error("The element to append to was not found")]
+    MissingAppendTarget,
+    /// Error from serializing into an [`XmlValue`](xmlity::XmlValue) first.
+    #[error("Value error: {0}")]
+    Value(#[from] xmlity::value::XmlValueSerializerError),
+    /// Writing the document would exceed [`SerializerConfig::max_output_bytes`].
+    #[error("Output exceeds the maximum of {max_output_bytes} bytes")]
+    OutputLimitExceeded {
+        /// The maximum number of bytes that could be written.
+        max_output_bytes: usize,
+    },
+    /// An attribute is in a namespace that is not declared, and [`SerializerConfig::undeclared_attribute_namespaces`] does not allow declaring it.
+    #[error("Namespace {namespace} of an attribute is not declared")]
+    UndeclaredNamespace {
+        /// The namespace that is not declared.
+        namespace: XmlNamespaceBuf,
+    },
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::Io(Arc::new(error))
+    }
+}
+
+impl From<Error> for xmlity::Error {
+    fn from(error: Error) -> Self {
+        xmlity::Error::serialize(error)
+    }
 }
 
 impl xmlity::ser::Error for Error {
     fn unexpected_serialize(unexpected: ser::Unexpected) -> Self {
-        Error::Custom(format!("Unexpected serialize: {unexpected:?}"))
+        Error::Unexpected(unexpected)
     }
 
     fn custom<T: ToString>(msg: T) -> Self {
@@ -75,6 +131,178 @@ where
     )
 }
 
+/// Serialize a value into a string with the given configuration.
+pub fn to_string_with_config<T>(value: &T, config: &SerializerConfig) -> Result<String, Error>
+where
+    T: Serialize,
+{
+    let mut serializer = Serializer::with_config(Vec::new(), config);
+    value.serialize(&mut serializer)?;
+    let bytes = serializer.into_inner();
+
+    String::from_utf8(bytes).map_err(Error::InvalidUtf8)
+}
+
+/// Serialize a value into a writer.
+///
+/// The document is written to `writer` as it is serialized instead of being collected in a string first, which keeps the memory use low for large documents. Writes are not buffered, so writers such as [`File`](std::fs::File) should be wrapped in a [`BufWriter`](std::io::BufWriter).
+///
+/// ```
+/// # use xmlity::Serialize;
+/// #[derive(Serialize)]
+/// #[xelement(name = "note")]
+/// struct Note(String);
+///
+/// let mut output = Vec::new();
+/// xmlity_quick_xml::to_writer(&mut output, &Note("Hello".to_string())).unwrap();
+///
+/// assert_eq!(output, b"<note>Hello</note>");
+/// ```
+pub fn to_writer<W, T>(writer: W, value: &T) -> Result<(), Error>
+where
+    W: Write,
+    T: Serialize,
+{
+    let mut serializer = Serializer::from(writer);
+    value.serialize(&mut serializer)
+}
+
+/// Serialize a value into a writer with the given configuration, see [`to_writer`].
+pub fn to_writer_with_config<W, T>(
+    writer: W,
+    value: &T,
+    config: &SerializerConfig,
+) -> Result<(), Error>
+where
+    W: Write,
+    T: Serialize,
+{
+    let mut serializer = Serializer::with_config(writer, config);
+    value.serialize(&mut serializer)
+}
+
+/// Configuration of how a [`Serializer`] writes documents, see [`Serializer::with_config`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SerializerConfig {
+    indentation: Option<usize>,
+    max_output_bytes: Option<usize>,
+    bool_format: BoolFormat,
+    undeclared_attribute_namespaces: UndeclaredAttributeNamespaces,
+}
+
+impl SerializerConfig {
+    /// Create a configuration without pretty printing or limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pretty print the document, indenting nested elements with the given number of spaces.
+    pub fn with_indentation(mut self, indentation: Option<usize>) -> Self {
+        self.indentation = indentation;
+        self
+    }
+
+    /// Abort serialization with [`Error::OutputLimitExceeded`] as soon as the document would grow beyond the given number of bytes.
+    ///
+    /// Nothing beyond the limit is written to the underlying writer, which makes it possible to bound the size of responses without having to build an oversized document first. Everything written before the limit was reached is left in the writer.
+    pub fn with_max_output_bytes(mut self, max_output_bytes: Option<usize>) -> Self {
+        self.max_output_bytes = max_output_bytes;
+        self
+    }
+
+    /// Set the lexical form [`bool`]s are written in. Defaults to [`BoolFormat::Text`].
+    ///
+    /// Fields can override this with `#[xvalue(bool_format = "...")]`.
+    pub fn with_bool_format(mut self, bool_format: BoolFormat) -> Self {
+        self.bool_format = bool_format;
+        self
+    }
+
+    /// Set what happens with attributes in namespaces that are not declared where they are written. Defaults to [`UndeclaredAttributeNamespaces::Declare`].
+    pub fn with_undeclared_attribute_namespaces(
+        mut self,
+        undeclared_attribute_namespaces: UndeclaredAttributeNamespaces,
+    ) -> Self {
+        self.undeclared_attribute_namespaces = undeclared_attribute_namespaces;
+        self
+    }
+
+    /// The number of spaces nested elements are indented with, if the document is pretty printed.
+    pub fn indentation(&self) -> Option<usize> {
+        self.indentation
+    }
+
+    /// The maximum number of bytes written, if any.
+    pub fn max_output_bytes(&self) -> Option<usize> {
+        self.max_output_bytes
+    }
+
+    /// The lexical form [`bool`]s are written in.
+    pub fn bool_format(&self) -> BoolFormat {
+        self.bool_format
+    }
+
+    /// What happens with attributes in namespaces that are not declared where they are written.
+    pub fn undeclared_attribute_namespaces(&self) -> UndeclaredAttributeNamespaces {
+        self.undeclared_attribute_namespaces
+    }
+}
+
+/// Counts the bytes written to the inner writer and refuses writes beyond the limit.
+struct LimitedWriter<W> {
+    inner: W,
+    written: usize,
+    max_output_bytes: Option<usize>,
+    exceeded: bool,
+}
+
+impl<W: Write> Write for LimitedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if let Some(max_output_bytes) = self.max_output_bytes {
+            if self.written + buf.len() > max_output_bytes {
+                self.exceeded = true;
+                return Err(std::io::Error::other("output limit exceeded"));
+            }
+        }
+        let written = self.inner.write(buf)?;
+        self.written += written;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+enum OutputWriter<W: Write> {
+    Plain(QuickXmlWriter<W>),
+    Limited(QuickXmlWriter<LimitedWriter<W>>),
+}
+
+impl<W: Write> OutputWriter<W> {
+    fn write_event<'a, E: Into<Event<'a>>>(&mut self, event: E) -> Result<(), Error> {
+        match self {
+            Self::Plain(writer) => writer.write_event(event).map_err(Error::from),
+            Self::Limited(writer) => writer.write_event(event).map_err(|err| {
+                let limited = writer.get_ref();
+                match limited.max_output_bytes {
+                    Some(max_output_bytes) if limited.exceeded => {
+                        Error::OutputLimitExceeded { max_output_bytes }
+                    }
+                    _ => Error::from(err),
+                }
+            }),
+        }
+    }
+
+    fn into_inner(self) -> W {
+        match self {
+            Self::Plain(writer) => writer.into_inner(),
+            Self::Limited(writer) => writer.into_inner().inner,
+        }
+    }
+}
+
 struct NamespaceScope {
     pub defined_namespaces: BTreeMap<Cow<'static, Prefix>, Cow<'static, XmlNamespace>>,
 }
@@ -89,6 +317,8 @@ impl NamespaceScope {
     const XML_PREFIX: &'static Prefix = unsafe { Prefix::new_unchecked("xml") };
     const XML_NAMESPACE: &'static XmlNamespace =
         unsafe { XmlNamespace::new_unchecked("http://www.w3.org/XML/1998/namespace") };
+    /// The value of `xmlns=""`, which the default namespace is bound to when it is undeclared.
+    const NO_NAMESPACE: &'static XmlNamespace = unsafe { XmlNamespace::new_unchecked("") };
 
     pub fn top_scope() -> Self {
         let mut scope = Self::new();
@@ -114,29 +344,12 @@ struct PrefixGenerator {
 }
 
 impl PrefixGenerator {
-    pub fn index_to_name(index: usize) -> PrefixBuf {
-        // 0 = a0
-        // 1 = a1
-        // 26 = b0
-        // 27 = b1
-        // 52 = c0
-        // 53 = c1
-        // ...
-
-        let letter = (index / 26) as u8 + b'a';
-        let number = (index % 26) as u8 + b'0';
-        let mut name = String::with_capacity(2);
-        name.push(letter as char);
-        name.push(number as char);
-        PrefixBuf::new(name).expect("Invalid prefix generated")
-    }
-
     pub fn new() -> Self {
         Self { count: 0 }
     }
 
     pub fn new_prefix(&mut self) -> PrefixBuf {
-        let name = Self::index_to_name(self.count);
+        let name = ser::generated_prefix(self.count);
         self.count += 1;
         name
     }
@@ -165,57 +378,159 @@ impl NamespaceScopeContainer {
             .find_map(|a| a.get_namespace(prefix))
     }
 
-    /// Find matching prefix
-    pub fn find_matching_namespace<'b>(&'b self, namespace: &XmlNamespace) -> Option<&'b Prefix> {
+    /// Find matching prefix. Attributes without a prefix are never in a namespace, so the default namespace is not used for attributes.
+    pub fn find_matching_namespace<'b>(
+        &'b self,
+        namespace: &XmlNamespace,
+        attribute: bool,
+    ) -> Option<&'b Prefix> {
         self.scopes.iter().rev().find_map(|a| {
             a.defined_namespaces
                 .iter()
-                .find(|(_, found_namespace)| *namespace == ***found_namespace)
+                .find(|(prefix, found_namespace)| {
+                    *namespace == ***found_namespace
+                        && !(attribute && ***prefix == *Prefix::BLANK)
+                        // The prefix could be shadowed by a closer scope.
+                        && self.get_namespace(prefix) == Some(namespace)
+                })
                 .map(|(prefix, _)| &**prefix)
         })
     }
 
+    /// Returns `prefix` as it is bound in the closest scope that binds it, if it is bound to `namespace` there.
+    fn bound_prefix<'b>(&'b self, prefix: &Prefix, namespace: &XmlNamespace) -> Option<&'b Prefix> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.defined_namespaces.get_key_value(prefix))
+            .filter(|(_, bound_namespace)| ***bound_namespace == *namespace)
+            .map(|(prefix, _)| &**prefix)
+    }
+
+    /// Returns the namespace that `prefix` is bound to if it cannot be bound to `namespace` on the current element, either because it is reserved or because it is already bound to another namespace on the current element.
+    fn conflicting_namespace<'b>(
+        &'b self,
+        prefix: &Prefix,
+        namespace: &XmlNamespace,
+    ) -> Option<&'b XmlNamespace> {
+        if prefix == Prefix::XMLNS {
+            return Some(XmlNamespace::XMLNS);
+        }
+
+        if prefix == NamespaceScope::XML_PREFIX || namespace == XmlNamespace::XML {
+            return (prefix != NamespaceScope::XML_PREFIX || namespace != XmlNamespace::XML)
+                .then_some(XmlNamespace::XML);
+        }
+
+        self.scopes
+            .last()
+            .and_then(|scope| scope.defined_namespaces.get(prefix))
+            .map(|existing| &**existing)
+            .filter(|existing| *existing != namespace)
+    }
+
+    fn prefix_conflict(
+        prefix: &Prefix,
+        namespace: &XmlNamespace,
+        existing: &XmlNamespace,
+    ) -> Error {
+        Error::PrefixConflict {
+            prefix: prefix.to_owned(),
+            namespace: namespace.to_owned(),
+            existing_namespace: existing.to_owned(),
+        }
+    }
+
     /// This function takes in a namespace and tries to resolve it in different ways depending on the options provided. Unless `always_declare` is true, it will try to use an existing declaration. Otherwise, or if the namespace has not yet been declared, it will provide a declaration.
+    ///
+    /// If the prefix is enforced, the preferred prefix may shadow a binding from an outer element, but it is an error if it is already bound to another namespace on the current element.
     pub fn resolve_namespace<'b>(
         &'b mut self,
         namespace: &'_ XmlNamespace,
         preferred_prefix: Option<&Prefix>,
         always_declare: IncludePrefix,
-    ) -> (&'b Prefix, Option<XmlnsDeclaration<'b>>) {
-        if always_declare != IncludePrefix::Always {
-            let existing_prefix = self.find_matching_namespace(namespace);
+        attribute: bool,
+    ) -> Result<(&'b Prefix, Option<XmlnsDeclaration<'b>>), Error> {
+        // Attributes can't be put in a namespace with the default namespace.
+        let preferred_prefix =
+            preferred_prefix.filter(|prefix| !(attribute && *prefix == Prefix::BLANK));
+
+        if namespace == XmlNamespace::XMLNS {
+            return Err(Error::UnboundNamespace {
+                namespace: namespace.to_owned(),
+            });
+        }
+
+        if always_declare != IncludePrefix::Always || namespace == XmlNamespace::XML {
+            // The preferred prefix is used as is if it is already bound to the namespace, even if another prefix is bound to it as well.
+            if let Some(preferred_prefix) = preferred_prefix.filter(|preferred_prefix| {
+                always_declare == IncludePrefix::WhenNecessaryForPreferredPrefix
+                    && self.bound_prefix(preferred_prefix, namespace).is_some()
+            }) {
+                let bound_prefix = self.bound_prefix(preferred_prefix, namespace).unwrap();
+                return Ok((bound_prefix, None));
+            }
+
+            let existing_prefix = self.find_matching_namespace(namespace, attribute);
 
             if let Some(existing_prefix) = existing_prefix {
                 if (always_declare == IncludePrefix::WhenNecessaryForPreferredPrefix
                     && preferred_prefix
                         .is_none_or(|preferred_prefix| preferred_prefix == existing_prefix))
                     || always_declare == IncludePrefix::Never
+                    || namespace == XmlNamespace::XML
                 {
-                    let existing_prefix = self.find_matching_namespace(namespace).unwrap();
-                    return (existing_prefix, None);
+                    let existing_prefix =
+                        self.find_matching_namespace(namespace, attribute).unwrap();
+                    return Ok((existing_prefix, None));
                 }
             }
         }
 
+        let enforce_prefix = always_declare != IncludePrefix::Never;
+
+        if let Some(preferred_prefix) = preferred_prefix.filter(|_| enforce_prefix) {
+            if let Some(existing) = self.conflicting_namespace(preferred_prefix, namespace) {
+                return Err(Self::prefix_conflict(preferred_prefix, namespace, existing));
+            }
+        }
+
         // If the namespace is not declared, use the specifically requested preferred prefix...
-        // ...if it is not already used and not the same as the existing prefix.
+        // ...if it is enforced, or if it is not already used and not the same as the existing prefix.
         let prefix = preferred_prefix
-            .filter(|p| self.get_namespace(p).is_none_or(|n| n == namespace))
-            // If the preferred prefix is not available, use the preferred namespace prefix from the serializer...
-            .or_else(|| {
-                preferred_prefix
-                    // ...if it is not already used and not the same as the existing prefix.
-                    .filter(|p| self.get_namespace(p).is_none_or(|n| n == namespace))
+            .filter(|p| {
+                enforce_prefix
+                    || (self.conflicting_namespace(p, namespace).is_none()
+                        && self.get_namespace(p).is_none_or(|n| n == namespace))
             })
             .map(|p| p.to_owned())
             // If the preferred namespace prefix is not available, use a random prefix.
-            .unwrap_or_else(|| self.prefix_generator.new_prefix());
+            .unwrap_or_else(|| loop {
+                let prefix = self.prefix_generator.new_prefix();
+                if self.get_namespace(&prefix).is_none() {
+                    break prefix;
+                }
+            });
 
         let scope = self
             .scopes
             .last_mut()
             .expect("There should be at least one scope");
 
+        // The binding may already be declared on the current element, in which case it is not declared again.
+        if scope
+            .defined_namespaces
+            .get(Borrow::<Prefix>::borrow(&prefix))
+            .is_some_and(|existing| **existing == *namespace)
+        {
+            let (prefix, _) = scope
+                .defined_namespaces
+                .get_key_value(Borrow::<Prefix>::borrow(&prefix))
+                .expect("The namespace should be defined as it was just found");
+
+            return Ok((prefix.as_ref(), None));
+        }
+
         //TODO: This currently requires one more allocation than necessary. It requires https://github.com/rust-lang/rust/issues/65225 to be stabilized.
         scope
             .defined_namespaces
@@ -228,7 +543,42 @@ impl NamespaceScopeContainer {
 
         let xmlns = XmlnsDeclaration::new(prefix.as_ref(), namespace.as_ref());
 
-        (prefix.as_ref(), Some(xmlns))
+        Ok((prefix.as_ref(), Some(xmlns)))
+    }
+
+    /// Declare a namespace binding in the current scope, unless the same binding is already in scope.
+    pub fn declare_namespace<'b>(
+        &'b mut self,
+        prefix: &Prefix,
+        namespace: &XmlNamespace,
+    ) -> Result<Option<XmlnsDeclaration<'b>>, Error> {
+        if self.get_namespace(prefix) == Some(namespace) {
+            return Ok(None);
+        }
+
+        if let Some(existing) = self.conflicting_namespace(prefix, namespace) {
+            return Err(Self::prefix_conflict(prefix, namespace, existing));
+        }
+
+        let scope = self
+            .scopes
+            .last_mut()
+            .expect("There should be at least one scope");
+
+        scope.defined_namespaces.insert(
+            Cow::Owned(prefix.to_owned()),
+            Cow::Owned(namespace.to_owned()),
+        );
+
+        let (prefix, namespace) = scope
+            .defined_namespaces
+            .get_key_value(prefix)
+            .expect("The namespace should be defined as it was just added");
+
+        Ok(Some(XmlnsDeclaration::new(
+            prefix.as_ref(),
+            namespace.as_ref(),
+        )))
     }
 
     pub fn resolve_name<'a>(
@@ -236,28 +586,56 @@ impl NamespaceScopeContainer {
         local_name: ExpandedName<'a>,
         preferred_prefix: Option<&'a Prefix>,
         always_declare: IncludePrefix,
-    ) -> (QName<'a>, Option<XmlnsDeclaration<'a>>) {
+        attribute: bool,
+    ) -> Result<(QName<'a>, Option<XmlnsDeclaration<'a>>), Error> {
         let (local_name, namespace) = local_name.into_parts();
 
+        // An element without a namespace has to undeclare the default namespace if one is in scope, or it would be read in it.
+        if namespace.is_none()
+            && !attribute
+            && self
+                .get_namespace(Prefix::BLANK)
+                .is_some_and(|namespace| namespace != NamespaceScope::NO_NAMESPACE)
+        {
+            let scope = self
+                .scopes
+                .last_mut()
+                .expect("There should be at least one scope");
+            scope.defined_namespaces.insert(
+                Cow::Borrowed(Prefix::BLANK),
+                Cow::Borrowed(NamespaceScope::NO_NAMESPACE),
+            );
+
+            let declaration = XmlnsDeclaration::new(Prefix::BLANK, NamespaceScope::NO_NAMESPACE);
+            return Ok((QName::new(None, local_name), Some(declaration)));
+        }
+
         let (prefix, declaration) = namespace
             .as_ref()
-            .map(|namespace| self.resolve_namespace(namespace, preferred_prefix, always_declare))
+            .map(|namespace| {
+                self.resolve_namespace(namespace, preferred_prefix, always_declare, attribute)
+            })
+            .transpose()?
             .unzip();
 
         let declaration = declaration.flatten();
 
         let name = QName::new(prefix, local_name);
-        (name, declaration)
+        Ok((name, declaration))
     }
 }
 
-/// The [`xmlity::Deserializer`] for the `quick-xml` crate.
+/// The [`xmlity::Serializer`] for the `quick-xml` crate.
+///
+/// The output only depends on the value serialized and the configuration, so serializing the same value always gives the same bytes. Namespace declarations are written in the order they are introduced on each element: explicit declarations first in the order they are made, then the declaration for the name of the element, then the declarations for attributes, each right before the first attribute that needs it. Generated prefixes are numbered in the same order. A binding is never declared twice on the same element, and bindings already in scope from an ancestor are not declared again unless a prefix has to be included.
 pub struct Serializer<W: Write> {
-    writer: QuickXmlWriter<W>,
+    writer: OutputWriter<W>,
     preferred_namespace_prefixes: BTreeMap<XmlNamespaceBuf, PrefixBuf>,
     namespace_scopes: NamespaceScopeContainer,
     buffered_bytes_start: BytesStart<'static>,
     buffered_bytes_start_empty: bool,
+    bool_format: BoolFormat,
+    undeclared_attribute_namespaces: UndeclaredAttributeNamespaces,
 }
 
 impl<W: Write> Serializer<W> {
@@ -270,6 +648,32 @@ impl<W: Write> Serializer<W> {
     pub fn new_with_namespaces(
         writer: QuickXmlWriter<W>,
         preferred_namespace_prefixes: BTreeMap<XmlNamespaceBuf, PrefixBuf>,
+    ) -> Self {
+        Self::from_output(OutputWriter::Plain(writer), preferred_namespace_prefixes)
+    }
+
+    /// Create a new serializer writing to `writer` as configured by `config`.
+    pub fn with_config(writer: W, config: &SerializerConfig) -> Self {
+        let writer = LimitedWriter {
+            inner: writer,
+            written: 0,
+            max_output_bytes: config.max_output_bytes,
+            exceeded: false,
+        };
+        let writer = match config.indentation {
+            Some(indentation) => QuickXmlWriter::new_with_indent(writer, b' ', indentation),
+            None => QuickXmlWriter::new(writer),
+        };
+
+        let mut serializer = Self::from_output(OutputWriter::Limited(writer), BTreeMap::new());
+        serializer.bool_format = config.bool_format;
+        serializer.undeclared_attribute_namespaces = config.undeclared_attribute_namespaces;
+        serializer
+    }
+
+    fn from_output(
+        writer: OutputWriter<W>,
+        preferred_namespace_prefixes: BTreeMap<XmlNamespaceBuf, PrefixBuf>,
     ) -> Self {
         Self {
             writer,
@@ -277,6 +681,8 @@ impl<W: Write> Serializer<W> {
             namespace_scopes: NamespaceScopeContainer::new(),
             buffered_bytes_start: BytesStart::new(""),
             buffered_bytes_start_empty: true,
+            bool_format: BoolFormat::default(),
+            undeclared_attribute_namespaces: UndeclaredAttributeNamespaces::default(),
         }
     }
 
@@ -285,6 +691,55 @@ impl<W: Write> Serializer<W> {
         self.writer.into_inner()
     }
 
+    /// Treats namespace bindings as already declared, as they are when serializing into an element of an existing document that declares them.
+    pub(crate) fn bind_namespaces<'a>(
+        &mut self,
+        bindings: impl IntoIterator<Item = (&'a Prefix, &'a XmlNamespace)>,
+    ) {
+        let scope = self
+            .namespace_scopes
+            .scopes
+            .first_mut()
+            .expect("There should be at least one scope");
+
+        for (prefix, namespace) in bindings {
+            scope.defined_namespaces.insert(
+                Cow::Owned(prefix.to_owned()),
+                Cow::Owned(namespace.to_owned()),
+            );
+        }
+    }
+
+    /// Create a serializer that writes to a separate buffer, but continues from the current position of this serializer.
+    ///
+    /// The namespace bindings in scope, the preferred namespace prefixes and the generated prefixes are shared with this serializer, so a subtree serialized with it uses the same prefixes as it would in the larger document, without declaring the bindings again. This makes it possible to serialize a subtree on its own in the middle of serializing a document, for example to embed, hash or log it. The output of the sub-serializer is not indented and not limited in size, and this serializer is not affected by it.
+    pub fn sub_serializer(&self) -> Serializer<Vec<u8>> {
+        let mut serializer = Serializer::new_with_namespaces(
+            QuickXmlWriter::new(Vec::new()),
+            self.preferred_namespace_prefixes.clone(),
+        );
+        serializer.bool_format = self.bool_format;
+        serializer.undeclared_attribute_namespaces = self.undeclared_attribute_namespaces;
+        serializer.namespace_scopes.prefix_generator.count =
+            self.namespace_scopes.prefix_generator.count;
+        serializer.bind_namespaces(self.namespace_scopes.scopes.iter().flat_map(|scope| {
+            scope
+                .defined_namespaces
+                .iter()
+                .map(|(prefix, namespace)| (&**prefix, &**namespace))
+        }));
+        serializer
+    }
+
+    /// Serialize a value into a string with a [sub-serializer](Serializer::sub_serializer) of this serializer.
+    pub fn subtree_to_string<T: Serialize>(&self, value: &T) -> Result<String, Error> {
+        let mut serializer = self.sub_serializer();
+        value.serialize(&mut serializer)?;
+        let bytes = serializer.into_inner();
+
+        String::from_utf8(bytes).map_err(Error::InvalidUtf8)
+    }
+
     fn push_namespace_scope(&mut self) {
         self.namespace_scopes.push_scope()
     }
@@ -312,6 +767,7 @@ pub struct SerializeElement<'s, W: Write> {
     name: ExpandedNameBuf,
     include_prefix: IncludePrefix,
     preferred_prefix: Option<PrefixBuf>,
+    declarations: Vec<(PrefixBuf, XmlNamespaceBuf)>,
 }
 
 /// The attribute serializer for the `quick-xml` crate.
@@ -319,12 +775,14 @@ pub struct AttributeSerializer<'t, W: Write> {
     name: ExpandedNameBuf,
     serializer: &'t mut Serializer<W>,
     preferred_prefix: Option<PrefixBuf>,
-    enforce_prefix: IncludePrefix,
+    /// The prefix setting, if one was set explicitly.
+    enforce_prefix: Option<IncludePrefix>,
 }
 
 /// The text serializer for the `quick-xml` crate. Used when serializing to an attribute value.
 pub struct TextSerializer {
     value: Option<String>,
+    bool_format: BoolFormat,
 }
 
 impl ser::SerializeSeq for &mut TextSerializer {
@@ -336,7 +794,10 @@ impl ser::SerializeSeq for &mut TextSerializer {
             return Err(Error::unexpected_serialize(Unexpected::Text));
         }
 
-        let mut text_ser = TextSerializer { value: None };
+        let mut text_ser = TextSerializer {
+            value: None,
+            bool_format: self.bool_format,
+        };
         value.serialize(&mut text_ser)?;
 
         if let Some(value) = text_ser.value {
@@ -381,9 +842,7 @@ impl<'a> ser::Serializer for &'a mut TextSerializer {
         self,
         name: &'_ ExpandedName<'_>,
     ) -> Result<Self::SerializeElement, Self::Error> {
-        let _ = name;
-
-        Err(Error::unexpected_serialize(Unexpected::Element))
+        Err(Error::unexpected_serialize(Unexpected::element(name)))
     }
 
     fn serialize_seq(self) -> Result<Self::SerializeSeq, Self::Error> {
@@ -402,9 +861,9 @@ impl<'a> ser::Serializer for &'a mut TextSerializer {
     }
 
     fn serialize_pi<S: AsRef<[u8]>>(self, target: S, content: S) -> Result<Self::Ok, Self::Error> {
-        let _ = (target, content);
+        let _ = content;
 
-        Err(Error::unexpected_serialize(Unexpected::PI))
+        Err(Error::unexpected_serialize(Unexpected::pi(target)))
     }
 
     fn serialize_comment<S: AsRef<[u8]>>(self, text: S) -> Result<Self::Ok, Self::Error> {
@@ -422,6 +881,10 @@ impl<'a> ser::Serializer for &'a mut TextSerializer {
     fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
         Err(Error::unexpected_serialize(Unexpected::None))
     }
+
+    fn bool_format(&self) -> BoolFormat {
+        self.bool_format
+    }
 }
 
 impl<W: Write> ser::SerializeAttributeAccess for AttributeSerializer<'_, W> {
@@ -429,7 +892,7 @@ impl<W: Write> ser::SerializeAttributeAccess for AttributeSerializer<'_, W> {
     type Error = Error;
 
     fn include_prefix(&mut self, should_enforce: IncludePrefix) -> Result<Self::Ok, Self::Error> {
-        self.enforce_prefix = should_enforce;
+        self.enforce_prefix = Some(should_enforce);
         Ok(())
     }
 
@@ -449,26 +912,60 @@ impl<W: Write> ser::SerializeAttributeAccess for AttributeSerializer<'_, W> {
                 .map(|p| &**p)
         });
 
+        if let Some(namespace) = self.name.namespace() {
+            if self.serializer.undeclared_attribute_namespaces
+                == UndeclaredAttributeNamespaces::Error
+                && namespace != XmlNamespace::XML
+                && namespace != XmlNamespace::XMLNS
+                && self
+                    .serializer
+                    .namespace_scopes
+                    .find_matching_namespace(namespace, true)
+                    .is_none()
+            {
+                return Err(Error::UndeclaredNamespace {
+                    namespace: namespace.to_owned(),
+                });
+            }
+
+            // Declaring the namespace would need a prefix, which an explicit `Never` rules out.
+            if self.enforce_prefix == Some(IncludePrefix::Never)
+                && namespace != XmlNamespace::XML
+                && self
+                    .serializer
+                    .namespace_scopes
+                    .find_matching_namespace(namespace, true)
+                    .is_none()
+            {
+                return Err(Error::UnboundNamespace {
+                    namespace: namespace.to_owned(),
+                });
+            }
+        }
+
         let (qname, decl) = self.serializer.namespace_scopes.resolve_name(
             self.name.as_ref(),
             preferred_prefix,
-            self.enforce_prefix,
-        );
+            self.enforce_prefix.unwrap_or_default(),
+            true,
+        )?;
 
         if let Some(decl) = decl {
             self.serializer.buffered_bytes_start.push_declaration(decl);
         }
 
-        let mut text_ser = TextSerializer { value: None };
+        let mut text_ser = TextSerializer {
+            value: None,
+            bool_format: self.serializer.bool_format,
+        };
 
         value.serialize(&mut text_ser)?;
 
         self.serializer.buffered_bytes_start.push_attribute_xmlity(
             qname,
             Cow::Owned(
-                text_ser
-                    .value
-                    .expect("TextSerializer should have a value")
+                escape_attr(&text_ser.value.expect("TextSerializer should have a value"))
+                    .into_owned()
                     .into_bytes(),
             ),
         );
@@ -494,7 +991,7 @@ impl<W: Write> ser::AttributeSerializer for &mut SerializeElementAttributes<'_,
             name: name.into_owned(),
             serializer: self.serializer.deref_mut(),
             preferred_prefix: None,
-            enforce_prefix: IncludePrefix::default(),
+            enforce_prefix: None,
         })
     }
 
@@ -504,11 +1001,12 @@ impl<W: Write> ser::AttributeSerializer for &mut SerializeElementAttributes<'_,
 }
 
 impl<'s, W: Write> SerializeElement<'s, W> {
-    fn finish_start(self) -> (QNameBuf, &'s mut Serializer<W>) {
+    fn finish_start(self) -> Result<(QNameBuf, &'s mut Serializer<W>), Error> {
         let Self {
             name,
             include_prefix,
             preferred_prefix,
+            declarations,
             serializer,
         } = self;
 
@@ -519,6 +1017,15 @@ impl<'s, W: Write> SerializeElement<'s, W> {
 
         serializer.buffered_bytes_start.clear_attributes();
 
+        for (prefix, namespace) in declarations.iter() {
+            if let Some(decl) = serializer
+                .namespace_scopes
+                .declare_namespace(prefix, namespace)?
+            {
+                serializer.buffered_bytes_start.push_declaration(decl);
+            }
+        }
+
         let preferred_prefix = preferred_prefix.as_deref().or_else(|| {
             name.as_ref()
                 .namespace()
@@ -530,7 +1037,8 @@ impl<'s, W: Write> SerializeElement<'s, W> {
             name.as_ref(),
             preferred_prefix,
             include_prefix,
-        );
+            false,
+        )?;
         let qname = qname.into_owned();
 
         serializer
@@ -542,7 +1050,7 @@ impl<'s, W: Write> SerializeElement<'s, W> {
         }
         serializer.buffered_bytes_start_empty = false;
 
-        (qname, serializer)
+        Ok((qname, serializer))
     }
 
     fn end_empty(serializer: &mut Serializer<W>) -> Result<(), Error> {
@@ -552,10 +1060,7 @@ impl<'s, W: Write> SerializeElement<'s, W> {
         );
         let start = serializer.buffered_bytes_start.borrow();
 
-        serializer
-            .writer
-            .write_event(Event::Empty(start))
-            .map_err(Error::Io)?;
+        serializer.writer.write_event(Event::Empty(start))?;
 
         serializer.buffered_bytes_start_empty = true;
 
@@ -592,7 +1097,11 @@ impl<'s, W: Write> ser::SerializeElementAttributes for SerializeElementAttribute
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        SerializeElement::end_empty(self.serializer)
+        SerializeElement::end_empty(self.serializer)?;
+
+        self.serializer.pop_namespace_scope();
+
+        Ok(())
     }
 }
 
@@ -614,9 +1123,19 @@ impl<'s, W: Write> ser::SerializeElement for SerializeElement<'s, W> {
         Ok(())
     }
 
+    fn declare_namespace(
+        &mut self,
+        prefix: &Prefix,
+        namespace: &XmlNamespace,
+    ) -> Result<(), Self::Error> {
+        self.declarations
+            .push((prefix.to_owned(), namespace.to_owned()));
+        Ok(())
+    }
+
     fn serialize_attributes(self) -> Result<Self::SerializeElementAttributes, Self::Error> {
         self.serializer.push_namespace_scope();
-        let (end_name, serializer) = self.finish_start();
+        let (end_name, serializer) = self.finish_start()?;
         Ok(SerializeElementAttributes {
             serializer,
             end_name,
@@ -625,7 +1144,7 @@ impl<'s, W: Write> ser::SerializeElement for SerializeElement<'s, W> {
 
     fn serialize_children(self) -> Result<Self::ChildrenSerializeSeq, Self::Error> {
         self.serializer.push_namespace_scope();
-        let (end_name, serializer) = self.finish_start();
+        let (end_name, serializer) = self.finish_start()?;
 
         Ok(ChildrenSerializeSeq {
             serializer,
@@ -635,7 +1154,7 @@ impl<'s, W: Write> ser::SerializeElement for SerializeElement<'s, W> {
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
         self.serializer.push_namespace_scope();
-        let (_, serializer) = self.finish_start();
+        let (_, serializer) = self.finish_start()?;
 
         SerializeElement::end_empty(serializer)?;
 
@@ -651,6 +1170,18 @@ pub struct ChildrenSerializeSeq<'s, W: Write> {
     end_name: QNameBuf,
 }
 
+impl<W: Write> ChildrenSerializeSeq<'_, W> {
+    /// Create a serializer that writes to a separate buffer, with the namespace bindings in scope among the children of this element. See [`Serializer::sub_serializer`].
+    pub fn sub_serializer(&self) -> Serializer<Vec<u8>> {
+        self.serializer.sub_serializer()
+    }
+
+    /// Serialize a value into a string as if it was a child of this element. See [`Serializer::subtree_to_string`].
+    pub fn subtree_to_string<T: Serialize>(&self, value: &T) -> Result<String, Error> {
+        self.serializer.subtree_to_string(value)
+    }
+}
+
 impl<W: Write> ser::SerializeSeq for ChildrenSerializeSeq<'_, W> {
     type Ok = ();
     type Error = Error;
@@ -664,18 +1195,14 @@ impl<W: Write> ser::SerializeSeq for ChildrenSerializeSeq<'_, W> {
         if !self.serializer.buffered_bytes_start_empty {
             self.serializer
                 .writer
-                .write_event(Event::Empty(self.serializer.buffered_bytes_start.borrow()))
-                .map_err(Error::Io)?;
+                .write_event(Event::Empty(self.serializer.buffered_bytes_start.borrow()))?;
             self.serializer.buffered_bytes_start_empty = true;
         } else {
             let end_name = OwnedQuickName::new(&self.end_name.as_ref());
 
             let bytes_end = BytesEnd::from(end_name.as_ref());
 
-            self.serializer
-                .writer
-                .write_event(Event::End(bytes_end))
-                .map_err(Error::Io)?;
+            self.serializer.writer.write_event(Event::End(bytes_end))?;
         }
 
         self.serializer.pop_namespace_scope();
@@ -721,7 +1248,10 @@ impl<'a> BytesStartExt<'a> for BytesStart<'a> {
 
         let key = XmlnsDeclaration::xmlns_qname(prefix);
 
-        self.push_attribute_xmlity(key, Cow::Owned(namespace.as_str().as_bytes().to_vec()));
+        self.push_attribute_xmlity(
+            key,
+            Cow::Owned(escape_attr(namespace.as_str()).into_owned().into_bytes()),
+        );
     }
 }
 
@@ -729,8 +1259,7 @@ impl<W: Write> Serializer<W> {
     fn try_start(&mut self) -> Result<(), Error> {
         if !self.buffered_bytes_start_empty {
             self.writer
-                .write_event(Event::Start(self.buffered_bytes_start.borrow()))
-                .map_err(Error::Io)?;
+                .write_event(Event::Start(self.buffered_bytes_start.borrow()))?;
             self.buffered_bytes_start_empty = true;
         }
         Ok(())
@@ -745,16 +1274,19 @@ impl<'s, W: Write> xmlity::Serializer for &'s mut Serializer<W> {
 
     fn serialize_cdata<S: AsRef<str>>(self, text: S) -> Result<Self::Ok, Self::Error> {
         self.try_start()?;
-        self.writer
-            .write_event(Event::CData(BytesCData::new(text.as_ref())))
-            .map_err(Error::Io)
+        // Content containing `]]>` is split into adjacent sections between `]]` and `>`, since the sequence would otherwise end the section early.
+        for cdata in BytesCData::escaped(text.as_ref()) {
+            self.writer.write_event(Event::CData(cdata))?;
+        }
+        Ok(())
     }
 
     fn serialize_text<S: AsRef<str>>(self, text: S) -> Result<Self::Ok, Self::Error> {
         self.try_start()?;
         self.writer
-            .write_event(Event::Text(BytesText::from_escaped(text.as_ref())))
-            .map_err(Error::Io)
+            .write_event(Event::Text(BytesText::from_escaped(escape_text(
+                text.as_ref(),
+            ))))
     }
 
     fn serialize_element<'a>(
@@ -768,6 +1300,7 @@ impl<'s, W: Write> xmlity::Serializer for &'s mut Serializer<W> {
             name: name.into_owned(),
             include_prefix: IncludePrefix::default(),
             preferred_prefix: None,
+            declarations: Vec::new(),
         })
     }
 
@@ -782,33 +1315,33 @@ impl<'s, W: Write> xmlity::Serializer for &'s mut Serializer<W> {
         standalone: Option<S>,
     ) -> Result<Self::Ok, Self::Error> {
         self.try_start()?;
-        self.writer
-            .write_event(Event::Decl(BytesDecl::new(
-                version.as_ref(),
-                encoding.as_ref().map(|s| s.as_ref()),
-                standalone.as_ref().map(|s| s.as_ref()),
-            )))
-            .map_err(Error::Io)
+        let encoding = encoding
+            .as_ref()
+            .map(|encoding| xmlity::ser::utf8_encoding_label(encoding.as_ref()));
+        self.writer.write_event(Event::Decl(BytesDecl::new(
+            version.as_ref(),
+            encoding,
+            standalone.as_ref().map(|s| s.as_ref()),
+        )))
     }
 
     fn serialize_pi<S: AsRef<[u8]>>(self, target: S, content: S) -> Result<Self::Ok, Self::Error> {
         self.try_start()?;
-        self.writer
-            .write_event(Event::PI(BytesPI::new(format!(
-                "{} {}",
-                str::from_utf8(target.as_ref()).unwrap(),
-                str::from_utf8(content.as_ref()).unwrap()
-            ))))
-            .map_err(Error::Io)
+        self.writer.write_event(Event::PI(BytesPI::new(format!(
+            "{} {}",
+            str::from_utf8(target.as_ref()).unwrap(),
+            str::from_utf8(content.as_ref()).unwrap()
+        ))))
     }
 
     fn serialize_comment<S: AsRef<[u8]>>(self, text: S) -> Result<Self::Ok, Self::Error> {
+        // Writing the comment verbatim would end it early or produce malformed XML.
+        xmlity::value::XmlComment::new(text.as_ref()).validate()?;
         self.try_start()?;
         self.writer
             .write_event(Event::Comment(BytesText::from_escaped(
                 str::from_utf8(text.as_ref()).unwrap(),
             )))
-            .map_err(Error::Io)
     }
 
     fn serialize_doctype<S: AsRef<[u8]>>(self, text: S) -> Result<Self::Ok, Self::Error> {
@@ -817,10 +1350,13 @@ impl<'s, W: Write> xmlity::Serializer for &'s mut Serializer<W> {
             .write_event(Event::DocType(BytesText::from_escaped(
                 str::from_utf8(text.as_ref()).unwrap(),
             )))
-            .map_err(Error::Io)
     }
 
     fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
         Ok(())
     }
+
+    fn bool_format(&self) -> BoolFormat {
+        self.bool_format
+    }
 }
@@ -0,0 +1,143 @@
+use std::fmt::Debug;
+
+use xmlity::{value::XmlValue, Deserialize, DeserializeOwned, Serialize};
+
+/// A boxed error returned by a [`TestBackend`].
+pub type BackendError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A way of turning values into XML and back, which [`backend_tests!`](crate::backend_tests) runs each test case against.
+pub trait TestBackend {
+    /// The name of the backend, used in assertion messages.
+    const NAME: &'static str;
+
+    /// Serializes a value to an XML string.
+    fn serialize<T: Serialize>(value: &T) -> Result<String, BackendError>;
+
+    /// Deserializes a value from an XML string.
+    fn deserialize<T: DeserializeOwned>(xml: &str) -> Result<T, BackendError>;
+}
+
+/// Serializes and deserializes directly with the [`Serializer`](crate::Serializer) and [`Deserializer`](crate::Deserializer) of this crate.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QuickXmlBackend;
+
+impl TestBackend for QuickXmlBackend {
+    const NAME: &'static str = "quick-xml";
+
+    fn serialize<T: Serialize>(value: &T) -> Result<String, BackendError> {
+        Ok(crate::to_string(value)?)
+    }
+
+    fn deserialize<T: DeserializeOwned>(xml: &str) -> Result<T, BackendError> {
+        let mut deserializer = crate::Deserializer::from(xml.as_bytes());
+        Ok(T::deserialize_seq(&mut deserializer)?)
+    }
+}
+
+/// Goes through an [`XmlValue`] in both directions, which checks that the [`XmlValue`] serializer and deserializer of `xmlity` agree with this crate.
+///
+/// Values are serialized into an [`XmlValue`] which is then written as XML, and XML is read into an [`XmlValue`] which values are then deserialized from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct XmlValueBackend;
+
+impl TestBackend for XmlValueBackend {
+    const NAME: &'static str = "XmlValue";
+
+    fn serialize<T: Serialize>(value: &T) -> Result<String, BackendError> {
+        let value = xmlity::value::to_value(value)?;
+        Ok(crate::to_string(&value)?)
+    }
+
+    fn deserialize<T: DeserializeOwned>(xml: &str) -> Result<T, BackendError> {
+        let mut deserializer = crate::Deserializer::from(xml.as_bytes());
+        let value = XmlValue::deserialize_seq(&mut deserializer)?;
+        Ok(xmlity::value::from_value(&value)?)
+    }
+}
+
+/// Asserts that `value` serializes to `xml` with the backend `B`.
+#[track_caller]
+pub fn assert_serializes<B: TestBackend, T: Serialize + Debug>(value: &T, xml: &str) {
+    match B::serialize(value) {
+        Ok(actual) => assert_eq!(
+            actual,
+            xml,
+            "{} backend serialized {value:?} differently",
+            B::NAME
+        ),
+        Err(err) => panic!("{} backend failed to serialize {value:?}: {err}", B::NAME),
+    }
+}
+
+/// Asserts that `xml` deserializes to `value` with the backend `B`.
+#[track_caller]
+pub fn assert_deserializes<B: TestBackend, T: DeserializeOwned + PartialEq + Debug>(
+    value: &T,
+    xml: &str,
+) {
+    match B::deserialize::<T>(xml) {
+        Ok(actual) => assert_eq!(
+            &actual,
+            value,
+            "{} backend deserialized {xml:?} differently",
+            B::NAME
+        ),
+        Err(err) => panic!("{} backend failed to deserialize {xml:?}: {err}", B::NAME),
+    }
+}
+
+/// Generates tests that check that values serialize to and deserialize from XML in the same way with every backend.
+///
+/// Each case is either a `(value, xml)` pair, or a `(value, serialized_xml, deserialized_xml)` triple for cases where the XML written differs from the XML read. The macro creates a module with the given name containing a serialize and a deserialize test for each [`TestBackend`]: [`QuickXmlBackend`] and [`XmlValueBackend`].
+///
+/// This is useful both to catch behavior drifting apart between the backends, and for crates defining bindings for a schema to test them against example documents.
+///
+/// ```
+/// use xmlity::{Deserialize, Serialize};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// #[xelement(name = "note")]
+/// struct Note {
+///     #[xattribute(name = "to")]
+///     to: String,
+/// }
+///
+/// xmlity_quick_xml::backend_tests!(
+///     note,
+///     [(Note { to: "Tove".to_string() }, r#"<note to="Tove"/>"#)]
+/// );
+/// ```
+#[macro_export]
+macro_rules! backend_tests {
+    (@test $test:ident, $assert:ident, $backend:ident, [$(($value:expr, $xml:expr)),*]) => {
+        #[test]
+        fn $test() {
+            $(
+                $crate::testing::$assert::<$crate::testing::$backend, _>(&$value, &$xml);
+            )*
+        }
+    };
+    (@impl $name:ident, [$(($value:expr, $serialize_xml:expr, $deserialize_xml:expr))*]) => {
+        mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            $crate::backend_tests!(@test quick_xml_serialize, assert_serializes, QuickXmlBackend, [$(($value, $serialize_xml)),*]);
+            $crate::backend_tests!(@test quick_xml_deserialize, assert_deserializes, QuickXmlBackend, [$(($value, $deserialize_xml)),*]);
+            $crate::backend_tests!(@test xml_value_serialize, assert_serializes, XmlValueBackend, [$(($value, $serialize_xml)),*]);
+            $crate::backend_tests!(@test xml_value_deserialize, assert_deserializes, XmlValueBackend, [$(($value, $deserialize_xml)),*]);
+        }
+    };
+    (@internal $name:ident, [$($existing:tt)*], []) => {
+        $crate::backend_tests!(@impl $name, [$($existing)*]);
+    };
+    (@internal $name:ident, [$($existing:tt)*], [($value:expr, $serialize_xml:expr, $deserialize_xml:expr) $(, $($tail:tt)*)?]) => {
+        $crate::backend_tests!(@internal $name, [$($existing)* ($value, $serialize_xml, $deserialize_xml)], [$($($tail)*)?]);
+    };
+    (@internal $name:ident, [$($existing:tt)*], [($value:expr, $xml:expr) $(, $($tail:tt)*)?]) => {
+        $crate::backend_tests!(@internal $name, [$($existing)* ($value, $xml, $xml)], [$($($tail)*)?]);
+    };
+    ($name:ident, [$($cases:tt)*]) => {
+        $crate::backend_tests!(@internal $name, [], [$($cases)*]);
+    };
+}
@@ -0,0 +1,186 @@
+//! Runner for the [W3C XML Conformance Test Suite](https://www.w3.org/XML/Test/).
+//!
+//! The suite is not vendored in this repository. To run it, download and extract it and point the `XMLITY_XMLCONF_DIR` environment variable to the extracted `xmlconf` directory:
+//!
+//! ```sh
+//! XMLITY_XMLCONF_DIR=/path/to/xmlconf cargo test -p xmlity-quick-xml --test conformance -- --ignored --nocapture
+//! ```
+//!
+//! Every test case of type `valid` is taken through a parse → [`XmlValue`] → serialize → parse round trip, and the result of the second parse is compared to the first. Failures are grouped by the stage they occurred in and reported at the end. Set `XMLITY_XMLCONF_DENY_FAILURES` to make the test fail if any case fails.
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use quick_xml::{events::Event, Reader};
+use xmlity::{value::XmlValue, Deserialize, Serialize};
+
+const XMLCONF_DIR_VAR: &str = "XMLITY_XMLCONF_DIR";
+const DENY_FAILURES_VAR: &str = "XMLITY_XMLCONF_DENY_FAILURES";
+
+/// A single `TEST` entry of a `TESTCASES` catalog.
+#[derive(Debug)]
+struct TestCase {
+    id: String,
+    path: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum FailureCategory {
+    Read,
+    Parse,
+    Serialize,
+    Reparse,
+    Mismatch,
+}
+
+impl fmt::Display for FailureCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FailureCategory::Read => f.write_str("reading the input file"),
+            FailureCategory::Parse => f.write_str("parsing the input"),
+            FailureCategory::Serialize => f.write_str("serializing the parsed value"),
+            FailureCategory::Reparse => f.write_str("parsing the serialized output"),
+            FailureCategory::Mismatch => f.write_str("round trip changed the value"),
+        }
+    }
+}
+
+fn catalog_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            catalog_files(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "xml") {
+            files.push(path);
+        }
+    }
+}
+
+/// Reads the `valid` test cases of a catalog file, returning nothing if the file is not a `TESTCASES` catalog.
+fn read_catalog(path: &Path) -> Vec<TestCase> {
+    let Ok(content) = fs::read(path) else {
+        return Vec::new();
+    };
+    let base = path.parent().unwrap_or(Path::new("."));
+
+    let mut reader = Reader::from_reader(content.as_slice());
+    let mut is_catalog = false;
+    let mut cases = Vec::new();
+
+    loop {
+        let start = match reader.read_event() {
+            Ok(Event::Start(start)) | Ok(Event::Empty(start)) => start,
+            Ok(Event::Eof) | Err(_) => break,
+            Ok(_) => continue,
+        };
+
+        match start.name().as_ref() {
+            b"TESTCASES" => is_catalog = true,
+            b"TEST" if is_catalog => {
+                let mut test_type = None;
+                let mut id = None;
+                let mut uri = None;
+                for attribute in start.attributes().flatten() {
+                    let value = String::from_utf8_lossy(&attribute.value).into_owned();
+                    match attribute.key.as_ref() {
+                        b"TYPE" => test_type = Some(value),
+                        b"ID" => id = Some(value),
+                        b"URI" => uri = Some(value),
+                        _ => {}
+                    }
+                }
+
+                if let (Some("valid"), Some(id), Some(uri)) = (test_type.as_deref(), id, uri) {
+                    cases.push(TestCase {
+                        id,
+                        path: base.join(uri),
+                    });
+                }
+            }
+            _ if !is_catalog => break,
+            _ => {}
+        }
+    }
+
+    cases
+}
+
+fn parse(input: &[u8]) -> Result<XmlValue, xmlity_quick_xml::de::Error> {
+    let mut deserializer = xmlity_quick_xml::Deserializer::from(input);
+    XmlValue::deserialize_seq(&mut deserializer)
+}
+
+fn round_trip(case: &TestCase) -> Result<(), (FailureCategory, String)> {
+    let input = fs::read(&case.path).map_err(|err| (FailureCategory::Read, err.to_string()))?;
+
+    let value = parse(&input).map_err(|err| (FailureCategory::Parse, err.to_string()))?;
+
+    let serializer = quick_xml::Writer::new(Vec::new());
+    let mut serializer = xmlity_quick_xml::Serializer::from(serializer);
+    value
+        .serialize(&mut serializer)
+        .map_err(|err| (FailureCategory::Serialize, err.to_string()))?;
+    let output = serializer.into_inner();
+
+    let reparsed = parse(&output).map_err(|err| (FailureCategory::Reparse, err.to_string()))?;
+
+    if reparsed != value {
+        return Err((
+            FailureCategory::Mismatch,
+            String::from_utf8_lossy(&output).into_owned(),
+        ));
+    }
+
+    Ok(())
+}
+
+#[test]
+#[ignore = "requires the W3C XML conformance suite, see the module documentation"]
+fn w3c_valid_round_trip() {
+    let Some(dir) = std::env::var_os(XMLCONF_DIR_VAR) else {
+        eprintln!("{XMLCONF_DIR_VAR} is not set, skipping the W3C conformance suite.");
+        return;
+    };
+
+    let mut files = Vec::new();
+    catalog_files(Path::new(&dir), &mut files);
+    files.sort();
+
+    let cases = files
+        .iter()
+        .flat_map(|file| read_catalog(file))
+        .collect::<Vec<_>>();
+
+    let mut failures: BTreeMap<FailureCategory, Vec<(String, String)>> = BTreeMap::new();
+    for case in &cases {
+        if let Err((category, message)) = round_trip(case) {
+            failures
+                .entry(category)
+                .or_default()
+                .push((case.id.clone(), message));
+        }
+    }
+
+    let failed = failures.values().map(Vec::len).sum::<usize>();
+    println!(
+        "W3C conformance: {} of {} valid test cases round tripped.",
+        cases.len() - failed,
+        cases.len()
+    );
+    for (category, cases) in &failures {
+        println!("\n{} failures while {category}:", cases.len());
+        for (id, message) in cases {
+            println!("  {id}: {message}");
+        }
+    }
+
+    if std::env::var_os(DENY_FAILURES_VAR).is_some() {
+        assert_eq!(failed, 0, "{failed} W3C conformance test cases failed");
+    }
+}
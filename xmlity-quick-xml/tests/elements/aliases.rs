@@ -0,0 +1,84 @@
+use pretty_assertions::assert_eq;
+use xmlity::{Deserialize, Serialize};
+
+use crate::define_test;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub enum Contact {
+    #[xelement(name = "email")]
+    #[xelement(name = "mail")]
+    Email(#[xattribute(name = "address")] String),
+    #[xelement(name = "phone", namespace = "http://example.com/v2")]
+    #[xelement(name = "phone", namespace = "http://example.com/v1")]
+    #[xelement(name = "telephone")]
+    Phone(#[xattribute(name = "number")] String),
+}
+
+define_test!(
+    variant_aliases,
+    [
+        (
+            Contact::Email("a@example.com".to_string()),
+            r#"<email address="a@example.com"/>"#
+        ),
+        (
+            Contact::Email("a@example.com".to_string()),
+            r#"<email address="a@example.com"/>"#,
+            r#"<mail address="a@example.com"/>"#
+        ),
+        (
+            Contact::Phone("123".to_string()),
+            r#"<a0:phone xmlns:a0="http://example.com/v2" number="123"/>"#,
+            r#"<phone xmlns="http://example.com/v1" number="123"/>"#
+        ),
+        (
+            Contact::Phone("123".to_string()),
+            r#"<a0:phone xmlns:a0="http://example.com/v2" number="123"/>"#,
+            r#"<telephone number="123"/>"#
+        )
+    ]
+);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "note")]
+#[xelement(name = "memo")]
+pub struct Note(String);
+
+define_test!(
+    struct_aliases,
+    [
+        (Note("Hi".to_string()), "<note>Hi</note>"),
+        (Note("Hi".to_string()), "<note>Hi</note>", "<memo>Hi</memo>")
+    ]
+);
+
+#[test]
+fn unknown_name_is_reported_against_first_name() {
+    let err = xmlity_quick_xml::from_str::<Note>("<letter>Hi</letter>").unwrap_err();
+
+    let xmlity_quick_xml::de::Error::WrongName { actual, expected } = err else {
+        panic!("unexpected error: {err:?}");
+    };
+    assert_eq!(actual.local_name().as_str(), "letter");
+    assert_eq!(expected.local_name().as_str(), "note");
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xvalue(exhaustive)]
+pub enum Shape {
+    #[xelement(name = "circle")]
+    #[xelement(name = "round")]
+    Circle,
+    #[xelement(name = "square")]
+    Square,
+}
+
+#[test]
+fn exhaustive_enum_lists_aliases() {
+    let err = xmlity_quick_xml::from_str::<Shape>("<oval/>").unwrap_err();
+
+    let xmlity_quick_xml::de::Error::NoMatchingVariant { expected, .. } = &err else {
+        panic!("unexpected error: {err:?}");
+    };
+    assert_eq!(expected, &["<circle>", "<round>", "<square>"]);
+}
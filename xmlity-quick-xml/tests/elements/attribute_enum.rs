@@ -0,0 +1,61 @@
+use crate::{define_test, utils::quick_xml_deserialize_test};
+
+use xmlity::{Deserialize, Serialize, SerializeAttribute};
+
+#[derive(Debug, PartialEq, SerializeAttribute, Deserialize)]
+pub enum Particle {
+    #[xattribute(name = "ref")]
+    Ref(String),
+    #[xattribute(name = "name")]
+    Name { name: String },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "element")]
+pub struct Element {
+    #[xattribute(deferred = true)]
+    pub particle: Particle,
+    #[xattribute(name = "type", optional)]
+    pub type_: Option<String>,
+}
+
+define_test!(
+    attribute_enum,
+    [
+        (
+            Element {
+                particle: Particle::Ref("xs:string".to_string()),
+                type_: None,
+            },
+            r#"<element ref="xs:string"/>"#
+        ),
+        (
+            Element {
+                particle: Particle::Name {
+                    name: "item".to_string()
+                },
+                type_: Some("xs:int".to_string()),
+            },
+            r#"<element name="item" type="xs:int"/>"#
+        ),
+        (
+            Element {
+                particle: Particle::Name {
+                    name: "item".to_string()
+                },
+                type_: Some("xs:int".to_string()),
+            },
+            r#"<element name="item" type="xs:int"/>"#,
+            r#"<element type="xs:int" name="item"/>"#
+        )
+    ]
+);
+
+#[test]
+fn attribute_enum_missing() {
+    let err = quick_xml_deserialize_test::<Element>(r#"<element type="xs:int"/>"#).unwrap_err();
+    assert!(
+        matches!(err, xmlity_quick_xml::de::Error::MissingField { .. }),
+        "{err:?}"
+    );
+}
@@ -0,0 +1,87 @@
+use pretty_assertions::assert_eq;
+use xmlity::{DeserializationGroup, Deserialize};
+use xmlity_quick_xml::Deserializer;
+
+fn from_str_with_duplicates<T: xmlity::DeserializeOwned>(
+    input: &str,
+) -> Result<T, xmlity_quick_xml::de::Error> {
+    let mut deserializer =
+        Deserializer::from(input.as_bytes()).with_allow_duplicate_attributes(true);
+    T::deserialize(&mut deserializer)
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "item")]
+pub struct Item {
+    #[xattribute(name = "class", extendable = "iterator", default)]
+    pub classes: Vec<String>,
+    #[xattribute(name = "note", extendable, default)]
+    pub note: String,
+    #[xattribute(name = "id")]
+    pub id: String,
+}
+
+#[test]
+fn extendable_attributes_merged() {
+    let actual: Item = from_str_with_duplicates(
+        r#"<item class="a" id="1" class="b" note="x" class="c" note="y"/>"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        actual,
+        Item {
+            classes: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            note: "xy".to_string(),
+            id: "1".to_string(),
+        }
+    );
+}
+
+#[test]
+fn extendable_attribute_single() {
+    let actual: Item = from_str_with_duplicates(r#"<item id="1" class="a"/>"#).unwrap();
+
+    assert_eq!(
+        actual,
+        Item {
+            classes: vec!["a".to_string()],
+            note: String::new(),
+            id: "1".to_string(),
+        }
+    );
+}
+
+#[test]
+fn duplicate_attributes_not_extendable() {
+    let actual: Item = from_str_with_duplicates(r#"<item id="1" id="2"/>"#).unwrap();
+
+    assert_eq!(actual.id, "1");
+}
+
+#[derive(Debug, PartialEq, DeserializationGroup)]
+pub struct Labels {
+    #[xattribute(name = "label", extendable = "iterator")]
+    pub labels: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "entry")]
+pub struct Entry {
+    #[xgroup]
+    pub labels: Labels,
+}
+
+#[test]
+fn extendable_attributes_in_group() {
+    let actual: Entry = from_str_with_duplicates(r#"<entry label="a" label="b"/>"#).unwrap();
+
+    assert_eq!(
+        actual,
+        Entry {
+            labels: Labels {
+                labels: vec!["a".to_string(), "b".to_string()],
+            },
+        }
+    );
+}
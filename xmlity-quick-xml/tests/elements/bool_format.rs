@@ -0,0 +1,60 @@
+use pretty_assertions::assert_eq;
+
+use crate::define_test;
+
+use xmlity::{ser::BoolFormat, Deserialize, Serialize};
+use xmlity_quick_xml::SerializerConfig;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "flags")]
+pub struct Flags {
+    #[xattribute(name = "visible")]
+    pub visible: bool,
+    #[xvalue(bool_format = "numeric")]
+    pub enabled: bool,
+}
+
+define_test!(
+    bool_format_numeric_field,
+    [
+        (
+            Flags {
+                visible: true,
+                enabled: true,
+            },
+            r#"<flags visible="true">1</flags>"#
+        ),
+        (
+            Flags {
+                visible: false,
+                enabled: false,
+            },
+            r#"<flags visible="false">0</flags>"#
+        )
+    ]
+);
+
+#[derive(Debug, PartialEq, Serialize)]
+#[xelement(name = "flags")]
+pub struct TextFlags {
+    #[xattribute(name = "visible")]
+    pub visible: bool,
+    #[xvalue(bool_format = "text")]
+    pub enabled: bool,
+}
+
+#[test]
+fn bool_format_config() {
+    let config = SerializerConfig::new().with_bool_format(BoolFormat::Numeric);
+
+    let actual = xmlity_quick_xml::to_string_with_config(
+        &TextFlags {
+            visible: true,
+            enabled: false,
+        },
+        &config,
+    )
+    .unwrap();
+
+    assert_eq!(actual, r#"<flags visible="1">false</flags>"#);
+}
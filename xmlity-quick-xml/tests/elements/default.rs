@@ -119,3 +119,85 @@ define_test!(
         (E { d: vec![] }, "<e/>")
     ]
 );
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "limits", default)]
+pub struct Limits {
+    #[xattribute(name = "max", default)]
+    pub max: u32,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self { max: 10 }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Default)]
+#[xelement(name = "server", default)]
+pub struct Server {
+    #[xattribute(name = "port", default)]
+    pub port: u16,
+    pub limits: Limits,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "config")]
+pub struct Config {
+    pub server: Server,
+    pub b: B,
+}
+
+define_test!(
+    missing_element_with_container_default,
+    [
+        (
+            Config {
+                server: Server {
+                    port: 80,
+                    limits: Limits { max: 5 }
+                },
+                b: B("A".to_string())
+            },
+            r#"<config><server port="80"><limits max="5"/></server><b>A</b></config>"#
+        ),
+        (
+            Config {
+                server: Server {
+                    port: 80,
+                    limits: Limits::default()
+                },
+                b: B("A".to_string())
+            },
+            r#"<config><server port="80"><limits max="10"/></server><b>A</b></config>"#,
+            r#"<config><server port="80"/><b>A</b></config>"#
+        ),
+        (
+            Config {
+                server: Server::default(),
+                b: B("A".to_string())
+            },
+            r#"<config><server port="0"><limits max="10"/></server><b>A</b></config>"#,
+            r#"<config><b>A</b></config>"#
+        )
+    ]
+);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "config", children_order = "strict")]
+pub struct StrictConfig {
+    pub b: B,
+    pub server: Server,
+}
+
+define_test!(
+    missing_element_with_container_default_in_strict_order,
+    [(
+        StrictConfig {
+            b: B("A".to_string()),
+            server: Server::default()
+        },
+        r#"<config><b>A</b><server port="0"><limits max="10"/></server></config>"#,
+        r#"<config><b>A</b></config>"#
+    )]
+);
@@ -0,0 +1,89 @@
+use pretty_assertions::assert_eq;
+
+use xmlity::{DeserializationGroup, Deserialize, SerializationGroup, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "item")]
+pub struct Item {
+    #[xattribute(name = "id", default_from = "name")]
+    pub id: String,
+    #[xattribute(name = "name")]
+    pub name: String,
+    #[xelement(name = "label", default_from = "id")]
+    pub label: String,
+}
+
+#[test]
+fn default_from_present() {
+    let actual: Item =
+        xmlity_quick_xml::from_str(r#"<item id="a" name="b"><label>c</label></item>"#).unwrap();
+
+    assert_eq!(
+        actual,
+        Item {
+            id: "a".to_string(),
+            name: "b".to_string(),
+            label: "c".to_string(),
+        }
+    );
+}
+
+#[test]
+fn default_from_missing() {
+    let actual: Item = xmlity_quick_xml::from_str(r#"<item name="b"/>"#).unwrap();
+
+    assert_eq!(
+        actual,
+        Item {
+            id: "b".to_string(),
+            name: "b".to_string(),
+            label: "b".to_string(),
+        }
+    );
+
+    let actual: Item = xmlity_quick_xml::from_str(r#"<item id="a" name="b"/>"#).unwrap();
+
+    assert_eq!(
+        actual,
+        Item {
+            id: "a".to_string(),
+            name: "b".to_string(),
+            label: "a".to_string(),
+        }
+    );
+}
+
+#[test]
+fn default_from_missing_source() {
+    xmlity_quick_xml::from_str::<Item>(r#"<item id="a"/>"#).unwrap_err();
+}
+
+#[derive(Debug, PartialEq, SerializationGroup, DeserializationGroup)]
+pub struct Names {
+    #[xattribute(name = "display", default_from = "name")]
+    pub display: String,
+    #[xattribute(name = "name")]
+    pub name: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "user")]
+pub struct User {
+    #[xgroup]
+    pub names: Names,
+}
+
+#[test]
+fn default_from_in_group() {
+    let actual: User = xmlity_quick_xml::from_str(r#"<user name="b"/>"#).unwrap();
+
+    assert_eq!(
+        actual,
+        User {
+            names: Names {
+                display: "b".to_string(),
+                name: "b".to_string(),
+            }
+        }
+    );
+}
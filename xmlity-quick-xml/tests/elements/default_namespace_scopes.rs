@@ -0,0 +1,301 @@
+use pretty_assertions::assert_eq;
+use xmlity::{
+    de::DeserializeContext, Deserialize, PrefixBuf, Serialize, XmlNamespaceBuf, XmlValue,
+};
+
+use super::namespace_access::DefaultNamespace;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "leaf")]
+pub struct Leaf {
+    #[xattribute(name = "id")]
+    pub id: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "leaf", namespace = "urn:a")]
+pub struct LeafA {
+    #[xattribute(name = "id")]
+    pub id: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "inner")]
+pub struct Inner {
+    pub leaves: Vec<Leaf>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "root", namespace = "urn:a")]
+pub struct Root {
+    pub first: LeafA,
+    pub inner: Inner,
+    pub last: LeafA,
+}
+
+fn leaf_a(id: &str) -> LeafA {
+    LeafA { id: id.to_string() }
+}
+
+fn leaf(id: &str) -> Leaf {
+    Leaf { id: id.to_string() }
+}
+
+#[test]
+fn rebind_default_namespace_to_none() {
+    let actual: Root = xmlity_quick_xml::from_str(
+        r#"<root xmlns="urn:a"><leaf id="1"/><inner xmlns=""><leaf id="2"/><leaf id="3"/></inner><leaf id="4"/></root>"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        actual,
+        Root {
+            first: leaf_a("1"),
+            inner: Inner {
+                leaves: vec![leaf("2"), leaf("3")],
+            },
+            last: leaf_a("4"),
+        }
+    );
+}
+
+#[test]
+fn empty_element_rebinding_does_not_leak() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[xelement(name = "root", namespace = "urn:a")]
+    pub struct Root {
+        pub inner: Inner,
+        pub leaves: Vec<LeafA>,
+    }
+
+    let actual: Root = xmlity_quick_xml::from_str(
+        r#"<root xmlns="urn:a"><inner xmlns=""/><leaf id="1"/><leaf id="2"/></root>"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        actual,
+        Root {
+            inner: Inner { leaves: Vec::new() },
+            leaves: vec![leaf_a("1"), leaf_a("2")],
+        }
+    );
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub enum AnyLeaf {
+    A(LeafA),
+    None(Leaf),
+}
+
+#[test]
+fn enum_variants_in_switched_scopes() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[xelement(name = "root", namespace = "urn:a")]
+    pub struct Root {
+        pub leaves: Vec<AnyLeaf>,
+    }
+
+    let actual: Root = xmlity_quick_xml::from_str(
+        r#"<root xmlns="urn:a"><leaf id="1"/><leaf xmlns="" id="2"/><leaf id="3"/></root>"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        actual.leaves,
+        vec![
+            AnyLeaf::A(leaf_a("1")),
+            AnyLeaf::None(leaf("2")),
+            AnyLeaf::A(leaf_a("3"))
+        ]
+    );
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "text")]
+pub struct Text(DefaultNamespace);
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "text", namespace = "urn:a")]
+pub struct TextA(DefaultNamespace);
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "root", namespace = "urn:a")]
+pub struct TextRoot {
+    pub first: TextA,
+    pub second: Text,
+    pub third: TextA,
+}
+
+#[test]
+fn default_namespace_of_context() {
+    let actual: TextRoot = xmlity_quick_xml::from_str(
+        r#"<root xmlns="urn:a"><text>a</text><text xmlns="">b</text><text>c</text></root>"#,
+    )
+    .unwrap();
+
+    let urn_a = Some(xmlity::XmlNamespaceBuf::new("urn:a".to_string()).unwrap());
+    assert_eq!(
+        actual,
+        TextRoot {
+            first: TextA(DefaultNamespace(urn_a.clone())),
+            second: Text(DefaultNamespace(None)),
+            third: TextA(DefaultNamespace(urn_a)),
+        }
+    );
+}
+
+#[test]
+fn xml_value_keeps_switched_namespaces() {
+    let xml = r#"<root xmlns="urn:a"><leaf id="1"/><inner xmlns=""><leaf id="2"/></inner><leaf id="4"/></root>"#;
+    let value: XmlValue = xmlity_quick_xml::from_str(xml).unwrap();
+
+    let expected = Root {
+        first: leaf_a("1"),
+        inner: Inner {
+            leaves: vec![leaf("2")],
+        },
+        last: leaf_a("4"),
+    };
+
+    assert_eq!(xmlity::value::from_value::<Root>(&value).unwrap(), expected);
+
+    let written = xmlity_quick_xml::to_string(&value).unwrap();
+    assert_eq!(
+        xmlity_quick_xml::from_str::<Root>(&written).unwrap(),
+        expected
+    );
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "mixed", namespace = "urn:a")]
+pub struct Mixed {
+    pub text: DefaultNamespace,
+    pub leaf: Leaf,
+}
+
+#[test]
+fn text_followed_by_rebinding_element() {
+    let actual: Mixed =
+        xmlity_quick_xml::from_str(r#"<mixed xmlns="urn:a">a<leaf xmlns="" id="1"/></mixed>"#)
+            .unwrap();
+
+    assert_eq!(
+        actual,
+        Mixed {
+            text: DefaultNamespace(Some(
+                xmlity::XmlNamespaceBuf::new("urn:a".to_string()).unwrap()
+            )),
+            leaf: leaf("1"),
+        }
+    );
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "p")]
+pub struct Paragraph {
+    #[xattribute(name = "lang", namespace = "urn:1", from_ancestor = "html")]
+    pub lang: String,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "body")]
+pub struct Body {
+    pub paragraph: Paragraph,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "html")]
+pub struct Html {
+    pub body: Body,
+}
+
+#[test]
+fn ancestor_attribute_resolved_in_ancestor_scope() {
+    let actual: Html = xmlity_quick_xml::from_str(
+        r#"<html xmlns:x="urn:1" x:lang="en"><body xmlns:x="urn:2"><p/></body></html>"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        actual.body.paragraph,
+        Paragraph {
+            lang: "en".to_string()
+        }
+    );
+}
+
+#[derive(Debug, PartialEq)]
+pub struct NamespacesInScope(pub Vec<(PrefixBuf, XmlNamespaceBuf)>);
+
+impl<'de> Deserialize<'de> for NamespacesInScope {
+    fn deserialize<D: xmlity::Deserializer<'de>>(reader: D) -> Result<Self, D::Error> {
+        struct __Visitor;
+
+        impl<'de> xmlity::de::Visitor<'de> for __Visitor {
+            type Value = NamespacesInScope;
+
+            fn visit_text<E, V>(self, value: V) -> Result<Self::Value, E>
+            where
+                E: xmlity::de::Error,
+                V: xmlity::de::XmlText<'de>,
+            {
+                Ok(NamespacesInScope(value.context().namespaces_in_scope()))
+            }
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("text")
+            }
+        }
+
+        reader.deserialize_any(__Visitor)
+    }
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "scope")]
+pub struct Scope(NamespacesInScope);
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "root", namespace = "urn:a")]
+pub struct ScopeRoot {
+    pub first: Scope,
+    pub second: ScopeA,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "scope", namespace = "urn:a")]
+pub struct ScopeA(NamespacesInScope);
+
+#[test]
+fn namespaces_in_scope() {
+    let actual: ScopeRoot = xmlity_quick_xml::from_str(
+        r#"<root xmlns="urn:a" xmlns:x="urn:x"><scope xmlns="" xmlns:y="urn:y">a</scope><scope xmlns:x="urn:z">b</scope></root>"#,
+    )
+    .unwrap();
+
+    let binding = |prefix: &str, namespace: &str| {
+        (
+            PrefixBuf::new(prefix.to_string()).unwrap(),
+            XmlNamespaceBuf::new(namespace.to_string()).unwrap(),
+        )
+    };
+
+    assert_eq!(
+        actual,
+        ScopeRoot {
+            first: Scope(NamespacesInScope(vec![
+                binding("y", "urn:y"),
+                binding("x", "urn:x"),
+            ])),
+            second: ScopeA(NamespacesInScope(vec![
+                binding("x", "urn:z"),
+                (
+                    PrefixBuf::default(),
+                    XmlNamespaceBuf::new("urn:a".to_string()).unwrap(),
+                ),
+            ])),
+        }
+    );
+}
@@ -0,0 +1,220 @@
+use pretty_assertions::assert_eq;
+use xmlity::{DeserializationGroup, Deserialize, SerializationGroup, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "p")]
+pub struct Paragraph {
+    #[xattribute(name = "lang", from_ancestor = "html", optional)]
+    pub lang: Option<String>,
+    #[xvalue]
+    pub text: String,
+}
+
+#[derive(Debug, PartialEq, SerializationGroup, DeserializationGroup)]
+pub struct Direction {
+    #[xattribute(name = "dir", from_ancestor = "html", default)]
+    pub dir: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "body")]
+pub struct Body {
+    #[xattribute(name = "lang", from_ancestor = "html")]
+    pub lang: String,
+    #[xattribute(name = "class", optional)]
+    pub class: Option<String>,
+    #[xgroup]
+    pub direction: Direction,
+    pub paragraphs: Vec<Paragraph>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "html")]
+pub struct Html {
+    #[xattribute(name = "lang")]
+    pub lang: String,
+    #[xattribute(name = "dir", optional)]
+    pub dir: Option<String>,
+    pub body: Body,
+}
+
+fn html() -> Html {
+    Html {
+        lang: "en".to_string(),
+        dir: Some("ltr".to_string()),
+        body: Body {
+            lang: "en".to_string(),
+            class: Some("main".to_string()),
+            direction: Direction {
+                dir: "ltr".to_string(),
+            },
+            paragraphs: vec![
+                Paragraph {
+                    lang: Some("en".to_string()),
+                    text: "Hello".to_string(),
+                },
+                Paragraph {
+                    lang: Some("en".to_string()),
+                    text: "World".to_string(),
+                },
+            ],
+        },
+    }
+}
+
+const HTML: &str =
+    r#"<html lang="en" dir="ltr"><body class="main"><p>Hello</p><p>World</p></body></html>"#;
+
+#[test]
+fn from_ancestor_deserialize() {
+    let actual: Html = xmlity_quick_xml::from_str(HTML).unwrap();
+    assert_eq!(actual, html());
+}
+
+#[test]
+fn from_ancestor_serialize() {
+    assert_eq!(xmlity_quick_xml::to_string(&html()).unwrap(), HTML);
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "html")]
+pub struct Outer {
+    #[xattribute(name = "lang")]
+    pub lang: String,
+    pub body: OuterBody,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "body")]
+pub struct OuterBody {
+    pub html: Html,
+}
+
+#[test]
+fn from_ancestor_closest_ancestor() {
+    let actual: Outer = xmlity_quick_xml::from_str(
+        r#"<html lang="en"><body><html lang="de"><body><p>Hallo</p></body></html></body></html>"#,
+    )
+    .unwrap();
+
+    let expected = Outer {
+        lang: "en".to_string(),
+        body: OuterBody {
+            html: Html {
+                lang: "de".to_string(),
+                dir: None,
+                body: Body {
+                    lang: "de".to_string(),
+                    class: None,
+                    direction: Direction { dir: String::new() },
+                    paragraphs: vec![Paragraph {
+                        lang: Some("de".to_string()),
+                        text: "Hallo".to_string(),
+                    }],
+                },
+            },
+        },
+    };
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn from_ancestor_missing_ancestor() {
+    let actual: Paragraph = xmlity_quick_xml::from_str("<p>Hello</p>").unwrap();
+    assert_eq!(
+        actual,
+        Paragraph {
+            lang: None,
+            text: "Hello".to_string(),
+        }
+    );
+
+    xmlity_quick_xml::from_str::<Body>("<body><p>Hello</p></body>").unwrap_err();
+}
+
+#[test]
+fn from_ancestor_deserialize_via_value() {
+    let actual: Html = xmlity_quick_xml::from_str_via_value(HTML).unwrap();
+    assert_eq!(actual, html());
+}
+
+#[test]
+fn from_ancestor_closest_ancestor_via_value() {
+    let actual: Outer = xmlity_quick_xml::from_str_via_value(
+        r#"<html lang="en"><body><html lang="de"><body><p>Hallo</p></body></html></body></html>"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        actual.body.html.body.paragraphs[0].lang.as_deref(),
+        Some("de")
+    );
+    assert_eq!(actual.body.html.body.lang, "de");
+}
+
+#[test]
+fn from_ancestor_after_doctype() {
+    let xml = format!("<!DOCTYPE html [ <!ENTITY lang \"en\"> ]>\n{HTML}");
+    let mut deserializer =
+        xmlity_quick_xml::Deserializer::from(xml.as_bytes()).with_skip_prolog(true);
+    let actual = Html::deserialize(&mut deserializer).unwrap();
+    assert_eq!(actual, html());
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "item", namespace = "http://example.com/order")]
+pub struct Item {
+    #[xattribute(
+        name = "currency",
+        from_ancestor = "order",
+        ancestor_namespace = "http://example.com/order",
+        optional
+    )]
+    pub currency: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "order", namespace = "http://example.com/other")]
+pub struct OtherOrder {
+    pub item: Item,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "order", namespace = "http://example.com/order")]
+pub struct Order {
+    pub other: OtherOrder,
+}
+
+const ORDER: &str = r#"<o:order xmlns:o="http://example.com/order" currency="EUR"><x:order xmlns:x="http://example.com/other" currency="USD"><o:item/></x:order></o:order>"#;
+
+fn order() -> Order {
+    Order {
+        other: OtherOrder {
+            item: Item {
+                currency: Some("EUR".to_string()),
+            },
+        },
+    }
+}
+
+#[test]
+fn from_ancestor_matches_namespace() {
+    let actual: Order = xmlity_quick_xml::from_str(ORDER).unwrap();
+    assert_eq!(actual, order());
+}
+
+#[test]
+fn from_ancestor_matches_namespace_via_value() {
+    let actual: Order = xmlity_quick_xml::from_str_via_value(ORDER).unwrap();
+    assert_eq!(actual, order());
+}
+
+#[test]
+fn from_ancestor_ignores_other_namespaces() {
+    let actual: OtherOrder = xmlity_quick_xml::from_str(
+        r#"<order xmlns="http://example.com/other" currency="USD"><o:item xmlns:o="http://example.com/order"/></order>"#,
+    )
+    .unwrap();
+    assert_eq!(actual.item.currency, None);
+}
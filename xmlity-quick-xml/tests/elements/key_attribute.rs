@@ -0,0 +1,180 @@
+use std::collections::{BTreeMap, HashMap};
+
+use pretty_assertions::assert_eq;
+
+use crate::{define_deserialize_test, define_test};
+
+use xmlity::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "user")]
+pub struct User {
+    #[xattribute(name = "id")]
+    pub id: String,
+    #[xattribute(name = "name")]
+    pub name: String,
+}
+
+fn user(id: &str, name: &str) -> User {
+    User {
+        id: id.to_string(),
+        name: name.to_string(),
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "users")]
+pub struct Users {
+    #[xvalue(key_attribute = "id")]
+    pub users: BTreeMap<String, User>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(
+    name = "users",
+    children_order = "strict",
+    allow_unknown_children = "none"
+)]
+pub struct StrictUsers {
+    #[xvalue(key_attribute = "id")]
+    pub users: BTreeMap<String, User>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "users", allow_unknown_children = "any")]
+pub struct AnyUsers {
+    #[xvalue(key_attribute = "id")]
+    pub users: BTreeMap<String, User>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "users")]
+pub struct FirstUsers {
+    #[xvalue(key_attribute = "id", duplicate_keys = "first")]
+    pub users: HashMap<String, User>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "users")]
+pub struct LastUsers {
+    #[xvalue(key_attribute = "id", duplicate_keys = "last")]
+    pub users: HashMap<String, User>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "item")]
+pub struct Item {
+    #[xattribute(name = "n")]
+    pub n: u32,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "inventory")]
+pub struct Inventory {
+    #[xvalue(key_attribute = "n")]
+    pub items: BTreeMap<u32, Item>,
+    pub note: Option<Note>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "note")]
+pub struct Note(String);
+
+define_test!(
+    key_attribute_btree_map,
+    [
+        (
+            Users {
+                users: BTreeMap::from([
+                    ("a".to_string(), user("a", "Alice")),
+                    ("b".to_string(), user("b", "Bob")),
+                ]),
+            },
+            r#"<users><user id="a" name="Alice"/><user id="b" name="Bob"/></users>"#
+        ),
+        (
+            Users {
+                users: BTreeMap::new()
+            },
+            "<users/>"
+        )
+    ]
+);
+
+define_deserialize_test!(
+    key_attribute_whitespace_and_order,
+    [(
+        Users {
+            users: BTreeMap::from([
+                ("a".to_string(), user("a", "Alice")),
+                ("b".to_string(), user("b", "Bob")),
+            ]),
+        },
+        "<users>\n  <user id=\"b\" name=\"Bob\"/>\n  <user id=\"a\" name=\"Alice\"/>\n</users>"
+    )]
+);
+
+define_test!(
+    key_attribute_strict_order,
+    [(
+        StrictUsers {
+            users: BTreeMap::from([("a".to_string(), user("a", "Alice"))]),
+        },
+        r#"<users><user id="a" name="Alice"/></users>"#
+    )]
+);
+
+define_test!(
+    key_attribute_typed_key,
+    [(
+        Inventory {
+            items: BTreeMap::from([(1, Item { n: 1 }), (2, Item { n: 2 })]),
+            note: Some(Note("Stock".to_string())),
+        },
+        r#"<inventory><item n="1"/><item n="2"/><note>Stock</note></inventory>"#
+    )]
+);
+
+const DUPLICATES: &str = r#"<users><user id="a" name="Alice"/><user id="a" name="Anna"/></users>"#;
+
+#[test]
+fn key_attribute_duplicates_error() {
+    let err = xmlity_quick_xml::from_str::<StrictUsers>(DUPLICATES).unwrap_err();
+
+    assert!(
+        err.to_string()
+            .contains("Duplicate value of key attribute id"),
+        "{err}"
+    );
+}
+
+#[test]
+fn key_attribute_duplicates_error_with_unknown_children() {
+    let err = xmlity_quick_xml::from_str::<AnyUsers>(DUPLICATES).unwrap_err();
+
+    assert!(
+        err.to_string()
+            .contains("Duplicate value of key attribute id"),
+        "{err}"
+    );
+}
+
+#[test]
+fn key_attribute_duplicates_first() {
+    let users: FirstUsers = xmlity_quick_xml::from_str(DUPLICATES).unwrap();
+
+    assert_eq!(
+        users.users,
+        HashMap::from([("a".to_string(), user("a", "Alice"))])
+    );
+}
+
+#[test]
+fn key_attribute_duplicates_last() {
+    let users: LastUsers = xmlity_quick_xml::from_str(DUPLICATES).unwrap();
+
+    assert_eq!(
+        users.users,
+        HashMap::from([("a".to_string(), user("a", "Anna"))])
+    );
+}
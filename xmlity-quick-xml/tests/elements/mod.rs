@@ -1,11 +1,18 @@
+pub mod aliases;
 pub mod allow_unknown;
 pub mod any_attributes;
 pub mod attribute;
+pub mod attribute_enum;
+pub mod attribute_extendable;
 pub mod attribute_namespace;
 pub mod basic;
+pub mod bool_format;
 pub mod default;
+pub mod default_from;
+pub mod default_namespace_scopes;
 pub mod enforce_prefix;
 pub mod extendable;
+pub mod from_ancestor;
 pub mod generics;
 pub mod group_element_equivalent;
 pub mod ignore_comments;
@@ -13,10 +20,19 @@ pub mod ignore_whitespace;
 pub mod inline_attribute_declarations;
 pub mod inline_blend;
 pub mod inline_declarations;
+pub mod key_attribute;
 pub mod mixed;
 pub mod namespace_access;
 pub mod namespace_expr;
+pub mod namespace_expr_self;
 pub mod option;
+pub mod qualified_names;
+pub mod radix_prefixes;
+pub mod raw_text;
+pub mod rename_all;
+pub mod schema_location;
 pub mod single_namespace;
 pub mod skip_serializing_if;
 pub mod strict_order;
+pub mod tuple_struct;
+pub mod variants;
@@ -0,0 +1,176 @@
+use std::marker::PhantomData;
+
+use pretty_assertions::assert_eq;
+use xmlity::{Deserialize, DeserializeOwned, Serialize, XmlNamespace};
+
+pub trait Schema {
+    const NAMESPACE: &'static XmlNamespace;
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Xs;
+
+impl Schema for Xs {
+    const NAMESPACE: &'static XmlNamespace = XmlNamespace::XS;
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Xsi;
+
+impl Schema for Xsi {
+    const NAMESPACE: &'static XmlNamespace = XmlNamespace::XSI;
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "annotation", namespace_expr = Self::NAMESPACE)]
+pub struct Annotation<S: Schema> {
+    #[xattribute(name = "id", namespace_expr = Self::NAMESPACE)]
+    pub id: String,
+    #[xelement(name = "documentation", namespace_expr = Self::NAMESPACE)]
+    pub documentation: String,
+    #[xgroup]
+    pub schema: PhantomData<S>,
+}
+
+impl<S: Schema> Annotation<S> {
+    const NAMESPACE: &'static XmlNamespace = S::NAMESPACE;
+}
+
+fn annotation<S: Schema>() -> Annotation<S> {
+    Annotation {
+        id: "a".to_string(),
+        documentation: "Text".to_string(),
+        schema: PhantomData,
+    }
+}
+
+const XS_ANNOTATION: &str = r#"<a0:annotation xmlns:a0="http://www.w3.org/2001/XMLSchema" a0:id="a"><a0:documentation>Text</a0:documentation></a0:annotation>"#;
+
+const XSI_ANNOTATION: &str = r#"<a0:annotation xmlns:a0="http://www.w3.org/2001/XMLSchema-instance" a0:id="a"><a0:documentation>Text</a0:documentation></a0:annotation>"#;
+
+#[test]
+fn self_namespace_serialize() {
+    assert_eq!(
+        xmlity_quick_xml::to_string(&annotation::<Xs>()).unwrap(),
+        XS_ANNOTATION
+    );
+    assert_eq!(
+        xmlity_quick_xml::to_string(&annotation::<Xsi>()).unwrap(),
+        XSI_ANNOTATION
+    );
+}
+
+#[test]
+fn self_namespace_deserialize() {
+    let actual: Annotation<Xs> = xmlity_quick_xml::from_str(XS_ANNOTATION).unwrap();
+    assert_eq!(actual, annotation());
+
+    let actual: Annotation<Xsi> = xmlity_quick_xml::from_str(XSI_ANNOTATION).unwrap();
+    assert_eq!(actual, annotation());
+}
+
+#[test]
+fn self_namespace_wrong_namespace_deserialize() {
+    xmlity_quick_xml::from_str::<Annotation<Xs>>(XSI_ANNOTATION).unwrap_err();
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "entry")]
+pub struct Entry<T: Serialize + DeserializeOwned> {
+    #[xelement(name = "key")]
+    pub key: String,
+    #[xelement(name = "value")]
+    pub value: T,
+}
+
+#[test]
+fn generic_element_field() {
+    let entry = Entry {
+        key: "size".to_string(),
+        value: 12u32,
+    };
+    let xml = "<entry><key>size</key><value>12</value></entry>";
+
+    assert_eq!(xmlity_quick_xml::to_string(&entry).unwrap(), xml);
+    assert_eq!(
+        xmlity_quick_xml::from_str::<Entry<u32>>(xml).unwrap(),
+        entry
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "pair")]
+pub struct Pair<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    #[xelement(name = "first")]
+    pub first: T,
+    #[xelement(name = "second")]
+    pub second: T,
+}
+
+#[test]
+fn where_bounded_element_fields() {
+    let pair = Pair {
+        first: 1u8,
+        second: 2u8,
+    };
+    let xml = "<pair><first>1</first><second>2</second></pair>";
+
+    assert_eq!(xmlity_quick_xml::to_string(&pair).unwrap(), xml);
+    assert_eq!(xmlity_quick_xml::from_str::<Pair<u8>>(xml).unwrap(), pair);
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "versioned", namespace_expr = Self::NAMESPACE)]
+pub struct Versioned<const V: u8> {
+    #[xelement(name = "value", namespace_expr = Self::NAMESPACE)]
+    pub value: String,
+}
+
+impl<const V: u8> Versioned<V> {
+    const NAMESPACE: &'static XmlNamespace = match V {
+        1 => XmlNamespace::XS,
+        _ => XmlNamespace::XSI,
+    };
+}
+
+#[test]
+fn const_generic_namespace() {
+    let xml = r#"<a0:versioned xmlns:a0="http://www.w3.org/2001/XMLSchema"><a0:value>v</a0:value></a0:versioned>"#;
+    let versioned = Versioned::<1> {
+        value: "v".to_string(),
+    };
+
+    assert_eq!(xmlity_quick_xml::to_string(&versioned).unwrap(), xml);
+    assert_eq!(
+        xmlity_quick_xml::from_str::<Versioned<1>>(xml).unwrap(),
+        versioned
+    );
+    xmlity_quick_xml::from_str::<Versioned<2>>(xml).unwrap_err();
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub enum Either<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    Pair(Pair<T>),
+    #[xelement(name = "single")]
+    Single(T),
+}
+
+#[test]
+fn where_bounded_enum() {
+    let xml = "<single>3</single>";
+
+    assert_eq!(
+        xmlity_quick_xml::to_string(&Either::Single(3u8)).unwrap(),
+        xml
+    );
+    assert_eq!(
+        xmlity_quick_xml::from_str::<Either<u8>>(xml).unwrap(),
+        Either::Single(3)
+    );
+}
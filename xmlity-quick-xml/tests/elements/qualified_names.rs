@@ -0,0 +1,96 @@
+use crate::define_test;
+use xmlity::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xmlns(xs = "http://www.w3.org/2001/XMLSchema")]
+#[xelement(name = "xs:element")]
+pub struct Element {
+    #[xattribute(name = "name")]
+    pub name: String,
+    #[xattribute(name = "xml:lang", optional)]
+    pub lang: Option<String>,
+    #[xelement(name = "xs:annotation", optional)]
+    pub annotation: Option<String>,
+}
+
+define_test!(
+    qualified_names,
+    [
+        (
+            Element {
+                name: "a".to_string(),
+                lang: None,
+                annotation: None,
+            },
+            r#"<xs:element xmlns:xs="http://www.w3.org/2001/XMLSchema" name="a"/>"#
+        ),
+        (
+            Element {
+                name: "b".to_string(),
+                lang: Some("en".to_string()),
+                annotation: Some("Docs".to_string()),
+            },
+            r#"<xs:element xmlns:xs="http://www.w3.org/2001/XMLSchema" name="b" xml:lang="en"><xs:annotation>Docs</xs:annotation></xs:element>"#,
+            r#"<s:element xmlns:s="http://www.w3.org/2001/XMLSchema" name="b" xml:lang="en"><s:annotation>Docs</s:annotation></s:element>"#
+        )
+    ]
+);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xmlns(
+    soap = "http://schemas.xmlsoap.org/soap/envelope/",
+    xsi = "http://www.w3.org/2001/XMLSchema-instance"
+)]
+#[xelement(name = "soap:Envelope")]
+pub struct Envelope {
+    #[xelement(name = "soap:Body")]
+    pub body: String,
+}
+
+define_test!(
+    declarations_are_serialized_up_front,
+    [(
+        Envelope {
+            body: "Hello".to_string(),
+        },
+        r#"<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><soap:Body>Hello</soap:Body></soap:Envelope>"#
+    )]
+);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xmlns(ex = "http://example.com")]
+#[xelement(name = "root")]
+pub struct Unused;
+
+define_test!(
+    unused_declarations_are_serialized,
+    [(Unused, r#"<root xmlns:ex="http://example.com"/>"#)]
+);
+
+/// The prefix declared with `#[xmlns]` is in scope for the element, so it is used instead of the preferred prefix.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xmlns(xs = "http://www.w3.org/2001/XMLSchema")]
+#[xelement(name = "xs:import", preferred_prefix = "xsd")]
+pub struct Import;
+
+define_test!(
+    declared_prefix_is_used_over_preferred_prefix,
+    [(
+        Import,
+        r#"<xs:import xmlns:xs="http://www.w3.org/2001/XMLSchema"/>"#,
+        r#"<xsd:import xmlns:xsd="http://www.w3.org/2001/XMLSchema"/>"#
+    )]
+);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xmlns("ex-1.a" = "http://example.com")]
+#[xelement(name = "ex-1.a:root")]
+pub struct NonIdentifierPrefix;
+
+define_test!(
+    non_identifier_prefix,
+    [(
+        NonIdentifierPrefix,
+        r#"<ex-1.a:root xmlns:ex-1.a="http://example.com"/>"#
+    )]
+);
@@ -0,0 +1,80 @@
+use crate::{define_deserialize_test, define_test};
+
+use xmlity::{types::radix::Hex, Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "register")]
+pub struct Register {
+    #[xattribute(name = "offset")]
+    pub offset: Hex<u32>,
+    pub reset: Reset,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "reset")]
+pub struct Reset {
+    #[xvalue(radix_prefixes)]
+    pub value: i64,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "mask")]
+pub struct Mask {
+    #[xvalue(radix_prefixes, int_format = "hex")]
+    pub value: u16,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "width", children_order = "strict")]
+pub struct Width {
+    #[xvalue(radix_prefixes)]
+    pub value: u8,
+}
+
+define_test!(
+    radix_prefixes_decimal_output,
+    [
+        (Reset { value: 31 }, "<reset>31</reset>"),
+        (Reset { value: -5 }, "<reset>-5</reset>")
+    ]
+);
+
+define_deserialize_test!(
+    radix_prefixes_input,
+    [
+        (Reset { value: 31 }, "<reset>0x1F</reset>"),
+        (Reset { value: 15 }, "<reset>0o17</reset>"),
+        (Reset { value: -5 }, "<reset>-0b101</reset>"),
+        (Reset { value: 17 }, "<reset>17</reset>")
+    ]
+);
+
+define_test!(
+    int_format_hex,
+    [
+        (Mask { value: 255 }, "<mask>0xff</mask>"),
+        (Mask { value: 0 }, "<mask>0x0</mask>")
+    ]
+);
+
+define_deserialize_test!(
+    radix_prefixes_strict_order,
+    [(Width { value: 8 }, "<width>0b1000</width>")]
+);
+
+define_test!(
+    hex_attribute,
+    [(
+        Register {
+            offset: Hex(0x10),
+            reset: Reset { value: 0 },
+        },
+        r#"<register offset="0x10"><reset>0</reset></register>"#
+    )]
+);
+
+#[test]
+fn radix_prefixes_reject_misplaced_sign() {
+    assert!(xmlity_quick_xml::from_str::<Reset>("<reset>0x-1</reset>").is_err());
+    assert!(xmlity_quick_xml::from_str::<Width>("<width>-0x1</width>").is_err());
+}
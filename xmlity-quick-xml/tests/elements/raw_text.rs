@@ -0,0 +1,42 @@
+use crate::{define_deserialize_test, define_test};
+
+use xmlity::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "price")]
+pub struct Price {
+    #[xattribute(name = "currency")]
+    pub currency: String,
+    #[xvalue(raw_text)]
+    pub amount: String,
+}
+
+fn price(amount: &str) -> Price {
+    Price {
+        currency: "USD".to_string(),
+        amount: amount.to_string(),
+    }
+}
+
+define_test!(
+    raw_text,
+    [
+        (price("10.5"), r#"<price currency="USD">10.5</price>"#),
+        (price(""), r#"<price currency="USD"></price>"#)
+    ]
+);
+
+define_deserialize_test!(
+    raw_text_joined,
+    [
+        (
+            price("10.5"),
+            r#"<price currency="USD">10<![CDATA[.5]]></price>"#
+        ),
+        (
+            price("1 < 2 & 3"),
+            r#"<price currency="USD">1 &lt; 2 &#38; 3</price>"#
+        ),
+        (price(""), r#"<price currency="USD"/>"#)
+    ]
+);
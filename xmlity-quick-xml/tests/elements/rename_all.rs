@@ -0,0 +1,95 @@
+use crate::define_test;
+
+use xmlity::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "title")]
+pub struct Title(String);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "book", rename_all = "camelCase")]
+pub struct Book {
+    #[xattribute]
+    pub page_count: u32,
+    #[xattribute]
+    pub first_edition: bool,
+    #[xattribute(name = "ISBN")]
+    pub isbn: String,
+    pub title: Title,
+    #[xelement]
+    pub original_title: String,
+}
+
+define_test!(
+    camel_case_fields,
+    [(
+        Book {
+            page_count: 320,
+            first_edition: true,
+            isbn: "0-0000-0000-0".to_string(),
+            title: Title("Title".to_string()),
+            original_title: "Titel".to_string(),
+        },
+        r#"<book pageCount="320" firstEdition="true" ISBN="0-0000-0000-0"><title>Title</title><originalTitle>Titel</originalTitle></book>"#
+    )]
+);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "config", rename_all = "kebab-case")]
+pub struct Config {
+    #[xattribute]
+    pub r#type: String,
+    #[xattribute(optional)]
+    pub max_retry_count: Option<u32>,
+    #[xelement]
+    pub log_file_path: String,
+}
+
+define_test!(
+    kebab_case_fields,
+    [
+        (
+            Config {
+                r#type: "server".to_string(),
+                max_retry_count: Some(3),
+                log_file_path: "/var/log/app".to_string(),
+            },
+            r#"<config type="server" max-retry-count="3"><log-file-path>/var/log/app</log-file-path></config>"#
+        ),
+        (
+            Config {
+                r#type: "client".to_string(),
+                max_retry_count: None,
+                log_file_path: "app.log".to_string(),
+            },
+            r#"<config type="client"><log-file-path>app.log</log-file-path></config>"#
+        )
+    ]
+);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub enum Event {
+    #[xelement(name = "Click", rename_all = "PascalCase")]
+    Click {
+        #[xattribute]
+        pos_x: i32,
+        #[xattribute]
+        pos_y: i32,
+    },
+    #[xelement(name = "key")]
+    Key {
+        #[xattribute]
+        key_code: u32,
+    },
+}
+
+define_test!(
+    variant_fields,
+    [
+        (
+            Event::Click { pos_x: 1, pos_y: 2 },
+            r#"<Click PosX="1" PosY="2"/>"#
+        ),
+        (Event::Key { key_code: 13 }, r#"<key key_code="13"/>"#)
+    ]
+);
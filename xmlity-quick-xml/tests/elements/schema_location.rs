@@ -0,0 +1,43 @@
+use crate::define_test;
+
+use xmlity::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(
+    name = "note",
+    namespace = "http://example.com/note",
+    xsi_schema_location = "http://example.com/note note.xsd"
+)]
+pub struct Note {
+    #[xattribute(name = "id")]
+    pub id: String,
+    #[xelement(name = "body", namespace = "http://example.com/note")]
+    pub body: String,
+}
+
+define_test!(
+    xsi_schema_location,
+    [(
+        Note {
+            id: "1".to_string(),
+            body: "Hello".to_string(),
+        },
+        r#"<a0:note xmlns:a0="http://example.com/note" id="1" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:schemaLocation="http://example.com/note note.xsd"><a0:body>Hello</a0:body></a0:note>"#
+    )]
+);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(
+    name = "config",
+    no_namespace_schema_location = "config.xsd",
+    xsi_schema_location = "http://example.com/note note.xsd"
+)]
+pub struct Config;
+
+define_test!(
+    no_namespace_schema_location,
+    [(
+        Config,
+        r#"<config xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:schemaLocation="http://example.com/note note.xsd" xsi:noNamespaceSchemaLocation="config.xsd"/>"#
+    )]
+);
@@ -0,0 +1,99 @@
+use crate::define_test;
+
+use xmlity::{Deserialize, Serialize, SerializeAttribute};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "a")]
+pub struct A(String);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "b")]
+pub struct B(String);
+
+#[derive(Debug, PartialEq, SerializeAttribute, Deserialize)]
+#[xattribute(name = "id")]
+pub struct Id(String);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "pair")]
+pub struct Pair(A, B);
+
+define_test!(
+    tuple_struct_children,
+    [(
+        Pair(A("1".to_string()), B("2".to_string())),
+        "<pair><a>1</a><b>2</b></pair>"
+    )]
+);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "item")]
+pub struct Item(
+    #[xattribute(deferred = true)] Id,
+    #[xelement(name = "name")] String,
+    B,
+);
+
+define_test!(
+    tuple_struct_field_options,
+    [(
+        Item(Id("x".to_string()), "Name".to_string(), B("2".to_string())),
+        r#"<item id="x"><name>Name</name><b>2</b></item>"#
+    )]
+);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub enum Choice {
+    #[xelement(name = "pair")]
+    Pair(A, B),
+    #[xelement(name = "item")]
+    Item(#[xattribute(deferred = true)] Id, B),
+}
+
+define_test!(
+    tuple_variant_children,
+    [
+        (
+            Choice::Pair(A("1".to_string()), B("2".to_string())),
+            "<pair><a>1</a><b>2</b></pair>"
+        ),
+        (
+            Choice::Item(Id("x".to_string()), B("2".to_string())),
+            r#"<item id="x"><b>2</b></item>"#
+        )
+    ]
+);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "same")]
+pub struct Same(A, A);
+
+define_test!(
+    tuple_struct_same_types,
+    [(
+        Same(A("1".to_string()), A("2".to_string())),
+        "<same><a>1</a><a>2</a></same>"
+    )]
+);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "strict", children_order = "strict")]
+pub struct Strict(A, B, Option<A>);
+
+define_test!(
+    tuple_struct_strict_order,
+    [
+        (
+            Strict(A("1".to_string()), B("2".to_string()), None),
+            "<strict><a>1</a><b>2</b></strict>"
+        ),
+        (
+            Strict(
+                A("1".to_string()),
+                B("2".to_string()),
+                Some(A("3".to_string()))
+            ),
+            "<strict><a>1</a><b>2</b><a>3</a></strict>"
+        )
+    ]
+);
@@ -0,0 +1,81 @@
+use crate::define_test;
+
+use xmlity::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub enum Shape {
+    #[xelement(name = "circle")]
+    Circle {
+        #[xattribute(name = "r")]
+        r: f64,
+    },
+    #[xelement(name = "rect")]
+    Rect {
+        #[xattribute(name = "w")]
+        w: f64,
+        #[xattribute(name = "h")]
+        h: f64,
+    },
+    #[xelement(name = "point")]
+    Point,
+}
+
+define_test!(
+    named_field_variants,
+    [
+        (Shape::Circle { r: 1.5 }, r#"<circle r="1.5"/>"#),
+        (Shape::Rect { w: 2.0, h: 3.0 }, r#"<rect w="2" h="3"/>"#),
+        (Shape::Point, "<point/>")
+    ]
+);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub enum Entry {
+    #[xelement(name = "note", namespace = "http://example.com/notes")]
+    Note {
+        #[xelement(name = "title", namespace = "http://example.com/notes")]
+        title: String,
+        #[xelement(name = "body", namespace = "http://example.com/notes")]
+        body: String,
+    },
+    #[xelement(name = "tag")]
+    Tag(#[xattribute(name = "name")] String, String),
+}
+
+define_test!(
+    element_variants_with_children,
+    [
+        (
+            Entry::Note {
+                title: "Title".to_string(),
+                body: "Body".to_string(),
+            },
+            r#"<a0:note xmlns:a0="http://example.com/notes"><a0:title>Title</a0:title><a0:body>Body</a0:body></a0:note>"#,
+            r#"<note xmlns="http://example.com/notes"><title>Title</title><body>Body</body></note>"#
+        ),
+        (
+            Entry::Tag("lang".to_string(), "rust".to_string()),
+            r#"<tag name="lang">rust</tag>"#
+        )
+    ]
+);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "drawing")]
+pub struct Drawing {
+    pub shapes: Vec<Shape>,
+}
+
+define_test!(
+    element_variants_as_children,
+    [(
+        Drawing {
+            shapes: vec![
+                Shape::Rect { w: 1.0, h: 2.0 },
+                Shape::Point,
+                Shape::Circle { r: 4.0 },
+            ],
+        },
+        r#"<drawing><rect w="1" h="2"/><point/><circle r="4"/></drawing>"#
+    )]
+);
@@ -0,0 +1,131 @@
+use xmlity::{DeserializationGroup, Deserialize, SerializationGroup, Serialize};
+
+use crate::define_test;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "street")]
+pub struct Street(pub String);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "city")]
+pub struct City(pub String);
+
+#[derive(Debug, PartialEq, SerializationGroup, DeserializationGroup)]
+pub struct AddressGroup {
+    #[xattribute(name = "country")]
+    pub country: String,
+    pub street: Street,
+    pub city: City,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "person")]
+pub struct Person {
+    #[xattribute(name = "name")]
+    pub name: String,
+    #[xelement(name = "address", group = true)]
+    pub address: AddressGroup,
+}
+
+define_test!(
+    element_group_field,
+    [(
+        Person {
+            name: "Tove".to_string(),
+            address: AddressGroup {
+                country: "Norway".to_string(),
+                street: Street("Storgata 1".to_string()),
+                city: City("Oslo".to_string()),
+            },
+        },
+        r#"<person name="Tove"><address country="Norway"><street>Storgata 1</street><city>Oslo</city></address></person>"#
+    )]
+);
+
+#[derive(Debug, PartialEq, Default, SerializationGroup, DeserializationGroup)]
+pub struct ContactGroup {
+    #[xattribute(name = "email", optional = true)]
+    pub email: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "card")]
+pub struct Card(
+    #[xelement(
+        name = "contact",
+        namespace = "http://example.com/contact",
+        preferred_prefix = "c",
+        group = true,
+        default = true
+    )]
+    pub ContactGroup,
+);
+
+define_test!(
+    element_group_field_namespace,
+    [(
+        Card(ContactGroup {
+            email: Some("a@example.com".to_string()),
+        }),
+        r#"<card><c:contact xmlns:c="http://example.com/contact" email="a@example.com"/></card>"#
+    )]
+);
+
+#[derive(Debug, PartialEq, SerializationGroup, DeserializationGroup)]
+pub struct PersonGroup {
+    #[xelement(name = "address", group = true)]
+    pub address: AddressGroup,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "employee")]
+pub struct Employee {
+    #[xgroup]
+    pub person: PersonGroup,
+}
+
+define_test!(
+    element_group_field_in_group,
+    [(
+        Employee {
+            person: PersonGroup {
+                address: AddressGroup {
+                    country: "Sweden".to_string(),
+                    street: Street("Drottninggatan 1".to_string()),
+                    city: City("Stockholm".to_string()),
+                },
+            },
+        },
+        r#"<employee><address country="Sweden"><street>Drottninggatan 1</street><city>Stockholm</city></address></employee>"#
+    )]
+);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub enum Entry {
+    #[xelement(name = "person")]
+    Person {
+        #[xelement(name = "address", group = true)]
+        address: AddressGroup,
+    },
+}
+
+define_test!(
+    element_group_field_in_variant,
+    [(
+        Entry::Person {
+            address: AddressGroup {
+                country: "Denmark".to_string(),
+                street: Street("Strøget 1".to_string()),
+                city: City("København".to_string()),
+            },
+        },
+        r#"<person><address country="Denmark"><street>Strøget 1</street><city>København</city></address></person>"#
+    )]
+);
+
+#[test]
+fn element_group_field_default() {
+    let card: Card = crate::utils::quick_xml_deserialize_test("<card/>").unwrap();
+
+    pretty_assertions::assert_eq!(card, Card(ContactGroup::default()));
+}
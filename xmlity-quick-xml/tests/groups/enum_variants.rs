@@ -0,0 +1,58 @@
+use xmlity::{DeserializationGroup, Deserialize, SerializationGroup, Serialize};
+
+use crate::define_test;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "content")]
+pub struct Content(pub String);
+
+#[derive(Debug, PartialEq, SerializationGroup, DeserializationGroup)]
+pub enum Source {
+    #[xattribute(name = "href")]
+    Href(String),
+    #[xattribute(name = "id")]
+    Id {
+        id: String,
+    },
+    Inline(Content),
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "include")]
+pub struct Include {
+    #[xgroup]
+    pub source: Source,
+}
+
+define_test!(
+    enum_group,
+    [
+        (
+            Include {
+                source: Source::Href("a.xml".to_string()),
+            },
+            r#"<include href="a.xml"/>"#
+        ),
+        (
+            Include {
+                source: Source::Id {
+                    id: "a".to_string(),
+                },
+            },
+            r#"<include id="a"/>"#
+        ),
+        (
+            Include {
+                source: Source::Inline(Content("Hello".to_string())),
+            },
+            "<include><content>Hello</content></include>"
+        )
+    ]
+);
+
+#[test]
+fn enum_group_no_matching_variant() {
+    let err = crate::utils::quick_xml_deserialize_test::<Include>("<include/>");
+
+    assert!(err.is_err());
+}
@@ -1,5 +1,11 @@
 pub mod basic;
+pub mod element_field;
+pub mod enum_variants;
 pub mod generics;
+pub mod order;
 pub mod other;
+pub mod partial;
+pub mod standalone;
+pub mod tuple;
 pub mod unit;
 pub mod unnamed;
@@ -0,0 +1,144 @@
+use crate::{define_serialize_test, define_test};
+
+use xmlity::{DeserializationGroup, Deserialize, SerializationGroup, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "a")]
+pub struct A(String);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "b")]
+pub struct B(String);
+
+#[derive(Debug, PartialEq, SerializationGroup, DeserializationGroup)]
+pub struct AbGroup {
+    pub a: A,
+    pub b: B,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "outer")]
+pub struct Outer {
+    #[xelement(name = "last", serialize_order = 2)]
+    pub last: String,
+    #[xgroup(serialize_order = 1)]
+    pub group: AbGroup,
+    #[xelement(name = "first")]
+    pub first: String,
+}
+
+fn outer() -> Outer {
+    Outer {
+        last: "2".to_string(),
+        group: AbGroup {
+            a: A("a".to_string()),
+            b: B("b".to_string()),
+        },
+        first: "0".to_string(),
+    }
+}
+
+define_test!(
+    ordered_group_children,
+    [(
+        outer(),
+        "<outer><first>0</first><a>a</a><b>b</b><last>2</last></outer>"
+    )]
+);
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Sequence {
+    #[xvalue(serialize_order = 1)]
+    pub b: B,
+    pub a: A,
+    #[xvalue(serialize_order = -1)]
+    pub c: A,
+}
+
+define_serialize_test!(
+    ordered_values,
+    [(
+        Sequence {
+            b: B("b".to_string()),
+            a: A("a".to_string()),
+            c: A("c".to_string()),
+        },
+        "<a>c</a><a>a</a><b>b</b>"
+    )]
+);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "outer", children_order = "strict")]
+pub struct StrictOuter {
+    #[xelement(name = "last", serialize_order = 2)]
+    pub last: String,
+    #[xgroup(serialize_order = 1)]
+    pub group: AbGroup,
+    #[xelement(name = "first")]
+    pub first: String,
+}
+
+define_test!(
+    strict_ordered_group_children,
+    [(
+        StrictOuter {
+            last: "2".to_string(),
+            group: AbGroup {
+                a: A("a".to_string()),
+                b: B("b".to_string()),
+            },
+            first: "0".to_string(),
+        },
+        "<outer><first>0</first><a>a</a><b>b</b><last>2</last></outer>"
+    )]
+);
+
+#[derive(Debug, PartialEq, SerializationGroup, DeserializationGroup)]
+#[xgroup(children_order = "strict")]
+pub struct StrictBaGroup {
+    pub a: A,
+    #[xvalue(serialize_order = -1)]
+    pub b: B,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "outer")]
+pub struct StrictGroupOuter {
+    #[xgroup]
+    pub group: StrictBaGroup,
+}
+
+define_test!(
+    strict_ordered_group,
+    [(
+        StrictGroupOuter {
+            group: StrictBaGroup {
+                a: A("a".to_string()),
+                b: B("b".to_string()),
+            },
+        },
+        "<outer><b>b</b><a>a</a></outer>"
+    )]
+);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xvalue(order = "strict")]
+pub struct StrictSequence {
+    #[xvalue(serialize_order = 1)]
+    pub b: B,
+    pub a: A,
+    #[xvalue(serialize_order = -1)]
+    pub c: A,
+}
+
+define_test!(
+    strict_ordered_values,
+    [(
+        StrictSequence {
+            b: B("b".to_string()),
+            a: A("a".to_string()),
+            c: A("c".to_string()),
+        },
+        "<a>c</a><a>a</a><b>b</b>"
+    )]
+);
@@ -0,0 +1,95 @@
+use pretty_assertions::assert_eq;
+
+use crate::utils::{clean_string, quick_xml_deserialize_test};
+
+use xmlity::{types::utils::PartialElement, DeserializationGroup, Deserialize};
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xattribute(name = "id")]
+pub struct Id(String);
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "name")]
+pub struct Name(String);
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "count")]
+pub struct Count(u32);
+
+#[derive(Debug, PartialEq, DeserializationGroup)]
+pub struct ItemGroup {
+    #[xattribute(deferred = true)]
+    pub id: Id,
+    pub name: Name,
+    pub count: Count,
+}
+
+#[test]
+fn partial_element_complete() {
+    let actual: PartialElement<ItemGroup> = quick_xml_deserialize_test(&clean_string(
+        r#"<item id="a"><name>Apple</name><count>3</count></item>"#,
+    ))
+    .unwrap();
+
+    assert_eq!(
+        actual,
+        PartialElement::Complete(ItemGroup {
+            id: Id("a".to_string()),
+            name: Name("Apple".to_string()),
+            count: Count(3),
+        })
+    );
+}
+
+#[test]
+fn partial_element_missing_field() {
+    let actual: PartialElement<ItemGroup> =
+        quick_xml_deserialize_test(&clean_string(r#"<item id="a"><name>Apple</name></item>"#))
+            .unwrap();
+
+    let PartialElement::Incomplete {
+        completed_fields, ..
+    } = actual
+    else {
+        panic!("expected an incomplete element, got {actual:?}");
+    };
+    assert_eq!(completed_fields, vec!["id", "name"]);
+}
+
+#[test]
+fn partial_element_invalid_field() {
+    let actual: PartialElement<ItemGroup> = quick_xml_deserialize_test(&clean_string(
+        r#"<item id="a"><name>Apple</name><count>many</count></item>"#,
+    ))
+    .unwrap();
+
+    let PartialElement::Incomplete {
+        completed_fields, ..
+    } = actual
+    else {
+        panic!("expected an incomplete element, got {actual:?}");
+    };
+    assert_eq!(completed_fields, vec!["id", "name"]);
+}
+
+#[derive(Debug, PartialEq, DeserializationGroup)]
+pub struct OrderGroup {
+    #[xgroup]
+    pub item: ItemGroup,
+    pub note: Name,
+}
+
+#[test]
+fn partial_element_nested_group() {
+    let actual: PartialElement<OrderGroup> =
+        quick_xml_deserialize_test(&clean_string(r#"<order id="a"><name>Apple</name></order>"#))
+            .unwrap();
+
+    let PartialElement::Incomplete {
+        completed_fields, ..
+    } = actual
+    else {
+        panic!("expected an incomplete element, got {actual:?}");
+    };
+    assert_eq!(completed_fields, vec!["id", "name"]);
+}
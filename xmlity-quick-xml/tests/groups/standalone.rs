@@ -0,0 +1,70 @@
+use pretty_assertions::assert_eq;
+use xmlity::{
+    ser::serialize_group_as_element, ExpandedName, LocalName, SerializationGroup, Serialize,
+    SerializeAttribute, XmlNamespace,
+};
+
+#[derive(Debug, SerializeAttribute)]
+#[xattribute(name = "to")]
+struct To(String);
+
+#[derive(Debug, SerializeAttribute)]
+#[xattribute(name = "from")]
+struct From(String);
+
+#[derive(Debug, SerializeAttribute)]
+#[xattribute(name = "heading")]
+struct Heading(String);
+
+#[derive(Debug, Serialize)]
+#[xelement(name = "body")]
+struct Body(String);
+
+#[derive(Debug, SerializationGroup)]
+struct NoteGroup {
+    #[xattribute(deferred = true)]
+    to: To,
+    #[xattribute(deferred = true)]
+    from: From,
+    #[xattribute(deferred = true)]
+    heading: Heading,
+    body: Body,
+}
+
+fn note_group() -> NoteGroup {
+    NoteGroup {
+        to: To("Tove".to_string()),
+        from: From("Jani".to_string()),
+        heading: Heading("Reminder".to_string()),
+        body: Body("Don't forget me this weekend!".to_string()),
+    }
+}
+
+#[test]
+fn group_as_element() {
+    let name = ExpandedName::new(LocalName::new("note").unwrap(), None);
+
+    let actual =
+        xmlity_quick_xml::to_string(&serialize_group_as_element(name, &note_group())).unwrap();
+
+    assert_eq!(
+        actual,
+        r#"<note to="Tove" from="Jani" heading="Reminder"><body>Don't forget me this weekend!</body></note>"#
+    );
+}
+
+#[test]
+fn group_as_namespaced_element() {
+    let name = ExpandedName::new(
+        LocalName::new("note").unwrap(),
+        Some(XmlNamespace::new("http://example.com/notes").unwrap()),
+    );
+
+    let actual =
+        xmlity_quick_xml::to_string(&serialize_group_as_element(name, &note_group())).unwrap();
+
+    assert_eq!(
+        actual,
+        r#"<a0:note xmlns:a0="http://example.com/notes" to="Tove" from="Jani" heading="Reminder"><body>Don't forget me this weekend!</body></a0:note>"#
+    );
+}
@@ -0,0 +1,97 @@
+use xmlity::{
+    DeserializationGroup, Deserialize, SerializationGroup, Serialize, SerializeAttribute,
+};
+
+use crate::define_test;
+
+#[derive(Debug, PartialEq, SerializeAttribute, Deserialize)]
+#[xattribute(name = "id")]
+pub struct Id(String);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "title")]
+pub struct Title(String);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "author")]
+pub struct Author(String);
+
+#[derive(Debug, PartialEq, SerializationGroup, DeserializationGroup)]
+pub struct Identity {
+    #[xattribute(deferred = true)]
+    pub id: Id,
+    pub title: Title,
+}
+
+#[derive(Debug, PartialEq, SerializationGroup, DeserializationGroup)]
+pub struct Credits {
+    pub author: Author,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "book")]
+pub struct Book {
+    #[xgroup]
+    pub group: (Identity, Credits),
+}
+
+fn book() -> Book {
+    Book {
+        group: (
+            Identity {
+                id: Id("b1".to_string()),
+                title: Title("Dune".to_string()),
+            },
+            Credits {
+                author: Author("Frank Herbert".to_string()),
+            },
+        ),
+    }
+}
+
+define_test!(
+    tuple_group,
+    [(
+        book(),
+        r#"<book id="b1"><title>Dune</title><author>Frank Herbert</author></book>"#
+    )]
+);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "book")]
+pub struct OptionalCreditsBook {
+    #[xgroup]
+    pub group: (Identity, Option<Credits>),
+}
+
+define_test!(
+    tuple_group_optional_member,
+    [
+        (
+            OptionalCreditsBook {
+                group: (
+                    Identity {
+                        id: Id("b1".to_string()),
+                        title: Title("Dune".to_string()),
+                    },
+                    Some(Credits {
+                        author: Author("Frank Herbert".to_string()),
+                    }),
+                ),
+            },
+            r#"<book id="b1"><title>Dune</title><author>Frank Herbert</author></book>"#
+        ),
+        (
+            OptionalCreditsBook {
+                group: (
+                    Identity {
+                        id: Id("b2".to_string()),
+                        title: Title("Emma".to_string()),
+                    },
+                    None,
+                ),
+            },
+            r#"<book id="b2"><title>Emma</title></book>"#
+        )
+    ]
+);
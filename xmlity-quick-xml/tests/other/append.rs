@@ -0,0 +1,103 @@
+use std::io::Cursor;
+
+use pretty_assertions::assert_eq;
+
+use xmlity::{ExpandedName, LocalName, Serialize, XmlNamespace};
+use xmlity_quick_xml::{append::Appender, ser::Error};
+
+#[derive(Debug, Serialize)]
+#[xelement(name = "record")]
+struct Record(String);
+
+#[derive(Debug, Serialize)]
+#[xelement(name = "record", namespace = "urn:records")]
+struct NamespacedRecord(String);
+
+fn append<T: Serialize>(document: &str, values: &[T]) -> String {
+    let mut appender = Appender::open(Cursor::new(document.as_bytes().to_vec())).unwrap();
+    for value in values {
+        appender.append(value).unwrap();
+    }
+    String::from_utf8(appender.into_inner().0.into_inner()).unwrap()
+}
+
+#[test]
+fn append_to_root() {
+    assert_eq!(
+        append(
+            "<?xml version=\"1.0\"?>\n<log>\n  <record>a</record>\n</log>\n<!-- end -->\n",
+            &[Record("b".to_string()), Record("c".to_string())]
+        ),
+        "<?xml version=\"1.0\"?>\n<log>\n  <record>a</record>\n<record>b</record><record>c</record></log>\n<!-- end -->\n"
+    );
+}
+
+#[test]
+fn append_to_empty_root() {
+    assert_eq!(
+        append(
+            "<log />",
+            &[Record("a".to_string()), Record("b".to_string())]
+        ),
+        "<log ><record>a</record><record>b</record></log>"
+    );
+}
+
+#[test]
+fn append_to_named_element() {
+    let document = r#"<doc xmlns:r="urn:records"><r:records><r:record>a</r:record></r:records><footer/></doc>"#;
+    let name = ExpandedName::new(
+        LocalName::new("records").unwrap(),
+        Some(XmlNamespace::new("urn:records").unwrap()),
+    );
+
+    let mut appender =
+        Appender::open_element(Cursor::new(document.as_bytes().to_vec()), &name).unwrap();
+    appender.append(&NamespacedRecord("b".to_string())).unwrap();
+
+    // The prefix declared by the document is reused.
+    assert_eq!(
+        String::from_utf8(appender.into_inner().0.into_inner()).unwrap(),
+        r#"<doc xmlns:r="urn:records"><r:records><r:record>a</r:record><r:record>b</r:record></r:records><footer/></doc>"#
+    );
+}
+
+#[test]
+fn append_in_default_namespace() {
+    let mut appender = Appender::open(Cursor::new(
+        br#"<log xmlns="urn:records"><record>a</record></log>"#.to_vec(),
+    ))
+    .unwrap();
+    appender.append(&NamespacedRecord("b".to_string())).unwrap();
+    appender.append(&Record("c".to_string())).unwrap();
+
+    // Children without a namespace undeclare the default namespace of the document.
+    assert_eq!(
+        String::from_utf8(appender.into_inner().0.into_inner()).unwrap(),
+        r#"<log xmlns="urn:records"><record>a</record><record>b</record><record xmlns="">c</record></log>"#
+    );
+}
+
+#[test]
+fn continue_from_state() {
+    let mut appender = Appender::open(Cursor::new(b"<log></log>".to_vec())).unwrap();
+    appender.append(&Record("a".to_string())).unwrap();
+    let (file, state) = appender.into_inner();
+
+    let mut appender = Appender::from_state(file, state);
+    appender.append(&Record("b".to_string())).unwrap();
+
+    assert_eq!(
+        String::from_utf8(appender.into_inner().0.into_inner()).unwrap(),
+        "<log><record>a</record><record>b</record></log>"
+    );
+}
+
+#[test]
+fn missing_element() {
+    let name = ExpandedName::new(LocalName::new("records").unwrap(), None);
+
+    let result = Appender::open_element(Cursor::new(b"<log></log>".to_vec()), &name);
+
+    assert!(matches!(result, Err(Error::MissingAppendTarget)));
+}
@@ -0,0 +1,38 @@
+use arbitrary::{Arbitrary, Unstructured};
+use pretty_assertions::assert_eq;
+use xmlity::value::XmlElement;
+
+/// Deterministic bytes for generating documents, so that failures can be reproduced.
+fn seed_bytes(seed: u64, len: usize) -> Vec<u8> {
+    let mut state = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as u8
+        })
+        .collect()
+}
+
+fn roundtrip(element: &XmlElement) {
+    let written = xmlity_quick_xml::to_string(element).unwrap();
+
+    let read: XmlElement = xmlity_quick_xml::from_str(&written)
+        .unwrap_or_else(|error| panic!("failed to read {written:?}: {error}"));
+    let rewritten = xmlity_quick_xml::to_string(&read).unwrap();
+    let reread: XmlElement = xmlity_quick_xml::from_str(&rewritten)
+        .unwrap_or_else(|error| panic!("failed to read {rewritten:?}: {error}"));
+
+    assert_eq!(read, reread, "{written:?} was rewritten as {rewritten:?}");
+}
+
+#[test]
+fn arbitrary_elements_roundtrip() {
+    for seed in 0..256 {
+        let bytes = seed_bytes(seed, 4096);
+        let element = XmlElement::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+
+        roundtrip(&element);
+    }
+}
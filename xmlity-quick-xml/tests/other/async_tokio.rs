@@ -0,0 +1,133 @@
+use pretty_assertions::assert_eq;
+
+use tokio::io::AsyncReadExt;
+use xmlity::{Deserialize, Serialize};
+use xmlity_quick_xml::{
+    from_reader_async, from_reader_items_async, to_writer_async, to_writer_async_with_config,
+    SerializerConfig,
+};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "record")]
+struct Record {
+    #[xattribute(name = "id")]
+    id: u32,
+    name: Name,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "name")]
+struct Name(String);
+
+fn record(id: u32, name: &str) -> Record {
+    Record {
+        id,
+        name: Name(name.to_string()),
+    }
+}
+
+#[tokio::test]
+async fn read_document() {
+    let input = r#"<record id="1"><name>A &amp; B</name></record>
+"#;
+
+    let value: Record = from_reader_async(input.as_bytes()).await.unwrap();
+
+    assert_eq!(value, record(1, "A & B"));
+}
+
+#[tokio::test]
+async fn stops_after_root_element() {
+    let mut input =
+        &br#"<record id="1"><name>A</name></record><record id="2"><name>B</name></record>"#[..];
+
+    let first: Record = from_reader_async(&mut input).await.unwrap();
+    let second: Record = from_reader_async(&mut input).await.unwrap();
+
+    assert_eq!(first, record(1, "A"));
+    assert_eq!(second, record(2, "B"));
+    assert!(input.is_empty());
+}
+
+#[tokio::test]
+async fn truncated_document() {
+    let input = r#"<record id="1"><name>A</name>"#;
+
+    let error = from_reader_async::<Record, _>(input.as_bytes())
+        .await
+        .unwrap_err();
+
+    assert!(matches!(
+        error,
+        xmlity_quick_xml::de::Error::StartElementWithoutEnd { .. }
+    ));
+}
+
+#[tokio::test]
+async fn children_of_root() {
+    let input = r#"<export xmlns:x="urn:x">
+  <record id="1"><name>A</name></record>
+  <!-- between -->
+  <record id="x"><name>B</name></record>
+  <record id="3"><name>C</name></record>
+</export>"#;
+
+    let mut items = from_reader_items_async::<Record, _>(input.as_bytes());
+
+    assert_eq!(items.next_item().await.unwrap().unwrap(), record(1, "A"));
+    assert!(items.next_item().await.unwrap().is_err());
+    assert_eq!(items.next_item().await.unwrap().unwrap(), record(3, "C"));
+    assert!(items.next_item().await.is_none());
+    assert!(items.next_item().await.is_none());
+}
+
+#[tokio::test]
+async fn doctype_entities_of_items() {
+    let input = r#"<!DOCTYPE export [<!ENTITY co "ACME">]>
+<export>
+  <record id="1"><name>&co;</name></record>
+</export>"#;
+
+    let mut items = from_reader_items_async::<Record, _>(input.as_bytes());
+
+    assert_eq!(items.next_item().await.unwrap().unwrap(), record(1, "ACME"));
+    assert!(items.next_item().await.is_none());
+}
+
+#[tokio::test]
+async fn write_document() {
+    let mut output = Vec::new();
+
+    to_writer_async(&mut output, &record(1, "A & B"))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        xmlity_quick_xml::to_string(&record(1, "A & B")).unwrap()
+    );
+}
+
+#[tokio::test]
+async fn write_document_with_config() {
+    let (mut client, mut server) = tokio::io::duplex(16);
+    let config = SerializerConfig::new().with_indentation(Some(2));
+
+    let (written, read) = tokio::join!(
+        async {
+            to_writer_async_with_config(&mut client, &record(1, "A"), &config).await?;
+            drop(client);
+            Ok::<_, xmlity_quick_xml::ser::Error>(())
+        },
+        async {
+            let mut output = String::new();
+            server.read_to_string(&mut output).await.map(|_| output)
+        },
+    );
+    written.unwrap();
+
+    assert_eq!(
+        read.unwrap(),
+        xmlity_quick_xml::to_string_with_config(&record(1, "A"), &config).unwrap()
+    );
+}
@@ -0,0 +1,160 @@
+use std::str::FromStr;
+
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use xmlity::{
+    ser::UndeclaredAttributeNamespaces,
+    value::{XmlAttribute, XmlElement, XmlnsDeclaration},
+    ExpandedNameBuf, Prefix, PrefixBuf, Serialize, XmlNamespace, XmlNamespaceBuf,
+};
+use xmlity_quick_xml::SerializerConfig;
+
+fn name(name: &str) -> ExpandedNameBuf {
+    ExpandedNameBuf::from_str(name).unwrap()
+}
+
+fn declaration(prefix: &str, namespace: &str) -> XmlnsDeclaration {
+    let prefix = if prefix.is_empty() {
+        Prefix::BLANK.to_owned()
+    } else {
+        PrefixBuf::new(prefix.to_string()).unwrap()
+    };
+    XmlnsDeclaration::new(prefix, XmlNamespaceBuf::new(namespace.to_string()).unwrap())
+}
+
+fn undeclared() -> XmlElement {
+    XmlElement::new(name("e"))
+        .with_attribute(XmlAttribute::new(name("{urn:a}x"), "1"))
+        .with_child(
+            XmlElement::new(name("c")).with_attribute(XmlAttribute::new(name("{urn:a}y"), "2")),
+        )
+}
+
+fn declared_on_ancestor() -> XmlElement {
+    XmlElement::new(name("e"))
+        .with_declaration(declaration("d", "urn:a"))
+        .with_child(
+            XmlElement::new(name("c")).with_attribute(XmlAttribute::new(name("{urn:a}y"), "2")),
+        )
+}
+
+fn only_default_namespace() -> XmlElement {
+    XmlElement::new(name("{urn:a}e"))
+        .with_declaration(declaration("", "urn:a"))
+        .with_attribute(XmlAttribute::new(name("{urn:a}x"), "1"))
+}
+
+fn xml_namespace() -> XmlElement {
+    XmlElement::new(name("e")).with_attribute(XmlAttribute::new(
+        name("{http://www.w3.org/XML/1998/namespace}lang"),
+        "en",
+    ))
+}
+
+fn quick_xml<T: Serialize>(
+    value: &T,
+    policy: UndeclaredAttributeNamespaces,
+) -> Result<String, String> {
+    let config = SerializerConfig::new().with_undeclared_attribute_namespaces(policy);
+    xmlity_quick_xml::to_string_with_config(value, &config).map_err(|err| format!("{err:?}"))
+}
+
+fn via_value<T: Serialize>(
+    value: &T,
+    policy: UndeclaredAttributeNamespaces,
+) -> Result<String, String> {
+    quick_xml(&xmlity::value::to_value(value).unwrap(), policy)
+}
+
+fn pure<T: Serialize>(value: &T, policy: UndeclaredAttributeNamespaces) -> Result<String, String> {
+    let config = xmlity::pure::SerializerConfig::new().with_undeclared_attribute_namespaces(policy);
+    xmlity::pure::to_string_with_config(value, &config).map_err(|err| format!("{err:?}"))
+}
+
+type Backend = fn(&XmlElement, UndeclaredAttributeNamespaces) -> Result<String, String>;
+
+#[rstest]
+#[case::undeclared(undeclared(), r#"<e xmlns:a0="urn:a" a0:x="1"><c a0:y="2"/></e>"#)]
+#[case::declared_on_ancestor(declared_on_ancestor(), r#"<e xmlns:d="urn:a"><c d:y="2"/></e>"#)]
+#[case::only_default_namespace(
+    only_default_namespace(),
+    r#"<e xmlns="urn:a" xmlns:a0="urn:a" a0:x="1"/>"#
+)]
+#[case::xml_namespace(xml_namespace(), r#"<e xml:lang="en"/>"#)]
+fn undeclared_namespaces_are_declared(
+    #[case] element: XmlElement,
+    #[case] expected: &str,
+    #[values(quick_xml, via_value, pure)] backend: Backend,
+) {
+    assert_eq!(
+        backend(&element, UndeclaredAttributeNamespaces::Declare).unwrap(),
+        expected
+    );
+}
+
+#[rstest]
+fn undeclared_namespaces_are_errors(
+    #[values(undeclared(), only_default_namespace())] element: XmlElement,
+    #[values(quick_xml, via_value, pure)] backend: Backend,
+) {
+    let err = backend(&element, UndeclaredAttributeNamespaces::Error).unwrap_err();
+
+    assert_eq!(
+        err,
+        r#"UndeclaredNamespace { namespace: XmlNamespaceBuf("urn:a") }"#
+    );
+}
+
+#[rstest]
+#[case::declared_on_ancestor(declared_on_ancestor(), r#"<e xmlns:d="urn:a"><c d:y="2"/></e>"#)]
+#[case::xml_namespace(xml_namespace(), r#"<e xml:lang="en"/>"#)]
+fn declared_namespaces_are_not_errors(
+    #[case] element: XmlElement,
+    #[case] expected: &str,
+    #[values(quick_xml, via_value, pure)] backend: Backend,
+) {
+    assert_eq!(
+        backend(&element, UndeclaredAttributeNamespaces::Error).unwrap(),
+        expected
+    );
+}
+
+#[rstest]
+fn xmlns_namespace_is_never_written(
+    #[values(
+        UndeclaredAttributeNamespaces::Declare,
+        UndeclaredAttributeNamespaces::Error
+    )]
+    policy: UndeclaredAttributeNamespaces,
+    #[values(quick_xml, via_value, pure)] backend: Backend,
+) {
+    let element = XmlElement::new(name("e")).with_attribute(XmlAttribute::new(
+        ExpandedNameBuf::new("p".parse().unwrap(), Some(XmlNamespace::XMLNS.to_owned())),
+        "urn:a",
+    ));
+
+    assert_eq!(
+        backend(&element, policy).unwrap_err(),
+        r#"UnboundNamespace { namespace: XmlNamespaceBuf("http://www.w3.org/2000/xmlns/") }"#
+    );
+}
+
+#[derive(Serialize)]
+#[xelement(name = "item")]
+struct Item {
+    #[xattribute(name = "id", namespace = "urn:ids")]
+    id: String,
+}
+
+#[test]
+fn policy_applies_to_derived_attributes() {
+    let item = Item {
+        id: "1".to_string(),
+    };
+
+    assert_eq!(
+        quick_xml(&item, UndeclaredAttributeNamespaces::Declare).unwrap(),
+        r#"<item xmlns:a0="urn:ids" a0:id="1"/>"#
+    );
+    assert!(quick_xml(&item, UndeclaredAttributeNamespaces::Error).is_err());
+}
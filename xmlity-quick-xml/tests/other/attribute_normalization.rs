@@ -0,0 +1,57 @@
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use xmlity::{value::XmlValue, Deserialize, Serialize};
+use xmlity_quick_xml::Deserializer;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "item")]
+struct Item {
+    #[xattribute(name = "label")]
+    label: String,
+}
+
+fn deserialize_item(xml: &str, normalize: bool) -> Item {
+    let mut deserializer =
+        Deserializer::from(xml.as_bytes()).with_normalize_attribute_values(normalize);
+    Item::deserialize(&mut deserializer).unwrap()
+}
+
+#[rstest]
+#[case::tab("<item label=\"a\tb\"/>", "a b")]
+#[case::newline("<item label=\"a\nb\"/>", "a b")]
+#[case::crlf("<item label=\"a\r\nb\"/>", "a b")]
+#[case::char_refs("<item label=\"a&#9;b&#10;c\"/>", "a\tb\nc")]
+fn attribute_values_are_normalized(#[case] xml: &str, #[case] expected: &str) {
+    assert_eq!(deserialize_item(xml, true).label, expected);
+}
+
+#[test]
+fn raw_attribute_values() {
+    assert_eq!(
+        deserialize_item("<item label=\"a\tb\r\nc&#10;\"/>", false).label,
+        "a\tb\r\nc\n"
+    );
+}
+
+#[test]
+fn normalized_values_match_through_xml_value() {
+    let xml = "<item label=\"a\n  b&#10;\"/>";
+
+    let direct = deserialize_item(xml, true);
+
+    let value = XmlValue::deserialize(&mut Deserializer::from(xml.as_bytes())).unwrap();
+    let via_value: Item = xmlity::value::from_value(&value).unwrap();
+
+    assert_eq!(direct, via_value);
+}
+
+#[test]
+fn serialized_whitespace_round_trips() {
+    let item = Item {
+        label: "a\tb\nc".to_owned(),
+    };
+
+    let xml = xmlity_quick_xml::to_string(&item).unwrap();
+
+    assert_eq!(deserialize_item(&xml, true), item);
+}
@@ -0,0 +1,45 @@
+use xmlity::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "from")]
+struct From(String);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "note")]
+struct Note {
+    #[xattribute(name = "to")]
+    to: String,
+    from: From,
+}
+
+fn note() -> Note {
+    Note {
+        to: "Tove".to_string(),
+        from: From("Jani".to_string()),
+    }
+}
+
+xmlity_quick_xml::backend_tests!(
+    note_element,
+    [
+        (note(), r#"<note to="Tove"><from>Jani</from></note>"#),
+        (
+            note(),
+            r#"<note to="Tove"><from>Jani</from></note>"#,
+            r#"<note to="Tove"><!-- comment --><from>Jani</from></note>"#
+        ),
+    ]
+);
+
+xmlity_quick_xml::backend_tests!(
+    escaped_attribute,
+    [(
+        Note {
+            to: "Tove & \"Jani\"".to_string(),
+            from: From("<Jani>".to_string()),
+        },
+        r#"<note to="Tove &amp; &quot;Jani&quot;"><from>&lt;Jani&gt;</from></note>"#
+    )]
+);
+
+xmlity_quick_xml::backend_tests!(text, [(String::from("Tove"), "Tove")]);
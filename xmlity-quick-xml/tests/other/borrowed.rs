@@ -0,0 +1,82 @@
+use std::borrow::Cow;
+
+use pretty_assertions::assert_eq;
+use xmlity::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "note")]
+struct Note<'a>(Cow<'a, str>);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "person")]
+struct Person<'a> {
+    #[xattribute(name = "id")]
+    id: Cow<'a, str>,
+    #[xattribute(name = "nick", optional)]
+    nick: Option<Cow<'a, str>>,
+    #[xelement(name = "name")]
+    name: &'a str,
+    note: Note<'a>,
+}
+
+#[test]
+fn borrowed_fields() {
+    let xml = r#"<person id="p1" nick="Al"><name>Alice</name><note>Hi</note></person>"#;
+
+    let person: Person = xmlity_quick_xml::from_str(xml).unwrap();
+
+    assert_eq!(
+        person,
+        Person {
+            id: Cow::Borrowed("p1"),
+            nick: Some(Cow::Borrowed("Al")),
+            name: "Alice",
+            note: Note(Cow::Borrowed("Hi")),
+        }
+    );
+    assert_eq!(xmlity_quick_xml::to_string(&person).unwrap(), xml);
+}
+
+#[test]
+fn escaped_text_is_copied() {
+    let xml = r#"<person id="p&amp;1"><name>Alice</name><note>A &amp; B</note></person>"#;
+
+    let person: Person = xmlity_quick_xml::from_str(xml).unwrap();
+
+    assert_eq!(person.id, "p&1");
+    assert_eq!(person.note, Note(Cow::Owned("A & B".to_string())));
+}
+
+#[test]
+fn escaped_text_can_not_be_borrowed_as_str() {
+    let xml = r#"<person id="p1"><name>A &amp; B</name><note>Hi</note></person>"#;
+
+    assert!(xmlity_quick_xml::from_str::<Person>(xml).is_err());
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Field<'a> {
+    #[xelement(name = "text")]
+    Text(&'a str),
+    Note(Note<'a>),
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "fields")]
+struct Fields<'a> {
+    fields: Vec<Field<'a>>,
+}
+
+#[test]
+fn borrowed_variants() {
+    let xml = "<fields><text>a</text><note>b</note></fields>";
+
+    let fields: Fields = xmlity_quick_xml::from_str(xml).unwrap();
+
+    assert_eq!(
+        fields,
+        Fields {
+            fields: vec![Field::Text("a"), Field::Note(Note(Cow::Borrowed("b")))],
+        }
+    );
+}
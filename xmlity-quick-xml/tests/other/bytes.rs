@@ -0,0 +1,39 @@
+use crate::define_test;
+
+use xmlity::{
+    types::bytes::{ByteSeq, Bytes},
+    Deserialize, Serialize,
+};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "file")]
+pub struct File {
+    #[xattribute(name = "checksum")]
+    checksum: ByteSeq,
+    #[xelement(name = "data")]
+    data: Bytes,
+}
+
+define_test!(
+    byte_buffers,
+    [
+        (
+            File {
+                checksum: ByteSeq(vec![1, 2, 255]),
+                data: Bytes(b"hello".to_vec()),
+            },
+            r#"<file checksum="1 2 255"><data>aGVsbG8=</data></file>"#,
+            r#"<file checksum=" 1 2  255 "><data>
+              aGVs
+              bG8=
+            </data></file>"#
+        ),
+        (
+            File {
+                checksum: ByteSeq(Vec::new()),
+                data: Bytes(Vec::new()),
+            },
+            r#"<file checksum=""><data></data></file>"#
+        )
+    ]
+);
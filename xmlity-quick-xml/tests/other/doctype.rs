@@ -0,0 +1,83 @@
+use pretty_assertions::assert_eq;
+use xmlity::{value::XmlDoctype, Deserialize, Serialize};
+
+use crate::define_test;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "a")]
+pub struct A(String);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Document {
+    pub doctype: XmlDoctype,
+    pub root: A,
+}
+
+/// An internal subset with conditional sections, and literals with brackets and `<` and `>` in them that don't end anything.
+const DOCTYPE: &str = r#"a [ <![INCLUDE[ <!ELEMENT a (#PCDATA)> ]]> <![IGNORE[ <!ENTITY e "]>"> <![IGNORE[ ]]> ]]> <![%draft;[ <!ATTLIST a id CDATA "<"> ]]> <!-- ]> --> ]"#;
+
+fn document() -> Document {
+    Document {
+        doctype: XmlDoctype::new(DOCTYPE),
+        root: A("x".to_string()),
+    }
+}
+
+define_test!(
+    doctype_with_conditional_sections,
+    [(document(), format!("<!DOCTYPE {DOCTYPE}><a>x</a>"))]
+);
+
+#[test]
+fn doctype_with_conditional_sections_after_declaration() {
+    let xml = format!("<?xml version=\"1.0\"?>\n<!DOCTYPE {DOCTYPE}>\n<a>x</a>");
+    let (_, actual): (xmlity::value::XmlDecl, Document) = xmlity_quick_xml::from_str(&xml).unwrap();
+
+    assert_eq!(actual, document());
+}
+
+#[test]
+fn pure_parser_doctype_with_conditional_sections() {
+    let value: xmlity::XmlValue = format!("<!DOCTYPE {DOCTYPE}><a>x</a>").parse().unwrap();
+
+    assert_eq!(
+        xmlity::value::from_value::<Document>(&value).unwrap(),
+        document()
+    );
+}
+
+#[test]
+fn conditional_sections() {
+    let doctype = XmlDoctype::new(DOCTYPE);
+
+    assert_eq!(doctype.name(), Some(&b"a"[..]));
+    assert_eq!(
+        doctype.internal_subset(),
+        Some(&DOCTYPE.as_bytes()[3..DOCTYPE.len() - 1])
+    );
+
+    let sections = doctype
+        .conditional_sections()
+        .into_iter()
+        .map(|section| {
+            (
+                std::str::from_utf8(section.keyword).unwrap(),
+                section.is_included(),
+                std::str::from_utf8(section.content).unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        sections,
+        vec![
+            ("INCLUDE", Some(true), " <!ELEMENT a (#PCDATA)> "),
+            (
+                "IGNORE",
+                Some(false),
+                r#" <!ENTITY e "]>"> <![IGNORE[ ]]> "#
+            ),
+            ("%draft;", None, r#" <!ATTLIST a id CDATA "<"> "#),
+        ]
+    );
+}
@@ -0,0 +1,74 @@
+use pretty_assertions::assert_eq;
+use xmlity::{
+    types::utils::XmlDocument,
+    value::{XmlComment, XmlDecl, XmlDoctype, XmlProcessingInstruction},
+    Deserialize, Serialize,
+};
+
+use crate::define_test;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "a")]
+pub struct A(String);
+
+fn licensed() -> XmlDocument<A> {
+    XmlDocument::new(A("x".to_string()))
+        .with_before_root(XmlComment::new(" SPDX-License-Identifier: MIT "))
+        .with_after_root(XmlProcessingInstruction::new("end", "now"))
+}
+
+fn full() -> XmlDocument<A> {
+    XmlDocument::new(A("x".to_string()))
+        .with_decl(XmlDecl::new("1.0", Some("UTF-8"), None))
+        .with_before_doctype(XmlComment::new(" license "))
+        .with_doctype(XmlDoctype::new("a"))
+        .with_before_root(XmlProcessingInstruction::new("page", "1"))
+        .with_after_root(XmlComment::new(" generated "))
+        .with_after_root(XmlComment::new(" end "))
+}
+
+define_test!(
+    document_misc,
+    [
+        (
+            licensed(),
+            "<!-- SPDX-License-Identifier: MIT --><a>x</a><?end now?>",
+            "<!-- SPDX-License-Identifier: MIT -->\n<a>x</a>\n<?end now?>\n"
+        ),
+        (
+            full(),
+            r#"<?xml version="1.0" encoding="UTF-8"?><!-- license --><!DOCTYPE a><?page 1?><a>x</a><!-- generated --><!-- end -->"#,
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!-- license -->\n<!DOCTYPE a>\n<?page 1?>\n<a>x</a>\n<!-- generated -->\n<!-- end -->\n"
+        )
+    ]
+);
+
+#[test]
+fn document_misc_without_doctype_is_before_root() {
+    let document: XmlDocument<A> =
+        xmlity_quick_xml::from_str("<?xml version=\"1.0\"?><!-- license --><a>x</a>").unwrap();
+
+    assert!(document.before_doctype.is_empty());
+    assert_eq!(document.before_root.len(), 1);
+    assert_eq!(document.into_root(), A("x".to_string()));
+}
+
+#[test]
+fn document_missing_root() {
+    xmlity_quick_xml::from_str::<XmlDocument<A>>("<!-- license -->").unwrap_err();
+}
+
+#[test]
+fn pure_parser_document_misc() {
+    let value: xmlity::XmlValue = "<!-- license --><!DOCTYPE a><a>x</a><!-- end -->"
+        .parse()
+        .unwrap();
+
+    assert_eq!(
+        xmlity::value::from_value::<XmlDocument<A>>(&value).unwrap(),
+        XmlDocument::new(A("x".to_string()))
+            .with_before_doctype(XmlComment::new(" license "))
+            .with_doctype(XmlDoctype::new("a"))
+            .with_after_root(XmlComment::new(" end "))
+    );
+}
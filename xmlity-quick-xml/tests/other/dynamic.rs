@@ -0,0 +1,164 @@
+use pretty_assertions::assert_eq;
+
+use xmlity::{
+    de::{DynDeserializer, ErasedDeserializer},
+    ser::{DynSerialize, DynSerializer, ErasedSerializer},
+    DeserializationGroup, Deserialize, SerializationGroup, Serialize,
+};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xvalue(rename_all = "lowercase")]
+enum Priority {
+    Low,
+    High,
+}
+
+#[derive(Debug, PartialEq, SerializationGroup, DeserializationGroup)]
+struct Meta {
+    #[xattribute(name = "lang", optional)]
+    lang: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "item")]
+struct Item(String);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "note", namespace = "http://example.com/note")]
+struct Note {
+    #[xattribute(name = "to")]
+    to: String,
+    #[xattribute(name = "priority")]
+    priority: Priority,
+    #[xgroup]
+    meta: Meta,
+    items: Vec<Item>,
+}
+
+fn note() -> Note {
+    Note {
+        to: "Tove".to_string(),
+        priority: Priority::High,
+        meta: Meta {
+            lang: Some("en".to_string()),
+        },
+        items: vec![Item("a".to_string()), Item("b".to_string())],
+    }
+}
+
+const NOTE: &str = r#"<a0:note xmlns:a0="http://example.com/note" to="Tove" priority="high" lang="en"><item>a</item><item>b</item></a0:note>"#;
+
+// Neither function is generic over the backend.
+fn write(
+    value: &dyn DynSerialize,
+    serializer: &mut dyn DynSerializer,
+) -> Result<(), xmlity::ser::DynError> {
+    value.serialize(serializer)
+}
+
+fn read(deserializer: &mut dyn DynDeserializer<'_>) -> Result<Note, xmlity::de::DynError> {
+    Note::deserialize(deserializer)
+}
+
+#[test]
+fn serialize_through_dyn() {
+    let mut serializer = xmlity_quick_xml::Serializer::from(quick_xml::Writer::new(Vec::new()));
+    let mut erased = ErasedSerializer::new(&mut serializer);
+    let result = write(&note(), &mut erased);
+    erased.finish(result).unwrap();
+
+    let xml = String::from_utf8(serializer.into_inner()).unwrap();
+    assert_eq!(xml, NOTE);
+    assert_eq!(xml, xmlity_quick_xml::to_string(&note()).unwrap());
+}
+
+#[test]
+fn deserialize_through_dyn() {
+    let mut deserializer = xmlity_quick_xml::Deserializer::from(NOTE.as_bytes());
+    let mut erased = ErasedDeserializer::new(&mut deserializer);
+    let result = read(&mut erased);
+    let actual = erased.finish(result).unwrap();
+
+    assert_eq!(actual, note());
+    assert_eq!(actual, xmlity_quick_xml::from_str::<Note>(NOTE).unwrap());
+}
+
+#[test]
+fn heterogeneous_values() {
+    let values: Vec<Box<dyn DynSerialize>> =
+        vec![Box::new(Item("a".to_string())), Box::new(note())];
+
+    let xml = xmlity_quick_xml::to_string(&values).unwrap();
+
+    assert_eq!(xml, format!("<item>a</item>{NOTE}"));
+}
+
+#[test]
+fn errors_of_the_backend_are_kept() {
+    let xml =
+        r#"<a0:note xmlns:a0="http://example.com/note" to="Tove" priority="urgent"></a0:note>"#;
+
+    let mut deserializer = xmlity_quick_xml::Deserializer::from(xml.as_bytes());
+    let mut erased = ErasedDeserializer::new(&mut deserializer);
+    let result = read(&mut erased);
+    let dyn_error = erased.finish(result).unwrap_err();
+
+    let error = xmlity_quick_xml::from_str::<Note>(xml).unwrap_err();
+    assert_eq!(dyn_error.to_string(), error.to_string());
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct BaseUrl(String);
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xvalue(deserialize_with_context = deserialize_href)]
+struct Href(String);
+
+fn deserialize_href<'de, D>(deserializer: D, base: Option<&BaseUrl>) -> Result<Href, D::Error>
+where
+    D: xmlity::Deserializer<'de>,
+{
+    let href = String::deserialize(deserializer)?;
+    Ok(Href(match base {
+        Some(BaseUrl(base)) => format!("{base}{href}"),
+        None => href,
+    }))
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "a")]
+struct Link {
+    #[xattribute(name = "href")]
+    href: Href,
+}
+
+/// External data is generic over its type, so it can't be forwarded through the trait objects.
+#[test]
+fn external_data_is_not_forwarded() {
+    let xml = r#"<a href="about.html"/>"#;
+    let mut external_data = xmlity_quick_xml::de::ExternalData::new();
+    external_data.insert(BaseUrl("https://example.com/".to_string()));
+
+    let mut deserializer =
+        xmlity_quick_xml::Deserializer::from(xml.as_bytes()).with_external_data(external_data);
+    let mut erased = ErasedDeserializer::new(&mut deserializer);
+    let result = Link::deserialize(&mut erased as &mut dyn DynDeserializer<'_>);
+    let actual = erased.finish(result).unwrap();
+
+    assert_eq!(
+        actual,
+        Link {
+            href: Href("about.html".to_string())
+        }
+    );
+    assert_eq!(
+        xmlity_quick_xml::from_str_with_context::<Link, _>(
+            xml,
+            BaseUrl("https://example.com/".to_string())
+        )
+        .unwrap(),
+        Link {
+            href: Href("https://example.com/about.html".to_string())
+        }
+    );
+}
@@ -0,0 +1,172 @@
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use xmlity::{value::XmlValue, Deserialize};
+use xmlity_quick_xml::{
+    de::{Entities, Error},
+    Deserializer,
+};
+
+use super::escaping::{note, Note};
+
+fn deserialize(
+    xml: &str,
+    configure: impl FnOnce(Deserializer) -> Deserializer,
+) -> Result<Note, Error> {
+    let mut deserializer = configure(Deserializer::from(xml.as_bytes()).with_skip_prolog(true));
+    Note::deserialize(&mut deserializer)
+}
+
+#[test]
+fn entities_declared_in_internal_subset() {
+    let xml = r#"<!DOCTYPE note [
+  <!ENTITY company "ACME &amp; Co">
+  <!ENTITY signed 'Signed by &company;'>
+]>
+<note title="&company;">&signed;, &#169; &company;</note>"#;
+
+    assert_eq!(
+        deserialize(xml, |de| de).unwrap(),
+        note("ACME & Co", "Signed by ACME & Co, \u{a9} ACME & Co")
+    );
+}
+
+#[test]
+fn provided_entities() {
+    let xml = r#"<note title="a&nbsp;b">&copy; 2024</note>"#;
+    let entities = Entities::new()
+        .with_entity("nbsp", "\u{a0}")
+        .with_entity("copy", "&#169;");
+
+    assert_eq!(
+        deserialize(xml, |de| de.with_entities(entities)).unwrap(),
+        note("a\u{a0}b", "\u{a9} 2024")
+    );
+}
+
+#[test]
+fn declarations_replace_provided_entities() {
+    let xml = r#"<!DOCTYPE note [ <!ENTITY who "document"> <!ENTITY who "ignored"> <!ENTITY % who "parameter"> ]><note title="&who;">&by;</note>"#;
+    let entities = Entities::new()
+        .with_entity("who", "provided")
+        .with_entity("by", "by &who;");
+
+    assert_eq!(
+        deserialize(xml, |de| de.with_entities(entities)).unwrap(),
+        note("document", "by document")
+    );
+}
+
+#[test]
+fn character_references_are_resolved_when_declared() {
+    // `&#38;#38;` is `&#38;` in the replacement text, which is read as `&` where the entity is used. `&#10;` is a literal line break in the replacement text, which is normalized in attribute values.
+    let xml = r#"<!DOCTYPE note [ <!ENTITY amp2 "&#38;#38;"> <!ENTITY lines "a&#10;b"> ]><note title="&amp2;&lines;">&amp2;&lines;</note>"#;
+
+    assert_eq!(deserialize(xml, |de| de).unwrap(), note("&a b", "&a\nb"));
+}
+
+const BILLION_LAUGHS: &str = r#"<?xml version="1.0"?>
+<!DOCTYPE note [
+  <!ENTITY lol "lol">
+  <!ENTITY lol1 "&lol;&lol;&lol;&lol;&lol;&lol;&lol;&lol;&lol;&lol;">
+  <!ENTITY lol2 "&lol1;&lol1;&lol1;&lol1;&lol1;&lol1;&lol1;&lol1;&lol1;&lol1;">
+  <!ENTITY lol3 "&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;">
+  <!ENTITY lol4 "&lol3;&lol3;&lol3;&lol3;&lol3;&lol3;&lol3;&lol3;&lol3;&lol3;">
+  <!ENTITY lol5 "&lol4;&lol4;&lol4;&lol4;&lol4;&lol4;&lol4;&lol4;&lol4;&lol4;">
+  <!ENTITY lol6 "&lol5;&lol5;&lol5;&lol5;&lol5;&lol5;&lol5;&lol5;&lol5;&lol5;">
+  <!ENTITY lol7 "&lol6;&lol6;&lol6;&lol6;&lol6;&lol6;&lol6;&lol6;&lol6;&lol6;">
+  <!ENTITY lol8 "&lol7;&lol7;&lol7;&lol7;&lol7;&lol7;&lol7;&lol7;&lol7;&lol7;">
+  <!ENTITY lol9 "&lol8;&lol8;&lol8;&lol8;&lol8;&lol8;&lol8;&lol8;&lol8;&lol8;">
+]>
+<note title="&lol9;">&lol9;</note>"#;
+
+#[rstest]
+#[case::text(BILLION_LAUGHS.replace(r#"title="&lol9;""#, r#"title="""#))]
+#[case::attribute(BILLION_LAUGHS.replace(">&lol9;<", "><"))]
+fn billion_laughs(#[case] xml: String) {
+    let err = deserialize(&xml, |de| de).unwrap_err();
+
+    assert!(matches!(
+        err,
+        Error::EntityExpansionLimitExceeded {
+            limit: Deserializer::DEFAULT_ENTITY_EXPANSION_LIMIT
+        }
+    ));
+}
+
+#[test]
+fn billion_laughs_into_xml_value() {
+    let err = xmlity_quick_xml::from_str::<(xmlity::value::XmlDecl, XmlValue, XmlValue, XmlValue)>(
+        BILLION_LAUGHS,
+    )
+    .unwrap_err();
+
+    assert!(matches!(err, Error::EntityExpansionLimitExceeded { .. }));
+}
+
+#[test]
+fn entities_expanding_to_nothing_are_limited() {
+    let xml = r#"<!DOCTYPE note [
+  <!ENTITY e "">
+  <!ENTITY e1 "&e;&e;&e;&e;&e;&e;&e;&e;&e;&e;">
+  <!ENTITY e2 "&e1;&e1;&e1;&e1;&e1;&e1;&e1;&e1;&e1;&e1;">
+  <!ENTITY e3 "&e2;&e2;&e2;&e2;&e2;&e2;&e2;&e2;&e2;&e2;">
+]><note title="">a&e3;b</note>"#;
+
+    assert_eq!(deserialize(xml, |de| de).unwrap(), note("", "ab"));
+    assert!(matches!(
+        deserialize(xml, |de| de.with_entity_expansion_limit(Some(1000))).unwrap_err(),
+        Error::EntityExpansionLimitExceeded { limit: 1000 }
+    ));
+}
+
+const NESTED: &str = r#"<!DOCTYPE note [ <!ENTITY a "a"> <!ENTITY b "&a;"> <!ENTITY c "&b;"> ]><note title="&c;">&c;</note>"#;
+
+#[test]
+fn within_entity_depth_limit() {
+    assert_eq!(
+        deserialize(NESTED, |de| de.with_entity_depth_limit(Some(3))).unwrap(),
+        note("a", "a")
+    );
+}
+
+#[rstest]
+#[case::text(NESTED.replace(r#"title="&c;""#, r#"title="""#))]
+#[case::attribute(NESTED.replace(">&c;<", "><"))]
+fn exceeding_entity_depth_limit(#[case] xml: String) {
+    let err = deserialize(&xml, |de| de.with_entity_depth_limit(Some(2))).unwrap_err();
+
+    assert!(matches!(err, Error::EntityDepthLimitExceeded { limit: 2 }));
+}
+
+#[test]
+fn recursive_entities() {
+    let xml =
+        r#"<!DOCTYPE note [ <!ENTITY a "x&b;"> <!ENTITY b "y&a;"> ]><note title="">&a;</note>"#;
+
+    let err = deserialize(xml, |de| {
+        de.with_entity_depth_limit(None)
+            .with_entity_expansion_limit(None)
+    })
+    .unwrap_err();
+
+    assert!(matches!(err, Error::RecursiveEntity { name } if name == "a"));
+}
+
+#[test]
+fn entities_with_markup() {
+    let xml = r#"<!DOCTYPE note [ <!ENTITY b "<b>bold</b>"> ]><note title="">&b;</note>"#;
+
+    let err = deserialize(xml, |de| de).unwrap_err();
+
+    assert!(matches!(err, Error::EntityMarkup { name } if name == "b"));
+}
+
+#[test]
+fn undeclared_entity_in_text() {
+    let err = deserialize(r#"<note title="">a &nbsp; b</note>"#, |de| de).unwrap_err();
+
+    assert!(matches!(
+        err,
+        Error::Unescape(xmlity::escape::UnescapeError::UnknownEntity(name)) if name == "nbsp"
+    ));
+}
@@ -0,0 +1,73 @@
+use pretty_assertions::assert_eq;
+use xmlity::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "a")]
+pub struct A {
+    #[xattribute(name = "id")]
+    pub id: u32,
+}
+
+fn assert_clone<T: Clone>() {}
+fn assert_eq_types<T: Clone + PartialEq + Eq>() {}
+
+#[test]
+fn errors_are_clone() {
+    assert_clone::<xmlity_quick_xml::de::Error>();
+    assert_clone::<xmlity_quick_xml::ser::Error>();
+
+    assert_eq_types::<xmlity::value::XmlValueDeserializerError>();
+    assert_eq_types::<xmlity::value::XmlValueSerializerError>();
+    assert_eq_types::<xmlity::de::DynError>();
+    assert_eq_types::<xmlity::ser::DynError>();
+    assert_eq_types::<xmlity::ser::Unexpected>();
+    assert_eq_types::<xmlity::escape::UnescapeError>();
+    assert_eq_types::<xmlity::XmlNamespaceParseError>();
+}
+
+#[test]
+fn cloned_error_keeps_message() {
+    let err = xmlity_quick_xml::from_str::<A>("<a/>").unwrap_err();
+
+    assert_eq!(err.clone().to_string(), err.to_string());
+}
+
+#[test]
+fn value_errors_compare() {
+    let value = xmlity_quick_xml::from_str::<xmlity::XmlValue>(r#"<a id="x"/>"#).unwrap();
+
+    let err = xmlity::value::from_value::<A>(&value).unwrap_err();
+
+    assert_eq!(err.clone(), err);
+}
+
+fn assert_send_sync_error<T: std::error::Error + Send + Sync + 'static>() {}
+
+#[test]
+fn errors_are_send_sync() {
+    assert_send_sync_error::<xmlity_quick_xml::de::Error>();
+    assert_send_sync_error::<xmlity_quick_xml::ser::Error>();
+    assert_send_sync_error::<xmlity::Error>();
+}
+
+#[test]
+fn backend_errors_convert_to_facade() {
+    let err: xmlity::Error = xmlity_quick_xml::from_str::<A>("<a/>").unwrap_err().into();
+
+    assert!(err.is_deserialize());
+    assert!(err.downcast_ref::<xmlity_quick_xml::de::Error>().is_some());
+
+    let boxed: Box<dyn std::error::Error + Send + Sync> = Box::new(err);
+    assert!(boxed.to_string().starts_with("Deserialization error: "));
+}
+
+#[test]
+fn value_errors_convert_to_backend_errors() {
+    let value_err = xmlity::value::XmlValueSerializerError::Custom("oops".to_string());
+    let err: xmlity_quick_xml::ser::Error = value_err.clone().into();
+
+    assert!(matches!(&err, xmlity_quick_xml::ser::Error::Value(inner) if *inner == value_err));
+
+    let err: xmlity::Error = err.into();
+    assert!(err.is_serialize());
+}
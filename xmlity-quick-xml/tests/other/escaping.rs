@@ -0,0 +1,88 @@
+use crate::{define_deserialize_test, define_test};
+
+use xmlity::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "note")]
+pub struct Note {
+    #[xattribute(name = "title")]
+    pub title: String,
+    pub text: String,
+}
+
+pub fn note(title: &str, text: &str) -> Note {
+    Note {
+        title: title.to_string(),
+        text: text.to_string(),
+    }
+}
+
+define_test!(
+    escaping,
+    [
+        (note("plain", "text"), r#"<note title="plain">text</note>"#),
+        (
+            note(r#"say "hi" & 'bye'"#, "a < b"),
+            r#"<note title="say &quot;hi&quot; &amp; &apos;bye&apos;">a &lt; b</note>"#
+        ),
+        (
+            note("tab\there", "a > b"),
+            r#"<note title="tab&#9;here">a &gt; b</note>"#
+        )
+    ]
+);
+
+define_deserialize_test!(
+    unescaping_char_refs,
+    [(
+        note("<&>", "<"),
+        r#"<note title="&#60;&#x26;&gt;">&#x3C;</note>"#
+    )]
+);
+
+#[test]
+fn unknown_entity_in_attribute() {
+    let err = xmlity_quick_xml::from_str::<xmlity::XmlValue>(r#"<note title="&nbsp;">text</note>"#)
+        .unwrap_err();
+
+    assert!(matches!(
+        err,
+        xmlity_quick_xml::de::Error::Unescape(xmlity::escape::UnescapeError::UnknownEntity(_))
+    ));
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "note")]
+pub struct UntitledNote {
+    pub text: String,
+}
+
+#[test]
+fn skipped_attributes_are_not_unescaped() {
+    let note =
+        xmlity_quick_xml::from_str::<UntitledNote>(r#"<note title="&nbsp;" lang="en">text</note>"#)
+            .unwrap();
+
+    assert_eq!(
+        note,
+        UntitledNote {
+            text: "text".to_string()
+        }
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "note", namespace = "urn:\"quoted\"<&>")]
+pub struct NamespacedNote {
+    pub text: String,
+}
+
+define_test!(
+    escaping_namespaces,
+    [(
+        NamespacedNote {
+            text: "text".to_string()
+        },
+        r#"<a0:note xmlns:a0="urn:&quot;quoted&quot;&lt;&amp;&gt;">text</a0:note>"#
+    )]
+);
@@ -0,0 +1,59 @@
+use xmlity::{Deserialize, Serialize};
+
+use crate::define_test;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xvalue(exhaustive, rename_all = "lowercase")]
+pub enum Shape {
+    #[xelement(name = "circle", namespace = "http://example.com/shapes")]
+    Circle {
+        #[xattribute(name = "r")]
+        r: u32,
+    },
+    #[xpi(target = "square")]
+    Square,
+    Empty,
+}
+
+define_test!(
+    exhaustive_enum,
+    [
+        (
+            Shape::Circle { r: 2 },
+            r#"<a0:circle xmlns:a0="http://example.com/shapes" r="2"/>"#
+        ),
+        (Shape::Empty, "empty")
+    ]
+);
+
+#[test]
+fn exhaustive_enum_lists_expected_names() {
+    let err = xmlity_quick_xml::from_str::<Shape>("<cirle r=\"2\"/>").unwrap_err();
+
+    let xmlity_quick_xml::de::Error::NoMatchingVariant { ident, expected } = &err else {
+        panic!("unexpected error: {err:?}");
+    };
+    assert_eq!(ident, "Shape");
+    assert_eq!(
+        expected,
+        &[
+            "<{http://example.com/shapes}circle>",
+            "<?square?>",
+            "\"empty\""
+        ]
+    );
+    assert_eq!(
+        err.to_string(),
+        r#"No variant of Shape matches, expected one of: <{http://example.com/shapes}circle>, <?square?>, "empty""#
+    );
+}
+
+#[test]
+fn exhaustive_enum_from_value() {
+    let value = xmlity::value::XmlValue::Text(xmlity::value::XmlText::new("full"));
+    let err = xmlity::value::from_value::<Shape>(&value).unwrap_err();
+
+    assert!(err
+        .to_string()
+        .contains(r#"expected one of: <{http://example.com/shapes}circle>, <?square?>, "empty""#));
+}
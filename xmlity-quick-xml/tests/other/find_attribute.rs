@@ -0,0 +1,120 @@
+use core::fmt;
+
+use pretty_assertions::assert_eq;
+
+use crate::utils::quick_xml_deserialize_test;
+
+use xmlity::{
+    de::{self, ElementAccess, Visitor},
+    value::from_value,
+    Deserialize, Deserializer, ExpandedName, ExpandedNameBuf, LocalName, XmlValue,
+};
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xattribute(name = "x")]
+struct X(i32);
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xattribute(name = "y")]
+struct Y(i32);
+
+#[derive(Debug, PartialEq)]
+struct Point {
+    x: i32,
+    y: i32,
+    first_attribute: Option<ExpandedNameBuf>,
+}
+
+fn name(local_name: &str) -> ExpandedName<'_> {
+    ExpandedName::new(LocalName::new(local_name).unwrap(), None)
+}
+
+impl<'de> Deserialize<'de> for Point {
+    fn deserialize<D: Deserializer<'de>>(reader: D) -> Result<Self, D::Error> {
+        struct PointVisitor;
+
+        impl<'de> Visitor<'de> for PointVisitor {
+            type Value = Point;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a point")
+            }
+
+            fn visit_element<A>(self, mut element: A) -> Result<Self::Value, A::Error>
+            where
+                A: ElementAccess<'de>,
+            {
+                let first_attribute = element.peek_name()?;
+                let X(x) = element
+                    .find_attribute(name("x"))?
+                    .ok_or_else(|| de::Error::missing_field("x"))?;
+                let Y(y) = element
+                    .find_attribute(name("y"))?
+                    .ok_or_else(|| de::Error::missing_field("y"))?;
+
+                Ok(Point {
+                    x,
+                    y,
+                    first_attribute,
+                })
+            }
+        }
+
+        reader.deserialize_any(PointVisitor)
+    }
+}
+
+#[test]
+fn find_attribute_in_any_order() {
+    let actual: Point = quick_xml_deserialize_test(r#"<point y="2" x="1"/>"#).unwrap();
+
+    assert_eq!(
+        actual,
+        Point {
+            x: 1,
+            y: 2,
+            first_attribute: Some(name("y").into_owned()),
+        }
+    );
+}
+
+#[test]
+fn find_attribute_skips_declarations() {
+    let actual: Point =
+        quick_xml_deserialize_test(r#"<point xmlns:a="http://example.com" x="1" y="2"/>"#).unwrap();
+
+    assert_eq!(
+        actual,
+        Point {
+            x: 1,
+            y: 2,
+            first_attribute: Some(name("x").into_owned()),
+        }
+    );
+}
+
+#[test]
+fn find_missing_attribute() {
+    let actual: Result<Point, _> = quick_xml_deserialize_test(r#"<point y="2"/>"#);
+
+    assert!(matches!(
+        actual,
+        Err(xmlity_quick_xml::de::Error::MissingField { field }) if field == "x"
+    ));
+}
+
+#[test]
+fn find_attribute_from_value() {
+    let value: XmlValue = quick_xml_deserialize_test(r#"<point y="2" x="1"/>"#).unwrap();
+
+    let actual: Point = from_value(&value).unwrap();
+
+    assert_eq!(
+        actual,
+        Point {
+            x: 1,
+            y: 2,
+            first_attribute: Some(name("y").into_owned()),
+        }
+    );
+}
@@ -0,0 +1,83 @@
+use pretty_assertions::assert_eq;
+
+use xmlity::Deserialize;
+use xmlity_quick_xml::from_str_fragments;
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "event")]
+struct Event {
+    #[xattribute(name = "level")]
+    level: String,
+    message: Message,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "message")]
+struct Message(String);
+
+fn event(level: &str, message: &str) -> Event {
+    Event {
+        level: level.to_string(),
+        message: Message(message.to_string()),
+    }
+}
+
+#[test]
+fn line_delimited_fragments() {
+    let input = r#"<event level="info"><message>Started</message></event>
+<event level="warn"><message>Slow</message></event>
+"#;
+
+    let events = from_str_fragments::<Event>(input)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(
+        events,
+        vec![event("info", "Started"), event("warn", "Slow")]
+    );
+}
+
+#[test]
+fn concatenated_documents() {
+    let input = r#"<?xml version="1.0"?>
+<event level="info">
+  <message>Started</message>
+</event><?xml version="1.0"?><!-- second --><event level="error"><message>Failed</message></event>"#;
+
+    let events = from_str_fragments::<Event>(input)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(
+        events,
+        vec![event("info", "Started"), event("error", "Failed")]
+    );
+}
+
+#[test]
+fn recovers_from_invalid_documents() {
+    let input = r#"<event level="info"><message>Started</message></event>
+<event><message>No level</message></event>
+<event level="info"><message>Broken</oops></event>
+<event level="warn"><message>Unclosed</message>
+<?xml version="1.0"?><event level="debug"><message>Done</message></event>
+"#;
+
+    let results = from_str_fragments::<Event>(input).collect::<Vec<_>>();
+
+    assert_eq!(results.len(), 5);
+    assert_eq!(results[0].as_ref().unwrap(), &event("info", "Started"));
+    assert!(results[1].is_err());
+    assert!(results[2].is_err());
+    assert!(matches!(
+        results[3],
+        Err(xmlity_quick_xml::de::Error::StartElementWithoutEnd { ref name }) if name == "event"
+    ));
+    assert_eq!(results[4].as_ref().unwrap(), &event("debug", "Done"));
+}
+
+#[test]
+fn empty_input() {
+    assert_eq!(from_str_fragments::<Event>("  \n").count(), 0);
+}
@@ -0,0 +1,37 @@
+use pretty_assertions::assert_eq;
+use xmlity::value::{InvalidContentError, XmlComment, XmlValue};
+use xmlity_quick_xml::ser::Error;
+
+#[test]
+fn valid_comment() {
+    let value = XmlValue::Comment(XmlComment::new(" a - comment "));
+
+    assert_eq!(
+        xmlity_quick_xml::to_string(&value).unwrap(),
+        "<!-- a - comment -->"
+    );
+}
+
+#[test]
+fn comment_with_double_hyphen() {
+    let value = XmlValue::Comment(XmlComment::new("a -- b"));
+
+    let err = xmlity_quick_xml::to_string(&value).unwrap_err();
+
+    assert!(matches!(
+        err,
+        Error::InvalidContent(InvalidContentError::DoubleHyphenInComment)
+    ));
+}
+
+#[test]
+fn comment_ending_with_hyphen() {
+    let value = XmlValue::Comment(XmlComment::new("a-"));
+
+    let err = xmlity_quick_xml::to_string(&value).unwrap_err();
+
+    assert!(matches!(
+        err,
+        Error::InvalidContent(InvalidContentError::CommentEndsWithHyphen)
+    ));
+}
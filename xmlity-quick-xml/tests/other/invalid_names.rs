@@ -0,0 +1,19 @@
+use rstest::rstest;
+use xmlity::value::{XmlElement, XmlValue};
+use xmlity_quick_xml::de::Error;
+
+#[rstest]
+#[case::element("<c&e; xmlns=''/>", "c&e;")]
+#[case::element_with_end("<1a>text</1a>", "1a")]
+#[case::prefix("<1a:b/>", "1a")]
+#[case::attribute("<a 1b='x'/>", "1b")]
+#[case::child("<a><b&c;/></a>", "b&c;")]
+fn invalid_names_are_errors(#[case] xml: &str, #[case] name: &str) {
+    let err = xmlity_quick_xml::from_str::<XmlElement>(xml).unwrap_err();
+    let Error::InvalidName { name: actual } = err else {
+        panic!("Expected an invalid name, got {err:?}");
+    };
+    assert_eq!(actual, name);
+
+    assert!(xmlity_quick_xml::from_str::<XmlValue>(xml).is_err());
+}
@@ -0,0 +1,67 @@
+use pretty_assertions::assert_eq;
+
+use crate::utils::{clean_string, quick_xml_deserialize_test, quick_xml_serialize_test};
+
+use xmlity::{types::utils::LazyXml, Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "details")]
+struct Details {
+    #[xattribute(name = "size")]
+    size: u32,
+    #[xvalue(default)]
+    notes: Vec<Note>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "note")]
+struct Note(String);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "file")]
+struct File {
+    #[xattribute(name = "name")]
+    name: String,
+    details: LazyXml<Details>,
+}
+
+const FILE_XML: &str = r###"
+<file name="a.txt">
+  <details size="12">
+    <note>First</note>
+    <note>Second</note>
+  </details>
+</file>
+"###;
+
+#[test]
+fn lazy_xml_parse() {
+    let file: File = quick_xml_deserialize_test(&clean_string(FILE_XML)).unwrap();
+
+    assert_eq!(file.name, "a.txt");
+    assert_eq!(
+        file.details.parse().unwrap(),
+        Details {
+            size: 12,
+            notes: vec![Note("First".to_string()), Note("Second".to_string())],
+        }
+    );
+}
+
+#[test]
+fn lazy_xml_defers_errors() {
+    let file: File = quick_xml_deserialize_test(&clean_string(
+        r#"<file name="a.txt"><details size="big"/></file>"#,
+    ))
+    .unwrap();
+
+    assert!(file.details.parse().is_err());
+}
+
+#[test]
+fn lazy_xml_round_trip() {
+    let xml = clean_string(FILE_XML);
+    let file: File = quick_xml_deserialize_test(&xml).unwrap();
+
+    assert_eq!(quick_xml_serialize_test(file).unwrap(), xml);
+}
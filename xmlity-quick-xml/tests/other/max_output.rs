@@ -0,0 +1,72 @@
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use xmlity::Serialize;
+use xmlity_quick_xml::{ser::Error, to_string_with_config, Serializer, SerializerConfig};
+
+#[derive(Debug, Serialize)]
+#[xelement(name = "item")]
+struct Item(String);
+
+#[derive(Debug, Serialize)]
+#[xelement(name = "list")]
+struct List {
+    items: Vec<Item>,
+}
+
+const TWO_ITEMS: &str = "<list><item>0</item><item>1</item></list>";
+
+fn list(count: usize) -> List {
+    List {
+        items: (0..count).map(|i| Item(i.to_string())).collect(),
+    }
+}
+
+#[rstest]
+#[case::no_limit(None)]
+#[case::exactly_at_limit(Some(TWO_ITEMS.len()))]
+#[case::below_limit(Some(100))]
+fn within_output_limit(#[case] max_output_bytes: Option<usize>) {
+    let config = SerializerConfig::new().with_max_output_bytes(max_output_bytes);
+
+    assert_eq!(to_string_with_config(&list(2), &config).unwrap(), TWO_ITEMS);
+}
+
+#[test]
+fn exceeding_output_limit() {
+    let max_output_bytes = TWO_ITEMS.len() - 1;
+    let config = SerializerConfig::new().with_max_output_bytes(Some(max_output_bytes));
+
+    let err = to_string_with_config(&list(2), &config).unwrap_err();
+
+    assert!(matches!(
+        err,
+        Error::OutputLimitExceeded { max_output_bytes: limit } if limit == max_output_bytes
+    ));
+}
+
+#[test]
+fn exceeding_output_limit_stops_writing() {
+    let config = SerializerConfig::new().with_max_output_bytes(Some(30));
+    let mut serializer = Serializer::with_config(Vec::new(), &config);
+
+    let err = list(1000).serialize(&mut serializer).unwrap_err();
+    assert!(matches!(err, Error::OutputLimitExceeded { .. }));
+
+    let written = String::from_utf8(serializer.into_inner()).unwrap();
+    assert!(written.len() <= 30);
+    assert!(written.starts_with("<list><item>0</item><item>1"));
+}
+
+#[test]
+fn output_limit_counts_indentation() {
+    let pretty = "<list>\n  <item>0</item>\n  <item>1</item>\n</list>";
+    let config = SerializerConfig::new().with_indentation(Some(2));
+
+    assert_eq!(to_string_with_config(&list(2), &config).unwrap(), pretty);
+
+    let config = config.with_max_output_bytes(Some(pretty.len() - 1));
+    assert!(matches!(
+        to_string_with_config(&list(2), &config),
+        Err(Error::OutputLimitExceeded { .. })
+    ));
+}
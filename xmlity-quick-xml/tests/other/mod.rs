@@ -1,6 +1,53 @@
+pub mod append;
+pub mod arbitrary;
+#[cfg(feature = "async-tokio")]
+pub mod async_tokio;
+pub mod attribute_namespaces;
+pub mod attribute_normalization;
+pub mod backend_tests;
+pub mod borrowed;
+pub mod bytes;
 pub mod combined;
+pub mod doctype;
+pub mod document;
+pub mod dynamic;
 pub mod empty;
 pub mod empty_variant;
+pub mod entities;
 pub mod enum_with_no_arm;
+pub mod error_traits;
+pub mod escaping;
+pub mod exhaustive;
+pub mod find_attribute;
+pub mod fragments;
+pub mod invalid_content;
+pub mod invalid_names;
+pub mod lazy;
+pub mod max_output;
+pub mod name_resolver;
+pub mod namespace_errors;
+pub mod namespace_order;
+pub mod net;
+pub mod newtype_visitor;
+pub mod one_direction;
+pub mod owned;
+pub mod peek;
+pub mod pi;
+pub mod preserve_prefixes;
+pub mod pure_backend;
+pub mod reader;
+pub mod reader_items;
+#[cfg(feature = "recovery")]
+pub mod recovery;
+pub mod recursion_limit;
+pub mod runtime_attribute;
+pub mod serialize_iter;
+pub mod skip_prolog;
+pub mod sub_serializer;
+pub mod transparent;
+pub mod unexpected;
 pub mod variant;
+pub mod via_value;
+pub mod writer;
+pub mod xml_space;
 pub mod xml_value;
@@ -0,0 +1,48 @@
+use pretty_assertions::assert_eq;
+use xmlity::{de::CaseInsensitiveNameResolver, Deserialize, ExpandedName, LocalName, Serialize};
+use xmlity_quick_xml::Deserializer;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "title")]
+pub struct Title(String);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "head")]
+pub struct Head {
+    #[xattribute(name = "lang")]
+    pub lang: String,
+    pub title: Title,
+}
+
+fn resolver() -> CaseInsensitiveNameResolver {
+    CaseInsensitiveNameResolver::new(
+        ["head", "title", "lang"]
+            .into_iter()
+            .map(|name| ExpandedName::new(LocalName::new(name).unwrap(), None).into_owned()),
+    )
+}
+
+#[test]
+fn case_insensitive_names() {
+    let mut deserializer =
+        Deserializer::from(r#"<HEAD Lang="en"><Title>Hello</Title></HEAD>"#.as_bytes())
+            .with_name_resolver(resolver());
+
+    let actual = Head::deserialize(&mut deserializer).unwrap();
+
+    assert_eq!(
+        actual,
+        Head {
+            lang: "en".to_string(),
+            title: Title("Hello".to_string()),
+        }
+    );
+}
+
+#[test]
+fn case_sensitive_without_resolver() {
+    let mut deserializer =
+        Deserializer::from(r#"<HEAD Lang="en"><Title>Hello</Title></HEAD>"#.as_bytes());
+
+    assert!(Head::deserialize(&mut deserializer).is_err());
+}
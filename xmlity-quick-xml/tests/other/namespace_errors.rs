@@ -0,0 +1,189 @@
+use crate::define_serialize_test;
+
+use xmlity::{
+    ser::{AttributeSerializer, IncludePrefix, SerializeAttributeAccess},
+    ExpandedName, LocalName, Serialize, SerializeAttribute, XmlNamespace,
+};
+use xmlity_quick_xml::ser::Error;
+
+#[derive(Debug, PartialEq, SerializeAttribute)]
+#[xattribute(
+    name = "attr",
+    namespace = "http://example.com/b",
+    preferred_prefix = "a",
+    enforce_prefix = true
+)]
+pub struct ConflictingAttribute(String);
+
+#[derive(Debug, PartialEq, Serialize)]
+#[xelement(
+    name = "element",
+    namespace = "http://example.com/a",
+    preferred_prefix = "a",
+    enforce_prefix = true
+)]
+pub struct PrefixConflict {
+    #[xattribute(deferred = true)]
+    attr: ConflictingAttribute,
+}
+
+#[test]
+fn prefix_conflict_on_same_element() {
+    let err = xmlity_quick_xml::to_string(&PrefixConflict {
+        attr: ConflictingAttribute("value".to_string()),
+    })
+    .unwrap_err();
+
+    let Error::PrefixConflict {
+        prefix,
+        namespace,
+        existing_namespace,
+    } = err
+    else {
+        panic!("Expected a prefix conflict, got {err:?}");
+    };
+    assert_eq!(prefix.as_str(), "a");
+    assert_eq!(namespace.as_str(), "http://example.com/b");
+    assert_eq!(existing_namespace.as_str(), "http://example.com/a");
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[xelement(
+    name = "element",
+    namespace = "http://example.com/a",
+    preferred_prefix = "xml",
+    enforce_prefix = true
+)]
+pub struct ReservedPrefix;
+
+#[test]
+fn prefix_conflict_with_reserved_prefix() {
+    let err = xmlity_quick_xml::to_string(&ReservedPrefix).unwrap_err();
+
+    let Error::PrefixConflict {
+        prefix,
+        existing_namespace,
+        ..
+    } = err
+    else {
+        panic!("Expected a prefix conflict, got {err:?}");
+    };
+    assert_eq!(prefix.as_str(), "xml");
+    assert_eq!(
+        existing_namespace.as_str(),
+        "http://www.w3.org/XML/1998/namespace"
+    );
+}
+
+#[derive(Debug, PartialEq, SerializeAttribute)]
+#[xattribute(name = "attr", namespace = "http://www.w3.org/2000/xmlns/")]
+pub struct XmlnsAttribute(String);
+
+#[derive(Debug, PartialEq, Serialize)]
+#[xelement(name = "element")]
+pub struct UnboundNamespace {
+    #[xattribute(deferred = true)]
+    attr: XmlnsAttribute,
+}
+
+#[test]
+fn unbound_namespace() {
+    let err = xmlity_quick_xml::to_string(&UnboundNamespace {
+        attr: XmlnsAttribute("value".to_string()),
+    })
+    .unwrap_err();
+
+    let Error::UnboundNamespace { namespace } = err else {
+        panic!("Expected an unbound namespace, got {err:?}");
+    };
+    assert_eq!(namespace.as_str(), "http://www.w3.org/2000/xmlns/");
+}
+
+/// An attribute that may only use an existing binding of its namespace.
+#[derive(Debug, PartialEq)]
+pub struct NeverPrefixedAttribute;
+
+impl SerializeAttribute for NeverPrefixedAttribute {
+    fn serialize_attribute<S: AttributeSerializer>(
+        &self,
+        mut serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let name = ExpandedName::new(
+            LocalName::new("attr").unwrap(),
+            Some(XmlNamespace::new("http://example.com/b").unwrap()),
+        );
+        let mut attribute = serializer.serialize_attribute(&name)?;
+        attribute.include_prefix(IncludePrefix::Never)?;
+        attribute.end(&"value")
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[xelement(name = "element")]
+pub struct NeverPrefixed {
+    #[xattribute(deferred = true)]
+    attr: NeverPrefixedAttribute,
+}
+
+#[test]
+fn unbound_namespace_for_attribute_that_never_includes_a_prefix() {
+    let err = xmlity_quick_xml::to_string(&NeverPrefixed {
+        attr: NeverPrefixedAttribute,
+    })
+    .unwrap_err();
+
+    let Error::UnboundNamespace { namespace } = err else {
+        panic!("Expected an unbound namespace, got {err:?}");
+    };
+    assert_eq!(namespace.as_str(), "http://example.com/b");
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[xelement(
+    name = "element",
+    namespace = "http://example.com/b",
+    preferred_prefix = "b"
+)]
+pub struct BoundNeverPrefixed {
+    #[xattribute(deferred = true)]
+    attr: NeverPrefixedAttribute,
+}
+
+define_serialize_test!(
+    attribute_that_never_includes_a_prefix_uses_existing_binding,
+    [(
+        BoundNeverPrefixed {
+            attr: NeverPrefixedAttribute
+        },
+        r#"<b:element xmlns:b="http://example.com/b" b:attr="value"/>"#
+    )]
+);
+
+#[derive(Debug, PartialEq, Serialize)]
+#[xelement(
+    name = "child",
+    namespace = "http://example.com/b",
+    preferred_prefix = "a",
+    enforce_prefix = true
+)]
+pub struct ShadowingChild;
+
+#[derive(Debug, PartialEq, Serialize)]
+#[xelement(
+    name = "parent",
+    namespace = "http://example.com/a",
+    preferred_prefix = "a"
+)]
+pub struct ShadowingParent {
+    child: ShadowingChild,
+}
+
+define_serialize_test!(
+    enforced_prefix_shadows_outer_binding,
+    [(
+        ShadowingParent {
+            child: ShadowingChild
+        },
+        r#"<a:parent xmlns:a="http://example.com/a"><a:child xmlns:a="http://example.com/b"/></a:parent>"#
+    )]
+);
@@ -0,0 +1,125 @@
+use crate::{define_serialize_test, utils::quick_xml_serialize_test};
+
+use pretty_assertions::assert_eq;
+use xmlity::{
+    ser::{IncludePrefix, SerializeElement},
+    ExpandedName, LocalName, Prefix, Serialize, SerializeAttribute, Serializer, XmlNamespace,
+};
+
+#[derive(Debug, PartialEq, SerializeAttribute)]
+#[xattribute(name = "c", namespace = "http://example.com/c")]
+pub struct AttrC(String);
+
+#[derive(Debug, PartialEq, SerializeAttribute)]
+#[xattribute(name = "b", namespace = "http://example.com/b")]
+pub struct AttrB(String);
+
+#[derive(Debug, PartialEq, SerializeAttribute)]
+#[xattribute(name = "b2", namespace = "http://example.com/b")]
+pub struct AttrB2(String);
+
+#[derive(Debug, PartialEq, Serialize)]
+#[xelement(name = "element", namespace = "http://example.com/a")]
+pub struct ManyNamespaces {
+    #[xattribute(deferred = true)]
+    c: AttrC,
+    #[xattribute(deferred = true)]
+    b: AttrB,
+    #[xattribute(deferred = true)]
+    b2: AttrB2,
+}
+
+/// The same value must always be written the same way, so namespaces are declared in the order they are introduced rather than by prefix or namespace.
+fn many_namespaces() -> ManyNamespaces {
+    ManyNamespaces {
+        c: AttrC("1".to_string()),
+        b: AttrB("2".to_string()),
+        b2: AttrB2("3".to_string()),
+    }
+}
+
+define_serialize_test!(
+    declarations_in_order_of_introduction,
+    [(
+        many_namespaces(),
+        r#"<a0:element xmlns:a0="http://example.com/a" xmlns:a1="http://example.com/c" a1:c="1" xmlns:a2="http://example.com/b" a2:b="2" a2:b2="3"/>"#
+    )]
+);
+
+#[derive(Debug, PartialEq, SerializeAttribute)]
+#[xattribute(
+    name = "attr",
+    namespace = "http://example.com/a",
+    preferred_prefix = "a",
+    enforce_prefix = true
+)]
+pub struct EnforcedAttribute(String);
+
+#[derive(Debug, PartialEq, Serialize)]
+#[xelement(
+    name = "element",
+    namespace = "http://example.com/a",
+    preferred_prefix = "a",
+    enforce_prefix = true
+)]
+pub struct SharedEnforcedPrefix {
+    #[xattribute(deferred = true)]
+    attr: EnforcedAttribute,
+}
+
+define_serialize_test!(
+    shared_enforced_prefix_declared_once,
+    [(
+        SharedEnforcedPrefix {
+            attr: EnforcedAttribute("1".to_string())
+        },
+        r#"<a:element xmlns:a="http://example.com/a" a:attr="1"/>"#
+    )]
+);
+
+/// An element that declares its own prefix explicitly and also always includes it.
+#[derive(Debug)]
+struct DeclaredAndAlwaysIncluded;
+
+impl Serialize for DeclaredAndAlwaysIncluded {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let prefix = Prefix::new("a").unwrap();
+        let namespace = XmlNamespace::new("http://example.com/a").unwrap();
+        let name = ExpandedName::new(LocalName::new("element").unwrap(), Some(namespace));
+
+        let mut element = serializer.serialize_element(&name)?;
+        element.declare_namespace(prefix, namespace)?;
+        element.preferred_prefix(Some(prefix))?;
+        element.include_prefix(IncludePrefix::Always)?;
+        element.end()
+    }
+}
+
+#[test]
+fn explicit_declaration_not_repeated() {
+    let actual = quick_xml_serialize_test(DeclaredAndAlwaysIncluded).unwrap();
+
+    assert_eq!(actual, r#"<a:element xmlns:a="http://example.com/a"/>"#);
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[xelement(name = "siblings")]
+pub struct Siblings {
+    first: SharedEnforcedPrefix,
+    second: SharedEnforcedPrefix,
+}
+
+define_serialize_test!(
+    declarations_of_empty_element_not_in_scope_of_siblings,
+    [(
+        Siblings {
+            first: SharedEnforcedPrefix {
+                attr: EnforcedAttribute("1".to_string())
+            },
+            second: SharedEnforcedPrefix {
+                attr: EnforcedAttribute("2".to_string())
+            },
+        },
+        r#"<siblings><a:element xmlns:a="http://example.com/a" a:attr="1"/><a:element xmlns:a="http://example.com/a" a:attr="2"/></siblings>"#
+    )]
+);
@@ -0,0 +1,38 @@
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use crate::define_test;
+
+use xmlity::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "listen")]
+struct Listen {
+    #[xattribute(name = "ip")]
+    ip: IpAddr,
+    #[xattribute(name = "fallback")]
+    fallback: Ipv4Addr,
+    #[xvalue]
+    address: SocketAddr,
+}
+
+define_test!(
+    net_types,
+    [
+        (
+            Listen {
+                ip: "10.0.0.1".parse().unwrap(),
+                fallback: Ipv4Addr::LOCALHOST,
+                address: "0.0.0.0:8080".parse().unwrap(),
+            },
+            r#"<listen ip="10.0.0.1" fallback="127.0.0.1">0.0.0.0:8080</listen>"#
+        ),
+        (
+            Listen {
+                ip: "fe80::1".parse().unwrap(),
+                fallback: Ipv4Addr::UNSPECIFIED,
+                address: "[::1]:443".parse().unwrap(),
+            },
+            r#"<listen ip="fe80::1" fallback="0.0.0.0">[::1]:443</listen>"#
+        )
+    ]
+);
@@ -0,0 +1,61 @@
+use pretty_assertions::assert_eq;
+
+use xmlity::{
+    types::{string::FromStrVisitor, utils::NewtypeVisitor},
+    Deserialize, Deserializer,
+};
+
+#[derive(Debug, PartialEq)]
+pub struct Email(String);
+
+impl TryFrom<String> for Email {
+    type Error = &'static str;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        if value.contains('@') {
+            Ok(Email(value))
+        } else {
+            Err("missing @ in email address")
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Email {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(NewtypeVisitor::new(FromStrVisitor::<String>::default()))
+    }
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "contact")]
+pub struct Contact {
+    #[xattribute(name = "email")]
+    pub email: Email,
+    pub backup: Email,
+}
+
+#[test]
+fn newtype_from_text_and_attribute() {
+    let contact: Contact = xmlity_quick_xml::from_str(
+        r#"<contact email="a@example.com"><![CDATA[b@example.com]]></contact>"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        contact,
+        Contact {
+            email: Email("a@example.com".to_string()),
+            backup: Email("b@example.com".to_string()),
+        }
+    );
+}
+
+#[test]
+fn newtype_conversion_error() {
+    let err = xmlity_quick_xml::from_str::<Email>("not an email").unwrap_err();
+
+    assert!(matches!(
+        err,
+        xmlity_quick_xml::de::Error::Custom(message) if message == "missing @ in email address"
+    ));
+}
@@ -0,0 +1,78 @@
+use pretty_assertions::assert_eq;
+use xmlity::{DeserializationGroup, Deserialize, SerializationGroup, Serialize};
+
+/// A type that can only be serialized.
+#[derive(Debug, PartialEq, Serialize)]
+#[xelement(name = "stamp")]
+pub struct Stamp(String);
+
+/// A type that can only be deserialized.
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "token")]
+pub struct Token(String);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xmlity(serialize_only)]
+#[xelement(name = "export")]
+pub struct Export {
+    pub stamp: Stamp,
+}
+
+#[test]
+fn serialize_only() {
+    let export = Export {
+        stamp: Stamp("a".to_string()),
+    };
+
+    assert_eq!(
+        xmlity_quick_xml::to_string(&export).unwrap(),
+        "<export><stamp>a</stamp></export>"
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(all(), xmlity(deserialize_only))]
+#[xelement(name = "import")]
+pub struct Import {
+    pub token: Token,
+}
+
+#[test]
+fn deserialize_only() {
+    let actual: Import = xmlity_quick_xml::from_str("<import><token>a</token></import>").unwrap();
+
+    assert_eq!(
+        actual,
+        Import {
+            token: Token("a".to_string()),
+        }
+    );
+}
+
+#[derive(Debug, PartialEq, SerializationGroup, DeserializationGroup)]
+#[xmlity(deserialize_only)]
+pub struct ImportGroup {
+    pub token: Token,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "import")]
+pub struct GroupImport {
+    #[xgroup]
+    pub group: ImportGroup,
+}
+
+#[test]
+fn deserialize_only_group() {
+    let actual: GroupImport =
+        xmlity_quick_xml::from_str("<import><token>a</token></import>").unwrap();
+
+    assert_eq!(
+        actual,
+        GroupImport {
+            group: ImportGroup {
+                token: Token("a".to_string()),
+            }
+        }
+    );
+}
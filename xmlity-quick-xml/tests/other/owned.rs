@@ -0,0 +1,51 @@
+use std::thread;
+
+use xmlity::{Deserialize, XmlValue};
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[xelement(name = "job")]
+struct Job {
+    #[xattribute(name = "id")]
+    id: String,
+    name: String,
+}
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn owned_outputs_are_send_and_sync() {
+    assert_send_sync::<XmlValue>();
+    assert_send_sync::<xmlity_quick_xml::de::Error>();
+    assert_send_sync::<xmlity_quick_xml::ser::Error>();
+}
+
+#[test]
+fn from_str_owned_moves_across_threads() {
+    let input = String::from(r#"<job id="1">Build</job>"#);
+
+    let job = thread::spawn(move || xmlity_quick_xml::from_str_owned::<Job>(&input))
+        .join()
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        job,
+        Job {
+            id: "1".to_string(),
+            name: "Build".to_string(),
+        }
+    );
+}
+
+#[test]
+fn from_str_owned_error_moves_across_threads() {
+    let error = thread::spawn(|| xmlity_quick_xml::from_str_owned::<Job>("<other/>"))
+        .join()
+        .unwrap()
+        .unwrap_err();
+
+    assert!(matches!(
+        error,
+        xmlity_quick_xml::de::Error::WrongName { .. }
+    ));
+}
@@ -0,0 +1,125 @@
+use core::fmt;
+
+use pretty_assertions::assert_eq;
+
+use crate::utils::{clean_string, quick_xml_deserialize_test};
+
+use xmlity::{
+    de::{self, SeqAccess, Visitor},
+    value::from_value,
+    Deserialize, Deserializer, ExpandedName, LocalName, XmlValue,
+};
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "circle")]
+struct Circle {
+    #[xattribute(name = "r")]
+    r: u32,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "square")]
+struct Square {
+    #[xattribute(name = "side")]
+    side: u32,
+}
+
+#[derive(Debug, PartialEq)]
+enum Shape {
+    Circle(Circle),
+    Square(Square),
+}
+
+#[derive(Debug, PartialEq)]
+struct Shapes {
+    shapes: Vec<Shape>,
+    size_hint: Option<usize>,
+}
+
+impl<'de> Deserialize<'de> for Shapes {
+    fn deserialize<D: Deserializer<'de>>(reader: D) -> Result<Self, D::Error> {
+        Self::deserialize_seq(reader)
+    }
+
+    fn deserialize_seq<D: Deserializer<'de>>(reader: D) -> Result<Self, D::Error> {
+        struct ShapesVisitor;
+
+        impl<'de> Visitor<'de> for ShapesVisitor {
+            type Value = Shapes;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a sequence of shapes")
+            }
+
+            fn visit_seq<S>(self, mut sequence: S) -> Result<Self::Value, S::Error>
+            where
+                S: SeqAccess<'de>,
+            {
+                let circle = ExpandedName::new(LocalName::new("circle").unwrap(), None);
+                let square = ExpandedName::new(LocalName::new("square").unwrap(), None);
+
+                let size_hint = sequence.size_hint();
+                let mut shapes = Vec::new();
+                while let Some(node) = sequence.peek_element()? {
+                    let shape = match node.element_name() {
+                        Some(name) if name == circle => sequence.next_element()?.map(Shape::Circle),
+                        Some(name) if name == square => sequence.next_element()?.map(Shape::Square),
+                        _ => return Err(de::Error::unknown_child()),
+                    };
+                    shapes.extend(shape);
+                }
+
+                Ok(Shapes { shapes, size_hint })
+            }
+        }
+
+        reader.deserialize_seq(ShapesVisitor)
+    }
+}
+
+const SHAPES_XML: &str = r###"
+<circle r="1"/>
+<square side="2"/>
+<circle r="3"/>
+"###;
+
+fn shapes() -> Vec<Shape> {
+    vec![
+        Shape::Circle(Circle { r: 1 }),
+        Shape::Square(Square { side: 2 }),
+        Shape::Circle(Circle { r: 3 }),
+    ]
+}
+
+#[test]
+fn peek_dispatches_by_name() {
+    let actual: Shapes = quick_xml_deserialize_test(&clean_string(SHAPES_XML)).unwrap();
+
+    assert_eq!(actual.shapes, shapes());
+}
+
+#[test]
+fn peek_dispatches_by_name_from_value() {
+    let value: XmlValue = quick_xml_deserialize_test(&clean_string(SHAPES_XML)).unwrap();
+
+    let actual: Shapes = from_value(&value).unwrap();
+
+    assert_eq!(
+        actual,
+        Shapes {
+            shapes: shapes(),
+            size_hint: Some(3),
+        }
+    );
+}
+
+#[test]
+fn peek_rejects_unknown_elements() {
+    let actual: Result<Shapes, _> =
+        quick_xml_deserialize_test(&clean_string(r#"<circle r="1"/><triangle/>"#));
+
+    assert!(matches!(
+        actual,
+        Err(xmlity_quick_xml::de::Error::UnknownChild)
+    ));
+}
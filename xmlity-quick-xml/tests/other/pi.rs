@@ -0,0 +1,149 @@
+use std::{fmt, str::FromStr};
+
+use xmlity::{
+    types::pi::{Pi, PiTarget, XmlStylesheet},
+    value::XmlProcessingInstruction,
+    Deserialize, Serialize,
+};
+
+use crate::define_test;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xpi(target = "xml-stylesheet")]
+pub struct Stylesheet {
+    pub href: String,
+    #[xattribute(name = "type")]
+    pub type_: String,
+    #[xattribute(optional)]
+    pub media: Option<String>,
+}
+
+define_test!(
+    stylesheet_pi,
+    [
+        (
+            Stylesheet {
+                href: "a.css".to_string(),
+                type_: "text/css".to_string(),
+                media: None,
+            },
+            r#"<?xml-stylesheet href="a.css" type="text/css"?>"#
+        ),
+        (
+            Stylesheet {
+                href: "b.css".to_string(),
+                type_: "text/css".to_string(),
+                media: Some("print".to_string()),
+            },
+            r#"<?xml-stylesheet href="b.css" type="text/css" media="print"?>"#,
+            r#"<?xml-stylesheet media='print' type="text/css" href="b.css"?>"#
+        )
+    ]
+);
+
+define_test!(
+    escaped_pseudo_attributes,
+    [(
+        Stylesheet {
+            href: "a.css?>".to_string(),
+            type_: "text/css".to_string(),
+            media: Some(r#"it's "print""#.to_string()),
+        },
+        r#"<?xml-stylesheet href="a.css?&gt;" type="text/css" media="it's &quot;print&quot;"?>"#,
+        r#"<?xml-stylesheet href="a.css?&#62;" type='text/css' media='it&apos;s "print"'?>"#
+    )]
+);
+
+#[test]
+fn escaped_xml_stylesheet() {
+    let stylesheet = XmlStylesheet {
+        title: Some(r#"it's "quoted""#.to_string()),
+        ..XmlStylesheet::new("a.css", "text/css")
+    };
+
+    assert_eq!(
+        stylesheet.to_string(),
+        r#"href="a.css" type="text/css" title="it's &quot;quoted&quot;""#
+    );
+    assert_eq!(stylesheet.to_string().parse(), Ok(stylesheet));
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xpi(target = "page")]
+pub struct PageBreak(u32);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "doc")]
+pub struct Document {
+    pub before: PageBreak,
+    pub text: String,
+}
+
+define_test!(
+    pi_in_element,
+    [(
+        Document {
+            before: PageBreak(3),
+            text: "Hello".to_string(),
+        },
+        "<doc><?page 3?>Hello</doc>"
+    )]
+);
+
+#[derive(Debug, PartialEq)]
+pub struct Page(u32);
+
+impl PiTarget for Page {
+    const TARGET: &'static str = "page";
+}
+
+impl fmt::Display for Page {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for Page {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(Page)
+    }
+}
+
+define_test!(pi_wrapper, [(Pi(Page(4)), "<?page 4?>")]);
+
+define_test!(
+    xml_stylesheet,
+    [
+        (
+            XmlStylesheet::new("a.css", "text/css"),
+            r#"<?xml-stylesheet href="a.css" type="text/css"?>"#
+        ),
+        (
+            XmlStylesheet {
+                title: Some("Print".to_string()),
+                alternate: Some(true),
+                ..XmlStylesheet::new("b.xsl", "text/xsl")
+            },
+            r#"<?xml-stylesheet href="b.xsl" type="text/xsl" title="Print" alternate="yes"?>"#,
+            r#"<?xml-stylesheet alternate="yes" title="Print" type="text/xsl" href="b.xsl" extra="ignored"?>"#
+        )
+    ]
+);
+
+define_test!(
+    pi_value,
+    [
+        (
+            XmlProcessingInstruction::new("page", "4"),
+            "<?page 4?>",
+            "<?page   4?>"
+        ),
+        (
+            XmlProcessingInstruction::new("page", "4 "),
+            "<?page 4 ?>",
+            "<?page\t4 ?>"
+        )
+    ]
+);
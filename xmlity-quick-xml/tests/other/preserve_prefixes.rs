@@ -0,0 +1,82 @@
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use xmlity::{
+    value::{XmlValue, XmlnsDeclaration},
+    Deserialize, Prefix, PrefixBuf, XmlNamespaceBuf,
+};
+use xmlity_quick_xml::Deserializer;
+
+fn round_trip(xml: &str, preserve_prefixes: bool) -> String {
+    let mut deserializer =
+        Deserializer::from(xml.as_bytes()).with_preserve_prefixes(preserve_prefixes);
+    let value = XmlValue::deserialize(&mut deserializer).unwrap();
+
+    xmlity_quick_xml::to_string(&value).unwrap()
+}
+
+#[rstest]
+#[case::prefixed(r#"<x:root xmlns:x="http://example.com/x"><x:child x:id="1"/></x:root>"#)]
+#[case::default_namespace(r#"<root xmlns="http://example.com/x"><child/></root>"#)]
+#[case::two_prefixes_same_namespace(
+    r#"<x:root xmlns:x="http://example.com/x"><y:child xmlns:y="http://example.com/x"/></x:root>"#
+)]
+#[case::no_namespace(r#"<root id="1"><child/></root>"#)]
+#[case::declared_on_ancestor(r#"<root xmlns:x="http://example.com/x"><x:child/><x:child/></root>"#)]
+#[case::unused_declaration(r#"<root xmlns:x="http://example.com/x"><child/></root>"#)]
+#[case::declaration_order(
+    r#"<root xmlns:b="http://example.com/b" xmlns:a="http://example.com/a" a:id="1" b:id="2"/>"#
+)]
+#[case::xml_lang(r#"<root xml:lang="en"><child/></root>"#)]
+#[case::shadowed_prefix(
+    r#"<x:root xmlns:x="http://example.com/x"><x:child xmlns:x="http://example.com/y"><x:leaf/></x:child></x:root>"#
+)]
+#[case::undeclared_default(r#"<root xmlns="http://example.com/d"><child xmlns=""/></root>"#)]
+#[case::redeclared_default(
+    r#"<root xmlns="http://example.com/d"><child xmlns="http://example.com/e"><leaf/></child></root>"#
+)]
+#[case::prefixed_and_default_same_namespace(
+    r#"<root xmlns="http://example.com/d" xmlns:d="http://example.com/d"><d:child/><child/></root>"#
+)]
+fn preserve_prefixes_round_trip(#[case] xml: &str) {
+    assert_eq!(round_trip(xml, true), xml);
+}
+
+#[test]
+fn prefixes_not_preserved_by_default() {
+    assert_eq!(
+        round_trip(r#"<x:root xmlns:x="http://example.com/x"/>"#, false),
+        r#"<a0:root xmlns:a0="http://example.com/x"/>"#
+    );
+}
+
+#[test]
+fn declarations_recorded() {
+    let xml = r#"<root xmlns="http://example.com/d" xmlns:x="http://example.com/x"/>"#;
+    let mut deserializer = Deserializer::from(xml.as_bytes()).with_preserve_prefixes(true);
+    let XmlValue::Element(element) = XmlValue::deserialize(&mut deserializer).unwrap() else {
+        panic!("expected an element");
+    };
+
+    assert_eq!(
+        element.declarations,
+        vec![
+            XmlnsDeclaration::new(
+                Prefix::BLANK.to_owned(),
+                XmlNamespaceBuf::new("http://example.com/d".to_string()).unwrap()
+            ),
+            XmlnsDeclaration::new(
+                PrefixBuf::new("x".to_string()).unwrap(),
+                XmlNamespaceBuf::new("http://example.com/x".to_string()).unwrap()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn declarations_not_recorded_by_default() {
+    let xml = r#"<root xmlns:x="http://example.com/x"><x:child/></root>"#;
+    assert_eq!(
+        round_trip(xml, false),
+        r#"<root><a0:child xmlns:a0="http://example.com/x"/></root>"#
+    );
+}
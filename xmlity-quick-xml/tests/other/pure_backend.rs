@@ -0,0 +1,201 @@
+use pretty_assertions::assert_eq;
+
+use xmlity::{
+    value::{InvalidContentError, XmlCData, XmlComment, XmlDecl, XmlProcessingInstruction},
+    Deserialize, Serialize, SerializeAttribute, XmlValue,
+};
+use xmlity_quick_xml::ReaderOptions;
+
+#[derive(Debug, PartialEq, SerializeAttribute, Deserialize)]
+#[xattribute(name = "lang", namespace = "http://www.w3.org/XML/1998/namespace")]
+pub struct Lang(String);
+
+#[derive(Debug, PartialEq, SerializeAttribute, Deserialize)]
+#[xattribute(name = "id", namespace = "http://example.com/ids")]
+pub struct Id(String);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "title", namespace = "http://example.com/books")]
+pub struct Title {
+    #[xattribute(deferred = true)]
+    pub lang: Option<Lang>,
+    pub text: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(
+    name = "author",
+    namespace = "http://example.com/people",
+    preferred_prefix = "p"
+)]
+pub struct Author(String);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "note")]
+pub struct Note(String);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "book", namespace = "http://example.com/books")]
+pub struct Book {
+    #[xattribute(deferred = true)]
+    pub id: Id,
+    pub title: Title,
+    pub authors: Vec<Author>,
+    pub note: Option<Note>,
+}
+
+fn book() -> Book {
+    Book {
+        id: Id("b&1".to_string()),
+        title: Title {
+            lang: Some(Lang("en".to_string())),
+            text: "Cats <& Dogs>".to_string(),
+        },
+        authors: vec![Author("Alice".to_string()), Author("Bob".to_string())],
+        note: Some(Note("Signed".to_string())),
+    }
+}
+
+#[test]
+fn pure_output_is_read_like_quick_xml_output() {
+    let pure = xmlity::pure::to_string(&book()).unwrap();
+    let quick = xmlity_quick_xml::to_string(&book()).unwrap();
+
+    // The backends choose prefixes differently, so the documents are compared by what they contain.
+    let pure: XmlValue = xmlity_quick_xml::from_str(&pure).unwrap();
+    let quick: XmlValue = xmlity_quick_xml::from_str(&quick).unwrap();
+
+    assert_eq!(pure, quick);
+}
+
+#[test]
+fn pure_serializes_with_default_namespace() {
+    let pure = xmlity::pure::to_string(&book()).unwrap();
+
+    assert_eq!(
+        pure,
+        concat!(
+            r#"<book xmlns="http://example.com/books" xmlns:a0="http://example.com/ids" a0:id="b&amp;1">"#,
+            r#"<title xml:lang="en">Cats &lt;&amp; Dogs&gt;</title>"#,
+            r#"<p:author xmlns:p="http://example.com/people">Alice</p:author>"#,
+            r#"<p:author xmlns:p="http://example.com/people">Bob</p:author>"#,
+            r#"<note xmlns="">Signed</note>"#,
+            r#"</book>"#,
+        )
+    );
+}
+
+#[test]
+fn pure_deserializes_like_quick_xml() {
+    let xml = xmlity_quick_xml::to_string(&book()).unwrap();
+
+    let pure: Book = xmlity::pure::from_str(&xml).unwrap();
+    let quick: Book = xmlity_quick_xml::from_str(&xml).unwrap();
+
+    assert_eq!(pure, quick);
+    assert_eq!(pure, book());
+}
+
+#[test]
+fn pure_deserializes_documents() {
+    let xml = r#"<b:book xmlns:b="http://example.com/books" xmlns:ids="http://example.com/ids" ids:id="b&amp;1">
+  <b:title xml:lang="en">Cats &lt;&amp; Dogs&gt;</b:title>
+  <author xmlns="http://example.com/people">Alice</author>
+  <p:author xmlns:p="http://example.com/people">Bob</p:author>
+  <note>Signed</note>
+</b:book>"#;
+
+    let pure: Book = xmlity::pure::from_str(xml).unwrap();
+    let quick: Book = xmlity_quick_xml::from_str(xml).unwrap();
+
+    assert_eq!(pure, quick);
+    assert_eq!(pure, book());
+
+    // The pure backend skips text that only consists of whitespace.
+    let options = ReaderOptions::new().with_trim_text(true);
+    let pure: XmlValue = xmlity::pure::from_str(xml).unwrap();
+    let quick: XmlValue = xmlity_quick_xml::from_str_with_options(xml, &options).unwrap();
+
+    assert_eq!(pure, quick);
+}
+
+#[test]
+fn pure_errors_convert_into_facade() {
+    let err = xmlity::pure::from_str::<Book>("<book").unwrap_err();
+    assert!(matches!(err, xmlity::pure::Error::Parse(_)));
+
+    let err: xmlity::Error = err.into();
+    assert!(err.is_deserialize());
+}
+
+#[test]
+fn pure_limits_nesting() {
+    let xml = format!("{}{}", "<a>".repeat(200_000), "</a>".repeat(200_000));
+
+    let err = xmlity::pure::from_str::<XmlValue>(&xml).unwrap_err();
+    assert!(matches!(
+        err,
+        xmlity::pure::Error::Parse(xmlity::value::XmlValueParseError::RecursionLimitExceeded {
+            limit: 128,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn pure_splits_cdata_like_quick_xml() {
+    let value = XmlValue::CData(XmlCData::new("a]]>b"));
+
+    let pure = xmlity::pure::to_string(&value).unwrap();
+    let quick = xmlity_quick_xml::to_string(&value).unwrap();
+
+    assert_eq!(pure, "<![CDATA[a]]]]><![CDATA[>b]]>");
+    assert_eq!(pure, quick);
+}
+
+#[test]
+fn pure_round_trips_split_cdata() {
+    let value = XmlValue::CData(XmlCData::new("a]]>b]]>"));
+
+    let xml = xmlity::pure::to_string(&value).unwrap();
+    assert_eq!(xml, "<![CDATA[a]]]]><![CDATA[>b]]]]><![CDATA[>]]>");
+
+    let pure: XmlValue = xmlity::pure::from_str(&xml).unwrap();
+    let quick: XmlValue = xmlity_quick_xml::from_str(&xml).unwrap();
+
+    assert_eq!(pure, value);
+    assert_eq!(pure, quick);
+}
+
+#[test]
+fn pure_rejects_unwritable_content() {
+    let err = xmlity::pure::to_string(&XmlValue::Comment(XmlComment::new("a -- b-"))).unwrap_err();
+    assert_eq!(
+        err,
+        xmlity::pure::Error::InvalidContent(InvalidContentError::DoubleHyphenInComment)
+    );
+
+    let err = xmlity::pure::to_string(&XmlValue::Comment(XmlComment::new("a-"))).unwrap_err();
+    assert_eq!(
+        err,
+        xmlity::pure::Error::InvalidContent(InvalidContentError::CommentEndsWithHyphen)
+    );
+
+    let pi = XmlValue::PI(XmlProcessingInstruction::new("target", "a?>b"));
+    let err = xmlity::pure::to_string(&pi).unwrap_err();
+    assert_eq!(
+        err,
+        xmlity::pure::Error::InvalidContent(InvalidContentError::PiTerminator)
+    );
+}
+
+#[test]
+fn pure_writes_utf8_encoding_labels_like_quick_xml() {
+    let value = XmlValue::Decl(XmlDecl::new("1.0", Some("ISO-8859-1"), None));
+
+    let pure = xmlity::pure::to_string(&value).unwrap();
+    let quick = xmlity_quick_xml::to_string(&value).unwrap();
+
+    assert_eq!(pure, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    assert_eq!(pure, quick);
+}
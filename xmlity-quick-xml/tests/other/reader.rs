@@ -0,0 +1,108 @@
+use pretty_assertions::assert_eq;
+use quick_xml::NsReader;
+use xmlity::{Deserialize, Serialize};
+use xmlity_quick_xml::{Deserializer, ReaderOptions};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "a")]
+pub struct A(String);
+
+#[test]
+fn from_reader_keeps_trim_text() {
+    let mut reader = NsReader::from_reader("<a>  Text  </a>".as_bytes());
+    reader.config_mut().trim_text(true);
+
+    let mut deserializer = Deserializer::from_reader(reader);
+    let actual = A::deserialize(&mut deserializer).unwrap();
+
+    assert_eq!(actual, A("Text".to_string()));
+}
+
+#[test]
+fn from_reader_without_trim_text() {
+    let reader = NsReader::from_reader("<a>  Text  </a>".as_bytes());
+
+    let mut deserializer = Deserializer::from_reader(reader);
+    let actual = A::deserialize(&mut deserializer).unwrap();
+
+    assert_eq!(actual, A("  Text  ".to_string()));
+}
+
+#[test]
+fn from_reader_trim_text_keeps_whitespace_around_references() {
+    let mut reader = NsReader::from_reader("<a>  Fish &amp; chips  </a>".as_bytes());
+    reader.config_mut().trim_text(true);
+
+    let mut deserializer = Deserializer::from_reader(reader);
+    let actual = A::deserialize(&mut deserializer).unwrap();
+
+    assert_eq!(actual, A("Fish & chips".to_string()));
+}
+
+#[test]
+fn reader_options_default() {
+    let actual: A =
+        xmlity_quick_xml::from_str_with_options("<a>  Text  </a>", &ReaderOptions::new()).unwrap();
+
+    assert_eq!(actual, A("  Text  ".to_string()));
+}
+
+#[test]
+fn reader_options_trim_text() {
+    let options = ReaderOptions::new().with_trim_text(true);
+
+    let actual: A =
+        xmlity_quick_xml::from_str_with_options("<a>  Fish &amp; chips  </a>", &options).unwrap();
+    assert_eq!(actual, A("Fish & chips".to_string()));
+
+    let actual: Vec<A> =
+        xmlity_quick_xml::from_str_with_options("\n  <a>First</a>\n  <a> Second </a>\n", &options)
+            .unwrap();
+    assert_eq!(
+        actual,
+        vec![A("First".to_string()), A("Second".to_string())]
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "b")]
+pub struct B {
+    #[xattribute(name = "id")]
+    pub id: String,
+    pub a: Option<A>,
+}
+
+#[test]
+fn reader_options_expand_empty_elements() {
+    let options = ReaderOptions::new().with_expand_empty_elements(true);
+
+    let actual: Vec<B> = xmlity_quick_xml::from_str_with_options(
+        r#"<b id="1"/><b id="2"><a>Text</a></b>"#,
+        &options,
+    )
+    .unwrap();
+
+    assert_eq!(
+        actual,
+        vec![
+            B {
+                id: "1".to_string(),
+                a: None,
+            },
+            B {
+                id: "2".to_string(),
+                a: Some(A("Text".to_string())),
+            },
+        ]
+    );
+}
+
+#[test]
+fn reader_options_check_end_names() {
+    xmlity_quick_xml::from_str_with_options::<A>("<a>Text</b>", &ReaderOptions::new()).unwrap_err();
+
+    let options = ReaderOptions::new().with_check_end_names(false);
+    let actual: A = xmlity_quick_xml::from_str_with_options("<a>Text</b>", &options).unwrap();
+
+    assert_eq!(actual, A("Text".to_string()));
+}
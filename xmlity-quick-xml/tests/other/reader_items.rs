@@ -0,0 +1,206 @@
+use std::io::Read;
+
+use pretty_assertions::assert_eq;
+
+use xmlity::Deserialize;
+use xmlity_quick_xml::{from_reader, from_reader_items};
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "record")]
+struct Record {
+    #[xattribute(name = "id")]
+    id: u32,
+    name: Name,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "name")]
+struct Name(String);
+
+fn record(id: u32, name: &str) -> Record {
+    Record {
+        id,
+        name: Name(name.to_string()),
+    }
+}
+
+#[test]
+fn read_document() {
+    let input = r#"<record id="1"><name>A &amp; B</name></record>
+"#;
+
+    let value: Record = from_reader(input.as_bytes()).unwrap();
+
+    assert_eq!(value, record(1, "A & B"));
+}
+
+#[test]
+fn read_document_stops_after_root_element() {
+    let input = r#"<record id="1"><name>A</name></record><record id="2"><name>B</name></record>"#;
+
+    let value: Record = from_reader(input.as_bytes()).unwrap();
+
+    assert_eq!(value, record(1, "A"));
+}
+
+#[test]
+fn read_truncated_document() {
+    let input = r#"<record id="1"><name>A</name>"#;
+
+    let error = from_reader::<Record, _>(input.as_bytes()).unwrap_err();
+
+    assert!(matches!(
+        error,
+        xmlity_quick_xml::de::Error::StartElementWithoutEnd { .. }
+    ));
+}
+
+#[test]
+fn children_of_root() {
+    let input = r#"<?xml version="1.0"?>
+<!-- export -->
+<export>
+  <record id="1"><name>A &amp; B</name></record>
+  <!-- between -->
+  <record id="2"><name>C</name></record>
+</export>
+"#;
+
+    let records = from_reader_items::<Record, _>(input.as_bytes())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(records, vec![record(1, "A & B"), record(2, "C")]);
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "item", namespace = "http://example.com/items")]
+struct Item(#[xattribute(name = "value")] String);
+
+#[test]
+fn root_namespaces_stay_in_scope() {
+    let input = r#"<i:items xmlns:i="http://example.com/items"><i:item value="a"/><item xmlns="http://example.com/items" value="b"></item></i:items>"#;
+
+    let items = from_reader_items::<Item, _>(input.as_bytes())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(items, vec![Item("a".to_string()), Item("b".to_string())]);
+}
+
+#[test]
+fn invalid_items_do_not_end_iteration() {
+    let input = r#"<export><record id="one"><name>A</name></record><record id="2"><name>B</name></record></export>"#;
+
+    let results = from_reader_items::<Record, _>(input.as_bytes()).collect::<Vec<_>>();
+
+    assert_eq!(results.len(), 2);
+    assert!(results[0].is_err());
+    assert_eq!(results[1].as_ref().unwrap(), &record(2, "B"));
+}
+
+#[test]
+fn truncated_document() {
+    let input = r#"<export><record id="1"><name>A</name></record><record id="2"><name>B"#;
+
+    let results = from_reader_items::<Record, _>(input.as_bytes()).collect::<Vec<_>>();
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].as_ref().unwrap(), &record(1, "A"));
+    assert!(matches!(
+        results[1],
+        Err(xmlity_quick_xml::de::Error::StartElementWithoutEnd { ref name }) if name == "record"
+    ));
+}
+
+#[test]
+fn doctype_entities() {
+    let input = r#"<!DOCTYPE export [
+  <!ENTITY co "ACME">
+  <!ENTITY full "&co; Inc.">
+]>
+<export>
+  <record id="1"><name>&co;</name></record>
+  <record id="2"><name>&full;</name></record>
+</export>
+"#;
+
+    let records = from_reader_items::<Record, _>(input.as_bytes())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(records, vec![record(1, "ACME"), record(2, "ACME Inc.")]);
+}
+
+#[test]
+fn entity_expansion_limit_applies_to_document() {
+    // Each record stays far below the limit, but all of them together exceed it.
+    let replacement_text = "a".repeat(64 * 1024);
+    let records = (0..=xmlity_quick_xml::Deserializer::DEFAULT_ENTITY_EXPANSION_LIMIT
+        / (64 * 1024))
+        .map(|id| format!(r#"<record id="{id}"><name>&e;</name></record>"#))
+        .collect::<String>();
+    let input = format!(
+        r#"<!DOCTYPE export [<!ENTITY e "{replacement_text}">]><export>{records}</export>"#
+    );
+
+    let results = from_reader_items::<Record, _>(input.as_bytes()).collect::<Vec<_>>();
+
+    assert!(results[0].is_ok());
+    assert!(matches!(
+        results.last(),
+        Some(Err(
+            xmlity_quick_xml::de::Error::EntityExpansionLimitExceeded { .. }
+        ))
+    ));
+}
+
+#[test]
+fn empty_root() {
+    assert_eq!(
+        from_reader_items::<Record, _>("<export/>".as_bytes()).count(),
+        0
+    );
+    assert_eq!(from_reader_items::<Record, _>("".as_bytes()).count(), 0);
+}
+
+/// A reader that produces an export with an endless number of records.
+struct EndlessExport {
+    pending: Vec<u8>,
+    next_id: u32,
+}
+
+impl Read for EndlessExport {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.pending.is_empty() {
+            self.pending = format!(
+                r#"<record id="{0}"><name>{0}</name></record>"#,
+                self.next_id
+            )
+            .into_bytes();
+            self.next_id += 1;
+        }
+        let len = buf.len().min(self.pending.len());
+        buf[..len].copy_from_slice(&self.pending[..len]);
+        self.pending.drain(..len);
+        Ok(len)
+    }
+}
+
+#[test]
+fn reads_incrementally() {
+    let reader = EndlessExport {
+        pending: b"<export>".to_vec(),
+        next_id: 1,
+    };
+
+    let records = from_reader_items::<Record, _>(reader)
+        .take(3)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(
+        records,
+        vec![record(1, "1"), record(2, "2"), record(3, "3")]
+    );
+}
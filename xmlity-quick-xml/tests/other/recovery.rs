@@ -0,0 +1,150 @@
+use pretty_assertions::assert_eq;
+
+use xmlity::{
+    value::{XmlChild, XmlElement, XmlError, XmlText},
+    Deserialize, ExpandedName, LocalName, XmlValue,
+};
+use xmlity_quick_xml::from_str_lenient;
+
+fn element(name: &'static str) -> XmlElement {
+    XmlElement::new(ExpandedName::new(LocalName::new(name).unwrap(), None))
+}
+
+fn errors(value: &XmlValue) -> Vec<XmlError> {
+    fn from_children(element: &XmlElement, errors: &mut Vec<XmlError>) {
+        for child in element.children.clone() {
+            match child {
+                XmlChild::Element(element) => from_children(&element, errors),
+                XmlChild::Error(error) => errors.push(error),
+                _ => {}
+            }
+        }
+    }
+
+    let mut errors = Vec::new();
+    let values = match value {
+        XmlValue::Seq(values) => values.iter().cloned().collect(),
+        value => vec![value.clone()],
+    };
+    for value in values {
+        match value {
+            XmlValue::Element(element) => from_children(&element, &mut errors),
+            XmlValue::Error(error) => errors.push(error),
+            _ => {}
+        }
+    }
+    errors
+}
+
+#[test]
+fn well_formed_document() {
+    let xml = r#"<?xml version="1.0"?><!-- note --><list xmlns="http://example.com" kind="a &amp; b"><item>x &lt; y</item><![CDATA[raw]]><empty/></list>"#;
+
+    let strict =
+        XmlValue::deserialize_seq(&mut xmlity_quick_xml::Deserializer::from(xml.as_bytes()))
+            .unwrap();
+
+    assert_eq!(from_str_lenient(xml), strict);
+}
+
+#[test]
+fn unclosed_element() {
+    let xml = "<list><item>a</item><item>b</list>";
+
+    let value = from_str_lenient(xml);
+
+    assert_eq!(
+        value,
+        XmlValue::Element(
+            element("list")
+                .with_child(element("item").with_child(XmlText::new("a")))
+                .with_child(
+                    element("item")
+                        .with_child(XmlText::new("b"))
+                        .with_child(XmlError::new(20..27, "element `item` is not closed"))
+                )
+        )
+    );
+}
+
+#[test]
+fn unclosed_at_end_of_document() {
+    let xml = "<list><item>a</item>";
+
+    assert_eq!(
+        errors(&from_str_lenient(xml)),
+        vec![XmlError::new(0..xml.len(), "element `list` is not closed")]
+    );
+}
+
+#[test]
+fn unmatched_end_tag() {
+    let xml = "<list><item>a</item></other><item>b</item></list>";
+
+    let value = from_str_lenient(xml);
+
+    assert_eq!(
+        value,
+        XmlValue::Element(
+            element("list")
+                .with_child(element("item").with_child(XmlText::new("a")))
+                .with_child(XmlError::new(
+                    20..28,
+                    "end tag `other` does not match any open element"
+                ))
+                .with_child(element("item").with_child(XmlText::new("b")))
+        )
+    );
+}
+
+#[test]
+fn unknown_entity() {
+    let xml = "<p>a &nbsp; b</p>";
+
+    assert_eq!(
+        from_str_lenient(xml),
+        XmlValue::Element(
+            element("p")
+                .with_child(XmlText::new("a "))
+                .with_child(XmlError::new(5..11, "unknown entity `&nbsp;`"))
+                .with_child(XmlText::new(" b"))
+        )
+    );
+}
+
+#[test]
+fn malformed_attribute() {
+    let xml = "<list><item id=1/><item/></list>";
+
+    let value = from_str_lenient(xml);
+    let errors = errors(&value);
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].span(), 6..18);
+    let XmlValue::Element(list) = value else {
+        panic!("expected an element, got {value:?}");
+    };
+    assert_eq!(
+        list.children.into_iter().nth(1),
+        Some(element("item").into())
+    );
+}
+
+#[test]
+fn truncated_document() {
+    let xml = "<list><item";
+
+    let value = from_str_lenient(xml);
+    let errors = errors(&value);
+
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[1].message, "element `list` is not closed");
+}
+
+#[test]
+fn error_nodes_cannot_be_deserialized() {
+    let value = XmlValue::Error(XmlError::new(0..1, "broken"));
+
+    assert!(xmlity::value::from_value::<String>(&value).is_err());
+    assert!(xmlity_quick_xml::to_string(&value).is_err());
+}
@@ -0,0 +1,82 @@
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use xmlity::{value::XmlValue, Deserialize, Serialize};
+use xmlity_quick_xml::{de::Error, Deserializer};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "node")]
+struct Node {
+    children: Vec<Node>,
+}
+
+fn nested(depth: usize) -> String {
+    "<node>".repeat(depth) + &"</node>".repeat(depth)
+}
+
+fn nested_node(depth: usize) -> Node {
+    (1..depth).fold(Node { children: vec![] }, |node, _| Node {
+        children: vec![node],
+    })
+}
+
+#[rstest]
+#[case::shallow(1)]
+#[case::at_limit(3)]
+fn within_recursion_limit(#[case] depth: usize) {
+    let xml = nested(depth);
+    let mut deserializer = Deserializer::from(xml.as_bytes()).with_recursion_limit(Some(3));
+
+    assert_eq!(
+        Node::deserialize(&mut deserializer).unwrap(),
+        nested_node(depth)
+    );
+}
+
+#[test]
+fn exceeding_recursion_limit() {
+    let xml = nested(4);
+    let mut deserializer = Deserializer::from(xml.as_bytes()).with_recursion_limit(Some(3));
+
+    let err = Node::deserialize(&mut deserializer).unwrap_err();
+
+    assert!(matches!(err, Error::RecursionLimitExceeded { limit: 3 }));
+}
+
+#[test]
+fn deeply_nested_document_fails_without_overflowing() {
+    let xml = nested(100_000);
+
+    let err = xmlity_quick_xml::from_str::<Node>(&xml).unwrap_err();
+
+    assert!(matches!(
+        err,
+        Error::RecursionLimitExceeded {
+            limit: Deserializer::DEFAULT_RECURSION_LIMIT
+        }
+    ));
+}
+
+#[test]
+fn deeply_nested_xml_value_fails_without_overflowing() {
+    let xml = nested(100_000);
+
+    let err = xmlity_quick_xml::from_str::<XmlValue>(&xml).unwrap_err();
+
+    assert!(matches!(err, Error::RecursionLimitExceeded { .. }));
+}
+
+#[test]
+fn disabled_recursion_limit() {
+    let deserialized = std::thread::Builder::new()
+        .stack_size(256 * 1024 * 1024)
+        .spawn(|| {
+            let xml = nested(200);
+            let mut deserializer = Deserializer::from(xml.as_bytes()).with_recursion_limit(None);
+            Node::deserialize(&mut deserializer).unwrap()
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+
+    assert_eq!(deserialized, nested_node(200));
+}
@@ -0,0 +1,96 @@
+use pretty_assertions::assert_eq;
+
+use crate::utils::{quick_xml_deserialize_test, quick_xml_serialize_test};
+
+use xmlity::{
+    ser::{SerializeAttributes, SerializeElement},
+    types::utils::Attribute,
+    Deserialize, ExpandedName, ExpandedNameBuf, LocalName, Serialize, Serializer,
+};
+
+/// A hand-built element with attributes that have no types of their own.
+#[derive(Debug, PartialEq)]
+struct Image {
+    width: u32,
+    height: u32,
+}
+
+impl Serialize for Image {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let name = ExpandedName::new(LocalName::new("img").unwrap(), None);
+        let mut attributes = serializer
+            .serialize_element(&name)?
+            .serialize_attributes()?;
+        attributes.serialize_attribute(&Attribute::new(
+            "width".parse::<ExpandedNameBuf>().unwrap(),
+            self.width,
+        ))?;
+        attributes.serialize_attribute(&Attribute::new(
+            "height".parse::<ExpandedNameBuf>().unwrap(),
+            self.height,
+        ))?;
+        xmlity::ser::SerializeElementAttributes::end(attributes)
+    }
+}
+
+#[test]
+fn serialize_runtime_named_attributes() {
+    let actual = quick_xml_serialize_test(Image {
+        width: 640,
+        height: 480,
+    })
+    .unwrap();
+
+    assert_eq!(actual, r#"<img width="640" height="480"/>"#);
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "img")]
+struct AnyImage {
+    #[xattribute(deferred = true)]
+    first: Attribute<u32>,
+    #[xattribute(deferred = true)]
+    second: Attribute<String>,
+}
+
+#[test]
+fn deserialize_runtime_named_attributes() {
+    let actual: AnyImage =
+        quick_xml_deserialize_test(r#"<img width="640" alt="A tree"/>"#).unwrap();
+
+    assert_eq!(
+        actual,
+        AnyImage {
+            first: Attribute::new("width".parse::<ExpandedNameBuf>().unwrap(), 640),
+            second: Attribute::new(
+                "alt".parse::<ExpandedNameBuf>().unwrap(),
+                "A tree".to_string()
+            ),
+        }
+    );
+}
+
+#[test]
+fn namespaced_runtime_attribute_roundtrip() {
+    let value = AnyImage {
+        first: Attribute::new(
+            "{http://example.com/sizes}width"
+                .parse::<ExpandedNameBuf>()
+                .unwrap(),
+            640,
+        ),
+        second: Attribute::new(
+            "alt".parse::<ExpandedNameBuf>().unwrap(),
+            "A tree".to_string(),
+        ),
+    };
+
+    let xml = quick_xml_serialize_test(&value).unwrap();
+    assert_eq!(
+        xml,
+        r#"<img xmlns:a0="http://example.com/sizes" a0:width="640" alt="A tree"/>"#
+    );
+
+    let actual: AnyImage = quick_xml_deserialize_test(&xml).unwrap();
+    assert_eq!(actual, value);
+}
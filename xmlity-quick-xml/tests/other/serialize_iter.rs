@@ -0,0 +1,39 @@
+use pretty_assertions::assert_eq;
+use xmlity::{types::iterator::SerializeIter, Serialize};
+
+use crate::utils::quick_xml_serialize_test;
+
+#[derive(Debug, PartialEq, Serialize)]
+#[xelement(name = "row")]
+pub struct Row(u32);
+
+#[test]
+fn serialize_iter_map() {
+    let actual = quick_xml_serialize_test(SerializeIter((1..=3).map(Row))).unwrap();
+
+    assert_eq!(actual, "<row>1</row><row>2</row><row>3</row>");
+}
+
+#[test]
+fn serialize_iter_empty() {
+    let actual = quick_xml_serialize_test(SerializeIter(std::iter::empty::<Row>())).unwrap();
+
+    assert_eq!(actual, "");
+}
+
+#[derive(Debug, Serialize)]
+#[xelement(name = "table")]
+pub struct Table<I: IntoIterator<Item = Row> + Clone + std::fmt::Debug> {
+    pub rows: SerializeIter<I>,
+}
+
+#[test]
+fn serialize_iter_in_element() {
+    let rows = [3, 4];
+    let actual = quick_xml_serialize_test(Table {
+        rows: SerializeIter(rows.into_iter().map(Row)),
+    })
+    .unwrap();
+
+    assert_eq!(actual, "<table><row>3</row><row>4</row></table>");
+}
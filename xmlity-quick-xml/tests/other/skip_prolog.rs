@@ -0,0 +1,70 @@
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use xmlity::{value::XmlValue, Deserialize};
+use xmlity_quick_xml::Deserializer;
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "message")]
+struct Message {
+    #[xattribute(name = "to")]
+    to: String,
+    body: Body,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "body")]
+struct Body(String);
+
+fn deserialize<'de, T: Deserialize<'de>>(
+    xml: &'de str,
+    skip_prolog: bool,
+) -> Result<T, xmlity_quick_xml::de::Error> {
+    let mut deserializer = Deserializer::from(xml.as_bytes()).with_skip_prolog(skip_prolog);
+    T::deserialize(&mut deserializer)
+}
+
+fn message() -> Message {
+    Message {
+        to: "a@example.com".to_string(),
+        body: Body("Hi".to_string()),
+    }
+}
+
+#[rstest]
+#[case::declaration(r#"<?xml version="1.0" encoding="UTF-8"?><message to="a@example.com"><body>Hi</body></message>"#)]
+#[case::doctype(r#"<!DOCTYPE message><message to="a@example.com"><body>Hi</body></message>"#)]
+#[case::whitespace("\n  \t<message to=\"a@example.com\"><body>Hi</body></message>")]
+#[case::comments(
+    "<!-- first --><!-- second -->\n<message to=\"a@example.com\"><body>Hi</body></message>"
+)]
+#[case::processing_instructions(
+    r#"<?xml-stylesheet href="a.xsl"?><message to="a@example.com"><body>Hi</body></message>"#
+)]
+#[case::everything("<?xml version=\"1.0\"?>\n<!DOCTYPE message>\n<!-- stream -->\n<message to=\"a@example.com\"><body>Hi</body></message>")]
+fn prolog_is_skipped(#[case] xml: &str) {
+    assert_eq!(deserialize::<Message>(xml, true).unwrap(), message());
+}
+
+#[test]
+fn prolog_is_not_skipped_by_default() {
+    let xml = r#"<?xml version="1.0"?><message to="a@example.com"><body>Hi</body></message>"#;
+
+    assert!(deserialize::<Message>(xml, false).is_err());
+}
+
+#[test]
+fn only_skipped_before_elements() {
+    let value = deserialize::<XmlValue>("<!-- note -->", true).unwrap();
+
+    assert!(matches!(value, XmlValue::Comment(_)));
+}
+
+#[test]
+fn prolog_before_each_top_level_element() {
+    let xml = "<?xml version=\"1.0\"?>\n<body>a</body>\n<!-- next -->\n<body>b</body>";
+
+    assert_eq!(
+        deserialize::<Vec<Body>>(xml, true).unwrap(),
+        vec![Body("a".to_string()), Body("b".to_string())]
+    );
+}
@@ -0,0 +1,58 @@
+use pretty_assertions::assert_eq;
+use xmlity::{
+    ser::{SerializeElement, SerializeSeq},
+    ExpandedName, LocalName, Prefix, Serialize, Serializer, XmlNamespace,
+};
+
+#[derive(Debug, PartialEq, Serialize)]
+#[xelement(name = "item", namespace = "http://example.com/a")]
+pub struct Item {
+    #[xattribute(name = "id")]
+    id: u32,
+    #[xelement(name = "note", namespace = "http://example.com/b")]
+    note: String,
+}
+
+#[test]
+fn subtree_uses_prefixes_of_document() {
+    let item = Item {
+        id: 1,
+        note: "hi".to_string(),
+    };
+
+    let prefix = Prefix::new("a").unwrap();
+    let namespace = XmlNamespace::new("http://example.com/a").unwrap();
+    let name = ExpandedName::new(LocalName::new("list").unwrap(), Some(namespace));
+
+    let mut serializer = xmlity_quick_xml::Serializer::from(Vec::new());
+    let mut element = (&mut serializer).serialize_element(&name).unwrap();
+    element.declare_namespace(prefix, namespace).unwrap();
+    element.preferred_prefix(Some(prefix)).unwrap();
+    let mut children = element.serialize_children().unwrap();
+
+    let subtree = children.subtree_to_string(&item).unwrap();
+    children.serialize_element(&item).unwrap();
+    children.end().unwrap();
+
+    let document = String::from_utf8(serializer.into_inner()).unwrap();
+
+    assert_eq!(
+        subtree,
+        r#"<a:item id="1"><a0:note xmlns:a0="http://example.com/b">hi</a0:note></a:item>"#
+    );
+    assert_eq!(
+        document,
+        format!(r#"<a:list xmlns:a="http://example.com/a">{subtree}</a:list>"#)
+    );
+}
+
+#[test]
+fn sub_serializer_does_not_affect_document() {
+    let serializer = xmlity_quick_xml::Serializer::from(Vec::new());
+
+    let mut sub_serializer = serializer.sub_serializer();
+    "text".serialize(&mut sub_serializer).unwrap();
+
+    assert_eq!(sub_serializer.into_inner(), b"text");
+    assert!(serializer.into_inner().is_empty());
+}
@@ -0,0 +1,67 @@
+use crate::define_test;
+
+use xmlity::{Deserialize, DeserializeOwned, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xvalue(transparent)]
+pub struct UserId(u64);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "user")]
+pub struct User {
+    #[xattribute(name = "id")]
+    id: UserId,
+    #[xelement(name = "manager", optional)]
+    manager: Option<UserId>,
+}
+
+define_test!(
+    transparent_tuple_struct,
+    [
+        (
+            User {
+                id: UserId(1),
+                manager: Some(UserId(2)),
+            },
+            r#"<user id="1"><manager>2</manager></user>"#
+        ),
+        (
+            User {
+                id: UserId(1),
+                manager: None,
+            },
+            r#"<user id="1"/>"#
+        )
+    ]
+);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xvalue(transparent)]
+pub struct Checked {
+    user: User,
+}
+
+define_test!(
+    transparent_named_struct,
+    [(
+        Checked {
+            user: User {
+                id: UserId(3),
+                manager: None,
+            },
+        },
+        r#"<user id="3"/>"#
+    )]
+);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xvalue(transparent)]
+pub struct Wrapper<T: Serialize + DeserializeOwned>(T);
+
+define_test!(
+    transparent_generic_struct,
+    [
+        (Wrapper(UserId(4)), "4"),
+        (Wrapper("text".to_string()), "text")
+    ]
+);
@@ -0,0 +1,59 @@
+use pretty_assertions::assert_eq;
+use xmlity::{de, ser, ExpandedNameBuf, Serialize};
+
+#[test]
+fn unexpected_element_has_name() {
+    let err = xmlity_quick_xml::from_str::<String>(r#"<a:b xmlns:a="http://example.com/a"/>"#)
+        .unwrap_err();
+
+    let xmlity_quick_xml::de::Error::Unexpected(unexpected) = err else {
+        panic!("unexpected error: {err:?}");
+    };
+    assert!(matches!(unexpected, de::Unexpected::Element { .. }));
+    assert_eq!(
+        unexpected.name(),
+        Some(
+            &"{http://example.com/a}b"
+                .parse::<ExpandedNameBuf>()
+                .unwrap()
+        )
+    );
+    assert_eq!(unexpected.to_string(), "element {http://example.com/a}b");
+}
+
+#[test]
+fn unexpected_pi_has_target() {
+    let err = xmlity_quick_xml::from_str::<String>("<?page 3?>").unwrap_err();
+
+    let xmlity_quick_xml::de::Error::Unexpected(unexpected) = err else {
+        panic!("unexpected error: {err:?}");
+    };
+    assert_eq!(unexpected.target(), Some("page"));
+    assert_eq!(unexpected.name(), None);
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[xelement(name = "inner")]
+pub struct Inner;
+
+#[derive(Debug, PartialEq, Serialize)]
+#[xelement(name = "outer")]
+pub struct Outer {
+    #[xattribute(name = "value")]
+    value: Inner,
+}
+
+#[test]
+fn unexpected_element_in_attribute_has_name() {
+    let err = xmlity_quick_xml::to_string(&Outer { value: Inner }).unwrap_err();
+
+    let xmlity_quick_xml::ser::Error::Unexpected(unexpected) = err else {
+        panic!("unexpected error: {err:?}");
+    };
+    assert_eq!(
+        unexpected,
+        ser::Unexpected::Element {
+            name: Box::new("inner".parse().unwrap())
+        }
+    );
+}
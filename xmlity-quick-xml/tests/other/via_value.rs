@@ -0,0 +1,86 @@
+use std::str::FromStr;
+
+use pretty_assertions::assert_eq;
+
+use xmlity::{value::XmlValuePathSegment, Deserialize, ExpandedNameBuf, LocalNameBuf};
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "title")]
+struct Title(String);
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "book")]
+struct Book {
+    #[xattribute(name = "id")]
+    id: u32,
+    title: Title,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "catalog")]
+struct Catalog {
+    first: Book,
+    second: Book,
+}
+
+fn value_error(xml: &str) -> xmlity::value::XmlValueDeserializerError {
+    match xmlity_quick_xml::from_str_via_value::<Catalog>(xml).unwrap_err() {
+        xmlity_quick_xml::de::Error::Value(err) => err,
+        err => panic!("unexpected error: {err}"),
+    }
+}
+
+#[test]
+fn deserializes_like_from_str() {
+    let xml = r#"<catalog><book id="1"><title>A</title></book><book id="2"><title>B</title></book></catalog>"#;
+
+    let catalog = xmlity_quick_xml::from_str_via_value::<Catalog>(xml).unwrap();
+
+    assert_eq!(catalog, xmlity_quick_xml::from_str::<Catalog>(xml).unwrap());
+    assert_eq!(catalog.second.id, 2);
+}
+
+#[test]
+fn invalid_attribute_is_root_cause() {
+    let err = value_error(
+        r#"<catalog><book id="1"><title>A</title></book><book id="two"><title>B</title></book></catalog>"#,
+    );
+
+    assert_eq!(err.path().unwrap().to_string(), "/catalog");
+    assert_eq!(
+        err.cause().unwrap().path().unwrap().to_string(),
+        "/catalog/book[2]"
+    );
+    assert_eq!(
+        err.root_cause().path().unwrap().segments().last(),
+        Some(&XmlValuePathSegment::Attribute {
+            name: ExpandedNameBuf::new(LocalNameBuf::from_str("id").unwrap(), None),
+        })
+    );
+    assert_eq!(
+        err.root_cause().path().unwrap().to_string(),
+        "/catalog/book[2]/@id"
+    );
+}
+
+#[test]
+fn invalid_child_is_cause() {
+    let err =
+        value_error(r#"<catalog><book id="1"><title>A</title></book><book id="2"/></catalog>"#);
+
+    assert_eq!(
+        err.to_string(),
+        r#"Missing field: "second" at /catalog, after Missing field: "title" at /catalog/book[2]"#
+    );
+}
+
+#[test]
+fn error_in_root_has_path() {
+    let err = match xmlity_quick_xml::from_str_via_value::<Book>(r#"<book id="1"/>"#).unwrap_err() {
+        xmlity_quick_xml::de::Error::Value(err) => err,
+        err => panic!("unexpected error: {err}"),
+    };
+
+    assert_eq!(err.to_string(), r#"Missing field: "title" at /book"#);
+    assert!(err.cause().is_none());
+}
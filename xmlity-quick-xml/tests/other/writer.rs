@@ -0,0 +1,77 @@
+use std::io::Write;
+
+use pretty_assertions::assert_eq;
+use xmlity::Serialize;
+use xmlity_quick_xml::{ser::Error, to_string, to_writer, to_writer_with_config, SerializerConfig};
+
+#[derive(Debug, Serialize)]
+#[xelement(name = "item")]
+struct Item(String);
+
+#[derive(Debug, Serialize)]
+#[xelement(name = "list")]
+struct List {
+    items: Vec<Item>,
+}
+
+fn list(count: usize) -> List {
+    List {
+        items: (0..count).map(|i| Item(i.to_string())).collect(),
+    }
+}
+
+#[test]
+fn same_output_as_to_string() {
+    let mut output = Vec::new();
+    to_writer(&mut output, &list(3)).unwrap();
+
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        to_string(&list(3)).unwrap()
+    );
+}
+
+#[test]
+fn with_config() {
+    let config = SerializerConfig::new().with_indentation(Some(2));
+
+    let mut output = Vec::new();
+    to_writer_with_config(&mut output, &list(2), &config).unwrap();
+
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "<list>\n  <item>0</item>\n  <item>1</item>\n</list>"
+    );
+}
+
+/// A writer that only accepts a fixed number of bytes, like a full disk.
+struct FullWriter {
+    remaining: usize,
+}
+
+impl Write for FullWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if buf.len() > self.remaining {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::WriteZero,
+                "no space left",
+            ));
+        }
+        self.remaining -= buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn writer_errors_are_returned() {
+    let err = to_writer(FullWriter { remaining: 10 }, &list(2)).unwrap_err();
+
+    let Error::Io(err) = err else {
+        panic!("expected an IO error, got {err:?}");
+    };
+    assert_eq!(err.kind(), std::io::ErrorKind::WriteZero);
+}
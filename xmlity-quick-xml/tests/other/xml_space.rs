@@ -0,0 +1,107 @@
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use xmlity::Deserialize;
+use xmlity_quick_xml::de::ReaderOptions;
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "b")]
+struct Bold(String);
+
+#[derive(Debug, PartialEq, Deserialize)]
+enum Inline {
+    Bold(Bold),
+    Text(String),
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "p")]
+struct Paragraph(Vec<Inline>);
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "a")]
+struct A;
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "wrapper", ignore_whitespace = "any")]
+struct Wrapper(A);
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "doc")]
+struct Doc(Wrapper);
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "list")]
+struct List(Vec<A>);
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "pre")]
+struct Pre(String);
+
+fn bold(text: &str) -> Inline {
+    Inline::Bold(Bold(text.to_string()))
+}
+
+#[test]
+fn whitespace_is_skipped_by_default() {
+    let xml = "<p><b>a</b> <b>b</b></p>";
+
+    assert_eq!(
+        xmlity_quick_xml::from_str::<Paragraph>(xml).unwrap(),
+        Paragraph(vec![bold("a"), bold("b")])
+    );
+}
+
+#[test]
+fn whitespace_is_kept_in_collections() {
+    let xml = r#"<p xml:space="preserve"><b>a</b> <b>b</b></p>"#;
+
+    assert_eq!(
+        xmlity_quick_xml::from_str::<Paragraph>(xml).unwrap(),
+        Paragraph(vec![bold("a"), Inline::Text(" ".to_string()), bold("b")])
+    );
+}
+
+#[rstest]
+#[case::ignored(r#"<doc><wrapper> <a/> </wrapper></doc>"#)]
+#[case::reset_to_default(
+    r#"<doc xml:space="preserve"><wrapper xml:space="default"> <a/> </wrapper></doc>"#
+)]
+#[case::unknown_value(r#"<doc><wrapper xml:space="other"> <a/> </wrapper></doc>"#)]
+fn ignore_whitespace_applies(#[case] xml: &str) {
+    assert_eq!(
+        xmlity_quick_xml::from_str::<Doc>(xml).unwrap(),
+        Doc(Wrapper(A))
+    );
+}
+
+#[rstest]
+#[case::on_element(r#"<doc><wrapper xml:space="preserve"> <a/> </wrapper></doc>"#)]
+#[case::inherited(r#"<doc xml:space="preserve"><wrapper> <a/> </wrapper></doc>"#)]
+fn ignore_whitespace_is_overridden(#[case] xml: &str) {
+    assert!(xmlity_quick_xml::from_str::<Doc>(xml).is_err());
+}
+
+#[test]
+fn empty_element_does_not_enclose_following_text() {
+    let xml = r#"<list><a xml:space="preserve"/> <a/></list>"#;
+
+    assert_eq!(
+        xmlity_quick_xml::from_str::<List>(xml).unwrap(),
+        List(vec![A, A])
+    );
+}
+
+#[rstest]
+#[case::trimmed("<pre>  fn main() {}\n</pre>", "fn main() {}")]
+#[case::preserved(
+    "<pre xml:space=\"preserve\">  fn main() {}\n</pre>",
+    "  fn main() {}\n"
+)]
+fn trimmed_text_is_preserved(#[case] xml: &str, #[case] expected: &str) {
+    let options = ReaderOptions::new().with_trim_text(true);
+
+    assert_eq!(
+        xmlity_quick_xml::de::from_str_with_options::<Pre>(xml, &options).unwrap(),
+        Pre(expected.to_string())
+    );
+}
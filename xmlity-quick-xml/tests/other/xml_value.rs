@@ -1,4 +1,7 @@
-use crate::{define_test, utils::clean_string};
+use crate::{
+    define_test,
+    utils::{clean_string, quick_xml_deserialize_test, quick_xml_serialize_test},
+};
 
 use xmlity::{
     value::{XmlAttribute, XmlChild, XmlDecl, XmlElement, XmlText, XmlValue},
@@ -85,3 +88,37 @@ define_test!(
         r#"<?xml version="1.0" encoding="UTF-8"?>"#
     )]
 );
+
+#[test]
+fn decl_with_other_encoding_is_serialized_as_utf8() {
+    let decl = XmlDecl::new("1.0", Some("ISO-8859-1"), Some("yes"));
+
+    let actual = quick_xml_serialize_test(decl).unwrap();
+
+    pretty_assertions::assert_eq!(
+        actual,
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#
+    );
+}
+
+#[test]
+fn decl_encoding_round_trip_is_rewritten_to_utf8() {
+    let input = r#"<?xml version="1.0" encoding="ISO-8859-1"?><to>Tove</to>"#;
+
+    let value: XmlValue = quick_xml_deserialize_test(input).unwrap();
+    let actual = quick_xml_serialize_test(value).unwrap();
+
+    pretty_assertions::assert_eq!(
+        actual,
+        r#"<?xml version="1.0" encoding="UTF-8"?><to>Tove</to>"#
+    );
+}
+
+#[test]
+fn decl_utf8_label_is_kept() {
+    let decl = XmlDecl::new("1.0", Some("utf-8"), None);
+
+    let actual = quick_xml_serialize_test(decl).unwrap();
+
+    pretty_assertions::assert_eq!(actual, r#"<?xml version="1.0" encoding="utf-8"?>"#);
+}
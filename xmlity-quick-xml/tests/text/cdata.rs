@@ -0,0 +1,59 @@
+use std::str::FromStr;
+
+use crate::define_test;
+
+use pretty_assertions::assert_eq;
+use xmlity::types::utils::CData;
+use xmlity::value::{XmlCData, XmlElement, XmlValue};
+use xmlity::{Deserialize, ExpandedNameBuf, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "script")]
+pub struct Script(pub CData<String>);
+
+fn script(content: &str) -> Script {
+    Script(CData(content.to_string()))
+}
+
+define_test!(
+    cdata_splitting,
+    [
+        (script("a < b"), "<script><![CDATA[a < b]]></script>"),
+        (
+            script("a]]>b"),
+            "<script><![CDATA[a]]]]><![CDATA[>b]]></script>"
+        ),
+        (
+            script("]]>]]>"),
+            "<script><![CDATA[]]]]><![CDATA[>]]]]><![CDATA[>]]></script>"
+        )
+    ]
+);
+
+#[test]
+fn split_xml_value_cdata() {
+    let value = XmlValue::CData(XmlCData::new("a]]>b"));
+
+    let xml = xmlity_quick_xml::to_string(&value).unwrap();
+    assert_eq!(xml, "<![CDATA[a]]]]><![CDATA[>b]]>");
+
+    let deserialized: XmlValue = xmlity_quick_xml::from_str(&xml).unwrap();
+    assert_eq!(deserialized, value);
+}
+
+#[test]
+fn adjacent_cdata_sections_stay_separate() {
+    let xml = "<script><![CDATA[a]]><![CDATA[b]]><![CDATA[c]]]]><![CDATA[d]]></script>";
+
+    let value: XmlElement = xmlity_quick_xml::from_str(xml).unwrap();
+    assert_eq!(
+        value,
+        XmlElement::new(ExpandedNameBuf::from_str("script").unwrap()).with_children([
+            XmlCData::new("a"),
+            XmlCData::new("b"),
+            XmlCData::new("c]]"),
+            XmlCData::new("d"),
+        ])
+    );
+    assert_eq!(xmlity_quick_xml::to_string(&value).unwrap(), xml);
+}
@@ -0,0 +1,138 @@
+use crate::define_test;
+
+use xmlity::{
+    DeserializationGroup, Deserialize, SerializationGroup, Serialize, SerializeAttribute,
+};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xvalue(rename_all = "lowercase")]
+pub enum Color {
+    Red,
+    Green,
+    #[xvalue(value = "dark-blue")]
+    DarkBlue,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "paint")]
+pub struct Paint {
+    #[xattribute(name = "color")]
+    pub color: Color,
+    #[xattribute(name = "accent", optional = true)]
+    pub accent: Option<Color>,
+}
+
+define_test!(
+    enum_value_attribute,
+    [
+        (
+            Paint {
+                color: Color::Red,
+                accent: None,
+            },
+            r#"<paint color="red"/>"#
+        ),
+        (
+            Paint {
+                color: Color::Green,
+                accent: Some(Color::DarkBlue),
+            },
+            r#"<paint color="green" accent="dark-blue"/>"#
+        )
+    ]
+);
+
+#[derive(Debug, PartialEq, SerializationGroup, DeserializationGroup)]
+pub struct ColorGroup {
+    #[xattribute(name = "color")]
+    pub color: Color,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "brush")]
+pub struct Brush {
+    #[xgroup]
+    pub colors: ColorGroup,
+}
+
+define_test!(
+    enum_value_attribute_in_group,
+    [(
+        Brush {
+            colors: ColorGroup {
+                color: Color::DarkBlue,
+            },
+        },
+        r#"<brush color="dark-blue"/>"#
+    )]
+);
+
+#[derive(Debug, PartialEq, SerializeAttribute, Deserialize)]
+#[xattribute(name = "color")]
+pub struct ColorAttribute(pub Color);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "pen")]
+pub struct Pen {
+    #[xattribute(deferred = true)]
+    pub color: ColorAttribute,
+}
+
+define_test!(
+    enum_value_deferred_attribute,
+    [(
+        Pen {
+            color: ColorAttribute(Color::Green),
+        },
+        r#"<pen color="green"/>"#
+    )]
+);
+
+xmlity_quick_xml::backend_tests!(
+    enum_value_attribute_backends,
+    [
+        (
+            Paint {
+                color: Color::Green,
+                accent: Some(Color::DarkBlue),
+            },
+            r#"<paint color="green" accent="dark-blue"/>"#
+        ),
+        (
+            Pen {
+                color: ColorAttribute(Color::Red),
+            },
+            r#"<pen color="red"/>"#
+        )
+    ]
+);
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "pencil")]
+pub struct Pencil {
+    #[xattribute(deferred = true)]
+    pub color: Color,
+}
+
+#[test]
+fn enum_value_from_any_attribute() {
+    let pencil: Pencil =
+        crate::utils::quick_xml_deserialize_test(r#"<pencil shade="dark-blue"/>"#).unwrap();
+
+    pretty_assertions::assert_eq!(
+        pencil,
+        Pencil {
+            color: Color::DarkBlue
+        }
+    );
+}
+
+#[test]
+fn enum_value_from_xml_attribute() {
+    let attribute = xmlity::value::XmlAttribute::new(
+        xmlity::ExpandedName::new(xmlity::LocalName::new("color").unwrap(), None),
+        "green".to_string(),
+    );
+
+    pretty_assertions::assert_eq!(Color::deserialize(&attribute).unwrap(), Color::Green);
+}
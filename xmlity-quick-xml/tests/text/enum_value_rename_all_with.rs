@@ -0,0 +1,55 @@
+use pretty_assertions::assert_eq;
+
+use crate::utils::{quick_xml_deserialize_test, quick_xml_serialize_test};
+
+use rstest::rstest;
+use xmlity::{Deserialize, Serialize};
+
+/// Turns `ZuluZuluZulu` into `zulu.zulu.zulu`.
+fn dotted(variant: &str) -> String {
+    let mut dotted = String::new();
+    for (i, ch) in variant.char_indices() {
+        if i > 0 && ch.is_uppercase() {
+            dotted.push('.');
+        }
+        dotted.push(ch.to_ascii_lowercase());
+    }
+    dotted
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xvalue(rename_all_with = "dotted")]
+enum EnumValueDotted {
+    Alpha,
+    ZuluZuluZulu,
+    #[xvalue(value = "explicit")]
+    Explicit,
+}
+
+#[rstest]
+#[case::alpha(EnumValueDotted::Alpha, "alpha")]
+#[case::zulu_zulu_zulu(EnumValueDotted::ZuluZuluZulu, "zulu.zulu.zulu")]
+#[case::explicit(EnumValueDotted::Explicit, "explicit")]
+fn serialize(#[case] value: EnumValueDotted, #[case] expected: &str) {
+    let actual = quick_xml_serialize_test(value).unwrap();
+
+    assert_eq!(actual, expected);
+}
+
+#[rstest]
+#[case::alpha(EnumValueDotted::Alpha, "alpha")]
+#[case::zulu_zulu_zulu(EnumValueDotted::ZuluZuluZulu, "zulu.zulu.zulu")]
+#[case::explicit(EnumValueDotted::Explicit, "explicit")]
+fn deserialize(#[case] expected: EnumValueDotted, #[case] text: &str) {
+    let actual: EnumValueDotted = quick_xml_deserialize_test(text).unwrap();
+
+    assert_eq!(actual, expected);
+}
+
+#[rstest]
+#[case::variant_name("ZuluZuluZulu")]
+#[case::unrenamed_explicit("Explicit")]
+fn wrong_deserialize(#[case] invalid: &str) {
+    let actual: Result<EnumValueDotted, _> = quick_xml_deserialize_test(invalid);
+    assert!(actual.is_err());
+}
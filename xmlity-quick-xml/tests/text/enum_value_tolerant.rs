@@ -0,0 +1,58 @@
+use crate::define_test;
+
+use xmlity::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xvalue(rename_all = "lowercase", case_insensitive, collapse_whitespace)]
+pub enum Status {
+    Active,
+    #[xvalue(value = "on hold")]
+    OnHold,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xelement(name = "task")]
+pub struct Task {
+    #[xattribute(name = "status")]
+    pub status: Status,
+    pub body: Status,
+}
+
+define_test!(
+    enum_value_tolerant,
+    [
+        (
+            Task {
+                status: Status::Active,
+                body: Status::OnHold,
+            },
+            r#"<task status="active">on hold</task>"#
+        ),
+        (
+            Task {
+                status: Status::Active,
+                body: Status::OnHold,
+            },
+            r#"<task status="active">on hold</task>"#,
+            "<task status=\"ACTIVE\">\n  On\t\n  HOLD\n</task>"
+        )
+    ]
+);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[xvalue(rename_all = "lowercase")]
+pub enum StrictStatus {
+    Active,
+    #[xvalue(value = "on hold")]
+    OnHold,
+}
+
+#[test]
+fn strict_enum_value_rejects_other_case_and_spacing() {
+    assert!(xmlity_quick_xml::from_str::<StrictStatus>("ACTIVE").is_err());
+    assert!(xmlity_quick_xml::from_str::<StrictStatus>("on  hold").is_err());
+    assert_eq!(
+        xmlity_quick_xml::from_str::<StrictStatus>(" on hold ").unwrap(),
+        StrictStatus::OnHold
+    );
+}
@@ -1,5 +1,9 @@
+pub mod cdata;
 pub mod enum_value;
+pub mod enum_value_attribute;
 pub mod enum_value_rename_all;
+pub mod enum_value_rename_all_with;
+pub mod enum_value_tolerant;
 pub mod extendable;
 pub mod mixed;
 pub mod strings;
@@ -1,3 +1,11 @@
 use crate::define_test;
 
 define_test!(single_string, [("Alpha".to_owned(), "Alpha")]);
+
+define_test!(
+    escaped_string,
+    [
+        ("a < b & c".to_owned(), "a &lt; b &amp; c"),
+        ("a\r\nb\r".to_owned(), "a&#13;\nb&#13;")
+    ]
+);
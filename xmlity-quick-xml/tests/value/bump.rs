@@ -0,0 +1,71 @@
+use pretty_assertions::assert_eq;
+use xmlity::{
+    value::bump::{Bump, XmlChildIn, XmlValueIn},
+    Deserialize,
+};
+
+const XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?><a xmlns="http://example.com/test" b="1 &amp; 2"><c>Text</c><!--comment--><![CDATA[<raw>]]></a>"#;
+
+#[test]
+fn deserialize_in_arena() {
+    let bump = Bump::new();
+    let mut deserializer = xmlity_quick_xml::Deserializer::from(XML.as_bytes());
+    let value = XmlValueIn::deserialize_in(&mut deserializer, &bump).unwrap();
+
+    let XmlValueIn::Seq(values) = &value else {
+        panic!("Expected a sequence, got {value:?}");
+    };
+    assert_eq!(values.len(), 2);
+
+    let XmlValueIn::Decl(decl) = &values[0] else {
+        panic!("Expected a declaration, got {:?}", values[0]);
+    };
+    assert_eq!(decl.version, "1.0");
+    assert_eq!(decl.encoding, Some("UTF-8"));
+
+    let XmlValueIn::Element(a) = &values[1] else {
+        panic!("Expected an element, got {:?}", values[1]);
+    };
+    assert_eq!(a.name.local_name().as_str(), "a");
+    assert_eq!(
+        a.name.namespace().map(|namespace| namespace.as_str()),
+        Some("http://example.com/test")
+    );
+    assert_eq!(a.attributes.len(), 1);
+    assert_eq!(a.attributes[0].name.local_name().as_str(), "b");
+    assert_eq!(a.attributes[0].value, "1 & 2");
+
+    assert_eq!(a.children.len(), 3);
+    let XmlChildIn::Element(c) = &a.children[0] else {
+        panic!("Expected an element, got {:?}", a.children[0]);
+    };
+    assert_eq!(c.children.as_slice(), [XmlChildIn::Text("Text")]);
+    assert_eq!(a.children[1], XmlChildIn::Comment(b"comment"));
+    assert_eq!(a.children[2], XmlChildIn::CData("<raw>"));
+}
+
+const UNQUALIFIED_XML: &str =
+    r#"<a b="1 &amp; 2"><c d="e">Text</c><!--comment--><![CDATA[<raw>]]></a>"#;
+
+#[test]
+fn serialize_from_arena() {
+    let bump = Bump::new();
+    let mut deserializer = xmlity_quick_xml::Deserializer::from(UNQUALIFIED_XML.as_bytes());
+    let value = XmlValueIn::deserialize_in(&mut deserializer, &bump).unwrap();
+
+    let actual = xmlity_quick_xml::to_string(&value).unwrap();
+
+    assert_eq!(actual, UNQUALIFIED_XML);
+}
+
+#[test]
+fn arena_matches_value() {
+    let bump = Bump::new();
+    let mut deserializer = xmlity_quick_xml::Deserializer::from(XML.as_bytes());
+    let value = XmlValueIn::deserialize_in(&mut deserializer, &bump).unwrap();
+
+    let mut deserializer = xmlity_quick_xml::Deserializer::from(XML.as_bytes());
+    let expected = xmlity::XmlValue::deserialize_seq(&mut deserializer).unwrap();
+
+    assert_eq!(xmlity::value::to_value(&value).unwrap(), expected);
+}
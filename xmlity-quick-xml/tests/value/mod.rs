@@ -1,2 +1,3 @@
+pub mod bump;
 pub mod indirect_direct_equal;
 pub mod sub_xml_value;
@@ -0,0 +1,71 @@
+use pretty_assertions::assert_eq;
+use xmlity::{Deserialize, Deserializer};
+
+#[derive(Debug, Clone, PartialEq)]
+struct BaseUrl(String);
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xvalue(deserialize_with_context = deserialize_href)]
+struct Href(String);
+
+fn deserialize_href<'de, D>(deserializer: D, base: Option<&BaseUrl>) -> Result<Href, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let href = String::deserialize(deserializer)?;
+    Ok(Href(match base {
+        Some(BaseUrl(base)) if !href.contains("://") => format!("{base}{href}"),
+        _ => href,
+    }))
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "a")]
+struct Link {
+    #[xattribute(name = "href")]
+    href: Href,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[xelement(name = "links")]
+struct Links {
+    #[xelement(name = "home")]
+    home: Href,
+    links: Vec<Link>,
+}
+
+const LINKS: &str = r#"<links><home>index.html</home><a href="about.html"/><a href="https://example.org/"/></links>"#;
+
+#[test]
+fn deserialize_with_context() {
+    let actual: Links =
+        xmlity_quick_xml::from_str_with_context(LINKS, BaseUrl("https://example.com/".to_string()))
+            .unwrap();
+
+    assert_eq!(
+        actual,
+        Links {
+            home: Href("https://example.com/index.html".to_string()),
+            links: vec![
+                Link {
+                    href: Href("https://example.com/about.html".to_string()),
+                },
+                Link {
+                    href: Href("https://example.org/".to_string()),
+                },
+            ],
+        }
+    );
+}
+
+#[test]
+fn deserialize_with_context_missing() {
+    let actual: Link = xmlity_quick_xml::from_str(r#"<a href="about.html"/>"#).unwrap();
+
+    assert_eq!(
+        actual,
+        Link {
+            href: Href("about.html".to_string()),
+        }
+    );
+}
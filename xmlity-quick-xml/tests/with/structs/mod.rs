@@ -1,3 +1,4 @@
 mod deserialize_with;
+mod deserialize_with_context;
 mod serialize_with;
 mod with_mod;
@@ -0,0 +1,263 @@
+//! Support items for code generated by `xmlity-derive`. Not public API.
+//!
+//! The derive macros validate names while expanding, and the generated code builds them in const items, so they are not validated again on every call.
+use crate::{
+    de::{self, AttributesAccess, SeqAccess},
+    ser::{AttributeSerializer, SerializeAttributeAccess},
+    types::utils::{IgnoredAny, NoneDeserializer, Whitespace},
+    value::{XmlComment, XmlText},
+    Deserialize, DeserializeOwned, ExpandedName, LocalName, Prefix, SerializeAttribute,
+    XmlNamespace,
+};
+
+/// Checks if text matches the value of a unit variant or struct, ignoring surrounding whitespace.
+pub fn text_matches_value(
+    text: &str,
+    value: &str,
+    case_insensitive: bool,
+    collapse_whitespace: bool,
+) -> bool {
+    match (collapse_whitespace, case_insensitive) {
+        (false, false) => text.trim() == value,
+        (false, true) => text.trim().to_lowercase() == value.to_lowercase(),
+        (true, false) => text.split_whitespace().eq(value.split_whitespace()),
+        (true, true) => text
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .eq(value.split_whitespace().map(str::to_lowercase)),
+    }
+}
+
+/// Creates a [`LocalName`] from a name in a derive attribute.
+///
+/// The generated code calls this in const items, so the name is only validated when compiling.
+pub const fn local_name(value: &'static str) -> &'static LocalName {
+    if crate::name_tokens::is_valid_name(value).is_err() {
+        panic!("invalid XML name in an xmlity attribute");
+    }
+    // SAFETY: The name has been validated.
+    unsafe { LocalName::new_unchecked(value) }
+}
+
+/// Creates an [`XmlNamespace`] from a namespace in a derive attribute.
+///
+/// The generated code calls this in const items, like [`local_name`].
+pub const fn xml_namespace(value: &'static str) -> &'static XmlNamespace {
+    // SAFETY: Like `XmlNamespace::new`, any value is accepted.
+    unsafe { XmlNamespace::new_unchecked(value) }
+}
+
+/// Creates a [`Prefix`] from a prefix in a derive attribute, where an empty prefix is the default prefix.
+///
+/// The generated code calls this in const items, like [`local_name`].
+pub const fn prefix(value: &'static str) -> &'static Prefix {
+    if !value.is_empty() && crate::name_tokens::is_valid_name(value).is_err() {
+        panic!("invalid XML prefix in an xmlity attribute");
+    }
+    // SAFETY: The prefix has been validated, or is the default prefix.
+    unsafe { Prefix::new_unchecked(value) }
+}
+
+/// An attribute in the XML Schema Instance namespace, like `xsi:schemaLocation`, written with the `xsi` prefix if it is available.
+pub struct XsiAttribute {
+    /// The local name of the attribute.
+    pub name: &'static LocalName,
+    /// The value of the attribute.
+    pub value: &'static str,
+}
+
+impl SerializeAttribute for XsiAttribute {
+    fn serialize_attribute<S: AttributeSerializer>(
+        &self,
+        mut serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let name = ExpandedName::new(self.name, Some(XmlNamespace::XSI));
+        let mut attribute = serializer.serialize_attribute(&name)?;
+        attribute.preferred_prefix(Some(prefix("xsi")))?;
+        attribute.end(&self.value)
+    }
+}
+
+// The functions below hold the parts of generated deserialization code that do not depend on the type being derived, so that they are not expanded again for every type.
+
+/// Skips the next child if it is whitespace, returning whether it did. Whitespace is never skipped where it is preserved.
+pub fn skip_whitespace<'de, A: SeqAccess<'de>>(access: &mut A) -> bool {
+    !access.preserves_whitespace() && matches!(access.next_element::<Whitespace>(), Ok(Some(_)))
+}
+
+/// Skips the next child if it is a comment, returning whether it did.
+pub fn skip_comment<'de, A: SeqAccess<'de>>(access: &mut A) -> bool {
+    matches!(access.next_element::<XmlComment>(), Ok(Some(_)))
+}
+
+/// Skips the next child, returning whether there was one.
+pub fn skip_child<'de, A: SeqAccess<'de>>(access: &mut A) -> bool {
+    matches!(access.next_element::<IgnoredAny>(), Ok(Some(_)))
+}
+
+/// Skips the next attribute, returning whether there was one.
+pub fn skip_attribute<'de, A: AttributesAccess<'de>>(access: &mut A) -> bool {
+    matches!(access.next_attribute::<IgnoredAny>(), Ok(Some(_)))
+}
+
+/// What the generated visit of the fields did with the next attribute or child.
+pub enum Visit {
+    /// There is nothing left to visit, either because the input ended or because every field has been visited.
+    End,
+    /// A field took the input, so the fields are visited again.
+    Next,
+    /// No field took the input.
+    Unmatched,
+}
+
+/// What is done with attributes or children that no field takes, as set with `allow_unknown_attributes` and `allow_unknown_children`.
+#[derive(Clone, Copy)]
+pub enum AllowUnknown {
+    /// They are skipped wherever they are.
+    Any,
+    /// Visiting stops at the first one, leaving the rest.
+    AtEnd,
+    /// They are an error.
+    None,
+}
+
+/// Visits the attributes of an element with `visit` until it returns [`Visit::End`], handling attributes that no field takes according to `unknown`.
+pub fn visit_attributes<'de, A, F>(
+    access: &mut A,
+    unknown: AllowUnknown,
+    mut visit: F,
+) -> Result<(), A::Error>
+where
+    A: AttributesAccess<'de>,
+    F: FnMut(&mut A) -> Result<Visit, A::Error>,
+{
+    loop {
+        match visit(access)? {
+            Visit::End => return Ok(()),
+            Visit::Next => continue,
+            Visit::Unmatched => {}
+        }
+
+        match unknown {
+            AllowUnknown::Any if skip_attribute(access) => continue,
+            AllowUnknown::None if skip_attribute(access) => return Err(de::Error::unknown_child()),
+            _ => return Ok(()),
+        }
+    }
+}
+
+/// Visits children in any order with `visit` until it returns [`Visit::End`], skipping whitespace and comments if they are ignored, and handling children that no field takes according to `unknown`.
+pub fn visit_children<'de, A, F>(
+    access: &mut A,
+    ignore_whitespace: bool,
+    ignore_comments: bool,
+    unknown: AllowUnknown,
+    mut visit: F,
+) -> Result<(), A::Error>
+where
+    A: SeqAccess<'de>,
+    F: FnMut(&mut A) -> Result<Visit, A::Error>,
+{
+    loop {
+        if (ignore_whitespace && skip_whitespace(access))
+            || (ignore_comments && skip_comment(access))
+        {
+            continue;
+        }
+
+        match visit(access)? {
+            Visit::End => return Ok(()),
+            Visit::Next => continue,
+            Visit::Unmatched => {}
+        }
+
+        match unknown {
+            AllowUnknown::Any if skip_child(access) => continue,
+            AllowUnknown::None if skip_child(access) => return Err(de::Error::unknown_child()),
+            _ => return Ok(()),
+        }
+    }
+}
+
+/// Visits children in the order of the fields, where `visit` deserializes the next field that has not been visited yet and returns [`Visit::End`] once every field has been visited. Whitespace and comments are skipped if they are ignored, and children that are left are an error unless `unknown` allows them at the end.
+pub fn visit_children_in_order<'de, A, F>(
+    access: &mut A,
+    ignore_whitespace: bool,
+    ignore_comments: bool,
+    unknown: AllowUnknown,
+    mut visit: F,
+) -> Result<(), A::Error>
+where
+    A: SeqAccess<'de>,
+    F: FnMut(&mut A) -> Result<Visit, A::Error>,
+{
+    loop {
+        if (ignore_whitespace && skip_whitespace(access))
+            || (ignore_comments && skip_comment(access))
+        {
+            continue;
+        }
+
+        if let Visit::End = visit(access)? {
+            if matches!(unknown, AllowUnknown::None) && skip_child(access) {
+                return Err(de::Error::custom("Unexpected element at end of sequence."));
+            }
+            return Ok(());
+        }
+    }
+}
+
+/// Deserializes the next child, treating errors as if there were no child.
+pub fn next_child<'de, T: Deserialize<'de>, A: SeqAccess<'de>>(access: &mut A) -> Option<T> {
+    access.next_element_seq::<T>().ok().flatten()
+}
+
+/// Deserializes the next child, falling back to deserializing the type from nothing if there is no child.
+pub fn next_child_or_none<'de, T: Deserialize<'de>, A: SeqAccess<'de>>(
+    access: &mut A,
+) -> Option<T> {
+    next_child(access).or_else(|| T::deserialize_seq(NoneDeserializer::<A::Error>::new()).ok())
+}
+
+/// Deserializes the value of a field that was not found from nothing, failing with a missing field error if the type needs data.
+pub fn deserialize_missing<'de, T: Deserialize<'de>, E: de::Error>(
+    field: &'static str,
+) -> Result<T, E> {
+    T::deserialize_seq(NoneDeserializer::<E>::new()).map_err(|_| E::missing_field(field))
+}
+
+/// Returns the value of a field, or deserializes it from nothing if it was not found.
+pub fn value_or_missing<'de, T: Deserialize<'de>, E: de::Error>(
+    value: Option<T>,
+    field: &'static str,
+) -> Result<T, E> {
+    match value {
+        Some(value) => Ok(value),
+        None => deserialize_missing(field),
+    }
+}
+
+/// Deserializes an attribute of an enclosing element for fields with `from_ancestor`.
+pub fn ancestor_attribute<'de, T: DeserializeOwned, A: AttributesAccess<'de>>(
+    access: &A,
+    ancestor: &ExpandedName<'_>,
+    attribute: &ExpandedName<'_>,
+) -> Result<Option<T>, A::Error> {
+    access
+        .ancestor_attribute(ancestor, attribute)
+        .map(|value| T::deserialize(&XmlText::new(value)).map_err(de::Error::custom))
+        .transpose()
+}
+
+/// Wraps a function used with `deserialize_with_context`, passing it a copy of the external data of its type as its context.
+pub fn deserialize_with_context<'de, D, C, T, F>(f: F) -> impl FnOnce(D) -> Result<T, D::Error>
+where
+    D: de::Deserializer<'de>,
+    C: Clone + 'static,
+    F: FnOnce(D, Option<&C>) -> Result<T, D::Error>,
+{
+    move |deserializer| {
+        let context = deserializer.external_data::<C>().cloned();
+        f(deserializer, context.as_ref())
+    }
+}
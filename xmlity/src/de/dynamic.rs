@@ -0,0 +1,1476 @@
+//! Dyn-compatible versions of the deserialization traits, which make it possible to deserialize through trait objects.
+use std::{
+    borrow::Cow,
+    fmt::{self, Display},
+    marker::PhantomData,
+};
+
+use crate::{ExpandedName, ExpandedNameBuf, Prefix, PrefixBuf, XmlNamespace, XmlNamespaceBuf};
+
+use super::{
+    AttributeAccess, AttributesAccess, Deserialize, DeserializeContext, DeserializeSeed,
+    Deserializer, ElementAccess, Error, PeekedNode, SeqAccess, Unexpected, Visitor, XmlCData,
+    XmlComment, XmlDeclaration, XmlDoctype, XmlProcessingInstruction, XmlText,
+};
+
+/// The error returned when deserializing through a [`DynDeserializer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DynError {
+    /// A custom error.
+    Custom(String),
+    /// Wrong name.
+    WrongName {
+        /// The actual name.
+        actual: Box<ExpandedNameBuf>,
+        /// The expected name.
+        expected: Box<ExpandedNameBuf>,
+    },
+    /// Unexpected visit.
+    UnexpectedVisit(Unexpected),
+    /// Missing field.
+    MissingField(String),
+    /// No possible variant.
+    NoPossibleVariant {
+        /// The ident of the enum.
+        ident: String,
+    },
+    /// Missing data.
+    MissingData,
+    /// Unknown child.
+    UnknownChild,
+    /// Invalid string.
+    InvalidString,
+    /// The underlying deserializer failed. Its error is returned by [`ErasedDeserializer::finish`].
+    Deserializer,
+}
+
+impl fmt::Display for DynError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Custom(value) => write!(f, "Custom: {value}"),
+            Self::WrongName { actual, expected } => {
+                write!(f, "Wrong name: {actual:?}, expected: {expected:?}")
+            }
+            Self::UnexpectedVisit(value) => write!(f, "Unexpected visit: {value}"),
+            Self::MissingField(value) => write!(f, "Missing field: {value}"),
+            Self::NoPossibleVariant { ident } => write!(f, "No possible variant: {ident}"),
+            Self::MissingData => f.write_str("Missing data"),
+            Self::UnknownChild => f.write_str("Unknown child"),
+            Self::InvalidString => f.write_str("Invalid string"),
+            Self::Deserializer => f.write_str("The underlying deserializer failed"),
+        }
+    }
+}
+
+impl std::error::Error for DynError {}
+
+impl Error for DynError {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: Display,
+    {
+        DynError::Custom(msg.to_string())
+    }
+
+    fn wrong_name(name: &ExpandedName<'_>, expected: &ExpandedName<'_>) -> Self {
+        DynError::WrongName {
+            actual: Box::new(name.into()),
+            expected: Box::new(expected.into()),
+        }
+    }
+
+    fn unexpected_visit<T>(unexpected: Unexpected, _expected: &T) -> Self {
+        DynError::UnexpectedVisit(unexpected)
+    }
+
+    fn missing_field(field: &str) -> Self {
+        DynError::MissingField(field.to_string())
+    }
+
+    fn no_possible_variant(ident: &str) -> Self {
+        DynError::NoPossibleVariant {
+            ident: ident.to_string(),
+        }
+    }
+
+    fn missing_data() -> Self {
+        DynError::MissingData
+    }
+
+    fn unknown_child() -> Self {
+        DynError::UnknownChild
+    }
+
+    fn invalid_string() -> Self {
+        DynError::InvalidString
+    }
+}
+
+impl DynError {
+    fn already_used() -> Self {
+        DynError::Custom("The deserializer has already been used".to_string())
+    }
+
+    fn not_visited() -> Self {
+        DynError::Custom("The visitor was not called".to_string())
+    }
+
+    /// Converts this error into the error type of a concrete deserializer.
+    fn into_error<E: Error>(self) -> E {
+        match self {
+            DynError::Custom(msg) => E::custom(msg),
+            DynError::WrongName { actual, expected } => {
+                E::wrong_name(&actual.as_ref().as_ref(), &expected.as_ref().as_ref())
+            }
+            DynError::UnexpectedVisit(unexpected) => E::unexpected_visit(unexpected, &()),
+            DynError::MissingField(field) => E::missing_field(&field),
+            DynError::NoPossibleVariant { ident } => E::no_possible_variant(&ident),
+            DynError::MissingData => E::missing_data(),
+            DynError::UnknownChild => E::unknown_child(),
+            DynError::InvalidString => E::invalid_string(),
+            err @ DynError::Deserializer => E::custom(err),
+        }
+    }
+}
+
+/// Stashes an error of the underlying deserializer, returning [`DynError::Deserializer`] in its place.
+fn stash<E>(slot: &mut Option<E>, err: E) -> DynError {
+    *slot = Some(err);
+    DynError::Deserializer
+}
+
+/// Turns an error returned through a trait object back into the error type of the underlying deserializer.
+fn restore<E: Error>(slot: &mut Option<E>, err: DynError) -> E {
+    match (err, slot.take()) {
+        (DynError::Deserializer, Some(err)) => err,
+        (err, _) => err.into_error(),
+    }
+}
+
+/// A dyn-compatible version of [`Deserializer`].
+///
+/// `&mut dyn DynDeserializer` implements [`Deserializer`], so non-generic code can accept one and deserialize any [`Deserialize`] type from it. Deserializers are turned into a [`DynDeserializer`] by wrapping them in an [`ErasedDeserializer`]. This is useful for plugin boundaries, and for keeping deserialization code from being instantiated for every deserializer in large binaries.
+///
+/// External data of the wrapped deserializer, such as a context passed to a backend to be read by functions used with `deserialize_with_context`, is not forwarded. [`Deserializer::external_data`] and [`DeserializeContext::external_data`] are generic over the type of the data, so they can't be called through a trait object, and always return [`None`] through a [`DynDeserializer`].
+///
+/// ```
+/// use xmlity::de::{DynDeserializer, DynError, ErasedDeserializer};
+/// use xmlity::{Deserialize, Serialize};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// #[xelement(name = "note")]
+/// struct Note {
+///     #[xattribute(name = "to")]
+///     to: String,
+/// }
+///
+/// // Not generic over the deserializer.
+/// fn read_note(deserializer: &mut dyn DynDeserializer<'_>) -> Result<Note, DynError> {
+///     Note::deserialize(deserializer)
+/// }
+///
+/// let value = xmlity::value::to_value(&Note { to: "Tove".to_string() }).unwrap();
+///
+/// let mut deserializer = ErasedDeserializer::new(&value);
+/// let result = read_note(&mut deserializer);
+/// let note = deserializer.finish(result).unwrap();
+///
+/// assert_eq!(note, Note { to: "Tove".to_string() });
+/// ```
+pub trait DynDeserializer<'de> {
+    /// Deserialize a value, calling the visitor with whatever is next.
+    fn dyn_deserialize_any(&mut self, visitor: &mut dyn DynVisitor<'de>) -> Result<(), DynError>;
+
+    /// Deserialize a sequence of values.
+    fn dyn_deserialize_seq(&mut self, visitor: &mut dyn DynVisitor<'de>) -> Result<(), DynError>;
+}
+
+/// A dyn-compatible version of [`Visitor`], passed to a [`DynDeserializer`].
+///
+/// Unlike a [`Visitor`], the value produced by a [`DynVisitor`] is kept by the visitor itself.
+pub trait DynVisitor<'de> {
+    /// Format a message stating what this visitor expects.
+    fn dyn_expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result;
+
+    /// Visit a text node.
+    fn dyn_visit_text(&mut self, value: Box<dyn DynXmlText<'de> + '_>) -> Result<(), DynError>;
+
+    /// Visit a CDATA section.
+    fn dyn_visit_cdata(&mut self, value: Box<dyn DynXmlText<'de> + '_>) -> Result<(), DynError>;
+
+    /// Visit an element.
+    fn dyn_visit_element(
+        &mut self,
+        element: Box<dyn DynElementAccess<'de> + '_>,
+    ) -> Result<(), DynError>;
+
+    /// Visit an attribute.
+    fn dyn_visit_attribute(
+        &mut self,
+        attribute: Box<dyn DynAttributeAccess<'de> + '_>,
+    ) -> Result<(), DynError>;
+
+    /// Visit a sequence of values.
+    fn dyn_visit_seq(&mut self, sequence: Box<dyn DynSeqAccess<'de> + '_>) -> Result<(), DynError>;
+
+    /// Visit a processing instruction.
+    fn dyn_visit_pi(&mut self, pi: &dyn DynXmlProcessingInstruction) -> Result<(), DynError>;
+
+    /// Visit a declaration.
+    fn dyn_visit_decl(&mut self, declaration: &dyn DynXmlDeclaration) -> Result<(), DynError>;
+
+    /// Visit a comment.
+    fn dyn_visit_comment(
+        &mut self,
+        comment: Box<dyn DynXmlBytes<'de> + '_>,
+    ) -> Result<(), DynError>;
+
+    /// Visit a doctype declaration.
+    fn dyn_visit_doctype(
+        &mut self,
+        doctype: Box<dyn DynXmlBytes<'de> + '_>,
+    ) -> Result<(), DynError>;
+
+    /// Visit nothing.
+    fn dyn_visit_none(&mut self) -> Result<(), DynError>;
+}
+
+/// A dyn-compatible version of [`DeserializeSeed`], which keeps the value it produces.
+pub trait DynDeserializeSeed<'de> {
+    /// Deserialize the value.
+    fn dyn_deserialize(
+        &mut self,
+        deserializer: &mut dyn DynDeserializer<'de>,
+    ) -> Result<(), DynError>;
+
+    /// Deserialize the value from a sequence.
+    fn dyn_deserialize_seq(
+        &mut self,
+        deserializer: &mut dyn DynDeserializer<'de>,
+    ) -> Result<(), DynError>;
+}
+
+/// A dyn-compatible version of [`AttributesAccess`].
+pub trait DynAttributesAccess<'de> {
+    /// Deserialize the next attribute with the seed. The seed is left without a value if there are no more attributes.
+    fn dyn_next_attribute_seed(
+        &mut self,
+        seed: &mut dyn DynDeserializeSeed<'de>,
+    ) -> Result<(), DynError>;
+
+    /// Get a sub-access to the attributes.
+    fn dyn_sub_access<'a>(&'a mut self)
+        -> Result<Box<dyn DynAttributesAccess<'de> + 'a>, DynError>;
+
+    /// Peek at the name of the next attribute.
+    fn dyn_peek_name(&mut self) -> Result<Option<ExpandedNameBuf>, DynError>;
+
+    /// Deserialize the attribute with the given name with the seed. The seed is left without a value if there is no such attribute.
+    fn dyn_find_attribute_seed(
+        &mut self,
+        name: ExpandedName<'_>,
+        seed: &mut dyn DynDeserializeSeed<'de>,
+    ) -> Result<(), DynError>;
+
+    /// Returns the value of an attribute of the closest enclosing element with the given name.
+    fn dyn_ancestor_attribute(
+        &self,
+        ancestor: &ExpandedName<'_>,
+        attribute: &ExpandedName<'_>,
+    ) -> Option<Cow<'_, str>>;
+}
+
+/// A dyn-compatible version of [`ElementAccess`], passed to [`DynVisitor::dyn_visit_element`].
+pub trait DynElementAccess<'de>: DynAttributesAccess<'de> {
+    /// Returns the name of the element.
+    fn dyn_name(&self) -> ExpandedName<'_>;
+
+    /// Returns the prefix of the element, if it is known.
+    fn dyn_prefix(&self) -> Option<&Prefix>;
+
+    /// Returns the namespace declarations of the element, if they are known.
+    fn dyn_namespace_declarations(&self) -> Vec<(PrefixBuf, XmlNamespaceBuf)>;
+
+    /// Returns an access to the children of the element.
+    fn dyn_children<'a>(self: Box<Self>) -> Result<Box<dyn DynSeqAccess<'de> + 'a>, DynError>
+    where
+        Self: 'a,
+        'de: 'a;
+
+    /// Returns the context of the element.
+    fn dyn_context(&self) -> Box<dyn DynDeserializeContext + '_>;
+}
+
+/// A dyn-compatible version of [`AttributeAccess`], passed to [`DynVisitor::dyn_visit_attribute`].
+///
+/// The value of the attribute is passed through as text. Types deserializing attribute values therefore see a [`DeserializeContext`] without namespace bindings or external data.
+pub trait DynAttributeAccess<'de> {
+    /// Returns the name of the attribute.
+    fn dyn_name(&self) -> ExpandedName<'_>;
+
+    /// Returns the prefix of the attribute, if it is known.
+    fn dyn_prefix(&self) -> Option<&Prefix>;
+
+    /// Returns the value of the attribute.
+    fn dyn_value(self: Box<Self>) -> Result<Cow<'de, str>, DynError>;
+}
+
+/// A dyn-compatible version of [`SeqAccess`].
+pub trait DynSeqAccess<'de> {
+    /// Deserialize the next element with the seed. The seed is left without a value if there are no more elements.
+    fn dyn_next_element_seed(
+        &mut self,
+        seed: &mut dyn DynDeserializeSeed<'de>,
+    ) -> Result<(), DynError>;
+
+    /// Deserialize the next element as a sequence with the seed. The seed is left without a value if there are no more elements.
+    fn dyn_next_element_seq_seed(
+        &mut self,
+        seed: &mut dyn DynDeserializeSeed<'de>,
+    ) -> Result<(), DynError>;
+
+    /// Get a sub-access to the sequence.
+    fn dyn_sub_access<'a>(&'a mut self) -> Result<Box<dyn DynSeqAccess<'de> + 'a>, DynError>
+    where
+        'de: 'a;
+
+    /// Returns a hint of how many elements are left.
+    fn dyn_size_hint(&self) -> Option<usize>;
+
+    /// Peek at the next element.
+    fn dyn_peek_element(&mut self) -> Result<Option<PeekedNode>, DynError>;
+
+    /// Returns whether whitespace in the sequence is significant.
+    fn dyn_preserves_whitespace(&self) -> bool;
+}
+
+/// A dyn-compatible version of [`DeserializeContext`].
+///
+/// External data is not available through a [`DynDeserializeContext`], see [`DynDeserializer`].
+pub trait DynDeserializeContext {
+    /// Get the default namespace.
+    fn dyn_default_namespace(&self) -> Option<&XmlNamespace>;
+
+    /// Resolve a prefix to a namespace.
+    fn dyn_resolve_prefix(&self, prefix: &Prefix) -> Option<&XmlNamespace>;
+
+    /// Returns the value of an attribute of the closest enclosing element with the given name.
+    fn dyn_ancestor_attribute(
+        &self,
+        ancestor: &ExpandedName<'_>,
+        attribute: &ExpandedName<'_>,
+    ) -> Option<Cow<'_, str>>;
+
+    /// Returns the namespaces in scope, starting with the ones declared closest to the context.
+    fn dyn_namespaces_in_scope(&self) -> Vec<(PrefixBuf, XmlNamespaceBuf)>;
+}
+
+/// A dyn-compatible version of [`XmlText`] and [`XmlCData`].
+pub trait DynXmlText<'de> {
+    /// Returns the bytes of the text.
+    fn dyn_into_bytes(self: Box<Self>) -> Cow<'de, [u8]>;
+
+    /// Returns the bytes of the text.
+    fn dyn_as_bytes(&self) -> &[u8];
+
+    /// Returns the text as a string.
+    fn dyn_into_string(self: Box<Self>) -> Cow<'de, str>;
+
+    /// Returns the text as a string.
+    fn dyn_as_str(&self) -> &str;
+
+    /// Returns the context of the text.
+    fn dyn_context(&self) -> Box<dyn DynDeserializeContext + '_>;
+}
+
+/// A dyn-compatible version of [`XmlComment`] and [`XmlDoctype`].
+pub trait DynXmlBytes<'de> {
+    /// Returns the bytes of the node.
+    fn dyn_into_bytes(self: Box<Self>) -> Cow<'de, [u8]>;
+
+    /// Returns the bytes of the node.
+    fn dyn_as_bytes(&self) -> &[u8];
+
+    /// Returns the context of the node.
+    fn dyn_context(&self) -> Box<dyn DynDeserializeContext + '_>;
+}
+
+/// A dyn-compatible version of [`XmlProcessingInstruction`].
+pub trait DynXmlProcessingInstruction {
+    /// Returns the target of the processing instruction.
+    fn dyn_target(&self) -> &[u8];
+
+    /// Returns the content of the processing instruction.
+    fn dyn_content(&self) -> &[u8];
+
+    /// Returns the context of the processing instruction.
+    fn dyn_context(&self) -> Box<dyn DynDeserializeContext + '_>;
+}
+
+/// A dyn-compatible version of [`XmlDeclaration`].
+pub trait DynXmlDeclaration {
+    /// Returns the version of the declaration.
+    fn dyn_version(&self) -> &[u8];
+
+    /// Returns the encoding of the declaration.
+    fn dyn_encoding(&self) -> Option<&[u8]>;
+
+    /// Returns the standalone value of the declaration.
+    fn dyn_standalone(&self) -> Option<&[u8]>;
+
+    /// Returns the context of the declaration.
+    fn dyn_context(&self) -> Box<dyn DynDeserializeContext + '_>;
+}
+
+// The trait objects implement the generic traits, so that they can be used with any `Deserialize` type.
+
+impl<'de> Deserializer<'de> for &mut (dyn DynDeserializer<'de> + '_) {
+    type Error = DynError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let mut visitor = VisitorSlot::new(visitor);
+        self.dyn_deserialize_any(&mut visitor)?;
+        visitor.value.ok_or_else(DynError::not_visited)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let mut visitor = VisitorSlot::new(visitor);
+        self.dyn_deserialize_seq(&mut visitor)?;
+        visitor.value.ok_or_else(DynError::not_visited)
+    }
+}
+
+impl DeserializeContext for Box<dyn DynDeserializeContext + '_> {
+    fn default_namespace(&self) -> Option<&XmlNamespace> {
+        self.as_ref().dyn_default_namespace()
+    }
+
+    fn resolve_prefix(&self, prefix: &Prefix) -> Option<&XmlNamespace> {
+        self.as_ref().dyn_resolve_prefix(prefix)
+    }
+
+    fn ancestor_attribute(
+        &self,
+        ancestor: &ExpandedName<'_>,
+        attribute: &ExpandedName<'_>,
+    ) -> Option<Cow<'_, str>> {
+        self.as_ref().dyn_ancestor_attribute(ancestor, attribute)
+    }
+
+    fn namespaces_in_scope(&self) -> Vec<(PrefixBuf, XmlNamespaceBuf)> {
+        self.as_ref().dyn_namespaces_in_scope()
+    }
+
+    fn external_data<T>(&self) -> Option<&T>
+    where
+        T: core::any::Any,
+    {
+        None
+    }
+}
+
+impl<'de> XmlText<'de> for Box<dyn DynXmlText<'de> + '_> {
+    type DeserializeContext<'a>
+        = Box<dyn DynDeserializeContext + 'a>
+    where
+        Self: 'a;
+
+    fn into_bytes(self) -> Cow<'de, [u8]> {
+        self.dyn_into_bytes()
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        self.as_ref().dyn_as_bytes()
+    }
+
+    fn into_string(self) -> Cow<'de, str> {
+        self.dyn_into_string()
+    }
+
+    fn as_str(&self) -> &str {
+        self.as_ref().dyn_as_str()
+    }
+
+    fn context(&self) -> Self::DeserializeContext<'_> {
+        self.as_ref().dyn_context()
+    }
+}
+
+impl<'de> XmlCData<'de> for Box<dyn DynXmlText<'de> + '_> {
+    type DeserializeContext<'a>
+        = Box<dyn DynDeserializeContext + 'a>
+    where
+        Self: 'a;
+
+    fn into_bytes(self) -> Cow<'de, [u8]> {
+        self.dyn_into_bytes()
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        self.as_ref().dyn_as_bytes()
+    }
+
+    fn into_string(self) -> Cow<'de, str> {
+        self.dyn_into_string()
+    }
+
+    fn as_str(&self) -> &str {
+        self.as_ref().dyn_as_str()
+    }
+
+    fn context(&self) -> Self::DeserializeContext<'_> {
+        self.as_ref().dyn_context()
+    }
+}
+
+impl<'de> XmlComment<'de> for Box<dyn DynXmlBytes<'de> + '_> {
+    type DeserializeContext<'a>
+        = Box<dyn DynDeserializeContext + 'a>
+    where
+        Self: 'a;
+
+    fn into_bytes(self) -> Cow<'de, [u8]> {
+        self.dyn_into_bytes()
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        self.as_ref().dyn_as_bytes()
+    }
+
+    fn context(&self) -> Self::DeserializeContext<'_> {
+        self.as_ref().dyn_context()
+    }
+}
+
+impl<'de> XmlDoctype<'de> for Box<dyn DynXmlBytes<'de> + '_> {
+    type DeserializeContext<'a>
+        = Box<dyn DynDeserializeContext + 'a>
+    where
+        Self: 'a;
+
+    fn into_bytes(self) -> Cow<'de, [u8]> {
+        self.dyn_into_bytes()
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        self.as_ref().dyn_as_bytes()
+    }
+
+    fn context(&self) -> Self::DeserializeContext<'_> {
+        self.as_ref().dyn_context()
+    }
+}
+
+impl XmlProcessingInstruction for &(dyn DynXmlProcessingInstruction + '_) {
+    type DeserializeContext<'a>
+        = Box<dyn DynDeserializeContext + 'a>
+    where
+        Self: 'a;
+
+    fn target(&self) -> &[u8] {
+        self.dyn_target()
+    }
+
+    fn content(&self) -> &[u8] {
+        self.dyn_content()
+    }
+
+    fn context(&self) -> Self::DeserializeContext<'_> {
+        self.dyn_context()
+    }
+}
+
+impl XmlDeclaration for &(dyn DynXmlDeclaration + '_) {
+    type DeserializeContext<'a>
+        = Box<dyn DynDeserializeContext + 'a>
+    where
+        Self: 'a;
+
+    fn version(&self) -> &[u8] {
+        self.dyn_version()
+    }
+
+    fn encoding(&self) -> Option<&[u8]> {
+        self.dyn_encoding()
+    }
+
+    fn standalone(&self) -> Option<&[u8]> {
+        self.dyn_standalone()
+    }
+
+    fn context(&self) -> Self::DeserializeContext<'_> {
+        self.dyn_context()
+    }
+}
+
+macro_rules! impl_attributes_access {
+    ($ty:ty) => {
+        impl<'de> AttributesAccess<'de> for Box<$ty> {
+            type Error = DynError;
+
+            type SubAccess<'a>
+                = Box<dyn DynAttributesAccess<'de> + 'a>
+            where
+                Self: 'a;
+
+            fn next_attribute_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
+            where
+                S: DeserializeSeed<'de>,
+            {
+                let mut seed = SeedSlot::new(seed);
+                self.dyn_next_attribute_seed(&mut seed)?;
+                Ok(seed.value)
+            }
+
+            fn sub_access(&mut self) -> Result<Self::SubAccess<'_>, Self::Error> {
+                self.as_mut().dyn_sub_access()
+            }
+
+            fn peek_name(&mut self) -> Result<Option<ExpandedNameBuf>, Self::Error> {
+                self.as_mut().dyn_peek_name()
+            }
+
+            fn find_attribute_seed<S>(
+                &mut self,
+                name: ExpandedName<'_>,
+                seed: S,
+            ) -> Result<Option<S::Value>, Self::Error>
+            where
+                S: DeserializeSeed<'de>,
+            {
+                let mut seed = SeedSlot::new(seed);
+                self.dyn_find_attribute_seed(name, &mut seed)?;
+                Ok(seed.value)
+            }
+
+            fn ancestor_attribute(
+                &self,
+                ancestor: &ExpandedName<'_>,
+                attribute: &ExpandedName<'_>,
+            ) -> Option<Cow<'_, str>> {
+                self.as_ref().dyn_ancestor_attribute(ancestor, attribute)
+            }
+        }
+    };
+}
+
+impl_attributes_access!(dyn DynAttributesAccess<'de> + '_);
+impl_attributes_access!(dyn DynElementAccess<'de> + '_);
+
+impl<'de: 'a, 'a> ElementAccess<'de> for Box<dyn DynElementAccess<'de> + 'a> {
+    type ChildrenAccess = Box<dyn DynSeqAccess<'de> + 'a>;
+
+    type DeserializeContext<'b>
+        = Box<dyn DynDeserializeContext + 'b>
+    where
+        Self: 'b;
+
+    fn name(&self) -> ExpandedName<'_> {
+        self.as_ref().dyn_name()
+    }
+
+    fn prefix(&self) -> Option<&Prefix> {
+        self.as_ref().dyn_prefix()
+    }
+
+    fn namespace_declarations(&self) -> Vec<(PrefixBuf, XmlNamespaceBuf)> {
+        self.as_ref().dyn_namespace_declarations()
+    }
+
+    fn children(self) -> Result<Self::ChildrenAccess, Self::Error> {
+        self.dyn_children()
+    }
+
+    fn context(&self) -> Self::DeserializeContext<'_> {
+        self.as_ref().dyn_context()
+    }
+}
+
+impl<'de> AttributeAccess<'de> for Box<dyn DynAttributeAccess<'de> + '_> {
+    type Error = DynError;
+
+    fn name(&self) -> ExpandedName<'_> {
+        self.as_ref().dyn_name()
+    }
+
+    fn prefix(&self) -> Option<&Prefix> {
+        self.as_ref().dyn_prefix()
+    }
+
+    fn value<T>(self) -> Result<T, Self::Error>
+    where
+        T: Deserialize<'de>,
+    {
+        let value = self.dyn_value()?;
+        T::deserialize(AttributeValue {
+            value,
+            used_up: false,
+        })
+    }
+}
+
+impl<'de> SeqAccess<'de> for Box<dyn DynSeqAccess<'de> + '_> {
+    type Error = DynError;
+
+    type SubAccess<'g>
+        = Box<dyn DynSeqAccess<'de> + 'g>
+    where
+        Self: 'g;
+
+    fn next_element_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        let mut seed = SeedSlot::new(seed);
+        self.dyn_next_element_seed(&mut seed)?;
+        Ok(seed.value)
+    }
+
+    fn next_element_seq_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        let mut seed = SeedSlot::new(seed);
+        self.dyn_next_element_seq_seed(&mut seed)?;
+        Ok(seed.value)
+    }
+
+    fn sub_access(&mut self) -> Result<Self::SubAccess<'_>, Self::Error> {
+        self.as_mut().dyn_sub_access()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.as_ref().dyn_size_hint()
+    }
+
+    fn peek_element(&mut self) -> Result<Option<PeekedNode>, Self::Error> {
+        self.as_mut().dyn_peek_element()
+    }
+
+    fn preserves_whitespace(&self) -> bool {
+        self.as_ref().dyn_preserves_whitespace()
+    }
+}
+
+/// Holds a [`Visitor`] until it is called, and the value it produced afterwards.
+struct VisitorSlot<V, T> {
+    visitor: Option<V>,
+    value: Option<T>,
+}
+
+impl<V, T> VisitorSlot<V, T> {
+    fn new(visitor: V) -> Self {
+        Self {
+            visitor: Some(visitor),
+            value: None,
+        }
+    }
+
+    fn visit(&mut self, visit: impl FnOnce(V) -> Result<T, DynError>) -> Result<(), DynError> {
+        let visitor = self.visitor.take().ok_or_else(DynError::already_used)?;
+        self.value = Some(visit(visitor)?);
+        Ok(())
+    }
+}
+
+impl<'de, V: Visitor<'de>> DynVisitor<'de> for VisitorSlot<V, V::Value> {
+    fn dyn_expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match &self.visitor {
+            Some(visitor) => visitor.expecting(formatter),
+            None => formatter.write_str("nothing"),
+        }
+    }
+
+    fn dyn_visit_text(&mut self, value: Box<dyn DynXmlText<'de> + '_>) -> Result<(), DynError> {
+        self.visit(|visitor| visitor.visit_text(value))
+    }
+
+    fn dyn_visit_cdata(&mut self, value: Box<dyn DynXmlText<'de> + '_>) -> Result<(), DynError> {
+        self.visit(|visitor| visitor.visit_cdata(value))
+    }
+
+    fn dyn_visit_element(
+        &mut self,
+        element: Box<dyn DynElementAccess<'de> + '_>,
+    ) -> Result<(), DynError> {
+        self.visit(|visitor| visitor.visit_element(element))
+    }
+
+    fn dyn_visit_attribute(
+        &mut self,
+        attribute: Box<dyn DynAttributeAccess<'de> + '_>,
+    ) -> Result<(), DynError> {
+        self.visit(|visitor| visitor.visit_attribute(attribute))
+    }
+
+    fn dyn_visit_seq(&mut self, sequence: Box<dyn DynSeqAccess<'de> + '_>) -> Result<(), DynError> {
+        self.visit(|visitor| visitor.visit_seq(sequence))
+    }
+
+    fn dyn_visit_pi(&mut self, pi: &dyn DynXmlProcessingInstruction) -> Result<(), DynError> {
+        self.visit(|visitor| visitor.visit_pi(pi))
+    }
+
+    fn dyn_visit_decl(&mut self, declaration: &dyn DynXmlDeclaration) -> Result<(), DynError> {
+        self.visit(|visitor| visitor.visit_decl(declaration))
+    }
+
+    fn dyn_visit_comment(
+        &mut self,
+        comment: Box<dyn DynXmlBytes<'de> + '_>,
+    ) -> Result<(), DynError> {
+        self.visit(|visitor| visitor.visit_comment(comment))
+    }
+
+    fn dyn_visit_doctype(
+        &mut self,
+        doctype: Box<dyn DynXmlBytes<'de> + '_>,
+    ) -> Result<(), DynError> {
+        self.visit(|visitor| visitor.visit_doctype(doctype))
+    }
+
+    fn dyn_visit_none(&mut self) -> Result<(), DynError> {
+        self.visit(|visitor| visitor.visit_none())
+    }
+}
+
+/// Holds a [`DeserializeSeed`] until it is used, and the value it produced afterwards.
+struct SeedSlot<S, T> {
+    seed: Option<S>,
+    value: Option<T>,
+}
+
+impl<S, T> SeedSlot<S, T> {
+    fn new(seed: S) -> Self {
+        Self {
+            seed: Some(seed),
+            value: None,
+        }
+    }
+}
+
+impl<'de, S: DeserializeSeed<'de>> DynDeserializeSeed<'de> for SeedSlot<S, S::Value> {
+    fn dyn_deserialize(
+        &mut self,
+        deserializer: &mut dyn DynDeserializer<'de>,
+    ) -> Result<(), DynError> {
+        let seed = self.seed.take().ok_or_else(DynError::already_used)?;
+        self.value = Some(seed.deserialize(deserializer)?);
+        Ok(())
+    }
+
+    fn dyn_deserialize_seq(
+        &mut self,
+        deserializer: &mut dyn DynDeserializer<'de>,
+    ) -> Result<(), DynError> {
+        let seed = self.seed.take().ok_or_else(DynError::already_used)?;
+        self.value = Some(seed.deserialize_seq(deserializer)?);
+        Ok(())
+    }
+}
+
+/// The value of an attribute visited through a [`DynAttributeAccess`], which is deserialized as text.
+struct AttributeValue<'de> {
+    value: Cow<'de, str>,
+    used_up: bool,
+}
+
+impl<'de> XmlText<'de> for AttributeValue<'de> {
+    type DeserializeContext<'a>
+        = ()
+    where
+        Self: 'a;
+
+    fn into_bytes(self) -> Cow<'de, [u8]> {
+        match self.value {
+            Cow::Borrowed(value) => Cow::Borrowed(value.as_bytes()),
+            Cow::Owned(value) => Cow::Owned(value.into_bytes()),
+        }
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        self.value.as_bytes()
+    }
+
+    fn into_string(self) -> Cow<'de, str> {
+        self.value
+    }
+
+    fn as_str(&self) -> &str {
+        &self.value
+    }
+
+    fn context(&self) -> Self::DeserializeContext<'_> {}
+}
+
+impl<'de> SeqAccess<'de> for AttributeValue<'de> {
+    type Error = DynError;
+
+    type SubAccess<'g>
+        = AttributeValue<'de>
+    where
+        Self: 'g;
+
+    fn next_element_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        if self.used_up {
+            return Ok(None);
+        }
+
+        let value = seed.deserialize(AttributeValue {
+            value: self.value.clone(),
+            used_up: false,
+        })?;
+        self.used_up = true;
+        Ok(Some(value))
+    }
+
+    fn next_element_seq_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        self.next_element_seed(seed)
+    }
+
+    fn sub_access(&mut self) -> Result<Self::SubAccess<'_>, Self::Error> {
+        Ok(AttributeValue {
+            value: self.value.clone(),
+            used_up: self.used_up,
+        })
+    }
+}
+
+impl<'de> Deserializer<'de> for AttributeValue<'de> {
+    type Error = DynError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_text(self)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_seq(self)
+    }
+}
+
+/// Captures the value of an attribute of the underlying deserializer as text.
+struct CapturedValue<'de>(Cow<'de, str>);
+
+impl<'de> Deserialize<'de> for CapturedValue<'de> {
+    fn deserialize<D: Deserializer<'de>>(reader: D) -> Result<Self, D::Error> {
+        struct CapturedValueVisitor;
+
+        impl<'de> Visitor<'de> for CapturedValueVisitor {
+            type Value = CapturedValue<'de>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an attribute value")
+            }
+
+            fn visit_text<E, V>(self, value: V) -> Result<Self::Value, E>
+            where
+                E: Error,
+                V: XmlText<'de>,
+            {
+                Ok(CapturedValue(value.into_string()))
+            }
+
+            fn visit_cdata<E, V>(self, value: V) -> Result<Self::Value, E>
+            where
+                E: Error,
+                V: XmlCData<'de>,
+            {
+                Ok(CapturedValue(value.into_string()))
+            }
+        }
+
+        reader.deserialize_any(CapturedValueVisitor)
+    }
+}
+
+/// Wraps a [`Deserializer`] so that it can be used as a [`DynDeserializer`].
+///
+/// A deserializer is consumed by deserializing one value. Once that is done, [`ErasedDeserializer::finish`] turns the result back into the error type of the deserializer.
+pub struct ErasedDeserializer<'de, D: Deserializer<'de>> {
+    deserializer: Option<D>,
+    error: Option<D::Error>,
+    marker: PhantomData<&'de ()>,
+}
+
+impl<'de, D: Deserializer<'de>> ErasedDeserializer<'de, D> {
+    /// Wraps a deserializer.
+    pub fn new(deserializer: D) -> Self {
+        Self {
+            deserializer: Some(deserializer),
+            error: None,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the value deserialized through this [`DynDeserializer`], with errors converted to the error type of the underlying deserializer.
+    ///
+    /// Errors of the underlying deserializer are returned as they are, while other errors are converted using the matching constructor of [`Error`].
+    pub fn finish<T>(mut self, result: Result<T, DynError>) -> Result<T, D::Error> {
+        result.map_err(|err| restore(&mut self.error, err))
+    }
+
+    fn deserialize(
+        &mut self,
+        deserialize: impl FnOnce(D) -> Result<(), D::Error>,
+    ) -> Result<(), DynError> {
+        let deserializer = self
+            .deserializer
+            .take()
+            .ok_or_else(DynError::already_used)?;
+        deserialize(deserializer).map_err(|err| stash(&mut self.error, err))
+    }
+}
+
+impl<'de, D: Deserializer<'de>> DynDeserializer<'de> for ErasedDeserializer<'de, D> {
+    fn dyn_deserialize_any(&mut self, visitor: &mut dyn DynVisitor<'de>) -> Result<(), DynError> {
+        self.deserialize(|deserializer| deserializer.deserialize_any(VisitorAdapter(visitor)))
+    }
+
+    fn dyn_deserialize_seq(&mut self, visitor: &mut dyn DynVisitor<'de>) -> Result<(), DynError> {
+        self.deserialize(|deserializer| deserializer.deserialize_seq(VisitorAdapter(visitor)))
+    }
+}
+
+/// Lets a [`DynVisitor`] be passed to the underlying deserializer.
+struct VisitorAdapter<'v, 'de>(&'v mut dyn DynVisitor<'de>);
+
+impl<'de> Visitor<'de> for VisitorAdapter<'_, 'de> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.0.dyn_expecting(formatter)
+    }
+
+    fn visit_text<E, V>(self, value: V) -> Result<Self::Value, E>
+    where
+        E: Error,
+        V: XmlText<'de>,
+    {
+        self.0
+            .dyn_visit_text(Box::new(ErasedText(value)))
+            .map_err(DynError::into_error)
+    }
+
+    fn visit_cdata<E, V>(self, value: V) -> Result<Self::Value, E>
+    where
+        E: Error,
+        V: XmlCData<'de>,
+    {
+        self.0
+            .dyn_visit_cdata(Box::new(ErasedCData(value)))
+            .map_err(DynError::into_error)
+    }
+
+    fn visit_element<A>(self, element: A) -> Result<Self::Value, A::Error>
+    where
+        A: ElementAccess<'de>,
+    {
+        let mut error = None;
+        let result = self.0.dyn_visit_element(Box::new(ErasedAccess {
+            access: element,
+            error: &mut error,
+        }));
+        result.map_err(|err| restore(&mut error, err))
+    }
+
+    fn visit_attribute<A>(self, attribute: A) -> Result<Self::Value, A::Error>
+    where
+        A: AttributeAccess<'de>,
+    {
+        let mut error = None;
+        let result = self.0.dyn_visit_attribute(Box::new(ErasedAccess {
+            access: attribute,
+            error: &mut error,
+        }));
+        result.map_err(|err| restore(&mut error, err))
+    }
+
+    fn visit_seq<S>(self, sequence: S) -> Result<Self::Value, S::Error>
+    where
+        S: SeqAccess<'de>,
+    {
+        let mut error = None;
+        let result = self.0.dyn_visit_seq(Box::new(ErasedAccess {
+            access: sequence,
+            error: &mut error,
+        }));
+        result.map_err(|err| restore(&mut error, err))
+    }
+
+    fn visit_pi<E, V>(self, pi: V) -> Result<Self::Value, E>
+    where
+        E: Error,
+        V: XmlProcessingInstruction,
+    {
+        self.0
+            .dyn_visit_pi(&ErasedProcessingInstruction(pi))
+            .map_err(DynError::into_error)
+    }
+
+    fn visit_decl<E, V>(self, declaration: V) -> Result<Self::Value, E>
+    where
+        E: Error,
+        V: XmlDeclaration,
+    {
+        self.0
+            .dyn_visit_decl(&ErasedDeclaration(declaration))
+            .map_err(DynError::into_error)
+    }
+
+    fn visit_comment<E, V>(self, comment: V) -> Result<Self::Value, E>
+    where
+        E: Error,
+        V: XmlComment<'de>,
+    {
+        self.0
+            .dyn_visit_comment(Box::new(ErasedComment(comment)))
+            .map_err(DynError::into_error)
+    }
+
+    fn visit_doctype<E, V>(self, doctype: V) -> Result<Self::Value, E>
+    where
+        E: Error,
+        V: XmlDoctype<'de>,
+    {
+        self.0
+            .dyn_visit_doctype(Box::new(ErasedDoctype(doctype)))
+            .map_err(DynError::into_error)
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        self.0.dyn_visit_none().map_err(DynError::into_error)
+    }
+}
+
+/// Lets a [`DynDeserializeSeed`] be passed to the underlying deserializer.
+struct SeedAdapter<'s, 'de>(&'s mut dyn DynDeserializeSeed<'de>);
+
+impl<'de> DeserializeSeed<'de> for SeedAdapter<'_, 'de> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, reader: D) -> Result<Self::Value, D::Error> {
+        let mut deserializer = ErasedDeserializer::new(reader);
+        let result = self.0.dyn_deserialize(&mut deserializer);
+        deserializer.finish(result)
+    }
+
+    fn deserialize_seq<D: Deserializer<'de>>(self, reader: D) -> Result<Self::Value, D::Error> {
+        let mut deserializer = ErasedDeserializer::new(reader);
+        let result = self.0.dyn_deserialize_seq(&mut deserializer);
+        deserializer.finish(result)
+    }
+}
+
+/// Wraps an access of the underlying deserializer, keeping its errors in `error` so that they can be restored once the access has been used.
+struct ErasedAccess<'e, A, E> {
+    access: A,
+    error: &'e mut Option<E>,
+}
+
+impl<'de, A: AttributesAccess<'de>> DynAttributesAccess<'de> for ErasedAccess<'_, A, A::Error> {
+    fn dyn_next_attribute_seed(
+        &mut self,
+        seed: &mut dyn DynDeserializeSeed<'de>,
+    ) -> Result<(), DynError> {
+        self.access
+            .next_attribute_seed(SeedAdapter(seed))
+            .map(drop)
+            .map_err(|err| stash(self.error, err))
+    }
+
+    fn dyn_sub_access<'a>(
+        &'a mut self,
+    ) -> Result<Box<dyn DynAttributesAccess<'de> + 'a>, DynError> {
+        match self.access.sub_access() {
+            Ok(access) => Ok(Box::new(ErasedAccess {
+                access,
+                error: &mut *self.error,
+            })),
+            Err(err) => Err(stash(self.error, err)),
+        }
+    }
+
+    fn dyn_peek_name(&mut self) -> Result<Option<ExpandedNameBuf>, DynError> {
+        self.access
+            .peek_name()
+            .map_err(|err| stash(self.error, err))
+    }
+
+    fn dyn_find_attribute_seed(
+        &mut self,
+        name: ExpandedName<'_>,
+        seed: &mut dyn DynDeserializeSeed<'de>,
+    ) -> Result<(), DynError> {
+        self.access
+            .find_attribute_seed(name, SeedAdapter(seed))
+            .map(drop)
+            .map_err(|err| stash(self.error, err))
+    }
+
+    fn dyn_ancestor_attribute(
+        &self,
+        ancestor: &ExpandedName<'_>,
+        attribute: &ExpandedName<'_>,
+    ) -> Option<Cow<'_, str>> {
+        self.access.ancestor_attribute(ancestor, attribute)
+    }
+}
+
+impl<'de, A: ElementAccess<'de>> DynElementAccess<'de> for ErasedAccess<'_, A, A::Error> {
+    fn dyn_name(&self) -> ExpandedName<'_> {
+        self.access.name()
+    }
+
+    fn dyn_prefix(&self) -> Option<&Prefix> {
+        self.access.prefix()
+    }
+
+    fn dyn_namespace_declarations(&self) -> Vec<(PrefixBuf, XmlNamespaceBuf)> {
+        self.access.namespace_declarations()
+    }
+
+    fn dyn_children<'a>(self: Box<Self>) -> Result<Box<dyn DynSeqAccess<'de> + 'a>, DynError>
+    where
+        Self: 'a,
+        'de: 'a,
+    {
+        let ErasedAccess { access, error } = *self;
+        match access.children() {
+            Ok(access) => Ok(Box::new(ErasedAccess { access, error })),
+            Err(err) => Err(stash(error, err)),
+        }
+    }
+
+    fn dyn_context(&self) -> Box<dyn DynDeserializeContext + '_> {
+        Box::new(ErasedContext(self.access.context()))
+    }
+}
+
+impl<'de, A: AttributeAccess<'de>> DynAttributeAccess<'de> for ErasedAccess<'_, A, A::Error> {
+    fn dyn_name(&self) -> ExpandedName<'_> {
+        self.access.name()
+    }
+
+    fn dyn_prefix(&self) -> Option<&Prefix> {
+        self.access.prefix()
+    }
+
+    fn dyn_value(self: Box<Self>) -> Result<Cow<'de, str>, DynError> {
+        let ErasedAccess { access, error } = *self;
+        access
+            .value::<CapturedValue<'de>>()
+            .map(|value| value.0)
+            .map_err(|err| stash(error, err))
+    }
+}
+
+impl<'de, A: SeqAccess<'de>> DynSeqAccess<'de> for ErasedAccess<'_, A, A::Error> {
+    fn dyn_next_element_seed(
+        &mut self,
+        seed: &mut dyn DynDeserializeSeed<'de>,
+    ) -> Result<(), DynError> {
+        self.access
+            .next_element_seed(SeedAdapter(seed))
+            .map(drop)
+            .map_err(|err| stash(self.error, err))
+    }
+
+    fn dyn_next_element_seq_seed(
+        &mut self,
+        seed: &mut dyn DynDeserializeSeed<'de>,
+    ) -> Result<(), DynError> {
+        self.access
+            .next_element_seq_seed(SeedAdapter(seed))
+            .map(drop)
+            .map_err(|err| stash(self.error, err))
+    }
+
+    fn dyn_sub_access<'a>(&'a mut self) -> Result<Box<dyn DynSeqAccess<'de> + 'a>, DynError>
+    where
+        'de: 'a,
+    {
+        match self.access.sub_access() {
+            Ok(access) => Ok(Box::new(ErasedAccess {
+                access,
+                error: &mut *self.error,
+            })),
+            Err(err) => Err(stash(self.error, err)),
+        }
+    }
+
+    fn dyn_size_hint(&self) -> Option<usize> {
+        self.access.size_hint()
+    }
+
+    fn dyn_peek_element(&mut self) -> Result<Option<PeekedNode>, DynError> {
+        self.access
+            .peek_element()
+            .map_err(|err| stash(self.error, err))
+    }
+
+    fn dyn_preserves_whitespace(&self) -> bool {
+        self.access.preserves_whitespace()
+    }
+}
+
+struct ErasedContext<C>(C);
+
+impl<C: DeserializeContext> DynDeserializeContext for ErasedContext<C> {
+    fn dyn_default_namespace(&self) -> Option<&XmlNamespace> {
+        self.0.default_namespace()
+    }
+
+    fn dyn_resolve_prefix(&self, prefix: &Prefix) -> Option<&XmlNamespace> {
+        self.0.resolve_prefix(prefix)
+    }
+
+    fn dyn_ancestor_attribute(
+        &self,
+        ancestor: &ExpandedName<'_>,
+        attribute: &ExpandedName<'_>,
+    ) -> Option<Cow<'_, str>> {
+        self.0.ancestor_attribute(ancestor, attribute)
+    }
+
+    fn dyn_namespaces_in_scope(&self) -> Vec<(PrefixBuf, XmlNamespaceBuf)> {
+        self.0.namespaces_in_scope()
+    }
+}
+
+struct ErasedText<V>(V);
+
+impl<'de, V: XmlText<'de>> DynXmlText<'de> for ErasedText<V> {
+    fn dyn_into_bytes(self: Box<Self>) -> Cow<'de, [u8]> {
+        self.0.into_bytes()
+    }
+
+    fn dyn_as_bytes(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+
+    fn dyn_into_string(self: Box<Self>) -> Cow<'de, str> {
+        self.0.into_string()
+    }
+
+    fn dyn_as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    fn dyn_context(&self) -> Box<dyn DynDeserializeContext + '_> {
+        Box::new(ErasedContext(self.0.context()))
+    }
+}
+
+struct ErasedCData<V>(V);
+
+impl<'de, V: XmlCData<'de>> DynXmlText<'de> for ErasedCData<V> {
+    fn dyn_into_bytes(self: Box<Self>) -> Cow<'de, [u8]> {
+        self.0.into_bytes()
+    }
+
+    fn dyn_as_bytes(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+
+    fn dyn_into_string(self: Box<Self>) -> Cow<'de, str> {
+        self.0.into_string()
+    }
+
+    fn dyn_as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    fn dyn_context(&self) -> Box<dyn DynDeserializeContext + '_> {
+        Box::new(ErasedContext(self.0.context()))
+    }
+}
+
+struct ErasedComment<V>(V);
+
+impl<'de, V: XmlComment<'de>> DynXmlBytes<'de> for ErasedComment<V> {
+    fn dyn_into_bytes(self: Box<Self>) -> Cow<'de, [u8]> {
+        self.0.into_bytes()
+    }
+
+    fn dyn_as_bytes(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+
+    fn dyn_context(&self) -> Box<dyn DynDeserializeContext + '_> {
+        Box::new(ErasedContext(self.0.context()))
+    }
+}
+
+struct ErasedDoctype<V>(V);
+
+impl<'de, V: XmlDoctype<'de>> DynXmlBytes<'de> for ErasedDoctype<V> {
+    fn dyn_into_bytes(self: Box<Self>) -> Cow<'de, [u8]> {
+        self.0.into_bytes()
+    }
+
+    fn dyn_as_bytes(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+
+    fn dyn_context(&self) -> Box<dyn DynDeserializeContext + '_> {
+        Box::new(ErasedContext(self.0.context()))
+    }
+}
+
+struct ErasedProcessingInstruction<V>(V);
+
+impl<V: XmlProcessingInstruction> DynXmlProcessingInstruction for ErasedProcessingInstruction<V> {
+    fn dyn_target(&self) -> &[u8] {
+        self.0.target()
+    }
+
+    fn dyn_content(&self) -> &[u8] {
+        self.0.content()
+    }
+
+    fn dyn_context(&self) -> Box<dyn DynDeserializeContext + '_> {
+        Box::new(ErasedContext(self.0.context()))
+    }
+}
+
+struct ErasedDeclaration<V>(V);
+
+impl<V: XmlDeclaration> DynXmlDeclaration for ErasedDeclaration<V> {
+    fn dyn_version(&self) -> &[u8] {
+        self.0.version()
+    }
+
+    fn dyn_encoding(&self) -> Option<&[u8]> {
+        self.0.encoding()
+    }
+
+    fn dyn_standalone(&self) -> Option<&[u8]> {
+        self.0.standalone()
+    }
+
+    fn dyn_context(&self) -> Box<dyn DynDeserializeContext + '_> {
+        Box::new(ErasedContext(self.0.context()))
+    }
+}
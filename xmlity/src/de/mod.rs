@@ -4,9 +4,17 @@ use std::{
     borrow::Cow,
     error::Error as StdError,
     fmt::{self, Debug, Display},
+    marker::PhantomData,
 };
 
-use crate::{ExpandedName, Prefix, XmlNamespace};
+use crate::{ExpandedName, ExpandedNameBuf, Prefix, PrefixBuf, XmlNamespace, XmlNamespaceBuf};
+
+mod dynamic;
+pub use dynamic::{
+    DynAttributeAccess, DynAttributesAccess, DynDeserializeContext, DynDeserializeSeed,
+    DynDeserializer, DynElementAccess, DynError, DynSeqAccess, DynVisitor, DynXmlBytes,
+    DynXmlDeclaration, DynXmlProcessingInstruction, DynXmlText, ErasedDeserializer,
+};
 
 /// A trait for errors that can be returned by a [`Deserializer`].
 pub trait Error: Sized + StdError {
@@ -27,6 +35,16 @@ pub trait Error: Sized + StdError {
     /// Error for when a type has no possible variants to deserialize into.
     fn no_possible_variant(ident: &str) -> Self;
 
+    /// Error for when none of the variants of a type matches, listing what the variants expect, such as the names of elements or the text values.
+    ///
+    /// By default, this is a [custom](Error::custom) error with the expected names in its message.
+    fn no_matching_variant(ident: &str, expected: &[&str]) -> Self {
+        Self::custom(format_args!(
+            "No variant of {ident} matches, expected one of: {}",
+            expected.join(", ")
+        ))
+    }
+
     /// Error for when a type is missing data that is required to deserialize it.
     fn missing_data() -> Self;
 
@@ -38,47 +56,96 @@ pub trait Error: Sized + StdError {
 }
 
 /// An enum representing the unexpected type of data that was encountered.
-#[derive(Debug, thiserror::Error)]
+///
+/// Elements, attributes and processing instructions carry their names, so that error handling code can react to what was encountered without parsing error messages.
+///
+/// ```
+/// use xmlity::{de::Unexpected, ExpandedNameBuf};
+///
+/// let unexpected = Unexpected::Attribute {
+///     name: Box::new("id".parse::<ExpandedNameBuf>().unwrap()),
+/// };
+///
+/// assert_eq!(unexpected.name().unwrap().local_name().as_str(), "id");
+/// assert_eq!(unexpected.to_string(), "attribute id");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum Unexpected {
     /// A text node.
-    #[error("text")]
     Text,
     /// A CDATA section.
-    #[error("cdata")]
     CData,
     /// A sequence of XML values.
-    #[error("sequence")]
     Seq,
-    /// An element start.
-    #[error("element start")]
-    ElementStart,
+    /// An element.
+    Element {
+        /// The name of the element.
+        name: Box<ExpandedNameBuf>,
+    },
     /// An element end.
-    #[error("element end")]
     ElementEnd,
     /// An attribute.
-    #[error("attribute")]
-    Attribute,
+    Attribute {
+        /// The name of the attribute.
+        name: Box<ExpandedNameBuf>,
+    },
     /// A comment.
-    #[error("comment")]
     Comment,
     /// A declaration.
-    #[error("declaration")]
     Decl,
     /// A processing instruction.
-    #[error("processing instruction")]
-    PI,
+    PI {
+        /// The target of the processing instruction.
+        target: String,
+    },
     /// A doctype.
-    #[error("doctype")]
     DocType,
     /// End of file.
-    #[error("eof")]
     Eof,
     /// Nothing.
-    #[error("none")]
     None,
 }
 
+impl fmt::Display for Unexpected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Text => f.write_str("text"),
+            Self::CData => f.write_str("cdata"),
+            Self::Seq => f.write_str("sequence"),
+            Self::Element { name } => write!(f, "element {name}"),
+            Self::ElementEnd => f.write_str("element end"),
+            Self::Attribute { name } => write!(f, "attribute {name}"),
+            Self::Comment => f.write_str("comment"),
+            Self::Decl => f.write_str("declaration"),
+            Self::PI { target } => write!(f, "processing instruction {target}"),
+            Self::DocType => f.write_str("doctype"),
+            Self::Eof => f.write_str("eof"),
+            Self::None => f.write_str("none"),
+        }
+    }
+}
+
+impl std::error::Error for Unexpected {}
+
+impl Unexpected {
+    /// The name of the element or attribute, if an element or attribute was encountered.
+    pub fn name(&self) -> Option<&ExpandedNameBuf> {
+        match self {
+            Unexpected::Element { name } | Unexpected::Attribute { name } => Some(name),
+            _ => None,
+        }
+    }
+
+    /// The target of the processing instruction, if a processing instruction was encountered.
+    pub fn target(&self) -> Option<&str> {
+        match self {
+            Unexpected::PI { target } => Some(target),
+            _ => None,
+        }
+    }
+}
+
 /// Trait that lets you access the namespaces declared on an XML node.
 pub trait DeserializeContext {
     /// Get the default namespace.
@@ -91,6 +158,87 @@ pub trait DeserializeContext {
     fn external_data<T>(&self) -> Option<&T>
     where
         T: core::any::Any;
+
+    /// Returns the value of an attribute of the closest enclosing element with the given name, which includes the element the context belongs to.
+    ///
+    /// This lets nested types read attributes written on one of their ancestors, such as the `lang` attribute of `<html>`. Deserializers that do not keep track of the ancestors of an element return [`None`], which is the default.
+    fn ancestor_attribute(
+        &self,
+        ancestor: &ExpandedName<'_>,
+        attribute: &ExpandedName<'_>,
+    ) -> Option<Cow<'_, str>> {
+        let _ = (ancestor, attribute);
+        None
+    }
+
+    /// Returns the namespaces in scope, starting with the ones declared closest to the context. The default namespace is bound to [`Prefix::BLANK`].
+    ///
+    /// Namespaces redeclared closer to the context are only returned once, and an undeclared default namespace (`xmlns=""`) is not returned. Deserializers that do not keep track of namespace scopes return nothing, which is the default.
+    fn namespaces_in_scope(&self) -> Vec<(PrefixBuf, XmlNamespaceBuf)> {
+        Vec::new()
+    }
+}
+
+/// A hook that lets a deserializer map the names it encounters to canonical names before they are matched against the names types expect.
+///
+/// This makes it possible to consume inputs with non-canonical names (for example legacy HTML-like documents with inconsistent casing) using types written against the canonical names, without adding aliases to every type.
+pub trait NameResolver {
+    /// Maps the name of an encountered element to its canonical name.
+    fn resolve_element_name<'a>(&'a self, name: ExpandedName<'a>) -> ExpandedName<'a> {
+        name
+    }
+
+    /// Maps the name of an encountered attribute to its canonical name.
+    fn resolve_attribute_name<'a>(&'a self, name: ExpandedName<'a>) -> ExpandedName<'a> {
+        name
+    }
+}
+
+/// A [`NameResolver`] that matches the local names of elements and attributes against a set of canonical names, ignoring ASCII case. Namespaces must still match exactly.
+///
+/// Names that do not match any of the canonical names are left as-is.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CaseInsensitiveNameResolver {
+    names: Vec<ExpandedNameBuf>,
+}
+
+impl CaseInsensitiveNameResolver {
+    /// Creates a new [`CaseInsensitiveNameResolver`] from the given canonical names.
+    pub fn new<I: IntoIterator<Item = ExpandedNameBuf>>(names: I) -> Self {
+        Self {
+            names: names.into_iter().collect(),
+        }
+    }
+
+    /// Adds a canonical name to the resolver.
+    pub fn with_name(mut self, name: ExpandedNameBuf) -> Self {
+        self.names.push(name);
+        self
+    }
+
+    fn resolve<'a>(&'a self, name: ExpandedName<'a>) -> ExpandedName<'a> {
+        self.names
+            .iter()
+            .map(ExpandedNameBuf::as_ref)
+            .find(|canonical| {
+                canonical.namespace() == name.namespace()
+                    && canonical
+                        .local_name()
+                        .as_str()
+                        .eq_ignore_ascii_case(name.local_name().as_str())
+            })
+            .unwrap_or(name)
+    }
+}
+
+impl NameResolver for CaseInsensitiveNameResolver {
+    fn resolve_element_name<'a>(&'a self, name: ExpandedName<'a>) -> ExpandedName<'a> {
+        self.resolve(name)
+    }
+
+    fn resolve_attribute_name<'a>(&'a self, name: ExpandedName<'a>) -> ExpandedName<'a> {
+        self.resolve(name)
+    }
 }
 
 /// Trait that lets you access the attributes of an XML node.
@@ -105,10 +253,66 @@ pub trait AttributesAccess<'de> {
     /// Get the next attribute.
     fn next_attribute<T>(&mut self) -> Result<Option<T>, Self::Error>
     where
-        T: Deserialize<'de>;
+        T: Deserialize<'de>,
+    {
+        self.next_attribute_seed(PhantomData)
+    }
+
+    /// Get the next attribute using a [`DeserializeSeed`].
+    fn next_attribute_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
+    where
+        S: DeserializeSeed<'de>;
 
     /// Get a sub access to the attributes.
     fn sub_access(&mut self) -> Result<Self::SubAccess<'_>, Self::Error>;
+
+    /// Returns the name of the next attribute without consuming it, or [`None`] if there are no more attributes.
+    ///
+    /// The default implementation returns an error, as not every attribute access can be peeked.
+    fn peek_name(&mut self) -> Result<Option<ExpandedNameBuf>, Self::Error> {
+        Err(Self::Error::custom(
+            "these attributes do not support peeking",
+        ))
+    }
+
+    /// Finds the attribute with the given name and deserializes it, or returns [`None`] if the element has no such attribute.
+    ///
+    /// The lookup does not depend on the order of the attributes, and does not affect which attribute is returned by [`AttributesAccess::next_attribute`].
+    fn find_attribute<T>(&mut self, name: ExpandedName<'_>) -> Result<Option<T>, Self::Error>
+    where
+        T: Deserialize<'de>,
+    {
+        self.find_attribute_seed(name, PhantomData)
+    }
+
+    /// Finds the attribute with the given name and deserializes it using a [`DeserializeSeed`].
+    ///
+    /// The default implementation returns an error, as not every attribute access supports lookup by name.
+    fn find_attribute_seed<S>(
+        &mut self,
+        name: ExpandedName<'_>,
+        seed: S,
+    ) -> Result<Option<S::Value>, Self::Error>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        let _ = (name, seed);
+        Err(Self::Error::custom(
+            "these attributes do not support lookup by name",
+        ))
+    }
+
+    /// Returns the value of an attribute of the closest enclosing element with the given name, as described by [`DeserializeContext::ancestor_attribute`].
+    ///
+    /// This makes the lookup available where only the attributes are accessible, such as in [`DeserializationGroupBuilder::contribute_attributes`]. The default implementation returns [`None`].
+    fn ancestor_attribute(
+        &self,
+        ancestor: &ExpandedName<'_>,
+        attribute: &ExpandedName<'_>,
+    ) -> Option<Cow<'_, str>> {
+        let _ = (ancestor, attribute);
+        None
+    }
 }
 
 impl<'de, T: AttributesAccess<'de>> AttributesAccess<'de> for &mut T {
@@ -125,9 +329,39 @@ impl<'de, T: AttributesAccess<'de>> AttributesAccess<'de> for &mut T {
         (*self).next_attribute()
     }
 
+    fn next_attribute_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        (*self).next_attribute_seed(seed)
+    }
+
     fn sub_access(&mut self) -> Result<Self::SubAccess<'_>, Self::Error> {
         (*self).sub_access()
     }
+
+    fn peek_name(&mut self) -> Result<Option<ExpandedNameBuf>, Self::Error> {
+        (*self).peek_name()
+    }
+
+    fn find_attribute_seed<S>(
+        &mut self,
+        name: ExpandedName<'_>,
+        seed: S,
+    ) -> Result<Option<S::Value>, Self::Error>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        (*self).find_attribute_seed(name, seed)
+    }
+
+    fn ancestor_attribute(
+        &self,
+        ancestor: &ExpandedName<'_>,
+        attribute: &ExpandedName<'_>,
+    ) -> Option<Cow<'_, str>> {
+        (**self).ancestor_attribute(ancestor, attribute)
+    }
 }
 
 /// A trait for accessing properties of an element. This is the first stage of element deserialization, where the element's name and attributes are accessed. The second stage is accessing the element's children, which is done by calling [`ElementAccess::children`].
@@ -143,6 +377,20 @@ pub trait ElementAccess<'de>: AttributesAccess<'de> {
     /// Returns the name of the element.
     fn name(&self) -> ExpandedName<'_>;
 
+    /// Returns the prefix the element was written with, where [`Prefix::BLANK`] means that it was written without a prefix.
+    ///
+    /// Deserializers only report this if they are set up to preserve prefixes, otherwise [`None`] is returned.
+    fn prefix(&self) -> Option<&Prefix> {
+        None
+    }
+
+    /// Returns the namespace declarations written on the element, in document order. The default namespace is declared with [`Prefix::BLANK`].
+    ///
+    /// Like [`ElementAccess::prefix`], deserializers only report this if they are set up to preserve prefixes, otherwise nothing is returned. Declarations of an empty default namespace (`xmlns=""`) are not reported.
+    fn namespace_declarations(&self) -> Vec<(PrefixBuf, XmlNamespaceBuf)> {
+        Vec::new()
+    }
+
     /// Returns an accessor for the element's children.
     fn children(self) -> Result<Self::ChildrenAccess, Self::Error>;
 
@@ -174,6 +422,13 @@ pub trait AttributeAccess<'de> {
     /// Returns the name of the attribute.
     fn name(&self) -> ExpandedName<'_>;
 
+    /// Returns the prefix the attribute was written with, where [`Prefix::BLANK`] means that it was written without a prefix.
+    ///
+    /// Deserializers only report this if they are set up to preserve prefixes, otherwise [`None`] is returned.
+    fn prefix(&self) -> Option<&Prefix> {
+        None
+    }
+
     /// Deserializes the value of the attribute.
     fn value<T>(self) -> Result<T, Self::Error>
     where
@@ -209,15 +464,82 @@ pub trait SeqAccess<'de> {
     /// Gets the next element in the sequence.
     fn next_element<T>(&mut self) -> Result<Option<T>, Self::Error>
     where
-        T: Deserialize<'de>;
+        T: Deserialize<'de>,
+    {
+        self.next_element_seed(PhantomData)
+    }
 
     /// Gets the next element by trying to deserialize it as a sequence.
     fn next_element_seq<T>(&mut self) -> Result<Option<T>, Self::Error>
     where
-        T: Deserialize<'de>;
+        T: Deserialize<'de>,
+    {
+        self.next_element_seq_seed(PhantomData)
+    }
+
+    /// Gets the next element in the sequence using a [`DeserializeSeed`].
+    fn next_element_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
+    where
+        S: DeserializeSeed<'de>;
+
+    /// Gets the next element by trying to deserialize it as a sequence using a [`DeserializeSeed`].
+    fn next_element_seq_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
+    where
+        S: DeserializeSeed<'de>;
 
     /// Gets the sub-access for the current sequence access.
     fn sub_access(&mut self) -> Result<Self::SubAccess<'_>, Self::Error>;
+
+    /// Returns the number of remaining elements in the sequence, if it is known.
+    fn size_hint(&self) -> Option<usize> {
+        None
+    }
+
+    /// Describes the next element in the sequence without consuming it, or returns [`None`] if the sequence has no more elements.
+    ///
+    /// This allows deserializers to decide what to deserialize next, for example by element name, instead of trying each alternative in turn. The default implementation returns an error, as not every sequence can be peeked.
+    fn peek_element(&mut self) -> Result<Option<PeekedNode>, Self::Error> {
+        Err(Self::Error::custom(
+            "this sequence does not support peeking",
+        ))
+    }
+
+    /// Returns whether whitespace in the sequence is significant, as it is inside an element with `xml:space="preserve"`.
+    ///
+    /// Types that skip whitespace between the nodes they expect, such as collections and types derived with `ignore_whitespace`, keep it when this returns `true`. Deserializers that do not keep track of `xml:space` return `false`, which is the default.
+    fn preserves_whitespace(&self) -> bool {
+        false
+    }
+}
+
+/// A description of the next node in a sequence, returned by [`SeqAccess::peek_element`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PeekedNode {
+    /// An element with the given name.
+    Element(ExpandedNameBuf),
+    /// A text node.
+    Text,
+    /// A CDATA section.
+    CData,
+    /// A comment.
+    Comment,
+    /// A processing instruction.
+    PI,
+    /// A declaration.
+    Decl,
+    /// A doctype.
+    DocType,
+}
+
+impl PeekedNode {
+    /// Returns the name of the element, if the node is an element.
+    pub fn element_name(&self) -> Option<ExpandedName<'_>> {
+        match self {
+            PeekedNode::Element(name) => Some(name.as_ref()),
+            _ => None,
+        }
+    }
 }
 
 impl<'de, T: SeqAccess<'de>> SeqAccess<'de> for &mut T {
@@ -241,9 +563,35 @@ impl<'de, T: SeqAccess<'de>> SeqAccess<'de> for &mut T {
         (*self).next_element_seq()
     }
 
+    fn next_element_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        (*self).next_element_seed(seed)
+    }
+
+    fn next_element_seq_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        (*self).next_element_seq_seed(seed)
+    }
+
     fn sub_access(&mut self) -> Result<Self::SubAccess<'_>, Self::Error> {
         (*self).sub_access()
     }
+
+    fn size_hint(&self) -> Option<usize> {
+        (**self).size_hint()
+    }
+
+    fn peek_element(&mut self) -> Result<Option<PeekedNode>, Self::Error> {
+        (*self).peek_element()
+    }
+
+    fn preserves_whitespace(&self) -> bool {
+        (**self).preserves_whitespace()
+    }
 }
 
 /// Trait for XML text.
@@ -396,8 +744,8 @@ pub trait Visitor<'de>: Sized {
     where
         A: ElementAccess<'de>,
     {
-        let _ = element;
-        Err(Error::unexpected_visit(Unexpected::ElementStart, &self))
+        let name = Box::new(element.name().into_owned());
+        Err(Error::unexpected_visit(Unexpected::Element { name }, &self))
     }
 
     /// Visits an XML attribute.
@@ -405,8 +753,11 @@ pub trait Visitor<'de>: Sized {
     where
         A: AttributeAccess<'de>,
     {
-        let _ = attribute;
-        Err(Error::unexpected_visit(Unexpected::Attribute, &self))
+        let name = Box::new(attribute.name().into_owned());
+        Err(Error::unexpected_visit(
+            Unexpected::Attribute { name },
+            &self,
+        ))
     }
 
     /// Visits a sequence of values.
@@ -425,8 +776,8 @@ pub trait Visitor<'de>: Sized {
         E: Error,
         V: XmlProcessingInstruction,
     {
-        let _ = pi;
-        Err(Error::unexpected_visit(Unexpected::PI, &self))
+        let target = String::from_utf8_lossy(pi.target()).into_owned();
+        Err(Error::unexpected_visit(Unexpected::PI { target }, &self))
     }
 
     /// Visits a declaration.
@@ -482,6 +833,16 @@ pub trait Deserializer<'de>: Sized {
     fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>;
+
+    /// Get a reference to external data supplied by the caller of the deserializer, the same as [`DeserializeContext::external_data`].
+    ///
+    /// This lets functions used with `deserialize_with_context` read data such as a base URL before deserializing a value. Deserializers without external data return [`None`], which is the default.
+    fn external_data<T>(&self) -> Option<&T>
+    where
+        T: core::any::Any,
+    {
+        None
+    }
 }
 
 /// A type that can be deserialized from a deserializer. This type has two methods: [`Deserialize::deserialize`] and [`Deserialize::deserialize_seq`]. The latter is used in cases where types can be constructed from multiple nodes, such as constructing a [`std::vec::Vec`] from multiple elements, or a [`std::string::String`] from multiple text nodes that are concatenated together.
@@ -497,6 +858,34 @@ pub trait Deserialize<'de>: Sized {
     }
 }
 
+/// A stateful variant of [`Deserialize`], for values that need data from the caller while they are deserialized, such as an arena to allocate them in.
+///
+/// Every type implementing [`Deserialize`] can be used as a seed through [`PhantomData`].
+pub trait DeserializeSeed<'de>: Sized {
+    /// The type of value produced by this seed.
+    type Value;
+
+    /// Deserializes a value from a deserializer using this seed.
+    fn deserialize<D: Deserializer<'de>>(self, reader: D) -> Result<Self::Value, D::Error>;
+
+    /// Deserializes a value from a deserializer using this seed, but tries to do it from a sequence of values.
+    fn deserialize_seq<D: Deserializer<'de>>(self, reader: D) -> Result<Self::Value, D::Error> {
+        self.deserialize(reader)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> DeserializeSeed<'de> for PhantomData<T> {
+    type Value = T;
+
+    fn deserialize<D: Deserializer<'de>>(self, reader: D) -> Result<Self::Value, D::Error> {
+        T::deserialize(reader)
+    }
+
+    fn deserialize_seq<D: Deserializer<'de>>(self, reader: D) -> Result<Self::Value, D::Error> {
+        T::deserialize_seq(reader)
+    }
+}
+
 /// A utility type for easier use of [`Deserialize`] trait without needing to specify the lifetime.
 pub trait DeserializeOwned: for<'de> Deserialize<'de> {}
 impl<T> DeserializeOwned for T where T: for<'de> Deserialize<'de> {}
@@ -544,6 +933,13 @@ pub trait DeserializationGroupBuilder<'de>: Sized {
         false
     }
 
+    /// Returns the names of the fields that have been deserialized so far.
+    ///
+    /// This makes it possible to report which parts of a group were valid when deserializing it fails, see [`PartialElement`](crate::types::utils::PartialElement). Builders that do not keep track of their fields return an empty list.
+    fn completed_fields(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+
     /// This function is called after all attributes and elements have been contributed.
     fn finish<E: Error>(self) -> Result<Self::Value, E>;
 }
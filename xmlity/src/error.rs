@@ -0,0 +1,120 @@
+//! A unified error type for applications using XMLity.
+//!
+//! Serializers and deserializers each define their own error types, which makes it awkward to handle errors from several of them in one place. [`Error`] wraps any of them while keeping track of which direction failed, and the original error can be recovered with [`Error::downcast_ref`].
+//!
+//! All error types defined by `xmlity` are [`Send`], [`Sync`] and `'static`, so they can be converted into [`Error`] or boxed into `Box<dyn std::error::Error + Send + Sync>` directly.
+use std::{error::Error as StdError, fmt, sync::Arc};
+
+use crate::{de, ser, value};
+
+/// A thread-safe, type-erased error.
+pub type BoxedError = Arc<dyn StdError + Send + Sync + 'static>;
+
+/// An error that occurred while serializing or deserializing.
+///
+/// Errors from `xmlity` itself convert into this type through [`From`], and backends provide the same conversions for their own error types. Errors from any other source can be wrapped using [`Error::serialize`] and [`Error::deserialize`].
+///
+/// ```
+/// use xmlity::value::XmlValueDeserializerError;
+///
+/// let err: xmlity::Error = XmlValueDeserializerError::MissingData.into();
+///
+/// assert!(err.is_deserialize());
+/// assert_eq!(
+///     err.downcast_ref::<XmlValueDeserializerError>(),
+///     Some(&XmlValueDeserializerError::MissingData)
+/// );
+/// ```
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum Error {
+    /// Serializing a value failed.
+    Serialize(BoxedError),
+    /// Deserializing a value failed.
+    Deserialize(BoxedError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Serialize(value) => write!(f, "Serialization error: {value}"),
+            Self::Deserialize(value) => write!(f, "Deserialization error: {value}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl Error {
+    /// Wraps an error that occurred while serializing.
+    pub fn serialize<E: StdError + Send + Sync + 'static>(error: E) -> Self {
+        Error::Serialize(Arc::new(error))
+    }
+
+    /// Wraps an error that occurred while deserializing.
+    pub fn deserialize<E: StdError + Send + Sync + 'static>(error: E) -> Self {
+        Error::Deserialize(Arc::new(error))
+    }
+
+    /// Returns `true` if the error occurred while serializing.
+    pub fn is_serialize(&self) -> bool {
+        matches!(self, Error::Serialize(_))
+    }
+
+    /// Returns `true` if the error occurred while deserializing.
+    pub fn is_deserialize(&self) -> bool {
+        matches!(self, Error::Deserialize(_))
+    }
+
+    /// The wrapped error.
+    pub fn inner(&self) -> &(dyn StdError + Send + Sync + 'static) {
+        match self {
+            Error::Serialize(error) | Error::Deserialize(error) => error.as_ref(),
+        }
+    }
+
+    /// Returns the wrapped error if it is of type `E`.
+    pub fn downcast_ref<E: StdError + 'static>(&self) -> Option<&E> {
+        self.inner().downcast_ref::<E>()
+    }
+}
+
+impl From<value::XmlValueSerializerError> for Error {
+    fn from(error: value::XmlValueSerializerError) -> Self {
+        Error::serialize(error)
+    }
+}
+
+impl From<ser::DynError> for Error {
+    fn from(error: ser::DynError) -> Self {
+        Error::serialize(error)
+    }
+}
+
+impl From<value::XmlValueDeserializerError> for Error {
+    fn from(error: value::XmlValueDeserializerError) -> Self {
+        Error::deserialize(error)
+    }
+}
+
+impl From<de::DynError> for Error {
+    fn from(error: de::DynError) -> Self {
+        Error::deserialize(error)
+    }
+}
+
+// Keeps the error types usable in `Box<dyn std::error::Error + Send + Sync>` based error stacks.
+const _: () = {
+    const fn assert_error<T: StdError + Send + Sync + 'static>() {}
+
+    assert_error::<Error>();
+    assert_error::<value::XmlValueSerializerError>();
+    assert_error::<value::XmlValueDeserializerError>();
+    assert_error::<value::InvalidContentError>();
+    assert_error::<ser::DynError>();
+    assert_error::<ser::Unexpected>();
+    assert_error::<de::DynError>();
+    assert_error::<de::Unexpected>();
+    assert_error::<crate::escape::UnescapeError>();
+    assert_error::<crate::XmlNamespaceParseError>();
+};
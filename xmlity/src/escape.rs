@@ -0,0 +1,248 @@
+//! Escaping and unescaping of XML character data.
+//!
+//! These are the routines used by serializers to write text and attribute values, and by deserializers to read them back. Custom [`Serializer`](crate::Serializer) and [`Deserializer`](crate::Deserializer) implementations can use them to stay consistent with the rest of the ecosystem.
+use std::{borrow::Cow, fmt};
+
+/// An error that can occur when unescaping a string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnescapeError {
+    /// An entity reference is missing its closing `;`.
+    UnterminatedEntity(usize),
+    /// An entity reference does not refer to one of the predefined entities.
+    UnknownEntity(String),
+    /// A character reference does not refer to a valid character.
+    InvalidCharRef(String),
+}
+
+impl fmt::Display for UnescapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnterminatedEntity(value) => write!(
+                f,
+                "Entity reference starting at byte {value} is not terminated"
+            ),
+            Self::UnknownEntity(value) => write!(f, "Unknown entity `&{value};`"),
+            Self::InvalidCharRef(value) => write!(f, "Invalid character reference `&{value};`"),
+        }
+    }
+}
+
+impl std::error::Error for UnescapeError {}
+
+fn escape_with(value: &str, escape: impl Fn(char) -> Option<&'static str>) -> Cow<'_, str> {
+    let Some(first) = value.find(|c| escape(c).is_some()) else {
+        return Cow::Borrowed(value);
+    };
+
+    let mut escaped = String::with_capacity(value.len() + 8);
+    escaped.push_str(&value[..first]);
+    for c in value[first..].chars() {
+        match escape(c) {
+            Some(replacement) => escaped.push_str(replacement),
+            None => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+/// Escapes a string for use as text content, replacing `&`, `<` and `>` with entity references.
+///
+/// Carriage returns are written as character references too, since parsers otherwise normalize them into line feeds.
+///
+/// Only allocates if the string contains any of those characters.
+pub fn escape_text(value: &str) -> Cow<'_, str> {
+    escape_with(value, |c| match c {
+        '&' => Some("&amp;"),
+        '<' => Some("&lt;"),
+        '>' => Some("&gt;"),
+        '\r' => Some("&#13;"),
+        _ => None,
+    })
+}
+
+/// Escapes a string for use as an attribute value in either single or double quotes.
+///
+/// In addition to the characters escaped by [`escape_text`], quotes are escaped, and tabs and line breaks are written as character references so they survive attribute value normalization.
+pub fn escape_attr(value: &str) -> Cow<'_, str> {
+    escape_with(value, |c| match c {
+        '&' => Some("&amp;"),
+        '<' => Some("&lt;"),
+        '>' => Some("&gt;"),
+        '"' => Some("&quot;"),
+        '\'' => Some("&apos;"),
+        '\t' => Some("&#9;"),
+        '\n' => Some("&#10;"),
+        '\r' => Some("&#13;"),
+        _ => None,
+    })
+}
+
+/// `Char` of the XML 1.0 specification. Surrogates can't occur in a [`char`].
+pub(crate) fn is_xml_char(c: char) -> bool {
+    matches!(c, '\t' | '\n' | '\r' | '\u{20}'..='\u{FFFD}' | '\u{10000}'..='\u{10FFFF}')
+}
+
+fn resolve_entity(entity: &str) -> Result<Cow<'static, str>, UnescapeError> {
+    let predefined = match entity {
+        "amp" => Some("&"),
+        "lt" => Some("<"),
+        "gt" => Some(">"),
+        "quot" => Some("\""),
+        "apos" => Some("'"),
+        _ => None,
+    };
+    if let Some(predefined) = predefined {
+        return Ok(Cow::Borrowed(predefined));
+    }
+
+    let Some(char_ref) = entity.strip_prefix('#') else {
+        return Err(UnescapeError::UnknownEntity(entity.to_owned()));
+    };
+
+    let (digits, radix) = match char_ref.strip_prefix('x') {
+        Some(hex) => (hex, 16),
+        None => (char_ref, 10),
+    };
+
+    Some(digits)
+        .filter(|digits| !digits.is_empty() && digits.chars().all(|c| c.is_digit(radix)))
+        .and_then(|digits| u32::from_str_radix(digits, radix).ok())
+        .and_then(char::from_u32)
+        .filter(|c| is_xml_char(*c))
+        .map(|c| Cow::Owned(c.to_string()))
+        .ok_or_else(|| UnescapeError::InvalidCharRef(entity.to_owned()))
+}
+
+/// Unescapes a string by resolving the predefined entity references (`&amp;`, `&lt;`, `&gt;`, `&quot;` and `&apos;`) and character references like `&#38;` or `&#x26;`. Character references to characters that XML doesn't allow, like `&#0;`, are rejected.
+///
+/// Only allocates if the string contains any references.
+pub fn unescape(value: &str) -> Result<Cow<'_, str>, UnescapeError> {
+    let Some(first) = value.find('&') else {
+        return Ok(Cow::Borrowed(value));
+    };
+
+    let mut unescaped = String::with_capacity(value.len());
+    unescaped.push_str(&value[..first]);
+
+    let mut position = first;
+    while let Some(offset) = value[position..].find('&') {
+        let start = position + offset;
+        unescaped.push_str(&value[position..start]);
+
+        let end = value[start..]
+            .find(';')
+            .map(|end| start + end)
+            .ok_or(UnescapeError::UnterminatedEntity(start))?;
+
+        unescaped.push_str(&resolve_entity(&value[start + 1..end])?);
+        position = end + 1;
+    }
+    unescaped.push_str(&value[position..]);
+
+    Ok(Cow::Owned(unescaped))
+}
+
+/// Unescapes an attribute value like [`unescape`], and applies attribute value normalization as described in [section 3.3.3 of the XML 1.0 specification](https://www.w3.org/TR/xml/#AVNormalize).
+///
+/// Line breaks (`\r\n`, `\r` and `\n`) and tabs written literally in the value are replaced by a single space each, while those written as character references like `&#10;` are kept, which is how [`escape_attr`] preserves them.
+///
+/// Only allocates if the string contains any references or whitespace that needs to be replaced.
+pub fn unescape_attr(value: &str) -> Result<Cow<'_, str>, UnescapeError> {
+    if !value.contains(['\t', '\n', '\r']) {
+        return unescape(value);
+    }
+
+    let mut normalized = String::with_capacity(value.len());
+    let mut position = 0;
+    for (start, c) in value.match_indices(['&', '\t', '\n', '\r']) {
+        if start < position {
+            // Part of an entity reference that has already been resolved.
+            continue;
+        }
+        normalized.push_str(&value[position..start]);
+        position = start + 1;
+
+        match c {
+            "&" => {
+                let end = value[start..]
+                    .find(';')
+                    .map(|end| start + end)
+                    .ok_or(UnescapeError::UnterminatedEntity(start))?;
+
+                normalized.push_str(&resolve_entity(&value[start + 1..end])?);
+                position = end + 1;
+            }
+            "\r" if value[position..].starts_with('\n') => {
+                normalized.push(' ');
+                position += 1;
+            }
+            _ => normalized.push(' '),
+        }
+    }
+    normalized.push_str(&value[position..]);
+
+    Ok(Cow::Owned(normalized))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::plain("plain text", "plain text")]
+    #[case::markup("a < b && c > d", "a &lt; b &amp;&amp; c &gt; d")]
+    #[case::quotes(r#"say "hi""#, r#"say "hi""#)]
+    #[case::carriage_return("a\r\nb", "a&#13;\nb")]
+    fn escape_text_cases(#[case] value: &str, #[case] expected: &str) {
+        assert_eq!(escape_text(value), expected);
+    }
+
+    #[rstest]
+    #[case::plain("plain", "plain")]
+    #[case::quotes(r#"it's "quoted""#, "it&apos;s &quot;quoted&quot;")]
+    #[case::whitespace("a\tb\nc", "a&#9;b&#10;c")]
+    #[case::carriage_return("a\r\nb", "a&#13;&#10;b")]
+    fn escape_attr_cases(#[case] value: &str, #[case] expected: &str) {
+        assert_eq!(escape_attr(value), expected);
+    }
+
+    #[rstest]
+    #[case::plain("plain", "plain")]
+    #[case::predefined("&lt;a&gt; &amp; &quot;b&quot; &apos;c&apos;", r#"<a> & "b" 'c'"#)]
+    #[case::char_refs("&#38;&#x3C;&#x1F600;", "&<\u{1F600}")]
+    fn unescape_cases(#[case] value: &str, #[case] expected: &str) {
+        assert_eq!(unescape(value).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case::plain("plain", "plain")]
+    #[case::literal_whitespace("a\tb\nc", "a b c")]
+    #[case::crlf("a\r\nb\rc", "a b c")]
+    #[case::char_refs("a&#9;b&#10;c&#13;&#10;d", "a\tb\nc\r\nd")]
+    #[case::mixed("&lt;a&gt;\n&amp;", "<a> &")]
+    fn unescape_attr_cases(#[case] value: &str, #[case] expected: &str) {
+        assert_eq!(unescape_attr(value).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case::unterminated("a &amp b", UnescapeError::UnterminatedEntity(2))]
+    #[case::unknown("&nbsp;", UnescapeError::UnknownEntity("nbsp".to_owned()))]
+    #[case::invalid_char_ref("&#xD800;", UnescapeError::InvalidCharRef("#xD800".to_owned()))]
+    #[case::null_char_ref("&#0;", UnescapeError::InvalidCharRef("#0".to_owned()))]
+    #[case::control_char_ref("&#x1;", UnescapeError::InvalidCharRef("#x1".to_owned()))]
+    #[case::non_char_ref("&#xFFFE;", UnescapeError::InvalidCharRef("#xFFFE".to_owned()))]
+    fn unescape_errors(#[case] value: &str, #[case] expected: UnescapeError) {
+        assert_eq!(unescape(value).unwrap_err(), expected);
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("a < b & \"c\" 'd'\t\n")]
+    #[case("a\r\nb\r")]
+    fn round_trip(#[case] value: &str) {
+        assert_eq!(unescape(&escape_text(value)).unwrap(), value);
+        assert_eq!(unescape(&escape_attr(value)).unwrap(), value);
+        assert_eq!(unescape_attr(&escape_attr(value)).unwrap(), value);
+    }
+}
@@ -9,25 +9,38 @@
 //! - [`Serialize`], [`SerializeAttribute`] and [`Deserialize`] which lets you define how to (de)serialize types,
 //! - [`Serializer`] and [`Deserializer`] which lets you define readers and writers for XML documents.
 //! - [`SerializationGroup`] and [`DeserializationGroup`] which lets you define how to (de)serialize groups of types that can be extended upon in other elements/groups recursively.
+//! - [`Error`] which unifies the errors of different serializers and deserializers for application-level error handling.
 //! - [`XmlValue`] and its variants which allow for generic deserialization of XML documents, similar to [`serde_json::Value`].
 //!
 //! The library includes derive macros for [`Serialize`], [`SerializeAttribute`], [`Deserialize`], [`SerializationGroup`] and [`DeserializationGroup`] which can be enabled with the `derive` feature. The macros can be used to create nearly any kind of XML structure you want. If there is something it cannot do, please open an issue or a pull request.
 //!
 //! The macro [`xml`] can be used to create [`XmlValues`](`XmlValue`) in a more ergonomic way. It is also possible to create [`XmlValues`](`XmlValue`) manually, but it is quite verbose.
+//!
+//! XMLity itself does not read or write XML documents, which is left to backends like `xmlity-quick-xml`. For cases where no backend is available, the `pure` feature enables the `pure` module, a small reference backend without any dependencies.
+//!
+//! Without any features enabled, XMLity has no dependencies of its own, which keeps the dependency tree small for security-sensitive users. Features such as `derive`, `json` and `wasm` only pull in the dependencies they need.
 use core::{fmt, str};
 use fmt::Display;
 use std::{borrow::Borrow, ops::Deref, str::FromStr};
 
 pub mod de;
+pub mod escape;
 pub use de::{DeserializationGroup, Deserialize, DeserializeOwned, Deserializer};
+pub mod error;
+pub use error::Error;
 pub mod ser;
 pub use ser::{AttributeSerializer, SerializationGroup, Serialize, SerializeAttribute, Serializer};
 mod macros;
+pub mod meta;
+#[cfg(feature = "pure")]
+pub mod pure;
 pub mod types;
 pub mod value;
 pub use value::XmlValue;
 mod noop;
 pub use noop::NoopDeSerializer;
+#[doc(hidden)]
+pub mod __private;
 
 #[cfg(feature = "derive")]
 extern crate xmlity_derive;
@@ -45,26 +58,26 @@ pub use xmlity_derive::{
 // [7]   	Nmtoken	   ::=   	(NameChar)+
 // [8]   	Nmtokens	   ::=   	Nmtoken (#x20 Nmtoken)*
 mod name_tokens {
-    const fn is_name_start_char(c: char) -> bool {
+    use core::fmt;
+
+    pub(crate) const fn is_name_start_char(c: char) -> bool {
         matches!(
             //Deliberately excluding : as we handle it separately
             c, 'A'..='Z' | '_' | 'a'..='z' | '\u{00C0}'..='\u{00D6}' | '\u{00D8}'..='\u{00F6}' | '\u{00F8}'..='\u{02FF}' | '\u{0370}'..='\u{037D}' | '\u{037F}'..='\u{1FFF}' | '\u{200C}'..='\u{200D}' | '\u{2070}'..='\u{218F}' | '\u{2C00}'..='\u{2FEF}' | '\u{3001}'..='\u{D7FF}' | '\u{F900}'..='\u{FDCF}' | '\u{FDF0}'..='\u{FFFD}' | '\u{10000}'..='\u{EFFFF}'
         )
     }
-    const fn is_name_char(c: char) -> bool {
+    pub(crate) const fn is_name_char(c: char) -> bool {
         is_name_start_char(c)
             || matches!(c, '-' | '.' | '0'..='9' | '\u{00B7}' | '\u{0300}'..='\u{036F}' | '\u{203F}'..='\u{2040}')
     }
 
     /// An error that occurs when a name is invalid.
-    #[derive(Debug, Clone, thiserror::Error, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
     #[non_exhaustive]
     pub enum InvalidXmlNameError {
         /// The name starts with an invalid character. The first character of an XML name is special and excludes some other characters incl "-" which is allowed in the middle of the name.
-        #[error("Invalid start character")]
         InvalidStartChar,
         /// The name contains an invalid character.
-        #[error("Invalid character at index {index}")]
         InvalidChar {
             /// The index of the invalid character.
             index: usize,
@@ -72,27 +85,79 @@ mod name_tokens {
             character: char,
         },
         /// The name is empty.
-        #[error("Empty name")]
         Empty,
     }
 
-    pub fn is_valid_name(name: &str) -> Result<(), InvalidXmlNameError> {
-        let mut chars = name.chars();
-        if let Some(c) = chars.next() {
-            if !is_name_start_char(c) {
-                return Err(InvalidXmlNameError::InvalidStartChar);
+    impl fmt::Display for InvalidXmlNameError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::InvalidStartChar => f.write_str("Invalid start character"),
+                Self::InvalidChar { index, .. } => write!(f, "Invalid character at index {index}"),
+                Self::Empty => f.write_str("Empty name"),
             }
-        } else {
+        }
+    }
+
+    impl std::error::Error for InvalidXmlNameError {}
+
+    pub const fn is_valid_name(name: &str) -> Result<(), InvalidXmlNameError> {
+        let bytes = name.as_bytes();
+        if bytes.is_empty() {
             return Err(InvalidXmlNameError::Empty);
         }
-        for (index, character) in chars.enumerate().map(|(i, c)| (i + 1, c)) {
+
+        // Characters are decoded by hand, since iterating over them is not possible in const functions.
+        let mut offset = 0;
+        let mut index = 0;
+        while offset < bytes.len() {
+            let (character, length) = decode_char(bytes, offset);
+            if index == 0 && !is_name_start_char(character) {
+                return Err(InvalidXmlNameError::InvalidStartChar);
+            }
             if !is_name_char(character) {
                 return Err(InvalidXmlNameError::InvalidChar { index, character });
             }
+            offset += length;
+            index += 1;
         }
 
         Ok(())
     }
+
+    /// Decodes the character starting at `offset` of valid UTF-8, returning it together with its length in bytes.
+    const fn decode_char(bytes: &[u8], offset: usize) -> (char, usize) {
+        let first = bytes[offset] as u32;
+        let (code, length) = if first < 0x80 {
+            (first, 1)
+        } else if first < 0xE0 {
+            (((first & 0x1F) << 6) | continuation(bytes, offset + 1), 2)
+        } else if first < 0xF0 {
+            (
+                ((first & 0x0F) << 12)
+                    | (continuation(bytes, offset + 1) << 6)
+                    | continuation(bytes, offset + 2),
+                3,
+            )
+        } else {
+            (
+                ((first & 0x07) << 18)
+                    | (continuation(bytes, offset + 1) << 12)
+                    | (continuation(bytes, offset + 2) << 6)
+                    | continuation(bytes, offset + 3),
+                4,
+            )
+        };
+
+        match char::from_u32(code) {
+            Some(character) => (character, length),
+            None => panic!("invalid UTF-8"),
+        }
+    }
+
+    /// Returns the bits of a continuation byte of UTF-8.
+    const fn continuation(bytes: &[u8], index: usize) -> u32 {
+        (bytes[index] as u32) & 0x3F
+    }
 }
 
 pub use name_tokens::InvalidXmlNameError;
@@ -107,7 +172,7 @@ pub struct ExpandedName<'a> {
 
 impl<'a> ExpandedName<'a> {
     /// Creates a new [`ExpandedName`].
-    pub fn new(local_name: &'a LocalName, namespace: Option<&'a XmlNamespace>) -> Self {
+    pub const fn new(local_name: &'a LocalName, namespace: Option<&'a XmlNamespace>) -> Self {
         Self {
             local_name,
             namespace,
@@ -199,18 +264,83 @@ impl From<ExpandedName<'_>> for ExpandedNameBuf {
     }
 }
 
+impl From<&ExpandedName<'_>> for ExpandedNameBuf {
+    fn from(value: &ExpandedName<'_>) -> Self {
+        value.into_owned()
+    }
+}
+
+impl<'a> From<&'a ExpandedNameBuf> for ExpandedName<'a> {
+    fn from(value: &'a ExpandedNameBuf) -> Self {
+        value.as_ref()
+    }
+}
+
+impl From<LocalNameBuf> for ExpandedNameBuf {
+    fn from(local_name: LocalNameBuf) -> Self {
+        ExpandedNameBuf::new(local_name, None)
+    }
+}
+
+impl From<(LocalNameBuf, Option<XmlNamespaceBuf>)> for ExpandedNameBuf {
+    fn from((local_name, namespace): (LocalNameBuf, Option<XmlNamespaceBuf>)) -> Self {
+        ExpandedNameBuf::new(local_name, namespace)
+    }
+}
+
+impl<'a> From<&'a LocalName> for ExpandedName<'a> {
+    fn from(local_name: &'a LocalName) -> Self {
+        ExpandedName::new(local_name, None)
+    }
+}
+
+impl<'a> From<(&'a LocalName, Option<&'a XmlNamespace>)> for ExpandedName<'a> {
+    fn from((local_name, namespace): (&'a LocalName, Option<&'a XmlNamespace>)) -> Self {
+        ExpandedName::new(local_name, namespace)
+    }
+}
+
 /// An error that can occur when parsing an [`ExpandedNameBuf`].
-#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ExpandedNameParseError {
     /// The [`XmlNamespace`] is invalid.
-    #[error("Invalid namespace: {0}")]
-    InvalidNamespace(#[from] XmlNamespaceParseError),
+    InvalidNamespace(XmlNamespaceParseError),
     /// The expanded name is missing a closing brace.
-    #[error("Missing closing brace in expanded name")]
     MissingClosingBrace,
     /// The [`LocalName`] is invalid.
-    #[error("Invalid local name: {0}")]
-    InvalidLocalName(#[from] LocalNameParseError),
+    InvalidLocalName(LocalNameParseError),
+}
+
+impl fmt::Display for ExpandedNameParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidNamespace(value) => write!(f, "Invalid namespace: {value}"),
+            Self::MissingClosingBrace => f.write_str("Missing closing brace in expanded name"),
+            Self::InvalidLocalName(value) => write!(f, "Invalid local name: {value}"),
+        }
+    }
+}
+
+impl std::error::Error for ExpandedNameParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidNamespace(error) => Some(error),
+            Self::InvalidLocalName(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<XmlNamespaceParseError> for ExpandedNameParseError {
+    fn from(error: XmlNamespaceParseError) -> Self {
+        Self::InvalidNamespace(error)
+    }
+}
+
+impl From<LocalNameParseError> for ExpandedNameParseError {
+    fn from(error: LocalNameParseError) -> Self {
+        Self::InvalidLocalName(error)
+    }
 }
 
 impl FromStr for ExpandedNameBuf {
@@ -321,14 +451,42 @@ impl QNameBuf {
 }
 
 /// An error that can occur when parsing a [`QNameBuf`].
-#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum QNameParseError {
     /// The [`Prefix`] is invalid.
-    #[error("Invalid prefix: {0}")]
-    InvalidPrefix(#[from] PrefixParseError),
+    InvalidPrefix(PrefixParseError),
     /// The [`LocalName`] is invalid.
-    #[error("Invalid local name: {0}")]
-    InvalidLocalName(#[from] LocalNameParseError),
+    InvalidLocalName(LocalNameParseError),
+}
+
+impl fmt::Display for QNameParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidPrefix(value) => write!(f, "Invalid prefix: {value}"),
+            Self::InvalidLocalName(value) => write!(f, "Invalid local name: {value}"),
+        }
+    }
+}
+
+impl std::error::Error for QNameParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidPrefix(error) => Some(error),
+            Self::InvalidLocalName(error) => Some(error),
+        }
+    }
+}
+
+impl From<PrefixParseError> for QNameParseError {
+    fn from(error: PrefixParseError) -> Self {
+        Self::InvalidPrefix(error)
+    }
+}
+
+impl From<LocalNameParseError> for QNameParseError {
+    fn from(error: LocalNameParseError) -> Self {
+        Self::InvalidLocalName(error)
+    }
 }
 
 impl FromStr for QNameBuf {
@@ -378,9 +536,17 @@ impl PartialEq<QNameBuf> for QName<'_> {
 pub struct XmlNamespace(str);
 
 /// An error that can occur when parsing a [`XmlNamespace`].
-#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum XmlNamespaceParseError {}
 
+impl fmt::Display for XmlNamespaceParseError {
+    fn fmt(&self, _: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {}
+    }
+}
+
+impl std::error::Error for XmlNamespaceParseError {}
+
 impl XmlNamespace {
     /// Creates a new [`XmlNamespace`] from a string slice without validating it.
     ///
@@ -525,11 +691,32 @@ impl PartialEq<XmlNamespaceBuf> for XmlNamespace {
 pub struct Prefix(str);
 
 /// An error that can occur when parsing a [`Prefix`].
-#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PrefixParseError {
     /// The [`Prefix`] is not a valid XML name.
-    #[error("Prefix has an invalid XML name: {0}")]
-    InvalidXmlName(#[from] name_tokens::InvalidXmlNameError),
+    InvalidXmlName(name_tokens::InvalidXmlNameError),
+}
+
+impl fmt::Display for PrefixParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidXmlName(value) => write!(f, "Prefix has an invalid XML name: {value}"),
+        }
+    }
+}
+
+impl std::error::Error for PrefixParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidXmlName(error) => Some(error),
+        }
+    }
+}
+
+impl From<name_tokens::InvalidXmlNameError> for PrefixParseError {
+    fn from(error: name_tokens::InvalidXmlNameError) -> Self {
+        Self::InvalidXmlName(error)
+    }
 }
 
 impl Prefix {
@@ -702,11 +889,32 @@ impl PartialEq<PrefixBuf> for Prefix {
 pub struct LocalName(str);
 
 /// An error that can occur when parsing a [`LocalName`].
-#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum LocalNameParseError {
     /// The [`LocalName`] is not a valid XML name.
-    #[error("Local name has an invalid XML name: {0}")]
-    InvalidXmlName(#[from] name_tokens::InvalidXmlNameError),
+    InvalidXmlName(name_tokens::InvalidXmlNameError),
+}
+
+impl fmt::Display for LocalNameParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidXmlName(value) => write!(f, "Local name has an invalid XML name: {value}"),
+        }
+    }
+}
+
+impl std::error::Error for LocalNameParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidXmlName(error) => Some(error),
+        }
+    }
+}
+
+impl From<name_tokens::InvalidXmlNameError> for LocalNameParseError {
+    fn from(error: name_tokens::InvalidXmlNameError) -> Self {
+        Self::InvalidXmlName(error)
+    }
 }
 
 impl LocalName {
@@ -884,6 +1092,27 @@ mod tests {
         assert_eq!(error, expected_error);
     }
 
+    #[test]
+    fn parse_error_messages_and_sources() {
+        use std::error::Error as _;
+
+        let error = QNameBuf::from_str("prefix:invalid localName").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid local name: Local name has an invalid XML name: Invalid character at index 7"
+        );
+
+        let source = error.source().unwrap();
+        assert_eq!(
+            source.to_string(),
+            "Local name has an invalid XML name: Invalid character at index 7"
+        );
+        assert_eq!(
+            source.source().unwrap().to_string(),
+            "Invalid character at index 7"
+        );
+    }
+
     #[rstest]
     #[case::basic("localName", None, "localName")]
     #[case::with_namespace("localName", Some(XmlNamespace::new("http://example.com").unwrap()), "{http://example.com}localName")]
@@ -918,6 +1147,38 @@ mod tests {
         assert_eq!(expanded_name.as_ref(), expected_expanded_name);
     }
 
+    #[rstest]
+    #[case::no_namespace("localName")]
+    #[case::with_namespace("{http://example.com}localName")]
+    fn expanded_name_conversions(#[case] expanded_name_text: &str) {
+        let owned = ExpandedNameBuf::from_str(expanded_name_text).unwrap();
+
+        let borrowed: ExpandedName<'_> = (&owned).into();
+        assert_eq!(borrowed, owned);
+        assert_eq!(ExpandedNameBuf::from(&borrowed), owned);
+
+        let (local_name, namespace) = owned.clone().into_parts();
+        assert_eq!(ExpandedNameBuf::from((local_name, namespace)), owned);
+        assert_eq!(
+            ExpandedName::from((owned.local_name(), owned.namespace())),
+            borrowed
+        );
+    }
+
+    #[test]
+    fn expanded_name_from_local_name() {
+        let local_name = LocalNameBuf::from_str("localName").unwrap();
+
+        assert_eq!(
+            ExpandedName::from(&*local_name),
+            ExpandedName::new(&local_name, None)
+        );
+        assert_eq!(
+            ExpandedNameBuf::from(local_name.clone()),
+            ExpandedNameBuf::new(local_name, None)
+        );
+    }
+
     #[rstest]
     #[case::invalid_local_name("prefix:invalid localName", QNameParseError::InvalidLocalName(LocalNameParseError::InvalidXmlName(InvalidXmlNameError::InvalidChar { index: 7, character: ' ' })))]
     fn invalid_qname_invalid_characters(
@@ -927,4 +1188,15 @@ mod tests {
         let error = QNameBuf::from_str(qname).unwrap_err();
         assert_eq!(error, expected_error);
     }
+
+    #[rstest]
+    #[case::first(0, "a0")]
+    #[case::last_digit(9, "a9")]
+    #[case::next_letter(10, "b0")]
+    #[case::last_letter(259, "z9")]
+    #[case::wrapped(260, "a10")]
+    #[case::wrapped_next_letter(275, "b15")]
+    fn generated_prefixes(#[case] index: usize, #[case] expected: &str) {
+        assert_eq!(ser::generated_prefix(index).as_str(), expected);
+    }
 }
@@ -0,0 +1,147 @@
+//! This module contains structural descriptions of types, which the derive macros generate as a `SCHEMA` constant for types with `#[xmlity(meta)]`.
+//!
+//! The descriptions follow the options of the derive macros: the names of elements and attributes, how each field is represented and how many times it can occur. They can be inspected at runtime, for example to generate documentation or schemas from Rust types.
+//!
+//! The types of fields are only described by their names as they are written in the source, since the descriptions of other types can not be reached from a constant.
+use crate::ExpandedName;
+
+/// The description of a type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TypeMeta {
+    /// The name of the type, or of the variant for the variants of an enum.
+    pub rust_name: &'static str,
+    /// How the type is represented in XML.
+    pub kind: TypeKind,
+}
+
+impl TypeMeta {
+    /// Creates a new [`TypeMeta`].
+    pub const fn new(rust_name: &'static str, kind: TypeKind) -> Self {
+        Self { rust_name, kind }
+    }
+
+    /// Returns the name of the element or attribute the type is represented as, if any.
+    pub fn name(&self) -> Option<ExpandedName<'static>> {
+        match self.kind {
+            TypeKind::Element { name, .. } | TypeKind::Attribute { name } => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Returns the fields of the type, which is empty for types without fields of their own.
+    pub fn fields(&self) -> &'static [FieldMeta] {
+        match self.kind {
+            TypeKind::Element { fields, .. }
+            | TypeKind::Value { fields }
+            | TypeKind::Group { fields } => fields,
+            _ => &[],
+        }
+    }
+}
+
+/// How a type is represented in XML.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TypeKind {
+    /// An element, given with `#[xelement]`.
+    Element {
+        /// The name of the element.
+        name: ExpandedName<'static>,
+        /// The fields of the element.
+        fields: &'static [FieldMeta],
+    },
+    /// An attribute, given with `#[xattribute]`, whose value is the single field of the type.
+    Attribute {
+        /// The name of the attribute.
+        name: ExpandedName<'static>,
+    },
+    /// The values of the fields one after another, for types without any of the other options or with `#[xvalue]`.
+    Value {
+        /// The fields of the value.
+        fields: &'static [FieldMeta],
+    },
+    /// A fixed text, for unit structs with `#[xvalue(value = "...")]` and unit variants.
+    Text {
+        /// The text, or [`None`] if it is computed by the function given with `rename_all_with`.
+        value: Option<&'static str>,
+    },
+    /// A processing instruction, given with `#[xpi]`.
+    Pi {
+        /// The target of the processing instruction.
+        target: &'static str,
+    },
+    /// A group of attributes and children, deriving [`SerializationGroup`](crate::SerializationGroup) or [`DeserializationGroup`](crate::DeserializationGroup).
+    Group {
+        /// The fields of the group.
+        fields: &'static [FieldMeta],
+    },
+    /// An enum, which is one of its variants.
+    Choice {
+        /// The variants of the enum.
+        variants: &'static [TypeMeta],
+    },
+}
+
+/// The description of a field of a type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct FieldMeta {
+    /// The name of the field, or [`None`] for the fields of tuple structs and variants.
+    pub ident: Option<&'static str>,
+    /// The type of the field as it is written in the source.
+    pub rust_type: &'static str,
+    /// How the field is represented in XML.
+    pub kind: FieldKind,
+    /// How many times the field can occur.
+    pub cardinality: Cardinality,
+}
+
+impl FieldMeta {
+    /// Creates a new [`FieldMeta`].
+    pub const fn new(
+        ident: Option<&'static str>,
+        rust_type: &'static str,
+        kind: FieldKind,
+        cardinality: Cardinality,
+    ) -> Self {
+        Self {
+            ident,
+            rust_type,
+            kind,
+            cardinality,
+        }
+    }
+}
+
+/// How a field is represented in XML.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FieldKind {
+    /// A child, represented as decided by the type of the field.
+    Value,
+    /// A child element given with `#[xelement]` on the field, containing the value of the field.
+    Element {
+        /// The name of the element.
+        name: ExpandedName<'static>,
+    },
+    /// An attribute given with `#[xattribute]` on the field.
+    Attribute {
+        /// The name of the attribute, or [`None`] for deferred attributes, whose name is decided by the type of the field.
+        name: Option<ExpandedName<'static>>,
+    },
+    /// The attributes and children of a group given with `#[xgroup]` on the field.
+    Group,
+}
+
+/// How many times a field can occur.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Cardinality {
+    /// The field occurs exactly once.
+    Required,
+    /// The field occurs at most once, because it is an [`Option`] or has a default value.
+    Optional,
+    /// The field occurs any number of times, because it is a [`Vec`].
+    Many,
+}
@@ -0,0 +1,486 @@
+//! A small reference backend without dependencies, enabled with the `pure` feature.
+//!
+//! Documents are parsed into an [`XmlValue`] with [`XmlValue::from_str`] and deserialized from it, and values are serialized into an [`XmlValue`] and written out from it. This is slow compared to a streaming backend like `xmlity-quick-xml`, and only supports the subset of XML that [`XmlValue::from_str`] does, but it is useful where other backends are not available, and to test other backends against.
+//!
+//! ```
+//! use xmlity::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! #[xelement(name = "note", namespace = "http://example.com")]
+//! struct Note {
+//!     #[xattribute(name = "id")]
+//!     id: u32,
+//!     text: String,
+//! }
+//!
+//! let note = Note { id: 1, text: "Hello".to_string() };
+//!
+//! let xml = xmlity::pure::to_string(&note).unwrap();
+//! assert_eq!(xml, r#"<note xmlns="http://example.com" id="1">Hello</note>"#);
+//!
+//! assert_eq!(xmlity::pure::from_str::<Note>(&xml).unwrap(), note);
+//! ```
+use std::fmt::{self, Write as _};
+
+use crate::{
+    de::XmlProcessingInstruction as _,
+    escape::{escape_attr, escape_text},
+    ser::{generated_prefix, UndeclaredAttributeNamespaces},
+    value::{
+        self, InvalidContentError, XmlAttribute, XmlChild, XmlElement, XmlValue,
+        XmlValueDeserializerError, XmlValueParseError, XmlValueSerializerError,
+    },
+    DeserializeOwned, Serialize, XmlNamespace, XmlNamespaceBuf,
+};
+
+/// Errors that can occur when using the pure backend.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The document could not be parsed.
+    Parse(XmlValueParseError),
+    /// The parsed document could not be deserialized.
+    Deserialize(XmlValueDeserializerError),
+    /// The value could not be serialized.
+    Serialize(XmlValueSerializerError),
+    /// A node contains content that is not valid UTF-8.
+    InvalidUtf8,
+    /// Malformed sections recorded by lenient parsers can't be written.
+    MalformedSection,
+    /// A comment or processing instruction has content that would end it early.
+    InvalidContent(InvalidContentError),
+    /// A namespace that cannot be bound to any prefix, such as the reserved `xmlns` namespace.
+    UnboundNamespace {
+        /// The namespace that could not be bound.
+        namespace: XmlNamespaceBuf,
+    },
+    /// An attribute is in a namespace that is not declared, and [`SerializerConfig::undeclared_attribute_namespaces`] does not allow declaring it.
+    UndeclaredNamespace {
+        /// The namespace that is not declared.
+        namespace: XmlNamespaceBuf,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(value) => write!(f, "Parse error: {value}"),
+            Self::Deserialize(value) => write!(f, "Deserialize error: {value}"),
+            Self::Serialize(value) => write!(f, "Serialize error: {value}"),
+            Self::InvalidUtf8 => f.write_str("Invalid UTF-8"),
+            Self::MalformedSection => f.write_str("Malformed sections cannot be written"),
+            Self::InvalidContent(value) => write!(f, "Invalid content: {value}"),
+            Self::UnboundNamespace { namespace } => {
+                write!(f, "Namespace {namespace} cannot be bound to a prefix")
+            }
+            Self::UndeclaredNamespace { namespace } => {
+                write!(f, "Namespace {namespace} of an attribute is not declared")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Parse(error) => Some(error),
+            Self::Deserialize(error) => Some(error),
+            Self::Serialize(error) => Some(error),
+            Self::InvalidContent(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<XmlValueParseError> for Error {
+    fn from(error: XmlValueParseError) -> Self {
+        Self::Parse(error)
+    }
+}
+
+impl From<XmlValueDeserializerError> for Error {
+    fn from(error: XmlValueDeserializerError) -> Self {
+        Self::Deserialize(error)
+    }
+}
+
+impl From<XmlValueSerializerError> for Error {
+    fn from(error: XmlValueSerializerError) -> Self {
+        Self::Serialize(error)
+    }
+}
+
+impl From<InvalidContentError> for Error {
+    fn from(error: InvalidContentError) -> Self {
+        Self::InvalidContent(error)
+    }
+}
+
+impl From<Error> for crate::Error {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::Parse(_) | Error::Deserialize(_) => crate::Error::deserialize(error),
+            Error::Serialize(_)
+            | Error::InvalidUtf8
+            | Error::MalformedSection
+            | Error::InvalidContent(_)
+            | Error::UnboundNamespace { .. }
+            | Error::UndeclaredNamespace { .. } => crate::Error::serialize(error),
+        }
+    }
+}
+
+/// Deserialize a value from a string.
+///
+/// Elements can be nested at most [`XmlValue::DEFAULT_RECURSION_LIMIT`] deep, so deeply nested input fails with [`XmlValueParseError::RecursionLimitExceeded`] instead of overflowing the stack.
+pub fn from_str<T: DeserializeOwned>(s: &str) -> Result<T, Error> {
+    let value: XmlValue = s.parse()?;
+
+    value::from_value(&value).map_err(Error::from)
+}
+
+/// Serialize a value into a string.
+pub fn to_string<T: Serialize>(value: &T) -> Result<String, Error> {
+    to_string_with_config(value, &SerializerConfig::default())
+}
+
+/// Serialize a value into a string as configured by `config`.
+pub fn to_string_with_config<T: Serialize>(
+    value: &T,
+    config: &SerializerConfig,
+) -> Result<String, Error> {
+    let value = value::to_value(value)?;
+
+    let mut writer = Writer {
+        undeclared_attribute_namespaces: config.undeclared_attribute_namespaces,
+        ..Writer::default()
+    };
+    writer.write_value(&value)?;
+    Ok(writer.output)
+}
+
+/// Configuration of how [`to_string_with_config`] writes documents.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SerializerConfig {
+    undeclared_attribute_namespaces: UndeclaredAttributeNamespaces,
+}
+
+impl SerializerConfig {
+    /// Create the default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set what happens with attributes in namespaces that are not declared where they are written. Defaults to [`UndeclaredAttributeNamespaces::Declare`].
+    pub fn with_undeclared_attribute_namespaces(
+        mut self,
+        undeclared_attribute_namespaces: UndeclaredAttributeNamespaces,
+    ) -> Self {
+        self.undeclared_attribute_namespaces = undeclared_attribute_namespaces;
+        self
+    }
+
+    /// What happens with attributes in namespaces that are not declared where they are written.
+    pub fn undeclared_attribute_namespaces(&self) -> UndeclaredAttributeNamespaces {
+        self.undeclared_attribute_namespaces
+    }
+}
+
+fn as_str(bytes: &[u8]) -> Result<&str, Error> {
+    std::str::from_utf8(bytes).map_err(|_| Error::InvalidUtf8)
+}
+
+/// Writes [`XmlValue`]s, keeping track of the namespace prefixes that are in scope.
+#[derive(Default)]
+struct Writer {
+    output: String,
+    /// The prefixes that are in scope, with the closest last. The default namespace is bound to the empty prefix, and `None` undeclares it.
+    bindings: Vec<(String, Option<String>)>,
+    /// The number of prefixes generated so far, see [`generated_prefix`].
+    generated_prefixes: usize,
+    undeclared_attribute_namespaces: UndeclaredAttributeNamespaces,
+}
+
+impl Writer {
+    fn lookup(&self, prefix: &str) -> Option<&str> {
+        self.bindings
+            .iter()
+            .rev()
+            .find(|(bound, _)| bound == prefix)
+            .and_then(|(_, namespace)| namespace.as_deref())
+    }
+
+    /// Finds a prefix other than the empty prefix that is bound to the namespace.
+    fn find_prefix(&self, namespace: &str) -> Option<&str> {
+        self.bindings
+            .iter()
+            .rev()
+            .map(|(prefix, _)| prefix.as_str())
+            .find(|prefix| !prefix.is_empty() && self.lookup(prefix) == Some(namespace))
+    }
+
+    fn write_value(&mut self, value: &XmlValue) -> Result<(), Error> {
+        match value {
+            XmlValue::Text(text) => self.output.push_str(&escape_text(as_str(&text.0)?)),
+            XmlValue::CData(cdata) => self.write_cdata(as_str(&cdata.0)?),
+            XmlValue::Element(element) => self.write_element(element)?,
+            XmlValue::Seq(seq) => {
+                for value in seq.iter() {
+                    self.write_value(value)?;
+                }
+            }
+            XmlValue::PI(pi) => self.write_pi(pi)?,
+            XmlValue::Decl(decl) => {
+                write!(self.output, r#"<?xml version="{}""#, decl.version).expect("infallible");
+                if let Some(encoding) = &decl.encoding {
+                    let encoding = crate::ser::utf8_encoding_label(encoding);
+                    write!(self.output, r#" encoding="{encoding}""#).expect("infallible");
+                }
+                if let Some(standalone) = &decl.standalone {
+                    write!(self.output, r#" standalone="{standalone}""#).expect("infallible");
+                }
+                self.output.push_str("?>");
+            }
+            XmlValue::Comment(comment) => self.write_comment(comment)?,
+            XmlValue::Doctype(doctype) => {
+                write!(self.output, "<!DOCTYPE {}>", as_str(&doctype.0)?).expect("infallible")
+            }
+            #[cfg(feature = "recovery")]
+            XmlValue::Error(_) => return Err(Error::MalformedSection),
+            XmlValue::None => {}
+        }
+
+        Ok(())
+    }
+
+    fn write_child(&mut self, child: &XmlChild) -> Result<(), Error> {
+        match child {
+            XmlChild::Text(text) => self.output.push_str(&escape_text(as_str(&text.0)?)),
+            XmlChild::CData(cdata) => self.write_cdata(as_str(&cdata.0)?),
+            XmlChild::Element(element) => self.write_element(element)?,
+            XmlChild::PI(pi) => self.write_pi(pi)?,
+            XmlChild::Comment(comment) => self.write_comment(comment)?,
+            #[cfg(feature = "recovery")]
+            XmlChild::Error(_) => return Err(Error::MalformedSection),
+            XmlChild::None => {}
+        }
+
+        Ok(())
+    }
+
+    /// Writes a CDATA section, splitting content containing `]]>` into adjacent sections between `]]` and `>` like the quick-xml backend does.
+    fn write_cdata(&mut self, mut text: &str) {
+        while let Some(end) = text.find("]]>") {
+            write!(self.output, "<![CDATA[{}]]>", &text[..end + 2]).expect("infallible");
+            text = &text[end + 2..];
+        }
+        write!(self.output, "<![CDATA[{text}]]>").expect("infallible");
+    }
+
+    fn write_comment(&mut self, comment: &value::XmlComment) -> Result<(), Error> {
+        comment.validate()?;
+        write!(self.output, "<!--{}-->", as_str(&comment.0)?).expect("infallible");
+        Ok(())
+    }
+
+    fn write_pi(&mut self, pi: &value::XmlProcessingInstruction) -> Result<(), Error> {
+        let target = as_str(pi.target())?;
+        let content = as_str(pi.content())?;
+
+        if content.contains("?>") {
+            return Err(InvalidContentError::PiTerminator.into());
+        }
+
+        if content.is_empty() {
+            write!(self.output, "<?{target}?>").expect("infallible");
+        } else {
+            write!(self.output, "<?{target} {content}?>").expect("infallible");
+        }
+
+        Ok(())
+    }
+
+    fn write_element(&mut self, element: &XmlElement) -> Result<(), Error> {
+        let scope_start = self.bindings.len();
+
+        for declaration in &element.declarations {
+            self.bindings.push((
+                declaration.prefix.as_str().to_owned(),
+                Some(declaration.namespace.as_str().to_owned()),
+            ));
+        }
+
+        let prefix = self.element_prefix(element, scope_start);
+        let attributes = element
+            .attributes
+            .iter()
+            .map(|attribute| {
+                let prefix = self.attribute_prefix(attribute, scope_start)?;
+                Ok((prefix, attribute))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let qname = match &prefix {
+            Some(prefix) => format!("{prefix}:{}", element.name.local_name()),
+            None => element.name.local_name().to_string(),
+        };
+
+        write!(self.output, "<{qname}").expect("infallible");
+
+        for (prefix, namespace) in &self.bindings[scope_start..] {
+            let namespace = escape_attr(namespace.as_deref().unwrap_or_default());
+            if prefix.is_empty() {
+                write!(self.output, r#" xmlns="{namespace}""#).expect("infallible");
+            } else {
+                write!(self.output, r#" xmlns:{prefix}="{namespace}""#).expect("infallible");
+            }
+        }
+
+        for (prefix, attribute) in attributes {
+            let value = escape_attr(as_str(&attribute.value.0)?);
+            match prefix {
+                Some(prefix) => write!(
+                    self.output,
+                    r#" {prefix}:{}="{value}""#,
+                    attribute.name.local_name()
+                ),
+                None => write!(self.output, r#" {}="{value}""#, attribute.name.local_name()),
+            }
+            .expect("infallible");
+        }
+
+        if element.children.is_empty() {
+            self.output.push_str("/>");
+        } else {
+            self.output.push('>');
+            for child in element.children.iter() {
+                self.write_child(child)?;
+            }
+            write!(self.output, "</{qname}>").expect("infallible");
+        }
+
+        self.bindings.truncate(scope_start);
+
+        Ok(())
+    }
+
+    /// Returns if the prefix can be bound on the current element, which is the case unless it already has a declaration for it.
+    fn can_declare(&self, prefix: &str, scope_start: usize) -> bool {
+        !self.bindings[scope_start..]
+            .iter()
+            .any(|(bound, _)| bound == prefix)
+    }
+
+    /// Binds a generated prefix that is not in use to the namespace.
+    fn declare_new_prefix(&mut self, namespace: &str) -> String {
+        let prefix = loop {
+            let prefix = generated_prefix(self.generated_prefixes)
+                .as_str()
+                .to_owned();
+            self.generated_prefixes += 1;
+            if self.lookup(&prefix).is_none() {
+                break prefix;
+            }
+        };
+
+        self.bindings
+            .push((prefix.clone(), Some(namespace.to_owned())));
+        prefix
+    }
+
+    /// Chooses the prefix of an element, declaring it if necessary. The element is written in the default namespace unless it has a preferred or original prefix.
+    fn element_prefix(&mut self, element: &XmlElement, scope_start: usize) -> Option<String> {
+        let Some(namespace) = element.name.namespace() else {
+            // An element without a namespace can only be written without a prefix, so the default namespace is undeclared if needed.
+            if self.lookup("").is_some() && self.can_declare("", scope_start) {
+                self.bindings.push((String::new(), None));
+            }
+            return None;
+        };
+        let namespace = namespace.as_str();
+
+        let wanted_prefix = element
+            .original_prefix
+            .as_deref()
+            .or(element.preferred_prefix.as_deref())
+            .map(|prefix| prefix.as_str())
+            .filter(|prefix| !prefix.is_empty());
+
+        if let Some(prefix) = wanted_prefix {
+            if self.lookup(prefix) == Some(namespace) {
+                return Some(prefix.to_owned());
+            }
+            if self.can_declare(prefix, scope_start) && namespace != XmlNamespace::XMLNS.as_str() {
+                self.bindings
+                    .push((prefix.to_owned(), Some(namespace.to_owned())));
+                return Some(prefix.to_owned());
+            }
+        }
+
+        if self.lookup("") == Some(namespace) {
+            return None;
+        }
+
+        if self.can_declare("", scope_start) {
+            self.bindings
+                .push((String::new(), Some(namespace.to_owned())));
+            return None;
+        }
+
+        Some(
+            self.find_prefix(namespace)
+                .map(str::to_owned)
+                .unwrap_or_else(|| self.declare_new_prefix(namespace)),
+        )
+    }
+
+    /// Chooses the prefix of an attribute, declaring it if necessary and allowed. Attributes without a prefix are never in a namespace, so attributes in a namespace always need a prefix.
+    fn attribute_prefix(
+        &mut self,
+        attribute: &XmlAttribute,
+        scope_start: usize,
+    ) -> Result<Option<String>, Error> {
+        let Some(namespace) = attribute.name.namespace() else {
+            return Ok(None);
+        };
+
+        if namespace == XmlNamespace::XML {
+            return Ok(Some("xml".to_owned()));
+        }
+        if namespace == XmlNamespace::XMLNS {
+            return Err(Error::UnboundNamespace {
+                namespace: namespace.to_owned(),
+            });
+        }
+        if self.undeclared_attribute_namespaces == UndeclaredAttributeNamespaces::Error
+            && self.find_prefix(namespace.as_str()).is_none()
+        {
+            return Err(Error::UndeclaredNamespace {
+                namespace: namespace.to_owned(),
+            });
+        }
+        let namespace = namespace.as_str();
+
+        if let Some(prefix) = attribute
+            .original_prefix
+            .as_deref()
+            .map(|prefix| prefix.as_str())
+            .filter(|prefix| !prefix.is_empty())
+        {
+            if self.lookup(prefix) == Some(namespace) {
+                return Ok(Some(prefix.to_owned()));
+            }
+            if self.can_declare(prefix, scope_start) {
+                self.bindings
+                    .push((prefix.to_owned(), Some(namespace.to_owned())));
+                return Ok(Some(prefix.to_owned()));
+            }
+        }
+
+        Ok(Some(
+            self.find_prefix(namespace)
+                .map(str::to_owned)
+                .unwrap_or_else(|| self.declare_new_prefix(namespace)),
+        ))
+    }
+}
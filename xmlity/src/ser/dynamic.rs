@@ -0,0 +1,753 @@
+//! Dyn-compatible versions of the serialization traits, which make it possible to serialize through trait objects.
+use std::fmt::{self, Display};
+
+use crate::{ExpandedName, Prefix, XmlNamespace};
+
+use super::{
+    AttributeSerializer, BoolFormat, Error, IncludePrefix, Serialize, SerializeAttribute,
+    SerializeAttributeAccess, SerializeAttributes, SerializeElement, SerializeElementAttributes,
+    SerializeSeq, Serializer, Unexpected,
+};
+
+/// The error returned when serializing through a [`DynSerializer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DynError {
+    /// The serializer did not expect this type of data.
+    Unexpected(Unexpected),
+    /// A custom error.
+    Custom(String),
+    /// The underlying serializer failed. Its error is returned by [`ErasedSerializer::finish`] or [`ErasedAttributeSerializer::finish`].
+    Serializer,
+}
+
+impl fmt::Display for DynError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unexpected(value) => write!(f, "Unexpected: {value}"),
+            Self::Custom(value) => write!(f, "Custom: {value}"),
+            Self::Serializer => f.write_str("The underlying serializer failed"),
+        }
+    }
+}
+
+impl std::error::Error for DynError {}
+
+impl Error for DynError {
+    fn unexpected_serialize(unexpected: Unexpected) -> Self {
+        DynError::Unexpected(unexpected)
+    }
+
+    fn custom<T>(msg: T) -> Self
+    where
+        T: Display,
+    {
+        DynError::Custom(msg.to_string())
+    }
+}
+
+impl DynError {
+    fn already_used() -> Self {
+        DynError::Custom("The serializer has already been used".to_string())
+    }
+}
+
+/// Where an erased serializer keeps the output or the error of the underlying serializer.
+type Outcome<T, E> = Option<Result<T, E>>;
+
+/// Records the outcome of a call to the underlying serializer, returning [`DynError::Serializer`] in its place if it failed.
+fn record<T, O, E>(outcome: &mut Outcome<O, E>, result: Result<T, E>) -> Result<T, DynError> {
+    result.map_err(|err| {
+        *outcome = Some(Err(err));
+        DynError::Serializer
+    })
+}
+
+/// Records the output of a call to the underlying serializer that ends serialization.
+fn record_end<O, E>(outcome: &mut Outcome<O, E>, result: Result<O, E>) -> Result<(), DynError> {
+    match result {
+        Ok(ok) => {
+            *outcome = Some(Ok(ok));
+            Ok(())
+        }
+        Err(err) => record(outcome, Err(err)),
+    }
+}
+
+/// Turns the outcome of serializing through an erased serializer back into the output and error types of the underlying serializer.
+fn finish<O, E: Error>(outcome: Outcome<O, E>, result: Result<(), DynError>) -> Result<O, E> {
+    match (result, outcome) {
+        (Err(DynError::Serializer), Some(Err(err))) => Err(err),
+        (Err(DynError::Unexpected(unexpected)), _) => Err(E::unexpected_serialize(unexpected)),
+        (Err(err), _) => Err(E::custom(err)),
+        (Ok(()), Some(outcome)) => outcome,
+        (Ok(()), None) => Err(E::custom("Nothing was serialized")),
+    }
+}
+
+/// A dyn-compatible version of [`Serializer`].
+///
+/// `&mut dyn DynSerializer` implements [`Serializer`], so non-generic code can accept one and serialize any [`Serialize`] type into it. Serializers are turned into a [`DynSerializer`] by wrapping them in an [`ErasedSerializer`]. This is useful for plugin boundaries, and for keeping serialization code from being instantiated for every serializer in large binaries.
+///
+/// ```
+/// use xmlity::ser::{DynError, DynSerializer, ErasedSerializer};
+/// use xmlity::{Serialize, XmlValue};
+///
+/// #[derive(Serialize)]
+/// #[xelement(name = "note")]
+/// struct Note {
+///     #[xattribute(name = "to")]
+///     to: String,
+/// }
+///
+/// // Not generic over the serializer.
+/// fn write_note(serializer: &mut dyn DynSerializer) -> Result<(), DynError> {
+///     Note { to: "Tove".to_string() }.serialize(serializer)
+/// }
+///
+/// let mut value = XmlValue::None;
+/// let mut serializer = ErasedSerializer::new(&mut value);
+/// let result = write_note(&mut serializer);
+/// serializer.finish(result).unwrap();
+///
+/// assert!(matches!(value, XmlValue::Element(_)));
+/// ```
+pub trait DynSerializer {
+    /// Serialize a text node.
+    fn dyn_serialize_text(&mut self, text: &str) -> Result<(), DynError>;
+
+    /// Serialize a CDATA section.
+    fn dyn_serialize_cdata(&mut self, text: &str) -> Result<(), DynError>;
+
+    /// Serialize an element with children.
+    fn dyn_serialize_element<'a>(
+        &'a mut self,
+        name: &ExpandedName<'_>,
+    ) -> Result<Box<dyn DynSerializeElement + 'a>, DynError>;
+
+    /// Serialize a sequence of elements.
+    fn dyn_serialize_seq<'a>(&'a mut self) -> Result<Box<dyn DynSerializeSeq + 'a>, DynError>;
+
+    /// Serialize an XML declaration.
+    fn dyn_serialize_decl(
+        &mut self,
+        version: &str,
+        encoding: Option<&str>,
+        standalone: Option<&str>,
+    ) -> Result<(), DynError>;
+
+    /// Serialize a processing instruction.
+    fn dyn_serialize_pi(&mut self, target: &[u8], content: &[u8]) -> Result<(), DynError>;
+
+    /// Serialize a comment.
+    fn dyn_serialize_comment(&mut self, text: &[u8]) -> Result<(), DynError>;
+
+    /// Serialize a doctype declaration.
+    fn dyn_serialize_doctype(&mut self, text: &[u8]) -> Result<(), DynError>;
+
+    /// Serialize nothing.
+    fn dyn_serialize_none(&mut self) -> Result<(), DynError>;
+
+    /// The lexical form [`bool`]s are written in.
+    fn dyn_bool_format(&self) -> BoolFormat;
+}
+
+/// A dyn-compatible version of [`SerializeElement`], returned by [`DynSerializer::dyn_serialize_element`].
+pub trait DynSerializeElement {
+    /// Always serialize this element with the given prefix.
+    fn dyn_include_prefix(&mut self, should_enforce: IncludePrefix) -> Result<(), DynError>;
+
+    /// Set the preferred prefix for this element.
+    fn dyn_preferred_prefix(&mut self, preferred_prefix: Option<&Prefix>) -> Result<(), DynError>;
+
+    /// Declare a namespace binding on this element.
+    fn dyn_declare_namespace(
+        &mut self,
+        prefix: &Prefix,
+        namespace: &XmlNamespace,
+    ) -> Result<(), DynError>;
+
+    /// Serialize the attributes of this element.
+    fn dyn_serialize_attributes<'a>(
+        self: Box<Self>,
+    ) -> Result<Box<dyn DynSerializeElementAttributes + 'a>, DynError>
+    where
+        Self: 'a;
+
+    /// Serialize the children of this element.
+    fn dyn_serialize_children<'a>(
+        self: Box<Self>,
+    ) -> Result<Box<dyn DynSerializeSeq + 'a>, DynError>
+    where
+        Self: 'a;
+
+    /// End the serialization of this element with no children.
+    fn dyn_end(self: Box<Self>) -> Result<(), DynError>;
+}
+
+/// A dyn-compatible version of [`SerializeElementAttributes`], returned by [`DynSerializeElement::dyn_serialize_attributes`].
+pub trait DynSerializeElementAttributes {
+    /// Serializes an attribute.
+    fn dyn_serialize_attribute(&mut self, a: &dyn DynSerializeAttribute) -> Result<(), DynError>;
+
+    /// Serialize the children of this element.
+    fn dyn_serialize_children<'a>(
+        self: Box<Self>,
+    ) -> Result<Box<dyn DynSerializeSeq + 'a>, DynError>
+    where
+        Self: 'a;
+
+    /// End the serialization of this element with no children.
+    fn dyn_end(self: Box<Self>) -> Result<(), DynError>;
+}
+
+/// A dyn-compatible version of [`SerializeSeq`], returned by [`DynSerializer::dyn_serialize_seq`].
+pub trait DynSerializeSeq {
+    /// Serialize an element in the sequence.
+    fn dyn_serialize_element(&mut self, v: &dyn DynSerialize) -> Result<(), DynError>;
+
+    /// End the serialization of the sequence.
+    fn dyn_end(self: Box<Self>) -> Result<(), DynError>;
+}
+
+/// A dyn-compatible version of [`AttributeSerializer`].
+///
+/// `&mut dyn DynAttributeSerializer` implements [`AttributeSerializer`]. Attribute serializers are turned into a [`DynAttributeSerializer`] by wrapping them in an [`ErasedAttributeSerializer`].
+pub trait DynAttributeSerializer {
+    /// Serialize an attribute.
+    fn dyn_serialize_attribute<'a>(
+        &'a mut self,
+        name: &ExpandedName<'_>,
+    ) -> Result<Box<dyn DynSerializeAttributeAccess + 'a>, DynError>;
+
+    /// Serialize nothing.
+    fn dyn_serialize_none(&mut self) -> Result<(), DynError>;
+}
+
+/// A dyn-compatible version of [`SerializeAttributeAccess`], returned by [`DynAttributeSerializer::dyn_serialize_attribute`].
+pub trait DynSerializeAttributeAccess {
+    /// Set whether to enforce a prefix when serializing.
+    fn dyn_include_prefix(&mut self, should_include: IncludePrefix) -> Result<(), DynError>;
+
+    /// Set the preferred prefix to use when serializing.
+    fn dyn_preferred_prefix(&mut self, preferred_prefix: Option<&Prefix>) -> Result<(), DynError>;
+
+    /// Serialize the attribute.
+    fn dyn_end(self: Box<Self>, value: &dyn DynSerialize) -> Result<(), DynError>;
+}
+
+/// A dyn-compatible version of [`Serialize`], implemented for every type implementing [`Serialize`].
+///
+/// `dyn DynSerialize` implements [`Serialize`], so values of different types can be serialized through the same code, for example from a `Vec<Box<dyn DynSerialize>>`.
+pub trait DynSerialize {
+    /// Serialize the value.
+    fn dyn_serialize(&self, serializer: &mut dyn DynSerializer) -> Result<(), DynError>;
+}
+
+impl<T: Serialize + ?Sized> DynSerialize for T {
+    fn dyn_serialize(&self, serializer: &mut dyn DynSerializer) -> Result<(), DynError> {
+        self.serialize(serializer)
+    }
+}
+
+impl Serialize for dyn DynSerialize + '_ {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut serializer = ErasedSerializer::new(serializer);
+        let result = self.dyn_serialize(&mut serializer);
+        serializer.finish(result)
+    }
+}
+
+/// Lets a `&dyn DynSerialize` be passed where a sized [`Serialize`] type is expected.
+struct SerializeDyn<'a>(&'a dyn DynSerialize);
+
+impl Serialize for SerializeDyn<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+/// A dyn-compatible version of [`SerializeAttribute`], implemented for every type implementing [`SerializeAttribute`].
+///
+/// `&dyn DynSerializeAttribute` implements [`SerializeAttribute`].
+pub trait DynSerializeAttribute {
+    /// Serialize the attribute.
+    fn dyn_serialize_attribute(
+        &self,
+        serializer: &mut dyn DynAttributeSerializer,
+    ) -> Result<(), DynError>;
+}
+
+impl<T: SerializeAttribute> DynSerializeAttribute for T {
+    fn dyn_serialize_attribute(
+        &self,
+        serializer: &mut dyn DynAttributeSerializer,
+    ) -> Result<(), DynError> {
+        self.serialize_attribute(serializer)
+    }
+}
+
+impl SerializeAttribute for &(dyn DynSerializeAttribute + '_) {
+    fn serialize_attribute<S: AttributeSerializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut serializer = ErasedAttributeSerializer::new(serializer);
+        let result = (**self).dyn_serialize_attribute(&mut serializer);
+        serializer.finish(result)
+    }
+}
+
+// Trait objects as serializers
+
+impl<'a> Serializer for &'a mut (dyn DynSerializer + '_) {
+    type Ok = ();
+    type Error = DynError;
+    type SerializeElement = Box<dyn DynSerializeElement + 'a>;
+    type SerializeSeq = Box<dyn DynSerializeSeq + 'a>;
+
+    fn serialize_text<S: AsRef<str>>(self, text: S) -> Result<Self::Ok, Self::Error> {
+        self.dyn_serialize_text(text.as_ref())
+    }
+
+    fn serialize_cdata<S: AsRef<str>>(self, text: S) -> Result<Self::Ok, Self::Error> {
+        self.dyn_serialize_cdata(text.as_ref())
+    }
+
+    fn serialize_element(
+        self,
+        name: &'_ ExpandedName<'_>,
+    ) -> Result<Self::SerializeElement, Self::Error> {
+        self.dyn_serialize_element(name)
+    }
+
+    fn serialize_seq(self) -> Result<Self::SerializeSeq, Self::Error> {
+        self.dyn_serialize_seq()
+    }
+
+    fn serialize_decl<S: AsRef<str>>(
+        self,
+        version: S,
+        encoding: Option<S>,
+        standalone: Option<S>,
+    ) -> Result<Self::Ok, Self::Error> {
+        self.dyn_serialize_decl(
+            version.as_ref(),
+            encoding.as_ref().map(AsRef::as_ref),
+            standalone.as_ref().map(AsRef::as_ref),
+        )
+    }
+
+    fn serialize_pi<S: AsRef<[u8]>>(self, target: S, content: S) -> Result<Self::Ok, Self::Error> {
+        self.dyn_serialize_pi(target.as_ref(), content.as_ref())
+    }
+
+    fn serialize_comment<S: AsRef<[u8]>>(self, text: S) -> Result<Self::Ok, Self::Error> {
+        self.dyn_serialize_comment(text.as_ref())
+    }
+
+    fn serialize_doctype<S: AsRef<[u8]>>(self, text: S) -> Result<Self::Ok, Self::Error> {
+        self.dyn_serialize_doctype(text.as_ref())
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        self.dyn_serialize_none()
+    }
+
+    fn bool_format(&self) -> BoolFormat {
+        self.dyn_bool_format()
+    }
+}
+
+impl<'a> SerializeElement for Box<dyn DynSerializeElement + 'a> {
+    type Ok = ();
+    type Error = DynError;
+    type ChildrenSerializeSeq = Box<dyn DynSerializeSeq + 'a>;
+    type SerializeElementAttributes = Box<dyn DynSerializeElementAttributes + 'a>;
+
+    fn include_prefix(&mut self, should_enforce: IncludePrefix) -> Result<Self::Ok, Self::Error> {
+        self.dyn_include_prefix(should_enforce)
+    }
+
+    fn preferred_prefix(
+        &mut self,
+        preferred_prefix: Option<&Prefix>,
+    ) -> Result<Self::Ok, Self::Error> {
+        self.dyn_preferred_prefix(preferred_prefix)
+    }
+
+    fn declare_namespace(
+        &mut self,
+        prefix: &Prefix,
+        namespace: &XmlNamespace,
+    ) -> Result<(), Self::Error> {
+        self.dyn_declare_namespace(prefix, namespace)
+    }
+
+    fn serialize_attributes(self) -> Result<Self::SerializeElementAttributes, Self::Error> {
+        self.dyn_serialize_attributes()
+    }
+
+    fn serialize_children(self) -> Result<Self::ChildrenSerializeSeq, Self::Error> {
+        self.dyn_serialize_children()
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.dyn_end()
+    }
+}
+
+impl SerializeAttributes for Box<dyn DynSerializeElementAttributes + '_> {
+    type Ok = ();
+    type Error = DynError;
+
+    fn serialize_attribute<A: SerializeAttribute>(
+        &mut self,
+        a: &A,
+    ) -> Result<Self::Ok, Self::Error> {
+        self.dyn_serialize_attribute(a)
+    }
+}
+
+impl<'a> SerializeElementAttributes for Box<dyn DynSerializeElementAttributes + 'a> {
+    type ChildrenSerializeSeq = Box<dyn DynSerializeSeq + 'a>;
+
+    fn serialize_children(self) -> Result<Self::ChildrenSerializeSeq, Self::Error> {
+        self.dyn_serialize_children()
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.dyn_end()
+    }
+}
+
+impl SerializeSeq for Box<dyn DynSerializeSeq + '_> {
+    type Ok = ();
+    type Error = DynError;
+
+    fn serialize_element<V: Serialize>(&mut self, v: &V) -> Result<(), Self::Error> {
+        self.dyn_serialize_element(v)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.dyn_end()
+    }
+}
+
+impl AttributeSerializer for &mut (dyn DynAttributeSerializer + '_) {
+    type Ok = ();
+    type Error = DynError;
+    type SerializeAttribute<'b>
+        = Box<dyn DynSerializeAttributeAccess + 'b>
+    where
+        Self: 'b;
+
+    fn serialize_attribute(
+        &mut self,
+        name: &'_ ExpandedName<'_>,
+    ) -> Result<Self::SerializeAttribute<'_>, Self::Error> {
+        self.dyn_serialize_attribute(name)
+    }
+
+    fn serialize_none(&mut self) -> Result<Self::Ok, Self::Error> {
+        self.dyn_serialize_none()
+    }
+}
+
+impl SerializeAttributeAccess for Box<dyn DynSerializeAttributeAccess + '_> {
+    type Ok = ();
+    type Error = DynError;
+
+    fn include_prefix(&mut self, should_include: IncludePrefix) -> Result<Self::Ok, Self::Error> {
+        self.dyn_include_prefix(should_include)
+    }
+
+    fn preferred_prefix(
+        &mut self,
+        preferred_prefix: Option<&Prefix>,
+    ) -> Result<Self::Ok, Self::Error> {
+        self.dyn_preferred_prefix(preferred_prefix)
+    }
+
+    fn end<S: Serialize>(self, value: &S) -> Result<Self::Ok, Self::Error> {
+        self.dyn_end(value)
+    }
+}
+
+// Serializers as trait objects
+
+/// Wraps a [`Serializer`] so that it can be used as a [`DynSerializer`].
+///
+/// A serializer is consumed by serializing one value. Once that is done, [`ErasedSerializer::finish`] returns the output of the serializer.
+pub struct ErasedSerializer<S: Serializer> {
+    serializer: Option<S>,
+    outcome: Outcome<S::Ok, S::Error>,
+}
+
+impl<S: Serializer> ErasedSerializer<S> {
+    /// Wraps a serializer.
+    pub fn new(serializer: S) -> Self {
+        Self {
+            serializer: Some(serializer),
+            outcome: None,
+        }
+    }
+
+    /// Returns the output of the underlying serializer, given the result of serializing through this [`DynSerializer`].
+    ///
+    /// Errors of the underlying serializer are returned as they are, while other errors are converted using [`Error::custom`].
+    pub fn finish(self, result: Result<(), DynError>) -> Result<S::Ok, S::Error> {
+        finish(self.outcome, result)
+    }
+
+    fn take(&mut self) -> Result<S, DynError> {
+        self.serializer.take().ok_or_else(DynError::already_used)
+    }
+}
+
+impl<S: Serializer> DynSerializer for ErasedSerializer<S> {
+    fn dyn_serialize_text(&mut self, text: &str) -> Result<(), DynError> {
+        let result = self.take()?.serialize_text(text);
+        record_end(&mut self.outcome, result)
+    }
+
+    fn dyn_serialize_cdata(&mut self, text: &str) -> Result<(), DynError> {
+        let result = self.take()?.serialize_cdata(text);
+        record_end(&mut self.outcome, result)
+    }
+
+    fn dyn_serialize_element<'a>(
+        &'a mut self,
+        name: &ExpandedName<'_>,
+    ) -> Result<Box<dyn DynSerializeElement + 'a>, DynError> {
+        let result = self.take()?.serialize_element(name);
+        let element = record(&mut self.outcome, result)?;
+        Ok(Box::new(ErasedSerializeElement {
+            element,
+            outcome: &mut self.outcome,
+        }))
+    }
+
+    fn dyn_serialize_seq<'a>(&'a mut self) -> Result<Box<dyn DynSerializeSeq + 'a>, DynError> {
+        let result = self.take()?.serialize_seq();
+        let seq = record(&mut self.outcome, result)?;
+        Ok(Box::new(ErasedSerializeSeq {
+            seq,
+            outcome: &mut self.outcome,
+        }))
+    }
+
+    fn dyn_serialize_decl(
+        &mut self,
+        version: &str,
+        encoding: Option<&str>,
+        standalone: Option<&str>,
+    ) -> Result<(), DynError> {
+        let result = self.take()?.serialize_decl(version, encoding, standalone);
+        record_end(&mut self.outcome, result)
+    }
+
+    fn dyn_serialize_pi(&mut self, target: &[u8], content: &[u8]) -> Result<(), DynError> {
+        let result = self.take()?.serialize_pi(target, content);
+        record_end(&mut self.outcome, result)
+    }
+
+    fn dyn_serialize_comment(&mut self, text: &[u8]) -> Result<(), DynError> {
+        let result = self.take()?.serialize_comment(text);
+        record_end(&mut self.outcome, result)
+    }
+
+    fn dyn_serialize_doctype(&mut self, text: &[u8]) -> Result<(), DynError> {
+        let result = self.take()?.serialize_doctype(text);
+        record_end(&mut self.outcome, result)
+    }
+
+    fn dyn_serialize_none(&mut self) -> Result<(), DynError> {
+        let result = self.take()?.serialize_none();
+        record_end(&mut self.outcome, result)
+    }
+
+    fn dyn_bool_format(&self) -> BoolFormat {
+        self.serializer
+            .as_ref()
+            .map(Serializer::bool_format)
+            .unwrap_or_default()
+    }
+}
+
+struct ErasedSerializeElement<'a, E: SerializeElement> {
+    element: E,
+    outcome: &'a mut Outcome<E::Ok, E::Error>,
+}
+
+impl<E: SerializeElement> DynSerializeElement for ErasedSerializeElement<'_, E> {
+    fn dyn_include_prefix(&mut self, should_enforce: IncludePrefix) -> Result<(), DynError> {
+        let result = self.element.include_prefix(should_enforce);
+        record(self.outcome, result).map(drop)
+    }
+
+    fn dyn_preferred_prefix(&mut self, preferred_prefix: Option<&Prefix>) -> Result<(), DynError> {
+        let result = self.element.preferred_prefix(preferred_prefix);
+        record(self.outcome, result).map(drop)
+    }
+
+    fn dyn_declare_namespace(
+        &mut self,
+        prefix: &Prefix,
+        namespace: &XmlNamespace,
+    ) -> Result<(), DynError> {
+        let result = self.element.declare_namespace(prefix, namespace);
+        record(self.outcome, result)
+    }
+
+    fn dyn_serialize_attributes<'b>(
+        self: Box<Self>,
+    ) -> Result<Box<dyn DynSerializeElementAttributes + 'b>, DynError>
+    where
+        Self: 'b,
+    {
+        let Self { element, outcome } = *self;
+        let attributes = record(outcome, element.serialize_attributes())?;
+        Ok(Box::new(ErasedSerializeElementAttributes {
+            attributes,
+            outcome,
+        }))
+    }
+
+    fn dyn_serialize_children<'b>(
+        self: Box<Self>,
+    ) -> Result<Box<dyn DynSerializeSeq + 'b>, DynError>
+    where
+        Self: 'b,
+    {
+        let Self { element, outcome } = *self;
+        let seq = record(outcome, element.serialize_children())?;
+        Ok(Box::new(ErasedSerializeSeq { seq, outcome }))
+    }
+
+    fn dyn_end(self: Box<Self>) -> Result<(), DynError> {
+        let Self { element, outcome } = *self;
+        record_end(outcome, element.end())
+    }
+}
+
+struct ErasedSerializeElementAttributes<'a, A: SerializeElementAttributes> {
+    attributes: A,
+    outcome: &'a mut Outcome<A::Ok, A::Error>,
+}
+
+impl<A: SerializeElementAttributes> DynSerializeElementAttributes
+    for ErasedSerializeElementAttributes<'_, A>
+{
+    fn dyn_serialize_attribute(&mut self, a: &dyn DynSerializeAttribute) -> Result<(), DynError> {
+        let result = self.attributes.serialize_attribute(&a);
+        record(self.outcome, result).map(drop)
+    }
+
+    fn dyn_serialize_children<'b>(
+        self: Box<Self>,
+    ) -> Result<Box<dyn DynSerializeSeq + 'b>, DynError>
+    where
+        Self: 'b,
+    {
+        let Self {
+            attributes,
+            outcome,
+        } = *self;
+        let seq = record(outcome, attributes.serialize_children())?;
+        Ok(Box::new(ErasedSerializeSeq { seq, outcome }))
+    }
+
+    fn dyn_end(self: Box<Self>) -> Result<(), DynError> {
+        let Self {
+            attributes,
+            outcome,
+        } = *self;
+        record_end(outcome, attributes.end())
+    }
+}
+
+struct ErasedSerializeSeq<'a, Q: SerializeSeq> {
+    seq: Q,
+    outcome: &'a mut Outcome<Q::Ok, Q::Error>,
+}
+
+impl<Q: SerializeSeq> DynSerializeSeq for ErasedSerializeSeq<'_, Q> {
+    fn dyn_serialize_element(&mut self, v: &dyn DynSerialize) -> Result<(), DynError> {
+        let result = self.seq.serialize_element(&SerializeDyn(v));
+        record(self.outcome, result)
+    }
+
+    fn dyn_end(self: Box<Self>) -> Result<(), DynError> {
+        let Self { seq, outcome } = *self;
+        record_end(outcome, seq.end())
+    }
+}
+
+/// Wraps an [`AttributeSerializer`] so that it can be used as a [`DynAttributeSerializer`].
+///
+/// Once an attribute has been serialized, [`ErasedAttributeSerializer::finish`] returns the output of the serializer.
+pub struct ErasedAttributeSerializer<S: AttributeSerializer> {
+    serializer: S,
+    outcome: Outcome<S::Ok, S::Error>,
+}
+
+impl<S: AttributeSerializer> ErasedAttributeSerializer<S> {
+    /// Wraps an attribute serializer.
+    pub fn new(serializer: S) -> Self {
+        Self {
+            serializer,
+            outcome: None,
+        }
+    }
+
+    /// Returns the output of the underlying serializer, given the result of serializing through this [`DynAttributeSerializer`].
+    ///
+    /// Errors of the underlying serializer are returned as they are, while other errors are converted using [`Error::custom`].
+    pub fn finish(self, result: Result<(), DynError>) -> Result<S::Ok, S::Error> {
+        finish(self.outcome, result)
+    }
+}
+
+impl<S: AttributeSerializer> DynAttributeSerializer for ErasedAttributeSerializer<S> {
+    fn dyn_serialize_attribute<'a>(
+        &'a mut self,
+        name: &ExpandedName<'_>,
+    ) -> Result<Box<dyn DynSerializeAttributeAccess + 'a>, DynError> {
+        let Self {
+            serializer,
+            outcome,
+        } = self;
+        let access = record(outcome, serializer.serialize_attribute(name))?;
+        Ok(Box::new(ErasedSerializeAttributeAccess { access, outcome }))
+    }
+
+    fn dyn_serialize_none(&mut self) -> Result<(), DynError> {
+        let result = self.serializer.serialize_none();
+        record_end(&mut self.outcome, result)
+    }
+}
+
+struct ErasedSerializeAttributeAccess<'a, A: SerializeAttributeAccess> {
+    access: A,
+    outcome: &'a mut Outcome<A::Ok, A::Error>,
+}
+
+impl<A: SerializeAttributeAccess> DynSerializeAttributeAccess
+    for ErasedSerializeAttributeAccess<'_, A>
+{
+    fn dyn_include_prefix(&mut self, should_include: IncludePrefix) -> Result<(), DynError> {
+        let result = self.access.include_prefix(should_include);
+        record(self.outcome, result).map(drop)
+    }
+
+    fn dyn_preferred_prefix(&mut self, preferred_prefix: Option<&Prefix>) -> Result<(), DynError> {
+        let result = self.access.preferred_prefix(preferred_prefix);
+        record(self.outcome, result).map(drop)
+    }
+
+    fn dyn_end(self: Box<Self>, value: &dyn DynSerialize) -> Result<(), DynError> {
+        let Self { access, outcome } = *self;
+        record_end(outcome, access.end(&SerializeDyn(value)))
+    }
+}
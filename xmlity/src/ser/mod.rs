@@ -1,47 +1,109 @@
 //! This module contains the [`Serialize`], [`SerializeAttribute`], [`Serializer`] and [`SerializationGroup`] traits and associated types.
-use std::fmt::{Debug, Display};
+use std::fmt::{self, Debug, Display};
 
-use crate::{ExpandedName, Prefix};
+use crate::{ExpandedName, ExpandedNameBuf, Prefix, XmlNamespace};
+
+mod dynamic;
+mod validate;
+
+pub use dynamic::{
+    DynAttributeSerializer, DynError, DynSerialize, DynSerializeAttribute,
+    DynSerializeAttributeAccess, DynSerializeElement, DynSerializeElementAttributes,
+    DynSerializeSeq, DynSerializer, ErasedAttributeSerializer, ErasedSerializer,
+};
+pub use validate::{validate, ValidationIssue, ValidationIssueKind, ValidationReport};
 
 /// An enum representing the unexpected type of data that was expected.
-#[derive(Debug, thiserror::Error)]
+///
+/// Elements, attributes and processing instructions carry their names, so that error handling code can react to what was written without parsing error messages.
+#[derive(Debug, Clone, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum Unexpected {
     /// A text node.
-    #[error("text")]
     Text,
     /// A CDATA section.
-    #[error("cdata")]
     CData,
     /// A sequence of XML values.
-    #[error("sequence")]
     Seq,
     /// An element.
-    #[error("element")]
-    Element,
+    Element {
+        /// The name of the element.
+        name: Box<ExpandedNameBuf>,
+    },
     /// An attribute.
-    #[error("attribute")]
-    Attribute,
+    Attribute {
+        /// The name of the attribute.
+        name: Box<ExpandedNameBuf>,
+    },
     /// A comment.
-    #[error("comment")]
     Comment,
     /// A declaration.
-    #[error("declaration")]
     Decl,
     /// A processing instruction.
-    #[error("processing instruction")]
-    PI,
+    PI {
+        /// The target of the processing instruction.
+        target: String,
+    },
     /// A doctype.
-    #[error("doctype")]
     DocType,
     /// End of file.
-    #[error("eof")]
     Eof,
     /// Nothing.
-    #[error("none")]
     None,
 }
 
+impl fmt::Display for Unexpected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Text => f.write_str("text"),
+            Self::CData => f.write_str("cdata"),
+            Self::Seq => f.write_str("sequence"),
+            Self::Element { name } => write!(f, "element {name}"),
+            Self::Attribute { name } => write!(f, "attribute {name}"),
+            Self::Comment => f.write_str("comment"),
+            Self::Decl => f.write_str("declaration"),
+            Self::PI { target } => write!(f, "processing instruction {target}"),
+            Self::DocType => f.write_str("doctype"),
+            Self::Eof => f.write_str("eof"),
+            Self::None => f.write_str("none"),
+        }
+    }
+}
+
+impl std::error::Error for Unexpected {}
+
+impl Unexpected {
+    /// Creates an [`Unexpected::Element`] with the given name.
+    pub fn element(name: &ExpandedName<'_>) -> Self {
+        Unexpected::Element {
+            name: Box::new(name.into_owned()),
+        }
+    }
+
+    /// Creates an [`Unexpected::PI`] with the given target.
+    pub fn pi<T: AsRef<[u8]>>(target: T) -> Self {
+        Unexpected::PI {
+            target: String::from_utf8_lossy(target.as_ref()).into_owned(),
+        }
+    }
+
+    /// The name of the element or attribute, if an element or attribute was written.
+    pub fn name(&self) -> Option<&ExpandedNameBuf> {
+        match self {
+            Unexpected::Element { name } | Unexpected::Attribute { name } => Some(name),
+            _ => None,
+        }
+    }
+
+    /// The target of the processing instruction, if a processing instruction was written.
+    pub fn target(&self) -> Option<&str> {
+        match self {
+            Unexpected::PI { target } => Some(target),
+            _ => None,
+        }
+    }
+}
+
 /// A trait for errors that can be returned by serializer after a serialization attempt.
 pub trait Error {
     /// Error for when a serializer expects a certain type, but it is not.
@@ -114,6 +176,18 @@ pub trait SerializeElement {
         preferred_prefix: Option<&Prefix>,
     ) -> Result<Self::Ok, Self::Error>;
 
+    /// Declare a namespace binding on this element, even if neither the element nor its attributes use it.
+    ///
+    /// Serializers that do not write namespace declarations can ignore this, which is the default.
+    fn declare_namespace(
+        &mut self,
+        prefix: &Prefix,
+        namespace: &XmlNamespace,
+    ) -> Result<(), Self::Error> {
+        let _ = (prefix, namespace);
+        Ok(())
+    }
+
     /// Serialize the attributes of this element.
     fn serialize_attributes(self) -> Result<Self::SerializeElementAttributes, Self::Error>;
 
@@ -184,6 +258,11 @@ pub trait Serializer: Sized {
 
     /// Serialize nothing.
     fn serialize_none(self) -> Result<Self::Ok, Self::Error>;
+
+    /// The lexical form [`bool`]s are written in. Defaults to [`BoolFormat::Text`].
+    fn bool_format(&self) -> BoolFormat {
+        BoolFormat::default()
+    }
 }
 
 /// A type that can serialize attributes. Works in a similar way to [`Serializer`].
@@ -239,6 +318,69 @@ pub enum IncludePrefix {
     Never,
 }
 
+/// The lexical form a [`bool`] is written in, since schemas sometimes only accept one of the forms `xs:boolean` allows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum BoolFormat {
+    /// `true` and `false`.
+    #[default]
+    Text,
+    /// `1` and `0`.
+    Numeric,
+}
+
+impl BoolFormat {
+    /// Returns the text `value` is written as in this format.
+    pub fn format(self, value: bool) -> &'static str {
+        match (self, value) {
+            (BoolFormat::Text, true) => "true",
+            (BoolFormat::Text, false) => "false",
+            (BoolFormat::Numeric, true) => "1",
+            (BoolFormat::Numeric, false) => "0",
+        }
+    }
+}
+
+/// What a serializer does with an attribute in a namespace that is not bound to any prefix where the attribute is written, such as an [`XmlAttribute`](crate::value::XmlAttribute) whose namespace is not declared on its element or any ancestor.
+///
+/// Attributes in the `xml` namespace can always be written, as its prefix is bound implicitly, and attributes in the `xmlns` namespace can never be written, as that namespace can't be bound to a prefix.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum UndeclaredAttributeNamespaces {
+    /// Declare the namespace on the element of the attribute, with the preferred prefix of the attribute if it is free and a generated prefix like `a0` otherwise.
+    #[default]
+    Declare,
+    /// Fail serialization, for documents where every namespace must be declared by the element or one of its ancestors.
+    Error,
+}
+
+/// Returns the prefix that serializers generate for the namespace with the given index, when a namespace has to be declared and no preferred prefix can be used.
+///
+/// The prefixes are `a0` to `a9`, `b0` to `b9` and so on up to `z9`, after which the letters start over with larger numbers, `a10` to `z19` and so on. Generating them the same way in every backend makes documents with undeclared namespaces come out the same regardless of the backend.
+pub fn generated_prefix(index: usize) -> crate::PrefixBuf {
+    let letter = (b'a' + (index / 10 % 26) as u8) as char;
+    let number = index % 10 + index / 260 * 10;
+    crate::PrefixBuf::new(format!("{letter}{number}")).expect("generated prefixes are valid")
+}
+
+/// Returns the encoding label that serializers write for the encoding label of an XML declaration.
+///
+/// Serializers always write UTF-8, so any other label would misdescribe the document, for example when re-serializing a document that was read from ISO-8859-1. Labels naming UTF-8 are kept as they are, and all others are replaced by `UTF-8`.
+///
+/// ```
+/// use xmlity::ser::utf8_encoding_label;
+///
+/// assert_eq!(utf8_encoding_label("utf-8"), "utf-8");
+/// assert_eq!(utf8_encoding_label("ISO-8859-1"), "UTF-8");
+/// ```
+pub fn utf8_encoding_label(label: &str) -> &str {
+    if label.eq_ignore_ascii_case("utf-8") || label.eq_ignore_ascii_case("utf8") {
+        label
+    } else {
+        "UTF-8"
+    }
+}
+
 /// A type that can be used to serialize an attribute.
 pub trait SerializeAttributeAccess: Sized {
     /// The type of the value that is returned when serialization is successful.
@@ -315,3 +457,56 @@ impl<T: SerializationGroup> SerializationGroup for &mut T {
         T::serialize_children(*self, serializer)
     }
 }
+
+/// A [`SerializationGroup`] serialized on its own as an element, created by [`serialize_group_as_element`].
+#[derive(Debug)]
+pub struct GroupAsElement<'a, G> {
+    name: ExpandedName<'a>,
+    group: &'a G,
+}
+
+impl<G> Clone for GroupAsElement<'_, G> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<G> Copy for GroupAsElement<'_, G> {}
+
+impl<G: SerializationGroup> Serialize for GroupAsElement<'_, G> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let element = serializer.serialize_element(&self.name)?;
+        let mut attributes = element.serialize_attributes()?;
+        self.group.serialize_attributes(&mut attributes)?;
+        let mut children = attributes.serialize_children()?;
+        self.group.serialize_children(&mut children)?;
+        children.end()
+    }
+}
+
+/// Wraps the attributes and children of a [`SerializationGroup`] in an element with the given name, so that the group can be serialized on its own.
+///
+/// Groups are normally only serialized as part of the element of the type containing them. Serializing one standalone is useful for testing groups, and for reusing groups as fragments of documents.
+///
+/// ```
+/// use xmlity::{ser::serialize_group_as_element, ExpandedName, LocalName};
+/// use xmlity_derive::SerializationGroup;
+///
+/// #[derive(SerializationGroup)]
+/// struct Common {
+///     #[xattribute(name = "id")]
+///     id: String,
+/// }
+///
+/// let name = ExpandedName::new(LocalName::new("fragment").unwrap(), None);
+/// let common = Common { id: "a".to_string() };
+///
+/// let value = xmlity::value::to_value(&serialize_group_as_element(name, &common)).unwrap();
+/// assert!(matches!(value, xmlity::XmlValue::Element(_)));
+/// ```
+pub fn serialize_group_as_element<'a, G: SerializationGroup>(
+    name: ExpandedName<'a>,
+    group: &'a G,
+) -> GroupAsElement<'a, G> {
+    GroupAsElement { name, group }
+}
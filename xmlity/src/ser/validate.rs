@@ -0,0 +1,726 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+
+use crate::{
+    escape::is_xml_char,
+    noop::NoopDeSerializer,
+    value::{InvalidContentError, XmlComment, XmlValuePath, XmlValuePathSegment},
+    ExpandedName, ExpandedNameBuf, Prefix, PrefixBuf, XmlNamespace, XmlNamespaceBuf,
+};
+
+use super::{
+    AttributeSerializer, Error, IncludePrefix, Serialize, SerializeAttribute,
+    SerializeAttributeAccess, SerializeAttributes, SerializeElement, SerializeElementAttributes,
+    SerializeSeq, Serializer, Unexpected,
+};
+
+/// Checks that a value serializes to well-formed XML, without producing any output.
+///
+/// The value is walked with a serializer that checks that text, attribute values and other content only contain characters allowed in XML, that comments and processing instructions can be written as such, that attributes are not repeated and that namespace prefixes are used and declared consistently. This makes it a cheap way to pre-flight values constructed at runtime, such as [`XmlValue`](crate::XmlValue)s built from user input, before handing them to a serializer.
+///
+/// All issues are collected into the returned [`ValidationReport`], except for errors that would also stop a serializer, such as an element written as the value of an attribute, which end the validation.
+///
+/// ```
+/// use xmlity::{
+///     ser::ValidationIssueKind,
+///     value::{XmlElement, XmlText},
+///     ExpandedNameBuf, LocalNameBuf,
+/// };
+///
+/// let element = XmlElement::new(ExpandedNameBuf::new(
+///     LocalNameBuf::new("note".to_string()).unwrap(),
+///     None,
+/// ))
+/// .with_child(XmlText::new("Bell: \u{7}"));
+///
+/// let report = xmlity::ser::validate(&element).unwrap_err();
+/// assert_eq!(report.issues()[0].path().to_string(), "/note");
+/// assert_eq!(
+///     report.issues()[0].kind(),
+///     &ValidationIssueKind::InvalidChar { character: '\u{7}' }
+/// );
+/// ```
+pub fn validate<T: Serialize + ?Sized>(value: &T) -> Result<(), ValidationReport> {
+    let mut state = State::default();
+    state.push_children();
+
+    if let Err(Abort(kind)) = value.serialize(ValidateSerializer { state: &mut state }) {
+        state.report(kind);
+    }
+
+    while !state.siblings.is_empty() {
+        state.pop_children();
+    }
+
+    if state.issues.is_empty() {
+        Ok(())
+    } else {
+        Err(ValidationReport {
+            issues: state
+                .issues
+                .into_iter()
+                .map(|(path, kind)| ValidationIssue {
+                    path: XmlValuePath::from_outermost(path),
+                    kind,
+                })
+                .collect(),
+        })
+    }
+}
+
+/// The issues found by [`validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationReport {
+    issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    /// Returns the issues in the order they were found.
+    pub fn issues(&self) -> &[ValidationIssue] {
+        &self.issues
+    }
+
+    /// Returns the issues in the order they were found, consuming the report.
+    pub fn into_issues(self) -> Vec<ValidationIssue> {
+        self.issues
+    }
+}
+
+impl Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, issue) in self.issues.iter().enumerate() {
+            if index > 0 {
+                f.write_str("; ")?;
+            }
+            issue.fmt(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationReport {}
+
+/// An issue found by [`validate`], together with where in the serialized document it was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+    path: XmlValuePath,
+    kind: ValidationIssueKind,
+}
+
+impl ValidationIssue {
+    /// The element or attribute the issue was found in. The path is empty for issues outside of any element.
+    pub fn path(&self) -> &XmlValuePath {
+        &self.path
+    }
+
+    /// What the issue is.
+    pub fn kind(&self) -> &ValidationIssueKind {
+        &self.kind
+    }
+}
+
+impl Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.segments().next().is_some() {
+            write!(f, "{} at {}", self.kind, self.path)
+        } else {
+            self.kind.fmt(f)
+        }
+    }
+}
+
+/// The kinds of issues found by [`validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ValidationIssueKind {
+    /// Content contains a character that is not allowed in XML documents, such as most control characters.
+    InvalidChar {
+        /// The first invalid character.
+        character: char,
+    },
+    /// A comment, processing instruction or doctype is not valid UTF-8.
+    InvalidUtf8,
+    /// Content can't be written as the node it is meant for, such as a comment containing `--`.
+    InvalidContent(InvalidContentError),
+    /// The target of a processing instruction is not a valid name, or is the reserved `xml`.
+    InvalidPiTarget {
+        /// The target of the processing instruction.
+        target: String,
+    },
+    /// A part of an XML declaration has an invalid value.
+    InvalidDeclaration {
+        /// The part of the declaration, which is `version`, `encoding` or `standalone`.
+        part: &'static str,
+        /// The invalid value.
+        value: String,
+    },
+    /// A reserved prefix is used for a namespace it can't be bound to, such as `xmlns` for any namespace.
+    ReservedPrefix {
+        /// The reserved prefix.
+        prefix: PrefixBuf,
+    },
+    /// A reserved namespace is used where it can't be, such as the namespace of `xmlns` for an element.
+    ReservedNamespace {
+        /// The reserved namespace.
+        namespace: XmlNamespaceBuf,
+    },
+    /// A prefix is declared for two different namespaces on the same element.
+    PrefixConflict {
+        /// The prefix declared twice.
+        prefix: PrefixBuf,
+        /// The namespace of the second declaration.
+        namespace: XmlNamespaceBuf,
+        /// The namespace of the first declaration.
+        existing_namespace: XmlNamespaceBuf,
+    },
+    /// An attribute is written more than once on the same element.
+    DuplicateAttribute {
+        /// The name of the attribute.
+        name: ExpandedNameBuf,
+    },
+    /// A node is written where it is not allowed, such as an element in the value of an attribute.
+    Unexpected(Unexpected),
+    /// A custom error from a [`Serialize`] implementation.
+    Custom(String),
+}
+
+impl fmt::Display for ValidationIssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidChar { character } => write!(f, "invalid character {character:?}"),
+            Self::InvalidUtf8 => f.write_str("content is not valid UTF-8"),
+            Self::InvalidContent(value) => write!(f, "{value}"),
+            Self::InvalidPiTarget { target } => {
+                write!(f, "invalid processing instruction target {target:?}")
+            }
+            Self::InvalidDeclaration { part, value } => {
+                write!(f, "invalid {part} {value:?} in XML declaration")
+            }
+            Self::ReservedPrefix { prefix } => write!(f, "prefix {prefix} is reserved"),
+            Self::ReservedNamespace { namespace } => write!(f, "namespace {namespace} is reserved"),
+            Self::PrefixConflict {
+                prefix,
+                namespace,
+                existing_namespace,
+            } => write!(
+                f,
+                "prefix {prefix} is declared for both {existing_namespace} and {namespace}"
+            ),
+            Self::DuplicateAttribute { name } => write!(f, "duplicate attribute {name}"),
+            Self::Unexpected(value) => write!(f, "unexpected {value}"),
+            Self::Custom(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+impl std::error::Error for ValidationIssueKind {}
+
+/// Ends the validation with an issue that would also stop a serializer.
+struct Abort(ValidationIssueKind);
+
+impl Error for Abort {
+    fn unexpected_serialize(unexpected: Unexpected) -> Self {
+        Abort(ValidationIssueKind::Unexpected(unexpected))
+    }
+
+    fn custom<T>(msg: T) -> Self
+    where
+        T: Display,
+    {
+        Abort(ValidationIssueKind::Custom(msg.to_string()))
+    }
+}
+
+#[derive(Default)]
+struct State {
+    /// The location being validated, outermost first.
+    path: Vec<XmlValuePathSegment>,
+    issues: Vec<(Vec<XmlValuePathSegment>, ValidationIssueKind)>,
+    siblings: Vec<Siblings>,
+}
+
+/// The elements written as children of the same parent, used to give positions to elements with siblings of the same name.
+struct Siblings {
+    depth: usize,
+    first_issue: usize,
+    names: BTreeMap<ExpandedNameBuf, usize>,
+}
+
+impl State {
+    fn report(&mut self, kind: ValidationIssueKind) {
+        self.issues.push((self.path.clone(), kind));
+    }
+
+    fn enter(&mut self, name: ExpandedNameBuf) {
+        let siblings = self
+            .siblings
+            .last_mut()
+            .expect("elements are always written as children");
+        let position = siblings.names.entry(name.clone()).or_default();
+        *position += 1;
+
+        self.path.push(XmlValuePathSegment::Element {
+            name,
+            position: Some(*position),
+        });
+    }
+
+    fn push_children(&mut self) {
+        self.siblings.push(Siblings {
+            depth: self.path.len(),
+            first_issue: self.issues.len(),
+            names: BTreeMap::new(),
+        });
+    }
+
+    fn pop_children(&mut self) {
+        let Some(siblings) = self.siblings.pop() else {
+            return;
+        };
+
+        for (path, _) in &mut self.issues[siblings.first_issue..] {
+            if let Some(XmlValuePathSegment::Element { name, position }) =
+                path.get_mut(siblings.depth)
+            {
+                if siblings.names.get(name) == Some(&1) {
+                    *position = None;
+                }
+            }
+        }
+    }
+
+    fn check_chars(&mut self, text: &str) {
+        if let Some(character) = text.chars().find(|c| !is_xml_char(*c)) {
+            self.report(ValidationIssueKind::InvalidChar { character });
+        }
+    }
+
+    fn check_bytes(&mut self, bytes: &[u8]) -> Option<String> {
+        match std::str::from_utf8(bytes) {
+            Ok(text) => {
+                self.check_chars(text);
+                Some(text.to_owned())
+            }
+            Err(_) => {
+                self.report(ValidationIssueKind::InvalidUtf8);
+                None
+            }
+        }
+    }
+
+    fn check_namespace(&mut self, namespace: Option<&XmlNamespace>) {
+        if let Some(namespace) = namespace.filter(|namespace| *namespace == XmlNamespace::XMLNS) {
+            self.report(ValidationIssueKind::ReservedNamespace {
+                namespace: namespace.to_owned(),
+            });
+        }
+    }
+
+    fn check_prefix(&mut self, prefix: &Prefix, namespace: Option<&XmlNamespace>) {
+        let reserved = match prefix.as_str() {
+            "xmlns" => true,
+            "xml" => namespace != Some(XmlNamespace::XML),
+            _ => false,
+        };
+        if reserved {
+            self.report(ValidationIssueKind::ReservedPrefix {
+                prefix: prefix.to_owned(),
+            });
+        }
+    }
+}
+
+fn is_valid_version(version: &str) -> bool {
+    version
+        .strip_prefix("1.")
+        .is_some_and(|minor| !minor.is_empty() && minor.bytes().all(|b| b.is_ascii_digit()))
+}
+
+fn is_valid_encoding(encoding: &str) -> bool {
+    let mut bytes = encoding.bytes();
+    bytes.next().is_some_and(|b| b.is_ascii_alphabetic())
+        && bytes.all(|b| b.is_ascii_alphanumeric() || matches!(b, b'.' | b'_' | b'-'))
+}
+
+struct ValidateSerializer<'a> {
+    state: &'a mut State,
+}
+
+impl<'a> Serializer for ValidateSerializer<'a> {
+    type Ok = ();
+    type Error = Abort;
+    type SerializeElement = ValidateElement<'a>;
+    type SerializeSeq = ValidateSeq<'a>;
+
+    fn serialize_text<S: AsRef<str>>(self, text: S) -> Result<Self::Ok, Self::Error> {
+        self.state.check_chars(text.as_ref());
+        Ok(())
+    }
+
+    fn serialize_cdata<S: AsRef<str>>(self, text: S) -> Result<Self::Ok, Self::Error> {
+        // A `]]>` in the content is not an issue, since serializers split the section around it.
+        self.state.check_chars(text.as_ref());
+        Ok(())
+    }
+
+    fn serialize_element(
+        self,
+        name: &'_ ExpandedName<'_>,
+    ) -> Result<Self::SerializeElement, Self::Error> {
+        self.state.enter(name.into_owned());
+        self.state.check_namespace(*name.namespace());
+
+        Ok(ValidateElement {
+            state: self.state,
+            namespace: name.namespace().map(ToOwned::to_owned),
+            attributes: Vec::new(),
+            declarations: BTreeMap::new(),
+        })
+    }
+
+    fn serialize_seq(self) -> Result<Self::SerializeSeq, Self::Error> {
+        Ok(ValidateSeq {
+            state: self.state,
+            closes_element: false,
+        })
+    }
+
+    fn serialize_decl<S: AsRef<str>>(
+        self,
+        version: S,
+        encoding: Option<S>,
+        standalone: Option<S>,
+    ) -> Result<Self::Ok, Self::Error> {
+        let parts = [
+            (
+                "version",
+                Some(version),
+                is_valid_version as fn(&str) -> bool,
+            ),
+            ("encoding", encoding, is_valid_encoding),
+            ("standalone", standalone, |value| {
+                matches!(value, "yes" | "no")
+            }),
+        ];
+
+        for (part, value, is_valid) in parts {
+            if let Some(value) = value.filter(|value| !is_valid(value.as_ref())) {
+                self.state.report(ValidationIssueKind::InvalidDeclaration {
+                    part,
+                    value: value.as_ref().to_owned(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn serialize_pi<S: AsRef<[u8]>>(self, target: S, content: S) -> Result<Self::Ok, Self::Error> {
+        let target = String::from_utf8_lossy(target.as_ref());
+        if crate::name_tokens::is_valid_name(&target).is_err() || target.eq_ignore_ascii_case("xml")
+        {
+            self.state.report(ValidationIssueKind::InvalidPiTarget {
+                target: target.into_owned(),
+            });
+        }
+
+        if let Some(content) = self.state.check_bytes(content.as_ref()) {
+            if content.contains("?>") {
+                self.state.report(ValidationIssueKind::InvalidContent(
+                    InvalidContentError::PiTerminator,
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn serialize_comment<S: AsRef<[u8]>>(self, text: S) -> Result<Self::Ok, Self::Error> {
+        self.state.check_bytes(text.as_ref());
+        if let Err(err) = XmlComment::new(text.as_ref()).validate() {
+            self.state.report(ValidationIssueKind::InvalidContent(err));
+        }
+        Ok(())
+    }
+
+    fn serialize_doctype<S: AsRef<[u8]>>(self, text: S) -> Result<Self::Ok, Self::Error> {
+        self.state.check_bytes(text.as_ref());
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        Ok(())
+    }
+}
+
+struct ValidateSeq<'a> {
+    state: &'a mut State,
+    /// Whether this is the sequence of children of an element, which ends the element when it ends.
+    closes_element: bool,
+}
+
+impl SerializeSeq for ValidateSeq<'_> {
+    type Ok = ();
+    type Error = Abort;
+
+    fn serialize_element<V: Serialize>(&mut self, v: &V) -> Result<(), Self::Error> {
+        v.serialize(ValidateSerializer {
+            state: &mut *self.state,
+        })
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        if self.closes_element {
+            self.state.pop_children();
+            self.state.path.pop();
+        }
+        Ok(())
+    }
+}
+
+struct ValidateElement<'a> {
+    state: &'a mut State,
+    namespace: Option<XmlNamespaceBuf>,
+    attributes: Vec<ExpandedNameBuf>,
+    declarations: BTreeMap<PrefixBuf, XmlNamespaceBuf>,
+}
+
+impl<'a> ValidateElement<'a> {
+    fn children(self) -> ValidateSeq<'a> {
+        self.state.push_children();
+        ValidateSeq {
+            state: self.state,
+            closes_element: true,
+        }
+    }
+
+    fn close(self) {
+        self.state.path.pop();
+    }
+}
+
+impl<'a> SerializeElement for ValidateElement<'a> {
+    type Ok = ();
+    type Error = Abort;
+    type ChildrenSerializeSeq = ValidateSeq<'a>;
+    type SerializeElementAttributes = ValidateElement<'a>;
+
+    fn include_prefix(&mut self, should_enforce: IncludePrefix) -> Result<Self::Ok, Self::Error> {
+        let _ = should_enforce;
+        Ok(())
+    }
+
+    fn preferred_prefix(
+        &mut self,
+        preferred_prefix: Option<&Prefix>,
+    ) -> Result<Self::Ok, Self::Error> {
+        if let Some(prefix) = preferred_prefix {
+            self.state.check_prefix(prefix, self.namespace.as_deref());
+        }
+        Ok(())
+    }
+
+    fn declare_namespace(
+        &mut self,
+        prefix: &Prefix,
+        namespace: &XmlNamespace,
+    ) -> Result<(), Self::Error> {
+        self.state.check_prefix(prefix, Some(namespace));
+        if namespace == XmlNamespace::XMLNS
+            || (namespace == XmlNamespace::XML && prefix.as_str() != "xml")
+        {
+            self.state.report(ValidationIssueKind::ReservedNamespace {
+                namespace: namespace.to_owned(),
+            });
+        }
+
+        match self.declarations.get(prefix) {
+            Some(existing_namespace) if **existing_namespace != *namespace => {
+                let existing_namespace = existing_namespace.clone();
+                self.state.report(ValidationIssueKind::PrefixConflict {
+                    prefix: prefix.to_owned(),
+                    namespace: namespace.to_owned(),
+                    existing_namespace,
+                });
+            }
+            Some(_) => {}
+            None => {
+                self.declarations
+                    .insert(prefix.to_owned(), namespace.to_owned());
+            }
+        }
+        Ok(())
+    }
+
+    fn serialize_attributes(self) -> Result<Self::SerializeElementAttributes, Self::Error> {
+        Ok(self)
+    }
+
+    fn serialize_children(self) -> Result<Self::ChildrenSerializeSeq, Self::Error> {
+        Ok(self.children())
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.close();
+        Ok(())
+    }
+}
+
+impl SerializeAttributes for ValidateElement<'_> {
+    type Ok = ();
+    type Error = Abort;
+
+    fn serialize_attribute<A: SerializeAttribute>(
+        &mut self,
+        a: &A,
+    ) -> Result<Self::Ok, Self::Error> {
+        a.serialize_attribute(self)
+    }
+}
+
+impl<'a> SerializeElementAttributes for ValidateElement<'a> {
+    type ChildrenSerializeSeq = ValidateSeq<'a>;
+
+    fn serialize_children(self) -> Result<Self::ChildrenSerializeSeq, Self::Error> {
+        Ok(self.children())
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.close();
+        Ok(())
+    }
+}
+
+impl AttributeSerializer for &mut ValidateElement<'_> {
+    type Ok = ();
+    type Error = Abort;
+    type SerializeAttribute<'b>
+        = ValidateAttribute<'b>
+    where
+        Self: 'b;
+
+    fn serialize_attribute(
+        &mut self,
+        name: &'_ ExpandedName<'_>,
+    ) -> Result<Self::SerializeAttribute<'_>, Self::Error> {
+        let name = name.into_owned();
+        self.state
+            .path
+            .push(XmlValuePathSegment::Attribute { name: name.clone() });
+        self.state.check_namespace(name.namespace());
+
+        if self.attributes.contains(&name) {
+            self.state
+                .report(ValidationIssueKind::DuplicateAttribute { name: name.clone() });
+        }
+        let namespace = name.namespace().map(ToOwned::to_owned);
+        self.attributes.push(name);
+
+        Ok(ValidateAttribute {
+            state: &mut *self.state,
+            namespace,
+        })
+    }
+
+    fn serialize_none(&mut self) -> Result<Self::Ok, Self::Error> {
+        Ok(())
+    }
+}
+
+struct ValidateAttribute<'a> {
+    state: &'a mut State,
+    namespace: Option<XmlNamespaceBuf>,
+}
+
+impl SerializeAttributeAccess for ValidateAttribute<'_> {
+    type Ok = ();
+    type Error = Abort;
+
+    fn include_prefix(&mut self, should_include: IncludePrefix) -> Result<Self::Ok, Self::Error> {
+        let _ = should_include;
+        Ok(())
+    }
+
+    fn preferred_prefix(
+        &mut self,
+        preferred_prefix: Option<&Prefix>,
+    ) -> Result<Self::Ok, Self::Error> {
+        if let Some(prefix) = preferred_prefix {
+            self.state.check_prefix(prefix, self.namespace.as_deref());
+        }
+        Ok(())
+    }
+
+    fn end<S: Serialize>(self, value: &S) -> Result<Self::Ok, Self::Error> {
+        value.serialize(ValidateAttributeValue {
+            state: &mut *self.state,
+        })?;
+        self.state.path.pop();
+        Ok(())
+    }
+}
+
+/// Validates the value of an attribute, which can only be text.
+struct ValidateAttributeValue<'a> {
+    state: &'a mut State,
+}
+
+impl Serializer for ValidateAttributeValue<'_> {
+    type Ok = ();
+    type Error = Abort;
+    type SerializeElement = NoopDeSerializer<(), Abort>;
+    type SerializeSeq = NoopDeSerializer<(), Abort>;
+
+    fn serialize_text<S: AsRef<str>>(self, text: S) -> Result<Self::Ok, Self::Error> {
+        self.state.check_chars(text.as_ref());
+        Ok(())
+    }
+
+    fn serialize_cdata<S: AsRef<str>>(self, text: S) -> Result<Self::Ok, Self::Error> {
+        let _ = text;
+
+        Err(Error::unexpected_serialize(Unexpected::CData))
+    }
+
+    fn serialize_element(
+        self,
+        name: &'_ ExpandedName<'_>,
+    ) -> Result<Self::SerializeElement, Self::Error> {
+        Err(Error::unexpected_serialize(Unexpected::element(name)))
+    }
+
+    fn serialize_seq(self) -> Result<Self::SerializeSeq, Self::Error> {
+        Err(Error::unexpected_serialize(Unexpected::Seq))
+    }
+
+    fn serialize_decl<S: AsRef<str>>(
+        self,
+        version: S,
+        encoding: Option<S>,
+        standalone: Option<S>,
+    ) -> Result<Self::Ok, Self::Error> {
+        let _ = (version, encoding, standalone);
+
+        Err(Error::unexpected_serialize(Unexpected::Decl))
+    }
+
+    fn serialize_pi<S: AsRef<[u8]>>(self, target: S, content: S) -> Result<Self::Ok, Self::Error> {
+        let _ = content;
+
+        Err(Error::unexpected_serialize(Unexpected::pi(target)))
+    }
+
+    fn serialize_comment<S: AsRef<[u8]>>(self, text: S) -> Result<Self::Ok, Self::Error> {
+        let _ = text;
+
+        Err(Error::unexpected_serialize(Unexpected::Comment))
+    }
+
+    fn serialize_doctype<S: AsRef<[u8]>>(self, text: S) -> Result<Self::Ok, Self::Error> {
+        let _ = text;
+
+        Err(Error::unexpected_serialize(Unexpected::DocType))
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        Ok(())
+    }
+}
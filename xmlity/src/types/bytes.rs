@@ -0,0 +1,299 @@
+//! This module contains wrappers for byte buffers, which have no single natural representation in XML.
+//!
+//! A bare [`Vec<u8>`] is a sequence like any other [`Vec`], which writes each byte as its own text node and can't be read back reliably, so the derive macros reject fields of that type. Instead, choose one of:
+//! - [`Bytes`], written as base64 text like `xs:base64Binary`.
+//! - [`ByteSeq`], written as a list of numbers separated by spaces like `xs:list` of `xs:unsignedByte`.
+//!
+//! ```
+//! use xmlity::{types::bytes::{ByteSeq, Bytes}, Deserialize, Serialize};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! #[xelement(name = "file")]
+//! struct File {
+//!     #[xattribute(name = "checksum")]
+//!     checksum: ByteSeq,
+//!     #[xelement(name = "data")]
+//!     data: Bytes,
+//! }
+//! ```
+use core::fmt;
+use std::{
+    ops::{Deref, DerefMut},
+    str::FromStr,
+};
+
+use crate::{
+    de::{self, Deserialize, Deserializer, Visitor},
+    ser::{Serialize, Serializer},
+    types::string::FromStrVisitor,
+};
+
+/// An error that can occur when parsing [`Bytes`] or [`ByteSeq`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseBytesError {
+    /// The text contains a character that is not part of the base64 alphabet.
+    InvalidBase64Character(char),
+    /// The base64 text does not have a valid length or padding.
+    InvalidBase64Length,
+    /// An item of a list is not a number between 0 and 255.
+    InvalidByte(String),
+}
+
+impl fmt::Display for ParseBytesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidBase64Character(value) => write!(f, "Invalid base64 character {value:?}"),
+            Self::InvalidBase64Length => f.write_str("Invalid base64 length or padding"),
+            Self::InvalidByte(value) => write!(f, "Invalid byte {value:?}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseBytesError {}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64_value(c: char) -> Result<u32, ParseBytesError> {
+    let value = match c {
+        'A'..='Z' => c as u32 - 'A' as u32,
+        'a'..='z' => c as u32 - 'a' as u32 + 26,
+        '0'..='9' => c as u32 - '0' as u32 + 52,
+        '+' => 62,
+        '/' => 63,
+        _ => return Err(ParseBytesError::InvalidBase64Character(c)),
+    };
+    Ok(value)
+}
+
+/// A byte buffer written as base64 text, like `xs:base64Binary`.
+///
+/// Whitespace in the text is ignored when reading, so line-wrapped base64 can be read as well.
+///
+/// ```
+/// use xmlity::types::bytes::Bytes;
+///
+/// let bytes: Bytes = "aGVs\n bG8=".parse().unwrap();
+///
+/// assert_eq!(bytes.0, b"hello");
+/// assert_eq!(bytes.to_string(), "aGVsbG8=");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Bytes(pub Vec<u8>);
+
+impl FromStr for Bytes {
+    type Err = ParseBytesError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let chars = s
+            .chars()
+            .filter(|c| !c.is_ascii_whitespace())
+            .collect::<Vec<_>>();
+        if chars.len() % 4 != 0 {
+            return Err(ParseBytesError::InvalidBase64Length);
+        }
+
+        let padding = chars.iter().rev().take_while(|&&c| c == '=').count();
+        if padding > 2 {
+            return Err(ParseBytesError::InvalidBase64Length);
+        }
+
+        let mut bytes = Vec::with_capacity(chars.len() / 4 * 3);
+        for (i, quad) in chars.chunks(4).enumerate() {
+            let is_last = (i + 1) * 4 == chars.len();
+            let quad_padding = if is_last { padding } else { 0 };
+
+            let mut value = 0u32;
+            for &c in &quad[..4 - quad_padding] {
+                value = value << 6 | base64_value(c)?;
+            }
+            value <<= 6 * quad_padding;
+
+            let [_, a, b, c] = value.to_be_bytes();
+            bytes.extend_from_slice(&[a, b, c][..3 - quad_padding]);
+        }
+
+        Ok(Bytes(bytes))
+    }
+}
+
+impl fmt::Display for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for chunk in self.0.chunks(3) {
+            let mut buffer = [0u8; 3];
+            buffer[..chunk.len()].copy_from_slice(chunk);
+            let value = u32::from_be_bytes([0, buffer[0], buffer[1], buffer[2]]);
+
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    let index = (value >> (18 - 6 * i)) & 0x3F;
+                    write!(f, "{}", BASE64_ALPHABET[index as usize] as char)?;
+                } else {
+                    f.write_str("=")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A byte buffer written as a list of numbers separated by spaces, like `1 2 255`.
+///
+/// ```
+/// use xmlity::types::bytes::ByteSeq;
+///
+/// let bytes: ByteSeq = " 1  2\n255 ".parse().unwrap();
+///
+/// assert_eq!(bytes.0, [1, 2, 255]);
+/// assert_eq!(bytes.to_string(), "1 2 255");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct ByteSeq(pub Vec<u8>);
+
+impl FromStr for ByteSeq {
+    type Err = ParseBytesError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split_ascii_whitespace()
+            .map(|item| {
+                item.parse()
+                    .map_err(|_| ParseBytesError::InvalidByte(item.to_string()))
+            })
+            .collect::<Result<_, _>>()
+            .map(ByteSeq)
+    }
+}
+
+impl fmt::Display for ByteSeq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, byte) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{byte}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses a byte buffer from text or CDATA, or an empty buffer from an empty element.
+struct ByteBufferVisitor<T>(FromStrVisitor<T>);
+
+impl<T> Default for ByteBufferVisitor<T> {
+    fn default() -> Self {
+        Self(FromStrVisitor::default())
+    }
+}
+
+impl<'de, T: Deserialize<'de> + FromStr + Default> Visitor<'de> for ByteBufferVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.0.expecting(formatter)
+    }
+
+    fn visit_text<E, V>(self, value: V) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+        V: de::XmlText<'de>,
+    {
+        self.0.visit_text(value)
+    }
+
+    fn visit_cdata<E, V>(self, value: V) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+        V: de::XmlCData<'de>,
+    {
+        self.0.visit_cdata(value)
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(T::default())
+    }
+}
+
+macro_rules! impl_byte_buffer {
+    ($($t:ident),*) => {
+        $(
+            impl Serialize for $t {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serializer.serialize_text(self.to_string())
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $t {
+                fn deserialize<D: Deserializer<'de>>(reader: D) -> Result<Self, D::Error> {
+                    reader.deserialize_any(ByteBufferVisitor::default())
+                }
+            }
+
+            impl From<Vec<u8>> for $t {
+                fn from(bytes: Vec<u8>) -> Self {
+                    $t(bytes)
+                }
+            }
+
+            impl From<$t> for Vec<u8> {
+                fn from(bytes: $t) -> Self {
+                    bytes.0
+                }
+            }
+
+            impl Deref for $t {
+                type Target = Vec<u8>;
+
+                fn deref(&self) -> &Vec<u8> {
+                    &self.0
+                }
+            }
+
+            impl DerefMut for $t {
+                fn deref_mut(&mut self) -> &mut Vec<u8> {
+                    &mut self.0
+                }
+            }
+        )*
+    };
+}
+
+impl_byte_buffer!(Bytes, ByteSeq);
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::empty(b"", "")]
+    #[case::two_padding(b"h", "aA==")]
+    #[case::one_padding(b"he", "aGU=")]
+    #[case::no_padding(b"hel", "aGVs")]
+    #[case::all_bits(&[0xFB, 0xFF, 0xBF], "+/+/")]
+    fn base64(#[case] bytes: &[u8], #[case] text: &str) {
+        assert_eq!(Bytes(bytes.to_vec()).to_string(), text);
+        assert_eq!(text.parse::<Bytes>().unwrap().0, bytes);
+    }
+
+    #[rstest]
+    #[case::length("aGV", ParseBytesError::InvalidBase64Length)]
+    #[case::padding_in_middle("aA==aGVs", ParseBytesError::InvalidBase64Character('='))]
+    #[case::too_much_padding("a===", ParseBytesError::InvalidBase64Length)]
+    #[case::character("aG-s", ParseBytesError::InvalidBase64Character('-'))]
+    fn base64_errors(#[case] text: &str, #[case] expected: ParseBytesError) {
+        assert_eq!(text.parse::<Bytes>().unwrap_err(), expected);
+    }
+
+    #[test]
+    fn byte_seq_out_of_range() {
+        assert_eq!(
+            "1 256".parse::<ByteSeq>().unwrap_err(),
+            ParseBytesError::InvalidByte("256".to_string())
+        );
+    }
+}
@@ -35,6 +35,25 @@ impl<'de> DeserializationGroupBuilder<'de> for () {
 
 impl SerializationGroup for () {}
 
+/// [`PhantomData`](std::marker::PhantomData) is an empty group, which makes it possible to use type parameters that are not part of the document in derived types with `#[xgroup]`.
+impl<'de, T: ?Sized> DeserializationGroup<'de> for std::marker::PhantomData<T> {
+    type Builder = Self;
+
+    fn builder() -> Self::Builder {
+        std::marker::PhantomData
+    }
+}
+
+impl<'de, T: ?Sized> DeserializationGroupBuilder<'de> for std::marker::PhantomData<T> {
+    type Value = Self;
+
+    fn finish<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(self)
+    }
+}
+
+impl<T: ?Sized> SerializationGroup for std::marker::PhantomData<T> {}
+
 struct OptionVisitor<T>(std::marker::PhantomData<T>);
 
 impl<T> OptionVisitor<T> {
@@ -170,15 +189,15 @@ where
     where
         Self: 'g;
 
-    fn next_element<T>(&mut self) -> Result<Option<T>, Self::Error>
+    fn next_element_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
     where
-        T: Deserialize<'de>,
+        S: de::DeserializeSeed<'de>,
     {
         let Some(text) = self.take() else {
             return Ok(None);
         };
 
-        match T::deserialize(text) {
+        match seed.deserialize(text) {
             Ok(value) => Ok(Some(value)),
             Err(_) => {
                 *self = Some(text);
@@ -187,15 +206,15 @@ where
         }
     }
 
-    fn next_element_seq<T>(&mut self) -> Result<Option<T>, Self::Error>
+    fn next_element_seq_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
     where
-        T: Deserialize<'de>,
+        S: de::DeserializeSeed<'de>,
     {
         let Some(text) = self.take() else {
             return Ok(None);
         };
 
-        match T::deserialize_seq(text) {
+        match seed.deserialize_seq(text) {
             Ok(value) => Ok(Some(value)),
             Err(_) => {
                 *self = Some(text);
@@ -207,9 +226,17 @@ where
     fn sub_access(&mut self) -> Result<Self::SubAccess<'_>, Self::Error> {
         Ok(*self)
     }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(usize::from(self.is_some()))
+    }
+
+    fn peek_element(&mut self) -> Result<Option<de::PeekedNode>, Self::Error> {
+        Ok(self.map(|_| de::PeekedNode::Text))
+    }
 }
 
-impl<T: Serialize> Serialize for Box<T> {
+impl<T: Serialize + ?Sized> Serialize for Box<T> {
     fn serialize<S: crate::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         (**self).serialize(serializer)
     }
@@ -273,6 +300,10 @@ impl<'de, T: DeserializationGroupBuilder<'de>> DeserializationGroupBuilder<'de>
         (**self).elements_done()
     }
 
+    fn completed_fields(&self) -> Vec<&'static str> {
+        (**self).completed_fields()
+    }
+
     fn finish<E: de::Error>(self) -> Result<Self::Value, E> {
         (*self).finish().map(Box::new)
     }
@@ -130,6 +130,29 @@ impl<T: Serialize> Serialize for &[T] {
     }
 }
 
+/// A wrapper that serializes the items of an iterator as a sequence, without collecting them first.
+///
+/// Since [`Serialize::serialize`] only takes a reference, the iterator is cloned every time the value is serialized, meaning it should be cheap to clone (such as an iterator adapter over a borrowed collection or a range).
+///
+/// ```
+/// use xmlity::types::iterator::SerializeIter;
+///
+/// let rows = SerializeIter((0..3).map(|i| i * 2));
+/// # let _ = rows;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SerializeIter<I>(pub I);
+
+impl<I> Serialize for SerializeIter<I>
+where
+    I: IntoIterator + Clone,
+    I::Item: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_seq(self.0.clone(), serializer)
+    }
+}
+
 // Array
 impl<'de, const N: usize, T: Deserialize<'de>> Deserialize<'de> for [T; N] {
     fn deserialize<D: Deserializer<'de>>(reader: D) -> Result<Self, D::Error> {
@@ -0,0 +1,265 @@
+//! This module contains types for collecting repeated elements into maps keyed by one of their attributes.
+//!
+//! These are used by `#[xvalue(key_attribute = "...")]`, but can also be used directly by implementing [`KeyAttribute`] for a marker type.
+use core::{fmt, marker::PhantomData};
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::{BuildHasher, Hash},
+};
+
+use crate::{
+    de::{self, DeserializeSeed, SeqAccess, Visitor},
+    ser::SerializeSeq,
+    types::utils::ValueOrWhitespace,
+    value::XmlElement,
+    Deserialize, DeserializeOwned, Deserializer, ExpandedName, Serialize, Serializer,
+};
+
+/// What to do when several elements have the same key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum DuplicateKeys {
+    /// Fail deserialization at the element with the duplicate key.
+    #[default]
+    Error,
+    /// Keep the first element with the key.
+    First,
+    /// Keep the last element with the key.
+    Last,
+}
+
+/// The attribute elements are keyed by, and how duplicate keys are handled.
+pub trait KeyAttribute {
+    /// The name of the attribute.
+    fn name() -> ExpandedName<'static>;
+
+    /// What to do when several elements have the same key.
+    const DUPLICATES: DuplicateKeys = DuplicateKeys::Error;
+}
+
+/// A map that elements can be collected into by key.
+///
+/// This is implemented for [`HashMap`] and [`BTreeMap`], and can be implemented for other maps to use them with [`KeyedBy`].
+pub trait KeyedMap: Default {
+    /// The type of the keys, deserialized from the key attribute.
+    type Key;
+    /// The type of the values, deserialized from the elements.
+    type Value;
+
+    /// Returns `true` if the map contains a value for the key.
+    fn contains_keyed(&self, key: &Self::Key) -> bool;
+
+    /// Inserts a value for the key, replacing any previous value.
+    fn insert_keyed(&mut self, key: Self::Key, value: Self::Value);
+
+    /// Returns the values of the map in the order they should be serialized.
+    fn keyed_values(&self) -> impl Iterator<Item = &Self::Value>;
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher + Default> KeyedMap for HashMap<K, V, S> {
+    type Key = K;
+    type Value = V;
+
+    fn contains_keyed(&self, key: &K) -> bool {
+        self.contains_key(key)
+    }
+
+    fn insert_keyed(&mut self, key: K, value: V) {
+        self.insert(key, value);
+    }
+
+    fn keyed_values(&self) -> impl Iterator<Item = &V> {
+        self.values()
+    }
+}
+
+impl<K: Ord, V> KeyedMap for BTreeMap<K, V> {
+    type Key = K;
+    type Value = V;
+
+    fn contains_keyed(&self, key: &K) -> bool {
+        self.contains_key(key)
+    }
+
+    fn insert_keyed(&mut self, key: K, value: V) {
+        self.insert(key, value);
+    }
+
+    fn keyed_values(&self) -> impl Iterator<Item = &V> {
+        self.values()
+    }
+}
+
+/// A map of elements keyed by the attribute given by `A`.
+///
+/// Each element is deserialized as a value of the map, and inserted with its key attribute deserialized as the key. Like sequences, the map ends at the first element that can't be deserialized. With [`DuplicateKeys::Error`], an element with a key that is already in the map fails deserialization.
+///
+/// Elements are serialized in the order of [`KeyedMap::keyed_values`], which for a [`HashMap`] is arbitrary.
+///
+/// ```
+/// use std::collections::BTreeMap;
+/// use xmlity::{
+///     types::keyed::{KeyAttribute, KeyedBy},
+///     Deserialize, ExpandedName, LocalName,
+/// };
+///
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// #[xelement(name = "user")]
+/// struct User {
+///     #[xattribute(name = "id")]
+///     id: String,
+///     #[xattribute(name = "name")]
+///     name: String,
+/// }
+///
+/// struct ById;
+///
+/// impl KeyAttribute for ById {
+///     fn name() -> ExpandedName<'static> {
+///         ExpandedName::new(LocalName::new("id").unwrap(), None)
+///     }
+/// }
+///
+/// let value: xmlity::XmlValue = r#"<user id="a" name="Alice"/>"#.parse().unwrap();
+/// let KeyedBy(users, _) = xmlity::value::from_value::<KeyedBy<BTreeMap<String, User>, ById>>(&value).unwrap();
+///
+/// assert_eq!(users["a"].name, "Alice");
+/// ```
+pub struct KeyedBy<M, A>(pub M, pub PhantomData<A>);
+
+impl<M, A> KeyedBy<M, A> {
+    /// Creates a new [`KeyedBy`] from a map.
+    pub fn new(map: M) -> Self {
+        Self(map, PhantomData)
+    }
+}
+
+impl<M: fmt::Debug, A> fmt::Debug for KeyedBy<M, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("KeyedBy").field(&self.0).finish()
+    }
+}
+
+/// Deserializes an element as an entry of a map. Whitespace is skipped by returning [`None`].
+struct KeyedEntrySeed<M, A>(PhantomData<(M, A)>);
+
+impl<'de, M, A> DeserializeSeed<'de> for KeyedEntrySeed<M, A>
+where
+    M: KeyedMap,
+    M::Key: DeserializeOwned,
+    M::Value: DeserializeOwned,
+    A: KeyAttribute,
+{
+    type Value = Option<(M::Key, M::Value)>;
+
+    fn deserialize<D: Deserializer<'de>>(self, reader: D) -> Result<Self::Value, D::Error> {
+        let ValueOrWhitespace::Value(element) =
+            ValueOrWhitespace::<XmlElement>::deserialize(reader)?
+        else {
+            return Ok(None);
+        };
+
+        let attribute = element
+            .attributes
+            .iter()
+            .find(|attribute| attribute.name == A::name())
+            .ok_or_else(|| de::Error::custom(format!("Missing key attribute {}", A::name())))?;
+
+        let key = M::Key::deserialize(&attribute.value).map_err(de::Error::custom)?;
+        let value = M::Value::deserialize(&element).map_err(de::Error::custom)?;
+
+        Ok(Some((key, value)))
+    }
+}
+
+impl<'de, M, A> Deserialize<'de> for KeyedBy<M, A>
+where
+    M: KeyedMap,
+    M::Key: DeserializeOwned,
+    M::Value: DeserializeOwned,
+    A: KeyAttribute,
+{
+    fn deserialize<D: Deserializer<'de>>(reader: D) -> Result<Self, D::Error> {
+        struct KeyedByVisitor<M, A>(PhantomData<(M, A)>);
+
+        impl<'de, M, A> Visitor<'de> for KeyedByVisitor<M, A>
+        where
+            M: KeyedMap,
+            M::Key: DeserializeOwned,
+            M::Value: DeserializeOwned,
+            A: KeyAttribute,
+        {
+            type Value = KeyedBy<M, A>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                write!(formatter, "a sequence of elements keyed by {}", A::name())
+            }
+
+            fn visit_seq<S>(self, mut seq: S) -> Result<Self::Value, S::Error>
+            where
+                S: SeqAccess<'de>,
+            {
+                let mut map = M::default();
+
+                while let Ok(Some(entry)) =
+                    seq.next_element_seq_seed(KeyedEntrySeed::<M, A>(PhantomData))
+                {
+                    let Some((key, value)) = entry else {
+                        continue;
+                    };
+
+                    if map.contains_keyed(&key) {
+                        match A::DUPLICATES {
+                            DuplicateKeys::Error => {
+                                return Err(de::Error::custom(format!(
+                                    "Duplicate value of key attribute {}",
+                                    A::name()
+                                )))
+                            }
+                            DuplicateKeys::First => continue,
+                            DuplicateKeys::Last => {}
+                        }
+                    }
+
+                    map.insert_keyed(key, value);
+                }
+
+                Ok(KeyedBy::new(map))
+            }
+
+            fn visit_none<E>(self) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(KeyedBy::new(M::default()))
+            }
+        }
+
+        reader.deserialize_seq(KeyedByVisitor(PhantomData))
+    }
+}
+
+impl<M: KeyedMap, A> Serialize for KeyedBy<M, A>
+where
+    M::Value: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        KeyedValues(&self.0).serialize(serializer)
+    }
+}
+
+/// Serializes the values of a [`KeyedMap`] as a sequence, leaving out the keys.
+pub struct KeyedValues<'a, M>(pub &'a M);
+
+impl<M: KeyedMap> Serialize for KeyedValues<'_, M>
+where
+    M::Value: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq()?;
+        for value in self.0.keyed_values() {
+            seq.serialize_element(value)?;
+        }
+        seq.end()
+    }
+}
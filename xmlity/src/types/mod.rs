@@ -2,11 +2,17 @@
 //!
 //! It also contains some visitors for the types which can be reused, including [`iterator::IteratorVisitor`].
 
+pub mod bytes;
 pub mod common;
 mod infallible;
 pub mod iterator;
+pub mod keyed;
+pub mod path;
+pub mod pi;
 mod primitive;
+pub mod radix;
 mod smart;
 pub mod string;
 mod tuples;
 pub mod utils;
+pub mod xsd;
@@ -0,0 +1,142 @@
+//! This module contains [`Serialize`]/[`Deserialize`] implementations for paths and OS strings, and wrappers for choosing how data that is not valid UTF-8 is written.
+//!
+//! Paths and OS strings can contain data that is not valid UTF-8 on some platforms, which can't be written to an XML document. By default, serializing such data fails. Wrapping the value in [`Lossy`] instead replaces the invalid data with `U+FFFD REPLACEMENT CHARACTER`, like [`Path::to_string_lossy`].
+//!
+//! Everything read from a document is valid UTF-8, so deserializing can't fail because of it.
+//!
+//! ```
+//! use std::path::PathBuf;
+//! use xmlity::{types::path::LossyPath, Deserialize, Serialize};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! #[xelement(name = "file")]
+//! struct File {
+//!     #[xattribute(name = "path")]
+//!     path: PathBuf,
+//!     #[xattribute(name = "backup", optional)]
+//!     backup: Option<LossyPath>,
+//! }
+//!
+//! let file = File {
+//!     path: PathBuf::from("src/lib.rs"),
+//!     backup: None,
+//! };
+//!
+//! let value = xmlity::value::to_value(&file).unwrap();
+//! assert_eq!(xmlity::value::from_value::<File>(&value).unwrap(), file);
+//! ```
+use std::{
+    ffi::{OsStr, OsString},
+    path::{Path, PathBuf},
+};
+
+use crate::{ser, Deserialize, Deserializer, Serialize, Serializer};
+
+fn serialize_os_str<S: Serializer>(value: &OsStr, serializer: S) -> Result<S::Ok, S::Error> {
+    let text = value.to_str().ok_or_else(|| {
+        ser::Error::custom(format!("{} is not valid UTF-8", value.to_string_lossy()))
+    })?;
+
+    serializer.serialize_text(text)
+}
+
+impl Serialize for OsStr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_os_str(self, serializer)
+    }
+}
+
+impl Serialize for OsString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_os_str(self, serializer)
+    }
+}
+
+impl Serialize for Path {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_os_str(self.as_os_str(), serializer)
+    }
+}
+
+impl Serialize for PathBuf {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_os_str(self.as_os_str(), serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for OsString {
+    fn deserialize<D: Deserializer<'de>>(reader: D) -> Result<Self, D::Error> {
+        String::deserialize(reader).map(OsString::from)
+    }
+}
+
+impl<'de> Deserialize<'de> for PathBuf {
+    fn deserialize<D: Deserializer<'de>>(reader: D) -> Result<Self, D::Error> {
+        String::deserialize(reader).map(PathBuf::from)
+    }
+}
+
+/// A path or OS string that is serialized lossily, replacing data that is not valid UTF-8 with `U+FFFD REPLACEMENT CHARACTER` instead of failing.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Lossy<T>(pub T);
+
+/// A [`PathBuf`] that is serialized lossily.
+pub type LossyPath = Lossy<PathBuf>;
+
+/// An [`OsString`] that is serialized lossily.
+pub type LossyOsString = Lossy<OsString>;
+
+impl<T: AsRef<OsStr>> Serialize for Lossy<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_text(self.0.as_ref().to_string_lossy())
+    }
+}
+
+impl<'de, T: From<String>> Deserialize<'de> for Lossy<T> {
+    fn deserialize<D: Deserializer<'de>>(reader: D) -> Result<Self, D::Error> {
+        String::deserialize(reader).map(|value| Lossy(T::from(value)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::value::{from_value, to_value, XmlText, XmlValue};
+
+    use super::*;
+
+    #[test]
+    fn test_path_roundtrip() {
+        let path = PathBuf::from("dir/file name.txt");
+
+        let value = to_value(&path).expect("serialize should not fail");
+        assert_eq!(value, XmlValue::Text(XmlText::new("dir/file name.txt")));
+
+        let result: PathBuf = from_value(&value).expect("deserialize should not fail");
+        assert_eq!(result, path);
+    }
+
+    #[cfg(unix)]
+    fn invalid_utf8() -> OsString {
+        use std::os::unix::ffi::OsStringExt;
+
+        OsString::from_vec(b"file\xFF.txt".to_vec())
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_serialize_invalid_utf8_path() {
+        let path = PathBuf::from(invalid_utf8());
+
+        let _err = to_value(&path).expect_err("serialize should fail");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_serialize_invalid_utf8_lossy() {
+        let path: LossyPath = Lossy(PathBuf::from(invalid_utf8()));
+
+        let value = to_value(&path).expect("serialize should not fail");
+
+        assert_eq!(value, XmlValue::Text(XmlText::new("file\u{FFFD}.txt")));
+    }
+}
@@ -0,0 +1,540 @@
+//! This module contains types for processing instructions with typed content, including the [`Pi`] wrapper and a parser for pseudo-attributes, the `name="value"` pairs used by processing instructions such as `<?xml-stylesheet href="style.css" type="text/css"?>`.
+//!
+//! The [`XmlStylesheet`] type models the common `xml-stylesheet` processing instruction. Structs with named fields can also be (de)serialized as processing instructions with pseudo-attributes using the `#[xpi(target = "...")]` attribute of the derive macros.
+
+use core::fmt::{self, Display};
+use std::{borrow::Cow, marker::PhantomData, str::FromStr};
+
+use crate::{
+    de::{self, Visitor, XmlProcessingInstruction},
+    escape::{unescape, UnescapeError},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+/// A type that is the content of a processing instruction with a fixed target.
+pub trait PiTarget {
+    /// The target of the processing instruction.
+    const TARGET: &'static str;
+}
+
+/// A wrapper type for processing instructions with a fixed target given by [`PiTarget::TARGET`].
+///
+/// The content of the processing instruction is serialized using [`Display`] and deserialized using [`FromStr`]. Whitespace between the target and the content is not part of the content.
+///
+/// ```
+/// use std::{fmt, str::FromStr};
+/// use xmlity::types::pi::{Pi, PiTarget};
+///
+/// struct Page(u32);
+///
+/// impl PiTarget for Page {
+///     const TARGET: &'static str = "page";
+/// }
+///
+/// impl fmt::Display for Page {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         write!(f, "{}", self.0)
+///     }
+/// }
+///
+/// impl FromStr for Page {
+///     type Err = std::num::ParseIntError;
+///
+///     fn from_str(s: &str) -> Result<Self, Self::Err> {
+///         s.parse().map(Page)
+///     }
+/// }
+///
+/// let page = Pi(Page(4));
+/// # let _ = page;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Pi<T>(pub T);
+
+impl<T: PiTarget + Display> Serialize for Pi<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let content = self.0.to_string();
+        serializer.serialize_pi(T::TARGET.as_bytes(), content.as_bytes())
+    }
+}
+
+/// A visitor for deserializing a [`Pi`] from a processing instruction with the target [`PiTarget::TARGET`], parsing its content using [`FromStr`].
+pub struct FromPiVisitor<T> {
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for FromPiVisitor<T> {
+    fn default() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, T: PiTarget + FromStr> Visitor<'de> for FromPiVisitor<T> {
+    type Value = Pi<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "a processing instruction with target {}",
+            T::TARGET
+        )
+    }
+
+    fn visit_pi<E, V>(self, pi: V) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+        V: XmlProcessingInstruction,
+    {
+        ensure_target(&pi, T::TARGET)?;
+
+        content_str(&pi)?
+            .parse()
+            .map(Pi)
+            .map_err(|_| E::custom("invalid processing instruction content"))
+    }
+}
+
+impl<'de, T: PiTarget + FromStr> Deserialize<'de> for Pi<T> {
+    fn deserialize<D: Deserializer<'de>>(reader: D) -> Result<Self, D::Error> {
+        reader.deserialize_any(FromPiVisitor::default())
+    }
+}
+
+/// Ensures that the processing instruction has the given target. If it does not, returns an error.
+pub fn ensure_target<E: de::Error, V: XmlProcessingInstruction>(
+    pi: &V,
+    target: &str,
+) -> Result<(), E> {
+    if pi.target() == target.as_bytes() {
+        Ok(())
+    } else {
+        Err(E::custom(format!(
+            "expected processing instruction with target {target}, got {}",
+            String::from_utf8_lossy(pi.target())
+        )))
+    }
+}
+
+/// Returns the content of the processing instruction as a string, without the whitespace separating it from the target.
+pub fn content_str<E: de::Error, V: XmlProcessingInstruction>(pi: &V) -> Result<&str, E> {
+    std::str::from_utf8(pi.content())
+        .map(|content| content.trim_start_matches(is_whitespace))
+        .map_err(|_| E::invalid_string())
+}
+
+/// An error that can occur when parsing pseudo-attributes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PseudoAttributeParseError {
+    /// A pseudo-attribute is missing its `=`.
+    MissingEquals(String),
+    /// A pseudo-attribute value is not quoted.
+    MissingQuote(String),
+    /// A pseudo-attribute value is missing its closing quote.
+    UnclosedQuote(String),
+    /// A pseudo-attribute value contains a reference that can't be resolved.
+    InvalidReference(String, UnescapeError),
+}
+
+impl fmt::Display for PseudoAttributeParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingEquals(value) => {
+                write!(f, "Missing `=` after pseudo-attribute name {value}")
+            }
+            Self::MissingQuote(value) => {
+                write!(f, "Pseudo-attribute value of {value} is not quoted")
+            }
+            Self::UnclosedQuote(value) => write!(
+                f,
+                "Pseudo-attribute value of {value} is missing its closing quote"
+            ),
+            Self::InvalidReference(value, error) => write!(
+                f,
+                "Pseudo-attribute value of {value} contains an invalid reference: {error}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PseudoAttributeParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidReference(_, error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+fn is_whitespace(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\r' | '\n')
+}
+
+/// An iterator over the pseudo-attributes in the content of a processing instruction, created by [`pseudo_attributes`].
+#[derive(Debug, Clone)]
+pub struct PseudoAttributes<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Iterator for PseudoAttributes<'a> {
+    type Item = Result<(&'a str, Cow<'a, str>), PseudoAttributeParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = self.rest.trim_start_matches(is_whitespace);
+        if rest.is_empty() {
+            self.rest = rest;
+            return None;
+        }
+        // Errors are not recoverable, so the iterator is fused after the first one.
+        self.rest = "";
+
+        let name_end = rest
+            .find(|c: char| c == '=' || is_whitespace(c))
+            .unwrap_or(rest.len());
+        let (name, rest) = rest.split_at(name_end);
+
+        let Some(rest) = rest.trim_start_matches(is_whitespace).strip_prefix('=') else {
+            return Some(Err(PseudoAttributeParseError::MissingEquals(
+                name.to_owned(),
+            )));
+        };
+        let rest = rest.trim_start_matches(is_whitespace);
+
+        let Some(quote) = rest.chars().next().filter(|c| matches!(c, '"' | '\'')) else {
+            return Some(Err(PseudoAttributeParseError::MissingQuote(
+                name.to_owned(),
+            )));
+        };
+        let rest = &rest[1..];
+
+        let Some(value_end) = rest.find(quote) else {
+            return Some(Err(PseudoAttributeParseError::UnclosedQuote(
+                name.to_owned(),
+            )));
+        };
+
+        self.rest = &rest[value_end + 1..];
+        Some(
+            unescape(&rest[..value_end])
+                .map(|value| (name, value))
+                .map_err(|error| {
+                    PseudoAttributeParseError::InvalidReference(name.to_owned(), error)
+                }),
+        )
+    }
+}
+
+/// Parses the pseudo-attributes in the content of a processing instruction.
+///
+/// Entity and character references in the values are resolved, as described in [Associating Style Sheets with XML documents](https://www.w3.org/TR/xml-stylesheet/#the-xml-stylesheet-processing-instruction).
+///
+/// ```
+/// use xmlity::types::pi::pseudo_attributes;
+///
+/// let attributes = pseudo_attributes(r#"href="style.css" title='Tom &amp; Jerry'"#)
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+///
+/// assert_eq!(attributes, [("href", "style.css".into()), ("title", "Tom & Jerry".into())]);
+/// ```
+pub fn pseudo_attributes(content: &str) -> PseudoAttributes<'_> {
+    PseudoAttributes { rest: content }
+}
+
+/// Appends a pseudo-attribute to the content of a processing instruction, separating it from any previous pseudo-attributes with a space.
+///
+/// The value is quoted using `"`, unless it contains a `"` but no `'`, in which case `'` is used. `&`, `<`, `>` and the quote are escaped as entity references, so any value can be written, including values containing both quotes or a `?>` that would otherwise end the processing instruction.
+///
+/// ```
+/// use xmlity::types::pi::push_pseudo_attribute;
+///
+/// let mut content = String::new();
+/// push_pseudo_attribute(&mut content, "href", "style.css");
+/// push_pseudo_attribute(&mut content, "title", r#"say "hi""#);
+/// push_pseudo_attribute(&mut content, "media", r#"it's "?>""#);
+/// assert_eq!(
+///     content,
+///     r#"href="style.css" title='say "hi"' media="it's &quot;?&gt;&quot;""#
+/// );
+/// ```
+pub fn push_pseudo_attribute(content: &mut String, name: &str, value: &str) {
+    let quote = if value.contains('"') && !value.contains('\'') {
+        '\''
+    } else {
+        '"'
+    };
+
+    if !content.is_empty() {
+        content.push(' ');
+    }
+    content.push_str(name);
+    content.push('=');
+    content.push(quote);
+    for c in value.chars() {
+        match c {
+            '&' => content.push_str("&amp;"),
+            '<' => content.push_str("&lt;"),
+            '>' => content.push_str("&gt;"),
+            '"' if quote == '"' => content.push_str("&quot;"),
+            '\'' if quote == '\'' => content.push_str("&apos;"),
+            c => content.push(c),
+        }
+    }
+    content.push(quote);
+}
+
+/// An error that can occur when parsing the content of an `xml-stylesheet` processing instruction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum XmlStylesheetParseError {
+    /// The pseudo-attributes could not be parsed.
+    PseudoAttribute(PseudoAttributeParseError),
+    /// A required pseudo-attribute is missing.
+    MissingPseudoAttribute(&'static str),
+    /// The `alternate` pseudo-attribute is neither `yes` nor `no`.
+    InvalidAlternate(String),
+}
+
+impl fmt::Display for XmlStylesheetParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PseudoAttribute(error) => fmt::Display::fmt(error, f),
+            Self::MissingPseudoAttribute(value) => write!(f, "Missing pseudo-attribute {value}"),
+            Self::InvalidAlternate(value) => {
+                write!(f, "Invalid value for pseudo-attribute alternate: {value}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for XmlStylesheetParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::PseudoAttribute(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<PseudoAttributeParseError> for XmlStylesheetParseError {
+    fn from(error: PseudoAttributeParseError) -> Self {
+        Self::PseudoAttribute(error)
+    }
+}
+
+/// An `xml-stylesheet` processing instruction, as defined in [Associating Style Sheets with XML documents](https://www.w3.org/TR/xml-stylesheet/).
+///
+/// Unknown pseudo-attributes are ignored when parsing. Values are escaped when formatting and serializing, see [`push_pseudo_attribute`], and references in them are resolved when parsing, see [`pseudo_attributes`].
+///
+/// ```
+/// use xmlity::types::pi::XmlStylesheet;
+///
+/// let stylesheet: XmlStylesheet = r#"href="style.css" type="text/css" media="print""#.parse().unwrap();
+///
+/// assert_eq!(stylesheet, XmlStylesheet {
+///     media: Some("print".to_string()),
+///     ..XmlStylesheet::new("style.css", "text/css")
+/// });
+/// assert_eq!(stylesheet.to_string(), r#"href="style.css" type="text/css" media="print""#);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct XmlStylesheet {
+    /// The URI of the style sheet.
+    pub href: String,
+    /// The MIME type of the style sheet.
+    pub type_: String,
+    /// The title of the style sheet.
+    pub title: Option<String>,
+    /// The media the style sheet applies to.
+    pub media: Option<String>,
+    /// The character encoding of the style sheet.
+    pub charset: Option<String>,
+    /// If the style sheet is an alternative style sheet.
+    pub alternate: Option<bool>,
+}
+
+impl XmlStylesheet {
+    /// Creates a new stylesheet with the given `href` and `type`, and no other pseudo-attributes.
+    pub fn new(href: impl Into<String>, type_: impl Into<String>) -> Self {
+        Self {
+            href: href.into(),
+            type_: type_.into(),
+            title: None,
+            media: None,
+            charset: None,
+            alternate: None,
+        }
+    }
+}
+
+impl PiTarget for XmlStylesheet {
+    const TARGET: &'static str = "xml-stylesheet";
+}
+
+impl Display for XmlStylesheet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut content = String::new();
+        push_pseudo_attribute(&mut content, "href", &self.href);
+        push_pseudo_attribute(&mut content, "type", &self.type_);
+        if let Some(title) = &self.title {
+            push_pseudo_attribute(&mut content, "title", title);
+        }
+        if let Some(media) = &self.media {
+            push_pseudo_attribute(&mut content, "media", media);
+        }
+        if let Some(charset) = &self.charset {
+            push_pseudo_attribute(&mut content, "charset", charset);
+        }
+        if let Some(alternate) = self.alternate {
+            push_pseudo_attribute(
+                &mut content,
+                "alternate",
+                if alternate { "yes" } else { "no" },
+            );
+        }
+        f.write_str(&content)
+    }
+}
+
+impl FromStr for XmlStylesheet {
+    type Err = XmlStylesheetParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut href = None;
+        let mut type_ = None;
+        let mut title = None;
+        let mut media = None;
+        let mut charset = None;
+        let mut alternate = None;
+
+        for attribute in pseudo_attributes(s) {
+            let (name, value) = attribute?;
+            match name {
+                "href" => href = Some(value.into_owned()),
+                "type" => type_ = Some(value.into_owned()),
+                "title" => title = Some(value.into_owned()),
+                "media" => media = Some(value.into_owned()),
+                "charset" => charset = Some(value.into_owned()),
+                "alternate" => {
+                    alternate = Some(match &*value {
+                        "yes" => true,
+                        "no" => false,
+                        _ => {
+                            return Err(XmlStylesheetParseError::InvalidAlternate(
+                                value.into_owned(),
+                            ))
+                        }
+                    })
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            href: href.ok_or(XmlStylesheetParseError::MissingPseudoAttribute("href"))?,
+            type_: type_.ok_or(XmlStylesheetParseError::MissingPseudoAttribute("type"))?,
+            title,
+            media,
+            charset,
+            alternate,
+        })
+    }
+}
+
+impl Serialize for XmlStylesheet {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let content = self.to_string();
+        serializer.serialize_pi(Self::TARGET.as_bytes(), content.as_bytes())
+    }
+}
+
+impl<'de> Deserialize<'de> for XmlStylesheet {
+    fn deserialize<D: Deserializer<'de>>(reader: D) -> Result<Self, D::Error> {
+        reader
+            .deserialize_any(FromPiVisitor::default())
+            .map(|Pi(stylesheet)| stylesheet)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::empty("", vec![])]
+    #[case::whitespace("  \n ", vec![])]
+    #[case::single(r#"href="a.css""#, vec![("href", "a.css")])]
+    #[case::references(r#"a="&lt;&#x3f;&#62;" b='&apos;&quot;'"#, vec![("a", "<?>"), ("b", r#"'""#)])]
+    #[case::single_quotes(r#" title='say "hi"' "#, vec![("title", r#"say "hi""#)])]
+    #[case::spaced(r#"a = "1"	b="2""#, vec![("a", "1"), ("b", "2")])]
+    fn parse_pseudo_attributes(#[case] content: &str, #[case] expected: Vec<(&str, &str)>) {
+        let actual = pseudo_attributes(content)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let expected = expected
+            .into_iter()
+            .map(|(name, value)| (name, Cow::Borrowed(value)))
+            .collect::<Vec<_>>();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    #[case::missing_equals("href", PseudoAttributeParseError::MissingEquals("href".to_owned()))]
+    #[case::missing_quote("href=a", PseudoAttributeParseError::MissingQuote("href".to_owned()))]
+    #[case::unclosed_quote(r#"href="a"#, PseudoAttributeParseError::UnclosedQuote("href".to_owned()))]
+    #[case::invalid_reference(
+        r#"href="a&b;""#,
+        PseudoAttributeParseError::InvalidReference(
+            "href".to_owned(),
+            UnescapeError::UnknownEntity("b".to_owned())
+        )
+    )]
+    fn parse_invalid_pseudo_attributes(
+        #[case] content: &str,
+        #[case] expected: PseudoAttributeParseError,
+    ) {
+        let actual = pseudo_attributes(content).collect::<Result<Vec<_>, _>>();
+
+        assert_eq!(actual, Err(expected));
+    }
+
+    #[rstest]
+    #[case::plain("a.css", r#"title="a.css""#)]
+    #[case::double_quotes(r#"say "hi""#, r#"title='say "hi"'"#)]
+    #[case::mixed_quotes(r#"it's "quoted""#, r#"title="it's &quot;quoted&quot;""#)]
+    #[case::pi_terminator("a?>b", r#"title="a?&gt;b""#)]
+    #[case::references("a &amp; <b>", r#"title="a &amp;amp; &lt;b&gt;""#)]
+    fn push_pseudo_attributes_round_trip(#[case] value: &str, #[case] expected: &str) {
+        let mut content = String::new();
+        push_pseudo_attribute(&mut content, "title", value);
+
+        assert_eq!(content, expected);
+
+        let parsed = pseudo_attributes(&content)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(parsed, [("title", value.into())]);
+    }
+
+    #[rstest]
+    #[case::missing_href(
+        r#"type="text/css""#,
+        XmlStylesheetParseError::MissingPseudoAttribute("href")
+    )]
+    #[case::missing_type(
+        r#"href="a.css""#,
+        XmlStylesheetParseError::MissingPseudoAttribute("type")
+    )]
+    #[case::invalid_alternate(
+        r#"href="a.css" type="text/css" alternate="maybe""#,
+        XmlStylesheetParseError::InvalidAlternate("maybe".to_owned())
+    )]
+    fn parse_invalid_xml_stylesheet(
+        #[case] content: &str,
+        #[case] expected: XmlStylesheetParseError,
+    ) {
+        assert_eq!(content.parse::<XmlStylesheet>(), Err(expected));
+    }
+}
@@ -37,9 +37,28 @@ impl_deserialize_for_primitive!(
     u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, usize, isize, f32, f64, char
 );
 
+impl_serialize_for_primitive!(
+    std::net::IpAddr,
+    std::net::Ipv4Addr,
+    std::net::Ipv6Addr,
+    std::net::SocketAddr,
+    std::net::SocketAddrV4,
+    std::net::SocketAddrV6
+);
+
+impl_deserialize_for_primitive!(
+    std::net::IpAddr,
+    std::net::Ipv4Addr,
+    std::net::Ipv6Addr,
+    std::net::SocketAddr,
+    std::net::SocketAddrV4,
+    std::net::SocketAddrV6
+);
+
 impl Serialize for bool {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-        serializer.serialize_text(if *self { "true" } else { "false" })
+        let text = serializer.bool_format().format(*self);
+        serializer.serialize_text(text)
     }
 }
 
@@ -167,4 +186,23 @@ mod tests {
 
         let _err = result.expect_err("deserialize should fail");
     }
+
+    #[test]
+    fn test_de_serialize_ip_addr() {
+        let value = XmlText::new("::1");
+
+        let result = <std::net::IpAddr>::deserialize(&value);
+
+        let result = result.expect("deserialize should not fail");
+        assert_eq!(result, std::net::IpAddr::V6(std::net::Ipv6Addr::LOCALHOST));
+    }
+
+    #[test]
+    fn test_de_serialize_socket_addr_invalid() {
+        let value = XmlText::new("127.0.0.1");
+
+        let result = <std::net::SocketAddr>::deserialize(&value);
+
+        let _err = result.expect_err("deserialize should fail");
+    }
 }
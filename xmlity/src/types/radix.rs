@@ -0,0 +1,159 @@
+//! This module contains wrappers for integers written with a radix prefix, like `0x1F`, `0o17` and `0b101`.
+//!
+//! These are used by `#[xvalue(radix_prefixes)]` and `#[xvalue(int_format = "hex")]`, but can also be used as field types directly, for example for attributes.
+use core::fmt;
+use std::{num::ParseIntError, str::FromStr};
+
+use crate::{types::string::FromStrVisitor, Deserialize, Deserializer, Serialize, Serializer};
+
+/// An integer type that can be parsed in any radix and written in hexadecimal.
+pub trait RadixInteger: Sized + Copy {
+    /// Parses the integer from digits in the given radix, with an optional sign.
+    fn from_str_radix(src: &str, radix: u32) -> Result<Self, ParseIntError>;
+
+    /// Writes the integer in hexadecimal with a `0x` prefix, after the sign if it is negative.
+    fn to_hex_string(self) -> String;
+}
+
+macro_rules! impl_radix_integer_for_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl RadixInteger for $t {
+                fn from_str_radix(src: &str, radix: u32) -> Result<Self, ParseIntError> {
+                    <$t>::from_str_radix(src, radix)
+                }
+
+                fn to_hex_string(self) -> String {
+                    format!("0x{self:x}")
+                }
+            }
+        )*
+    };
+}
+
+impl_radix_integer_for_unsigned!(u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_radix_integer_for_signed {
+    ($($t:ty),*) => {
+        $(
+            impl RadixInteger for $t {
+                fn from_str_radix(src: &str, radix: u32) -> Result<Self, ParseIntError> {
+                    <$t>::from_str_radix(src, radix)
+                }
+
+                fn to_hex_string(self) -> String {
+                    if self < 0 {
+                        format!("-0x{:x}", self.unsigned_abs())
+                    } else {
+                        format!("0x{self:x}")
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_radix_integer_for_signed!(i8, i16, i32, i64, i128, isize);
+
+/// Parses an integer that may have a `0x`, `0o` or `0b` prefix after its sign, and is decimal otherwise.
+///
+/// ```
+/// use xmlity::types::radix::parse_radix_prefixed;
+///
+/// assert_eq!(parse_radix_prefixed::<u32>("0x1F"), Ok(31));
+/// assert_eq!(parse_radix_prefixed::<i32>("-0b101"), Ok(-5));
+/// assert_eq!(parse_radix_prefixed::<u32>("17"), Ok(17));
+/// ```
+pub fn parse_radix_prefixed<T: RadixInteger>(s: &str) -> Result<T, ParseIntError> {
+    let (sign, unsigned) = match s.strip_prefix(['+', '-']) {
+        Some(unsigned) => (&s[..1], unsigned),
+        None => ("", s),
+    };
+
+    let prefix = unsigned.get(..2).map(str::to_ascii_lowercase);
+    let radix = match prefix.as_deref() {
+        Some("0x") => 16,
+        Some("0o") => 8,
+        Some("0b") => 2,
+        _ => return T::from_str_radix(s, 10),
+    };
+    let digits = &unsigned[2..];
+
+    // A sign after the prefix is not allowed. A lone sign is always an invalid digit.
+    if digits.starts_with(['+', '-']) {
+        return T::from_str_radix(&digits[..1], radix);
+    }
+
+    if sign.is_empty() {
+        T::from_str_radix(digits, radix)
+    } else {
+        T::from_str_radix(&format!("{sign}{digits}"), radix)
+    }
+}
+
+/// An integer that is deserialized from text that may have a radix prefix, as parsed by [`parse_radix_prefixed`]. It is serialized in decimal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct RadixPrefixed<T>(pub T);
+
+impl<T: RadixInteger> FromStr for RadixPrefixed<T> {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_radix_prefixed(s).map(RadixPrefixed)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for RadixPrefixed<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<'de, T: RadixInteger> Deserialize<'de> for RadixPrefixed<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(FromStrVisitor::default())
+    }
+}
+
+impl<T: Serialize> Serialize for RadixPrefixed<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+/// An integer that is serialized in hexadecimal with a `0x` prefix. Like [`RadixPrefixed`], it is deserialized from text with any radix prefix.
+///
+/// ```
+/// use xmlity::types::radix::Hex;
+///
+/// assert_eq!(Hex(255u8).to_string(), "0xff");
+/// assert_eq!(Hex(-16i32).to_string(), "-0x10");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Hex<T>(pub T);
+
+impl<T: RadixInteger> FromStr for Hex<T> {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_radix_prefixed(s).map(Hex)
+    }
+}
+
+impl<T: RadixInteger> fmt::Display for Hex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0.to_hex_string())
+    }
+}
+
+impl<'de, T: RadixInteger> Deserialize<'de> for Hex<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(FromStrVisitor::default())
+    }
+}
+
+impl<T: RadixInteger> Serialize for Hex<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_text(self.0.to_hex_string())
+    }
+}
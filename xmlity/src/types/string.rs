@@ -3,7 +3,7 @@
 use super::utils::FromCDataVisitor;
 use core::fmt;
 use core::fmt::Debug;
-use std::{marker::PhantomData, str::FromStr};
+use std::{borrow::Cow, marker::PhantomData, str::FromStr};
 
 use crate::{
     de::{Error, Visitor, XmlCData, XmlText},
@@ -143,6 +143,55 @@ impl Serialize for String {
     }
 }
 
+/// This visitor deserializes text or a CDATA section as a string borrowed from the input where the deserializer allows it.
+struct CowStrVisitor;
+
+impl<'de> Visitor<'de> for CowStrVisitor {
+    type Value = Cow<'de, str>;
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a string")
+    }
+
+    fn visit_text<E, V>(self, v: V) -> Result<Self::Value, E>
+    where
+        E: Error,
+        V: XmlText<'de>,
+    {
+        Ok(v.into_string())
+    }
+
+    fn visit_cdata<E, V>(self, v: V) -> Result<Self::Value, E>
+    where
+        E: Error,
+        V: XmlCData<'de>,
+    {
+        Ok(v.into_string())
+    }
+}
+
+/// Text is borrowed from the input if the deserializer can do so, and copied otherwise.
+impl<'de: 'a, 'a> Deserialize<'de> for Cow<'a, str> {
+    fn deserialize<D: Deserializer<'de>>(reader: D) -> Result<Self, D::Error> {
+        reader.deserialize_any(CowStrVisitor)
+    }
+}
+
+/// Text can only be deserialized as a `&str` if the deserializer can borrow it from the input, which is not possible for text that has to be unescaped. Use [`Cow<str>`](Cow) to fall back to copying it.
+impl<'de: 'a, 'a> Deserialize<'de> for &'a str {
+    fn deserialize<D: Deserializer<'de>>(reader: D) -> Result<Self, D::Error> {
+        match reader.deserialize_any(CowStrVisitor)? {
+            Cow::Borrowed(text) => Ok(text),
+            Cow::Owned(_) => Err(Error::custom("the text can not be borrowed from the input")),
+        }
+    }
+}
+
+impl Serialize for Cow<'_, str> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_text(self)
+    }
+}
+
 impl Serialize for &str {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         //TODO: Change to serialize as CDATA if it contains invalid XML characters
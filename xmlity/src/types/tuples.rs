@@ -1,4 +1,8 @@
-use crate::{de, ser::SerializeSeq, Deserialize, Deserializer, Serialize};
+use crate::{
+    de::{self, AttributesAccess, DeserializationGroupBuilder, SeqAccess},
+    ser::{SerializeAttributes, SerializeSeq},
+    DeserializationGroup, Deserialize, Deserializer, SerializationGroup, Serialize,
+};
 
 macro_rules! impl_serialize_tuple {
     (@impl $(($name:ident, $index:tt)),+) => {
@@ -102,3 +106,104 @@ impl_deserialize_tuple!(
     (T2),
     (T1)
 );
+
+/// Tuples of groups are groups themselves, contributing the attributes and children of their members in order. This makes it possible to combine groups without defining a new group type.
+macro_rules! impl_group_tuple {
+    (@impl $(($name:ident, $index:tt)),+) => {
+        impl<$($name: SerializationGroup),+> SerializationGroup for ($($name,)+) {
+            fn serialize_attributes<S: SerializeAttributes>(
+                &self,
+                serializer: &mut S,
+            ) -> Result<(), S::Error> {
+                $(self.$index.serialize_attributes(serializer)?;)+
+                Ok(())
+            }
+
+            fn serialize_children<S: SerializeSeq>(&self, serializer: &mut S) -> Result<(), S::Error> {
+                $(self.$index.serialize_children(serializer)?;)+
+                Ok(())
+            }
+        }
+
+        impl<'de, $($name: DeserializationGroup<'de>),+> DeserializationGroup<'de> for ($($name,)+) {
+            type Builder = ($($name::Builder,)+);
+
+            fn builder() -> Self::Builder {
+                ($($name::builder(),)+)
+            }
+        }
+
+        impl<'de, $($name: DeserializationGroupBuilder<'de>),+> DeserializationGroupBuilder<'de> for ($($name,)+) {
+            type Value = ($($name::Value,)+);
+
+            fn contribute_attributes<D: AttributesAccess<'de>>(
+                &mut self,
+                mut access: D,
+            ) -> Result<bool, D::Error> {
+                $(
+                    if !self.$index.attributes_done() {
+                        if let Ok(true) = self.$index.contribute_attributes(access.sub_access()?) {
+                            return Ok(true);
+                        }
+                    }
+                )+
+                Ok(false)
+            }
+
+            fn attributes_done(&self) -> bool {
+                $(self.$index.attributes_done())&&+
+            }
+
+            fn contribute_elements<D: SeqAccess<'de>>(&mut self, mut access: D) -> Result<bool, D::Error> {
+                $(
+                    if !self.$index.elements_done() {
+                        if let Ok(true) = self.$index.contribute_elements(access.sub_access()?) {
+                            return Ok(true);
+                        }
+                    }
+                )+
+                Ok(false)
+            }
+
+            fn elements_done(&self) -> bool {
+                $(self.$index.elements_done())&&+
+            }
+
+            fn completed_fields(&self) -> Vec<&'static str> {
+                let mut completed = Vec::new();
+                $(completed.extend(self.$index.completed_fields());)+
+                completed
+            }
+
+            fn finish<E: de::Error>(self) -> Result<Self::Value, E> {
+                Ok(($(self.$index.finish::<E>()?,)+))
+            }
+        }
+    };
+    //Recursive case
+    ([$($done:tt)*] ($name:ident, $index:tt) $(,$rest:tt)*) => {
+        impl_group_tuple!(@impl $($done,)* ($name, $index));
+        impl_group_tuple!([$($done)* ($name, $index)] $($rest),*);
+    };
+    //Base case
+    ([$($done:tt)*]) => {};
+}
+
+impl_group_tuple!(
+    [](T1, 0),
+    (T2, 1),
+    (T3, 2),
+    (T4, 3),
+    (T5, 4),
+    (T6, 5),
+    (T7, 6),
+    (T8, 7),
+    (T9, 8),
+    (T10, 9),
+    (T11, 10),
+    (T12, 11),
+    (T13, 12),
+    (T14, 13),
+    (T15, 14),
+    (T16, 15)
+);
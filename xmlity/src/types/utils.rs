@@ -5,11 +5,12 @@ use std::{borrow::Cow, marker::PhantomData, str::FromStr};
 
 use crate::{
     de::{
-        self, Visitor, XmlCData, XmlComment, XmlDeclaration, XmlDoctype, XmlProcessingInstruction,
-        XmlText,
+        self, DeserializationGroupBuilder, SeqAccess as _, Visitor, XmlCData, XmlComment,
+        XmlDeclaration, XmlDoctype, XmlProcessingInstruction, XmlText,
     },
     value::{self, XmlDecl},
-    Deserialize, Deserializer, Serialize, Serializer,
+    AttributeSerializer, DeserializationGroup, Deserialize, Deserializer, ExpandedNameBuf,
+    Serialize, SerializeAttribute, Serializer,
 };
 
 /// This utility type represents an XML root document.
@@ -289,6 +290,234 @@ impl<T> Default for XmlRoot<T> {
     }
 }
 
+/// A comment or processing instruction outside of the root element of a document, which the XML specification calls a "misc" item.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum XmlMisc {
+    /// A comment, like a license header at the top of a generated file.
+    Comment(value::XmlComment),
+    /// A processing instruction, like `<?xml-stylesheet ...?>`.
+    PI(value::XmlProcessingInstruction),
+}
+
+impl From<value::XmlComment> for XmlMisc {
+    fn from(value: value::XmlComment) -> Self {
+        XmlMisc::Comment(value)
+    }
+}
+
+impl From<value::XmlProcessingInstruction> for XmlMisc {
+    fn from(value: value::XmlProcessingInstruction) -> Self {
+        XmlMisc::PI(value)
+    }
+}
+
+impl Serialize for XmlMisc {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            XmlMisc::Comment(comment) => comment.serialize(serializer),
+            XmlMisc::PI(pi) => pi.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for XmlMisc {
+    fn deserialize<D: Deserializer<'de>>(reader: D) -> Result<Self, D::Error> {
+        struct __Visitor<'v> {
+            lifetime: PhantomData<&'v ()>,
+        }
+
+        impl<'v> Visitor<'v> for __Visitor<'v> {
+            type Value = XmlMisc;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a comment or processing instruction")
+            }
+
+            fn visit_comment<E, V>(self, comment: V) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+                V: XmlComment<'v>,
+            {
+                value::deserialize::XmlCommentVisitor::new()
+                    .visit_comment(comment)
+                    .map(XmlMisc::Comment)
+            }
+
+            fn visit_pi<E, V>(self, pi: V) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+                V: XmlProcessingInstruction,
+            {
+                value::deserialize::XmlProcessingInstructionVisitor::new()
+                    .visit_pi(pi)
+                    .map(XmlMisc::PI)
+            }
+        }
+
+        reader.deserialize_any(__Visitor {
+            lifetime: PhantomData,
+        })
+    }
+}
+
+/// An XML document with a single root element, keeping the declaration, doctype, and the comments and processing instructions around the root element in the places they were read from.
+///
+/// Deserializing a type from a document directly fails if a comment or processing instruction comes before its root element. Wrapping the type in [`XmlDocument`] instead keeps them, so that they are written back in the same places, for example to keep a license header at the top of a generated file. Unlike [`XmlRoot`], the root element is required and can be accessed directly.
+///
+/// ```
+/// use xmlity::{types::utils::XmlDocument, value::XmlComment, Deserialize, Serialize};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// #[xelement(name = "config")]
+/// struct Config {
+///     #[xattribute(name = "version")]
+///     version: u32,
+/// }
+///
+/// let document = XmlDocument::new(Config { version: 2 })
+///     .with_before_root(XmlComment::new(" SPDX-License-Identifier: MIT "));
+///
+/// assert_eq!(document.root.version, 2);
+/// assert_eq!(document.before_root.len(), 1);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct XmlDocument<T> {
+    /// The declaration of the document.
+    pub decl: Option<XmlDecl>,
+    /// The comments and processing instructions before the doctype declaration. If the document has no doctype declaration, these are all kept in [`XmlDocument::before_root`] instead.
+    pub before_doctype: Vec<XmlMisc>,
+    /// The doctype declaration of the document.
+    pub doctype: Option<value::XmlDoctype>,
+    /// The comments and processing instructions before the root element.
+    pub before_root: Vec<XmlMisc>,
+    /// The root element of the document.
+    pub root: T,
+    /// The comments and processing instructions after the root element.
+    pub after_root: Vec<XmlMisc>,
+}
+
+impl<T> XmlDocument<T> {
+    /// Creates a new document with the given root element.
+    pub fn new(root: T) -> Self {
+        Self {
+            decl: None,
+            before_doctype: Vec::new(),
+            doctype: None,
+            before_root: Vec::new(),
+            root,
+            after_root: Vec::new(),
+        }
+    }
+
+    /// Sets the declaration of the document.
+    pub fn with_decl<U: Into<XmlDecl>>(mut self, decl: U) -> Self {
+        self.decl = Some(decl.into());
+        self
+    }
+
+    /// Sets the doctype declaration of the document.
+    pub fn with_doctype<U: Into<value::XmlDoctype>>(mut self, doctype: U) -> Self {
+        self.doctype = Some(doctype.into());
+        self
+    }
+
+    /// Adds a comment or processing instruction before the doctype declaration.
+    pub fn with_before_doctype<U: Into<XmlMisc>>(mut self, misc: U) -> Self {
+        self.before_doctype.push(misc.into());
+        self
+    }
+
+    /// Adds a comment or processing instruction before the root element.
+    pub fn with_before_root<U: Into<XmlMisc>>(mut self, misc: U) -> Self {
+        self.before_root.push(misc.into());
+        self
+    }
+
+    /// Adds a comment or processing instruction after the root element.
+    pub fn with_after_root<U: Into<XmlMisc>>(mut self, misc: U) -> Self {
+        self.after_root.push(misc.into());
+        self
+    }
+
+    /// Consumes the document, returning the root element.
+    pub fn into_root(self) -> T {
+        self.root
+    }
+}
+
+impl<T: Serialize> Serialize for XmlDocument<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq()?;
+        crate::ser::SerializeSeq::serialize_element(&mut seq, &self.decl)?;
+        crate::ser::SerializeSeq::serialize_element(&mut seq, &self.before_doctype)?;
+        crate::ser::SerializeSeq::serialize_element(&mut seq, &self.doctype)?;
+        crate::ser::SerializeSeq::serialize_element(&mut seq, &self.before_root)?;
+        crate::ser::SerializeSeq::serialize_element(&mut seq, &self.root)?;
+        crate::ser::SerializeSeq::serialize_element(&mut seq, &self.after_root)?;
+        crate::ser::SerializeSeq::end(seq)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for XmlDocument<T> {
+    fn deserialize<D: Deserializer<'de>>(reader: D) -> Result<Self, D::Error> {
+        struct __Visitor<'v, T> {
+            marker: PhantomData<T>,
+            lifetime: PhantomData<&'v ()>,
+        }
+
+        impl<'v, T: Deserialize<'v>> Visitor<'v> for __Visitor<'v, T> {
+            type Value = XmlDocument<T>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an XML document")
+            }
+
+            fn visit_seq<S>(self, mut sequence: S) -> Result<Self::Value, S::Error>
+            where
+                S: de::SeqAccess<'v>,
+            {
+                let decl = sequence.next_element::<XmlDecl>().ok().flatten();
+                let mut before_root = sequence
+                    .next_element_seq::<Vec<XmlMisc>>()?
+                    .unwrap_or_default();
+
+                let doctype = sequence.next_element::<value::XmlDoctype>().ok().flatten();
+                let before_doctype = if doctype.is_some() {
+                    let before_doctype = before_root;
+                    before_root = sequence
+                        .next_element_seq::<Vec<XmlMisc>>()?
+                        .unwrap_or_default();
+                    before_doctype
+                } else {
+                    Vec::new()
+                };
+
+                let root = sequence
+                    .next_element::<T>()?
+                    .ok_or_else(de::Error::missing_data)?;
+
+                let after_root = sequence
+                    .next_element_seq::<Vec<XmlMisc>>()?
+                    .unwrap_or_default();
+
+                Ok(XmlDocument {
+                    decl,
+                    before_doctype,
+                    doctype,
+                    before_root,
+                    root,
+                    after_root,
+                })
+            }
+        }
+
+        reader.deserialize_seq(__Visitor {
+            marker: PhantomData,
+            lifetime: PhantomData,
+        })
+    }
+}
+
 /// A visitor for deserializing a string from a CDATA section.
 pub struct FromCDataVisitor<T> {
     _marker: PhantomData<fn() -> T>,
@@ -337,6 +566,76 @@ impl<S: AsRef<str>> Serialize for CData<S> {
     }
 }
 
+/// An attribute with a name chosen at runtime, making it possible to add one-off attributes without defining a type for them.
+///
+/// The value is serialized as the text of the attribute, so any text type like strings and numbers can be used. When deserializing, any attribute is accepted and its name is kept in [`Attribute::name`].
+///
+/// ```
+/// use xmlity::{types::utils::Attribute, ExpandedNameBuf};
+///
+/// let count = Attribute::new("count".parse::<ExpandedNameBuf>().unwrap(), 3u32);
+/// assert_eq!(count.value, 3);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Attribute<T> {
+    /// The name of the attribute.
+    pub name: ExpandedNameBuf,
+    /// The value of the attribute.
+    pub value: T,
+}
+
+impl<T> Attribute<T> {
+    /// Creates a new attribute with the given name and value.
+    pub fn new<N: Into<ExpandedNameBuf>>(name: N, value: T) -> Self {
+        Self {
+            name: name.into(),
+            value,
+        }
+    }
+}
+
+impl<T: Serialize> SerializeAttribute for Attribute<T> {
+    fn serialize_attribute<S: AttributeSerializer>(
+        &self,
+        mut serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let attribute = serializer.serialize_attribute(&self.name.as_ref())?;
+        crate::ser::SerializeAttributeAccess::end(attribute, &self.value)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Attribute<T> {
+    fn deserialize<D: Deserializer<'de>>(reader: D) -> Result<Self, D::Error> {
+        struct __Visitor<'v, T> {
+            marker: PhantomData<T>,
+            lifetime: PhantomData<&'v ()>,
+        }
+
+        impl<'v, T: Deserialize<'v>> Visitor<'v> for __Visitor<'v, T> {
+            type Value = Attribute<T>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an attribute")
+            }
+
+            fn visit_attribute<A>(self, attribute: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::AttributeAccess<'v>,
+            {
+                Ok(Attribute {
+                    name: attribute.name().into_owned(),
+                    value: attribute.value()?,
+                })
+            }
+        }
+
+        reader.deserialize_any(__Visitor {
+            marker: PhantomData,
+            lifetime: PhantomData,
+        })
+    }
+}
+
 /// A type that ignores that uses the value that visits it, but results in nothing. Useful for skipping over values.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Whitespace<'a>(pub std::borrow::Cow<'a, str>);
@@ -406,14 +705,16 @@ impl<'de, T: Deserialize<'de>> Deserialize<'de> for ValueOrWhitespace<'de, T> {
             where
                 S: de::SeqAccess<'v>,
             {
-                if let Ok(Some(text)) = sequence.next_element::<Whitespace>() {
-                    Ok(ValueOrWhitespace::Whitespace(text.0))
-                } else {
-                    sequence
-                        .next_element_seq::<T>()?
-                        .ok_or_else(de::Error::missing_data)
-                        .map(ValueOrWhitespace::Value)
+                if !sequence.preserves_whitespace() {
+                    if let Ok(Some(text)) = sequence.next_element::<Whitespace>() {
+                        return Ok(ValueOrWhitespace::Whitespace(text.0));
+                    }
                 }
+
+                sequence
+                    .next_element_seq::<T>()?
+                    .ok_or_else(de::Error::missing_data)
+                    .map(ValueOrWhitespace::Value)
             }
 
             fn visit_none<E>(self) -> Result<Self::Value, E>
@@ -531,6 +832,43 @@ impl<'de> Deserialize<'de> for IgnoredAny {
     }
 }
 
+/// A [`bool`] that is always written in the given [`BoolFormat`](crate::ser::BoolFormat), regardless of the format of the serializer.
+///
+/// This is what `#[xvalue(bool_format = "...")]` uses to override the format for a single field. Deserializing accepts every form that [`bool`] accepts.
+///
+/// ```
+/// use xmlity::{ser::BoolFormat, types::utils::FormattedBool};
+///
+/// let value = FormattedBool::new(true, BoolFormat::Numeric);
+/// assert_eq!(value.format.format(value.value), "1");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FormattedBool {
+    /// The value.
+    pub value: bool,
+    /// The format the value is written in.
+    pub format: crate::ser::BoolFormat,
+}
+
+impl FormattedBool {
+    /// Creates a new [`FormattedBool`].
+    pub fn new(value: bool, format: crate::ser::BoolFormat) -> Self {
+        Self { value, format }
+    }
+}
+
+impl Serialize for FormattedBool {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_text(self.format.format(self.value))
+    }
+}
+
+impl<'de> Deserialize<'de> for FormattedBool {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        bool::deserialize(deserializer).map(|value| Self::new(value, Default::default()))
+    }
+}
+
 /// A deserializer that always runs [`Visitor::visit_none`].
 pub struct NoneDeserializer<E: de::Error> {
     _marker: PhantomData<E>,
@@ -568,3 +906,331 @@ impl<'de, E: de::Error> Deserializer<'de> for NoneDeserializer<E> {
         visitor.visit_none()
     }
 }
+
+/// A visitor that forwards every node to the visitor `V` and converts the value it produces into `T` using [`TryFrom`].
+///
+/// This makes it easy to implement [`Deserialize`] for newtype wrappers, such as validated strings, without writing out every visit method. Conversion errors are reported through [`de::Error::custom`].
+///
+/// ```
+/// use xmlity::{types::{string::FromStrVisitor, utils::NewtypeVisitor}, Deserialize, Deserializer};
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Email(String);
+///
+/// impl TryFrom<String> for Email {
+///     type Error = &'static str;
+///
+///     fn try_from(value: String) -> Result<Self, Self::Error> {
+///         if value.contains('@') {
+///             Ok(Email(value))
+///         } else {
+///             Err("missing @ in email address")
+///         }
+///     }
+/// }
+///
+/// impl<'de> Deserialize<'de> for Email {
+///     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+///         deserializer.deserialize_any(NewtypeVisitor::new(FromStrVisitor::<String>::default()))
+///     }
+/// }
+/// ```
+pub struct NewtypeVisitor<V, T> {
+    inner: V,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<V, T> NewtypeVisitor<V, T> {
+    /// Creates a new [`NewtypeVisitor`] forwarding to `inner`.
+    pub fn new(inner: V) -> Self {
+        Self {
+            inner,
+            _marker: PhantomData,
+        }
+    }
+
+    fn convert<'de, E>(value: V::Value) -> Result<T, E>
+    where
+        V: Visitor<'de>,
+        T: TryFrom<V::Value>,
+        T::Error: fmt::Display,
+        E: de::Error,
+    {
+        T::try_from(value).map_err(E::custom)
+    }
+}
+
+impl<V: Default, T> Default for NewtypeVisitor<V, T> {
+    fn default() -> Self {
+        Self::new(V::default())
+    }
+}
+
+impl<'de, V, T> Visitor<'de> for NewtypeVisitor<V, T>
+where
+    V: Visitor<'de>,
+    T: Deserialize<'de> + TryFrom<V::Value>,
+    T::Error: fmt::Display,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.inner.expecting(formatter)
+    }
+
+    fn visit_text<E, X>(self, value: X) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+        X: XmlText<'de>,
+    {
+        self.inner.visit_text(value).and_then(Self::convert)
+    }
+
+    fn visit_cdata<E, X>(self, value: X) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+        X: XmlCData<'de>,
+    {
+        self.inner.visit_cdata(value).and_then(Self::convert)
+    }
+
+    fn visit_element<A>(self, element: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::ElementAccess<'de>,
+    {
+        self.inner.visit_element(element).and_then(Self::convert)
+    }
+
+    fn visit_attribute<A>(self, attribute: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::AttributeAccess<'de>,
+    {
+        self.inner
+            .visit_attribute(attribute)
+            .and_then(Self::convert)
+    }
+
+    fn visit_seq<S>(self, sequence: S) -> Result<Self::Value, S::Error>
+    where
+        S: de::SeqAccess<'de>,
+    {
+        self.inner.visit_seq(sequence).and_then(Self::convert)
+    }
+
+    fn visit_pi<E, X>(self, pi: X) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+        X: XmlProcessingInstruction,
+    {
+        self.inner.visit_pi(pi).and_then(Self::convert)
+    }
+
+    fn visit_decl<E, X>(self, declaration: X) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+        X: XmlDeclaration,
+    {
+        self.inner.visit_decl(declaration).and_then(Self::convert)
+    }
+
+    fn visit_comment<E, X>(self, comment: X) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+        X: XmlComment<'de>,
+    {
+        self.inner.visit_comment(comment).and_then(Self::convert)
+    }
+
+    fn visit_doctype<E, X>(self, doctype: X) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+        X: XmlDoctype<'de>,
+    {
+        self.inner.visit_doctype(doctype).and_then(Self::convert)
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.inner.visit_none().and_then(Self::convert)
+    }
+}
+
+/// An element deserialized into the [`DeserializationGroup`] `T`, which keeps track of what was deserialized if it fails.
+///
+/// Errors inside the element do not fail deserialization. Instead, [`PartialElement::Incomplete`] reports the fields that were completed before the error, so tooling can show which portions of a document were valid. The name of the element is not checked, and attributes and children that `T` does not accept are skipped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PartialElement<T> {
+    /// The element was deserialized completely.
+    Complete(T),
+    /// Deserializing the element failed.
+    Incomplete {
+        /// The fields that were deserialized before the error, as reported by [`DeserializationGroupBuilder::completed_fields`].
+        completed_fields: Vec<&'static str>,
+        /// A description of the error.
+        error: String,
+    },
+}
+
+impl<T> PartialElement<T> {
+    /// Returns the complete value, if there is one.
+    pub fn complete(self) -> Option<T> {
+        match self {
+            PartialElement::Complete(value) => Some(value),
+            PartialElement::Incomplete { .. } => None,
+        }
+    }
+}
+
+fn contribute_element<'de, B: DeserializationGroupBuilder<'de>, A: de::ElementAccess<'de>>(
+    builder: &mut B,
+    mut element: A,
+) -> Result<(), A::Error> {
+    while !builder.attributes_done() {
+        if !builder.contribute_attributes(element.sub_access()?)?
+            && element.next_attribute::<IgnoredAny>()?.is_none()
+        {
+            break;
+        }
+    }
+
+    let mut children = element.children()?;
+    while !builder.elements_done() {
+        if !builder.contribute_elements(children.sub_access()?)?
+            && children.next_element::<IgnoredAny>()?.is_none()
+        {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+impl<'de, T: DeserializationGroup<'de>> Deserialize<'de> for PartialElement<T> {
+    fn deserialize<D: Deserializer<'de>>(reader: D) -> Result<Self, D::Error> {
+        struct __Visitor<'v, T> {
+            marker: PhantomData<T>,
+            lifetime: PhantomData<&'v ()>,
+        }
+
+        impl<'v, T: DeserializationGroup<'v>> Visitor<'v> for __Visitor<'v, T> {
+            type Value = PartialElement<T>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an element")
+            }
+
+            fn visit_element<A>(self, element: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::ElementAccess<'v>,
+            {
+                let mut builder = T::builder();
+
+                let result = contribute_element(&mut builder, element);
+                let result = match result {
+                    Ok(()) => {
+                        let completed_fields = builder.completed_fields();
+                        builder
+                            .finish::<A::Error>()
+                            .map_err(|error| (completed_fields, error))
+                    }
+                    Err(error) => Err((builder.completed_fields(), error)),
+                };
+
+                Ok(match result {
+                    Ok(value) => PartialElement::Complete(value),
+                    Err((completed_fields, error)) => PartialElement::Incomplete {
+                        completed_fields,
+                        error: error.to_string(),
+                    },
+                })
+            }
+        }
+
+        reader.deserialize_any(__Visitor {
+            marker: PhantomData,
+            lifetime: PhantomData,
+        })
+    }
+}
+
+/// A subtree that is captured as an [`XmlValue`](value::XmlValue) during deserialization and only parsed into `T` when [`LazyXml::parse`] is called.
+///
+/// This is useful for large branches of a document that are rarely accessed, as the cost of deserializing them into `T` is only paid when needed. Serializing a [`LazyXml`] writes the captured subtree back out as-is.
+///
+/// Since nothing is checked until [`LazyXml::parse`] is called, a [`LazyXml`] accepts any node in the position it is used, including elements with a different name than `T` expects.
+pub struct LazyXml<T> {
+    value: value::XmlValue,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> LazyXml<T> {
+    /// Creates a new [`LazyXml`] from a captured value.
+    pub fn new(value: value::XmlValue) -> Self {
+        Self {
+            value,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the captured value.
+    pub fn value(&self) -> &value::XmlValue {
+        &self.value
+    }
+
+    /// Returns the captured value, consuming the [`LazyXml`].
+    pub fn into_value(self) -> value::XmlValue {
+        self.value
+    }
+
+    /// Parses the captured value into `T`.
+    pub fn parse<'a>(&'a self) -> Result<T, value::XmlValueDeserializerError>
+    where
+        T: Deserialize<'a>,
+    {
+        value::from_value(&self.value)
+    }
+}
+
+impl<T> From<value::XmlValue> for LazyXml<T> {
+    fn from(value: value::XmlValue) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T> Debug for LazyXml<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("LazyXml").field(&self.value).finish()
+    }
+}
+
+impl<T> Clone for LazyXml<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.value.clone())
+    }
+}
+
+impl<T> PartialEq for LazyXml<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T> Eq for LazyXml<T> {}
+
+impl<'de, T> Deserialize<'de> for LazyXml<T> {
+    fn deserialize<D: Deserializer<'de>>(reader: D) -> Result<Self, D::Error> {
+        value::XmlValue::deserialize(reader).map(Self::new)
+    }
+
+    fn deserialize_seq<D: Deserializer<'de>>(reader: D) -> Result<Self, D::Error> {
+        value::XmlValue::deserialize_seq(reader).map(Self::new)
+    }
+}
+
+impl<T> Serialize for LazyXml<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize(serializer)
+    }
+}
@@ -0,0 +1,413 @@
+//! This module contains types for the partial date primitives of XML Schema, `xs:gYear`, `xs:gYearMonth`, `xs:gMonth` and `xs:gDay`, which have no equivalent in the standard library or common date crates.
+//!
+//! All of them parse their lexical representation including the optional timezone suffix, and serialize to the canonical representation.
+use core::fmt;
+use std::str::FromStr;
+
+use crate::{
+    de::{Deserialize, Deserializer},
+    ser::{Serialize, Serializer},
+    types::string::FromStrVisitor,
+};
+
+/// An error that can occur when parsing one of the partial date types.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseGregorianError {
+    /// The value does not have the expected format.
+    InvalidFormat(&'static str),
+    /// The year is out of range.
+    InvalidYear,
+    /// The month is not between 1 and 12.
+    InvalidMonth,
+    /// The day is not between 1 and 31.
+    InvalidDay,
+    /// The timezone is not `Z` or an offset between `-14:00` and `+14:00`.
+    InvalidTimezone,
+}
+
+impl fmt::Display for ParseGregorianError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidFormat(value) => write!(f, "Invalid format, expected {value}"),
+            Self::InvalidYear => f.write_str("Invalid year"),
+            Self::InvalidMonth => f.write_str("Invalid month"),
+            Self::InvalidDay => f.write_str("Invalid day"),
+            Self::InvalidTimezone => f.write_str("Invalid timezone"),
+        }
+    }
+}
+
+impl std::error::Error for ParseGregorianError {}
+
+/// A timezone offset, as used by the suffix of the partial date types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timezone {
+    offset_minutes: i16,
+}
+
+impl Timezone {
+    /// The UTC timezone, written as `Z`.
+    pub const UTC: Self = Self { offset_minutes: 0 };
+
+    /// Creates a timezone from its offset from UTC in minutes, which must be between -840 and 840.
+    pub fn from_offset_minutes(offset_minutes: i16) -> Option<Self> {
+        (-14 * 60..=14 * 60)
+            .contains(&offset_minutes)
+            .then_some(Self { offset_minutes })
+    }
+
+    /// Returns the offset from UTC in minutes.
+    pub fn offset_minutes(&self) -> i16 {
+        self.offset_minutes
+    }
+
+    fn parse(s: &str) -> Result<Option<Self>, ParseGregorianError> {
+        if s.is_empty() {
+            return Ok(None);
+        }
+        if s == "Z" {
+            return Ok(Some(Self::UTC));
+        }
+
+        let (sign, offset) = match s.split_at(1) {
+            ("+", offset) => (1, offset),
+            ("-", offset) => (-1, offset),
+            _ => return Err(ParseGregorianError::InvalidTimezone),
+        };
+        let (hours, minutes) = match offset.as_bytes() {
+            [h1, h2, b':', m1, m2] => (
+                parse_digits(&[*h1, *h2]).ok_or(ParseGregorianError::InvalidTimezone)?,
+                parse_digits(&[*m1, *m2]).ok_or(ParseGregorianError::InvalidTimezone)?,
+            ),
+            _ => return Err(ParseGregorianError::InvalidTimezone),
+        };
+        if minutes > 59 {
+            return Err(ParseGregorianError::InvalidTimezone);
+        }
+
+        Self::from_offset_minutes(sign * (hours as i16 * 60 + minutes as i16))
+            .map(Some)
+            .ok_or(ParseGregorianError::InvalidTimezone)
+    }
+}
+
+impl fmt::Display for Timezone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.offset_minutes == 0 {
+            return f.write_str("Z");
+        }
+
+        let sign = if self.offset_minutes < 0 { '-' } else { '+' };
+        let offset = self.offset_minutes.unsigned_abs();
+        write!(f, "{sign}{:02}:{:02}", offset / 60, offset % 60)
+    }
+}
+
+fn parse_digits(digits: &[u8]) -> Option<u32> {
+    if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    std::str::from_utf8(digits).ok()?.parse().ok()
+}
+
+/// Splits off the timezone suffix, which starts at the first `Z`, `+` or `-` at or after `start`.
+fn split_timezone(s: &str, start: usize) -> Result<(&str, Option<Timezone>), ParseGregorianError> {
+    let split = s
+        .get(start..)
+        .and_then(|rest| rest.find(['Z', '+', '-']))
+        .map_or(s.len(), |index| start + index);
+    let (value, timezone) = s.split_at(split);
+    Ok((value, Timezone::parse(timezone)?))
+}
+
+fn parse_year(s: &str) -> Result<i32, ParseGregorianError> {
+    let (negative, digits) = match s.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, s),
+    };
+    // Years have at least four digits, and only have leading zeros if they have exactly four.
+    if digits.len() < 4 || (digits.len() > 4 && digits.starts_with('0')) {
+        return Err(ParseGregorianError::InvalidYear);
+    }
+    let year = parse_digits(digits.as_bytes())
+        .and_then(|year| i32::try_from(year).ok())
+        .ok_or(ParseGregorianError::InvalidYear)?;
+    Ok(if negative { -year } else { year })
+}
+
+fn parse_month(digits: &str) -> Result<u8, ParseGregorianError> {
+    match digits.as_bytes() {
+        [_, _] => parse_digits(digits.as_bytes())
+            .filter(|month| (1..=12).contains(month))
+            .map(|month| month as u8)
+            .ok_or(ParseGregorianError::InvalidMonth),
+        _ => Err(ParseGregorianError::InvalidMonth),
+    }
+}
+
+fn parse_day(digits: &str) -> Result<u8, ParseGregorianError> {
+    match digits.as_bytes() {
+        [_, _] => parse_digits(digits.as_bytes())
+            .filter(|day| (1..=31).contains(day))
+            .map(|day| day as u8)
+            .ok_or(ParseGregorianError::InvalidDay),
+        _ => Err(ParseGregorianError::InvalidDay),
+    }
+}
+
+fn write_year(f: &mut fmt::Formatter<'_>, year: i32) -> fmt::Result {
+    if year < 0 {
+        f.write_str("-")?;
+    }
+    write!(f, "{:04}", year.unsigned_abs())
+}
+
+fn write_timezone(f: &mut fmt::Formatter<'_>, timezone: Option<Timezone>) -> fmt::Result {
+    match timezone {
+        Some(timezone) => write!(f, "{timezone}"),
+        None => Ok(()),
+    }
+}
+
+macro_rules! impl_text_serde {
+    ($($t:ty),*) => {
+        $(
+            impl Serialize for $t {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serializer.serialize_text(self.to_string())
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $t {
+                fn deserialize<D: Deserializer<'de>>(reader: D) -> Result<Self, D::Error> {
+                    reader.deserialize_any(FromStrVisitor::default())
+                }
+            }
+        )*
+    };
+}
+
+/// A Gregorian year, corresponding to `xs:gYear`, like `2024` or `-0044+01:00`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GYear {
+    /// The year. Negative years are before the common era.
+    pub year: i32,
+    /// The timezone, if one was given.
+    pub timezone: Option<Timezone>,
+}
+
+impl FromStr for GYear {
+    type Err = ParseGregorianError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (year, timezone) = split_timezone(s.trim(), 1)?;
+        Ok(Self {
+            year: parse_year(year)?,
+            timezone,
+        })
+    }
+}
+
+impl fmt::Display for GYear {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_year(f, self.year)?;
+        write_timezone(f, self.timezone)
+    }
+}
+
+/// A month in a Gregorian year, corresponding to `xs:gYearMonth`, like `2024-02` or `2024-02Z`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GYearMonth {
+    /// The year. Negative years are before the common era.
+    pub year: i32,
+    /// The month, between 1 and 12.
+    pub month: u8,
+    /// The timezone, if one was given.
+    pub timezone: Option<Timezone>,
+}
+
+impl FromStr for GYearMonth {
+    type Err = ParseGregorianError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let month_start = s
+            .get(1..)
+            .and_then(|rest| rest.find('-'))
+            .map(|index| index + 2)
+            .ok_or(ParseGregorianError::InvalidFormat("YYYY-MM"))?;
+        let (value, timezone) = split_timezone(s, month_start)?;
+        let (year, month) = value.split_at(month_start - 1);
+        Ok(Self {
+            year: parse_year(year)?,
+            month: parse_month(&month[1..])?,
+            timezone,
+        })
+    }
+}
+
+impl fmt::Display for GYearMonth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_year(f, self.year)?;
+        write!(f, "-{:02}", self.month)?;
+        write_timezone(f, self.timezone)
+    }
+}
+
+/// A recurring Gregorian month, corresponding to `xs:gMonth`, like `--02` or `--02-05:00`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GMonth {
+    /// The month, between 1 and 12.
+    pub month: u8,
+    /// The timezone, if one was given.
+    pub timezone: Option<Timezone>,
+}
+
+impl FromStr for GMonth {
+    type Err = ParseGregorianError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let month = s
+            .trim()
+            .strip_prefix("--")
+            .ok_or(ParseGregorianError::InvalidFormat("--MM"))?;
+        let (month, timezone) = split_timezone(month, 2)?;
+        Ok(Self {
+            month: parse_month(month)?,
+            timezone,
+        })
+    }
+}
+
+impl fmt::Display for GMonth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "--{:02}", self.month)?;
+        write_timezone(f, self.timezone)
+    }
+}
+
+/// A recurring day of a Gregorian month, corresponding to `xs:gDay`, like `---15` or `---15Z`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GDay {
+    /// The day, between 1 and 31.
+    pub day: u8,
+    /// The timezone, if one was given.
+    pub timezone: Option<Timezone>,
+}
+
+impl FromStr for GDay {
+    type Err = ParseGregorianError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let day = s
+            .trim()
+            .strip_prefix("---")
+            .ok_or(ParseGregorianError::InvalidFormat("---DD"))?;
+        let (day, timezone) = split_timezone(day, 2)?;
+        Ok(Self {
+            day: parse_day(day)?,
+            timezone,
+        })
+    }
+}
+
+impl fmt::Display for GDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "---{:02}", self.day)?;
+        write_timezone(f, self.timezone)
+    }
+}
+
+impl_text_serde!(GYear, GYearMonth, GMonth, GDay);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn tz(offset_minutes: i16) -> Option<Timezone> {
+        Timezone::from_offset_minutes(offset_minutes)
+    }
+
+    #[rstest]
+    #[case::plain("2024", GYear { year: 2024, timezone: None }, "2024")]
+    #[case::utc("2024Z", GYear { year: 2024, timezone: tz(0) }, "2024Z")]
+    #[case::offset("-0044+01:30", GYear { year: -44, timezone: tz(90) }, "-0044+01:30")]
+    #[case::long_year("12345-14:00", GYear { year: 12345, timezone: tz(-840) }, "12345-14:00")]
+    #[case::zero_offset(" 2024+00:00 ", GYear { year: 2024, timezone: tz(0) }, "2024Z")]
+    fn g_year(#[case] input: &str, #[case] expected: GYear, #[case] canonical: &str) {
+        let actual: GYear = input.parse().unwrap();
+        assert_eq!(actual, expected);
+        assert_eq!(actual.to_string(), canonical);
+    }
+
+    #[rstest]
+    #[case::plain("2024-02", GYearMonth { year: 2024, month: 2, timezone: None })]
+    #[case::negative_year("-0500-12Z", GYearMonth { year: -500, month: 12, timezone: tz(0) })]
+    #[case::offset("2024-02-05:00", GYearMonth { year: 2024, month: 2, timezone: tz(-300) })]
+    fn g_year_month(#[case] input: &str, #[case] expected: GYearMonth) {
+        let actual: GYearMonth = input.parse().unwrap();
+        assert_eq!(actual, expected);
+        assert_eq!(actual.to_string(), input);
+    }
+
+    #[rstest]
+    #[case::plain("--02", GMonth { month: 2, timezone: None })]
+    #[case::offset("--12-05:00", GMonth { month: 12, timezone: tz(-300) })]
+    fn g_month(#[case] input: &str, #[case] expected: GMonth) {
+        let actual: GMonth = input.parse().unwrap();
+        assert_eq!(actual, expected);
+        assert_eq!(actual.to_string(), input);
+    }
+
+    #[rstest]
+    #[case::plain("---01", GDay { day: 1, timezone: None })]
+    #[case::utc("---31Z", GDay { day: 31, timezone: tz(0) })]
+    fn g_day(#[case] input: &str, #[case] expected: GDay) {
+        let actual: GDay = input.parse().unwrap();
+        assert_eq!(actual, expected);
+        assert_eq!(actual.to_string(), input);
+    }
+
+    #[rstest]
+    #[case::short_year("204", ParseGregorianError::InvalidYear)]
+    #[case::leading_zero("02024", ParseGregorianError::InvalidYear)]
+    #[case::timezone_out_of_range("2024+14:30", ParseGregorianError::InvalidTimezone)]
+    #[case::timezone_minutes("2024+01:60", ParseGregorianError::InvalidTimezone)]
+    #[case::timezone_format("2024+0100", ParseGregorianError::InvalidTimezone)]
+    fn g_year_errors(#[case] input: &str, #[case] expected: ParseGregorianError) {
+        assert_eq!(input.parse::<GYear>().unwrap_err(), expected);
+    }
+
+    #[rstest]
+    #[case::month_zero("2024-00", ParseGregorianError::InvalidMonth)]
+    #[case::month_13("2024-13", ParseGregorianError::InvalidMonth)]
+    #[case::no_month("2024", ParseGregorianError::InvalidFormat("YYYY-MM"))]
+    fn g_year_month_errors(#[case] input: &str, #[case] expected: ParseGregorianError) {
+        assert_eq!(input.parse::<GYearMonth>().unwrap_err(), expected);
+    }
+
+    #[rstest]
+    #[case::day_zero("---00", ParseGregorianError::InvalidDay)]
+    #[case::day_32("---32", ParseGregorianError::InvalidDay)]
+    #[case::month_prefix("--15", ParseGregorianError::InvalidFormat("---DD"))]
+    fn g_day_errors(#[case] input: &str, #[case] expected: ParseGregorianError) {
+        assert_eq!(input.parse::<GDay>().unwrap_err(), expected);
+    }
+
+    #[test]
+    fn deserialize_from_text() {
+        let value = crate::value::XmlText::new("2024-02Z");
+
+        let actual = GYearMonth::deserialize(&value).unwrap();
+
+        assert_eq!(
+            actual,
+            GYearMonth {
+                year: 2024,
+                month: 2,
+                timezone: tz(0)
+            }
+        );
+    }
+}
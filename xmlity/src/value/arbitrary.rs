@@ -0,0 +1,238 @@
+//! [`Arbitrary`] implementations for values, enabled with the `arbitrary` feature.
+//!
+//! Generated values are always well-formed: names are valid and not reserved, attributes of an element have distinct names, and comments and processing instructions don't contain the sequences that would end them. Names and namespaces are drawn from small sets so that generated documents often reuse them, which exercises how backends resolve prefixes. Text favours characters that need escaping.
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::{escape::is_xml_char, ExpandedNameBuf, LocalNameBuf, PrefixBuf, XmlNamespaceBuf};
+
+use super::{
+    XmlAttribute, XmlCData, XmlChild, XmlComment, XmlDecl, XmlDoctype, XmlElement,
+    XmlProcessingInstruction, XmlSeq, XmlText, XmlValue, XmlnsDeclaration,
+};
+
+/// How deeply elements and sequences are nested at most.
+const MAX_DEPTH: usize = 4;
+
+const NAME_START_CHARS: &[char] = &['a', 'b', 'c', 'n', 'x', 'A', '_', 'é'];
+const NAME_CHARS: &[char] = &['a', 'b', '1', '-', '.', '_', 'é'];
+const NAMESPACES: &[&str] = &[
+    "http://example.com/a",
+    "http://example.com/b",
+    "urn:example:c",
+];
+const SPECIAL_CHARS: &[char] = &[
+    '<', '>', '&', '"', '\'', ']', ' ', '\n', '\t', ';', '#', '?', '-',
+];
+const DOCTYPES: &[&str] = &[
+    "root",
+    r#"root SYSTEM "root.dtd""#,
+    r#"root PUBLIC "-//Example//DTD Root//EN" "root.dtd""#,
+    "root [ <!ELEMENT root ANY> ]",
+    r#"root [ <![INCLUDE[ <!ENTITY e "]>"> ]]> <![IGNORE[ <!ELEMENT b ANY> ]]> ]"#,
+];
+
+fn xml_char(u: &mut Unstructured<'_>) -> Result<char> {
+    if u.ratio(1u8, 2)? {
+        return u.choose(SPECIAL_CHARS).copied();
+    }
+
+    let c = char::arbitrary(u)?;
+    // Carriage returns are left out, since parsers normalize them away.
+    Ok(if is_xml_char(c) && c != '\r' { c } else { ' ' })
+}
+
+/// Generates text that does not contain any of the characters in `excluded`.
+fn xml_string(u: &mut Unstructured<'_>, excluded: &[char]) -> Result<String> {
+    let len = u.int_in_range(0..=16)?;
+    (0..len)
+        .map(|_| {
+            let c = xml_char(u)?;
+            Ok(if excluded.contains(&c) { '_' } else { c })
+        })
+        .collect()
+}
+
+/// Generates a name that does not start with the reserved `xml`.
+fn name(u: &mut Unstructured<'_>) -> Result<String> {
+    let mut name = String::new();
+    name.push(*u.choose(NAME_START_CHARS)?);
+    for _ in 0..u.int_in_range(0..=3)? {
+        name.push(*u.choose(NAME_CHARS)?);
+    }
+
+    if name.to_ascii_lowercase().starts_with("xml") {
+        name.insert(0, '_');
+    }
+    Ok(name)
+}
+
+impl<'a> Arbitrary<'a> for LocalNameBuf {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(LocalNameBuf::new(name(u)?).expect("generated names are valid"))
+    }
+}
+
+impl<'a> Arbitrary<'a> for PrefixBuf {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(PrefixBuf::new(name(u)?).expect("generated names are valid"))
+    }
+}
+
+impl<'a> Arbitrary<'a> for XmlNamespaceBuf {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let namespace = u.choose(NAMESPACES)?;
+        Ok(XmlNamespaceBuf::new(namespace.to_string()).expect("namespaces are valid"))
+    }
+}
+
+impl<'a> Arbitrary<'a> for ExpandedNameBuf {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(ExpandedNameBuf::new(u.arbitrary()?, u.arbitrary()?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for XmlText {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(XmlText::new(xml_string(u, &[])?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for XmlCData {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(XmlCData::new(xml_string(u, &[])?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for XmlComment {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(XmlComment::new(xml_string(u, &['-'])?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for XmlProcessingInstruction {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(XmlProcessingInstruction::new(
+            name(u)?,
+            xml_string(u, &['?'])?,
+        ))
+    }
+}
+
+impl<'a> Arbitrary<'a> for XmlDecl {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(XmlDecl::new(
+            "1.0",
+            *u.choose(&[None, Some("UTF-8")])?,
+            *u.choose(&[None, Some("yes"), Some("no")])?,
+        ))
+    }
+}
+
+impl<'a> Arbitrary<'a> for XmlDoctype {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(XmlDoctype::new(*u.choose(DOCTYPES)?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for XmlnsDeclaration {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(XmlnsDeclaration::new(u.arbitrary()?, u.arbitrary()?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for XmlAttribute {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(XmlAttribute::new(
+            u.arbitrary::<ExpandedNameBuf>()?,
+            u.arbitrary::<XmlText>()?,
+        ))
+    }
+}
+
+fn element(u: &mut Unstructured<'_>, depth: usize) -> Result<XmlElement> {
+    let mut element = XmlElement::new(u.arbitrary::<ExpandedNameBuf>()?);
+    element.preferred_prefix = u.arbitrary()?;
+
+    for _ in 0..u.int_in_range(0..=2)? {
+        let declaration: XmlnsDeclaration = u.arbitrary()?;
+        if element
+            .declarations
+            .iter()
+            .all(|existing| existing.prefix != declaration.prefix)
+        {
+            element.declarations.push(declaration);
+        }
+    }
+
+    for _ in 0..u.int_in_range(0..=3)? {
+        let attribute: XmlAttribute = u.arbitrary()?;
+        if element
+            .attributes
+            .iter()
+            .all(|existing| existing.name != attribute.name)
+        {
+            element.attributes.push_back(attribute);
+        }
+    }
+
+    if depth > 0 {
+        for _ in 0..u.int_in_range(0..=4)? {
+            element.children.values.push_back(child(u, depth - 1)?);
+        }
+    }
+
+    Ok(element)
+}
+
+fn child(u: &mut Unstructured<'_>, depth: usize) -> Result<XmlChild> {
+    Ok(match u.int_in_range(0..=5)? {
+        0 => XmlChild::Text(u.arbitrary()?),
+        1 => XmlChild::CData(u.arbitrary()?),
+        2 => XmlChild::Element(element(u, depth)?),
+        3 => XmlChild::PI(u.arbitrary()?),
+        4 => XmlChild::Comment(u.arbitrary()?),
+        _ => XmlChild::None,
+    })
+}
+
+fn value(u: &mut Unstructured<'_>, depth: usize) -> Result<XmlValue> {
+    Ok(match u.int_in_range(0..=8)? {
+        0 => XmlValue::Text(u.arbitrary()?),
+        1 => XmlValue::CData(u.arbitrary()?),
+        2 => XmlValue::Element(element(u, depth)?),
+        3 if depth > 0 => XmlValue::Seq(
+            (0..u.int_in_range(0..=4)?)
+                .map(|_| value(u, depth - 1))
+                .collect::<Result<_>>()?,
+        ),
+        4 => XmlValue::PI(u.arbitrary()?),
+        5 => XmlValue::Decl(u.arbitrary()?),
+        6 => XmlValue::Comment(u.arbitrary()?),
+        7 => XmlValue::Doctype(u.arbitrary()?),
+        _ => XmlValue::None,
+    })
+}
+
+impl<'a> Arbitrary<'a> for XmlElement {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        element(u, MAX_DEPTH)
+    }
+}
+
+impl<'a> Arbitrary<'a> for XmlChild {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        child(u, MAX_DEPTH)
+    }
+}
+
+impl<'a> Arbitrary<'a> for XmlValue {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        value(u, MAX_DEPTH)
+    }
+}
+
+impl<'a, T: Arbitrary<'a>> Arbitrary<'a> for XmlSeq<T> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.arbitrary_iter()?.collect()
+    }
+}
@@ -0,0 +1,559 @@
+//! Arena-allocated XML values, available with the `bumpalo` feature.
+//!
+//! [`XmlValueIn`] mirrors [`XmlValue`], but allocates its nodes, names and text in a [`Bump`] arena instead of on the heap. Parsing a document into it costs far fewer allocations, and the whole tree is freed at once when the arena is reset or dropped, which suits workloads that parse a document, inspect it and throw it away.
+//!
+//! ```
+//! # use xmlity::value::{bump::{Bump, XmlValueIn}, XmlValue, XmlElement};
+//! # use xmlity::LocalNameBuf;
+//! # use std::str::FromStr;
+//! let value = XmlValue::from(XmlElement::new(LocalNameBuf::from_str("root").unwrap()));
+//!
+//! let bump = Bump::new();
+//! let in_arena = XmlValueIn::deserialize_in(&value, &bump).unwrap();
+//!
+//! let XmlValueIn::Element(element) = &in_arena else { unreachable!() };
+//! assert_eq!(element.name.local_name().as_str(), "root");
+//! ```
+use core::{fmt, marker::PhantomData};
+use std::borrow::Cow;
+
+use bumpalo::collections::Vec;
+
+/// The arena type used by this module, re-exported so that it always matches the version used by `xmlity`.
+pub use bumpalo::Bump;
+
+use crate::{
+    de::{self, DeserializeSeed, SeqAccess as _, Visitor},
+    ser::{
+        IncludePrefix, SerializeAttributeAccess as _, SerializeAttributes as _,
+        SerializeElement as _, SerializeElementAttributes as _, SerializeSeq as _,
+    },
+    AttributeSerializer, Deserialize, Deserializer, ExpandedName, LocalName, Prefix, Serialize,
+    SerializeAttribute, Serializer, XmlNamespace,
+};
+
+#[cfg(doc)]
+use super::XmlValue;
+
+/// An arena-allocated counterpart to [`XmlValue`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum XmlValueIn<'bump> {
+    /// A text node.
+    Text(&'bump str),
+    /// A CDATA section.
+    CData(&'bump str),
+    /// An element.
+    Element(XmlElementIn<'bump>),
+    /// A sequence of XML values.
+    Seq(Vec<'bump, XmlValueIn<'bump>>),
+    /// A processing instruction.
+    PI(XmlProcessingInstructionIn<'bump>),
+    /// A declaration.
+    Decl(XmlDeclIn<'bump>),
+    /// A comment.
+    Comment(&'bump [u8]),
+    /// A doctype.
+    Doctype(&'bump [u8]),
+    /// Nothing.
+    None,
+}
+
+impl<'bump> XmlValueIn<'bump> {
+    /// Deserializes all remaining nodes of `deserializer` into `bump`.
+    ///
+    /// Like [`XmlValue`], a single node is returned as is and multiple nodes are returned as [`XmlValueIn::Seq`].
+    pub fn deserialize_in<'de, D: Deserializer<'de>>(
+        deserializer: D,
+        bump: &'bump Bump,
+    ) -> Result<Self, D::Error> {
+        deserializer
+            .deserialize_seq(SeqVisitor { bump })
+            .map(|Allocated(value)| value)
+    }
+}
+
+/// An arena-allocated counterpart to [`XmlChild`](super::XmlChild).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum XmlChildIn<'bump> {
+    /// A text node.
+    Text(&'bump str),
+    /// A CDATA section.
+    CData(&'bump str),
+    /// An element.
+    Element(XmlElementIn<'bump>),
+    /// A processing instruction.
+    PI(XmlProcessingInstructionIn<'bump>),
+    /// A comment.
+    Comment(&'bump [u8]),
+}
+
+/// An arena-allocated counterpart to [`XmlElement`](super::XmlElement).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct XmlElementIn<'bump> {
+    /// The name of the element.
+    pub name: ExpandedName<'bump>,
+    /// The attributes of the element.
+    pub attributes: Vec<'bump, XmlAttributeIn<'bump>>,
+    /// The children of the element.
+    pub children: Vec<'bump, XmlChildIn<'bump>>,
+    /// The prefix the element was written with, if it was recorded by the deserializer.
+    pub original_prefix: Option<&'bump Prefix>,
+}
+
+/// An arena-allocated counterpart to [`XmlAttribute`](super::XmlAttribute).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct XmlAttributeIn<'bump> {
+    /// The name of the attribute.
+    pub name: ExpandedName<'bump>,
+    /// The value of the attribute.
+    pub value: &'bump str,
+    /// The prefix the attribute was written with, if it was recorded by the deserializer.
+    pub original_prefix: Option<&'bump Prefix>,
+}
+
+/// An arena-allocated counterpart to [`XmlProcessingInstruction`](super::XmlProcessingInstruction).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct XmlProcessingInstructionIn<'bump> {
+    /// The target of the processing instruction.
+    pub target: &'bump [u8],
+    /// The content of the processing instruction.
+    pub content: &'bump [u8],
+}
+
+/// An arena-allocated counterpart to [`XmlDecl`](super::XmlDecl).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct XmlDeclIn<'bump> {
+    /// The version of the XML document.
+    pub version: &'bump str,
+    /// The encoding of the XML document.
+    pub encoding: Option<&'bump str>,
+    /// The standalone status of the XML document.
+    pub standalone: Option<&'bump str>,
+}
+
+// Allocation helpers
+
+fn alloc_name<'bump>(bump: &'bump Bump, name: ExpandedName<'_>) -> ExpandedName<'bump> {
+    let (local_name, namespace) = name.into_parts();
+    // SAFETY: The copy is identical to a local name that is already valid.
+    let local_name = unsafe { LocalName::new_unchecked(bump.alloc_str(local_name.as_str())) };
+    // SAFETY: The copy is identical to a namespace that is already valid.
+    let namespace = namespace.map(|namespace| unsafe {
+        XmlNamespace::new_unchecked(bump.alloc_str(namespace.as_str()))
+    });
+    ExpandedName::new(local_name, namespace)
+}
+
+fn alloc_prefix<'bump>(bump: &'bump Bump, prefix: &Prefix) -> &'bump Prefix {
+    // SAFETY: The copy is identical to a prefix that is already valid.
+    unsafe { Prefix::new_unchecked(bump.alloc_str(prefix.as_str())) }
+}
+
+fn alloc_utf8<'bump, E: de::Error>(bump: &'bump Bump, bytes: &[u8]) -> Result<&'bump str, E> {
+    std::str::from_utf8(bytes)
+        .map(|value| &*bump.alloc_str(value))
+        .map_err(|_| E::invalid_string())
+}
+
+// Deserialization
+//
+// Visitors have to produce a type implementing `Deserialize`, which arena-allocated values can not do since they need the arena. They are wrapped in `Allocated` instead, and only ever created through the seeds below.
+
+struct Allocated<T>(T);
+
+impl<'de, T> Deserialize<'de> for Allocated<T> {
+    fn deserialize<D: Deserializer<'de>>(_reader: D) -> Result<Self, D::Error> {
+        Err(de::Error::custom(
+            "arena-allocated values can only be deserialized with an arena",
+        ))
+    }
+}
+
+/// The owned or borrowed text of an attribute value.
+struct AttributeText<'de>(Cow<'de, str>);
+
+impl<'de> Deserialize<'de> for AttributeText<'de> {
+    fn deserialize<D: Deserializer<'de>>(reader: D) -> Result<Self, D::Error> {
+        struct __Visitor<'v>(PhantomData<&'v ()>);
+
+        impl<'v> Visitor<'v> for __Visitor<'v> {
+            type Value = AttributeText<'v>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an attribute value")
+            }
+
+            fn visit_text<E, V>(self, value: V) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+                V: de::XmlText<'v>,
+            {
+                Ok(AttributeText(value.into_string()))
+            }
+        }
+
+        reader.deserialize_any(__Visitor(PhantomData))
+    }
+}
+
+struct SeqVisitor<'bump> {
+    bump: &'bump Bump,
+}
+
+impl<'de, 'bump> Visitor<'de> for SeqVisitor<'bump> {
+    type Value = Allocated<XmlValueIn<'bump>>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence of XML values")
+    }
+
+    fn visit_seq<S>(self, mut sequence: S) -> Result<Self::Value, S::Error>
+    where
+        S: de::SeqAccess<'de>,
+    {
+        let mut values = Vec::new_in(self.bump);
+        while let Some(Allocated(value)) =
+            sequence.next_element_seq_seed(ValueSeed { bump: self.bump })?
+        {
+            values.push(value);
+        }
+
+        Ok(Allocated(match values.len() {
+            0 => XmlValueIn::None,
+            1 => values.pop().expect("Just checked."),
+            _ => XmlValueIn::Seq(values),
+        }))
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Allocated(XmlValueIn::None))
+    }
+}
+
+#[derive(Clone, Copy)]
+struct ValueSeed<'bump> {
+    bump: &'bump Bump,
+}
+
+impl<'de, 'bump> DeserializeSeed<'de> for ValueSeed<'bump> {
+    type Value = Allocated<XmlValueIn<'bump>>;
+
+    fn deserialize<D: Deserializer<'de>>(self, reader: D) -> Result<Self::Value, D::Error> {
+        reader.deserialize_any(self)
+    }
+}
+
+impl<'de, 'bump> Visitor<'de> for ValueSeed<'bump> {
+    type Value = Allocated<XmlValueIn<'bump>>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an XML value")
+    }
+
+    fn visit_text<E, V>(self, value: V) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+        V: de::XmlText<'de>,
+    {
+        Ok(Allocated(XmlValueIn::Text(
+            self.bump.alloc_str(value.as_str()),
+        )))
+    }
+
+    fn visit_cdata<E, V>(self, value: V) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+        V: de::XmlCData<'de>,
+    {
+        Ok(Allocated(XmlValueIn::CData(
+            self.bump.alloc_str(value.as_str()),
+        )))
+    }
+
+    fn visit_element<A>(self, element: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::ElementAccess<'de>,
+    {
+        visit_element_in(self.bump, element).map(|element| Allocated(XmlValueIn::Element(element)))
+    }
+
+    fn visit_pi<E, V>(self, pi: V) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+        V: de::XmlProcessingInstruction,
+    {
+        Ok(Allocated(XmlValueIn::PI(XmlProcessingInstructionIn {
+            target: self.bump.alloc_slice_copy(pi.target()),
+            content: self.bump.alloc_slice_copy(pi.content()),
+        })))
+    }
+
+    fn visit_decl<E, V>(self, declaration: V) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+        V: de::XmlDeclaration,
+    {
+        Ok(Allocated(XmlValueIn::Decl(XmlDeclIn {
+            version: alloc_utf8(self.bump, declaration.version())?,
+            encoding: declaration
+                .encoding()
+                .map(|encoding| alloc_utf8(self.bump, encoding))
+                .transpose()?,
+            standalone: declaration
+                .standalone()
+                .map(|standalone| alloc_utf8(self.bump, standalone))
+                .transpose()?,
+        })))
+    }
+
+    fn visit_comment<E, V>(self, comment: V) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+        V: de::XmlComment<'de>,
+    {
+        Ok(Allocated(XmlValueIn::Comment(
+            self.bump.alloc_slice_copy(comment.as_bytes()),
+        )))
+    }
+
+    fn visit_doctype<E, V>(self, doctype: V) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+        V: de::XmlDoctype<'de>,
+    {
+        Ok(Allocated(XmlValueIn::Doctype(
+            self.bump.alloc_slice_copy(doctype.as_bytes()),
+        )))
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Allocated(XmlValueIn::None))
+    }
+}
+
+#[derive(Clone, Copy)]
+struct ChildSeed<'bump> {
+    bump: &'bump Bump,
+}
+
+impl<'de, 'bump> DeserializeSeed<'de> for ChildSeed<'bump> {
+    type Value = Allocated<XmlChildIn<'bump>>;
+
+    fn deserialize<D: Deserializer<'de>>(self, reader: D) -> Result<Self::Value, D::Error> {
+        reader.deserialize_any(self)
+    }
+}
+
+impl<'de, 'bump> Visitor<'de> for ChildSeed<'bump> {
+    type Value = Allocated<XmlChildIn<'bump>>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an XML child")
+    }
+
+    fn visit_text<E, V>(self, value: V) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+        V: de::XmlText<'de>,
+    {
+        Ok(Allocated(XmlChildIn::Text(
+            self.bump.alloc_str(value.as_str()),
+        )))
+    }
+
+    fn visit_cdata<E, V>(self, value: V) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+        V: de::XmlCData<'de>,
+    {
+        Ok(Allocated(XmlChildIn::CData(
+            self.bump.alloc_str(value.as_str()),
+        )))
+    }
+
+    fn visit_element<A>(self, element: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::ElementAccess<'de>,
+    {
+        visit_element_in(self.bump, element).map(|element| Allocated(XmlChildIn::Element(element)))
+    }
+
+    fn visit_pi<E, V>(self, pi: V) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+        V: de::XmlProcessingInstruction,
+    {
+        Ok(Allocated(XmlChildIn::PI(XmlProcessingInstructionIn {
+            target: self.bump.alloc_slice_copy(pi.target()),
+            content: self.bump.alloc_slice_copy(pi.content()),
+        })))
+    }
+
+    fn visit_comment<E, V>(self, comment: V) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+        V: de::XmlComment<'de>,
+    {
+        Ok(Allocated(XmlChildIn::Comment(
+            self.bump.alloc_slice_copy(comment.as_bytes()),
+        )))
+    }
+}
+
+#[derive(Clone, Copy)]
+struct AttributeSeed<'bump> {
+    bump: &'bump Bump,
+}
+
+impl<'de, 'bump> DeserializeSeed<'de> for AttributeSeed<'bump> {
+    type Value = Allocated<XmlAttributeIn<'bump>>;
+
+    fn deserialize<D: Deserializer<'de>>(self, reader: D) -> Result<Self::Value, D::Error> {
+        reader.deserialize_any(self)
+    }
+}
+
+impl<'de, 'bump> Visitor<'de> for AttributeSeed<'bump> {
+    type Value = Allocated<XmlAttributeIn<'bump>>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an attribute")
+    }
+
+    fn visit_attribute<A>(self, attribute: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::AttributeAccess<'de>,
+    {
+        let name = alloc_name(self.bump, attribute.name());
+        let original_prefix = attribute
+            .prefix()
+            .map(|prefix| alloc_prefix(self.bump, prefix));
+        let AttributeText(value) = attribute.value::<AttributeText<'de>>()?;
+
+        Ok(Allocated(XmlAttributeIn {
+            name,
+            value: self.bump.alloc_str(&value),
+            original_prefix,
+        }))
+    }
+}
+
+fn visit_element_in<'de, 'bump, A: de::ElementAccess<'de>>(
+    bump: &'bump Bump,
+    mut element: A,
+) -> Result<XmlElementIn<'bump>, A::Error> {
+    let name = alloc_name(bump, element.name());
+    let original_prefix = element.prefix().map(|prefix| alloc_prefix(bump, prefix));
+
+    let mut attributes = Vec::new_in(bump);
+    while let Some(Allocated(attribute)) = element.next_attribute_seed(AttributeSeed { bump })? {
+        attributes.push(attribute);
+    }
+
+    let mut children_access = element.children()?;
+    let mut children = Vec::new_in(bump);
+    while let Some(Allocated(child)) = children_access.next_element_seed(ChildSeed { bump })? {
+        children.push(child);
+    }
+
+    Ok(XmlElementIn {
+        name,
+        attributes,
+        children,
+        original_prefix,
+    })
+}
+
+// Serialization
+
+impl Serialize for XmlValueIn<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            XmlValueIn::Text(text) => serializer.serialize_text(text),
+            XmlValueIn::CData(cdata) => serializer.serialize_cdata(cdata),
+            XmlValueIn::Element(element) => element.serialize(serializer),
+            XmlValueIn::Seq(values) => {
+                let mut seq = serializer.serialize_seq()?;
+                for value in values.iter() {
+                    seq.serialize_element(value)?;
+                }
+                seq.end()
+            }
+            XmlValueIn::PI(pi) => pi.serialize(serializer),
+            XmlValueIn::Decl(decl) => {
+                serializer.serialize_decl(decl.version, decl.encoding, decl.standalone)
+            }
+            XmlValueIn::Comment(comment) => serializer.serialize_comment(comment),
+            XmlValueIn::Doctype(doctype) => serializer.serialize_doctype(doctype),
+            XmlValueIn::None => serializer.serialize_none(),
+        }
+    }
+}
+
+impl Serialize for XmlChildIn<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            XmlChildIn::Text(text) => serializer.serialize_text(text),
+            XmlChildIn::CData(cdata) => serializer.serialize_cdata(cdata),
+            XmlChildIn::Element(element) => element.serialize(serializer),
+            XmlChildIn::PI(pi) => pi.serialize(serializer),
+            XmlChildIn::Comment(comment) => serializer.serialize_comment(comment),
+        }
+    }
+}
+
+impl Serialize for XmlElementIn<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut element = serializer.serialize_element(&self.name)?;
+        if let Some(original_prefix) = self.original_prefix {
+            element.preferred_prefix(Some(original_prefix))?;
+            element.include_prefix(IncludePrefix::WhenNecessaryForPreferredPrefix)?;
+        }
+
+        let mut attributes = element.serialize_attributes()?;
+        for attribute in self.attributes.iter() {
+            attributes.serialize_attribute(attribute)?;
+        }
+
+        if self.children.is_empty() {
+            return attributes.end();
+        }
+
+        let mut children = attributes.serialize_children()?;
+        for child in self.children.iter() {
+            children.serialize_element(child)?;
+        }
+        children.end()
+    }
+}
+
+impl SerializeAttribute for XmlAttributeIn<'_> {
+    fn serialize_attribute<S: AttributeSerializer>(
+        &self,
+        mut serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut attribute = serializer.serialize_attribute(&self.name)?;
+        if let Some(original_prefix) = self.original_prefix {
+            attribute.preferred_prefix(Some(original_prefix))?;
+            attribute.include_prefix(IncludePrefix::WhenNecessaryForPreferredPrefix)?;
+        }
+
+        attribute.end(&self.value)
+    }
+}
+
+impl Serialize for XmlProcessingInstructionIn<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_pi(self.target, self.content)
+    }
+}
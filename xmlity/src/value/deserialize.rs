@@ -360,6 +360,12 @@ impl<'v> crate::de::Visitor<'v> for XmlElementVisitor<'v> {
         A: de::ElementAccess<'v>,
     {
         let name = element.name().into_owned();
+        let original_prefix = element.prefix().map(Prefix::to_owned);
+        let declarations = element
+            .namespace_declarations()
+            .into_iter()
+            .map(XmlnsDeclaration::from)
+            .collect();
         let attributes = iter::from_fn(|| match element.next_attribute::<XmlAttribute>() {
             Ok(Some(attr)) => Some(Ok(attr)),
             Ok(None) => None,
@@ -381,6 +387,8 @@ impl<'v> crate::de::Visitor<'v> for XmlElementVisitor<'v> {
             children,
             preferred_prefix: None,
             enforce_prefix: crate::ser::IncludePrefix::Never,
+            original_prefix,
+            declarations,
         })
     }
 }
@@ -430,6 +438,7 @@ impl<'v> crate::de::Visitor<'v> for XmlAttributeVisitor<'v> {
     {
         Ok(XmlAttribute {
             name: attribute.name().into_owned(),
+            original_prefix: attribute.prefix().map(Prefix::to_owned),
             value: attribute.value()?,
         })
     }
@@ -442,6 +451,10 @@ impl<'a> de::AttributeAccess<'a> for &'a XmlAttribute {
         self.name.as_ref()
     }
 
+    fn prefix(&self) -> Option<&Prefix> {
+        self.original_prefix.as_deref()
+    }
+
     fn value<T>(self) -> Result<T, Self::Error>
     where
         T: Deserialize<'a>,
@@ -1,10 +1,12 @@
 use crate::{
-    de::{self, AttributesAccess, ElementAccess, Visitor},
-    Deserialize, Deserializer, ExpandedName,
+    de::{self, AttributesAccess, DeserializeSeed, ElementAccess, PeekedNode, Visitor},
+    Deserializer, ExpandedName, ExpandedNameBuf, Prefix,
 };
 
 use super::*;
 
+use std::{cell::Cell, rc::Rc};
+
 impl<'de> Deserializer<'de> for &'de XmlValue {
     type Error = XmlValueDeserializerError;
 
@@ -21,6 +23,8 @@ impl<'de> Deserializer<'de> for &'de XmlValue {
             XmlValue::Decl(xml_decl) => xml_decl.deserialize_any(visitor),
             XmlValue::Comment(xml_comment) => xml_comment.deserialize_any(visitor),
             XmlValue::Doctype(xml_doctype) => xml_doctype.deserialize_any(visitor),
+            #[cfg(feature = "recovery")]
+            XmlValue::Error(xml_error) => Err(xml_error.into()),
             XmlValue::None => visitor.visit_none(),
         }
     }
@@ -38,6 +42,8 @@ impl<'de> Deserializer<'de> for &'de XmlValue {
             XmlValue::Decl(xml_decl) => xml_decl.deserialize_seq(visitor),
             XmlValue::Comment(xml_comment) => xml_comment.deserialize_seq(visitor),
             XmlValue::Doctype(xml_doctype) => xml_doctype.deserialize_seq(visitor),
+            #[cfg(feature = "recovery")]
+            XmlValue::Error(xml_error) => Err(xml_error.into()),
             XmlValue::None => visitor.visit_none(),
         }
     }
@@ -138,6 +144,8 @@ impl<'de> Deserializer<'de> for &'de XmlChild {
             XmlChild::Element(xml_element) => xml_element.deserialize_any(visitor),
             XmlChild::PI(xml_pi) => xml_pi.deserialize_any(visitor),
             XmlChild::Comment(xml_comment) => xml_comment.deserialize_any(visitor),
+            #[cfg(feature = "recovery")]
+            XmlChild::Error(xml_error) => Err(xml_error.into()),
             XmlChild::None => visitor.visit_none(),
         }
     }
@@ -152,6 +160,8 @@ impl<'de> Deserializer<'de> for &'de XmlChild {
             XmlChild::Element(xml_element) => xml_element.deserialize_seq(visitor),
             XmlChild::PI(xml_pi) => xml_pi.deserialize_seq(visitor),
             XmlChild::Comment(xml_comment) => xml_comment.deserialize_seq(visitor),
+            #[cfg(feature = "recovery")]
+            XmlChild::Error(xml_error) => Err(xml_error.into()),
             XmlChild::None => visitor.visit_none(),
         }
     }
@@ -163,11 +173,11 @@ impl<'de> Deserializer<'de> for &'de XmlElement {
     where
         V: Visitor<'de>,
     {
-        visitor.visit_element(XmlElementAccess {
-            element: self,
-            attribute_index: 0,
-            write_attribute_index_to: None,
-        })
+        ScopedNode {
+            node: self,
+            parent: None,
+        }
+        .deserialize_any(visitor)
     }
 
     fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
@@ -185,14 +195,28 @@ impl<'de> crate::de::Deserializer<'de> for &'de XmlAttribute {
     where
         V: Visitor<'de>,
     {
-        visitor.visit_attribute(self)
+        visitor.visit_attribute(self).map_err(|err| {
+            err.within(
+                XmlValuePathSegment::Attribute {
+                    name: self.name.clone(),
+                },
+                None,
+            )
+        })
     }
 
     fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        visitor.visit_attribute(self)
+        visitor.visit_attribute(self).map_err(|err| {
+            err.within(
+                XmlValuePathSegment::Attribute {
+                    name: self.name.clone(),
+                },
+                None,
+            )
+        })
     }
 }
 
@@ -332,12 +356,205 @@ impl<'de> Deserializer<'de> for &'de XmlDoctype {
     }
 }
 
+// Ancestors
+
+/// An element whose children are being deserialized, linked to the element enclosing it, so that the attributes of enclosing elements can be looked up.
+#[derive(Debug)]
+struct Ancestor<'de> {
+    element: &'de XmlElement,
+    parent: Option<Rc<Ancestor<'de>>>,
+}
+
+/// A node that is deserialized inside of the elements enclosing it.
+#[derive(Debug, Clone)]
+struct ScopedNode<'de, T> {
+    node: &'de T,
+    parent: Option<Rc<Ancestor<'de>>>,
+}
+
+impl<'de> Deserializer<'de> for ScopedNode<'de, XmlElement> {
+    type Error = XmlValueDeserializerError;
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let child_error = ChildErrorSlot::default();
+        visitor
+            .visit_element(XmlElementAccess {
+                element: self.node,
+                parent: self.parent,
+                attribute_index: 0,
+                write_attribute_index_to: None,
+                child_error: child_error.clone(),
+            })
+            .map_err(|err| {
+                err.within(
+                    XmlValuePathSegment::Element {
+                        name: self.node.name.clone(),
+                        position: None,
+                    },
+                    child_error.take(),
+                )
+            })
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_seq(Some(self))
+    }
+}
+
+impl<'de> Deserializer<'de> for ScopedNode<'de, XmlChild> {
+    type Error = XmlValueDeserializerError;
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.node {
+            XmlChild::Element(node) => ScopedNode {
+                node,
+                parent: self.parent,
+            }
+            .deserialize_any(visitor),
+            node => node.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.node {
+            XmlChild::Element(node) => ScopedNode {
+                node,
+                parent: self.parent,
+            }
+            .deserialize_seq(visitor),
+            node => node.deserialize_seq(visitor),
+        }
+    }
+}
+
+impl<'de> Deserializer<'de> for ScopedNode<'de, XmlValue> {
+    type Error = XmlValueDeserializerError;
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.node {
+            XmlValue::Element(node) => ScopedNode {
+                node,
+                parent: self.parent,
+            }
+            .deserialize_any(visitor),
+            XmlValue::Seq(seq) => XmlSeqAccess::new(seq)
+                .within(self.parent)
+                .deserialize_any(visitor),
+            node => node.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.node {
+            XmlValue::Element(node) => ScopedNode {
+                node,
+                parent: self.parent,
+            }
+            .deserialize_seq(visitor),
+            XmlValue::Seq(seq) => XmlSeqAccess::new(seq)
+                .within(self.parent)
+                .deserialize_seq(visitor),
+            node => node.deserialize_seq(visitor),
+        }
+    }
+}
+
+/// An element deserialized as a sequence of itself, like [`Option<&XmlElement>`] but keeping the enclosing elements.
+impl<'de> de::SeqAccess<'de> for Option<ScopedNode<'de, XmlElement>> {
+    type Error = XmlValueDeserializerError;
+    type SubAccess<'g>
+        = Self
+    where
+        Self: 'g;
+
+    fn next_element_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        let Some(element) = self.take() else {
+            return Ok(None);
+        };
+
+        match seed.deserialize(element.clone()) {
+            Ok(value) => Ok(Some(value)),
+            Err(_) => {
+                *self = Some(element);
+                Ok(None)
+            }
+        }
+    }
+
+    fn next_element_seq_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        let Some(element) = self.take() else {
+            return Ok(None);
+        };
+
+        match seed.deserialize_seq(element.clone()) {
+            Ok(value) => Ok(Some(value)),
+            Err(_) => {
+                *self = Some(element);
+                Ok(None)
+            }
+        }
+    }
+
+    fn sub_access(&mut self) -> Result<Self::SubAccess<'_>, Self::Error> {
+        Ok(self.clone())
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(usize::from(self.is_some()))
+    }
+
+    fn peek_element(&mut self) -> Result<Option<PeekedNode>, Self::Error> {
+        Ok(self
+            .as_ref()
+            .map(|element| PeekedNode::Element(element.node.name.clone())))
+    }
+}
+
 // Element access
 
+/// Where the attributes and children of an element record the error of the last one that failed to deserialize.
+///
+/// Types usually treat attributes and children that fail to deserialize as absent, so this is used to explain the error of the element itself.
+type ChildErrorSlot = Rc<Cell<Option<XmlValueDeserializerError>>>;
+
+/// Records an error in a [`ChildErrorSlot`] unless it only means that the node was not the one being looked for, and returns it.
+fn record_child_error(
+    slot: &ChildErrorSlot,
+    err: XmlValueDeserializerError,
+) -> XmlValueDeserializerError {
+    if !err.is_mismatch() {
+        slot.set(Some(err.clone()));
+    }
+    err
+}
+
 struct XmlElementAccess<'de, 'i> {
     element: &'de XmlElement,
+    parent: Option<Rc<Ancestor<'de>>>,
     attribute_index: usize,
     write_attribute_index_to: Option<&'i mut usize>,
+    child_error: ChildErrorSlot,
 }
 
 impl Drop for XmlElementAccess<'_, '_> {
@@ -356,25 +573,76 @@ impl<'de> AttributesAccess<'de> for XmlElementAccess<'de, '_> {
     where
         Self: 'a;
 
-    fn next_attribute<T>(&mut self) -> Result<Option<T>, Self::Error>
+    fn next_attribute_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
     where
-        T: Deserialize<'de>,
+        S: DeserializeSeed<'de>,
     {
         let Some(attribute) = self.element.attributes.get(self.attribute_index) else {
             return Ok(None);
         };
-        let attribute = T::deserialize(attribute)?;
+        let attribute = seed
+            .deserialize(attribute)
+            .map_err(|err| record_child_error(&self.child_error, err))?;
         self.attribute_index += 1;
         Ok(Some(attribute))
     }
 
+    fn peek_name(&mut self) -> Result<Option<ExpandedNameBuf>, Self::Error> {
+        Ok(self
+            .element
+            .attributes
+            .get(self.attribute_index)
+            .map(|attribute| attribute.name.clone()))
+    }
+
+    fn find_attribute_seed<S>(
+        &mut self,
+        name: ExpandedName<'_>,
+        seed: S,
+    ) -> Result<Option<S::Value>, Self::Error>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        self.element
+            .attributes
+            .iter()
+            .find(|attribute| attribute.name == name)
+            .map(|attribute| {
+                seed.deserialize(attribute)
+                    .map_err(|err| record_child_error(&self.child_error, err))
+            })
+            .transpose()
+    }
+
     fn sub_access(&mut self) -> Result<Self::SubAccess<'_>, Self::Error> {
         Ok(XmlElementAccess {
             attribute_index: self.attribute_index,
             element: self.element,
+            parent: self.parent.clone(),
             write_attribute_index_to: Some(&mut self.attribute_index),
+            child_error: self.child_error.clone(),
         })
     }
+
+    fn ancestor_attribute(
+        &self,
+        ancestor: &ExpandedName<'_>,
+        attribute: &ExpandedName<'_>,
+    ) -> Option<Cow<'_, str>> {
+        let parents = std::iter::successors(self.parent.as_deref(), |ancestor| {
+            ancestor.parent.as_deref()
+        });
+        let element = std::iter::once(self.element)
+            .chain(parents.map(|ancestor| ancestor.element))
+            .find(|element| element.name == *ancestor)?;
+        let value = &element
+            .attributes
+            .iter()
+            .find(|candidate| candidate.name == *attribute)?
+            .value;
+
+        std::str::from_utf8(&value.0).ok().map(Cow::Borrowed)
+    }
 }
 
 impl<'de> ElementAccess<'de> for XmlElementAccess<'de, '_> {
@@ -388,11 +656,28 @@ impl<'de> ElementAccess<'de> for XmlElementAccess<'de, '_> {
         self.element.name.as_ref()
     }
 
-    fn children(self) -> Result<Self::ChildrenAccess, Self::Error> {
+    fn prefix(&self) -> Option<&Prefix> {
+        self.element.original_prefix.as_deref()
+    }
+
+    fn namespace_declarations(&self) -> Vec<(PrefixBuf, XmlNamespaceBuf)> {
+        self.element
+            .declarations
+            .iter()
+            .map(|declaration| (declaration.prefix.clone(), declaration.namespace.clone()))
+            .collect()
+    }
+
+    fn children(mut self) -> Result<Self::ChildrenAccess, Self::Error> {
         Ok(XmlSeqAccess {
             seq: &self.element.children,
             index: 0,
             write_index_to: None,
+            child_error: Some(self.child_error.clone()),
+            parent: Some(Rc::new(Ancestor {
+                element: self.element,
+                parent: self.parent.take(),
+            })),
         })
     }
 
@@ -405,6 +690,9 @@ struct XmlSeqAccess<'de, 'i, T> {
     seq: &'de XmlSeq<T>,
     index: usize,
     write_index_to: Option<&'i mut usize>,
+    child_error: Option<ChildErrorSlot>,
+    /// The element the sequence is the children of, if any.
+    parent: Option<Rc<Ancestor<'de>>>,
 }
 
 impl<'de, T> XmlSeqAccess<'de, '_, T> {
@@ -413,6 +701,87 @@ impl<'de, T> XmlSeqAccess<'de, '_, T> {
             seq,
             index: 0,
             write_index_to: None,
+            child_error: None,
+            parent: None,
+        }
+    }
+
+    /// Sets the element the sequence is the children of.
+    fn within(mut self, parent: Option<Rc<Ancestor<'de>>>) -> Self {
+        self.parent = parent;
+        self
+    }
+
+    /// Returns the current node, to be deserialized inside of the element the sequence is the children of.
+    fn current(&self) -> Option<ScopedNode<'de, T>> {
+        Some(ScopedNode {
+            node: self.seq.values.get(self.index)?,
+            parent: self.parent.clone(),
+        })
+    }
+}
+
+impl<T: AsElement> XmlSeqAccess<'_, '_, T> {
+    /// Records an error of the current child as the last child error, and returns it.
+    ///
+    /// Errors that only mean that the child was not the one being looked for are returned as they are, since they happen for most children and finding the position of the child is linear in the number of its siblings.
+    fn record_child_error(&self, err: XmlValueDeserializerError) -> XmlValueDeserializerError {
+        if err.is_mismatch() {
+            return err;
+        }
+        let err = self.at_current_position(err);
+        if let Some(child_error) = &self.child_error {
+            child_error.set(Some(err.clone()));
+        }
+        err
+    }
+
+    /// Records the position of the current element among its siblings with the same name in the path of an error, if it has any such siblings.
+    fn at_current_position(&self, err: XmlValueDeserializerError) -> XmlValueDeserializerError {
+        let Some(name) = self
+            .seq
+            .values
+            .get(self.index)
+            .and_then(AsElement::as_element)
+            .map(|element| &element.name)
+        else {
+            return err;
+        };
+        let mut same_name = self
+            .seq
+            .values
+            .iter()
+            .enumerate()
+            .filter(|(_, value)| value.as_element().is_some_and(|e| e.name == *name));
+        let position = same_name
+            .by_ref()
+            .position(|(index, _)| index == self.index)
+            .expect("The current element has its own name.");
+        if position == 0 && same_name.next().is_none() {
+            return err;
+        }
+        err.at_position(position + 1)
+    }
+}
+
+trait AsElement {
+    fn as_element(&self) -> Option<&XmlElement>;
+}
+
+impl AsElement for XmlValue {
+    fn as_element(&self) -> Option<&XmlElement> {
+        match self {
+            XmlValue::Element(element) => Some(element),
+            _ => None,
+        }
+    }
+}
+
+impl AsElement for XmlChild {
+    fn as_element(&self) -> Option<&XmlElement> {
+        match self {
+            XmlChild::Element(element) => Some(element),
+            _ => None,
         }
     }
 }
@@ -434,23 +803,25 @@ impl<'de> de::SeqAccess<'de> for XmlSeqAccess<'de, '_, XmlChild> {
         = XmlSeqAccess<'de, 'g, XmlChild>
     where
         Self: 'g;
-    fn next_element<T>(&mut self) -> Result<Option<T>, Self::Error>
+    fn next_element_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
     where
-        T: Deserialize<'de>,
+        S: DeserializeSeed<'de>,
     {
-        let Some(value) = self.seq.values.get(self.index) else {
+        let Some(value) = self.current() else {
             return Ok(None);
         };
-        let value = T::deserialize(value)?;
+        let value = seed
+            .deserialize(value)
+            .map_err(|err| self.record_child_error(err))?;
         self.index += 1;
         Ok(Some(value))
     }
 
-    fn next_element_seq<T>(&mut self) -> Result<Option<T>, Self::Error>
+    fn next_element_seq_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
     where
-        T: Deserialize<'de>,
+        S: DeserializeSeed<'de>,
     {
-        T::deserialize_seq(self).map(Some)
+        seed.deserialize_seq(self).map(Some)
     }
 
     fn sub_access(&mut self) -> Result<Self::SubAccess<'_>, Self::Error> {
@@ -458,8 +829,31 @@ impl<'de> de::SeqAccess<'de> for XmlSeqAccess<'de, '_, XmlChild> {
             seq: self.seq,
             index: self.index,
             write_index_to: Some(&mut self.index),
+            child_error: self.child_error.clone(),
+            parent: self.parent.clone(),
         })
     }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.seq.values.len().saturating_sub(self.index))
+    }
+
+    fn peek_element(&mut self) -> Result<Option<PeekedNode>, Self::Error> {
+        Ok(self
+            .seq
+            .values
+            .get(self.index)
+            .and_then(|child| match child {
+                XmlChild::Text(_) => Some(PeekedNode::Text),
+                XmlChild::CData(_) => Some(PeekedNode::CData),
+                XmlChild::Element(element) => Some(PeekedNode::Element(element.name.clone())),
+                XmlChild::PI(_) => Some(PeekedNode::PI),
+                XmlChild::Comment(_) => Some(PeekedNode::Comment),
+                #[cfg(feature = "recovery")]
+                XmlChild::Error(_) => None,
+                XmlChild::None => None,
+            }))
+    }
 }
 
 impl<'de> de::SeqAccess<'de> for XmlSeqAccess<'de, '_, XmlValue> {
@@ -468,23 +862,25 @@ impl<'de> de::SeqAccess<'de> for XmlSeqAccess<'de, '_, XmlValue> {
         = XmlSeqAccess<'de, 'g, XmlValue>
     where
         Self: 'g;
-    fn next_element<T>(&mut self) -> Result<Option<T>, Self::Error>
+    fn next_element_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
     where
-        T: Deserialize<'de>,
+        S: DeserializeSeed<'de>,
     {
-        let Some(value) = self.seq.values.get(self.index) else {
+        let Some(value) = self.current() else {
             return Ok(None);
         };
-        let value = T::deserialize(value)?;
+        let value = seed
+            .deserialize(value)
+            .map_err(|err| self.record_child_error(err))?;
         self.index += 1;
         Ok(Some(value))
     }
 
-    fn next_element_seq<T>(&mut self) -> Result<Option<T>, Self::Error>
+    fn next_element_seq_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
     where
-        T: Deserialize<'de>,
+        S: DeserializeSeed<'de>,
     {
-        T::deserialize_seq(self).map(Some)
+        seed.deserialize_seq(self).map(Some)
     }
 
     fn sub_access(&mut self) -> Result<Self::SubAccess<'_>, Self::Error> {
@@ -492,8 +888,34 @@ impl<'de> de::SeqAccess<'de> for XmlSeqAccess<'de, '_, XmlValue> {
             seq: self.seq,
             index: self.index,
             write_index_to: Some(&mut self.index),
+            child_error: self.child_error.clone(),
+            parent: self.parent.clone(),
         })
     }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.seq.values.len().saturating_sub(self.index))
+    }
+
+    fn peek_element(&mut self) -> Result<Option<PeekedNode>, Self::Error> {
+        Ok(self.seq.values.get(self.index).and_then(peek_value))
+    }
+}
+
+fn peek_value(value: &XmlValue) -> Option<PeekedNode> {
+    match value {
+        XmlValue::Text(_) => Some(PeekedNode::Text),
+        XmlValue::CData(_) => Some(PeekedNode::CData),
+        XmlValue::Element(element) => Some(PeekedNode::Element(element.name.clone())),
+        XmlValue::Seq(seq) => seq.values.front().and_then(peek_value),
+        XmlValue::PI(_) => Some(PeekedNode::PI),
+        XmlValue::Decl(_) => Some(PeekedNode::Decl),
+        XmlValue::Comment(_) => Some(PeekedNode::Comment),
+        XmlValue::Doctype(_) => Some(PeekedNode::DocType),
+        #[cfg(feature = "recovery")]
+        XmlValue::Error(_) => None,
+        XmlValue::None => None,
+    }
 }
 
 impl de::XmlProcessingInstruction for &XmlProcessingInstruction {
@@ -0,0 +1,314 @@
+//! Scanning of doctype declarations. The internal subset of a doctype can contain literals, comments and conditional sections with brackets and `>` in them that don't end the declaration, so they can't be found by matching brackets alone.
+use super::XmlDoctype;
+
+/// A conditional section in the internal subset of a doctype, like `<![INCLUDE[ ... ]]>` or `<![IGNORE[ ... ]]>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct XmlConditionalSection<'a> {
+    /// The keyword of the section: `INCLUDE`, `IGNORE`, or a parameter entity reference like `%draft;` that expands to one of them.
+    pub keyword: &'a [u8],
+    /// The content of the section as written, including any sections nested in it.
+    pub content: &'a [u8],
+}
+
+impl XmlConditionalSection<'_> {
+    /// Returns if the declarations in the section are included, which is unknown if the keyword is a parameter entity reference.
+    pub fn is_included(&self) -> Option<bool> {
+        match self.keyword {
+            b"INCLUDE" => Some(true),
+            b"IGNORE" => Some(false),
+            _ => None,
+        }
+    }
+}
+
+/// A general entity declared with a literal value in the internal subset of a doctype, like `<!ENTITY name "value">`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct XmlEntityDeclaration<'a> {
+    /// The name of the entity.
+    pub name: &'a [u8],
+    /// The value of the entity as written between the quotes, with any references in it left unresolved.
+    pub value: &'a [u8],
+}
+
+/// Parses the content of a markup declaration after its `<!` and before its `>` as a general entity declaration with a literal value. Parameter entities and external entities are not declared with a literal value that can be used in content.
+fn entity_declaration(declaration: &[u8]) -> Option<XmlEntityDeclaration<'_>> {
+    let rest = declaration.strip_prefix(b"ENTITY")?;
+    if !rest.first()?.is_ascii_whitespace() {
+        return None;
+    }
+
+    let rest = rest.trim_ascii_start();
+    let name_len = rest
+        .iter()
+        .position(|byte| byte.is_ascii_whitespace() || matches!(byte, b'"' | b'\''))?;
+    let (name, rest) = rest.split_at(name_len);
+    if name.is_empty() || name.starts_with(b"%") {
+        return None;
+    }
+
+    let rest = rest.trim_ascii_start();
+    let quote = *rest.first().filter(|quote| matches!(quote, b'"' | b'\''))?;
+    let value_len = rest[1..].iter().position(|&byte| byte == quote)?;
+    rest[value_len + 2..]
+        .trim_ascii()
+        .is_empty()
+        .then_some(XmlEntityDeclaration {
+            name,
+            value: &rest[1..value_len + 1],
+        })
+}
+
+struct Scanner<'a> {
+    input: &'a [u8],
+    position: usize,
+    /// The entity declarations that have been moved past, outside of sections that are not known to be included.
+    entity_declarations: Vec<XmlEntityDeclaration<'a>>,
+}
+
+impl<'a> Scanner<'a> {
+    fn rest(&self) -> &'a [u8] {
+        &self.input[self.position..]
+    }
+
+    fn eat(&mut self, prefix: &[u8]) -> bool {
+        let found = self.rest().starts_with(prefix);
+        if found {
+            self.position += prefix.len();
+        }
+        found
+    }
+
+    /// Moves past the next occurrence of `end`.
+    fn skip_past(&mut self, end: &[u8]) -> Option<()> {
+        let offset = self
+            .rest()
+            .windows(end.len())
+            .position(|window| window == end)?;
+        self.position += offset + end.len();
+        Some(())
+    }
+
+    /// Moves past a quoted literal if one starts here, returning whether one did.
+    fn skip_literal(&mut self) -> Option<bool> {
+        let quote = match self.rest().first()? {
+            quote @ (b'"' | b'\'') => *quote,
+            _ => return Some(false),
+        };
+        self.position += 1;
+        self.skip_past(&[quote])?;
+        Some(true)
+    }
+
+    /// Moves past a markup declaration like `<!ELEMENT ...>` after its `<!`.
+    fn skip_declaration(&mut self) -> Option<()> {
+        loop {
+            if self.skip_literal()? {
+                continue;
+            }
+            let byte = *self.rest().first()?;
+            self.position += 1;
+            if byte == b'>' {
+                return Some(());
+            }
+        }
+    }
+
+    /// Moves past one item of a subset, returning the conditional section if the item is one. Parameter entity references and whitespace are moved past one byte at a time.
+    fn subset_item(&mut self) -> Option<Option<XmlConditionalSection<'a>>> {
+        if self.eat(b"<!--") {
+            self.skip_past(b"-->")?;
+        } else if self.eat(b"<?") {
+            self.skip_past(b"?>")?;
+        } else if self.eat(b"<![") {
+            return self.conditional_section().map(Some);
+        } else if self.eat(b"<!") {
+            let start = self.position;
+            self.skip_declaration()?;
+            self.entity_declarations
+                .extend(entity_declaration(&self.input[start..self.position - 1]));
+        } else if self.rest().is_empty() {
+            return None;
+        } else {
+            self.position += 1;
+        }
+        Some(None)
+    }
+
+    /// Moves past a conditional section after its `<![`.
+    fn conditional_section(&mut self) -> Option<XmlConditionalSection<'a>> {
+        let keyword_start = self.position;
+        let keyword_len = self.rest().iter().position(|&byte| byte == b'[')?;
+        let keyword = self.input[keyword_start..keyword_start + keyword_len].trim_ascii();
+        self.position += keyword_len + 1;
+
+        let content_start = self.position;
+        if keyword == b"INCLUDE" {
+            while !self.rest().starts_with(b"]]>") {
+                self.subset_item()?;
+            }
+        } else {
+            // The content of other sections may be ignored and is then not parsed at all, apart from the sections nested in it.
+            let mut depth = 0usize;
+            loop {
+                if self.rest().starts_with(b"]]>") {
+                    if depth == 0 {
+                        break;
+                    }
+                    depth -= 1;
+                    self.position += 3;
+                } else if self.eat(b"<![") {
+                    depth += 1;
+                } else if self.rest().is_empty() {
+                    return None;
+                } else {
+                    self.position += 1;
+                }
+            }
+        }
+        let content = &self.input[content_start..self.position];
+        self.position += 3;
+
+        Some(XmlConditionalSection { keyword, content })
+    }
+
+    /// Moves past an internal subset after its `[`, up to and including the `]` that ends it.
+    fn internal_subset(
+        &mut self,
+        sections: &mut Vec<XmlConditionalSection<'a>>,
+    ) -> Option<&'a [u8]> {
+        let start = self.position;
+        loop {
+            if self.rest().starts_with(b"]") {
+                let subset = &self.input[start..self.position];
+                self.position += 1;
+                return Some(subset);
+            }
+            if let Some(section) = self.subset_item()? {
+                sections.push(section);
+            }
+        }
+    }
+}
+
+/// The parts of a doctype declaration.
+struct DoctypeParts<'a> {
+    /// The length of the declaration, including the `>` that ends it if there is one.
+    len: usize,
+    internal_subset: Option<&'a [u8]>,
+    conditional_sections: Vec<XmlConditionalSection<'a>>,
+    entity_declarations: Vec<XmlEntityDeclaration<'a>>,
+}
+
+/// Scans a doctype declaration after `<!DOCTYPE`. If `terminated` is false, the declaration may also end at the end of the input.
+fn scan_doctype(input: &[u8], terminated: bool) -> Option<DoctypeParts<'_>> {
+    let mut scanner = Scanner {
+        input,
+        position: 0,
+        entity_declarations: Vec::new(),
+    };
+    let mut internal_subset = None;
+    let mut conditional_sections = Vec::new();
+
+    loop {
+        let Some(&byte) = scanner.rest().first() else {
+            return (!terminated).then_some(DoctypeParts {
+                len: scanner.position,
+                internal_subset,
+                conditional_sections,
+                entity_declarations: scanner.entity_declarations,
+            });
+        };
+
+        if scanner.skip_literal()? {
+            continue;
+        }
+
+        scanner.position += 1;
+        match byte {
+            b'[' if internal_subset.is_none() => {
+                internal_subset = Some(scanner.internal_subset(&mut conditional_sections)?);
+            }
+            b'>' => {
+                return Some(DoctypeParts {
+                    len: scanner.position,
+                    internal_subset,
+                    conditional_sections,
+                    entity_declarations: scanner.entity_declarations,
+                })
+            }
+            _ => {}
+        }
+    }
+}
+
+impl XmlDoctype {
+    /// Returns the length of a doctype declaration at the start of `input`, which starts directly after `<!DOCTYPE`, up to and including the `>` that ends it. Returns [`None`] if the declaration is not closed.
+    ///
+    /// This is meant for parsers, which can't find the end of a declaration by matching brackets alone.
+    ///
+    /// ```
+    /// use xmlity::value::XmlDoctype;
+    ///
+    /// let input = br#" a [ <![IGNORE[ <!ENTITY e "]>"> ]]> ]><a/>"#;
+    /// let len = XmlDoctype::declaration_len(input).unwrap();
+    ///
+    /// assert_eq!(&input[len..], b"<a/>");
+    /// ```
+    pub fn declaration_len(input: &[u8]) -> Option<usize> {
+        scan_doctype(input, true).map(|parts| parts.len)
+    }
+
+    /// Returns the name of the root element the doctype declares.
+    pub fn name(&self) -> Option<&[u8]> {
+        let content = self.0.trim_ascii_start();
+        let len = content
+            .iter()
+            .position(|&byte| byte.is_ascii_whitespace() || byte == b'[')
+            .unwrap_or(content.len());
+
+        (len > 0).then(|| &content[..len])
+    }
+
+    /// Returns the internal subset of the doctype as written, without the brackets around it.
+    pub fn internal_subset(&self) -> Option<&[u8]> {
+        scan_doctype(&self.0, false)?.internal_subset
+    }
+
+    /// Returns the conditional sections in the internal subset of the doctype. Sections nested in other sections are part of the content of those and are not returned separately.
+    ///
+    /// ```
+    /// use xmlity::value::XmlDoctype;
+    ///
+    /// let doctype = XmlDoctype::new("a [ <![INCLUDE[ <!ELEMENT a ANY> ]]> ]");
+    /// let sections = doctype.conditional_sections();
+    ///
+    /// assert_eq!(sections.len(), 1);
+    /// assert_eq!(sections[0].is_included(), Some(true));
+    /// assert_eq!(sections[0].content, b" <!ELEMENT a ANY> ");
+    /// ```
+    pub fn conditional_sections(&self) -> Vec<XmlConditionalSection<'_>> {
+        scan_doctype(&self.0, false)
+            .map(|parts| parts.conditional_sections)
+            .unwrap_or_default()
+    }
+
+    /// Returns the general entities declared with a literal value in the internal subset of the doctype, in the order they are declared. Declarations in conditional sections are only returned if the sections are included with `INCLUDE`.
+    ///
+    /// ```
+    /// use xmlity::value::XmlDoctype;
+    ///
+    /// let doctype = XmlDoctype::new(r#"note [ <!ENTITY % p "x"> <!ENTITY me 'Me &amp; co'> <!ENTITY logo SYSTEM "logo.gif"> ]"#);
+    /// let declarations = doctype.entity_declarations();
+    ///
+    /// assert_eq!(declarations.len(), 1);
+    /// assert_eq!(declarations[0].name, b"me");
+    /// assert_eq!(declarations[0].value, b"Me &amp; co");
+    /// ```
+    pub fn entity_declarations(&self) -> Vec<XmlEntityDeclaration<'_>> {
+        scan_doctype(&self.0, false)
+            .map(|parts| parts.entity_declarations)
+            .unwrap_or_default()
+    }
+}
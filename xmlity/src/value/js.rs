@@ -0,0 +1,361 @@
+//! Conversions between [`XmlValue`] and plain JavaScript values, enabled with the `wasm` feature.
+//!
+//! This allows documents to be handed to and from JavaScript, for example to build editors running in the browser. Every node is represented by a plain object with a `type` property:
+//!
+//! | Node | Object |
+//! |------|--------|
+//! | [`XmlValue::None`] | `null` |
+//! | [`XmlValue::Seq`] | An array of nodes |
+//! | [`XmlText`] | `{ type: "text", text }` |
+//! | [`XmlCData`] | `{ type: "cdata", text }` |
+//! | [`XmlComment`] | `{ type: "comment", text }` |
+//! | [`XmlDoctype`] | `{ type: "doctype", text }` |
+//! | [`XmlProcessingInstruction`] | `{ type: "pi", target, content }` |
+//! | [`XmlDecl`] | `{ type: "decl", version, encoding?, standalone? }` |
+//! | [`XmlElement`] | `{ type: "element", localName, namespace?, prefix?, declarations, attributes, children }` |
+//! | `XmlError` | `{ type: "error", start, end, message }` |
+//!
+//! Attributes are represented as `{ localName, namespace?, prefix?, value }` and namespace declarations as `{ prefix, namespace }`. Optional properties are left out when they are not set, and may be `undefined` or `null` when converting from JavaScript.
+use core::{fmt, str};
+
+use js_sys::{Array, Object, Reflect};
+use wasm_bindgen::JsValue;
+
+use crate::{ExpandedNameBuf, LocalNameBuf, PrefixBuf, XmlNamespaceBuf};
+
+use super::{
+    XmlAttribute, XmlCData, XmlChild, XmlComment, XmlDecl, XmlDoctype, XmlElement,
+    XmlProcessingInstruction, XmlSeq, XmlText, XmlValue, XmlnsDeclaration,
+};
+
+/// Errors that can occur when converting between [`XmlValue`] and JavaScript values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum JsConversionError {
+    /// The content of a node is not valid UTF-8, so it can't be represented as a JavaScript string.
+    InvalidUtf8(&'static str),
+    /// A value is not an object or array where one was expected.
+    ExpectedObject,
+    /// A required property is missing.
+    MissingProperty(&'static str),
+    /// A property has a value of the wrong type.
+    InvalidProperty(&'static str),
+    /// A node has a `type` that is not known.
+    UnknownType(String),
+    /// A node can't occur where it was found, like a declaration inside an element.
+    InvalidChild(&'static str),
+    /// A name, prefix or namespace is not valid.
+    InvalidName(String),
+}
+
+impl fmt::Display for JsConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidUtf8(value) => write!(f, "Invalid UTF-8 in {value}"),
+            Self::ExpectedObject => f.write_str("Expected an object or array"),
+            Self::MissingProperty(value) => write!(f, "Missing property {value:?}"),
+            Self::InvalidProperty(value) => write!(f, "Invalid value for property {value:?}"),
+            Self::UnknownType(value) => write!(f, "Unknown node type {value:?}"),
+            Self::InvalidChild(value) => write!(f, "A {value} node can't be a child of an element"),
+            Self::InvalidName(value) => write!(f, "Invalid name: {value}"),
+        }
+    }
+}
+
+impl std::error::Error for JsConversionError {}
+
+/// Converts an [`XmlValue`] into a plain JavaScript value.
+pub fn to_js(value: &XmlValue) -> Result<JsValue, JsConversionError> {
+    match value {
+        XmlValue::Text(text) => text_node("text", &text.0),
+        XmlValue::CData(cdata) => text_node("cdata", &cdata.0),
+        XmlValue::Element(element) => element_to_js(element),
+        XmlValue::Seq(seq) => seq
+            .iter()
+            .map(to_js)
+            .collect::<Result<Array, _>>()
+            .map(JsValue::from),
+        XmlValue::PI(pi) => pi_to_js(pi),
+        XmlValue::Decl(decl) => {
+            let node = node("decl");
+            set(&node, "version", decl.version.as_str().into());
+            if let Some(encoding) = &decl.encoding {
+                set(&node, "encoding", encoding.as_str().into());
+            }
+            if let Some(standalone) = &decl.standalone {
+                set(&node, "standalone", standalone.as_str().into());
+            }
+            Ok(node.into())
+        }
+        XmlValue::Comment(comment) => text_node("comment", &comment.0),
+        XmlValue::Doctype(doctype) => text_node("doctype", &doctype.0),
+        #[cfg(feature = "recovery")]
+        XmlValue::Error(error) => Ok(error_to_js(error)),
+        XmlValue::None => Ok(JsValue::NULL),
+    }
+}
+
+/// Converts a plain JavaScript value, as produced by [`to_js`], into an [`XmlValue`].
+pub fn from_js(value: &JsValue) -> Result<XmlValue, JsConversionError> {
+    if value.is_null() || value.is_undefined() {
+        return Ok(XmlValue::None);
+    }
+
+    if Array::is_array(value) {
+        return Array::from(value)
+            .iter()
+            .map(|value| from_js(&value))
+            .collect::<Result<XmlSeq<_>, _>>()
+            .map(XmlValue::Seq);
+    }
+
+    let node_type = get_string(value, "type")?;
+    match node_type.as_str() {
+        "text" => Ok(XmlValue::Text(XmlText(
+            get_string(value, "text")?.into_bytes(),
+        ))),
+        "cdata" => Ok(XmlValue::CData(XmlCData(
+            get_string(value, "text")?.into_bytes(),
+        ))),
+        "comment" => Ok(XmlValue::Comment(XmlComment(
+            get_string(value, "text")?.into_bytes(),
+        ))),
+        "doctype" => Ok(XmlValue::Doctype(XmlDoctype(
+            get_string(value, "text")?.into_bytes(),
+        ))),
+        "pi" => Ok(XmlValue::PI(XmlProcessingInstruction::new(
+            get_string(value, "target")?,
+            get_optional_string(value, "content")?.unwrap_or_default(),
+        ))),
+        "decl" => Ok(XmlValue::Decl(XmlDecl {
+            version: get_string(value, "version")?,
+            encoding: get_optional_string(value, "encoding")?,
+            standalone: get_optional_string(value, "standalone")?,
+        })),
+        "element" => element_from_js(value).map(XmlValue::Element),
+        #[cfg(feature = "recovery")]
+        "error" => error_from_js(value).map(XmlValue::Error),
+        _ => Err(JsConversionError::UnknownType(node_type)),
+    }
+}
+
+fn as_str<'a>(bytes: &'a [u8], what: &'static str) -> Result<&'a str, JsConversionError> {
+    str::from_utf8(bytes).map_err(|_| JsConversionError::InvalidUtf8(what))
+}
+
+fn set(object: &Object, key: &str, value: JsValue) {
+    Reflect::set(object, &JsValue::from_str(key), &value)
+        .expect("setting a property of a plain object does not fail");
+}
+
+fn node(node_type: &str) -> Object {
+    let node = Object::new();
+    set(&node, "type", node_type.into());
+    node
+}
+
+fn text_node(node_type: &'static str, text: &[u8]) -> Result<JsValue, JsConversionError> {
+    let node = node(node_type);
+    set(&node, "text", as_str(text, node_type)?.into());
+    Ok(node.into())
+}
+
+fn pi_to_js(pi: &XmlProcessingInstruction) -> Result<JsValue, JsConversionError> {
+    let node = node("pi");
+    set(&node, "target", as_str(&pi.target, "pi")?.into());
+    set(&node, "content", as_str(&pi.content, "pi")?.into());
+    Ok(node.into())
+}
+
+#[cfg(feature = "recovery")]
+fn error_to_js(error: &super::XmlError) -> JsValue {
+    let node = node("error");
+    set(&node, "start", JsValue::from_f64(error.start as f64));
+    set(&node, "end", JsValue::from_f64(error.end as f64));
+    set(&node, "message", error.message.as_str().into());
+    node.into()
+}
+
+fn set_name(object: &Object, name: &ExpandedNameBuf, prefix: Option<&PrefixBuf>) {
+    set(object, "localName", name.local_name().as_str().into());
+    if let Some(namespace) = name.namespace() {
+        set(object, "namespace", namespace.as_str().into());
+    }
+    if let Some(prefix) = prefix {
+        set(object, "prefix", prefix.as_str().into());
+    }
+}
+
+fn element_to_js(element: &XmlElement) -> Result<JsValue, JsConversionError> {
+    let node = node("element");
+    set_name(&node, &element.name, element.original_prefix.as_ref());
+
+    let declarations = element
+        .declarations
+        .iter()
+        .map(|declaration| {
+            let object = Object::new();
+            set(&object, "prefix", declaration.prefix.as_str().into());
+            set(&object, "namespace", declaration.namespace.as_str().into());
+            JsValue::from(object)
+        })
+        .collect::<Array>();
+    set(&node, "declarations", declarations.into());
+
+    let attributes = element
+        .attributes
+        .iter()
+        .map(|attribute| {
+            let object = Object::new();
+            set_name(&object, &attribute.name, attribute.original_prefix.as_ref());
+            set(
+                &object,
+                "value",
+                as_str(&attribute.value.0, "attribute")?.into(),
+            );
+            Ok(JsValue::from(object))
+        })
+        .collect::<Result<Array, _>>()?;
+    set(&node, "attributes", attributes.into());
+
+    let children = element
+        .children
+        .iter()
+        .map(|child| match child {
+            XmlChild::Text(text) => text_node("text", &text.0),
+            XmlChild::CData(cdata) => text_node("cdata", &cdata.0),
+            XmlChild::Element(element) => element_to_js(element),
+            XmlChild::PI(pi) => pi_to_js(pi),
+            XmlChild::Comment(comment) => text_node("comment", &comment.0),
+            #[cfg(feature = "recovery")]
+            XmlChild::Error(error) => Ok(error_to_js(error)),
+            XmlChild::None => Ok(JsValue::NULL),
+        })
+        .collect::<Result<Array, _>>()?;
+    set(&node, "children", children.into());
+
+    Ok(node.into())
+}
+
+fn get(object: &JsValue, key: &'static str) -> Result<JsValue, JsConversionError> {
+    if !object.is_object() {
+        return Err(JsConversionError::ExpectedObject);
+    }
+
+    Reflect::get(object, &JsValue::from_str(key))
+        .map_err(|_| JsConversionError::InvalidProperty(key))
+}
+
+fn get_optional_string(
+    object: &JsValue,
+    key: &'static str,
+) -> Result<Option<String>, JsConversionError> {
+    let value = get(object, key)?;
+    if value.is_null() || value.is_undefined() {
+        return Ok(None);
+    }
+
+    value
+        .as_string()
+        .map(Some)
+        .ok_or(JsConversionError::InvalidProperty(key))
+}
+
+fn get_string(object: &JsValue, key: &'static str) -> Result<String, JsConversionError> {
+    get_optional_string(object, key)?.ok_or(JsConversionError::MissingProperty(key))
+}
+
+fn get_array(object: &JsValue, key: &'static str) -> Result<Array, JsConversionError> {
+    let value = get(object, key)?;
+    if value.is_null() || value.is_undefined() {
+        return Ok(Array::new());
+    }
+
+    if !Array::is_array(&value) {
+        return Err(JsConversionError::InvalidProperty(key));
+    }
+    Ok(Array::from(&value))
+}
+
+fn invalid_name(error: impl core::fmt::Display) -> JsConversionError {
+    JsConversionError::InvalidName(error.to_string())
+}
+
+fn name_from_js(
+    object: &JsValue,
+) -> Result<(ExpandedNameBuf, Option<PrefixBuf>), JsConversionError> {
+    let local_name = LocalNameBuf::new(get_string(object, "localName")?).map_err(invalid_name)?;
+    let namespace = get_optional_string(object, "namespace")?
+        .map(XmlNamespaceBuf::new)
+        .transpose()
+        .map_err(invalid_name)?;
+    let prefix = get_optional_string(object, "prefix")?
+        .map(PrefixBuf::new)
+        .transpose()
+        .map_err(invalid_name)?;
+
+    Ok((ExpandedNameBuf::new(local_name, namespace), prefix))
+}
+
+fn element_from_js(value: &JsValue) -> Result<XmlElement, JsConversionError> {
+    let (name, prefix) = name_from_js(value)?;
+    let mut element = XmlElement::new(name);
+    element.original_prefix = prefix;
+
+    element.declarations = get_array(value, "declarations")?
+        .iter()
+        .map(|declaration| {
+            let prefix =
+                PrefixBuf::new(get_string(&declaration, "prefix")?).map_err(invalid_name)?;
+            let namespace = XmlNamespaceBuf::new(get_string(&declaration, "namespace")?)
+                .map_err(invalid_name)?;
+            Ok(XmlnsDeclaration::new(prefix, namespace))
+        })
+        .collect::<Result<_, _>>()?;
+
+    element.attributes = get_array(value, "attributes")?
+        .iter()
+        .map(|attribute| {
+            let (name, prefix) = name_from_js(&attribute)?;
+            let mut attribute =
+                XmlAttribute::new(name, XmlText(get_string(&attribute, "value")?.into_bytes()));
+            attribute.original_prefix = prefix;
+            Ok(attribute)
+        })
+        .collect::<Result<_, _>>()?;
+
+    element.children = get_array(value, "children")?
+        .iter()
+        .map(|child| match from_js(&child)? {
+            XmlValue::Text(text) => Ok(XmlChild::Text(text)),
+            XmlValue::CData(cdata) => Ok(XmlChild::CData(cdata)),
+            XmlValue::Element(element) => Ok(XmlChild::Element(element)),
+            XmlValue::PI(pi) => Ok(XmlChild::PI(pi)),
+            XmlValue::Comment(comment) => Ok(XmlChild::Comment(comment)),
+            #[cfg(feature = "recovery")]
+            XmlValue::Error(error) => Ok(XmlChild::Error(error)),
+            XmlValue::None => Ok(XmlChild::None),
+            XmlValue::Seq(_) => Err(JsConversionError::InvalidChild("sequence")),
+            XmlValue::Decl(_) => Err(JsConversionError::InvalidChild("decl")),
+            XmlValue::Doctype(_) => Err(JsConversionError::InvalidChild("doctype")),
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(element)
+}
+
+#[cfg(feature = "recovery")]
+fn error_from_js(value: &JsValue) -> Result<super::XmlError, JsConversionError> {
+    let offset = |key| {
+        get(value, key)?
+            .as_f64()
+            .filter(|offset| *offset >= 0.0 && offset.fract() == 0.0)
+            .map(|offset| offset as usize)
+            .ok_or(JsConversionError::InvalidProperty(key))
+    };
+
+    Ok(super::XmlError::new(
+        offset("start")?..offset("end")?,
+        get_string(value, "message")?,
+    ))
+}
@@ -0,0 +1,407 @@
+//! Conversions between [`XmlValue`] and [`serde_json::Value`], enabled with the `json` feature.
+//!
+//! Elements are converted using the common convention where an element becomes a property named after the element:
+//! - An element without attributes or child elements becomes its text, or `null` if it is empty.
+//! - Otherwise it becomes an object, where attributes are properties with [`JsonOptions::attribute_prefix`] in front of their names, text is the [`JsonOptions::text_key`] property and child elements are properties named after them. Child elements with the same name are grouped into an array.
+//!
+//! JSON does not keep the order of properties, and comments, processing instructions, declarations and doctypes have no JSON equivalent, so they are left out. Text that only consists of whitespace is left out of elements with child elements, as it is usually indentation. All text and attribute values are JSON strings.
+//!
+//! ```
+//! use serde_json::json;
+//! use xmlity::{value::json::{to_json, from_json, JsonOptions}, XmlValue};
+//!
+//! let value: XmlValue = r#"<note id="1"><body>Hi!</body><to>Tove</to><to>Jani</to></note>"#
+//!     .parse()
+//!     .unwrap();
+//!
+//! let json = to_json(&value, &JsonOptions::new()).unwrap();
+//! assert_eq!(
+//!     json,
+//!     json!({ "note": { "@id": "1", "body": "Hi!", "to": ["Tove", "Jani"] } })
+//! );
+//!
+//! // Properties are read in alphabetical order, so this only round trips because the elements already were.
+//! assert_eq!(from_json(&json, &JsonOptions::new()).unwrap(), value);
+//! ```
+use core::{fmt, str};
+
+use serde_json::{Map, Value};
+
+use crate::{ExpandedNameBuf, LocalNameBuf, XmlNamespaceBuf};
+
+use super::{XmlAttribute, XmlChild, XmlElement, XmlSeq, XmlText, XmlValue};
+
+/// How the namespaces of elements and attributes are represented in JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum JsonNamespaces {
+    /// Names in a namespace are written as `{namespace}name`, also known as Clark notation. Names without a namespace are written as-is.
+    #[default]
+    Clark,
+    /// Only local names are written, and names are read without a namespace.
+    Ignore,
+}
+
+/// Options for converting between [`XmlValue`] and [`serde_json::Value`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct JsonOptions {
+    /// The prefix in front of property names for attributes.
+    pub attribute_prefix: String,
+    /// The property name for text in elements that also have attributes or child elements.
+    pub text_key: String,
+    /// How namespaces are represented.
+    pub namespaces: JsonNamespaces,
+}
+
+impl JsonOptions {
+    /// Creates the default options, which prefix attributes with `@`, put text in `#text` and write namespaces in Clark notation.
+    pub fn new() -> Self {
+        Self {
+            attribute_prefix: "@".to_string(),
+            text_key: "#text".to_string(),
+            namespaces: JsonNamespaces::default(),
+        }
+    }
+
+    /// Sets the prefix in front of property names for attributes.
+    ///
+    /// The prefix must not be empty, as attributes could otherwise not be told apart from child elements.
+    pub fn with_attribute_prefix<T: Into<String>>(mut self, attribute_prefix: T) -> Self {
+        self.attribute_prefix = attribute_prefix.into();
+        self
+    }
+
+    /// Sets the property name for text.
+    pub fn with_text_key<T: Into<String>>(mut self, text_key: T) -> Self {
+        self.text_key = text_key.into();
+        self
+    }
+
+    /// Sets how namespaces are represented.
+    pub fn with_namespaces(mut self, namespaces: JsonNamespaces) -> Self {
+        self.namespaces = namespaces;
+        self
+    }
+}
+
+impl Default for JsonOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Errors that can occur when converting between [`XmlValue`] and [`serde_json::Value`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum JsonConversionError {
+    /// Text or an attribute value is not valid UTF-8, so it can't be represented as a JSON string.
+    InvalidUtf8,
+    /// A property name is not a valid element or attribute name.
+    InvalidName(String),
+    /// An attribute or text has a value that is not a string, number, boolean or `null`.
+    ExpectedScalar(String),
+    /// An attribute was found outside of an element.
+    AttributeOutsideElement(String),
+    /// An array was found directly inside another array.
+    NestedArray,
+}
+
+impl fmt::Display for JsonConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidUtf8 => f.write_str("Invalid UTF-8"),
+            Self::InvalidName(value) => write!(f, "Invalid name {value:?}"),
+            Self::ExpectedScalar(value) => write!(
+                f,
+                "Property {value:?} must be a string, number, boolean or null"
+            ),
+            Self::AttributeOutsideElement(value) => {
+                write!(f, "Attribute {value:?} is not in an element")
+            }
+            Self::NestedArray => f.write_str("Arrays can't be nested directly"),
+        }
+    }
+}
+
+impl std::error::Error for JsonConversionError {}
+
+/// Converts an [`XmlValue`] into a [`serde_json::Value`].
+pub fn to_json(value: &XmlValue, options: &JsonOptions) -> Result<Value, JsonConversionError> {
+    match value {
+        XmlValue::Text(text) => text_to_json(&text.0),
+        XmlValue::CData(cdata) => text_to_json(&cdata.0),
+        XmlValue::Element(element) => {
+            let mut object = Map::new();
+            insert_grouped(
+                &mut object,
+                name_to_key(&element.name, options),
+                element_content_to_json(element, options)?,
+            );
+            Ok(Value::Object(object))
+        }
+        XmlValue::Seq(seq) => {
+            let children = seq.iter().filter_map(|value| match value {
+                XmlValue::Text(text) => Some(Child::Text(&text.0)),
+                XmlValue::CData(cdata) => Some(Child::Text(&cdata.0)),
+                XmlValue::Element(element) => Some(Child::Element(element)),
+                _ => None,
+            });
+            children_to_json(Map::new(), children, options)
+        }
+        _ => Ok(Value::Null),
+    }
+}
+
+/// Converts a [`serde_json::Value`], using the same conventions as [`to_json`], into an [`XmlValue`].
+///
+/// An object with a single element property becomes an [`XmlValue::Element`], and other objects and arrays become an [`XmlValue::Seq`]. Text is put before child elements.
+pub fn from_json(value: &Value, options: &JsonOptions) -> Result<XmlValue, JsonConversionError> {
+    match value {
+        Value::Null => Ok(XmlValue::None),
+        Value::Array(values) => values
+            .iter()
+            .map(|value| from_json(value, options))
+            .collect::<Result<XmlSeq<_>, _>>()
+            .map(XmlValue::Seq),
+        Value::Object(object) => {
+            let mut values = object
+                .iter()
+                .map(|(key, value)| {
+                    if is_attribute_key(key, options) {
+                        return Err(JsonConversionError::AttributeOutsideElement(key.clone()));
+                    }
+
+                    Ok(properties_to_children(key, value, options)?
+                        .into_iter()
+                        .map(|child| match child {
+                            XmlChild::Element(element) => XmlValue::Element(element),
+                            XmlChild::Text(text) => XmlValue::Text(text),
+                            _ => unreachable!("only elements and text are created from JSON"),
+                        }))
+                })
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>();
+
+            if values.len() == 1 {
+                Ok(values.remove(0))
+            } else {
+                Ok(XmlValue::Seq(values.into_iter().collect()))
+            }
+        }
+        scalar => Ok(XmlValue::Text(XmlText(
+            scalar_to_string(scalar, &options.text_key)?.into_bytes(),
+        ))),
+    }
+}
+
+enum Child<'a> {
+    Text(&'a [u8]),
+    Element(&'a XmlElement),
+}
+
+fn as_str(bytes: &[u8]) -> Result<&str, JsonConversionError> {
+    str::from_utf8(bytes).map_err(|_| JsonConversionError::InvalidUtf8)
+}
+
+fn text_to_json(text: &[u8]) -> Result<Value, JsonConversionError> {
+    as_str(text).map(|text| Value::String(text.to_owned()))
+}
+
+fn name_to_key(name: &ExpandedNameBuf, options: &JsonOptions) -> String {
+    match (options.namespaces, name.namespace()) {
+        (JsonNamespaces::Clark, Some(namespace)) => {
+            format!("{{{}}}{}", namespace.as_str(), name.local_name().as_str())
+        }
+        _ => name.local_name().as_str().to_owned(),
+    }
+}
+
+fn key_to_name(key: &str, options: &JsonOptions) -> Result<ExpandedNameBuf, JsonConversionError> {
+    let invalid_name = |_| JsonConversionError::InvalidName(key.to_owned());
+
+    let clark = key
+        .strip_prefix('{')
+        .and_then(|key| key.split_once('}'))
+        .filter(|_| options.namespaces == JsonNamespaces::Clark);
+
+    match clark {
+        Some((namespace, local_name)) => Ok(ExpandedNameBuf::new(
+            LocalNameBuf::new(local_name.to_owned()).map_err(invalid_name)?,
+            Some(
+                XmlNamespaceBuf::new(namespace.to_owned())
+                    .map_err(|_| JsonConversionError::InvalidName(key.to_owned()))?,
+            ),
+        )),
+        None => Ok(ExpandedNameBuf::new(
+            LocalNameBuf::new(key.to_owned()).map_err(invalid_name)?,
+            None,
+        )),
+    }
+}
+
+fn is_attribute_key(key: &str, options: &JsonOptions) -> bool {
+    !options.attribute_prefix.is_empty() && key.starts_with(&options.attribute_prefix)
+}
+
+/// Inserts a property, turning it into an array if there already is a property with the same name.
+fn insert_grouped(object: &mut Map<String, Value>, key: String, value: Value) {
+    match object.get_mut(&key) {
+        Some(Value::Array(values)) => values.push(value),
+        Some(existing) => {
+            let existing = existing.take();
+            object.insert(key, Value::Array(vec![existing, value]));
+        }
+        None => {
+            object.insert(key, value);
+        }
+    }
+}
+
+fn element_content_to_json(
+    element: &XmlElement,
+    options: &JsonOptions,
+) -> Result<Value, JsonConversionError> {
+    let mut object = Map::new();
+    for attribute in element.attributes.iter() {
+        object.insert(
+            format!(
+                "{}{}",
+                options.attribute_prefix,
+                name_to_key(&attribute.name, options)
+            ),
+            text_to_json(&attribute.value.0)?,
+        );
+    }
+
+    let children = element.children.iter().filter_map(|child| match child {
+        XmlChild::Text(text) => Some(Child::Text(&text.0)),
+        XmlChild::CData(cdata) => Some(Child::Text(&cdata.0)),
+        XmlChild::Element(element) => Some(Child::Element(element)),
+        _ => None,
+    });
+
+    children_to_json(object, children, options)
+}
+
+/// Adds text and child elements to an object, simplifying it to its text or `null` if it has nothing else.
+fn children_to_json<'a>(
+    mut object: Map<String, Value>,
+    children: impl Iterator<Item = Child<'a>>,
+    options: &JsonOptions,
+) -> Result<Value, JsonConversionError> {
+    let mut text = String::new();
+    let mut has_elements = false;
+
+    for child in children {
+        match child {
+            Child::Text(child_text) => text.push_str(as_str(child_text)?),
+            Child::Element(element) => {
+                has_elements = true;
+                insert_grouped(
+                    &mut object,
+                    name_to_key(&element.name, options),
+                    element_content_to_json(element, options)?,
+                );
+            }
+        }
+    }
+
+    if has_elements && text.trim().is_empty() {
+        text.clear();
+    }
+
+    if object.is_empty() {
+        return Ok(if text.is_empty() {
+            Value::Null
+        } else {
+            Value::String(text)
+        });
+    }
+
+    if !text.is_empty() {
+        object.insert(options.text_key.clone(), Value::String(text));
+    }
+
+    Ok(Value::Object(object))
+}
+
+fn scalar_to_string(value: &Value, key: &str) -> Result<String, JsonConversionError> {
+    match value {
+        Value::Null => Ok(String::new()),
+        Value::Bool(value) => Ok(value.to_string()),
+        Value::Number(value) => Ok(value.to_string()),
+        Value::String(value) => Ok(value.clone()),
+        Value::Array(_) | Value::Object(_) => {
+            Err(JsonConversionError::ExpectedScalar(key.to_owned()))
+        }
+    }
+}
+
+/// Converts a property that is not an attribute into text or elements.
+fn properties_to_children(
+    key: &str,
+    value: &Value,
+    options: &JsonOptions,
+) -> Result<Vec<XmlChild>, JsonConversionError> {
+    if key == options.text_key {
+        return Ok(vec![XmlChild::Text(XmlText(
+            scalar_to_string(value, key)?.into_bytes(),
+        ))]);
+    }
+
+    match value {
+        Value::Array(values) => values
+            .iter()
+            .map(|value| match value {
+                Value::Array(_) => Err(JsonConversionError::NestedArray),
+                value => element_from_json(key, value, options).map(XmlChild::Element),
+            })
+            .collect(),
+        value => Ok(vec![XmlChild::Element(element_from_json(
+            key, value, options,
+        )?)]),
+    }
+}
+
+fn element_from_json(
+    key: &str,
+    value: &Value,
+    options: &JsonOptions,
+) -> Result<XmlElement, JsonConversionError> {
+    let mut element = XmlElement::new(key_to_name(key, options)?);
+
+    let Value::Object(object) = value else {
+        let text = scalar_to_string(value, key)?;
+        if !text.is_empty() {
+            element
+                .children
+                .push_back(XmlChild::Text(XmlText(text.into_bytes())));
+        }
+        return Ok(element);
+    };
+
+    let mut text = Vec::new();
+    let mut elements = Vec::new();
+    for (key, value) in object {
+        if let Some(name) = key
+            .strip_prefix(&options.attribute_prefix)
+            .filter(|_| is_attribute_key(key, options))
+        {
+            element.attributes.push_back(XmlAttribute::new(
+                key_to_name(name, options)?,
+                XmlText(scalar_to_string(value, key)?.into_bytes()),
+            ));
+        } else if key == &options.text_key {
+            text.extend(properties_to_children(key, value, options)?);
+        } else {
+            elements.extend(properties_to_children(key, value, options)?);
+        }
+    }
+
+    element.children = text.into_iter().chain(elements).collect();
+
+    Ok(element)
+}
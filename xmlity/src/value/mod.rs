@@ -17,18 +17,39 @@ use std::{
     ops::{Deref, DerefMut},
 };
 
+#[cfg(feature = "xsd-validate")]
+use std::collections::BTreeMap;
+
 use crate::{
     de,
     ser::{self, IncludePrefix},
     types::iterator::IteratorVisitor,
-    ExpandedName, ExpandedNameBuf, PrefixBuf,
+    ExpandedName, ExpandedNameBuf, PrefixBuf, XmlNamespaceBuf,
 };
 
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
+#[cfg(feature = "bumpalo")]
+pub mod bump;
 pub mod deserialize;
 mod deserializer;
+mod doctype;
+#[cfg(feature = "wasm")]
+pub mod js;
+#[cfg(feature = "json")]
+pub mod json;
+mod normalize;
+mod parse;
+pub mod query;
+#[cfg(feature = "xsd-validate")]
+pub mod schema;
 mod serialize;
 mod serializer;
 
+pub use doctype::{XmlConditionalSection, XmlEntityDeclaration};
+pub use normalize::NormalizeOptions;
+pub use parse::XmlValueParseError;
+
 /// Creates any `T` implementing [`Deserialize`] from an [`XmlValue`]
 pub fn from_value<'de, T: crate::Deserialize<'de>>(
     value: &'de XmlValue,
@@ -62,6 +83,9 @@ pub enum XmlValue {
     Comment(XmlComment),
     /// A doctype.
     Doctype(XmlDoctype),
+    /// A malformed section of a document.
+    #[cfg(feature = "recovery")]
+    Error(XmlError),
     /// Nothing.
     #[default]
     None,
@@ -111,6 +135,12 @@ impl From<XmlDoctype> for XmlValue {
         XmlValue::Doctype(value)
     }
 }
+#[cfg(feature = "recovery")]
+impl From<XmlError> for XmlValue {
+    fn from(value: XmlError) -> Self {
+        XmlValue::Error(value)
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 enum XmlValueWithoutSeq {
@@ -188,9 +218,51 @@ pub struct XmlCData(pub Vec<u8>);
 
 impl XmlCData {
     /// Creates a new [CDATA section](`XmlCData`).
+    ///
+    /// The content is not validated, see [`XmlCData::try_new`] for a constructor that does.
     pub fn new<T: Into<Vec<u8>>>(text: T) -> Self {
         Self(text.into())
     }
+
+    /// Creates a new [CDATA section](`XmlCData`), failing if the content can't be written as one.
+    ///
+    /// ```
+    /// use xmlity::value::{InvalidContentError, XmlCData};
+    ///
+    /// assert!(XmlCData::try_new("if (a < b) {}").is_ok());
+    /// assert_eq!(XmlCData::try_new("a[b]]>"), Err(InvalidContentError::CDataTerminator));
+    /// ```
+    pub fn try_new<T: Into<Vec<u8>>>(text: T) -> Result<Self, InvalidContentError> {
+        let cdata = Self(text.into());
+        cdata.validate()?;
+        Ok(cdata)
+    }
+
+    /// Checks that the content can be written as a single CDATA section, meaning that it does not contain the `]]>` that ends the section.
+    ///
+    /// Serializers may still write content that fails this check by splitting it into several adjacent sections.
+    pub fn validate(&self) -> Result<(), InvalidContentError> {
+        if self.0.windows(3).any(|window| window == b"]]>") {
+            return Err(InvalidContentError::CDataTerminator);
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<String> for XmlCData {
+    type Error = InvalidContentError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::try_new(value)
+    }
+}
+
+impl TryFrom<&str> for XmlCData {
+    type Error = InvalidContentError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::try_new(value)
+    }
 }
 
 /// An XML child node.
@@ -206,6 +278,9 @@ pub enum XmlChild {
     PI(XmlProcessingInstruction),
     /// A comment node.
     Comment(XmlComment),
+    /// A malformed section of a document.
+    #[cfg(feature = "recovery")]
+    Error(XmlError),
     /// Nothing.
     #[default]
     None,
@@ -241,6 +316,13 @@ impl From<XmlComment> for XmlChild {
     }
 }
 
+#[cfg(feature = "recovery")]
+impl From<XmlError> for XmlChild {
+    fn from(value: XmlError) -> Self {
+        XmlChild::Error(value)
+    }
+}
+
 /// An XML element.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[non_exhaustive]
@@ -255,6 +337,14 @@ pub struct XmlElement {
     pub enforce_prefix: IncludePrefix,
     /// The preferred prefix of the element.
     pub preferred_prefix: Option<PrefixBuf>,
+    /// The prefix the element was written with, if it was recorded by the deserializer.
+    ///
+    /// When set, the element is serialized with exactly this prefix, declaring it again if it is not bound to the namespace of the element in the current scope. This allows documents to round trip with their prefixes intact.
+    pub original_prefix: Option<PrefixBuf>,
+    /// The namespace declarations written on the element, if they were recorded by the deserializer.
+    ///
+    /// The element is serialized with these declarations in this order, in addition to any declarations needed for the names of the element and its attributes. This allows documents that rely on where namespaces are declared to round trip faithfully.
+    pub declarations: Vec<XmlnsDeclaration>,
 }
 
 impl XmlElement {
@@ -266,9 +356,17 @@ impl XmlElement {
             children: XmlSeq::new(),
             enforce_prefix: IncludePrefix::default(),
             preferred_prefix: None,
+            original_prefix: None,
+            declarations: Vec::new(),
         }
     }
 
+    /// Adds a namespace declaration to the element.
+    pub fn with_declaration<T: Into<XmlnsDeclaration>>(mut self, declaration: T) -> Self {
+        self.declarations.push(declaration.into());
+        self
+    }
+
     /// Adds an attribute to the element.
     pub fn with_attribute<T: Into<XmlAttribute>>(mut self, attribute: T) -> Self {
         self.attributes.push_back(attribute.into());
@@ -303,6 +401,29 @@ impl XmlElement {
     }
 }
 
+/// A namespace declaration of an element, binding a prefix to a namespace. The default namespace is declared with [`Prefix::BLANK`](crate::Prefix::BLANK).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub struct XmlnsDeclaration {
+    /// The prefix that is declared.
+    pub prefix: PrefixBuf,
+    /// The namespace the prefix is bound to.
+    pub namespace: XmlNamespaceBuf,
+}
+
+impl XmlnsDeclaration {
+    /// Creates a new namespace declaration.
+    pub fn new(prefix: PrefixBuf, namespace: XmlNamespaceBuf) -> Self {
+        Self { prefix, namespace }
+    }
+}
+
+impl From<(PrefixBuf, XmlNamespaceBuf)> for XmlnsDeclaration {
+    fn from((prefix, namespace): (PrefixBuf, XmlNamespaceBuf)) -> Self {
+        Self::new(prefix, namespace)
+    }
+}
+
 /// An XML attribute.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[non_exhaustive]
@@ -311,6 +432,10 @@ pub struct XmlAttribute {
     pub name: ExpandedNameBuf,
     /// The value of the attribute.
     pub value: XmlText,
+    /// The prefix the attribute was written with, if it was recorded by the deserializer.
+    ///
+    /// When set, the attribute is serialized with exactly this prefix.
+    pub original_prefix: Option<PrefixBuf>,
 }
 
 impl XmlAttribute {
@@ -319,6 +444,7 @@ impl XmlAttribute {
         Self {
             name: name.into(),
             value: value.into(),
+            original_prefix: None,
         }
     }
 }
@@ -369,15 +495,15 @@ impl<T> XmlSeq<T> {
     }
 }
 
-impl Deref for XmlSeq<XmlValue> {
-    type Target = VecDeque<XmlValue>;
+impl<T> Deref for XmlSeq<T> {
+    type Target = VecDeque<T>;
 
     fn deref(&self) -> &Self::Target {
         &self.values
     }
 }
 
-impl DerefMut for XmlSeq<XmlValue> {
+impl<T> DerefMut for XmlSeq<T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.values
     }
@@ -437,12 +563,55 @@ pub struct XmlComment(pub Vec<u8>);
 
 impl XmlComment {
     /// Creates a new XML comment.
+    ///
+    /// The content is not validated, see [`XmlComment::try_new`] for a constructor that does.
     pub fn new<T: Into<Vec<u8>>>(comment: T) -> Self {
         Self(comment.into())
     }
+
+    /// Creates a new XML comment, failing if the content can't be written as one.
+    ///
+    /// ```
+    /// use xmlity::value::{InvalidContentError, XmlComment};
+    ///
+    /// assert!(XmlComment::try_new(" a comment ").is_ok());
+    /// assert_eq!(XmlComment::try_new("a -- b"), Err(InvalidContentError::DoubleHyphenInComment));
+    /// ```
+    pub fn try_new<T: Into<Vec<u8>>>(comment: T) -> Result<Self, InvalidContentError> {
+        let comment = Self(comment.into());
+        comment.validate()?;
+        Ok(comment)
+    }
+
+    /// Checks that the content can be written as a comment, meaning that it does not contain `--` and does not end with `-`.
+    pub fn validate(&self) -> Result<(), InvalidContentError> {
+        if self.0.windows(2).any(|window| window == b"--") {
+            return Err(InvalidContentError::DoubleHyphenInComment);
+        }
+        if self.0.ends_with(b"-") {
+            return Err(InvalidContentError::CommentEndsWithHyphen);
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<String> for XmlComment {
+    type Error = InvalidContentError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::try_new(value)
+    }
 }
 
-/// A doctype declaration.
+impl TryFrom<&str> for XmlComment {
+    type Error = InvalidContentError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::try_new(value)
+    }
+}
+
+/// A doctype declaration, kept as written between `<!DOCTYPE` and `>`, including any internal subset.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[non_exhaustive]
 pub struct XmlDoctype(pub Vec<u8>);
@@ -454,17 +623,85 @@ impl XmlDoctype {
     }
 }
 
+/// A malformed section of a document.
+///
+/// Lenient parsers produce these in place of the sections they could not parse, so that the rest of a partially broken document can still be inspected. They can neither be serialized nor deserialized from.
+#[cfg(feature = "recovery")]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub struct XmlError {
+    /// The byte offset where the malformed section starts.
+    pub start: usize,
+    /// The byte offset where the malformed section ends.
+    pub end: usize,
+    /// A description of what is wrong with the section.
+    pub message: String,
+}
+
+#[cfg(feature = "recovery")]
+impl XmlError {
+    /// Creates a new error node spanning the given byte range.
+    pub fn new<T: Into<String>>(span: std::ops::Range<usize>, message: T) -> Self {
+        Self {
+            start: span.start,
+            end: span.end,
+            message: message.into(),
+        }
+    }
+
+    /// The byte range of the malformed section.
+    pub fn span(&self) -> std::ops::Range<usize> {
+        self.start..self.end
+    }
+}
+
+/// Error for content that can't be written as the node it is meant for, as returned by [`XmlCData::try_new`] and [`XmlComment::try_new`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum InvalidContentError {
+    /// CDATA sections can't contain `]]>`, since it ends the section.
+    CDataTerminator,
+    /// Comments can't contain `--`.
+    DoubleHyphenInComment,
+    /// Comments can't end with `-`, since it would be followed by the `-->` that ends the comment.
+    CommentEndsWithHyphen,
+    /// Processing instructions can't contain `?>`, since it ends the processing instruction.
+    PiTerminator,
+}
+
+impl fmt::Display for InvalidContentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CDataTerminator => f.write_str("CDATA sections cannot contain `]]>`"),
+            Self::DoubleHyphenInComment => f.write_str("comments cannot contain `--`"),
+            Self::CommentEndsWithHyphen => f.write_str("comments cannot end with `-`"),
+            Self::PiTerminator => f.write_str("processing instructions cannot contain `?>`"),
+        }
+    }
+}
+
+impl std::error::Error for InvalidContentError {}
+
 /// Error type for serializing XML values.
-#[derive(Debug, thiserror::Error)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum XmlValueSerializerError {
     /// Error for when a custom error occurs during serialization.
-    #[error("Custom error: {0}")]
     Custom(String),
     /// Error for when an unexpected serialization occurs.
-    #[error("Unexpected serialization: {0}")]
     UnexpectedSerialize(ser::Unexpected),
 }
 
+impl fmt::Display for XmlValueSerializerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Custom(value) => write!(f, "Custom error: {value}"),
+            Self::UnexpectedSerialize(value) => write!(f, "Unexpected serialization: {value}"),
+        }
+    }
+}
+
+impl std::error::Error for XmlValueSerializerError {}
+
 impl ser::Error for XmlValueSerializerError {
     fn unexpected_serialize(unexpected: ser::Unexpected) -> Self {
         Self::UnexpectedSerialize(unexpected)
@@ -479,16 +716,13 @@ impl ser::Error for XmlValueSerializerError {
 }
 
 /// Error type for deserializing XML values.
-#[derive(Debug, thiserror::Error)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum XmlValueDeserializerError {
     /// Error for when an unexpected visit occurs during deserialization.
-    #[error("Unexpected visit: {0}")]
     UnexpectedVisit(crate::de::Unexpected),
     /// Error for when a custom error occurs during deserialization.
-    #[error("Custom error: {0}")]
     Custom(String),
     /// Error for when a name is expected to be a certain value, but it is not.
-    #[error("Wrong name: {actual:?}, expected: {expected:?}")]
     WrongName {
         /// The actual name that was encountered.
         actual: Box<ExpandedNameBuf>,
@@ -496,23 +730,238 @@ pub enum XmlValueDeserializerError {
         expected: Box<ExpandedNameBuf>,
     },
     /// Error for when a field is missing.
-    #[error("Missing field: {0}")]
     MissingField(String),
     /// Error for when a child cannot be identified, and ignoring it is not allowed.
-    #[error("Unknown child")]
     UnknownChild,
     /// Error for when a string is invalid for the type.
-    #[error("Invalid string")]
     InvalidString,
     /// Error for when a type has no possible variants to deserialize into.
-    #[error("No possible variant")]
     NoPossibleVariant {
         /// The name of the type that has no possible variants.
         ident: String,
     },
     /// Error for when a type is missing data that is required to deserialize it.
-    #[error("Missing data")]
     MissingData,
+    /// Error that occurred within an element or attribute of the value, recording where.
+    AtPath {
+        /// The location of the element or attribute where the error occurred.
+        path: XmlValuePath,
+        /// The error that occurred.
+        error: Box<XmlValueDeserializerError>,
+        /// The error of the last attribute or child of the element that was invalid, if any.
+        ///
+        /// Types usually treat attributes and children that fail to deserialize as absent, so this is often what actually caused the error, such as a child that is reported as missing because it was invalid.
+        cause: Option<Box<XmlValueDeserializerError>>,
+    },
+}
+
+impl fmt::Display for XmlValueDeserializerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedVisit(value) => write!(f, "Unexpected visit: {value}"),
+            Self::Custom(value) => write!(f, "Custom error: {value}"),
+            Self::WrongName { actual, expected } => {
+                write!(f, "Wrong name: {actual:?}, expected: {expected:?}")
+            }
+            Self::MissingField(value) => write!(f, "Missing field: {value}"),
+            Self::UnknownChild => f.write_str("Unknown child"),
+            Self::InvalidString => f.write_str("Invalid string"),
+            Self::NoPossibleVariant { .. } => f.write_str("No possible variant"),
+            Self::MissingData => f.write_str("Missing data"),
+            Self::AtPath { path, error, cause } => write!(
+                f,
+                "{error} at {path}{}",
+                cause
+                    .as_ref()
+                    .map(|cause| format!(", after {cause}"))
+                    .unwrap_or_default()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for XmlValueDeserializerError {}
+
+impl XmlValueDeserializerError {
+    /// Returns the location within the value where the error occurred, if known.
+    pub fn path(&self) -> Option<&XmlValuePath> {
+        match self {
+            Self::AtPath { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Returns the error of the last invalid attribute or child of the element where this error occurred, if any.
+    pub fn cause(&self) -> Option<&XmlValueDeserializerError> {
+        match self {
+            Self::AtPath { cause, .. } => cause.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Follows [`cause`](Self::cause) to the innermost error, which is usually the most precise explanation of what went wrong.
+    pub fn root_cause(&self) -> &XmlValueDeserializerError {
+        let mut error = self;
+        while let Some(cause) = error.cause() {
+            error = cause;
+        }
+        error
+    }
+
+    fn within(self, segment: XmlValuePathSegment, cause: Option<Self>) -> Self {
+        match self {
+            Self::AtPath {
+                mut path,
+                error,
+                cause: inner_cause,
+            } => {
+                let cause = inner_cause.map(|cause| Box::new(cause.within(segment.clone(), None)));
+                path.segments.push(segment);
+                Self::AtPath { path, error, cause }
+            }
+            error => Self::AtPath {
+                cause: cause.map(|cause| Box::new(cause.within(segment.clone(), None))),
+                path: XmlValuePath {
+                    segments: vec![segment],
+                },
+                error: Box::new(error),
+            },
+        }
+    }
+
+    /// Whether the error only means that a node was not the one being looked for, rather than that it was invalid.
+    fn is_mismatch(&self) -> bool {
+        match self {
+            Self::AtPath { error, .. } => error.is_mismatch(),
+            Self::WrongName { .. } | Self::UnexpectedVisit(_) => true,
+            _ => false,
+        }
+    }
+
+    fn at_position(mut self, new_position: usize) -> Self {
+        if let Self::AtPath { path, cause, .. } = &mut self {
+            if let Some(XmlValuePathSegment::Element {
+                position: position @ None,
+                ..
+            }) = path.segments.last_mut()
+            {
+                *position = Some(new_position);
+            }
+            if let Some(inner) = cause.take() {
+                *cause = Some(Box::new(inner.at_position(new_position)));
+            }
+        }
+        self
+    }
+}
+
+/// A location within an [`XmlValue`], written like `/catalog/book[2]/@id`.
+///
+/// Elements are only given a position if they have siblings with the same name.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct XmlValuePath {
+    /// The segments of the path, innermost first.
+    segments: Vec<XmlValuePathSegment>,
+}
+
+impl XmlValuePath {
+    /// Creates a path from its segments, starting from the outermost element.
+    pub(crate) fn from_outermost(mut segments: Vec<XmlValuePathSegment>) -> Self {
+        segments.reverse();
+        Self { segments }
+    }
+
+    /// Returns the segments of the path, starting from the outermost element.
+    pub fn segments(&self) -> impl DoubleEndedIterator<Item = &XmlValuePathSegment> {
+        self.segments.iter().rev()
+    }
+}
+
+impl fmt::Display for XmlValuePath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for segment in self.segments() {
+            write!(f, "/{segment}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A segment of an [`XmlValuePath`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum XmlValuePathSegment {
+    /// An element.
+    Element {
+        /// The name of the element.
+        name: ExpandedNameBuf,
+        /// The 1-based position of the element among its siblings with the same name, if it has any.
+        position: Option<usize>,
+    },
+    /// An attribute.
+    Attribute {
+        /// The name of the attribute.
+        name: ExpandedNameBuf,
+    },
+}
+
+impl fmt::Display for XmlValuePathSegment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Element {
+                name,
+                position: Some(position),
+            } => write!(f, "{name}[{position}]"),
+            Self::Element {
+                name,
+                position: None,
+            } => write!(f, "{name}"),
+            Self::Attribute { name } => write!(f, "@{name}"),
+        }
+    }
+}
+
+/// Returns the path segments of child elements, with positions for those that have siblings with the same name.
+#[cfg(feature = "xsd-validate")]
+pub(crate) fn sibling_segments(children: &[&XmlElement]) -> Vec<XmlValuePathSegment> {
+    let mut counts = BTreeMap::<&ExpandedNameBuf, usize>::new();
+    for child in children {
+        *counts.entry(&child.name).or_default() += 1;
+    }
+
+    let mut positions = BTreeMap::<&ExpandedNameBuf, usize>::new();
+    children
+        .iter()
+        .map(|child| {
+            let position = (counts[&child.name] > 1).then(|| {
+                let position = positions.entry(&child.name).or_default();
+                *position += 1;
+                *position
+            });
+
+            XmlValuePathSegment::Element {
+                name: child.name.clone(),
+                position,
+            }
+        })
+        .collect()
+}
+
+/// Collects the values of a value, with sequences replaced by the values in them.
+pub(crate) fn flatten<'v>(value: &'v XmlValue, nodes: &mut Vec<&'v XmlValue>) {
+    match value {
+        XmlValue::Seq(values) => values.iter().for_each(|value| flatten(value, nodes)),
+        value => nodes.push(value),
+    }
+}
+
+#[cfg(feature = "recovery")]
+impl From<&XmlError> for XmlValueDeserializerError {
+    fn from(value: &XmlError) -> Self {
+        Self::Custom(format!(
+            "cannot deserialize malformed XML at {}..{}: {}",
+            value.start, value.end, value.message
+        ))
+    }
 }
 
 impl de::Error for XmlValueDeserializerError {
@@ -0,0 +1,252 @@
+//! Normalized hashing of [`XmlValue`]s.
+use super::*;
+
+/// Options for [`XmlValue::normalized_hash`], deciding which differences between documents are considered insignificant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct NormalizeOptions {
+    /// Whether to ignore text that only contains whitespace, such as indentation between elements. Whitespace next to other text is always kept.
+    pub ignore_whitespace: bool,
+    /// Whether to ignore the order of attributes on elements.
+    pub ignore_attribute_order: bool,
+    /// Whether to ignore comments.
+    pub ignore_comments: bool,
+}
+
+impl NormalizeOptions {
+    /// Creates the default options, which ignore whitespace-only text and attribute order, but not comments.
+    pub fn new() -> Self {
+        Self {
+            ignore_whitespace: true,
+            ignore_attribute_order: true,
+            ignore_comments: false,
+        }
+    }
+
+    /// Sets whether to ignore text that only contains whitespace.
+    pub fn with_ignore_whitespace(mut self, ignore_whitespace: bool) -> Self {
+        self.ignore_whitespace = ignore_whitespace;
+        self
+    }
+
+    /// Sets whether to ignore the order of attributes on elements.
+    pub fn with_ignore_attribute_order(mut self, ignore_attribute_order: bool) -> Self {
+        self.ignore_attribute_order = ignore_attribute_order;
+        self
+    }
+
+    /// Sets whether to ignore comments.
+    pub fn with_ignore_comments(mut self, ignore_comments: bool) -> Self {
+        self.ignore_comments = ignore_comments;
+        self
+    }
+}
+
+impl Default for NormalizeOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A 64-bit FNV-1a hasher. Unlike [`std::hash::DefaultHasher`], its output is the same across platforms, processes and Rust versions.
+struct Fnv1aHasher(u64);
+
+impl Fnv1aHasher {
+    fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write_tag(&mut self, tag: u8) {
+        self.0 ^= u64::from(tag);
+        self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) {
+        for byte in (bytes.len() as u64)
+            .to_le_bytes()
+            .into_iter()
+            .chain(bytes.iter().copied())
+        {
+            self.write_tag(byte);
+        }
+    }
+
+    fn write_name(&mut self, name: &ExpandedNameBuf) {
+        let name = name.as_ref();
+        self.write_bytes(name.local_name().as_str().as_bytes());
+        match name.namespace() {
+            Some(namespace) => {
+                self.write_tag(1);
+                self.write_bytes(namespace.as_str().as_bytes());
+            }
+            None => self.write_tag(0),
+        }
+    }
+}
+
+mod tag {
+    pub const TEXT: u8 = 1;
+    pub const CDATA: u8 = 2;
+    pub const ELEMENT: u8 = 3;
+    pub const ELEMENT_END: u8 = 4;
+    pub const ATTRIBUTE: u8 = 5;
+    pub const PI: u8 = 6;
+    pub const DECL: u8 = 7;
+    pub const COMMENT: u8 = 8;
+    pub const DOCTYPE: u8 = 9;
+    #[cfg(feature = "recovery")]
+    pub const ERROR: u8 = 10;
+}
+
+struct Normalizer<'a> {
+    options: &'a NormalizeOptions,
+    hasher: Fnv1aHasher,
+    /// Text that has not been hashed yet, so that adjacent text nodes are hashed as one.
+    text: Vec<u8>,
+}
+
+impl Normalizer<'_> {
+    fn text(&mut self, text: &XmlText) {
+        self.text.extend_from_slice(&text.0);
+    }
+
+    /// Hashes the text collected since the last hashed node. Nodes that are not hashed, like ignored comments, don't end the text, so the text around them is hashed as one.
+    fn end_text(&mut self) {
+        let is_whitespace = self
+            .text
+            .iter()
+            .all(|byte| matches!(byte, b' ' | b'\t' | b'\r' | b'\n'));
+        if !(is_whitespace && self.options.ignore_whitespace) {
+            self.hasher.write_tag(tag::TEXT);
+            self.hasher.write_bytes(&self.text);
+        }
+        self.text.clear();
+    }
+
+    fn write_tag(&mut self, tag: u8) {
+        if !self.text.is_empty() {
+            self.end_text();
+        }
+        self.hasher.write_tag(tag);
+    }
+
+    fn comment(&mut self, comment: &XmlComment) {
+        if self.options.ignore_comments {
+            return;
+        }
+        self.write_tag(tag::COMMENT);
+        self.hasher.write_bytes(&comment.0);
+    }
+
+    fn pi(&mut self, pi: &XmlProcessingInstruction) {
+        self.write_tag(tag::PI);
+        self.hasher.write_bytes(&pi.target);
+        self.hasher.write_bytes(&pi.content);
+    }
+
+    fn element(&mut self, element: &XmlElement) {
+        self.write_tag(tag::ELEMENT);
+        self.hasher.write_name(&element.name);
+
+        let mut attributes = element.attributes.iter().collect::<Vec<_>>();
+        if self.options.ignore_attribute_order {
+            attributes.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+        for attribute in attributes {
+            self.write_tag(tag::ATTRIBUTE);
+            self.hasher.write_name(&attribute.name);
+            self.hasher.write_bytes(&attribute.value.0);
+        }
+
+        for child in element.children.values.iter() {
+            self.child(child);
+        }
+        self.write_tag(tag::ELEMENT_END);
+    }
+
+    fn child(&mut self, child: &XmlChild) {
+        match child {
+            XmlChild::Text(text) => self.text(text),
+            XmlChild::CData(cdata) => {
+                self.write_tag(tag::CDATA);
+                self.hasher.write_bytes(&cdata.0);
+            }
+            XmlChild::Element(element) => self.element(element),
+            XmlChild::PI(pi) => self.pi(pi),
+            XmlChild::Comment(comment) => self.comment(comment),
+            #[cfg(feature = "recovery")]
+            XmlChild::Error(error) => {
+                self.write_tag(tag::ERROR);
+                self.hasher.write_bytes(error.message.as_bytes());
+            }
+            XmlChild::None => {}
+        }
+    }
+
+    fn value(&mut self, value: &XmlValue) {
+        match value {
+            XmlValue::Text(text) => self.text(text),
+            XmlValue::CData(cdata) => {
+                self.write_tag(tag::CDATA);
+                self.hasher.write_bytes(&cdata.0);
+            }
+            XmlValue::Element(element) => self.element(element),
+            XmlValue::Seq(seq) => {
+                for value in seq.values.iter() {
+                    self.value(value);
+                }
+            }
+            XmlValue::PI(pi) => self.pi(pi),
+            XmlValue::Decl(decl) => {
+                self.write_tag(tag::DECL);
+                self.hasher.write_bytes(decl.version.as_bytes());
+                self.hasher
+                    .write_bytes(decl.encoding.as_deref().unwrap_or_default().as_bytes());
+                self.hasher
+                    .write_bytes(decl.standalone.as_deref().unwrap_or_default().as_bytes());
+            }
+            XmlValue::Comment(comment) => self.comment(comment),
+            XmlValue::Doctype(doctype) => {
+                self.write_tag(tag::DOCTYPE);
+                self.hasher.write_bytes(&doctype.0);
+            }
+            #[cfg(feature = "recovery")]
+            XmlValue::Error(error) => {
+                self.write_tag(tag::ERROR);
+                self.hasher.write_bytes(error.message.as_bytes());
+            }
+            XmlValue::None => {}
+        }
+    }
+}
+
+impl XmlValue {
+    /// Computes a hash of the value that is the same for documents that only differ in ways the `options` consider insignificant.
+    ///
+    /// Names are compared by namespace and local name, so the prefixes used in a document never affect the hash. Adjacent text nodes are hashed as one, so text split into several nodes hashes like the same text in one node.
+    ///
+    /// The hash is a 64-bit FNV-1a hash, which is stable across platforms, processes and versions of Rust, so it can be used as a key for deduplication and caching. It is not a cryptographic digest and different documents can collide, so values with equal hashes should be compared before treating them as duplicates when that matters.
+    ///
+    /// ```
+    /// use xmlity::value::{NormalizeOptions, XmlValue};
+    /// use xmlity::xml;
+    ///
+    /// let a = XmlValue::from(xml!(<"note" "to"="Tove" "from"="Jani">["Hello"]</"note">));
+    /// let b = XmlValue::from(xml!(<"note" "from"="Jani" "to"="Tove">["Hello"]</"note">));
+    ///
+    /// let options = NormalizeOptions::new();
+    /// assert_eq!(a.normalized_hash(&options), b.normalized_hash(&options));
+    /// ```
+    pub fn normalized_hash(&self, options: &NormalizeOptions) -> u64 {
+        let mut normalizer = Normalizer {
+            options,
+            hasher: Fnv1aHasher::new(),
+            text: Vec::new(),
+        };
+        normalizer.value(self);
+        if !normalizer.text.is_empty() {
+            normalizer.end_text();
+        }
+        normalizer.hasher.0
+    }
+}
@@ -0,0 +1,693 @@
+//! A small parser for XML strings, used to construct [`XmlValue`]s from literal XML without a backend.
+use std::{collections::VecDeque, fmt, str::FromStr};
+
+use crate::{
+    escape::is_xml_char, ExpandedNameBuf, LocalNameBuf, Prefix, PrefixBuf, XmlNamespace,
+    XmlNamespaceBuf,
+};
+
+use super::{
+    XmlAttribute, XmlCData, XmlChild, XmlComment, XmlDecl, XmlDoctype, XmlElement,
+    XmlProcessingInstruction, XmlSeq, XmlText, XmlValue,
+};
+
+/// Error for XML that could not be parsed by [`XmlValue::from_str`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum XmlValueParseError {
+    /// The input ended in the middle of a node.
+    UnexpectedEof,
+    /// A character was found where it is not allowed.
+    UnexpectedChar {
+        /// The byte offset of the character.
+        position: usize,
+        /// The character that was found.
+        found: char,
+    },
+    /// A name is not a valid XML name.
+    InvalidName {
+        /// The byte offset of the name.
+        position: usize,
+        /// The name that was found.
+        name: String,
+    },
+    /// An end tag does not match the start tag it closes.
+    MismatchedEndTag {
+        /// The byte offset of the end tag.
+        position: usize,
+        /// The name of the start tag.
+        expected: String,
+        /// The name of the end tag.
+        found: String,
+    },
+    /// An end tag has no start tag.
+    UnmatchedEndTag {
+        /// The byte offset of the end tag.
+        position: usize,
+        /// The name of the end tag.
+        name: String,
+    },
+    /// An entity reference is not one of the predefined entities or a character reference.
+    UnknownEntity {
+        /// The byte offset of the entity reference.
+        position: usize,
+        /// The name of the entity.
+        name: String,
+    },
+    /// A prefix is used without being declared.
+    UnboundPrefix {
+        /// The byte offset of the name using the prefix.
+        position: usize,
+        /// The prefix that is not bound.
+        prefix: String,
+    },
+    /// A namespace declaration has an invalid namespace.
+    InvalidNamespace {
+        /// The byte offset of the declaration.
+        position: usize,
+        /// The namespace that was found.
+        namespace: String,
+    },
+    /// An XML declaration or doctype is placed inside an element.
+    MisplacedNode {
+        /// The byte offset of the node.
+        position: usize,
+        /// The kind of node.
+        node: &'static str,
+    },
+    /// An element has the same attribute or namespace declaration more than once.
+    DuplicateAttribute {
+        /// The byte offset of the repeated attribute.
+        position: usize,
+        /// The name of the attribute as written.
+        name: String,
+    },
+    /// The elements are nested deeper than the recursion limit of the parser.
+    RecursionLimitExceeded {
+        /// The byte offset of the element that exceeds the limit.
+        position: usize,
+        /// The recursion limit that was exceeded.
+        limit: usize,
+    },
+}
+
+impl fmt::Display for XmlValueParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedEof => f.write_str("unexpected end of input"),
+            Self::UnexpectedChar { position, found } => {
+                write!(f, "unexpected {found:?} at byte {position}")
+            }
+            Self::InvalidName { position, name } => {
+                write!(f, "invalid name {name:?} at byte {position}")
+            }
+            Self::MismatchedEndTag {
+                position,
+                expected,
+                found,
+            } => write!(
+                f,
+                "end tag `</{found}>` at byte {position} does not match start tag `<{expected}>`"
+            ),
+            Self::UnmatchedEndTag { position, name } => {
+                write!(f, "end tag `</{name}>` at byte {position} has no start tag")
+            }
+            Self::UnknownEntity { position, name } => {
+                write!(f, "unknown entity `&{name};` at byte {position}")
+            }
+            Self::UnboundPrefix { position, prefix } => write!(
+                f,
+                "prefix `{prefix}` at byte {position} is not bound to a namespace"
+            ),
+            Self::InvalidNamespace {
+                position,
+                namespace,
+            } => write!(f, "invalid namespace {namespace:?} at byte {position}"),
+            Self::MisplacedNode { position, node } => write!(
+                f,
+                "{node} at byte {position} is only allowed outside of elements"
+            ),
+            Self::DuplicateAttribute { position, name } => {
+                write!(f, "duplicate attribute `{name}` at byte {position}")
+            }
+            Self::RecursionLimitExceeded { position, limit } => write!(
+                f,
+                "element at byte {position} exceeds the recursion limit of {limit} nested elements"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for XmlValueParseError {}
+
+/// Parses XML into an [`XmlValue`].
+///
+/// This makes it possible to construct values from literal XML, for example as the expected value in tests, without depending on a backend. The input can be a complete document or any sequence of nodes. A single node is returned as is, several nodes as an [`XmlValue::Seq`] and empty input as [`XmlValue::None`].
+///
+/// Namespace declarations are resolved into the names of elements and attributes and are not kept as attributes. Text that only consists of whitespace is skipped, while other text is kept exactly as written apart from resolving entity references. Only the predefined entities and character references are supported, and any internal subset of a doctype, including conditional sections, is kept as-is without being interpreted. Adjacent CDATA sections that were split to escape a `]]>` in them, by ending a section after the `]]` and starting the next with the `>`, are joined into one.
+///
+/// Characters that XML doesn't allow, including in character references, are rejected, as are duplicate attributes, `]]>` in text, `--` in comments and `<` in attribute values. Unlike a full XML parser, line breaks and attribute values are not normalized.
+///
+/// Elements can be nested at most [`XmlValue::DEFAULT_RECURSION_LIMIT`] deep, see [`XmlValue::from_str_with_recursion_limit`].
+///
+/// ```
+/// use xmlity::value::{XmlElement, XmlText, XmlValue};
+/// use xmlity::LocalNameBuf;
+///
+/// let value: XmlValue = "<note><to>Tove</to></note>".parse().unwrap();
+///
+/// let expected = XmlElement::new(LocalNameBuf::new("note".to_string()).unwrap())
+///     .with_child(
+///         XmlElement::new(LocalNameBuf::new("to".to_string()).unwrap())
+///             .with_child(XmlText::new("Tove")),
+///     );
+/// assert_eq!(value, XmlValue::Element(expected));
+/// ```
+impl FromStr for XmlValue {
+    type Err = XmlValueParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_str_with_recursion_limit(s, Some(Self::DEFAULT_RECURSION_LIMIT))
+    }
+}
+
+impl XmlValue {
+    /// The default maximum number of nested elements when parsing, see [`XmlValue::from_str_with_recursion_limit`].
+    pub const DEFAULT_RECURSION_LIMIT: usize = 128;
+
+    /// Parses XML into an [`XmlValue`] like [`XmlValue::from_str`], with at most `recursion_limit` nested elements, or without a limit if it is `None`.
+    ///
+    /// The parser uses more stack the deeper the elements are nested, so without a limit, deeply nested input can overflow the stack and abort the process. With a limit, such input instead fails with [`XmlValueParseError::RecursionLimitExceeded`]. Only disable the limit for trusted input.
+    ///
+    /// ```
+    /// use xmlity::value::{XmlValue, XmlValueParseError};
+    ///
+    /// let xml = "<a><b><c/></b></a>";
+    /// assert!(XmlValue::from_str_with_recursion_limit(xml, Some(3)).is_ok());
+    /// assert_eq!(
+    ///     XmlValue::from_str_with_recursion_limit(xml, Some(2)),
+    ///     Err(XmlValueParseError::RecursionLimitExceeded { position: 6, limit: 2 })
+    /// );
+    /// ```
+    pub fn from_str_with_recursion_limit(
+        s: &str,
+        recursion_limit: Option<usize>,
+    ) -> Result<Self, XmlValueParseError> {
+        if let Some((position, found)) = s.char_indices().find(|(_, c)| !is_xml_char(*c)) {
+            return Err(XmlValueParseError::UnexpectedChar { position, found });
+        }
+
+        let mut parser = Parser {
+            input: s,
+            position: 0,
+            scopes: Vec::new(),
+            recursion_limit,
+        };
+        let mut nodes = parser.parse_top_level()?;
+
+        Ok(match nodes.len() {
+            0 => XmlValue::None,
+            1 => nodes.pop_front().expect("one node"),
+            _ => XmlValue::Seq(XmlSeq::from_vec_deque(nodes)),
+        })
+    }
+}
+
+/// Compares the value with the value parsed from the string, as by [`XmlValue::from_str`]. Strings that can't be parsed are not equal to any value.
+impl PartialEq<str> for XmlValue {
+    fn eq(&self, other: &str) -> bool {
+        other.parse::<XmlValue>().is_ok_and(|other| *self == other)
+    }
+}
+
+impl PartialEq<&str> for XmlValue {
+    fn eq(&self, other: &&str) -> bool {
+        *self == **other
+    }
+}
+
+enum Node {
+    Value(XmlValue),
+    End { name: String, position: usize },
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+    scopes: Vec<Vec<(Option<PrefixBuf>, Option<XmlNamespaceBuf>)>>,
+    recursion_limit: Option<usize>,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.input[self.position..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn eat(&mut self, prefix: &str) -> bool {
+        if self.rest().starts_with(prefix) {
+            self.position += prefix.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, prefix: &str) -> Result<(), XmlValueParseError> {
+        if self.eat(prefix) {
+            return Ok(());
+        }
+        match self.peek() {
+            Some(found) => Err(XmlValueParseError::UnexpectedChar {
+                position: self.position,
+                found,
+            }),
+            None => Err(XmlValueParseError::UnexpectedEof),
+        }
+    }
+
+    fn skip_whitespace(&mut self) -> bool {
+        let rest = self.rest();
+        let trimmed = rest.trim_start_matches(is_xml_whitespace);
+        self.position += rest.len() - trimmed.len();
+        rest.len() != trimmed.len()
+    }
+
+    /// Returns everything up to `terminator`, consuming the terminator.
+    fn take_until(&mut self, terminator: &str) -> Result<&'a str, XmlValueParseError> {
+        let rest = self.rest();
+        let end = rest
+            .find(terminator)
+            .ok_or(XmlValueParseError::UnexpectedEof)?;
+        self.position += end + terminator.len();
+        Ok(&rest[..end])
+    }
+
+    fn take_name(&mut self) -> Result<(usize, &'a str), XmlValueParseError> {
+        let start = self.position;
+        let rest = self.rest();
+        let end = rest
+            .find(|c: char| is_xml_whitespace(c) || matches!(c, '/' | '>' | '=' | '?'))
+            .unwrap_or(rest.len());
+        if end == 0 {
+            return Err(match self.peek() {
+                Some(found) => XmlValueParseError::UnexpectedChar {
+                    position: start,
+                    found,
+                },
+                None => XmlValueParseError::UnexpectedEof,
+            });
+        }
+        self.position += end;
+        Ok((start, &rest[..end]))
+    }
+
+    fn parse_top_level(&mut self) -> Result<VecDeque<XmlValue>, XmlValueParseError> {
+        let mut nodes = VecDeque::new();
+        while self.position < self.input.len() {
+            match self.parse_node(true)? {
+                Some(Node::Value(value)) => nodes.push_back(value),
+                Some(Node::End { name, position }) => {
+                    return Err(XmlValueParseError::UnmatchedEndTag { position, name })
+                }
+                None => {}
+            }
+        }
+        Ok(nodes)
+    }
+
+    /// Parses the next node, returning [`None`] for text that only consists of whitespace.
+    fn parse_node(&mut self, top_level: bool) -> Result<Option<Node>, XmlValueParseError> {
+        let start = self.position;
+
+        if self.eat("<!--") {
+            let comment = self.take_until("-->")?;
+            let double_hyphen = comment
+                .find("--")
+                .map(|offset| offset + 1)
+                .or_else(|| comment.ends_with('-').then_some(comment.len()));
+            if let Some(offset) = double_hyphen {
+                return Err(XmlValueParseError::UnexpectedChar {
+                    position: start + "<!--".len() + offset,
+                    found: '-',
+                });
+            }
+            return Ok(Some(Node::Value(XmlValue::Comment(XmlComment::new(
+                comment,
+            )))));
+        }
+
+        if self.eat("<![CDATA[") {
+            let mut cdata = self.take_until("]]>")?.to_owned();
+            // Join sections that were split to escape a `]]>`, like the quick-xml backend does.
+            while cdata.ends_with("]]") && self.rest().starts_with("<![CDATA[>") {
+                self.position += "<![CDATA[".len();
+                cdata.push_str(self.take_until("]]>")?);
+            }
+            return Ok(Some(Node::Value(XmlValue::CData(XmlCData::new(cdata)))));
+        }
+
+        if self.eat("<!DOCTYPE") {
+            if !top_level {
+                return Err(XmlValueParseError::MisplacedNode {
+                    position: start,
+                    node: "doctype",
+                });
+            }
+            let doctype = self.take_doctype()?;
+            return Ok(Some(Node::Value(XmlValue::Doctype(XmlDoctype::new(
+                doctype.trim(),
+            )))));
+        }
+
+        if self.eat("<?") {
+            let (_, target) = self.take_name()?;
+            let target = target.to_owned();
+            self.skip_whitespace();
+            let content = self.take_until("?>")?;
+
+            if target == "xml" {
+                if !top_level {
+                    return Err(XmlValueParseError::MisplacedNode {
+                        position: start,
+                        node: "XML declaration",
+                    });
+                }
+                let decl = parse_decl(content, start)?;
+                return Ok(Some(Node::Value(XmlValue::Decl(decl))));
+            }
+
+            return Ok(Some(Node::Value(XmlValue::PI(
+                XmlProcessingInstruction::new(target, content.trim_end()),
+            ))));
+        }
+
+        if self.eat("</") {
+            let (_, name) = self.take_name()?;
+            let name = name.to_owned();
+            self.skip_whitespace();
+            self.expect(">")?;
+            return Ok(Some(Node::End {
+                name,
+                position: start,
+            }));
+        }
+
+        if self.eat("<") {
+            return self
+                .parse_element()
+                .map(|element| Some(Node::Value(XmlValue::Element(element))));
+        }
+
+        let end = self.rest().find('<').unwrap_or(self.rest().len());
+        let text = &self.input[start..start + end];
+        self.position += end;
+        if let Some(offset) = text.find("]]>") {
+            return Err(XmlValueParseError::UnexpectedChar {
+                position: start + offset + 2,
+                found: '>',
+            });
+        }
+        if text.chars().all(is_xml_whitespace) {
+            return Ok(None);
+        }
+
+        Ok(Some(Node::Value(XmlValue::Text(XmlText::new(unescape(
+            text, start,
+        )?)))))
+    }
+
+    fn take_doctype(&mut self) -> Result<&'a str, XmlValueParseError> {
+        let start = self.position;
+        let len = XmlDoctype::declaration_len(self.rest().as_bytes())
+            .ok_or(XmlValueParseError::UnexpectedEof)?;
+        self.position += len;
+        Ok(&self.input[start..start + len - 1])
+    }
+
+    fn parse_element(&mut self) -> Result<XmlElement, XmlValueParseError> {
+        // Every open element has a scope, so their number is the depth of the parent of this element.
+        if let Some(limit) = self.recursion_limit {
+            if self.scopes.len() >= limit {
+                return Err(XmlValueParseError::RecursionLimitExceeded {
+                    position: self.position - 1,
+                    limit,
+                });
+            }
+        }
+
+        let (name_position, qname) = self.take_name()?;
+        let qname = qname.to_owned();
+
+        let mut raw_attributes = Vec::new();
+        let mut bindings = Vec::new();
+        let empty = loop {
+            let had_whitespace = self.skip_whitespace();
+            if self.eat("/>") {
+                break true;
+            }
+            if self.eat(">") {
+                break false;
+            }
+            if !had_whitespace {
+                return Err(match self.peek() {
+                    Some(found) => XmlValueParseError::UnexpectedChar {
+                        position: self.position,
+                        found,
+                    },
+                    None => XmlValueParseError::UnexpectedEof,
+                });
+            }
+
+            let (position, name) = self.take_name()?;
+            let name = name.to_owned();
+            self.skip_whitespace();
+            self.expect("=")?;
+            self.skip_whitespace();
+            let quote = match self.peek() {
+                Some(quote @ ('"' | '\'')) => quote,
+                Some(found) => {
+                    return Err(XmlValueParseError::UnexpectedChar {
+                        position: self.position,
+                        found,
+                    })
+                }
+                None => return Err(XmlValueParseError::UnexpectedEof),
+            };
+            self.position += 1;
+            let value_position = self.position;
+            let raw_value = self.take_until(&quote.to_string())?;
+            if let Some(offset) = raw_value.find('<') {
+                return Err(XmlValueParseError::UnexpectedChar {
+                    position: value_position + offset,
+                    found: '<',
+                });
+            }
+            let value = unescape(raw_value, value_position)?;
+
+            let binding = if name == "xmlns" {
+                Some(None)
+            } else {
+                name.strip_prefix("xmlns:").map(Some)
+            };
+            match binding {
+                Some(prefix) => {
+                    let prefix = prefix
+                        .map(|prefix| parse_prefix(prefix, position))
+                        .transpose()?;
+                    let namespace = if value.is_empty() {
+                        None
+                    } else {
+                        Some(XmlNamespaceBuf::new(value.clone()).map_err(|_| {
+                            XmlValueParseError::InvalidNamespace {
+                                position,
+                                namespace: value,
+                            }
+                        })?)
+                    };
+                    if bindings.iter().any(|(bound, _)| *bound == prefix) {
+                        return Err(XmlValueParseError::DuplicateAttribute { position, name });
+                    }
+                    bindings.push((prefix, namespace));
+                }
+                None => raw_attributes.push((position, name, value)),
+            }
+        };
+
+        self.scopes.push(bindings);
+
+        let name = self.resolve(&qname, name_position, true)?;
+        let mut attributes: Vec<XmlAttribute> = Vec::with_capacity(raw_attributes.len());
+        for (position, qname, value) in raw_attributes {
+            let name = self.resolve(&qname, position, false)?;
+            // Attributes with different prefixes bound to the same namespace are duplicates too.
+            if attributes.iter().any(|attribute| attribute.name == name) {
+                return Err(XmlValueParseError::DuplicateAttribute {
+                    position,
+                    name: qname,
+                });
+            }
+            attributes.push(XmlAttribute::new(name, value));
+        }
+
+        let mut element = XmlElement::new(name).with_attributes(attributes);
+
+        if !empty {
+            loop {
+                if self.position >= self.input.len() {
+                    return Err(XmlValueParseError::UnexpectedEof);
+                }
+                match self.parse_node(false)? {
+                    Some(Node::Value(value)) => element.children.values.push_back(match value {
+                        XmlValue::Text(text) => XmlChild::Text(text),
+                        XmlValue::CData(cdata) => XmlChild::CData(cdata),
+                        XmlValue::Element(element) => XmlChild::Element(element),
+                        XmlValue::PI(pi) => XmlChild::PI(pi),
+                        XmlValue::Comment(comment) => XmlChild::Comment(comment),
+                        _ => unreachable!("only child nodes are parsed inside elements"),
+                    }),
+                    Some(Node::End { name, position }) => {
+                        if name != qname {
+                            return Err(XmlValueParseError::MismatchedEndTag {
+                                position,
+                                expected: qname,
+                                found: name,
+                            });
+                        }
+                        break;
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        self.scopes.pop();
+
+        Ok(element)
+    }
+
+    /// Resolves a qualified name against the namespace declarations in scope. Unprefixed attributes are never in a namespace.
+    fn resolve(
+        &self,
+        qname: &str,
+        position: usize,
+        is_element: bool,
+    ) -> Result<ExpandedNameBuf, XmlValueParseError> {
+        let (prefix, local_name) = match qname.split_once(':') {
+            Some((prefix, local_name)) => (Some(parse_prefix(prefix, position)?), local_name),
+            None => (None, qname),
+        };
+        let local_name = LocalNameBuf::new(local_name.to_owned()).map_err(|_| {
+            XmlValueParseError::InvalidName {
+                position,
+                name: qname.to_owned(),
+            }
+        })?;
+
+        let namespace = match &prefix {
+            None if !is_element => None,
+            Some(prefix) if prefix.as_str() == "xml" => Some(XmlNamespace::XML.to_owned()),
+            prefix => {
+                let bound = self
+                    .scopes
+                    .iter()
+                    .rev()
+                    .flatten()
+                    .find(|(bound, _)| bound == prefix);
+                match (bound, prefix) {
+                    (Some((_, namespace)), _) => namespace.clone(),
+                    (None, None) => None,
+                    (None, Some(prefix)) => {
+                        return Err(XmlValueParseError::UnboundPrefix {
+                            position,
+                            prefix: prefix.to_string(),
+                        })
+                    }
+                }
+            }
+        };
+
+        Ok(ExpandedNameBuf::new(local_name, namespace))
+    }
+}
+
+fn is_xml_whitespace(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\r' | '\n')
+}
+
+fn parse_prefix(prefix: &str, position: usize) -> Result<PrefixBuf, XmlValueParseError> {
+    Prefix::new(prefix)
+        .map(Prefix::into_owned)
+        .map_err(|_| XmlValueParseError::InvalidName {
+            position,
+            name: prefix.to_owned(),
+        })
+}
+
+fn parse_decl(content: &str, position: usize) -> Result<XmlDecl, XmlValueParseError> {
+    let mut version = None;
+    let mut encoding = None;
+    let mut standalone = None;
+
+    let mut rest = content.trim();
+    while !rest.is_empty() {
+        let invalid = || XmlValueParseError::InvalidName {
+            position,
+            name: rest.to_owned(),
+        };
+        let (name, value) = rest.split_once('=').ok_or_else(invalid)?;
+        let value = value.trim_start();
+        let quote = value.chars().next().filter(|c| matches!(c, '"' | '\''));
+        let quote = quote.ok_or_else(invalid)?;
+        let (value, tail) = value[1..].split_once(quote).ok_or_else(invalid)?;
+
+        match name.trim() {
+            "version" => version = Some(value),
+            "encoding" => encoding = Some(value),
+            "standalone" => standalone = Some(value),
+            _ => return Err(invalid()),
+        }
+        rest = tail.trim_start();
+    }
+
+    Ok(XmlDecl::new(version.unwrap_or("1.0"), encoding, standalone))
+}
+
+fn unescape(text: &str, position: usize) -> Result<String, XmlValueParseError> {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        let entity_position = position + (text.len() - rest.len()) + start;
+        let end = rest[start..]
+            .find(';')
+            .ok_or(XmlValueParseError::UnexpectedEof)?;
+        let name = &rest[start + 1..start + end];
+
+        let resolved = match name {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => name
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| name.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32)
+                .filter(|c| is_xml_char(*c)),
+        };
+        result.push(resolved.ok_or_else(|| XmlValueParseError::UnknownEntity {
+            position: entity_position,
+            name: name.to_owned(),
+        })?);
+
+        rest = &rest[start + end + 1..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
@@ -0,0 +1,1424 @@
+//! This module contains [`Query`], which selects nodes of an [`XmlValue`] with a subset of XPath 1.0.
+//!
+//! Queries are also used by the rules of the schemas in `schema::schematron`, enabled with the `schematron` feature.
+use core::{fmt, str::FromStr};
+
+use crate::{ExpandedNameBuf, LocalNameBuf, XmlNamespaceBuf};
+
+use super::{flatten, XmlAttribute, XmlCData, XmlChild, XmlElement, XmlText, XmlValue};
+#[cfg(feature = "schematron")]
+use super::{sibling_segments, XmlValuePath, XmlValuePathSegment};
+
+/// A query that selects nodes of a document or computes a value from them, written in a subset of XPath 1.0.
+///
+/// Location paths can use all axes except `following`, `preceding` and `namespace`, along with the `//`, `.`, `..` and `@` abbreviations. Steps test names, `*`, `text()` and `node()`, and can be filtered by predicates. Expressions can use unions, `or`, `and`, comparisons, arithmetic, literals and the functions of XPath 1.0 except `id` and `lang`, as well as `ends-with`.
+///
+/// Names are written the way [`ExpandedNameBuf`] displays them: a name without a namespace as `book`, and a name in a namespace as `{http://example.com/ns}book`, or `{http://example.com/ns}*` for any name in the namespace. Prefixes and variables are not supported.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    source: String,
+    expr: Expr,
+}
+
+impl Query {
+    /// Parses a query.
+    pub fn new(source: &str) -> Result<Self, QueryError> {
+        let mut parser = Parser {
+            tokens: lex(source)?,
+            index: 0,
+            end: source.len(),
+        };
+        let expr = parser.expr()?;
+        if let Some((position, _)) = parser.tokens.get(parser.index) {
+            return Err(QueryError::new(*position, "expected the end of the query"));
+        }
+
+        Ok(Self {
+            source: source.to_string(),
+            expr,
+        })
+    }
+
+    /// Selects the nodes of a value in document order, evaluating the query with the document as the context node.
+    ///
+    /// Queries that do not result in nodes, like `count(//book) > 1`, select nothing.
+    pub fn select<'v>(&self, value: &'v XmlValue) -> Vec<QueryNode<'v>> {
+        let document = Document::new(value);
+        match document.evaluate(&self.expr, Context::new(0)) {
+            Value::Nodes(nodes) => nodes
+                .into_iter()
+                .map(|node| document.nodes[node].node)
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Selects the nodes the query matches anywhere in a document, like a pattern of XSLT.
+    ///
+    /// Absolute queries are evaluated once from the document node, other queries from every node.
+    #[cfg(feature = "schematron")]
+    pub(crate) fn matches(&self, document: &Document<'_>) -> Vec<usize> {
+        if self.expr.is_absolute() {
+            return document.nodes_of(document.evaluate(&self.expr, Context::new(0)));
+        }
+
+        let mut matches: Vec<usize> = (0..document.nodes.len())
+            .flat_map(|node| document.nodes_of(document.evaluate(&self.expr, Context::new(node))))
+            .collect();
+        matches.sort_unstable();
+        matches.dedup();
+        matches
+    }
+
+    /// Evaluates the query as a boolean with a node as the context node.
+    #[cfg(feature = "schematron")]
+    pub(crate) fn test(&self, document: &Document<'_>, node: usize) -> bool {
+        boolean(&document.evaluate(&self.expr, Context::new(node)))
+    }
+}
+
+impl FromStr for Query {
+    type Err = QueryError;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        Self::new(source)
+    }
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+/// An error in the text of a [`Query`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryError {
+    position: usize,
+    reason: String,
+}
+
+impl QueryError {
+    fn new(position: usize, reason: impl Into<String>) -> Self {
+        Self {
+            position,
+            reason: reason.into(),
+        }
+    }
+
+    /// Returns the byte offset in the query where the error was found.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid query at position {}: {}",
+            self.position, self.reason
+        )
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+/// A node selected by a [`Query`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub enum QueryNode<'v> {
+    /// The document itself, selected by `/`.
+    Document(&'v XmlValue),
+    /// An element.
+    Element(&'v XmlElement),
+    /// An attribute.
+    Attribute(&'v XmlAttribute),
+    /// A text node.
+    Text(&'v XmlText),
+    /// A CDATA section, which is a text node to queries.
+    CData(&'v XmlCData),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Axis {
+    Ancestor,
+    AncestorOrSelf,
+    Attribute,
+    Child,
+    Descendant,
+    DescendantOrSelf,
+    FollowingSibling,
+    Parent,
+    PrecedingSibling,
+    SelfNode,
+}
+
+impl Axis {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "ancestor" => Self::Ancestor,
+            "ancestor-or-self" => Self::AncestorOrSelf,
+            "attribute" => Self::Attribute,
+            "child" => Self::Child,
+            "descendant" => Self::Descendant,
+            "descendant-or-self" => Self::DescendantOrSelf,
+            "following-sibling" => Self::FollowingSibling,
+            "parent" => Self::Parent,
+            "preceding-sibling" => Self::PrecedingSibling,
+            "self" => Self::SelfNode,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum NameTest {
+    Any,
+    Namespace(XmlNamespaceBuf),
+    Name(ExpandedNameBuf),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum NodeTest {
+    Name(NameTest),
+    Text,
+    Node,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Or,
+    And,
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    Plus,
+    Minus,
+    Multiply,
+    Div,
+    Mod,
+    Union,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Function {
+    Last,
+    Position,
+    Count,
+    LocalName,
+    NamespaceUri,
+    Name,
+    String,
+    Concat,
+    StartsWith,
+    EndsWith,
+    Contains,
+    SubstringBefore,
+    SubstringAfter,
+    Substring,
+    StringLength,
+    NormalizeSpace,
+    Translate,
+    Boolean,
+    Not,
+    True,
+    False,
+    Number,
+    Sum,
+    Floor,
+    Ceiling,
+    Round,
+}
+
+impl Function {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "last" => Self::Last,
+            "position" => Self::Position,
+            "count" => Self::Count,
+            "local-name" => Self::LocalName,
+            "namespace-uri" => Self::NamespaceUri,
+            "name" => Self::Name,
+            "string" => Self::String,
+            "concat" => Self::Concat,
+            "starts-with" => Self::StartsWith,
+            "ends-with" => Self::EndsWith,
+            "contains" => Self::Contains,
+            "substring-before" => Self::SubstringBefore,
+            "substring-after" => Self::SubstringAfter,
+            "substring" => Self::Substring,
+            "string-length" => Self::StringLength,
+            "normalize-space" => Self::NormalizeSpace,
+            "translate" => Self::Translate,
+            "boolean" => Self::Boolean,
+            "not" => Self::Not,
+            "true" => Self::True,
+            "false" => Self::False,
+            "number" => Self::Number,
+            "sum" => Self::Sum,
+            "floor" => Self::Floor,
+            "ceiling" => Self::Ceiling,
+            "round" => Self::Round,
+            _ => return None,
+        })
+    }
+
+    /// The minimum and maximum number of arguments.
+    fn arity(self) -> (usize, usize) {
+        match self {
+            Self::Last | Self::Position | Self::True | Self::False => (0, 0),
+            Self::LocalName
+            | Self::NamespaceUri
+            | Self::Name
+            | Self::String
+            | Self::StringLength
+            | Self::NormalizeSpace
+            | Self::Number => (0, 1),
+            Self::Count
+            | Self::Boolean
+            | Self::Not
+            | Self::Sum
+            | Self::Floor
+            | Self::Ceiling
+            | Self::Round => (1, 1),
+            Self::StartsWith
+            | Self::EndsWith
+            | Self::Contains
+            | Self::SubstringBefore
+            | Self::SubstringAfter => (2, 2),
+            Self::Substring => (2, 3),
+            Self::Translate => (3, 3),
+            Self::Concat => (2, usize::MAX),
+        }
+    }
+
+    /// Whether the arguments must be node sets.
+    fn takes_nodes(self) -> bool {
+        matches!(
+            self,
+            Self::Count | Self::Sum | Self::LocalName | Self::NamespaceUri | Self::Name
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Binary(Operator, Box<Expr>, Box<Expr>),
+    Negate(Box<Expr>),
+    Path(Start, Vec<Step>),
+    Filter(Box<Expr>, Vec<Expr>),
+    Literal(String),
+    Number(f64),
+    Call(Function, Vec<Expr>),
+}
+
+impl Expr {
+    /// Whether the expression results in nodes, which is known when the query is parsed.
+    fn is_node_set(&self) -> bool {
+        match self {
+            Self::Binary(Operator::Union, _, _) | Self::Path(_, _) => true,
+            Self::Filter(expr, _) => expr.is_node_set(),
+            _ => false,
+        }
+    }
+
+    #[cfg(feature = "schematron")]
+    fn is_absolute(&self) -> bool {
+        match self {
+            Self::Binary(Operator::Union, left, right) => left.is_absolute() && right.is_absolute(),
+            Self::Path(Start::Root, _) => true,
+            Self::Path(Start::Expr(expr), _) | Self::Filter(expr, _) => expr.is_absolute(),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Start {
+    Context,
+    Root,
+    Expr(Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Step {
+    axis: Axis,
+    test: NodeTest,
+    predicates: Vec<Expr>,
+}
+
+impl Step {
+    fn descendant_or_self() -> Self {
+        Self {
+            axis: Axis::DescendantOrSelf,
+            test: NodeTest::Node,
+            predicates: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Slash,
+    DoubleSlash,
+    Dot,
+    DotDot,
+    At,
+    Comma,
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Operator(Operator),
+    NameTest(NameTest),
+    NodeType(NodeTest),
+    Function(String),
+    Axis(Axis),
+    Literal(String),
+    Number(f64),
+}
+
+impl Token {
+    /// Whether a `*` or a name after the token is an operator, following the lexical rules of XPath.
+    fn precedes_operator(&self) -> bool {
+        !matches!(
+            self,
+            Self::Slash
+                | Self::DoubleSlash
+                | Self::At
+                | Self::Comma
+                | Self::LParen
+                | Self::LBracket
+                | Self::Operator(_)
+                | Self::Axis(_)
+        )
+    }
+
+    fn starts_step(&self) -> bool {
+        matches!(
+            self,
+            Self::Dot
+                | Self::DotDot
+                | Self::At
+                | Self::Axis(_)
+                | Self::NameTest(_)
+                | Self::NodeType(_)
+        )
+    }
+}
+
+fn is_name_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_'
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '.')
+}
+
+fn lex(source: &str) -> Result<Vec<(usize, Token)>, QueryError> {
+    let mut tokens: Vec<(usize, Token)> = Vec::new();
+    let mut chars = source.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        let operator_expected = tokens
+            .last()
+            .is_some_and(|(_, token)| token.precedes_operator());
+        let rest = &source[start..];
+        let (token, length) = match c {
+            '/' if rest.starts_with("//") => (Token::DoubleSlash, 2),
+            '/' => (Token::Slash, 1),
+            '.' if rest.starts_with("..") => (Token::DotDot, 2),
+            '.' if !rest[1..].starts_with(|c: char| c.is_ascii_digit()) => (Token::Dot, 1),
+            '@' => (Token::At, 1),
+            ',' => (Token::Comma, 1),
+            '(' => (Token::LParen, 1),
+            ')' => (Token::RParen, 1),
+            '[' => (Token::LBracket, 1),
+            ']' => (Token::RBracket, 1),
+            '|' => (Token::Operator(Operator::Union), 1),
+            '+' => (Token::Operator(Operator::Plus), 1),
+            '-' => (Token::Operator(Operator::Minus), 1),
+            '=' => (Token::Operator(Operator::Eq), 1),
+            '!' if rest.starts_with("!=") => (Token::Operator(Operator::NotEq), 2),
+            '<' if rest.starts_with("<=") => (Token::Operator(Operator::LtEq), 2),
+            '<' => (Token::Operator(Operator::Lt), 1),
+            '>' if rest.starts_with(">=") => (Token::Operator(Operator::GtEq), 2),
+            '>' => (Token::Operator(Operator::Gt), 1),
+            '*' if operator_expected => (Token::Operator(Operator::Multiply), 1),
+            '*' => (Token::NameTest(NameTest::Any), 1),
+            '"' | '\'' => {
+                let Some(end) = rest[1..].find(c) else {
+                    return Err(QueryError::new(start, "unterminated literal"));
+                };
+                (Token::Literal(rest[1..=end].to_string()), end + 2)
+            }
+            '0'..='9' | '.' => {
+                let digits = |text: &str| {
+                    text.find(|c: char| !c.is_ascii_digit())
+                        .unwrap_or(text.len())
+                };
+                let mut length = digits(rest);
+                if rest[length..].starts_with('.') {
+                    length += 1 + digits(&rest[length + 1..]);
+                }
+                // The number is made of digits and at most one period, which always parses.
+                let number = rest[..length].parse().unwrap_or(f64::NAN);
+                (Token::Number(number), length)
+            }
+            '{' => {
+                let Some(end) = rest.find('}') else {
+                    return Err(QueryError::new(start, "missing closing brace"));
+                };
+                let namespace = XmlNamespaceBuf::new(rest[1..end].to_string())
+                    .map_err(|_| QueryError::new(start, "invalid namespace"))?;
+                let local = &rest[end + 1..];
+                if local.starts_with('*') {
+                    (Token::NameTest(NameTest::Namespace(namespace)), end + 2)
+                } else {
+                    let length = local.find(|c| !is_name_char(c)).unwrap_or(local.len());
+                    let local_name = LocalNameBuf::new(local[..length].to_string())
+                        .map_err(|_| QueryError::new(start, "invalid name"))?;
+                    (
+                        Token::NameTest(NameTest::Name(ExpandedNameBuf::new(
+                            local_name,
+                            Some(namespace),
+                        ))),
+                        end + 1 + length,
+                    )
+                }
+            }
+            c if is_name_start(c) => {
+                let length = rest.find(|c| !is_name_char(c)).unwrap_or(rest.len());
+                let name = &rest[..length];
+                let after = &rest[length..];
+                let next = after.trim_start();
+
+                if operator_expected {
+                    let operator = match name {
+                        "or" => Operator::Or,
+                        "and" => Operator::And,
+                        "div" => Operator::Div,
+                        "mod" => Operator::Mod,
+                        _ => return Err(QueryError::new(start, "expected an operator")),
+                    };
+                    (Token::Operator(operator), length)
+                } else if next.starts_with("::") {
+                    let axis = Axis::parse(name).ok_or_else(|| {
+                        QueryError::new(start, format!("unsupported axis {name:?}"))
+                    })?;
+                    (Token::Axis(axis), rest.len() - next.len() + 2)
+                } else if after.starts_with(':') {
+                    return Err(QueryError::new(
+                        start,
+                        "prefixes are not supported, write names in a namespace as {namespace}name",
+                    ));
+                } else if next.starts_with('(') {
+                    match name {
+                        "text" => (Token::NodeType(NodeTest::Text), length),
+                        "node" => (Token::NodeType(NodeTest::Node), length),
+                        "comment" | "processing-instruction" => {
+                            return Err(QueryError::new(
+                                start,
+                                format!("the node test {name}() is not supported"),
+                            ))
+                        }
+                        _ => (Token::Function(name.to_string()), length),
+                    }
+                } else {
+                    let local_name = LocalNameBuf::new(name.to_string())
+                        .map_err(|_| QueryError::new(start, "invalid name"))?;
+                    (
+                        Token::NameTest(NameTest::Name(ExpandedNameBuf::new(local_name, None))),
+                        length,
+                    )
+                }
+            }
+            '$' => return Err(QueryError::new(start, "variables are not supported")),
+            c => {
+                return Err(QueryError::new(
+                    start,
+                    format!("unexpected character {c:?}"),
+                ))
+            }
+        };
+
+        tokens.push((start, token));
+        while chars
+            .peek()
+            .is_some_and(|&(index, _)| index < start + length)
+        {
+            chars.next();
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    index: usize,
+    /// The length of the query, which is the position of errors at its end.
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index).map(|(_, token)| token)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens
+            .get(self.index)
+            .map_or(self.end, |(position, _)| *position)
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        let found = self.peek() == Some(token);
+        if found {
+            self.index += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, token: &Token, description: &str) -> Result<(), QueryError> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(QueryError::new(
+                self.position(),
+                format!("expected {description}"),
+            ))
+        }
+    }
+
+    fn eat_operator(&mut self, operators: &[Operator]) -> Option<Operator> {
+        match self.peek() {
+            Some(Token::Operator(operator)) if operators.contains(operator) => {
+                let operator = *operator;
+                self.index += 1;
+                Some(operator)
+            }
+            _ => None,
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, QueryError> {
+        self.binary(&[Operator::Or], Self::and)
+    }
+
+    fn and(&mut self) -> Result<Expr, QueryError> {
+        self.binary(&[Operator::And], Self::equality)
+    }
+
+    fn equality(&mut self) -> Result<Expr, QueryError> {
+        self.binary(&[Operator::Eq, Operator::NotEq], Self::relational)
+    }
+
+    fn relational(&mut self) -> Result<Expr, QueryError> {
+        self.binary(
+            &[Operator::Lt, Operator::LtEq, Operator::Gt, Operator::GtEq],
+            Self::additive,
+        )
+    }
+
+    fn additive(&mut self) -> Result<Expr, QueryError> {
+        self.binary(&[Operator::Plus, Operator::Minus], Self::multiplicative)
+    }
+
+    fn multiplicative(&mut self) -> Result<Expr, QueryError> {
+        self.binary(
+            &[Operator::Multiply, Operator::Div, Operator::Mod],
+            Self::unary,
+        )
+    }
+
+    fn binary(
+        &mut self,
+        operators: &[Operator],
+        operand: fn(&mut Self) -> Result<Expr, QueryError>,
+    ) -> Result<Expr, QueryError> {
+        let mut left = operand(self)?;
+        while let Some(operator) = self.eat_operator(operators) {
+            let right = operand(self)?;
+            left = Expr::Binary(operator, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr, QueryError> {
+        if self.eat_operator(&[Operator::Minus]).is_some() {
+            Ok(Expr::Negate(Box::new(self.unary()?)))
+        } else {
+            self.union()
+        }
+    }
+
+    fn union(&mut self) -> Result<Expr, QueryError> {
+        let position = self.position();
+        let mut left = self.path()?;
+        while let Some(operator) = self.eat_operator(&[Operator::Union]) {
+            let right_position = self.position();
+            let right = self.path()?;
+            for (expr, position) in [(&left, position), (&right, right_position)] {
+                if !expr.is_node_set() {
+                    return Err(QueryError::new(
+                        position,
+                        "only nodes can be combined with |",
+                    ));
+                }
+            }
+            left = Expr::Binary(operator, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn path(&mut self) -> Result<Expr, QueryError> {
+        match self.peek() {
+            Some(Token::Slash) => {
+                self.index += 1;
+                let mut steps = Vec::new();
+                if self.peek().is_some_and(Token::starts_step) {
+                    self.steps(&mut steps)?;
+                }
+                Ok(Expr::Path(Start::Root, steps))
+            }
+            Some(Token::DoubleSlash) => {
+                self.index += 1;
+                let mut steps = vec![Step::descendant_or_self()];
+                self.steps(&mut steps)?;
+                Ok(Expr::Path(Start::Root, steps))
+            }
+            Some(token) if token.starts_step() => {
+                let mut steps = Vec::new();
+                self.steps(&mut steps)?;
+                Ok(Expr::Path(Start::Context, steps))
+            }
+            _ => {
+                let position = self.position();
+                let filter = self.filter()?;
+                let mut steps = Vec::new();
+                if self.eat(&Token::Slash) {
+                    self.steps(&mut steps)?;
+                } else if self.eat(&Token::DoubleSlash) {
+                    steps.push(Step::descendant_or_self());
+                    self.steps(&mut steps)?;
+                } else {
+                    return Ok(filter);
+                }
+
+                if !filter.is_node_set() {
+                    return Err(QueryError::new(position, "only nodes can start a path"));
+                }
+                Ok(Expr::Path(Start::Expr(Box::new(filter)), steps))
+            }
+        }
+    }
+
+    /// Parses a relative location path.
+    fn steps(&mut self, steps: &mut Vec<Step>) -> Result<(), QueryError> {
+        steps.push(self.step()?);
+        loop {
+            if self.eat(&Token::DoubleSlash) {
+                steps.push(Step::descendant_or_self());
+            } else if !self.eat(&Token::Slash) {
+                return Ok(());
+            }
+            steps.push(self.step()?);
+        }
+    }
+
+    fn step(&mut self) -> Result<Step, QueryError> {
+        let axis = match self.peek().cloned() {
+            Some(token @ (Token::Dot | Token::DotDot)) => {
+                self.index += 1;
+                return Ok(Step {
+                    axis: if token == Token::Dot {
+                        Axis::SelfNode
+                    } else {
+                        Axis::Parent
+                    },
+                    test: NodeTest::Node,
+                    predicates: Vec::new(),
+                });
+            }
+            Some(Token::At) => {
+                self.index += 1;
+                Axis::Attribute
+            }
+            Some(Token::Axis(axis)) => {
+                self.index += 1;
+                axis
+            }
+            _ => Axis::Child,
+        };
+
+        let test = match self.peek() {
+            Some(Token::NameTest(test)) => NodeTest::Name(test.clone()),
+            Some(Token::NodeType(test)) => test.clone(),
+            _ => return Err(QueryError::new(self.position(), "expected a node test")),
+        };
+        self.index += 1;
+        if matches!(test, NodeTest::Text | NodeTest::Node) {
+            self.expect(&Token::LParen, "(")?;
+            self.expect(&Token::RParen, ")")?;
+        }
+
+        Ok(Step {
+            axis,
+            test,
+            predicates: self.predicates()?,
+        })
+    }
+
+    fn predicates(&mut self) -> Result<Vec<Expr>, QueryError> {
+        let mut predicates = Vec::new();
+        while self.eat(&Token::LBracket) {
+            predicates.push(self.expr()?);
+            self.expect(&Token::RBracket, "]")?;
+        }
+        Ok(predicates)
+    }
+
+    fn filter(&mut self) -> Result<Expr, QueryError> {
+        let position = self.position();
+        let primary = self.primary()?;
+        let predicates = self.predicates()?;
+        if predicates.is_empty() {
+            return Ok(primary);
+        }
+
+        if !primary.is_node_set() {
+            return Err(QueryError::new(position, "only nodes can be filtered"));
+        }
+        Ok(Expr::Filter(Box::new(primary), predicates))
+    }
+
+    fn primary(&mut self) -> Result<Expr, QueryError> {
+        let position = self.position();
+        let Some(token) = self.peek().cloned() else {
+            return Err(QueryError::new(position, "expected an expression"));
+        };
+        self.index += 1;
+
+        match token {
+            Token::LParen => {
+                let expr = self.expr()?;
+                self.expect(&Token::RParen, ")")?;
+                Ok(expr)
+            }
+            Token::Literal(literal) => Ok(Expr::Literal(literal)),
+            Token::Number(number) => Ok(Expr::Number(number)),
+            Token::Function(name) => {
+                let function = Function::parse(&name).ok_or_else(|| {
+                    QueryError::new(position, format!("unknown function {name}()"))
+                })?;
+                self.expect(&Token::LParen, "(")?;
+                let mut args = Vec::new();
+                if !self.eat(&Token::RParen) {
+                    loop {
+                        let arg_position = self.position();
+                        let arg = self.expr()?;
+                        if function.takes_nodes() && !arg.is_node_set() {
+                            return Err(QueryError::new(
+                                arg_position,
+                                format!("{name}() takes nodes"),
+                            ));
+                        }
+                        args.push(arg);
+                        if !self.eat(&Token::Comma) {
+                            break;
+                        }
+                    }
+                    self.expect(&Token::RParen, ")")?;
+                }
+
+                let (min, max) = function.arity();
+                if args.len() < min || args.len() > max {
+                    return Err(QueryError::new(
+                        position,
+                        format!("wrong number of arguments for {name}()"),
+                    ));
+                }
+                Ok(Expr::Call(function, args))
+            }
+            _ => Err(QueryError::new(position, "expected an expression")),
+        }
+    }
+}
+
+/// The result of evaluating an expression.
+enum Value {
+    /// Indices of nodes in document order.
+    Nodes(Vec<usize>),
+    Boolean(bool),
+    Number(f64),
+    String(String),
+}
+
+#[derive(Clone, Copy)]
+struct Context {
+    node: usize,
+    position: usize,
+    size: usize,
+}
+
+impl Context {
+    fn new(node: usize) -> Self {
+        Self {
+            node,
+            position: 1,
+            size: 1,
+        }
+    }
+}
+
+struct Node<'v> {
+    node: QueryNode<'v>,
+    parent: Option<usize>,
+    attributes: Vec<usize>,
+    children: Vec<usize>,
+    /// The index after the last descendant of the node.
+    end: usize,
+}
+
+/// The nodes of a document in document order, where every element is followed by its attributes and then its descendants.
+pub(crate) struct Document<'v> {
+    nodes: Vec<Node<'v>>,
+}
+
+impl<'v> Document<'v> {
+    pub(crate) fn new(value: &'v XmlValue) -> Self {
+        let mut document = Self { nodes: Vec::new() };
+        document.add(None, QueryNode::Document(value));
+
+        let mut values = Vec::new();
+        flatten(value, &mut values);
+        for value in values {
+            match value {
+                XmlValue::Element(element) => document.add_element(0, element),
+                XmlValue::Text(text) => document.add(Some(0), QueryNode::Text(text)),
+                XmlValue::CData(cdata) => document.add(Some(0), QueryNode::CData(cdata)),
+                _ => {}
+            }
+        }
+        document.nodes[0].end = document.nodes.len();
+        document
+    }
+
+    fn add(&mut self, parent: Option<usize>, node: QueryNode<'v>) {
+        let index = self.nodes.len();
+        self.nodes.push(Node {
+            node,
+            parent,
+            attributes: Vec::new(),
+            children: Vec::new(),
+            end: index + 1,
+        });
+        if let Some(parent) = parent {
+            match node {
+                QueryNode::Attribute(_) => self.nodes[parent].attributes.push(index),
+                _ => self.nodes[parent].children.push(index),
+            }
+        }
+    }
+
+    fn add_element(&mut self, parent: usize, element: &'v XmlElement) {
+        let index = self.nodes.len();
+        self.add(Some(parent), QueryNode::Element(element));
+        for attribute in &element.attributes {
+            self.add(Some(index), QueryNode::Attribute(attribute));
+        }
+        for child in element.children.iter() {
+            match child {
+                XmlChild::Element(element) => self.add_element(index, element),
+                XmlChild::Text(text) => self.add(Some(index), QueryNode::Text(text)),
+                XmlChild::CData(cdata) => self.add(Some(index), QueryNode::CData(cdata)),
+                _ => {}
+            }
+        }
+        self.nodes[index].end = self.nodes.len();
+    }
+
+    /// Returns the path of a node, or of the element containing it for text.
+    #[cfg(feature = "schematron")]
+    pub(crate) fn path(&self, mut node: usize) -> XmlValuePath {
+        let mut segments = Vec::new();
+        while let Some(parent) = self.nodes[node].parent {
+            match self.nodes[node].node {
+                QueryNode::Element(element) => {
+                    let siblings: Vec<&XmlElement> = self.nodes[parent]
+                        .children
+                        .iter()
+                        .filter_map(|&child| match self.nodes[child].node {
+                            QueryNode::Element(element) => Some(element),
+                            _ => None,
+                        })
+                        .collect();
+                    let index = siblings
+                        .iter()
+                        .position(|sibling| core::ptr::eq(*sibling, element))
+                        .unwrap_or_default();
+                    segments.push(sibling_segments(&siblings).swap_remove(index));
+                }
+                QueryNode::Attribute(attribute) => {
+                    segments.push(XmlValuePathSegment::Attribute {
+                        name: attribute.name.clone(),
+                    });
+                }
+                _ => {}
+            }
+            node = parent;
+        }
+        segments.reverse();
+        XmlValuePath::from_outermost(segments)
+    }
+
+    fn name(&self, node: usize) -> Option<&'v ExpandedNameBuf> {
+        match self.nodes[node].node {
+            QueryNode::Element(element) => Some(&element.name),
+            QueryNode::Attribute(attribute) => Some(&attribute.name),
+            _ => None,
+        }
+    }
+
+    fn string_value(&self, node: usize) -> String {
+        match self.nodes[node].node {
+            QueryNode::Attribute(attribute) => {
+                String::from_utf8_lossy(&attribute.value.0).into_owned()
+            }
+            QueryNode::Text(text) => String::from_utf8_lossy(&text.0).into_owned(),
+            QueryNode::CData(cdata) => String::from_utf8_lossy(&cdata.0).into_owned(),
+            QueryNode::Document(_) | QueryNode::Element(_) => self.nodes
+                [node + 1..self.nodes[node].end]
+                .iter()
+                .filter_map(|descendant| match descendant.node {
+                    QueryNode::Text(text) => Some(String::from_utf8_lossy(&text.0)),
+                    QueryNode::CData(cdata) => Some(String::from_utf8_lossy(&cdata.0)),
+                    _ => None,
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the nodes of an axis in the order of proximity to the node, which is the reverse document order for reverse axes.
+    fn axis(&self, axis: Axis, node: usize) -> Vec<usize> {
+        let is_attribute = |node: &usize| matches!(self.nodes[*node].node, QueryNode::Attribute(_));
+        let ancestors = core::iter::successors(self.nodes[node].parent, |&ancestor| {
+            self.nodes[ancestor].parent
+        });
+        let siblings = || {
+            self.nodes[node]
+                .parent
+                .filter(|_| !is_attribute(&node))
+                .map_or(&[][..], |parent| &self.nodes[parent].children[..])
+        };
+
+        match axis {
+            Axis::Ancestor => ancestors.collect(),
+            Axis::AncestorOrSelf => core::iter::once(node).chain(ancestors).collect(),
+            Axis::Attribute => self.nodes[node].attributes.clone(),
+            Axis::Child => self.nodes[node].children.clone(),
+            Axis::Descendant => (node + 1..self.nodes[node].end)
+                .filter(|node| !is_attribute(node))
+                .collect(),
+            Axis::DescendantOrSelf => core::iter::once(node)
+                .chain((node + 1..self.nodes[node].end).filter(|node| !is_attribute(node)))
+                .collect(),
+            Axis::FollowingSibling => siblings()
+                .iter()
+                .copied()
+                .filter(|&sibling| sibling > node)
+                .collect(),
+            Axis::Parent => self.nodes[node].parent.into_iter().collect(),
+            Axis::PrecedingSibling => siblings()
+                .iter()
+                .rev()
+                .copied()
+                .filter(|&sibling| sibling < node)
+                .collect(),
+            Axis::SelfNode => vec![node],
+        }
+    }
+
+    fn test(&self, step: &Step, node: usize) -> bool {
+        let name = match (&self.nodes[node].node, step.axis) {
+            (QueryNode::Attribute(attribute), Axis::Attribute) => &attribute.name,
+            (QueryNode::Element(element), axis) if axis != Axis::Attribute => &element.name,
+            (QueryNode::Text(_) | QueryNode::CData(_), _) => {
+                return matches!(step.test, NodeTest::Text | NodeTest::Node)
+            }
+            _ => return step.test == NodeTest::Node,
+        };
+
+        match &step.test {
+            NodeTest::Name(NameTest::Any) | NodeTest::Node => true,
+            NodeTest::Name(NameTest::Namespace(namespace)) => {
+                name.namespace() == Some(&**namespace)
+            }
+            NodeTest::Name(NameTest::Name(test)) => test == name,
+            NodeTest::Text => false,
+        }
+    }
+
+    fn filter(&self, nodes: Vec<usize>, predicates: &[Expr]) -> Vec<usize> {
+        predicates.iter().fold(nodes, |nodes, predicate| {
+            let size = nodes.len();
+            nodes
+                .into_iter()
+                .enumerate()
+                .filter(|&(index, node)| {
+                    let context = Context {
+                        node,
+                        position: index + 1,
+                        size,
+                    };
+                    match self.evaluate(predicate, context) {
+                        Value::Number(number) => number == context.position as f64,
+                        value => boolean(&value),
+                    }
+                })
+                .map(|(_, node)| node)
+                .collect()
+        })
+    }
+
+    fn nodes_of(&self, value: Value) -> Vec<usize> {
+        match value {
+            Value::Nodes(nodes) => nodes,
+            _ => Vec::new(),
+        }
+    }
+
+    fn evaluate(&self, expr: &Expr, context: Context) -> Value {
+        match expr {
+            Expr::Binary(Operator::Or, left, right) => Value::Boolean(
+                boolean(&self.evaluate(left, context)) || boolean(&self.evaluate(right, context)),
+            ),
+            Expr::Binary(Operator::And, left, right) => Value::Boolean(
+                boolean(&self.evaluate(left, context)) && boolean(&self.evaluate(right, context)),
+            ),
+            Expr::Binary(Operator::Union, left, right) => {
+                let mut nodes = self.nodes_of(self.evaluate(left, context));
+                nodes.extend(self.nodes_of(self.evaluate(right, context)));
+                nodes.sort_unstable();
+                nodes.dedup();
+                Value::Nodes(nodes)
+            }
+            Expr::Binary(
+                operator @ (Operator::Eq
+                | Operator::NotEq
+                | Operator::Lt
+                | Operator::LtEq
+                | Operator::Gt
+                | Operator::GtEq),
+                left,
+                right,
+            ) => Value::Boolean(self.compare(
+                *operator,
+                self.evaluate(left, context),
+                self.evaluate(right, context),
+            )),
+            Expr::Binary(operator, left, right) => {
+                let left = self.number(&self.evaluate(left, context));
+                let right = self.number(&self.evaluate(right, context));
+                Value::Number(match operator {
+                    Operator::Plus => left + right,
+                    Operator::Minus => left - right,
+                    Operator::Multiply => left * right,
+                    Operator::Div => left / right,
+                    _ => left % right,
+                })
+            }
+            Expr::Negate(expr) => Value::Number(-self.number(&self.evaluate(expr, context))),
+            Expr::Path(start, steps) => {
+                let start = match start {
+                    Start::Context => vec![context.node],
+                    Start::Root => vec![0],
+                    Start::Expr(expr) => self.nodes_of(self.evaluate(expr, context)),
+                };
+                Value::Nodes(steps.iter().fold(start, |nodes, step| {
+                    let mut selected: Vec<usize> = nodes
+                        .into_iter()
+                        .flat_map(|node| {
+                            let candidates = self
+                                .axis(step.axis, node)
+                                .into_iter()
+                                .filter(|&candidate| self.test(step, candidate))
+                                .collect();
+                            self.filter(candidates, &step.predicates)
+                        })
+                        .collect();
+                    selected.sort_unstable();
+                    selected.dedup();
+                    selected
+                }))
+            }
+            Expr::Filter(expr, predicates) => {
+                Value::Nodes(self.filter(self.nodes_of(self.evaluate(expr, context)), predicates))
+            }
+            Expr::Literal(literal) => Value::String(literal.clone()),
+            Expr::Number(number) => Value::Number(*number),
+            Expr::Call(function, args) => self.call(*function, args, context),
+        }
+    }
+
+    fn call(&self, function: Function, args: &[Expr], context: Context) -> Value {
+        let arg = |index: usize| self.evaluate(&args[index], context);
+        let string = |index: usize| self.string(&arg(index));
+        // Functions that take an optional argument default to the context node.
+        let string_or_context = || {
+            if args.is_empty() {
+                self.string_value(context.node)
+            } else {
+                string(0)
+            }
+        };
+
+        match function {
+            Function::Last => Value::Number(context.size as f64),
+            Function::Position => Value::Number(context.position as f64),
+            Function::Count => Value::Number(self.nodes_of(arg(0)).len() as f64),
+            Function::LocalName | Function::NamespaceUri | Function::Name => {
+                let node = if args.is_empty() {
+                    Some(context.node)
+                } else {
+                    self.nodes_of(arg(0)).first().copied()
+                };
+                let name = node.and_then(|node| self.name(node));
+                Value::String(name.map_or_else(String::new, |name| {
+                    match function {
+                        Function::LocalName => name.local_name().to_string(),
+                        Function::NamespaceUri => name
+                            .namespace()
+                            .map_or_else(String::new, |namespace| namespace.to_string()),
+                        _ => name.to_string(),
+                    }
+                }))
+            }
+            Function::String => Value::String(string_or_context()),
+            Function::Concat => Value::String((0..args.len()).map(string).collect()),
+            Function::StartsWith => Value::Boolean(string(0).starts_with(&string(1))),
+            Function::EndsWith => Value::Boolean(string(0).ends_with(&string(1))),
+            Function::Contains => Value::Boolean(string(0).contains(&string(1))),
+            Function::SubstringBefore => {
+                let (text, pattern) = (string(0), string(1));
+                Value::String(
+                    text.find(&pattern)
+                        .map_or_else(String::new, |index| text[..index].to_string()),
+                )
+            }
+            Function::SubstringAfter => {
+                let (text, pattern) = (string(0), string(1));
+                Value::String(text.find(&pattern).map_or_else(String::new, |index| {
+                    text[index + pattern.len()..].to_string()
+                }))
+            }
+            Function::Substring => {
+                let text = string(0);
+                let start = round(self.number(&arg(1)));
+                let end = if args.len() > 2 {
+                    start + round(self.number(&arg(2)))
+                } else {
+                    f64::INFINITY
+                };
+                Value::String(
+                    text.chars()
+                        .enumerate()
+                        .filter(|&(index, _)| {
+                            let position = (index + 1) as f64;
+                            position >= start && position < end
+                        })
+                        .map(|(_, c)| c)
+                        .collect(),
+                )
+            }
+            Function::StringLength => Value::Number(string_or_context().chars().count() as f64),
+            Function::NormalizeSpace => Value::String(
+                string_or_context()
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            ),
+            Function::Translate => {
+                let (from, to): (Vec<char>, Vec<char>) =
+                    (string(1).chars().collect(), string(2).chars().collect());
+                Value::String(
+                    string(0)
+                        .chars()
+                        .filter_map(|c| match from.iter().position(|&from| from == c) {
+                            Some(index) => to.get(index).copied(),
+                            None => Some(c),
+                        })
+                        .collect(),
+                )
+            }
+            Function::Boolean => Value::Boolean(boolean(&arg(0))),
+            Function::Not => Value::Boolean(!boolean(&arg(0))),
+            Function::True => Value::Boolean(true),
+            Function::False => Value::Boolean(false),
+            Function::Number => Value::Number(if args.is_empty() {
+                parse_number(&self.string_value(context.node))
+            } else {
+                self.number(&arg(0))
+            }),
+            Function::Sum => Value::Number(
+                self.nodes_of(arg(0))
+                    .into_iter()
+                    .map(|node| parse_number(&self.string_value(node)))
+                    .sum(),
+            ),
+            Function::Floor => Value::Number(self.number(&arg(0)).floor()),
+            Function::Ceiling => Value::Number(self.number(&arg(0)).ceil()),
+            Function::Round => Value::Number(round(self.number(&arg(0)))),
+        }
+    }
+
+    /// Compares two values, where nodes compare true if any of them does.
+    fn compare(&self, operator: Operator, left: Value, right: Value) -> bool {
+        let strings = |nodes: &[usize]| -> Vec<Value> {
+            nodes
+                .iter()
+                .map(|&node| Value::String(self.string_value(node)))
+                .collect()
+        };
+
+        match (left, right) {
+            (Value::Nodes(nodes), Value::Boolean(boolean)) => compare_atomic(
+                operator,
+                &Value::Boolean(!nodes.is_empty()),
+                &Value::Boolean(boolean),
+            ),
+            (Value::Boolean(boolean), Value::Nodes(nodes)) => compare_atomic(
+                operator,
+                &Value::Boolean(boolean),
+                &Value::Boolean(!nodes.is_empty()),
+            ),
+            (Value::Nodes(left), Value::Nodes(right)) => {
+                let right = strings(&right);
+                strings(&left).iter().any(|left| {
+                    right
+                        .iter()
+                        .any(|right| compare_atomic(operator, left, right))
+                })
+            }
+            (Value::Nodes(nodes), value) => strings(&nodes)
+                .iter()
+                .any(|left| compare_atomic(operator, left, &value)),
+            (value, Value::Nodes(nodes)) => strings(&nodes)
+                .iter()
+                .any(|right| compare_atomic(operator, &value, right)),
+            (left, right) => compare_atomic(operator, &left, &right),
+        }
+    }
+
+    fn number(&self, value: &Value) -> f64 {
+        match value {
+            Value::Nodes(_) => parse_number(&self.string(value)),
+            value => atomic_number(value),
+        }
+    }
+
+    fn string(&self, value: &Value) -> String {
+        match value {
+            Value::Nodes(nodes) => nodes
+                .first()
+                .map_or_else(String::new, |&node| self.string_value(node)),
+            value => atomic_string(value),
+        }
+    }
+}
+
+/// Compares two values that are not nodes.
+fn compare_atomic(operator: Operator, left: &Value, right: &Value) -> bool {
+    let is = |check: fn(&Value) -> bool| check(left) || check(right);
+    match operator {
+        Operator::Eq | Operator::NotEq => {
+            let equal = if is(|value| matches!(value, Value::Boolean(_))) {
+                boolean(left) == boolean(right)
+            } else if is(|value| matches!(value, Value::Number(_))) {
+                atomic_number(left) == atomic_number(right)
+            } else {
+                atomic_string(left) == atomic_string(right)
+            };
+            equal == (operator == Operator::Eq)
+        }
+        operator => {
+            let (left, right) = (atomic_number(left), atomic_number(right));
+            match operator {
+                Operator::Lt => left < right,
+                Operator::LtEq => left <= right,
+                Operator::Gt => left > right,
+                _ => left >= right,
+            }
+        }
+    }
+}
+
+fn boolean(value: &Value) -> bool {
+    match value {
+        Value::Nodes(nodes) => !nodes.is_empty(),
+        Value::Boolean(boolean) => *boolean,
+        Value::Number(number) => *number != 0.0 && !number.is_nan(),
+        Value::String(string) => !string.is_empty(),
+    }
+}
+
+fn atomic_number(value: &Value) -> f64 {
+    match value {
+        Value::Nodes(_) => f64::NAN,
+        Value::Boolean(boolean) => f64::from(u8::from(*boolean)),
+        Value::Number(number) => *number,
+        Value::String(string) => parse_number(string),
+    }
+}
+
+fn atomic_string(value: &Value) -> String {
+    match value {
+        Value::Nodes(_) => String::new(),
+        Value::Boolean(boolean) => boolean.to_string(),
+        Value::Number(number) if number.is_nan() => "NaN".to_string(),
+        Value::Number(number) if number.is_infinite() => if *number > 0.0 {
+            "Infinity"
+        } else {
+            "-Infinity"
+        }
+        .to_string(),
+        // Negative zero is written without its sign.
+        Value::Number(number) if *number == 0.0 => "0".to_string(),
+        Value::Number(number) => number.to_string(),
+        Value::String(string) => string.clone(),
+    }
+}
+
+/// Parses a number the way XPath does, which only allows digits with an optional period and sign.
+fn parse_number(text: &str) -> f64 {
+    let text = text.trim();
+    let digits = text.strip_prefix('-').unwrap_or(text);
+    let valid = digits.chars().any(|c| c.is_ascii_digit())
+        && digits.chars().all(|c| c.is_ascii_digit() || c == '.')
+        && digits.matches('.').count() <= 1;
+
+    if valid {
+        text.parse().unwrap_or(f64::NAN)
+    } else {
+        f64::NAN
+    }
+}
+
+/// Rounds half up, like the `round` function of XPath.
+fn round(number: f64) -> f64 {
+    if number.is_finite() {
+        (number + 0.5).floor()
+    } else {
+        number
+    }
+}
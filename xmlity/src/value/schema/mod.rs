@@ -0,0 +1,1005 @@
+//! This module contains a validator that checks [`XmlValue`]s against a schema in a practical subset of XML Schema.
+//!
+//! A [`Schema`] is built out of element declarations whose content is described with sequences, choices and `all` groups of child elements, with the usual `minOccurs` and `maxOccurs` bounds, and whose text and attribute values are described with [`SimpleType`]s restricted by enumerations, patterns and lengths. Identity constraints, wildcards, substitution groups and type derivation are not supported.
+//!
+//! To validate a value that implements [`Serialize`](crate::Serialize), first turn it into an [`XmlValue`] with [`to_value`](crate::value::to_value). Streamed input can be validated by deserializing it into an [`XmlValue`] first.
+//!
+//! With the `relaxng` feature, the `relaxng` module contains a validator for schemas written in the compact syntax of RELAX NG, which reports issues the same way.
+//!
+//! With the `schematron` feature, the `schematron` module contains a validator for business rules written as assertions over queries, in the style of Schematron.
+//!
+//! ```
+//! use xmlity::{
+//!     value::{
+//!         schema::{
+//!             AttributeDecl, BuiltinType, ComplexType, ElementDecl, Particle, Schema,
+//!             SchemaIssueKind, SimpleType,
+//!         },
+//!         XmlAttribute, XmlElement, XmlText,
+//!     },
+//!     ExpandedNameBuf, LocalNameBuf,
+//! };
+//!
+//! fn name(name: &str) -> ExpandedNameBuf {
+//!     ExpandedNameBuf::new(LocalNameBuf::new(name.to_string()).unwrap(), None)
+//! }
+//!
+//! let schema = Schema::new().with_element(ElementDecl::new(
+//!     name("order"),
+//!     ComplexType::new(Particle::sequence([Particle::element(ElementDecl::new(
+//!         name("quantity"),
+//!         SimpleType::new(BuiltinType::PositiveInteger),
+//!     ))
+//!     .with_occurs(1, None)]))
+//!     .with_attribute(
+//!         AttributeDecl::new(name("id"), SimpleType::new(BuiltinType::Token)).with_required(true),
+//!     ),
+//! ));
+//!
+//! let order = XmlElement::new(name("order"))
+//!     .with_attribute(XmlAttribute::new(name("id"), "A-1"))
+//!     .with_child(XmlElement::new(name("quantity")).with_child(XmlText::new("2")))
+//!     .with_child(XmlElement::new(name("quantity")).with_child(XmlText::new("0")));
+//!
+//! let report = schema.validate(&order.into()).unwrap_err();
+//! assert_eq!(report.issues()[0].path().to_string(), "/order/quantity[2]");
+//! assert!(matches!(
+//!     report.issues()[0].kind(),
+//!     SchemaIssueKind::InvalidValue { .. }
+//! ));
+//! ```
+use core::fmt;
+use std::{collections::BTreeMap, rc::Rc};
+
+use crate::{
+    value::{
+        flatten, sibling_segments, XmlChild, XmlElement, XmlValue, XmlValuePath,
+        XmlValuePathSegment,
+    },
+    ExpandedNameBuf, XmlNamespace,
+};
+
+mod pattern;
+#[cfg(feature = "relaxng")]
+pub mod relaxng;
+#[cfg(feature = "schematron")]
+pub mod schematron;
+
+pub use pattern::{Pattern, PatternError};
+
+/// A schema made of global element declarations, which the root element of a validated document must be one of.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Schema {
+    elements: Vec<ElementDecl>,
+}
+
+impl Schema {
+    /// Creates a new schema without any elements.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a global element declaration, which can be the root of a document or be referred to with [`Particle::element_ref`].
+    pub fn with_element(mut self, element: ElementDecl) -> Self {
+        self.elements.push(element);
+        self
+    }
+
+    /// Returns the global element declaration with the given name.
+    pub fn element(&self, name: &ExpandedNameBuf) -> Option<&ElementDecl> {
+        self.elements.iter().find(|element| &element.name == name)
+    }
+
+    /// Validates a value against the schema.
+    ///
+    /// The value is either a single element, or a document of a sequence containing exactly one element, which can be surrounded by an XML declaration, a doctype, comments, processing instructions and whitespace.
+    pub fn validate(&self, value: &XmlValue) -> Result<(), SchemaReport> {
+        let mut validator = Validator {
+            schema: self,
+            path: Vec::new(),
+            issues: Vec::new(),
+        };
+        validator.validate_document(value);
+
+        if validator.issues.is_empty() {
+            Ok(())
+        } else {
+            Err(SchemaReport {
+                issues: validator.issues,
+            })
+        }
+    }
+}
+
+/// The declaration of an element.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElementDecl {
+    name: ExpandedNameBuf,
+    element_type: ElementType,
+}
+
+impl ElementDecl {
+    /// Creates a new element declaration.
+    pub fn new(name: ExpandedNameBuf, element_type: impl Into<ElementType>) -> Self {
+        Self {
+            name,
+            element_type: element_type.into(),
+        }
+    }
+
+    /// Returns the name of the element.
+    pub fn name(&self) -> &ExpandedNameBuf {
+        &self.name
+    }
+
+    /// Returns the type of the element.
+    pub fn element_type(&self) -> &ElementType {
+        &self.element_type
+    }
+}
+
+/// The type of an element.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ElementType {
+    /// An element with text content and no attributes.
+    Simple(SimpleType),
+    /// An element with attributes or child elements.
+    Complex(ComplexType),
+}
+
+impl From<SimpleType> for ElementType {
+    fn from(value: SimpleType) -> Self {
+        Self::Simple(value)
+    }
+}
+
+impl From<ComplexType> for ElementType {
+    fn from(value: ComplexType) -> Self {
+        Self::Complex(value)
+    }
+}
+
+/// The type of an element with attributes or child elements.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComplexType {
+    content: Content,
+    attributes: Vec<AttributeDecl>,
+    mixed: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Content {
+    Empty,
+    Simple(SimpleType),
+    Elements(Particle),
+}
+
+impl ComplexType {
+    fn with_content(content: Content) -> Self {
+        Self {
+            content,
+            attributes: Vec::new(),
+            mixed: false,
+        }
+    }
+
+    /// Creates a type with child elements described by a particle.
+    pub fn new(particle: Particle) -> Self {
+        Self::with_content(Content::Elements(particle))
+    }
+
+    /// Creates a type without any content.
+    pub fn empty() -> Self {
+        Self::with_content(Content::Empty)
+    }
+
+    /// Creates a type with text content, for elements with a simple type that also have attributes.
+    pub fn simple(simple_type: SimpleType) -> Self {
+        Self::with_content(Content::Simple(simple_type))
+    }
+
+    /// Adds an attribute declaration.
+    pub fn with_attribute(mut self, attribute: AttributeDecl) -> Self {
+        self.attributes.push(attribute);
+        self
+    }
+
+    /// Sets whether text is allowed between the child elements. Defaults to `false`.
+    pub fn with_mixed(mut self, mixed: bool) -> Self {
+        self.mixed = mixed;
+        self
+    }
+}
+
+/// The declaration of an attribute.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttributeDecl {
+    name: ExpandedNameBuf,
+    simple_type: SimpleType,
+    required: bool,
+}
+
+impl AttributeDecl {
+    /// Creates a new optional attribute declaration.
+    pub fn new(name: ExpandedNameBuf, simple_type: SimpleType) -> Self {
+        Self {
+            name,
+            simple_type,
+            required: false,
+        }
+    }
+
+    /// Sets whether the attribute must be present. Defaults to `false`.
+    pub fn with_required(mut self, required: bool) -> Self {
+        self.required = required;
+        self
+    }
+}
+
+/// A part of the content of an element, which occurs between `min_occurs` and `max_occurs` times.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Particle {
+    term: Term,
+    min_occurs: u32,
+    max_occurs: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Term {
+    Element(Box<ElementDecl>),
+    ElementRef(ExpandedNameBuf),
+    Sequence(Vec<Particle>),
+    Choice(Vec<Particle>),
+    All(Vec<Particle>),
+}
+
+impl Particle {
+    fn with_term(term: Term) -> Self {
+        Self {
+            term,
+            min_occurs: 1,
+            max_occurs: Some(1),
+        }
+    }
+
+    /// A local element declaration.
+    pub fn element(element: ElementDecl) -> Self {
+        Self::with_term(Term::Element(Box::new(element)))
+    }
+
+    /// A reference to a global element declaration of the schema.
+    pub fn element_ref(name: ExpandedNameBuf) -> Self {
+        Self::with_term(Term::ElementRef(name))
+    }
+
+    /// The particles one after another.
+    pub fn sequence(particles: impl IntoIterator<Item = Particle>) -> Self {
+        Self::with_term(Term::Sequence(particles.into_iter().collect()))
+    }
+
+    /// One of the particles.
+    pub fn choice(particles: impl IntoIterator<Item = Particle>) -> Self {
+        Self::with_term(Term::Choice(particles.into_iter().collect()))
+    }
+
+    /// The particles in any order, each at most once.
+    pub fn all(particles: impl IntoIterator<Item = Particle>) -> Self {
+        Self::with_term(Term::All(particles.into_iter().collect()))
+    }
+
+    /// Sets how many times the particle occurs, where a `max_occurs` of [`None`] is unbounded. Defaults to exactly once.
+    pub fn with_occurs(mut self, min_occurs: u32, max_occurs: Option<u32>) -> Self {
+        self.min_occurs = min_occurs;
+        self.max_occurs = max_occurs;
+        self
+    }
+}
+
+/// The built-in types of XML Schema that a [`SimpleType`] can restrict.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BuiltinType {
+    /// `xs:string`, any text. Whitespace is preserved.
+    String,
+    /// `xs:token`, any text. Whitespace is collapsed, like for all other types.
+    Token,
+    /// `xs:boolean`, which is `true`, `false`, `1` or `0`.
+    Boolean,
+    /// `xs:decimal`, a decimal number like `-1.23`.
+    Decimal,
+    /// `xs:integer`, a whole number of any size.
+    Integer,
+    /// `xs:nonNegativeInteger`.
+    NonNegativeInteger,
+    /// `xs:positiveInteger`.
+    PositiveInteger,
+    /// `xs:double`, a floating point number like `1.5E3`, `INF` or `NaN`.
+    Double,
+}
+
+impl BuiltinType {
+    fn is_valid(self, value: &str) -> bool {
+        match self {
+            BuiltinType::String | BuiltinType::Token => true,
+            BuiltinType::Boolean => matches!(value, "true" | "false" | "1" | "0"),
+            BuiltinType::Decimal => {
+                let unsigned = value.strip_prefix(['+', '-']).unwrap_or(value);
+                let (whole, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+                !(whole.is_empty() && fraction.is_empty())
+                    && whole
+                        .chars()
+                        .chain(fraction.chars())
+                        .all(|c| c.is_ascii_digit())
+            }
+            BuiltinType::Integer => parse_integer(value).is_some(),
+            BuiltinType::NonNegativeInteger => {
+                parse_integer(value).is_some_and(|(negative, zero)| !negative || zero)
+            }
+            BuiltinType::PositiveInteger => {
+                parse_integer(value).is_some_and(|(negative, zero)| !negative && !zero)
+            }
+            BuiltinType::Double => {
+                matches!(value, "INF" | "-INF" | "NaN")
+                    || (value
+                        .chars()
+                        .all(|c| c.is_ascii_digit() || matches!(c, '+' | '-' | '.' | 'e' | 'E'))
+                        && value.parse::<f64>().is_ok())
+            }
+        }
+    }
+}
+
+/// Parses an integer of any size, returning whether it is negative and whether it is zero.
+fn parse_integer(value: &str) -> Option<(bool, bool)> {
+    let negative = value.starts_with('-');
+    let digits = value.strip_prefix(['+', '-']).unwrap_or(value);
+    (!digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()))
+        .then(|| (negative, digits.chars().all(|c| c == '0')))
+}
+
+impl fmt::Display for BuiltinType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            BuiltinType::String => "xs:string",
+            BuiltinType::Token => "xs:token",
+            BuiltinType::Boolean => "xs:boolean",
+            BuiltinType::Decimal => "xs:decimal",
+            BuiltinType::Integer => "xs:integer",
+            BuiltinType::NonNegativeInteger => "xs:nonNegativeInteger",
+            BuiltinType::PositiveInteger => "xs:positiveInteger",
+            BuiltinType::Double => "xs:double",
+        })
+    }
+}
+
+/// A built-in type restricted by facets, for text content and attribute values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimpleType {
+    base: BuiltinType,
+    enumeration: Option<Vec<String>>,
+    patterns: Vec<Pattern>,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+}
+
+impl SimpleType {
+    /// Creates a simple type allowing all values of a built-in type.
+    pub fn new(base: BuiltinType) -> Self {
+        Self {
+            base,
+            enumeration: None,
+            patterns: Vec::new(),
+            min_length: None,
+            max_length: None,
+        }
+    }
+
+    /// Restricts the values to the given ones.
+    pub fn with_enumeration<T: Into<String>>(
+        mut self,
+        values: impl IntoIterator<Item = T>,
+    ) -> Self {
+        self.enumeration = Some(values.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Restricts the values to those matching a pattern. If more than one pattern is given, the values must match all of them.
+    pub fn with_pattern(mut self, pattern: Pattern) -> Self {
+        self.patterns.push(pattern);
+        self
+    }
+
+    /// Restricts the values to those with at least this many characters.
+    pub fn with_min_length(mut self, min_length: usize) -> Self {
+        self.min_length = Some(min_length);
+        self
+    }
+
+    /// Restricts the values to those with at most this many characters.
+    pub fn with_max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    /// Checks a value after normalizing its whitespace, returning the facet it violates.
+    fn check(&self, value: &str) -> Result<(), Facet> {
+        let value = match self.base {
+            BuiltinType::String => value.to_owned(),
+            _ => value.split_ascii_whitespace().collect::<Vec<_>>().join(" "),
+        };
+
+        if !self.base.is_valid(&value) {
+            return Err(Facet::Type(self.base));
+        }
+
+        let length = value.chars().count();
+        if let Some(min_length) = self.min_length.filter(|min_length| length < *min_length) {
+            return Err(Facet::MinLength(min_length));
+        }
+        if let Some(max_length) = self.max_length.filter(|max_length| length > *max_length) {
+            return Err(Facet::MaxLength(max_length));
+        }
+        if self
+            .enumeration
+            .as_ref()
+            .is_some_and(|values| !values.contains(&value))
+        {
+            return Err(Facet::Enumeration);
+        }
+        if let Some(pattern) = self
+            .patterns
+            .iter()
+            .find(|pattern| !pattern.is_match(&value))
+        {
+            return Err(Facet::Pattern(pattern.as_str().to_owned()));
+        }
+
+        Ok(())
+    }
+}
+
+/// The issues found by validating a document against a schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaReport {
+    issues: Vec<SchemaIssue>,
+}
+
+impl SchemaReport {
+    /// Returns the issues in document order.
+    pub fn issues(&self) -> &[SchemaIssue] {
+        &self.issues
+    }
+
+    /// Returns the issues in document order, consuming the report.
+    pub fn into_issues(self) -> Vec<SchemaIssue> {
+        self.issues
+    }
+}
+
+impl fmt::Display for SchemaReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, issue) in self.issues.iter().enumerate() {
+            if index > 0 {
+                f.write_str("; ")?;
+            }
+            issue.fmt(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for SchemaReport {}
+
+/// An issue found by validating a document against a schema, together with where in the document it was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaIssue {
+    path: XmlValuePath,
+    kind: SchemaIssueKind,
+}
+
+impl SchemaIssue {
+    /// The element or attribute the issue was found in. The path is empty for issues outside of the root element.
+    pub fn path(&self) -> &XmlValuePath {
+        &self.path
+    }
+
+    /// What the issue is.
+    pub fn kind(&self) -> &SchemaIssueKind {
+        &self.kind
+    }
+}
+
+impl fmt::Display for SchemaIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.segments().next().is_some() {
+            write!(f, "{} at {}", self.kind, self.path)
+        } else {
+            self.kind.fmt(f)
+        }
+    }
+}
+
+/// The kinds of issues found by validating a document against a schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SchemaIssueKind {
+    /// The root element is not declared in the schema.
+    UndeclaredElement {
+        /// The name of the element.
+        name: ExpandedNameBuf,
+    },
+    /// An element is not allowed at its position by the content of its parent, or a document has more than one root element.
+    UnexpectedElement {
+        /// The name of the element.
+        name: ExpandedNameBuf,
+    },
+    /// The content of an element, or a document, ends before all required elements have occurred.
+    IncompleteContent,
+    /// Text that is not whitespace is in an element that only allows child elements, or outside of the root element.
+    UnexpectedText,
+    /// A required attribute is missing.
+    MissingAttribute {
+        /// The name of the attribute.
+        name: ExpandedNameBuf,
+    },
+    /// An attribute is not declared for its element.
+    UndeclaredAttribute {
+        /// The name of the attribute.
+        name: ExpandedNameBuf,
+    },
+    /// Text content or an attribute value is not allowed by its simple type.
+    InvalidValue {
+        /// The value as it is written in the document.
+        value: String,
+        /// The facet the value violates.
+        facet: Facet,
+    },
+    /// An assertion of a rule failed, or a report of a rule was triggered.
+    FailedAssertion {
+        /// The message of the assertion.
+        message: String,
+    },
+}
+
+impl fmt::Display for SchemaIssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UndeclaredElement { name } => write!(f, "element {name} is not declared"),
+            Self::UnexpectedElement { name } => write!(f, "unexpected element {name}"),
+            Self::IncompleteContent => f.write_str("content ends before a required element"),
+            Self::UnexpectedText => f.write_str("unexpected text in element-only content"),
+            Self::MissingAttribute { name } => write!(f, "missing required attribute {name}"),
+            Self::UndeclaredAttribute { name } => write!(f, "attribute {name} is not declared"),
+            Self::InvalidValue { value, facet } => write!(f, "invalid value {value:?}: {facet}"),
+            Self::FailedAssertion { message } => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for SchemaIssueKind {}
+
+/// A restriction of a [`SimpleType`] that a value can violate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Facet {
+    /// The value is not a valid value of the built-in type.
+    Type(BuiltinType),
+    /// The value is shorter than the minimum length.
+    MinLength(usize),
+    /// The value is longer than the maximum length.
+    MaxLength(usize),
+    /// The value is not one of the enumerated values.
+    Enumeration,
+    /// The value does not match the pattern.
+    Pattern(String),
+}
+
+impl fmt::Display for Facet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Type(base) => write!(f, "not a valid {base}"),
+            Self::MinLength(length) => write!(f, "shorter than {length} characters"),
+            Self::MaxLength(length) => write!(f, "longer than {length} characters"),
+            Self::Enumeration => f.write_str("not one of the allowed values"),
+            Self::Pattern(pattern) => write!(f, "does not match the pattern {pattern:?}"),
+        }
+    }
+}
+
+/// The declarations the children before a position were matched with, last first. The earlier declarations are shared between ways of matching, so extending them does not copy them.
+#[derive(Clone, Default)]
+struct Matched<'s>(Option<Rc<MatchedDecl<'s>>>);
+
+struct MatchedDecl<'s> {
+    decl: &'s ElementDecl,
+    previous: Matched<'s>,
+}
+
+impl<'s> Matched<'s> {
+    fn with(&self, decl: &'s ElementDecl) -> Self {
+        Self(Some(Rc::new(MatchedDecl {
+            decl,
+            previous: self.clone(),
+        })))
+    }
+
+    /// Returns the declarations in the order of the children.
+    fn to_vec(&self) -> Vec<&'s ElementDecl> {
+        let mut decls = Vec::new();
+        let mut current = &self.0;
+        while let Some(matched) = current {
+            decls.push(matched.decl);
+            current = &matched.previous.0;
+        }
+        decls.reverse();
+        decls
+    }
+}
+
+impl Drop for MatchedDecl<'_> {
+    // Unlinks the list iteratively, since dropping it recursively could overflow the stack for elements with many children.
+    fn drop(&mut self) {
+        let mut previous = self.previous.0.take();
+        while let Some(matched) = previous {
+            previous = match Rc::try_unwrap(matched) {
+                Ok(mut matched) => matched.previous.0.take(),
+                Err(_) => None,
+            };
+        }
+    }
+}
+
+/// The ways the children before a position can be matched, together with the declarations they were matched with.
+type Matches<'s> = BTreeMap<usize, Matched<'s>>;
+
+/// How far into the children any way of matching them got.
+struct Progress<'s> {
+    furthest: usize,
+    matched: Matched<'s>,
+}
+
+struct Validator<'s> {
+    schema: &'s Schema,
+    /// The location being validated, outermost first.
+    path: Vec<XmlValuePathSegment>,
+    issues: Vec<SchemaIssue>,
+}
+
+impl<'s> Validator<'s> {
+    fn report(&mut self, kind: SchemaIssueKind) {
+        self.issues.push(SchemaIssue {
+            path: XmlValuePath::from_outermost(self.path.clone()),
+            kind,
+        });
+    }
+
+    fn validate_document(&mut self, value: &XmlValue) {
+        let Some(root) = document_root(value, &mut self.issues) else {
+            return;
+        };
+
+        self.path.push(XmlValuePathSegment::Element {
+            name: root.name.clone(),
+            position: None,
+        });
+        match self.schema.element(&root.name) {
+            Some(decl) => self.validate_element(decl, root),
+            None => self.report(SchemaIssueKind::UndeclaredElement {
+                name: root.name.clone(),
+            }),
+        }
+        self.path.pop();
+    }
+
+    /// Validates an element whose segment is already in the path.
+    fn validate_element(&mut self, decl: &'s ElementDecl, element: &XmlElement) {
+        match &decl.element_type {
+            ElementType::Simple(simple_type) => {
+                self.validate_attributes(&[], element);
+                self.validate_simple_content(simple_type, element);
+            }
+            ElementType::Complex(complex_type) => {
+                self.validate_attributes(&complex_type.attributes, element);
+                match &complex_type.content {
+                    Content::Empty => self.validate_children(None, element, complex_type.mixed),
+                    Content::Simple(simple_type) => {
+                        self.validate_simple_content(simple_type, element)
+                    }
+                    Content::Elements(particle) => {
+                        self.validate_children(Some(particle), element, complex_type.mixed)
+                    }
+                }
+            }
+        }
+    }
+
+    fn validate_simple_content(&mut self, simple_type: &SimpleType, element: &XmlElement) {
+        for child in element.children.iter() {
+            if let XmlChild::Element(child) = child {
+                self.report(SchemaIssueKind::UnexpectedElement {
+                    name: child.name.clone(),
+                });
+            }
+        }
+        self.validate_value(simple_type, &text_content(element));
+    }
+
+    fn validate_attributes(&mut self, decls: &'s [AttributeDecl], element: &XmlElement) {
+        for attribute in &element.attributes {
+            if attribute.name.namespace() == Some(XmlNamespace::XSI) {
+                continue;
+            }
+
+            self.path.push(XmlValuePathSegment::Attribute {
+                name: attribute.name.clone(),
+            });
+            match decls.iter().find(|decl| decl.name == attribute.name) {
+                Some(decl) => self.validate_value(
+                    &decl.simple_type,
+                    &String::from_utf8_lossy(&attribute.value.0),
+                ),
+                None => self.report(SchemaIssueKind::UndeclaredAttribute {
+                    name: attribute.name.clone(),
+                }),
+            }
+            self.path.pop();
+        }
+
+        for decl in decls.iter().filter(|decl| decl.required) {
+            if !element
+                .attributes
+                .iter()
+                .any(|attribute| attribute.name == decl.name)
+            {
+                self.report(SchemaIssueKind::MissingAttribute {
+                    name: decl.name.clone(),
+                });
+            }
+        }
+    }
+
+    fn validate_value(&mut self, simple_type: &SimpleType, value: &str) {
+        if let Err(facet) = simple_type.check(value) {
+            self.report(SchemaIssueKind::InvalidValue {
+                value: value.to_owned(),
+                facet,
+            });
+        }
+    }
+
+    /// Validates the child elements of an element against its particle, or against empty content if there is none.
+    fn validate_children(
+        &mut self,
+        particle: Option<&'s Particle>,
+        element: &XmlElement,
+        mixed: bool,
+    ) {
+        let has_text = element.children.iter().any(|child| match child {
+            XmlChild::Text(text) => !is_whitespace(&text.0),
+            XmlChild::CData(cdata) => !is_whitespace(&cdata.0),
+            _ => false,
+        });
+        if has_text && !mixed {
+            self.report(SchemaIssueKind::UnexpectedText);
+        }
+
+        let children = element
+            .children
+            .iter()
+            .filter_map(|child| match child {
+                XmlChild::Element(child) => Some(child),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let mut progress = Progress {
+            furthest: 0,
+            matched: Matched::default(),
+        };
+        let complete = match particle {
+            Some(particle) => self
+                .match_particle(
+                    particle,
+                    &children,
+                    Matches::from([(0, Matched::default())]),
+                    &mut progress,
+                )
+                .remove(&children.len()),
+            None => children.is_empty().then(Matched::default),
+        };
+
+        let mut segments = sibling_segments(&children);
+        let matched = match complete {
+            Some(matched) => matched,
+            None => {
+                match children.get(progress.furthest) {
+                    Some(child) => {
+                        self.path.push(segments[progress.furthest].clone());
+                        self.report(SchemaIssueKind::UnexpectedElement {
+                            name: child.name.clone(),
+                        });
+                        self.path.pop();
+                    }
+                    None => self.report(SchemaIssueKind::IncompleteContent),
+                }
+                progress.matched
+            }
+        };
+
+        for ((decl, child), segment) in matched
+            .to_vec()
+            .into_iter()
+            .zip(children)
+            .zip(segments.drain(..))
+        {
+            self.path.push(segment);
+            self.validate_element(decl, child);
+            self.path.pop();
+        }
+    }
+
+    /// Returns the ways the children can be matched after matching the particle from any of the given ways.
+    fn match_particle(
+        &self,
+        particle: &'s Particle,
+        children: &[&XmlElement],
+        starts: Matches<'s>,
+        progress: &mut Progress<'s>,
+    ) -> Matches<'s> {
+        // Once matching another occurrence reaches the same positions, further occurrences do too, so large bounds stop as soon as no children are left to match.
+        let mut current = starts;
+        for _ in 0..particle.min_occurs {
+            let next = self.match_term(&particle.term, children, current.clone(), progress);
+            if next.keys().eq(current.keys()) {
+                break;
+            }
+            current = next;
+        }
+
+        // Positions that were already reached with fewer occurrences are not matched again, which also ends the loop for particles that can match nothing.
+        let mut ends = current.clone();
+        let mut count = particle.min_occurs;
+        while !current.is_empty() && particle.max_occurs.is_none_or(|max| count < max) {
+            current = self.match_term(&particle.term, children, current, progress);
+            current.retain(|position, _| !ends.contains_key(position));
+            ends.extend(current.clone());
+            count += 1;
+        }
+        ends
+    }
+
+    fn match_term(
+        &self,
+        term: &'s Term,
+        children: &[&XmlElement],
+        starts: Matches<'s>,
+        progress: &mut Progress<'s>,
+    ) -> Matches<'s> {
+        match term {
+            Term::Element(decl) => match_element(decl, children, starts, progress),
+            Term::ElementRef(name) => match self.schema.element(name) {
+                Some(decl) => match_element(decl, children, starts, progress),
+                None => Matches::new(),
+            },
+            Term::Sequence(particles) => particles.iter().fold(starts, |current, particle| {
+                self.match_particle(particle, children, current, progress)
+            }),
+            Term::Choice(particles) => {
+                let mut ends = Matches::new();
+                for particle in particles {
+                    for (position, matched) in
+                        self.match_particle(particle, children, starts.clone(), progress)
+                    {
+                        ends.entry(position).or_insert(matched);
+                    }
+                }
+                ends
+            }
+            Term::All(particles) => {
+                let mut ends = Matches::new();
+                let mut states = starts
+                    .into_iter()
+                    .map(|(position, matched)| ((position, vec![false; particles.len()]), matched))
+                    .collect::<BTreeMap<_, _>>();
+
+                while let Some(((position, used), matched)) = states.pop_first() {
+                    let complete = particles
+                        .iter()
+                        .zip(&used)
+                        .all(|(particle, used)| *used || particle.min_occurs == 0);
+                    if complete {
+                        ends.entry(position).or_insert_with(|| matched.clone());
+                    }
+
+                    for (index, particle) in particles.iter().enumerate() {
+                        if used[index] {
+                            continue;
+                        }
+                        let mut used = used.clone();
+                        used[index] = true;
+                        let starts = Matches::from([(position, matched.clone())]);
+                        for (end, matched) in
+                            self.match_term(&particle.term, children, starts, progress)
+                        {
+                            states.entry((end, used.clone())).or_insert(matched);
+                        }
+                    }
+                }
+                ends
+            }
+        }
+    }
+}
+
+fn match_element<'s>(
+    decl: &'s ElementDecl,
+    children: &[&XmlElement],
+    starts: Matches<'s>,
+    progress: &mut Progress<'s>,
+) -> Matches<'s> {
+    let mut ends = Matches::new();
+    for (position, matched) in starts {
+        if children
+            .get(position)
+            .is_some_and(|child| child.name == decl.name)
+        {
+            let matched = matched.with(decl);
+            if position + 1 > progress.furthest {
+                progress.furthest = position + 1;
+                progress.matched = matched.clone();
+            }
+            ends.entry(position + 1).or_insert(matched);
+        }
+    }
+    ends
+}
+
+/// Finds the root element of a document, reporting anything else in it that is not allowed outside of the root element.
+fn document_root<'v>(value: &'v XmlValue, issues: &mut Vec<SchemaIssue>) -> Option<&'v XmlElement> {
+    let mut nodes = Vec::new();
+    flatten(value, &mut nodes);
+
+    let mut report = |kind| {
+        issues.push(SchemaIssue {
+            path: XmlValuePath::default(),
+            kind,
+        })
+    };
+    let mut root = None;
+    for node in nodes {
+        match node {
+            XmlValue::Element(element) if root.is_none() => root = Some(element),
+            XmlValue::Element(element) => report(SchemaIssueKind::UnexpectedElement {
+                name: element.name.clone(),
+            }),
+            XmlValue::Text(text) if !is_whitespace(&text.0) => {
+                report(SchemaIssueKind::UnexpectedText)
+            }
+            XmlValue::CData(cdata) if !is_whitespace(&cdata.0) => {
+                report(SchemaIssueKind::UnexpectedText)
+            }
+            _ => {}
+        }
+    }
+
+    if root.is_none() {
+        report(SchemaIssueKind::IncompleteContent);
+    }
+    root
+}
+
+fn text_content(element: &XmlElement) -> String {
+    element
+        .children
+        .iter()
+        .filter_map(|child| match child {
+            XmlChild::Text(text) => Some(String::from_utf8_lossy(&text.0)),
+            XmlChild::CData(cdata) => Some(String::from_utf8_lossy(&cdata.0)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn is_whitespace(text: &[u8]) -> bool {
+    text.iter().all(u8::is_ascii_whitespace)
+}
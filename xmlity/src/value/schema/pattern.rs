@@ -0,0 +1,419 @@
+//! Regular expressions as used by the `pattern` facet of XML Schema.
+use core::fmt;
+use std::collections::BTreeSet;
+
+use crate::name_tokens::{is_name_char, is_name_start_char};
+
+/// A compiled regular expression in the syntax of XML Schema, for the `pattern` facet of a [`SimpleType`](super::SimpleType).
+///
+/// Patterns always match the whole value, as if they were anchored at both ends. Branches, groups, the quantifiers `?`, `*`, `+` and `{n,m}`, `.`, character class expressions including negation and subtraction, and the escapes `\d`, `\s`, `\w`, `\i`, `\c` and their negations are supported. Unicode category escapes like `\p{Lu}` are not.
+///
+/// ```
+/// use xmlity::value::schema::Pattern;
+///
+/// let pattern = Pattern::new(r"[A-Z]{2}-\d+").unwrap();
+/// assert!(pattern.is_match("AB-123"));
+/// assert!(!pattern.is_match("AB-123 "));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    source: String,
+    node: Node,
+}
+
+impl Pattern {
+    /// Compiles a pattern.
+    pub fn new(pattern: &str) -> Result<Self, PatternError> {
+        let mut parser = Parser {
+            chars: pattern.chars().collect(),
+            position: 0,
+        };
+        let node = parser.parse_branches()?;
+        if parser.position < parser.chars.len() {
+            return Err(parser.error("unmatched `)`"));
+        }
+
+        Ok(Self {
+            source: pattern.to_owned(),
+            node,
+        })
+    }
+
+    /// Returns the pattern as it was written.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns `true` if the pattern matches the whole of `value`.
+    pub fn is_match(&self, value: &str) -> bool {
+        let chars = value.chars().collect::<Vec<_>>();
+        self.node
+            .ends(&chars, BTreeSet::from([0]))
+            .contains(&chars.len())
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+/// An error that occurs when compiling a [`Pattern`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternError {
+    position: usize,
+    reason: &'static str,
+}
+
+impl PatternError {
+    /// Returns the position of the character in the pattern where the error was found.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid pattern at {}: {}", self.position, self.reason)
+    }
+}
+
+impl std::error::Error for PatternError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    Char(CharClass),
+    Concat(Vec<Node>),
+    Branches(Vec<Node>),
+    Repeat {
+        node: Box<Node>,
+        min: u32,
+        max: Option<u32>,
+    },
+}
+
+impl Node {
+    /// Returns every position a match of this node can end at, starting from any of `starts`.
+    fn ends(&self, input: &[char], starts: BTreeSet<usize>) -> BTreeSet<usize> {
+        match self {
+            Node::Char(class) => starts
+                .into_iter()
+                .filter(|&start| input.get(start).is_some_and(|c| class.matches(*c)))
+                .map(|start| start + 1)
+                .collect(),
+            Node::Concat(nodes) => nodes
+                .iter()
+                .fold(starts, |starts, node| node.ends(input, starts)),
+            Node::Branches(nodes) => nodes
+                .iter()
+                .flat_map(|node| node.ends(input, starts.clone()))
+                .collect(),
+            Node::Repeat { node, min, max } => {
+                // Once another repetition reaches the same positions, further repetitions do too, so large bounds stop as soon as the input runs out.
+                let mut current = starts;
+                for _ in 0..*min {
+                    let next = node.ends(input, current.clone());
+                    if next == current {
+                        break;
+                    }
+                    current = next;
+                }
+
+                // Positions reached with fewer repetitions allow at least as many further matches, so positions that were already reached are not repeated again.
+                let mut ends = current.clone();
+                let mut count = *min;
+                while !current.is_empty() && max.is_none_or(|max| count < max) {
+                    current = node
+                        .ends(input, current)
+                        .into_iter()
+                        .filter(|end| !ends.contains(end))
+                        .collect();
+                    ends.extend(current.iter().copied());
+                    count += 1;
+                }
+                ends
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CharClass {
+    Literal(char),
+    /// `.`, which matches anything but line breaks.
+    Any,
+    Escape(MultiCharEscape),
+    Group {
+        negated: bool,
+        items: Vec<GroupItem>,
+        subtraction: Option<Box<CharClass>>,
+    },
+}
+
+impl CharClass {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            CharClass::Literal(literal) => *literal == c,
+            CharClass::Any => !matches!(c, '\n' | '\r'),
+            CharClass::Escape(escape) => escape.matches(c),
+            CharClass::Group {
+                negated,
+                items,
+                subtraction,
+            } => {
+                items.iter().any(|item| item.matches(c)) != *negated
+                    && !subtraction
+                        .as_ref()
+                        .is_some_and(|subtraction| subtraction.matches(c))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum GroupItem {
+    Range(char, char),
+    Class(CharClass),
+}
+
+impl GroupItem {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            GroupItem::Range(start, end) => (*start..=*end).contains(&c),
+            GroupItem::Class(class) => class.matches(c),
+        }
+    }
+}
+
+/// The escapes that stand for a set of characters. Unicode categories are approximated with the character predicates of the standard library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct MultiCharEscape {
+    kind: char,
+    negated: bool,
+}
+
+impl MultiCharEscape {
+    fn matches(self, c: char) -> bool {
+        let matches = match self.kind {
+            's' => matches!(c, ' ' | '\t' | '\n' | '\r'),
+            'i' => c == ':' || is_name_start_char(c),
+            'c' => c == ':' || is_name_char(c),
+            'd' => c.is_numeric(),
+            'w' => !(c.is_ascii_punctuation() || c.is_whitespace() || c.is_control()),
+            _ => unreachable!("Only known escapes are parsed."),
+        };
+        matches != self.negated
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn error(&self, reason: &'static str) -> PatternError {
+        PatternError {
+            position: self.position,
+            reason,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += 1;
+        Some(c)
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        let found = self.peek() == Some(expected);
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn parse_branches(&mut self) -> Result<Node, PatternError> {
+        let mut branches = vec![self.parse_branch()?];
+        while self.eat('|') {
+            branches.push(self.parse_branch()?);
+        }
+
+        Ok(if branches.len() == 1 {
+            branches.pop().expect("There is exactly one branch.")
+        } else {
+            Node::Branches(branches)
+        })
+    }
+
+    fn parse_branch(&mut self) -> Result<Node, PatternError> {
+        let mut pieces = Vec::new();
+        while self.peek().is_some_and(|c| !matches!(c, '|' | ')')) {
+            let atom = self.parse_atom()?;
+            pieces.push(self.parse_quantifier(atom)?);
+        }
+        Ok(Node::Concat(pieces))
+    }
+
+    fn parse_atom(&mut self) -> Result<Node, PatternError> {
+        match self.next().expect("The caller checks for the end.") {
+            '(' => {
+                let node = self.parse_branches()?;
+                if !self.eat(')') {
+                    return Err(self.error("unclosed `(`"));
+                }
+                Ok(node)
+            }
+            '[' => Ok(Node::Char(self.parse_group()?)),
+            '.' => Ok(Node::Char(CharClass::Any)),
+            '\\' => Ok(Node::Char(self.parse_escape()?)),
+            '?' | '*' | '+' | '{' => Err(self.error("quantifier without anything to repeat")),
+            ']' | '}' => Err(self.error("unescaped `]` or `}`")),
+            c => Ok(Node::Char(CharClass::Literal(c))),
+        }
+    }
+
+    fn parse_quantifier(&mut self, atom: Node) -> Result<Node, PatternError> {
+        let (min, max) = match self.peek() {
+            Some('?') => (0, Some(1)),
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('{') => {
+                self.position += 1;
+                let min = self
+                    .parse_number()?
+                    .ok_or_else(|| self.error("expected the minimum of a quantity"))?;
+                let max = if self.eat(',') {
+                    self.parse_number()?
+                } else {
+                    Some(min)
+                };
+                if self.peek() != Some('}') {
+                    return Err(self.error("unclosed `{`"));
+                }
+                if max.is_some_and(|max| max < min) {
+                    return Err(self.error("the maximum of a quantity is less than the minimum"));
+                }
+                (min, max)
+            }
+            _ => return Ok(atom),
+        };
+        self.position += 1;
+
+        Ok(Node::Repeat {
+            node: Box::new(atom),
+            min,
+            max,
+        })
+    }
+
+    fn parse_number(&mut self) -> Result<Option<u32>, PatternError> {
+        let start = self.position;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.position += 1;
+        }
+        if start == self.position {
+            return Ok(None);
+        }
+
+        self.chars[start..self.position]
+            .iter()
+            .collect::<String>()
+            .parse()
+            .map(Some)
+            .map_err(|_| self.error("quantity is too large"))
+    }
+
+    /// Parses an escape after the `\`.
+    fn parse_escape(&mut self) -> Result<CharClass, PatternError> {
+        let c = self
+            .next()
+            .ok_or_else(|| self.error("pattern ends with `\\`"))?;
+        Ok(match c {
+            'n' => CharClass::Literal('\n'),
+            'r' => CharClass::Literal('\r'),
+            't' => CharClass::Literal('\t'),
+            '\\' | '|' | '.' | '?' | '*' | '+' | '(' | ')' | '{' | '}' | '-' | '[' | ']' | '^' => {
+                CharClass::Literal(c)
+            }
+            's' | 'i' | 'c' | 'd' | 'w' => CharClass::Escape(MultiCharEscape {
+                kind: c,
+                negated: false,
+            }),
+            'S' | 'I' | 'C' | 'D' | 'W' => CharClass::Escape(MultiCharEscape {
+                kind: c.to_ascii_lowercase(),
+                negated: true,
+            }),
+            'p' | 'P' => return Err(self.error("Unicode category escapes are not supported")),
+            _ => return Err(self.error("unknown escape")),
+        })
+    }
+
+    /// Parses a character class expression after the `[`.
+    fn parse_group(&mut self) -> Result<CharClass, PatternError> {
+        let negated = self.eat('^');
+        let mut items = Vec::new();
+        let mut subtraction = None;
+
+        loop {
+            let Some(c) = self.next() else {
+                return Err(self.error("unclosed `[`"));
+            };
+            let item = match c {
+                ']' if !items.is_empty() => break,
+                '-' if self.peek() == Some('[') && !items.is_empty() => {
+                    self.position += 1;
+                    subtraction = Some(Box::new(self.parse_group()?));
+                    if !self.eat(']') {
+                        return Err(self.error("a subtraction must end the character class"));
+                    }
+                    break;
+                }
+                '[' => return Err(self.error("unescaped `[` in a character class")),
+                '\\' => match self.parse_escape()? {
+                    CharClass::Literal(c) => self.parse_range(c)?,
+                    class => GroupItem::Class(class),
+                },
+                c => self.parse_range(c)?,
+            };
+            items.push(item);
+        }
+
+        Ok(CharClass::Group {
+            negated,
+            items,
+            subtraction,
+        })
+    }
+
+    /// Parses the rest of a range starting with `start`, if it is one.
+    fn parse_range(&mut self, start: char) -> Result<GroupItem, PatternError> {
+        let is_range = self.peek() == Some('-')
+            && self
+                .chars
+                .get(self.position + 1)
+                .is_some_and(|c| !matches!(c, ']' | '['));
+        if !is_range {
+            return Ok(GroupItem::Class(CharClass::Literal(start)));
+        }
+        self.position += 1;
+
+        let end = match self.next().expect("The range was checked to have an end.") {
+            '\\' => match self.parse_escape()? {
+                CharClass::Literal(c) => c,
+                _ => return Err(self.error("a range can not end with a multi-character escape")),
+            },
+            c => c,
+        };
+        if end < start {
+            return Err(self.error("the end of a range is before its start"));
+        }
+
+        Ok(GroupItem::Range(start, end))
+    }
+}
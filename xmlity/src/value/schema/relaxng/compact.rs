@@ -0,0 +1,738 @@
+//! A parser for the compact syntax of RELAX NG.
+use std::collections::BTreeMap;
+
+use crate::{
+    name_tokens::{is_name_char, is_name_start_char},
+    ExpandedNameBuf, LocalNameBuf, XmlNamespace, XmlNamespaceBuf,
+};
+
+use super::{
+    super::{BuiltinType, Pattern as RegexPattern, SimpleType},
+    CompileError, NameClass,
+};
+
+const XSD_DATATYPES: &str = "http://www.w3.org/2001/XMLSchema-datatypes";
+
+const KEYWORDS: &[&str] = &[
+    "attribute",
+    "default",
+    "datatypes",
+    "div",
+    "element",
+    "empty",
+    "external",
+    "grammar",
+    "include",
+    "inherit",
+    "list",
+    "mixed",
+    "namespace",
+    "notAllowed",
+    "parent",
+    "start",
+    "string",
+    "text",
+    "token",
+];
+
+/// A pattern as it is written in the schema, with its names resolved.
+#[derive(Debug, Clone)]
+pub(super) enum Ast {
+    /// An element, with an id that is unique for every `element` in the schema.
+    Element(NameClass, Box<Ast>, usize),
+    Attribute(NameClass, Box<Ast>),
+    Group(Vec<Ast>),
+    Choice(Vec<Ast>),
+    Interleave(Vec<Ast>),
+    Optional(Box<Ast>),
+    ZeroOrMore(Box<Ast>),
+    OneOrMore(Box<Ast>),
+    List(Box<Ast>),
+    Mixed(Box<Ast>),
+    /// A reference to a definition, with the line it is on.
+    Ref(String, usize),
+    Text,
+    Empty,
+    NotAllowed,
+    Data(SimpleType),
+    Value(SimpleType, String),
+}
+
+/// How a definition is combined with other definitions of the same name.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Combine {
+    Replace,
+    Choice,
+    Interleave,
+}
+
+/// The definitions of a schema, where `start` is the pattern of the document.
+pub(super) struct Grammar {
+    pub start: Ast,
+    pub defines: BTreeMap<String, Ast>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// An identifier or keyword, which is never a keyword if it was escaped with `\`.
+    Ident {
+        name: String,
+        escaped: bool,
+    },
+    /// A prefixed name like `xsd:integer`.
+    CName(String, String),
+    /// A namespace wildcard like `xhtml:*`.
+    NsName(String),
+    Literal(String),
+    Punct(&'static str),
+}
+
+impl Token {
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self, Token::Ident { name, escaped: false } if name == keyword)
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, CompileError> {
+    let chars = source.chars().collect::<Vec<_>>();
+    let mut tokens = Vec::new();
+    let mut position = 0;
+    let mut line = 1;
+
+    let read_name = |position: &mut usize| {
+        let start = *position;
+        while chars.get(*position).is_some_and(|c| is_name_char(*c)) {
+            *position += 1;
+        }
+        chars[start..*position].iter().collect::<String>()
+    };
+
+    while let Some(&c) = chars.get(position) {
+        let token = match c {
+            '\n' => {
+                line += 1;
+                position += 1;
+                continue;
+            }
+            c if c.is_whitespace() => {
+                position += 1;
+                continue;
+            }
+            '#' => {
+                while chars.get(position).is_some_and(|c| *c != '\n') {
+                    position += 1;
+                }
+                continue;
+            }
+            '"' | '\'' => {
+                let triple = chars.get(position..position + 3) == Some(&[c, c, c]);
+                let delimiter = if triple { 3 } else { 1 };
+                position += delimiter;
+                let start = position;
+                loop {
+                    match chars.get(position) {
+                        None => return Err(CompileError::new(line, "unterminated literal")),
+                        Some('\n') if !triple => {
+                            return Err(CompileError::new(line, "unterminated literal"))
+                        }
+                        Some(&end)
+                            if end == c
+                                && (!triple
+                                    || chars.get(position..position + 3) == Some(&[c, c, c])) =>
+                        {
+                            break
+                        }
+                        Some(&other) => {
+                            if other == '\n' {
+                                line += 1;
+                            }
+                            position += 1;
+                        }
+                    }
+                }
+                let literal = chars[start..position].iter().collect();
+                position += delimiter;
+                Token::Literal(literal)
+            }
+            '\\' if chars
+                .get(position + 1)
+                .is_some_and(|c| is_name_start_char(*c)) =>
+            {
+                position += 1;
+                Token::Ident {
+                    name: read_name(&mut position),
+                    escaped: true,
+                }
+            }
+            c if is_name_start_char(c) => {
+                let name = read_name(&mut position);
+                match (chars.get(position), chars.get(position + 1)) {
+                    (Some(':'), Some('*')) => {
+                        position += 2;
+                        Token::NsName(name)
+                    }
+                    (Some(':'), Some(c)) if is_name_start_char(*c) => {
+                        position += 1;
+                        Token::CName(name, read_name(&mut position))
+                    }
+                    _ => Token::Ident {
+                        name,
+                        escaped: false,
+                    },
+                }
+            }
+            _ => {
+                let punct = ["|=", "&=", ">>"]
+                    .into_iter()
+                    .find(|punct| chars[position..].starts_with(&punct.chars().collect::<Vec<_>>()))
+                    .or_else(|| {
+                        [
+                            "{", "}", "(", ")", "[", "]", "=", ",", "|", "&", "?", "*", "+", "-",
+                            "~",
+                        ]
+                        .into_iter()
+                        .find(|punct| punct.starts_with(c))
+                    })
+                    .ok_or_else(|| {
+                        CompileError::new(line, format!("unexpected character {c:?}"))
+                    })?;
+                position += punct.chars().count();
+                Token::Punct(punct)
+            }
+        };
+        tokens.push((token, line));
+    }
+
+    Ok(tokens)
+}
+
+pub(super) struct Parser {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+    namespaces: BTreeMap<String, Option<String>>,
+    default_namespace: Option<String>,
+    datatypes: BTreeMap<String, String>,
+    defines: Vec<(String, Combine, Ast, usize)>,
+    next_element_id: usize,
+}
+
+impl Parser {
+    pub fn parse(source: &str) -> Result<Grammar, CompileError> {
+        let mut parser = Self {
+            tokens: tokenize(source)?,
+            position: 0,
+            namespaces: BTreeMap::from([(
+                "xml".to_owned(),
+                Some(XmlNamespace::XML.as_str().to_owned()),
+            )]),
+            default_namespace: None,
+            datatypes: BTreeMap::from([("xsd".to_owned(), XSD_DATATYPES.to_owned())]),
+            defines: Vec::new(),
+            next_element_id: 0,
+        };
+
+        parser.parse_declarations()?;
+
+        let is_grammar = parser.peek().is_none()
+            || parser.peek_is_keyword("div")
+            || parser.peek_is_keyword("include")
+            || matches!(
+                (parser.peek(), parser.tokens.get(parser.position + 1)),
+                (
+                    Some(Token::Ident { .. }),
+                    Some((Token::Punct("=" | "|=" | "&="), _))
+                )
+            );
+
+        if !is_grammar {
+            let start = parser.parse_pattern()?;
+            if parser.peek().is_some() {
+                return Err(parser.error("expected the end of the schema"));
+            }
+            return Ok(Grammar {
+                start,
+                defines: BTreeMap::new(),
+            });
+        }
+
+        while parser.peek().is_some() {
+            parser.parse_grammar_content()?;
+        }
+        parser.into_grammar()
+    }
+
+    fn line(&self) -> usize {
+        self.tokens
+            .get(self.position)
+            .or(self.tokens.last())
+            .map_or(1, |(_, line)| *line)
+    }
+
+    fn error(&self, reason: impl Into<String>) -> CompileError {
+        CompileError::new(self.line(), reason)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+
+    fn peek_is_keyword(&self, keyword: &str) -> bool {
+        self.peek().is_some_and(|token| token.is_keyword(keyword))
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.peek().cloned()?;
+        self.position += 1;
+        Some(token)
+    }
+
+    fn eat_punct(&mut self, punct: &'static str) -> bool {
+        let found = self.peek() == Some(&Token::Punct(punct));
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn expect_punct(&mut self, punct: &'static str) -> Result<(), CompileError> {
+        if self.eat_punct(punct) {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected `{punct}`")))
+        }
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.peek_is_keyword(keyword);
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    /// Parses an identifier or a keyword.
+    fn parse_name(&mut self) -> Result<String, CompileError> {
+        match self.next() {
+            Some(Token::Ident { name, .. }) => Ok(name),
+            _ => Err(self.error("expected a name")),
+        }
+    }
+
+    fn parse_literal(&mut self) -> Result<String, CompileError> {
+        let mut literal = match self.next() {
+            Some(Token::Literal(literal)) => literal,
+            _ => return Err(self.error("expected a literal")),
+        };
+        while self.eat_punct("~") {
+            match self.next() {
+                Some(Token::Literal(next)) => literal.push_str(&next),
+                _ => return Err(self.error("expected a literal after `~`")),
+            }
+        }
+        Ok(literal)
+    }
+
+    /// Skips annotations, which are written in brackets before most parts of a schema.
+    fn skip_annotations(&mut self) -> Result<(), CompileError> {
+        while self.eat_punct("[") {
+            let mut depth = 1;
+            while depth > 0 {
+                match self.next() {
+                    Some(Token::Punct("[")) => depth += 1,
+                    Some(Token::Punct("]")) => depth -= 1,
+                    Some(_) => {}
+                    None => return Err(self.error("unclosed annotation")),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Skips following annotations, written like `>> name [ ... ]` after patterns.
+    fn skip_following_annotations(&mut self) -> Result<(), CompileError> {
+        while self.eat_punct(">>") {
+            match self.next() {
+                Some(Token::Ident { .. } | Token::CName(..)) => self.skip_annotations()?,
+                _ => return Err(self.error("expected the name of an annotation")),
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_namespace_uri(&mut self) -> Result<Option<String>, CompileError> {
+        if self.eat_keyword("inherit") {
+            Ok(None)
+        } else {
+            self.parse_literal().map(Some)
+        }
+    }
+
+    fn parse_declarations(&mut self) -> Result<(), CompileError> {
+        loop {
+            self.skip_annotations()?;
+            if self.eat_keyword("namespace") {
+                let prefix = self.parse_name()?;
+                self.expect_punct("=")?;
+                let uri = self.parse_namespace_uri()?;
+                self.namespaces.insert(prefix, uri);
+            } else if self.peek_is_keyword("default")
+                && self
+                    .tokens
+                    .get(self.position + 1)
+                    .is_some_and(|(token, _)| token.is_keyword("namespace"))
+            {
+                self.position += 2;
+                let prefix = match self.peek() {
+                    Some(Token::Ident { .. }) => Some(self.parse_name()?),
+                    _ => None,
+                };
+                self.expect_punct("=")?;
+                let uri = self.parse_namespace_uri()?;
+                if let Some(prefix) = prefix {
+                    self.namespaces.insert(prefix, uri.clone());
+                }
+                self.default_namespace = uri;
+            } else if self.eat_keyword("datatypes") {
+                let prefix = self.parse_name()?;
+                self.expect_punct("=")?;
+                let uri = self.parse_literal()?;
+                self.datatypes.insert(prefix, uri);
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn parse_grammar_content(&mut self) -> Result<(), CompileError> {
+        self.skip_annotations()?;
+        let line = self.line();
+        if self.eat_keyword("div") {
+            self.expect_punct("{")?;
+            while !self.eat_punct("}") {
+                if self.peek().is_none() {
+                    return Err(self.error("expected `}`"));
+                }
+                self.parse_grammar_content()?;
+            }
+            return Ok(());
+        }
+        if self.peek_is_keyword("include") {
+            return Err(self.error("`include` is not supported"));
+        }
+
+        let name = match self.next() {
+            Some(Token::Ident { name, escaped }) if escaped || name == "start" => name,
+            Some(Token::Ident { name, .. }) if !KEYWORDS.contains(&name.as_str()) => name,
+            _ => return Err(CompileError::new(line, "expected a definition")),
+        };
+        let combine = match self.next() {
+            Some(Token::Punct("=")) => Combine::Replace,
+            Some(Token::Punct("|=")) => Combine::Choice,
+            Some(Token::Punct("&=")) => Combine::Interleave,
+            _ => return Err(self.error("expected `=`, `|=` or `&=`")),
+        };
+        let pattern = self.parse_pattern()?;
+        self.defines.push((name, combine, pattern, line));
+        Ok(())
+    }
+
+    fn into_grammar(self) -> Result<Grammar, CompileError> {
+        let mut combined: BTreeMap<String, (Combine, Vec<Ast>)> = BTreeMap::new();
+        for (name, combine, pattern, line) in self.defines {
+            let (existing, patterns) = combined
+                .entry(name.clone())
+                .or_insert((combine, Vec::new()));
+            if !patterns.is_empty() {
+                let combine = match (*existing, combine) {
+                    (Combine::Replace, Combine::Replace) => {
+                        return Err(CompileError::new(
+                            line,
+                            format!("`{name}` is defined more than once"),
+                        ))
+                    }
+                    (Combine::Replace, other) | (other, Combine::Replace) => other,
+                    (existing, other) if existing == other => other,
+                    _ => {
+                        return Err(CompileError::new(
+                            line,
+                            format!("`{name}` is combined with both `|=` and `&=`"),
+                        ))
+                    }
+                };
+                *existing = combine;
+            }
+            patterns.push(pattern);
+        }
+
+        let mut defines = combined
+            .into_iter()
+            .map(|(name, (combine, mut patterns))| {
+                let pattern = match combine {
+                    _ if patterns.len() == 1 => patterns.pop().expect("There is one pattern."),
+                    Combine::Interleave => Ast::Interleave(patterns),
+                    _ => Ast::Choice(patterns),
+                };
+                (name, pattern)
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        let start = defines
+            .remove("start")
+            .ok_or_else(|| CompileError::new(0, "the grammar has no `start`"))?;
+        Ok(Grammar { start, defines })
+    }
+
+    fn parse_pattern(&mut self) -> Result<Ast, CompileError> {
+        let first = self.parse_particle()?;
+        let operator = match self.peek() {
+            Some(Token::Punct(operator @ ("," | "|" | "&"))) => *operator,
+            _ => return Ok(first),
+        };
+
+        let mut particles = vec![first];
+        while self.peek() == Some(&Token::Punct(operator)) {
+            self.position += 1;
+            particles.push(self.parse_particle()?);
+        }
+        if matches!(self.peek(), Some(Token::Punct("," | "|" | "&"))) {
+            return Err(self.error("`,`, `|` and `&` can't be mixed without parentheses"));
+        }
+
+        Ok(match operator {
+            "," => Ast::Group(particles),
+            "|" => Ast::Choice(particles),
+            _ => Ast::Interleave(particles),
+        })
+    }
+
+    fn parse_particle(&mut self) -> Result<Ast, CompileError> {
+        let primary = self.parse_primary()?;
+        self.skip_following_annotations()?;
+        let particle = match self.peek() {
+            Some(Token::Punct("?")) => Ast::Optional(Box::new(primary)),
+            Some(Token::Punct("*")) => Ast::ZeroOrMore(Box::new(primary)),
+            Some(Token::Punct("+")) => Ast::OneOrMore(Box::new(primary)),
+            _ => return Ok(primary),
+        };
+        self.position += 1;
+        self.skip_following_annotations()?;
+        Ok(particle)
+    }
+
+    fn parse_braced_pattern(&mut self) -> Result<Ast, CompileError> {
+        self.expect_punct("{")?;
+        let pattern = self.parse_pattern()?;
+        self.expect_punct("}")?;
+        Ok(pattern)
+    }
+
+    fn parse_primary(&mut self) -> Result<Ast, CompileError> {
+        self.skip_annotations()?;
+        let line = self.line();
+        let token = self
+            .next()
+            .ok_or_else(|| self.error("expected a pattern"))?;
+
+        match token {
+            Token::Punct("(") => {
+                let pattern = self.parse_pattern()?;
+                self.expect_punct(")")?;
+                Ok(pattern)
+            }
+            Token::Literal(_) => {
+                self.position -= 1;
+                let value = self.parse_literal()?;
+                Ok(Ast::Value(SimpleType::new(BuiltinType::Token), value))
+            }
+            Token::CName(prefix, local) => {
+                let simple_type = self.resolve_datatype(&prefix, &local)?;
+                self.parse_datatype(simple_type)
+            }
+            Token::Ident {
+                name,
+                escaped: false,
+            } if KEYWORDS.contains(&name.as_str()) => match name.as_str() {
+                "element" => {
+                    let name_class = self.parse_name_class(true)?;
+                    let content = self.parse_braced_pattern()?;
+                    let id = self.next_element_id;
+                    self.next_element_id += 1;
+                    Ok(Ast::Element(name_class, Box::new(content), id))
+                }
+                "attribute" => {
+                    let name_class = self.parse_name_class(false)?;
+                    let content = self.parse_braced_pattern()?;
+                    Ok(Ast::Attribute(name_class, Box::new(content)))
+                }
+                "list" => Ok(Ast::List(Box::new(self.parse_braced_pattern()?))),
+                "mixed" => Ok(Ast::Mixed(Box::new(self.parse_braced_pattern()?))),
+                "empty" => Ok(Ast::Empty),
+                "notAllowed" => Ok(Ast::NotAllowed),
+                "text" => Ok(Ast::Text),
+                "string" => self.parse_datatype(SimpleType::new(BuiltinType::String)),
+                "token" => self.parse_datatype(SimpleType::new(BuiltinType::Token)),
+                other => Err(CompileError::new(
+                    line,
+                    format!("`{other}` is not supported in patterns"),
+                )),
+            },
+            Token::Ident { name, .. } => Ok(Ast::Ref(name, line)),
+            _ => Err(CompileError::new(line, "expected a pattern")),
+        }
+    }
+
+    /// Parses the parameters or value after the name of a datatype.
+    fn parse_datatype(&mut self, mut simple_type: SimpleType) -> Result<Ast, CompileError> {
+        if matches!(self.peek(), Some(Token::Literal(_))) {
+            let value = self.parse_literal()?;
+            return Ok(Ast::Value(simple_type, value));
+        }
+
+        if self.eat_punct("{") {
+            while !self.eat_punct("}") {
+                self.skip_annotations()?;
+                let line = self.line();
+                let param = self.parse_name()?;
+                self.expect_punct("=")?;
+                let value = self.parse_literal()?;
+                let length = || {
+                    value
+                        .trim()
+                        .parse::<usize>()
+                        .map_err(|_| CompileError::new(line, "expected a length"))
+                };
+                simple_type = match param.as_str() {
+                    "length" => simple_type
+                        .with_min_length(length()?)
+                        .with_max_length(length()?),
+                    "minLength" => simple_type.with_min_length(length()?),
+                    "maxLength" => simple_type.with_max_length(length()?),
+                    "pattern" => simple_type.with_pattern(
+                        RegexPattern::new(&value)
+                            .map_err(|error| CompileError::new(line, error.to_string()))?,
+                    ),
+                    _ => {
+                        return Err(CompileError::new(
+                            line,
+                            format!("the parameter `{param}` is not supported"),
+                        ))
+                    }
+                };
+            }
+        }
+        if self.peek() == Some(&Token::Punct("-")) {
+            return Err(self.error("excepting values from datatypes is not supported"));
+        }
+
+        Ok(Ast::Data(simple_type))
+    }
+
+    fn resolve_datatype(&self, prefix: &str, local: &str) -> Result<SimpleType, CompileError> {
+        let library = self
+            .datatypes
+            .get(prefix)
+            .ok_or_else(|| self.error(format!("undeclared datatypes prefix `{prefix}`")))?;
+        if library != XSD_DATATYPES {
+            return Err(self.error(format!("the datatype library {library:?} is not supported")));
+        }
+
+        let base = match local {
+            "string" => BuiltinType::String,
+            "token" => BuiltinType::Token,
+            "boolean" => BuiltinType::Boolean,
+            "decimal" => BuiltinType::Decimal,
+            "integer" => BuiltinType::Integer,
+            "nonNegativeInteger" => BuiltinType::NonNegativeInteger,
+            "positiveInteger" => BuiltinType::PositiveInteger,
+            "double" => BuiltinType::Double,
+            _ => {
+                return Err(self.error(format!("the datatype `{prefix}:{local}` is not supported")))
+            }
+        };
+        Ok(SimpleType::new(base))
+    }
+
+    fn parse_name_class(&mut self, is_element: bool) -> Result<NameClass, CompileError> {
+        let mut name_class = self.parse_inner_name_class(is_element)?;
+        while self.peek() == Some(&Token::Punct("|")) {
+            self.position += 1;
+            let other = self.parse_inner_name_class(is_element)?;
+            name_class = NameClass::Choice(Box::new(name_class), Box::new(other));
+        }
+        Ok(name_class)
+    }
+
+    fn parse_inner_name_class(&mut self, is_element: bool) -> Result<NameClass, CompileError> {
+        self.skip_annotations()?;
+        match self.next() {
+            Some(Token::Ident { name, .. }) => {
+                let namespace = if is_element {
+                    self.default_namespace.clone()
+                } else {
+                    None
+                };
+                self.name(namespace, name).map(NameClass::Name)
+            }
+            Some(Token::CName(prefix, local)) => {
+                let namespace = self.resolve_prefix(&prefix)?;
+                self.name(namespace, local).map(NameClass::Name)
+            }
+            Some(Token::NsName(prefix)) => {
+                let namespace = self.resolve_prefix(&prefix)?;
+                let namespace = self.namespace(namespace)?;
+                let except = self.parse_name_class_except(is_element)?;
+                Ok(NameClass::NsName(namespace, except))
+            }
+            Some(Token::Punct("*")) => {
+                let except = self.parse_name_class_except(is_element)?;
+                Ok(NameClass::AnyName(except))
+            }
+            Some(Token::Punct("(")) => {
+                let name_class = self.parse_name_class(is_element)?;
+                self.expect_punct(")")?;
+                Ok(name_class)
+            }
+            _ => Err(self.error("expected a name class")),
+        }
+    }
+
+    fn parse_name_class_except(
+        &mut self,
+        is_element: bool,
+    ) -> Result<Option<Box<NameClass>>, CompileError> {
+        if self.eat_punct("-") {
+            Ok(Some(Box::new(self.parse_inner_name_class(is_element)?)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn resolve_prefix(&self, prefix: &str) -> Result<Option<String>, CompileError> {
+        self.namespaces
+            .get(prefix)
+            .cloned()
+            .ok_or_else(|| self.error(format!("undeclared namespace prefix `{prefix}`")))
+    }
+
+    fn namespace(
+        &self,
+        namespace: Option<String>,
+    ) -> Result<Option<XmlNamespaceBuf>, CompileError> {
+        namespace
+            .filter(|namespace| !namespace.is_empty())
+            .map(|namespace| {
+                XmlNamespaceBuf::new(namespace).map_err(|error| self.error(error.to_string()))
+            })
+            .transpose()
+    }
+
+    fn name(
+        &self,
+        namespace: Option<String>,
+        local_name: String,
+    ) -> Result<ExpandedNameBuf, CompileError> {
+        let local_name =
+            LocalNameBuf::new(local_name).map_err(|error| self.error(error.to_string()))?;
+        Ok(ExpandedNameBuf::new(local_name, self.namespace(namespace)?))
+    }
+}
@@ -0,0 +1,721 @@
+//! This module contains a validator for schemas written in the compact syntax of RELAX NG, as used by documentation formats like DocBook and TEI.
+//!
+//! A [`Schema`] is compiled from the text of a schema with [`Schema::compile`] and validates [`XmlValue`]s with [`Schema::validate`], reporting the issues it finds in a [`SchemaReport`] like the validator of the parent module.
+//!
+//! Grammars with `start`, definitions combined with `|=` and `&=`, and `div`, as well as single patterns, are supported. Patterns can use `element`, `attribute`, `,`, `|`, `&`, `?`, `*`, `+`, `mixed`, `list`, `text`, `empty`, `notAllowed`, values, and the `string` and `token` datatypes together with the XML Schema datatypes supported by [`BuiltinType`](super::BuiltinType), restricted by the `length`, `minLength`, `maxLength` and `pattern` parameters. Namespaces are declared with `namespace` and `default namespace`. Annotations are ignored. `include`, `external`, nested grammars and `parent` are not supported.
+//!
+//! ```
+//! use xmlity::value::{schema::relaxng::Schema, XmlValue};
+//!
+//! let schema = Schema::compile(
+//!     r#"
+//!     start = element book { attribute id { xsd:token }, title, chapter+ }
+//!     title = element title { text }
+//!     chapter = element chapter { title?, element para { mixed { element em { text }* } }* }
+//!     "#,
+//! )
+//! .unwrap();
+//!
+//! let book: XmlValue = r#"<book id="b1"><title>T</title><chapter><para>An <em>x</em>.</para></chapter></book>"#
+//!     .parse()
+//!     .unwrap();
+//! assert!(schema.validate(&book).is_ok());
+//!
+//! let book: XmlValue = r#"<book><title>T</title></book>"#.parse().unwrap();
+//! assert_eq!(
+//!     schema.validate(&book).unwrap_err().to_string(),
+//!     "missing required attribute id at /book; content ends before a required element at /book"
+//! );
+//! ```
+use core::fmt;
+use std::{collections::BTreeMap, sync::Arc};
+
+use crate::{
+    value::{sibling_segments, XmlChild, XmlElement, XmlValue, XmlValuePathSegment},
+    ExpandedNameBuf, XmlNamespaceBuf,
+};
+
+use super::{
+    document_root, is_whitespace, BuiltinType, Facet, SchemaIssue, SchemaIssueKind, SchemaReport,
+    SimpleType, XmlValuePath,
+};
+
+mod compact;
+
+use compact::{Ast, Grammar, Parser};
+
+/// A compiled RELAX NG schema.
+#[derive(Debug, Clone)]
+pub struct Schema {
+    start: Arc<Pat>,
+    /// The content of every element pattern of the schema.
+    elements: Vec<Arc<Pat>>,
+}
+
+impl Schema {
+    /// Compiles a schema written in the compact syntax.
+    pub fn compile(source: &str) -> Result<Self, CompileError> {
+        let Grammar { start, defines } = Parser::parse(source)?;
+
+        let mut compiler = Compiler {
+            defines: &defines,
+            elements: Vec::new(),
+            slots: BTreeMap::new(),
+            refs: Vec::new(),
+        };
+        let start = compiler.compile(&start)?;
+
+        Ok(Self {
+            start,
+            elements: compiler.elements,
+        })
+    }
+
+    /// Validates a value against the schema.
+    ///
+    /// The value is either a single element, or a document of a sequence containing exactly one element, which can be surrounded by an XML declaration, a doctype, comments, processing instructions and whitespace.
+    pub fn validate(&self, value: &XmlValue) -> Result<(), SchemaReport> {
+        let mut issues = Vec::new();
+        if let Some(root) = document_root(value, &mut issues) {
+            let mut validator = Validator {
+                schema: self,
+                path: vec![XmlValuePathSegment::Element {
+                    name: root.name.clone(),
+                    position: None,
+                }],
+            };
+
+            let candidates = self.start.first_elements(&root.name);
+            if candidates.is_empty() {
+                issues.push(validator.issue(SchemaIssueKind::UndeclaredElement {
+                    name: root.name.clone(),
+                }));
+            } else {
+                issues.extend(validator.validate_candidates(&candidates, root).1);
+            }
+        }
+
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(SchemaReport { issues })
+        }
+    }
+}
+
+/// An error that occurs when compiling a [`Schema`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompileError {
+    line: usize,
+    reason: String,
+}
+
+impl CompileError {
+    fn new(line: usize, reason: impl Into<String>) -> Self {
+        Self {
+            line,
+            reason: reason.into(),
+        }
+    }
+
+    /// Returns the line of the schema where the error was found, starting from 1, or 0 for errors that concern the whole schema.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            write!(f, "invalid schema: {}", self.reason)
+        } else {
+            write!(f, "invalid schema at line {}: {}", self.line, self.reason)
+        }
+    }
+}
+
+impl std::error::Error for CompileError {}
+
+/// The names an element or attribute pattern allows.
+#[derive(Debug, Clone, PartialEq)]
+enum NameClass {
+    Name(ExpandedNameBuf),
+    /// Any name in a namespace, except for the names of the second name class.
+    NsName(Option<XmlNamespaceBuf>, Option<Box<NameClass>>),
+    /// Any name, except for the names of the name class.
+    AnyName(Option<Box<NameClass>>),
+    Choice(Box<NameClass>, Box<NameClass>),
+}
+
+impl NameClass {
+    fn contains(&self, name: &ExpandedNameBuf) -> bool {
+        let excepted = |except: &Option<Box<NameClass>>| {
+            except.as_ref().is_some_and(|except| except.contains(name))
+        };
+        match self {
+            NameClass::Name(expected) => expected == name,
+            NameClass::NsName(namespace, except) => {
+                namespace.as_deref() == name.namespace() && !excepted(except)
+            }
+            NameClass::AnyName(except) => !excepted(except),
+            NameClass::Choice(first, second) => first.contains(name) || second.contains(name),
+        }
+    }
+
+    /// Returns the first name the name class contains by name, if any.
+    fn first_name(&self) -> Option<&ExpandedNameBuf> {
+        match self {
+            NameClass::Name(name) => Some(name),
+            NameClass::Choice(first, second) => first.first_name().or_else(|| second.first_name()),
+            _ => None,
+        }
+    }
+}
+
+/// A compiled pattern, on which validation works by taking derivatives with respect to the content of elements.
+#[derive(Debug, Clone, PartialEq)]
+enum Pat {
+    Empty,
+    NotAllowed,
+    Text,
+    Choice(Arc<Pat>, Arc<Pat>),
+    Interleave(Arc<Pat>, Arc<Pat>),
+    Group(Arc<Pat>, Arc<Pat>),
+    OneOrMore(Arc<Pat>),
+    List(Arc<Pat>),
+    Data(SimpleType),
+    Value(SimpleType, String),
+    Attribute(NameClass, Arc<Pat>),
+    /// An element, whose content is in [`Schema::elements`] at the index.
+    Element(NameClass, usize),
+}
+
+fn empty() -> Arc<Pat> {
+    Arc::new(Pat::Empty)
+}
+
+fn not_allowed() -> Arc<Pat> {
+    Arc::new(Pat::NotAllowed)
+}
+
+fn choice(first: Arc<Pat>, second: Arc<Pat>) -> Arc<Pat> {
+    match (&*first, &*second) {
+        (Pat::NotAllowed, _) => second,
+        (_, Pat::NotAllowed) => first,
+        _ if first == second => first,
+        _ => Arc::new(Pat::Choice(first, second)),
+    }
+}
+
+fn group(first: Arc<Pat>, second: Arc<Pat>) -> Arc<Pat> {
+    match (&*first, &*second) {
+        (Pat::NotAllowed, _) | (_, Pat::NotAllowed) => not_allowed(),
+        (Pat::Empty, _) => second,
+        (_, Pat::Empty) => first,
+        _ => Arc::new(Pat::Group(first, second)),
+    }
+}
+
+fn interleave(first: Arc<Pat>, second: Arc<Pat>) -> Arc<Pat> {
+    match (&*first, &*second) {
+        (Pat::NotAllowed, _) | (_, Pat::NotAllowed) => not_allowed(),
+        (Pat::Empty, _) => second,
+        (_, Pat::Empty) => first,
+        _ => Arc::new(Pat::Interleave(first, second)),
+    }
+}
+
+fn one_or_more(pattern: Arc<Pat>) -> Arc<Pat> {
+    match &*pattern {
+        Pat::NotAllowed | Pat::Empty => pattern,
+        _ => Arc::new(Pat::OneOrMore(pattern)),
+    }
+}
+
+impl Pat {
+    fn is_not_allowed(&self) -> bool {
+        matches!(self, Pat::NotAllowed)
+    }
+
+    /// Whether the pattern matches nothing, so that it can end the content of an element.
+    fn nullable(&self) -> bool {
+        match self {
+            Pat::Empty | Pat::Text => true,
+            Pat::Choice(first, second) => first.nullable() || second.nullable(),
+            Pat::Interleave(first, second) | Pat::Group(first, second) => {
+                first.nullable() && second.nullable()
+            }
+            Pat::OneOrMore(pattern) => pattern.nullable(),
+            _ => false,
+        }
+    }
+
+    /// Takes the derivative of the pattern with respect to one of its parts, given by `derive`, which decides what leaf patterns become when the part is consumed.
+    ///
+    /// Groups are ordered for text and elements, but not for attributes.
+    fn derive(
+        self: &Arc<Self>,
+        ordered: bool,
+        derive: &impl Fn(&Arc<Pat>) -> Option<Arc<Pat>>,
+    ) -> Arc<Pat> {
+        if let Some(derived) = derive(self) {
+            return derived;
+        }
+
+        match &**self {
+            Pat::Choice(first, second) => choice(
+                first.derive(ordered, derive),
+                second.derive(ordered, derive),
+            ),
+            Pat::Interleave(first, second) => choice(
+                interleave(first.derive(ordered, derive), second.clone()),
+                interleave(first.clone(), second.derive(ordered, derive)),
+            ),
+            Pat::Group(first, second) => {
+                let derived = group(first.derive(ordered, derive), second.clone());
+                if !ordered {
+                    choice(
+                        derived,
+                        group(first.clone(), second.derive(ordered, derive)),
+                    )
+                } else if first.nullable() {
+                    choice(derived, second.derive(ordered, derive))
+                } else {
+                    derived
+                }
+            }
+            Pat::OneOrMore(pattern) => group(
+                pattern.derive(ordered, derive),
+                choice(self.clone(), empty()),
+            ),
+            _ => not_allowed(),
+        }
+    }
+
+    fn text_deriv(self: &Arc<Self>, text: &str) -> Arc<Pat> {
+        self.derive(true, &|pattern| match &**pattern {
+            Pat::Text => Some(pattern.clone()),
+            Pat::Data(simple_type) => Some(if simple_type.check(text).is_ok() {
+                empty()
+            } else {
+                not_allowed()
+            }),
+            Pat::Value(simple_type, value) => Some(if values_equal(simple_type, value, text) {
+                empty()
+            } else {
+                not_allowed()
+            }),
+            Pat::List(pattern) => {
+                let derived = text
+                    .split_ascii_whitespace()
+                    .fold(pattern.clone(), |pattern, token| pattern.text_deriv(token));
+                Some(if derived.nullable() {
+                    empty()
+                } else {
+                    not_allowed()
+                })
+            }
+            _ => None,
+        })
+    }
+
+    /// Whether a text value matches the pattern, such as the value of an attribute.
+    fn matches_value(self: &Arc<Self>, text: &str) -> bool {
+        (self.nullable() && text.bytes().all(|byte| byte.is_ascii_whitespace()))
+            || self.text_deriv(text).nullable()
+    }
+
+    /// Derives the pattern by an attribute, where a `value` of `None` matches any value so validation can continue past an invalid one.
+    fn attribute_deriv(self: &Arc<Self>, name: &ExpandedNameBuf, value: Option<&str>) -> Arc<Pat> {
+        self.derive(false, &|pattern| match &**pattern {
+            Pat::Attribute(name_class, content) => Some(
+                if name_class.contains(name)
+                    && value.is_none_or(|value| content.matches_value(value))
+                {
+                    empty()
+                } else {
+                    not_allowed()
+                },
+            ),
+            _ => None,
+        })
+    }
+
+    /// Replaces the attributes that were not matched with `replacement`, since all attributes of an element have been seen.
+    fn close_attributes(self: &Arc<Self>, replacement: &Arc<Pat>) -> Arc<Pat> {
+        match &**self {
+            Pat::Attribute(..) => replacement.clone(),
+            Pat::Choice(first, second) => choice(
+                first.close_attributes(replacement),
+                second.close_attributes(replacement),
+            ),
+            Pat::Interleave(first, second) => interleave(
+                first.close_attributes(replacement),
+                second.close_attributes(replacement),
+            ),
+            Pat::Group(first, second) => group(
+                first.close_attributes(replacement),
+                second.close_attributes(replacement),
+            ),
+            Pat::OneOrMore(pattern) => one_or_more(pattern.close_attributes(replacement)),
+            _ => self.clone(),
+        }
+    }
+
+    fn element_deriv(self: &Arc<Self>, accepted: &[usize]) -> Arc<Pat> {
+        self.derive(true, &|pattern| match &**pattern {
+            Pat::Element(_, slot) => Some(if accepted.contains(slot) {
+                empty()
+            } else {
+                not_allowed()
+            }),
+            _ => None,
+        })
+    }
+
+    /// Calls `f` with every part of the pattern that can match next.
+    fn visit_first<'p>(&'p self, f: &mut impl FnMut(&'p Pat)) {
+        match self {
+            Pat::Choice(first, second) | Pat::Interleave(first, second) => {
+                first.visit_first(f);
+                second.visit_first(f);
+            }
+            Pat::Group(first, second) => {
+                first.visit_first(f);
+                if first.nullable() {
+                    second.visit_first(f);
+                }
+            }
+            Pat::OneOrMore(pattern) => pattern.visit_first(f),
+            leaf => f(leaf),
+        }
+    }
+
+    /// Calls `f` with every attribute pattern that has not been matched yet.
+    fn visit_attributes<'p>(&'p self, f: &mut impl FnMut(&'p NameClass, &'p Arc<Pat>)) {
+        match self {
+            Pat::Choice(first, second)
+            | Pat::Interleave(first, second)
+            | Pat::Group(first, second) => {
+                first.visit_attributes(f);
+                second.visit_attributes(f);
+            }
+            Pat::OneOrMore(pattern) => pattern.visit_attributes(f),
+            Pat::Attribute(name_class, content) => f(name_class, content),
+            _ => {}
+        }
+    }
+
+    /// Returns the element patterns that an element with the name can match next.
+    fn first_elements(&self, name: &ExpandedNameBuf) -> Vec<usize> {
+        let mut slots = Vec::new();
+        self.visit_first(&mut |pattern| {
+            if let Pat::Element(name_class, slot) = pattern {
+                if name_class.contains(name) && !slots.contains(slot) {
+                    slots.push(*slot);
+                }
+            }
+        });
+        slots
+    }
+
+    /// Explains why a text does not match the pattern.
+    fn explain_text(&self, text: &str) -> SchemaIssueKind {
+        let mut expects_text = false;
+        let mut facet = None;
+        self.visit_first(&mut |pattern| match pattern {
+            Pat::Data(simple_type) => {
+                expects_text = true;
+                facet = facet.take().or(simple_type.check(text).err());
+            }
+            Pat::Value(..) | Pat::List(_) => expects_text = true,
+            _ => {}
+        });
+
+        if expects_text {
+            SchemaIssueKind::InvalidValue {
+                value: text.to_owned(),
+                facet: facet.unwrap_or(Facet::Enumeration),
+            }
+        } else {
+            SchemaIssueKind::UnexpectedText
+        }
+    }
+}
+
+/// Compares a value of a pattern with a text, after normalizing the whitespace of both unless the datatype is `string`.
+fn values_equal(simple_type: &SimpleType, value: &str, text: &str) -> bool {
+    if simple_type.base == BuiltinType::String {
+        value == text
+    } else {
+        value
+            .split_ascii_whitespace()
+            .eq(text.split_ascii_whitespace())
+    }
+}
+
+struct Compiler<'g> {
+    defines: &'g BTreeMap<String, Ast>,
+    elements: Vec<Arc<Pat>>,
+    /// The index in `elements` of each element pattern that has been compiled, by its id.
+    slots: BTreeMap<usize, usize>,
+    /// The references being expanded, which can only be recursive through an element.
+    refs: Vec<&'g str>,
+}
+
+impl<'g> Compiler<'g> {
+    fn compile(&mut self, ast: &'g Ast) -> Result<Arc<Pat>, CompileError> {
+        let fold = |compiler: &mut Self,
+                    asts: &'g [Ast],
+                    combine: fn(Arc<Pat>, Arc<Pat>) -> Arc<Pat>|
+         -> Result<Arc<Pat>, CompileError> {
+            let mut patterns = asts.iter().map(|ast| compiler.compile(ast));
+            let first = patterns
+                .next()
+                .expect("Combined patterns are never empty.")?;
+            patterns.try_fold(first, |combined, pattern| Ok(combine(combined, pattern?)))
+        };
+
+        Ok(match ast {
+            Ast::Element(name_class, content, id) => {
+                let slot = match self.slots.get(id) {
+                    Some(slot) => *slot,
+                    None => {
+                        let slot = self.elements.len();
+                        self.elements.push(not_allowed());
+                        self.slots.insert(*id, slot);
+
+                        let refs = std::mem::take(&mut self.refs);
+                        self.elements[slot] = self.compile(content)?;
+                        self.refs = refs;
+                        slot
+                    }
+                };
+                Arc::new(Pat::Element(name_class.clone(), slot))
+            }
+            Ast::Attribute(name_class, content) => {
+                Arc::new(Pat::Attribute(name_class.clone(), self.compile(content)?))
+            }
+            Ast::Group(asts) => fold(self, asts, group)?,
+            Ast::Choice(asts) => fold(self, asts, choice)?,
+            Ast::Interleave(asts) => fold(self, asts, interleave)?,
+            Ast::Optional(ast) => choice(self.compile(ast)?, empty()),
+            Ast::ZeroOrMore(ast) => choice(one_or_more(self.compile(ast)?), empty()),
+            Ast::OneOrMore(ast) => one_or_more(self.compile(ast)?),
+            Ast::List(ast) => Arc::new(Pat::List(self.compile(ast)?)),
+            Ast::Mixed(ast) => interleave(self.compile(ast)?, Arc::new(Pat::Text)),
+            Ast::Ref(name, line) => {
+                let (name, define) = self
+                    .defines
+                    .get_key_value(name)
+                    .ok_or_else(|| CompileError::new(*line, format!("`{name}` is not defined")))?;
+                if self.refs.contains(&name.as_str()) {
+                    return Err(CompileError::new(
+                        *line,
+                        format!("`{name}` refers to itself outside of an element"),
+                    ));
+                }
+
+                self.refs.push(name);
+                let pattern = self.compile(define)?;
+                self.refs.pop();
+                pattern
+            }
+            Ast::Text => Arc::new(Pat::Text),
+            Ast::Empty => empty(),
+            Ast::NotAllowed => not_allowed(),
+            Ast::Data(simple_type) => Arc::new(Pat::Data(simple_type.clone())),
+            Ast::Value(simple_type, value) => {
+                Arc::new(Pat::Value(simple_type.clone(), value.clone()))
+            }
+        })
+    }
+}
+
+/// A part of the content of an element, with adjacent text joined together.
+enum Content<'v> {
+    Text(String),
+    Element(usize, &'v XmlElement),
+}
+
+struct Validator<'s> {
+    schema: &'s Schema,
+    /// The location being validated, outermost first.
+    path: Vec<XmlValuePathSegment>,
+}
+
+impl Validator<'_> {
+    fn issue(&self, kind: SchemaIssueKind) -> SchemaIssue {
+        SchemaIssue {
+            path: XmlValuePath::from_outermost(self.path.clone()),
+            kind,
+        }
+    }
+
+    /// Validates an element whose segment is already in the path against the element patterns it can match, returning the ones it matches and the issues of the closest one if there are none.
+    fn validate_candidates(
+        &mut self,
+        candidates: &[usize],
+        element: &XmlElement,
+    ) -> (Vec<usize>, Vec<SchemaIssue>) {
+        let mut accepted = Vec::new();
+        let mut closest: Option<Vec<SchemaIssue>> = None;
+        for slot in candidates {
+            let issues = self.validate_element(*slot, element);
+            if issues.is_empty() {
+                accepted.push(*slot);
+            } else if closest
+                .as_ref()
+                .is_none_or(|closest| issues.len() < closest.len())
+            {
+                closest = Some(issues);
+            }
+        }
+
+        if accepted.is_empty() {
+            (candidates.to_vec(), closest.unwrap_or_default())
+        } else {
+            (accepted, Vec::new())
+        }
+    }
+
+    fn validate_element(&mut self, slot: usize, element: &XmlElement) -> Vec<SchemaIssue> {
+        let mut issues = Vec::new();
+        let mut pattern = self.schema.elements[slot].clone();
+
+        for attribute in &element.attributes {
+            let value = String::from_utf8_lossy(&attribute.value.0);
+            let derived = pattern.attribute_deriv(&attribute.name, Some(&value));
+            if derived.is_not_allowed() {
+                self.path.push(XmlValuePathSegment::Attribute {
+                    name: attribute.name.clone(),
+                });
+                issues.push(self.issue(explain_attribute(&pattern, &attribute.name, &value)));
+                self.path.pop();
+
+                let recovered = pattern.attribute_deriv(&attribute.name, None);
+                if !recovered.is_not_allowed() {
+                    pattern = recovered;
+                }
+            } else {
+                pattern = derived;
+            }
+        }
+
+        let closed = pattern.close_attributes(&not_allowed());
+        pattern = if closed.is_not_allowed() {
+            let mut missing = None;
+            pattern.visit_attributes(&mut |name_class, _| {
+                missing = missing.take().or(name_class.first_name().cloned());
+            });
+            issues.push(self.issue(match missing {
+                Some(name) => SchemaIssueKind::MissingAttribute { name },
+                None => SchemaIssueKind::IncompleteContent,
+            }));
+            pattern.close_attributes(&empty())
+        } else {
+            closed
+        };
+
+        let children = element
+            .children
+            .iter()
+            .filter_map(|child| match child {
+                XmlChild::Element(child) => Some(child),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let mut content = Vec::new();
+        let mut text = String::new();
+        let mut index = 0;
+        for child in element.children.iter() {
+            match child {
+                XmlChild::Text(value) => text.push_str(&String::from_utf8_lossy(&value.0)),
+                XmlChild::CData(value) => text.push_str(&String::from_utf8_lossy(&value.0)),
+                XmlChild::Element(child) => {
+                    content.push(Content::Text(std::mem::take(&mut text)));
+                    content.push(Content::Element(index, child));
+                    index += 1;
+                }
+                _ => {}
+            }
+        }
+        content.push(Content::Text(text));
+
+        if children.is_empty() {
+            let Some(Content::Text(text)) = content.pop() else {
+                unreachable!("The content of an element without children is its text.");
+            };
+            let mut derived = pattern.text_deriv(&text);
+            if is_whitespace(text.as_bytes()) {
+                derived = choice(pattern.clone(), derived);
+            }
+            if !derived.nullable() {
+                issues.push(self.issue(match pattern.explain_text(&text) {
+                    SchemaIssueKind::UnexpectedText if !derived.is_not_allowed() => {
+                        SchemaIssueKind::IncompleteContent
+                    }
+                    kind => kind,
+                }));
+                return issues;
+            }
+            pattern = derived;
+        }
+
+        let segments = sibling_segments(&children);
+        for part in content {
+            match part {
+                Content::Text(text) if is_whitespace(text.as_bytes()) => {}
+                Content::Text(text) => {
+                    let derived = pattern.text_deriv(&text);
+                    if derived.is_not_allowed() {
+                        issues.push(self.issue(pattern.explain_text(&text)));
+                    } else {
+                        pattern = derived;
+                    }
+                }
+                Content::Element(index, child) => {
+                    self.path.push(segments[index].clone());
+                    let candidates = pattern.first_elements(&child.name);
+                    if candidates.is_empty() {
+                        issues.push(self.issue(SchemaIssueKind::UnexpectedElement {
+                            name: child.name.clone(),
+                        }));
+                    } else {
+                        let (accepted, child_issues) = self.validate_candidates(&candidates, child);
+                        issues.extend(child_issues);
+                        pattern = pattern.element_deriv(&accepted);
+                    }
+                    self.path.pop();
+                }
+            }
+        }
+
+        if !pattern.nullable() {
+            issues.push(self.issue(SchemaIssueKind::IncompleteContent));
+        }
+        issues
+    }
+}
+
+/// Explains why an attribute does not match the pattern of its element.
+fn explain_attribute(pattern: &Pat, name: &ExpandedNameBuf, value: &str) -> SchemaIssueKind {
+    let mut declared = None;
+    pattern.visit_attributes(&mut |name_class, content| {
+        if declared.is_none() && name_class.contains(name) {
+            declared = Some(content.clone());
+        }
+    });
+
+    match declared {
+        Some(content) => match content.explain_text(value) {
+            SchemaIssueKind::UnexpectedText => SchemaIssueKind::InvalidValue {
+                value: value.to_owned(),
+                facet: Facet::Enumeration,
+            },
+            kind => kind,
+        },
+        None => SchemaIssueKind::UndeclaredAttribute { name: name.clone() },
+    }
+}
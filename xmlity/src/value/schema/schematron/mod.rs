@@ -0,0 +1,149 @@
+//! This module contains a validator for business rules in the style of Schematron, which check what grammars cannot express, like a total matching the sum of its items, without a separate toolchain.
+//!
+//! A [`Schema`] is a list of [`Rule`]s. A rule selects context nodes with a [`Query`], and evaluates the tests of its assertions with each context node as the context of the test. An assertion added with [`Rule::with_assert`] fails when its test is false, and one added with [`Rule::with_report`] fails when its test is true. Every failure is reported as a [`SchemaIssueKind::FailedAssertion`] at the path of the context node.
+//!
+//! Queries are written in a subset of XPath 1.0, described on [`Query`]. Like in Schematron, contexts are matched anywhere in the document unless they start with `/`, so a context of `book` matches every `book` element. Unlike in Schematron, rules are not grouped in patterns, so a node is checked by every rule whose context matches it.
+//!
+//! Rules check [`XmlValue`]s, so they can check documents after deserializing them into [`XmlValue`]s, and values that implement [`Serialize`](crate::Serialize) before serializing them by first turning them into [`XmlValue`]s with [`to_value`](crate::value::to_value).
+//!
+//! ```
+//! use xmlity::value::{
+//!     schema::schematron::{Rule, Schema},
+//!     XmlValue,
+//! };
+//!
+//! let schema = Schema::new()
+//!     .with_rule(
+//!         Rule::new("order".parse().unwrap())
+//!             .with_assert("@id".parse().unwrap(), "an order has an id")
+//!             .with_assert(
+//!                 "sum(item/@price) = total".parse().unwrap(),
+//!                 "the total is the sum of the prices",
+//!             ),
+//!     )
+//!     .with_rule(
+//!         Rule::new("item".parse().unwrap())
+//!             .with_report("@price < 0".parse().unwrap(), "prices are not negative"),
+//!     );
+//!
+//! let order: XmlValue = r#"<order id="1"><item price="2.5"/><item price="1.5"/><total>4</total></order>"#
+//!     .parse()
+//!     .unwrap();
+//! assert!(schema.validate(&order).is_ok());
+//!
+//! let order: XmlValue = r#"<order><item price="2"/><item price="-1"/><total>2</total></order>"#
+//!     .parse()
+//!     .unwrap();
+//! assert_eq!(
+//!     schema.validate(&order).unwrap_err().to_string(),
+//!     "an order has an id at /order; the total is the sum of the prices at /order; prices are not negative at /order/item[2]"
+//! );
+//! ```
+use crate::value::{
+    query::{Document, Query},
+    XmlValue,
+};
+
+use super::{SchemaIssue, SchemaIssueKind, SchemaReport};
+
+/// A list of rules that documents are validated against.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Schema {
+    rules: Vec<Rule>,
+}
+
+impl Schema {
+    /// Creates a schema without rules.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a rule to the schema.
+    pub fn with_rule(mut self, rule: Rule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Validates a value against the rules of the schema.
+    ///
+    /// The value is either a single element or a whole document. The issues are in document order of their context nodes, and in the order the rules and assertions were added for the same context node.
+    pub fn validate(&self, value: &XmlValue) -> Result<(), SchemaReport> {
+        let document = Document::new(value);
+
+        let mut failures = Vec::new();
+        for rule in &self.rules {
+            for node in rule.context.matches(&document) {
+                failures.extend(
+                    rule.assertions
+                        .iter()
+                        .filter(|assertion| {
+                            assertion.test.test(&document, node) == assertion.report
+                        })
+                        .map(|assertion| (node, &assertion.message)),
+                );
+            }
+        }
+        failures.sort_by_key(|(node, _)| *node);
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(SchemaReport {
+                issues: failures
+                    .into_iter()
+                    .map(|(node, message)| SchemaIssue {
+                        path: document.path(node),
+                        kind: SchemaIssueKind::FailedAssertion {
+                            message: message.clone(),
+                        },
+                    })
+                    .collect(),
+            })
+        }
+    }
+}
+
+/// A rule that checks assertions for every node its context matches.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    context: Query,
+    assertions: Vec<Assertion>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Assertion {
+    test: Query,
+    message: String,
+    /// Whether the assertion fails when the test is true, like `report` in Schematron.
+    report: bool,
+}
+
+impl Rule {
+    /// Creates a rule for the nodes the context matches.
+    pub fn new(context: Query) -> Self {
+        Self {
+            context,
+            assertions: Vec::new(),
+        }
+    }
+
+    /// Adds an assertion that fails with the message when the test is false.
+    pub fn with_assert(mut self, test: Query, message: impl Into<String>) -> Self {
+        self.assertions.push(Assertion {
+            test,
+            message: message.into(),
+            report: false,
+        });
+        self
+    }
+
+    /// Adds an assertion that fails with the message when the test is true.
+    pub fn with_report(mut self, test: Query, message: impl Into<String>) -> Self {
+        self.assertions.push(Assertion {
+            test,
+            message: message.into(),
+            report: true,
+        });
+        self
+    }
+}
@@ -19,6 +19,8 @@ impl Serialize for XmlValue {
             XmlValue::Decl(xml_decl) => xml_decl.serialize(serializer),
             XmlValue::Comment(xml_comment) => xml_comment.serialize(serializer),
             XmlValue::Doctype(xml_doctype) => xml_doctype.serialize(serializer),
+            #[cfg(feature = "recovery")]
+            XmlValue::Error(xml_error) => xml_error.serialize(serializer),
             XmlValue::None => serializer.serialize_none(),
         }
     }
@@ -51,6 +53,8 @@ impl Serialize for XmlChild {
             XmlChild::Element(v) => v.serialize(serializer),
             XmlChild::PI(v) => v.serialize(serializer),
             XmlChild::Comment(v) => v.serialize(serializer),
+            #[cfg(feature = "recovery")]
+            XmlChild::Error(v) => v.serialize(serializer),
             XmlChild::None => serializer.serialize_none(),
         }
     }
@@ -60,7 +64,14 @@ impl Serialize for XmlElement {
     where
         S: Serializer,
     {
-        let element = serializer.serialize_element(&self.name.as_ref())?;
+        let mut element = serializer.serialize_element(&self.name.as_ref())?;
+        if let Some(original_prefix) = &self.original_prefix {
+            element.preferred_prefix(Some(original_prefix))?;
+            element.include_prefix(IncludePrefix::WhenNecessaryForPreferredPrefix)?;
+        }
+        for declaration in &self.declarations {
+            element.declare_namespace(&declaration.prefix, &declaration.namespace)?;
+        }
 
         let mut attributes = element.serialize_attributes()?;
         for attr in &self.attributes {
@@ -134,8 +145,25 @@ impl SerializeAttribute for XmlAttribute {
     where
         S: AttributeSerializer,
     {
-        let attr = serializer.serialize_attribute(&self.name.as_ref())?;
+        let mut attr = serializer.serialize_attribute(&self.name.as_ref())?;
+        if let Some(original_prefix) = &self.original_prefix {
+            attr.preferred_prefix(Some(original_prefix))?;
+            attr.include_prefix(IncludePrefix::WhenNecessaryForPreferredPrefix)?;
+        }
 
         attr.end(&self.value)
     }
 }
+
+#[cfg(feature = "recovery")]
+impl Serialize for XmlError {
+    fn serialize<S>(&self, _serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Err(<S::Error as crate::ser::Error>::custom(format_args!(
+            "cannot serialize malformed XML at {}..{}: {}",
+            self.start, self.end, self.message
+        )))
+    }
+}
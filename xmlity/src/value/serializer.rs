@@ -316,6 +316,18 @@ impl<'s> ser::SerializeElement for &'s mut XmlElement {
         Ok(())
     }
 
+    fn declare_namespace(
+        &mut self,
+        prefix: &crate::Prefix,
+        namespace: &crate::XmlNamespace,
+    ) -> Result<(), Self::Error> {
+        self.declarations.push(XmlnsDeclaration::new(
+            prefix.to_owned(),
+            namespace.to_owned(),
+        ));
+        Ok(())
+    }
+
     fn serialize_children(self) -> Result<Self::ChildrenSerializeSeq, Self::Error> {
         Ok(&mut self.children)
     }
@@ -385,6 +397,7 @@ impl SerializeAttributeAccess for XmlAttributeBuilder<'_> {
         self.write_to.push_back(XmlAttribute {
             name: self.name,
             value: value_container,
+            original_prefix: None,
         });
         Ok(())
     }
@@ -415,9 +428,7 @@ impl crate::ser::Serializer for &mut XmlText {
         self,
         name: &'_ ExpandedName<'_>,
     ) -> Result<Self::SerializeElement, Self::Error> {
-        let _ = name;
-
-        Err(Error::unexpected_serialize(Unexpected::Element))
+        Err(Error::unexpected_serialize(Unexpected::element(name)))
     }
 
     fn serialize_seq(self) -> Result<Self::SerializeSeq, Self::Error> {
@@ -436,9 +447,9 @@ impl crate::ser::Serializer for &mut XmlText {
     }
 
     fn serialize_pi<S: AsRef<[u8]>>(self, target: S, content: S) -> Result<Self::Ok, Self::Error> {
-        let _ = (target, content);
+        let _ = content;
 
-        Err(Error::unexpected_serialize(Unexpected::PI))
+        Err(Error::unexpected_serialize(Unexpected::pi(target)))
     }
 
     fn serialize_comment<S: AsRef<[u8]>>(self, text: S) -> Result<Self::Ok, Self::Error> {
@@ -0,0 +1,60 @@
+//! Helpers shared by the integration tests. Each test crate only uses some of them.
+#![allow(dead_code)]
+use std::str::FromStr;
+
+use xmlity::{value::XmlValue, ExpandedNameBuf};
+
+/// Parses an expanded name in Clark notation, like `{http://example.com}local` or `local`.
+pub fn name(name: &str) -> ExpandedNameBuf {
+    ExpandedNameBuf::from_str(name).unwrap()
+}
+
+/// Parses an XML string into an [`XmlValue`].
+pub fn parse(xml: &str) -> XmlValue {
+    xml.parse().unwrap()
+}
+
+#[cfg(feature = "xsd-validate")]
+pub mod schema {
+    use xmlity::value::{
+        schema::{SchemaIssueKind, SchemaReport},
+        XmlValue,
+    };
+
+    /// The schemas that XML values can be validated against.
+    pub trait Validate {
+        fn validate(&self, value: &XmlValue) -> Result<(), SchemaReport>;
+    }
+
+    impl Validate for xmlity::value::schema::Schema {
+        fn validate(&self, value: &XmlValue) -> Result<(), SchemaReport> {
+            self.validate(value)
+        }
+    }
+
+    #[cfg(feature = "relaxng")]
+    impl Validate for xmlity::value::schema::relaxng::Schema {
+        fn validate(&self, value: &XmlValue) -> Result<(), SchemaReport> {
+            self.validate(value)
+        }
+    }
+
+    #[cfg(feature = "schematron")]
+    impl Validate for xmlity::value::schema::schematron::Schema {
+        fn validate(&self, value: &XmlValue) -> Result<(), SchemaReport> {
+            self.validate(value)
+        }
+    }
+
+    /// Parses `xml` and returns the paths and kinds of the issues found validating it against `schema`.
+    pub fn issues<S: Validate>(schema: &S, xml: &str) -> Vec<(String, SchemaIssueKind)> {
+        match schema.validate(&super::parse(xml)) {
+            Ok(()) => Vec::new(),
+            Err(report) => report
+                .into_issues()
+                .into_iter()
+                .map(|issue| (issue.path().to_string(), issue.kind().clone()))
+                .collect(),
+        }
+    }
+}
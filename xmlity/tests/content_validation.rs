@@ -0,0 +1,42 @@
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use xmlity::value::{InvalidContentError, XmlCData, XmlComment, XmlText};
+
+#[rstest]
+#[case::empty("")]
+#[case::brackets("a[b]c]]")]
+#[case::markup("<a>&amp;</a>")]
+fn valid_cdata(#[case] content: &str) {
+    assert_eq!(XmlCData::try_from(content), Ok(XmlCData::new(content)));
+}
+
+#[rstest]
+#[case::terminator("]]>")]
+#[case::terminator_inside("a]]>b")]
+fn invalid_cdata(#[case] content: &str) {
+    assert_eq!(
+        XmlCData::try_from(content.to_string()),
+        Err(InvalidContentError::CDataTerminator)
+    );
+}
+
+#[rstest]
+#[case::empty("")]
+#[case::single_hyphens(" a - b - c ")]
+#[case::starts_with_hyphen("-a")]
+fn valid_comment(#[case] content: &str) {
+    assert_eq!(XmlComment::try_from(content), Ok(XmlComment::new(content)));
+}
+
+#[rstest]
+#[case::double_hyphen("a--b", InvalidContentError::DoubleHyphenInComment)]
+#[case::only_hyphens("--", InvalidContentError::DoubleHyphenInComment)]
+#[case::ends_with_hyphen("a-", InvalidContentError::CommentEndsWithHyphen)]
+fn invalid_comment(#[case] content: &str, #[case] expected: InvalidContentError) {
+    assert_eq!(XmlComment::try_from(content.to_string()), Err(expected));
+}
+
+#[test]
+fn any_text_is_valid() {
+    assert_eq!(XmlText::from("]]> -- <"), XmlText::new("]]> -- <"));
+}
@@ -0,0 +1,115 @@
+#![cfg(all(feature = "wasm", target_arch = "wasm32"))]
+use js_sys::{Object, Reflect};
+use pretty_assertions::assert_eq;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+mod common;
+use common::parse;
+
+use xmlity::value::{
+    js::{from_js, to_js, JsConversionError},
+    XmlValue,
+};
+
+fn round_trip(value: &XmlValue) -> XmlValue {
+    from_js(&to_js(value).unwrap()).unwrap()
+}
+
+fn property(value: &JsValue, key: &str) -> JsValue {
+    Reflect::get(value, &key.into()).unwrap()
+}
+
+fn object(properties: &[(&str, JsValue)]) -> JsValue {
+    let object = Object::new();
+    for (key, value) in properties {
+        Reflect::set(&object, &(*key).into(), value).unwrap();
+    }
+    object.into()
+}
+
+#[wasm_bindgen_test]
+fn elements_round_trip() {
+    let value = parse(
+        r#"<p:a xmlns:p="http://example.com/ns" p:id="1" b="2"><c>Text</c><![CDATA[<d>]]><!-- comment --><?target content?></p:a>"#,
+    );
+
+    assert_eq!(round_trip(&value), value);
+}
+
+#[wasm_bindgen_test]
+fn documents_round_trip() {
+    let value = parse(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<!DOCTYPE a>\n<a/>\n",
+    );
+
+    assert_eq!(round_trip(&value), value);
+}
+
+#[wasm_bindgen_test]
+fn none_becomes_null() {
+    assert!(to_js(&XmlValue::None).unwrap().is_null());
+    assert_eq!(from_js(&JsValue::UNDEFINED).unwrap(), XmlValue::None);
+}
+
+#[wasm_bindgen_test]
+fn elements_become_plain_objects() {
+    let value = to_js(&parse(
+        r#"<a xmlns="http://example.com/ns" id="1">Text</a>"#,
+    ))
+    .unwrap();
+
+    assert_eq!(property(&value, "type"), "element");
+    assert_eq!(property(&value, "localName"), "a");
+    assert_eq!(property(&value, "namespace"), "http://example.com/ns");
+    assert!(property(&value, "prefix").is_undefined());
+
+    let attribute = Reflect::get_u32(&property(&value, "attributes"), 0).unwrap();
+    assert_eq!(property(&attribute, "localName"), "id");
+    assert_eq!(property(&attribute, "value"), "1");
+
+    let text = Reflect::get_u32(&property(&value, "children"), 0).unwrap();
+    assert_eq!(property(&text, "type"), "text");
+    assert_eq!(property(&text, "text"), "Text");
+}
+
+#[wasm_bindgen_test]
+fn unknown_types_are_rejected() {
+    let value = object(&[("type", "entity".into())]);
+
+    assert_eq!(
+        from_js(&value),
+        Err(JsConversionError::UnknownType("entity".to_string()))
+    );
+}
+
+#[wasm_bindgen_test]
+fn missing_properties_are_rejected() {
+    let value = object(&[("type", "pi".into())]);
+
+    assert_eq!(
+        from_js(&value),
+        Err(JsConversionError::MissingProperty("target"))
+    );
+}
+
+#[wasm_bindgen_test]
+fn declarations_are_not_children() {
+    let value = object(&[
+        ("type", "element".into()),
+        ("localName", "a".into()),
+        (
+            "children",
+            js_sys::Array::of1(&object(&[
+                ("type", "decl".into()),
+                ("version", "1.0".into()),
+            ]))
+            .into(),
+        ),
+    ]);
+
+    assert_eq!(
+        from_js(&value),
+        Err(JsConversionError::InvalidChild("decl"))
+    );
+}
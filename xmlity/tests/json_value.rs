@@ -0,0 +1,117 @@
+#![cfg(feature = "json")]
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+mod common;
+use common::parse;
+
+use xmlity::{
+    value::{
+        json::{from_json, to_json, JsonConversionError, JsonNamespaces, JsonOptions},
+        XmlValue,
+    },
+    xml,
+};
+
+#[test]
+fn text_only_elements_become_strings() {
+    let value = parse("<a><b>Text</b><c/></a>");
+
+    assert_eq!(
+        to_json(&value, &JsonOptions::new()).unwrap(),
+        json!({ "a": { "b": "Text", "c": null } })
+    );
+}
+
+#[test]
+fn mixed_content_keeps_text() {
+    let value = parse(r#"<a id="1">Hello <b>World</b>!</a>"#);
+
+    assert_eq!(
+        to_json(&value, &JsonOptions::new()).unwrap(),
+        json!({ "a": { "@id": "1", "b": "World", "#text": "Hello !" } })
+    );
+}
+
+#[test]
+fn indentation_is_left_out() {
+    let value =
+        XmlValue::from(xml!(<"a">["\n  " <"b">["1"]</"b"> "\n  " <"b">["2"]</"b"> "\n"]</"a">));
+
+    assert_eq!(
+        to_json(&value, &JsonOptions::new()).unwrap(),
+        json!({ "a": { "b": ["1", "2"] } })
+    );
+}
+
+#[test]
+fn custom_keys() {
+    let options = JsonOptions::new()
+        .with_attribute_prefix("-")
+        .with_text_key("$");
+    let value = parse(r#"<a id="1">Text</a>"#);
+
+    let json = to_json(&value, &options).unwrap();
+    assert_eq!(json, json!({ "a": { "-id": "1", "$": "Text" } }));
+    assert_eq!(from_json(&json, &options).unwrap(), value);
+}
+
+#[test]
+fn namespaces_in_clark_notation() {
+    let value = parse(
+        r#"<a:root xmlns:a="http://example.com/a" xmlns:b="http://example.com/b" b:id="1"><child/></a:root>"#,
+    );
+
+    let json = to_json(&value, &JsonOptions::new()).unwrap();
+    assert_eq!(
+        json,
+        json!({
+            "{http://example.com/a}root": {
+                "@{http://example.com/b}id": "1",
+                "child": null,
+            }
+        })
+    );
+    assert_eq!(from_json(&json, &JsonOptions::new()).unwrap(), value);
+}
+
+#[test]
+fn namespaces_ignored() {
+    let options = JsonOptions::new().with_namespaces(JsonNamespaces::Ignore);
+    let value = parse(r#"<root xmlns="http://example.com/a"><child>Text</child></root>"#);
+
+    let json = to_json(&value, &options).unwrap();
+    assert_eq!(json, json!({ "root": { "child": "Text" } }));
+    assert_eq!(
+        from_json(&json, &options).unwrap(),
+        XmlValue::from(xml!(<"root">[<"child">["Text"]</"child">]</"root">))
+    );
+}
+
+#[test]
+fn scalars_become_text() {
+    let json = json!({ "a": { "@count": 2, "@enabled": true, "b": 1.5, "c": null } });
+
+    assert_eq!(
+        from_json(&json, &JsonOptions::new()).unwrap(),
+        XmlValue::from(xml!(<"a" "count"="2" "enabled"="true">[<"b">["1.5"]</"b"> <"c"/>]</"a">))
+    );
+}
+
+#[test]
+fn top_level_attribute_is_rejected() {
+    assert_eq!(
+        from_json(&json!({ "@id": "1" }), &JsonOptions::new()),
+        Err(JsonConversionError::AttributeOutsideElement(
+            "@id".to_string()
+        ))
+    );
+}
+
+#[test]
+fn object_attribute_is_rejected() {
+    assert_eq!(
+        from_json(&json!({ "a": { "@id": { "x": 1 } } }), &JsonOptions::new()),
+        Err(JsonConversionError::ExpectedScalar("@id".to_string()))
+    );
+}
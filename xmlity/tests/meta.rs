@@ -0,0 +1,214 @@
+//! Tests for the `SCHEMA` constant generated with `#[xmlity(meta)]`.
+use pretty_assertions::assert_eq;
+
+use xmlity::{
+    meta::{Cardinality, FieldKind, FieldMeta, TypeKind, TypeMeta},
+    DeserializationGroup, Deserialize, ExpandedName, LocalName, SerializationGroup, Serialize,
+    SerializeAttribute, XmlNamespace,
+};
+
+fn name(local_name: &'static str) -> ExpandedName<'static> {
+    ExpandedName::new(LocalName::new(local_name).unwrap(), None)
+}
+
+#[derive(Serialize, Deserialize)]
+#[xmlity(meta)]
+#[xelement(name = "author")]
+struct Author(String);
+
+#[derive(Serialize, Deserialize)]
+#[xmlity(meta)]
+#[xvalue(rename_all = "kebab-case")]
+enum Format {
+    Hardcover,
+    #[xvalue(value = "pocket")]
+    Paperback,
+    #[xelement(name = "other")]
+    Other(String),
+}
+
+#[derive(SerializationGroup, DeserializationGroup)]
+#[xmlity(meta)]
+struct Publication {
+    #[xattribute(name = "year")]
+    year: u16,
+    #[xelement(name = "publisher", optional)]
+    publisher: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[xmlity(meta)]
+#[xelement(name = "book", namespace = "http://my.namespace.example.com/books")]
+struct Book {
+    #[xattribute(name = "isbn")]
+    isbn: String,
+    #[xattribute(name = "lang", default)]
+    lang: String,
+    authors: Vec<Author>,
+    format: Format,
+    #[xgroup]
+    publication: Publication,
+}
+
+#[test]
+fn element_schema() {
+    assert_eq!(
+        Book::SCHEMA.name(),
+        Some(ExpandedName::new(
+            LocalName::new("book").unwrap(),
+            Some(XmlNamespace::new("http://my.namespace.example.com/books").unwrap()),
+        ))
+    );
+    assert_eq!(
+        Book::SCHEMA.fields(),
+        &[
+            FieldMeta::new(
+                Some("isbn"),
+                "String",
+                FieldKind::Attribute {
+                    name: Some(name("isbn"))
+                },
+                Cardinality::Required,
+            ),
+            FieldMeta::new(
+                Some("lang"),
+                "String",
+                FieldKind::Attribute {
+                    name: Some(name("lang"))
+                },
+                Cardinality::Optional,
+            ),
+            FieldMeta::new(
+                Some("authors"),
+                "Vec<Author>",
+                FieldKind::Value,
+                Cardinality::Many,
+            ),
+            FieldMeta::new(
+                Some("format"),
+                "Format",
+                FieldKind::Value,
+                Cardinality::Required,
+            ),
+            FieldMeta::new(
+                Some("publication"),
+                "Publication",
+                FieldKind::Group,
+                Cardinality::Required,
+            ),
+        ]
+    );
+}
+
+#[test]
+fn tuple_struct_schema() {
+    assert_eq!(Author::SCHEMA.rust_name, "Author");
+    assert_eq!(Author::SCHEMA.name(), Some(name("author")));
+    assert_eq!(
+        Author::SCHEMA.fields(),
+        &[FieldMeta::new(
+            None,
+            "String",
+            FieldKind::Value,
+            Cardinality::Required
+        )]
+    );
+}
+
+#[test]
+fn enum_schema() {
+    let TypeKind::Choice { variants } = Format::SCHEMA.kind else {
+        panic!("Expected a choice, found {:?}", Format::SCHEMA.kind);
+    };
+
+    assert_eq!(
+        variants[0],
+        TypeMeta::new(
+            "Hardcover",
+            TypeKind::Text {
+                value: Some("hardcover")
+            }
+        )
+    );
+    assert_eq!(
+        variants[1],
+        TypeMeta::new(
+            "Paperback",
+            TypeKind::Text {
+                value: Some("pocket")
+            }
+        )
+    );
+    assert_eq!(variants[2].rust_name, "Other");
+    assert_eq!(variants[2].name(), Some(name("other")));
+    assert_eq!(
+        variants[2].fields(),
+        &[FieldMeta::new(
+            None,
+            "String",
+            FieldKind::Value,
+            Cardinality::Required
+        )]
+    );
+    assert_eq!(variants.len(), 3);
+}
+
+#[test]
+fn group_schema() {
+    assert!(matches!(Publication::SCHEMA.kind, TypeKind::Group { .. }));
+    assert_eq!(
+        Publication::SCHEMA.fields(),
+        &[
+            FieldMeta::new(
+                Some("year"),
+                "u16",
+                FieldKind::Attribute {
+                    name: Some(name("year"))
+                },
+                Cardinality::Required,
+            ),
+            FieldMeta::new(
+                Some("publisher"),
+                "Option<String>",
+                FieldKind::Element {
+                    name: name("publisher")
+                },
+                Cardinality::Optional,
+            ),
+        ]
+    );
+}
+
+#[derive(Serialize, Deserialize, SerializationGroup, DeserializationGroup)]
+#[xmlity(meta)]
+#[xgroup]
+struct Both {
+    #[xelement(name = "a")]
+    a: String,
+}
+
+#[test]
+fn group_deriving_both_forms_is_described_as_group() {
+    assert!(matches!(Both::SCHEMA.kind, TypeKind::Group { .. }));
+    assert_eq!(
+        Both::SCHEMA.fields(),
+        &[FieldMeta::new(
+            Some("a"),
+            "String",
+            FieldKind::Element { name: name("a") },
+            Cardinality::Required,
+        )]
+    );
+}
+
+#[derive(SerializeAttribute)]
+#[xmlity(meta, serialize_only)]
+#[xattribute(name = "id")]
+struct Id(String);
+
+#[test]
+fn serialize_only_schema() {
+    assert_eq!(Id::SCHEMA.rust_name, "Id");
+    assert!(matches!(Id::SCHEMA.kind, TypeKind::Attribute { .. }));
+    assert_eq!(Id::SCHEMA.name(), Some(name("id")));
+}
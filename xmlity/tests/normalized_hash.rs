@@ -0,0 +1,81 @@
+use rstest::rstest;
+use xmlity::{
+    value::{NormalizeOptions, XmlValue},
+    xml,
+};
+
+fn hash<T: Into<XmlValue>>(value: T, options: &NormalizeOptions) -> u64 {
+    value.into().normalized_hash(options)
+}
+
+#[rstest]
+#[case::attribute_order(
+    xml!(<"note" "to"="Tove" "from"="Jani"/>),
+    xml!(<"note" "from"="Jani" "to"="Tove"/>)
+)]
+#[case::whitespace(
+    xml!(<"note">[<"to">["Tove"]</"to"> <"from">["Jani"]</"from">]</"note">),
+    xml!(<"note">["\n  " <"to">["Tove"]</"to"> "\n  " <"from">["Jani"]</"from"> "\n"]</"note">)
+)]
+#[case::split_text(
+    xml!(<"note">["Hello, World"]</"note">),
+    xml!(<"note">["Hello," " " "World"]</"note">)
+)]
+#[case::split_whitespace(
+    xml!(<"note">[<"to"/> <"from"/>]</"note">),
+    xml!(<"note">["\n" <"to"/> " " "\t" <"from"/>]</"note">)
+)]
+fn equivalent_documents(
+    #[case] a: xmlity::value::XmlElement,
+    #[case] b: xmlity::value::XmlElement,
+) {
+    let options = NormalizeOptions::new();
+    assert_eq!(hash(a, &options), hash(b, &options));
+}
+
+#[rstest]
+#[case::text(xml!(<"note">["Tove"]</"note">), xml!(<"note">["Jani"]</"note">))]
+#[case::name(xml!(<"to"/>), xml!(<"from"/>))]
+#[case::namespace(xml!(<"note":"http://example.com/a"/>), xml!(<"note":"http://example.com/b"/>))]
+#[case::attribute_value(xml!(<"note" "to"="Tove"/>), xml!(<"note" "to"="Jani"/>))]
+#[case::whitespace_between_text(
+    xml!(<"note">["Hello," " " "World"]</"note">),
+    xml!(<"note">["Hello," "World"]</"note">)
+)]
+#[case::nesting(
+    xml!(<"a">[<"b"/> <"c"/>]</"a">),
+    xml!(<"a">[<"b">[<"c"/>]</"b">]</"a">)
+)]
+fn different_documents(#[case] a: xmlity::value::XmlElement, #[case] b: xmlity::value::XmlElement) {
+    let options = NormalizeOptions::new();
+    assert_ne!(hash(a, &options), hash(b, &options));
+}
+
+#[test]
+fn options_are_respected() {
+    let a = xml!(<"note" "to"="Tove" "from"="Jani">[<!--"draft"-->]</"note">);
+    let b = xml!(<"note" "from"="Jani" "to"="Tove"/>);
+
+    let strict = NormalizeOptions::new()
+        .with_ignore_attribute_order(false)
+        .with_ignore_comments(false);
+    let lenient = NormalizeOptions::new()
+        .with_ignore_attribute_order(true)
+        .with_ignore_comments(true);
+
+    assert_ne!(hash(a.clone(), &strict), hash(b.clone(), &strict));
+    assert_eq!(hash(a, &lenient), hash(b, &lenient));
+
+    let a = xml!(<"note">["Hello, " <!--"draft"--> "World"]</"note">);
+    let b = xml!(<"note">["Hello, World"]</"note">);
+
+    assert_ne!(hash(a.clone(), &strict), hash(b.clone(), &strict));
+    assert_eq!(hash(a, &lenient), hash(b, &lenient));
+}
+
+#[test]
+fn hash_is_stable() {
+    let value = xml!(<"note" "to"="Tove">["Hello"]</"note">);
+
+    assert_eq!(hash(value, &NormalizeOptions::new()), 16185460639285875668);
+}
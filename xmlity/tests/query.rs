@@ -0,0 +1,100 @@
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+use xmlity::value::{
+    query::{Query, QueryNode},
+    XmlValue,
+};
+
+fn query(source: &str) -> Query {
+    Query::new(source).unwrap()
+}
+
+const LIBRARY: &str = r#"<library>
+  <shelf name="a">
+    <book year="1999"><title>One</title></book>
+    <book year="2005"><title>Two</title><note>Signed <b>copy</b></note></book>
+  </shelf>
+  <shelf name="b">
+    <book year="2010"><title>Three</title></book>
+  </shelf>
+</library>"#;
+
+fn select(source: &str) -> Vec<String> {
+    let value: XmlValue = LIBRARY.parse().unwrap();
+    query(source)
+        .select(&value)
+        .into_iter()
+        .map(|node| match node {
+            QueryNode::Element(element) => element.name.to_string(),
+            QueryNode::Attribute(attribute) => {
+                format!("@{}", String::from_utf8_lossy(&attribute.value.0))
+            }
+            QueryNode::Text(text) => String::from_utf8_lossy(&text.0).trim().to_string(),
+            node => format!("{node:?}"),
+        })
+        .collect()
+}
+
+#[rstest]
+#[case::child_path("/library/shelf/@name", &["@a", "@b"])]
+#[case::descendants("//title/text()", &["One", "Two", "Three"])]
+#[case::position("//book[2]/title/text()", &["Two"])]
+#[case::positions_of_a_union("(//book)[last()]/@year", &["@2010"])]
+#[case::comparison_predicate("//book[@year > 2000]/@year", &["@2005", "@2010"])]
+#[case::parent("//b/../..", &["book"])]
+#[case::ancestors("//b/ancestor::*", &["library", "shelf", "book", "note"])]
+#[case::nearest_ancestor("//b/ancestor::*[2]", &["book"])]
+#[case::following_siblings("//shelf[1]/book[1]/following-sibling::*", &["book"])]
+#[case::union("//shelf[@name = 'b']/book | //note", &["note", "book"])]
+#[case::string_functions("//book[contains(normalize-space(note), 'd c')]/@year", &["@2005"])]
+#[case::not_a_node_set("count(//book)", &[])]
+fn selecting_nodes(#[case] source: &str, #[case] expected: &[&str]) {
+    assert_eq!(select(source), expected);
+}
+
+#[rstest]
+#[case::arithmetic("1 + 2 * 3 - 4 div 2 = 5")]
+#[case::modulo("7 mod 3 = 1")]
+#[case::negation("-(1 - 3) = 2")]
+#[case::string_value("string(//book[2]/note) = 'Signed copy'")]
+#[case::substring("substring('12345', 1.5, 2.6) = '234'")]
+#[case::substring_before_after(
+    "substring-before('a=b', '=') = 'a' and substring-after('a=b', '=') = 'b'"
+)]
+#[case::translate("translate('bar', 'abc', 'AB') = 'BAr'")]
+#[case::number_formatting(
+    "string(1 div 0) = 'Infinity' and string(2.50) = '2.5' and string(-0) = '0'"
+)]
+#[case::not_a_number("string(number('1e3')) = 'NaN'")]
+#[case::rounding("round(2.5) = 3 and floor(-1.5) = -2 and ceiling(1.2) = 2")]
+#[case::names("local-name(/*) = 'library' and name(//@year) = 'year' and namespace-uri(/*) = ''")]
+#[case::concat("concat(//shelf[1]/@name, '-', count(//book)) = 'a-3'")]
+#[case::string_length("string-length(//title) = 3")]
+#[case::node_sets_compare_any("//@year = 2010 and //@year != 2010 and not(//@year = 2000)")]
+#[case::node_sets_compare_pairwise(
+    "//shelf/@name != //book/@year and not(//shelf/@name = //book/@year)"
+)]
+#[case::empty_node_sets("not(//missing) and not(//missing = '') and not(//missing != 1)")]
+#[case::booleans_compare_node_sets_by_existence("//book = true() and //missing = false()")]
+fn evaluating_expressions(#[case] source: &str) {
+    // The root element is only selected if the expression is true.
+    assert_eq!(select(&format!("/*[{source}]")), ["library"], "{source}");
+}
+
+#[rstest]
+#[case::unterminated_literal("'abc", 0)]
+#[case::unknown_function("book[foo()]", 5)]
+#[case::wrong_arity("not()", 0)]
+#[case::count_of_a_string("count('a')", 6)]
+#[case::union_of_numbers("1 | book", 0)]
+#[case::filtered_number("1[1]", 0)]
+#[case::prefix("p:book", 0)]
+#[case::variable("$x", 0)]
+#[case::unsupported_axis("following::book", 0)]
+#[case::missing_node_test("book/", 5)]
+#[case::unclosed_predicate("book[1", 6)]
+#[case::trailing_tokens("book book", 5)]
+fn invalid_queries(#[case] source: &str, #[case] position: usize) {
+    assert_eq!(Query::new(source).unwrap_err().position(), position);
+}
@@ -0,0 +1,267 @@
+#![cfg(feature = "relaxng")]
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+mod common;
+use common::{name, schema::issues};
+
+use xmlity::{
+    value::{
+        schema::{relaxng::Schema, BuiltinType, Facet, SchemaIssueKind},
+        XmlValue,
+    },
+    ExpandedNameBuf, LocalNameBuf, XmlNamespaceBuf,
+};
+
+const ARTICLE: &str = r#"
+# A small documentation format.
+default namespace = ""
+datatypes xsd = "http://www.w3.org/2001/XMLSchema-datatypes"
+
+start = article
+
+article = element article {
+  attribute id { xsd:token { pattern = "[a-z][a-z0-9-]*" } },
+  attribute status { "draft" | "final" }?,
+  info?,
+  section+
+}
+
+info = element info { element author { text }* & element date { xsd:token }? }
+
+div {
+  section = element section { title, block* }
+  title = element title { inline }
+}
+
+block |= element para { inline }
+block |= element list { element item { inline }+ }
+
+[ doc = "Text with inline markup." ]
+inline = mixed { (element em { inline } | element code { text })* }
+"#;
+
+fn article() -> Schema {
+    Schema::compile(ARTICLE).unwrap()
+}
+
+#[test]
+fn valid_document() {
+    let xml = r#"<?xml version="1.0"?>
+<article id="intro" status="final">
+  <info><date>2024-01-01</date><author>A</author><author>B</author></info>
+  <section>
+    <title>Getting <em>started</em></title>
+    <para>Run <code>cargo build</code>.</para>
+    <list><item>One</item><item>Two</item></list>
+  </section>
+  <section><title>Done</title></section>
+</article>
+"#;
+
+    assert_eq!(issues(&article(), xml), Vec::new());
+}
+
+#[test]
+fn attribute_issues() {
+    let xml =
+        r#"<article id="Intro" status="published" lang="en"><section><title/></section></article>"#;
+
+    assert_eq!(
+        issues(&article(), xml),
+        vec![
+            (
+                "/article/@id".to_string(),
+                SchemaIssueKind::InvalidValue {
+                    value: "Intro".to_string(),
+                    facet: Facet::Pattern("[a-z][a-z0-9-]*".to_string()),
+                }
+            ),
+            (
+                "/article/@status".to_string(),
+                SchemaIssueKind::InvalidValue {
+                    value: "published".to_string(),
+                    facet: Facet::Enumeration,
+                }
+            ),
+            (
+                "/article/@lang".to_string(),
+                SchemaIssueKind::UndeclaredAttribute { name: name("lang") }
+            ),
+        ]
+    );
+    assert_eq!(
+        issues(&article(), "<article><section><title/></section></article>"),
+        vec![(
+            "/article".to_string(),
+            SchemaIssueKind::MissingAttribute { name: name("id") }
+        )]
+    );
+}
+
+#[test]
+fn content_issues_are_reported_at_their_paths() {
+    let xml = r#"<article id="a"><section><para>Text</para><title>T</title></section><section><title>T</title><list/></section></article>"#;
+
+    assert_eq!(
+        issues(&article(), xml),
+        vec![
+            (
+                "/article/section[1]/para".to_string(),
+                SchemaIssueKind::UnexpectedElement { name: name("para") }
+            ),
+            (
+                "/article/section[2]/list".to_string(),
+                SchemaIssueKind::IncompleteContent
+            ),
+        ]
+    );
+}
+
+#[test]
+fn text_issues() {
+    let schema = Schema::compile(
+        "element numbers { element n { xsd:integer }+, element total { list { xsd:integer+ } } }",
+    )
+    .unwrap();
+
+    assert_eq!(
+        issues(
+            &schema,
+            "<numbers><n> 1 </n><n>x</n><total>1 2 3</total></numbers>"
+        ),
+        vec![(
+            "/numbers/n[2]".to_string(),
+            SchemaIssueKind::InvalidValue {
+                value: "x".to_string(),
+                facet: Facet::Type(BuiltinType::Integer),
+            }
+        )]
+    );
+    assert_eq!(
+        issues(&schema, "<numbers>Text<n>1</n><total></total></numbers>"),
+        vec![
+            ("/numbers".to_string(), SchemaIssueKind::UnexpectedText),
+            (
+                "/numbers/total".to_string(),
+                SchemaIssueKind::InvalidValue {
+                    value: "".to_string(),
+                    facet: Facet::Enumeration,
+                }
+            ),
+        ]
+    );
+}
+
+#[test]
+fn undeclared_root() {
+    assert_eq!(
+        issues(&article(), "<book/>"),
+        vec![(
+            "/book".to_string(),
+            SchemaIssueKind::UndeclaredElement { name: name("book") }
+        )]
+    );
+}
+
+#[test]
+fn namespaces_and_name_classes() {
+    let schema = Schema::compile(
+        r#"
+        default namespace doc = "http://example.com/doc"
+        namespace ext = "http://example.com/ext"
+
+        start = element doc:root { attribute * - doc:* { text }*, element ext:* { any }* }
+        any = (element * { any } | attribute * { text } | text)*
+        "#,
+    )
+    .unwrap();
+    let doc = |local_name: &str| {
+        ExpandedNameBuf::new(
+            LocalNameBuf::new(local_name.to_string()).unwrap(),
+            Some(XmlNamespaceBuf::new("http://example.com/doc".to_string()).unwrap()),
+        )
+    };
+
+    assert_eq!(
+        issues(
+            &schema,
+            r#"<root xmlns="http://example.com/doc" xmlns:e="http://example.com/ext" a="1" e:b="2"><e:x y="1"><z/>Text</e:x></root>"#
+        ),
+        Vec::new()
+    );
+    assert_eq!(
+        issues(
+            &schema,
+            r#"<d:root xmlns:d="http://example.com/doc" d:a="1"><d:x/></d:root>"#
+        ),
+        vec![
+            (
+                "/{http://example.com/doc}root/@{http://example.com/doc}a".to_string(),
+                SchemaIssueKind::UndeclaredAttribute { name: doc("a") }
+            ),
+            (
+                "/{http://example.com/doc}root/{http://example.com/doc}x".to_string(),
+                SchemaIssueKind::UnexpectedElement { name: doc("x") }
+            ),
+        ]
+    );
+}
+
+#[rstest]
+#[case::interleave("<a><y/><x/><x/></a>", true)]
+#[case::interleave_missing("<a><x/></a>", false)]
+#[case::group_order("<b><x/><y/></b>", true)]
+#[case::group_wrong_order("<b><y/><x/></b>", false)]
+#[case::choice("<c><y/></c>", true)]
+#[case::choice_both("<c><x/><y/></c>", false)]
+#[case::empty("<d> </d>", true)]
+#[case::empty_with_text("<d>x</d>", false)]
+fn combinators(#[case] xml: &str, #[case] valid: bool) {
+    let schema = Schema::compile(
+        r#"
+        start = element a { x+ & y } | element b { x, y } | element c { x | y } | element d { empty }
+        x = element x { empty }
+        y = element y { empty }
+        "#,
+    )
+    .unwrap();
+    let value: XmlValue = xml.parse().unwrap();
+
+    assert_eq!(schema.validate(&value).is_ok(), valid);
+}
+
+#[rstest]
+#[case::undefined_reference("start = element a { b }", 1)]
+#[case::missing_start("a = element a { empty }", 0)]
+#[case::recursion_outside_of_element("start = a\na = a | element a { empty }", 2)]
+#[case::mixed_operators("start = element a { empty , empty | empty }", 1)]
+#[case::unknown_datatype("start = element a { xsd:date }", 1)]
+#[case::unknown_prefix("start = element p:a { empty }", 1)]
+#[case::include("include \"other.rnc\"", 1)]
+#[case::unterminated_literal("start = element a { \"x }", 1)]
+fn invalid_schemas(#[case] source: &str, #[case] line: usize) {
+    assert_eq!(Schema::compile(source).unwrap_err().line(), line);
+}
+
+#[test]
+fn recursion_through_elements() {
+    let schema = Schema::compile(
+        "start = node\nnode = element node { attribute v { xsd:positiveInteger }, node* }",
+    )
+    .unwrap();
+
+    assert_eq!(
+        issues(
+            &schema,
+            r#"<node v="1"><node v="2"><node v="0"/></node></node>"#
+        ),
+        vec![(
+            "/node/node/node/@v".to_string(),
+            SchemaIssueKind::InvalidValue {
+                value: "0".to_string(),
+                facet: Facet::Type(BuiltinType::PositiveInteger),
+            }
+        )]
+    );
+}
@@ -0,0 +1,352 @@
+#![cfg(feature = "xsd-validate")]
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+mod common;
+use common::{name, parse, schema::issues};
+
+use xmlity::value::schema::{
+    AttributeDecl, BuiltinType, ComplexType, ElementDecl, Facet, Particle, Pattern, Schema,
+    SchemaIssueKind, SimpleType,
+};
+
+fn element(local_name: &str, simple_type: SimpleType) -> Particle {
+    Particle::element(ElementDecl::new(name(local_name), simple_type))
+}
+
+fn string() -> SimpleType {
+    SimpleType::new(BuiltinType::String)
+}
+
+fn book_schema() -> Schema {
+    Schema::new()
+        .with_element(ElementDecl::new(name("author"), string()))
+        .with_element(ElementDecl::new(
+            name("book"),
+            ComplexType::new(Particle::sequence([
+                element("title", string()),
+                Particle::element_ref(name("author")).with_occurs(1, None),
+                Particle::choice([
+                    element(
+                        "isbn",
+                        string().with_pattern(Pattern::new(r"\d{3}-\d{10}").unwrap()),
+                    ),
+                    element("issn", string()),
+                ])
+                .with_occurs(0, Some(1)),
+                element(
+                    "format",
+                    SimpleType::new(BuiltinType::Token)
+                        .with_enumeration(["hardcover", "paperback"]),
+                )
+                .with_occurs(0, Some(1)),
+            ]))
+            .with_attribute(
+                AttributeDecl::new(name("pages"), SimpleType::new(BuiltinType::PositiveInteger))
+                    .with_required(true),
+            )
+            .with_attribute(AttributeDecl::new(
+                name("lang"),
+                SimpleType::new(BuiltinType::Token).with_max_length(2),
+            )),
+        ))
+}
+
+#[test]
+fn valid_document() {
+    let xml = r#"<?xml version="1.0"?>
+<!-- A book -->
+<book pages="320" lang="en">
+  <title>The Book</title>
+  <author>A</author>
+  <author>B</author>
+  <isbn>978-0123456789</isbn>
+  <format> paperback </format>
+</book>
+"#;
+
+    assert_eq!(issues(&book_schema(), xml), Vec::new());
+}
+
+#[test]
+fn invalid_values_are_reported_with_paths() {
+    let xml = r#"<book pages="0" lang="eng"><title>T</title><author>A</author><isbn>978-123</isbn><format>ebook</format></book>"#;
+
+    assert_eq!(
+        issues(&book_schema(), xml),
+        vec![
+            (
+                "/book/@pages".to_string(),
+                SchemaIssueKind::InvalidValue {
+                    value: "0".to_string(),
+                    facet: Facet::Type(BuiltinType::PositiveInteger),
+                }
+            ),
+            (
+                "/book/@lang".to_string(),
+                SchemaIssueKind::InvalidValue {
+                    value: "eng".to_string(),
+                    facet: Facet::MaxLength(2),
+                }
+            ),
+            (
+                "/book/isbn".to_string(),
+                SchemaIssueKind::InvalidValue {
+                    value: "978-123".to_string(),
+                    facet: Facet::Pattern(r"\d{3}-\d{10}".to_string()),
+                }
+            ),
+            (
+                "/book/format".to_string(),
+                SchemaIssueKind::InvalidValue {
+                    value: "ebook".to_string(),
+                    facet: Facet::Enumeration,
+                }
+            ),
+        ]
+    );
+}
+
+#[test]
+fn unexpected_element_stops_content_but_earlier_children_are_checked() {
+    let xml = r#"<book pages="1"><title>T</title><author>A</author><format>ebook</format><isbn>1</isbn></book>"#;
+
+    assert_eq!(
+        issues(&book_schema(), xml),
+        vec![
+            (
+                "/book/isbn".to_string(),
+                SchemaIssueKind::UnexpectedElement { name: name("isbn") }
+            ),
+            (
+                "/book/format".to_string(),
+                SchemaIssueKind::InvalidValue {
+                    value: "ebook".to_string(),
+                    facet: Facet::Enumeration,
+                }
+            ),
+        ]
+    );
+}
+
+#[test]
+fn incomplete_content_and_attributes() {
+    let xml = r#"<book version="2"><title>T</title></book>"#;
+
+    assert_eq!(
+        issues(&book_schema(), xml),
+        vec![
+            (
+                "/book/@version".to_string(),
+                SchemaIssueKind::UndeclaredAttribute {
+                    name: name("version")
+                }
+            ),
+            (
+                "/book".to_string(),
+                SchemaIssueKind::MissingAttribute {
+                    name: name("pages")
+                }
+            ),
+            ("/book".to_string(), SchemaIssueKind::IncompleteContent),
+        ]
+    );
+}
+
+#[test]
+fn positions_are_given_to_repeated_elements() {
+    let schema = Schema::new().with_element(ElementDecl::new(
+        name("list"),
+        ComplexType::new(
+            element("item", SimpleType::new(BuiltinType::Integer)).with_occurs(0, None),
+        ),
+    ));
+
+    assert_eq!(
+        issues(&schema, "<list><item>1</item><item>two</item></list>"),
+        vec![(
+            "/list/item[2]".to_string(),
+            SchemaIssueKind::InvalidValue {
+                value: "two".to_string(),
+                facet: Facet::Type(BuiltinType::Integer),
+            }
+        )]
+    );
+}
+
+#[test]
+fn occurs_bounds() {
+    let schema = Schema::new().with_element(ElementDecl::new(
+        name("list"),
+        ComplexType::new(element("item", string()).with_occurs(2, Some(3))),
+    ));
+
+    assert_eq!(
+        issues(&schema, "<list><item/></list>"),
+        vec![("/list".to_string(), SchemaIssueKind::IncompleteContent)]
+    );
+    assert_eq!(
+        issues(&schema, "<list><item/><item/><item/></list>"),
+        Vec::new()
+    );
+    assert_eq!(
+        issues(&schema, "<list><item/><item/><item/><item/></list>"),
+        vec![(
+            "/list/item[4]".to_string(),
+            SchemaIssueKind::UnexpectedElement { name: name("item") }
+        )]
+    );
+}
+
+#[test]
+fn large_occurs_bounds() {
+    let schema = Schema::new().with_element(ElementDecl::new(
+        name("list"),
+        ComplexType::new(Particle::sequence([
+            element("item", string()).with_occurs(u32::MAX, None),
+            Particle::sequence([element("note", string()).with_occurs(0, Some(1))])
+                .with_occurs(u32::MAX, None),
+        ])),
+    ));
+
+    assert_eq!(
+        issues(&schema, "<list><item/><item/><note/></list>"),
+        vec![(
+            "/list/note".to_string(),
+            SchemaIssueKind::UnexpectedElement { name: name("note") }
+        )]
+    );
+}
+
+#[test]
+fn many_children() {
+    let schema = Schema::new().with_element(ElementDecl::new(
+        name("list"),
+        ComplexType::new(
+            Particle::choice([element("a", string()), element("b", string())]).with_occurs(0, None),
+        ),
+    ));
+    let xml = format!("<list>{}</list>", "<a/><b/>".repeat(50_000));
+
+    assert_eq!(issues(&schema, &xml), Vec::new());
+}
+
+#[rstest]
+#[case::in_order("<a><x/><y/></a>", true)]
+#[case::reversed("<a><y/><x/></a>", true)]
+#[case::optional_member("<a><z/><y/><x/></a>", true)]
+#[case::missing_member("<a><y/></a>", false)]
+#[case::repeated_member("<a><x/><x/><y/></a>", false)]
+fn all_group(#[case] xml: &str, #[case] valid: bool) {
+    let schema = Schema::new().with_element(ElementDecl::new(
+        name("a"),
+        ComplexType::new(Particle::all([
+            element("x", string()),
+            element("y", string()),
+            element("z", string()).with_occurs(0, Some(1)),
+        ])),
+    ));
+
+    assert_eq!(schema.validate(&parse(xml)).is_ok(), valid);
+}
+
+#[test]
+fn text_content() {
+    let schema = Schema::new()
+        .with_element(ElementDecl::new(
+            name("a"),
+            ComplexType::new(element("b", string()).with_occurs(0, None)),
+        ))
+        .with_element(ElementDecl::new(
+            name("p"),
+            ComplexType::new(element("b", string()).with_occurs(0, None)).with_mixed(true),
+        ))
+        .with_element(ElementDecl::new(
+            name("price"),
+            ComplexType::simple(SimpleType::new(BuiltinType::Decimal))
+                .with_attribute(AttributeDecl::new(name("currency"), string())),
+        ));
+
+    assert_eq!(issues(&schema, "<a>\n  <b/>\n</a>"), Vec::new());
+    assert_eq!(
+        issues(&schema, "<a>Text<b/></a>"),
+        vec![("/a".to_string(), SchemaIssueKind::UnexpectedText)]
+    );
+    assert_eq!(issues(&schema, "<p>Some <b>bold</b> text</p>"), Vec::new());
+    assert_eq!(
+        issues(&schema, r#"<price currency="EUR">12.50</price>"#),
+        Vec::new()
+    );
+    assert_eq!(
+        issues(&schema, "<price><b/>1</price>"),
+        vec![(
+            "/price".to_string(),
+            SchemaIssueKind::UnexpectedElement { name: name("b") }
+        )]
+    );
+}
+
+#[test]
+fn undeclared_root() {
+    let report = book_schema().validate(&parse("<magazine/>")).unwrap_err();
+
+    assert_eq!(
+        report.to_string(),
+        "element magazine is not declared at /magazine"
+    );
+}
+
+#[rstest]
+#[case::boolean(BuiltinType::Boolean, &["true", "0", " false "], &["yes", "True"])]
+#[case::decimal(BuiltinType::Decimal, &["1", "-1.5", ".5", "+2."], &["1e3", ".", "1,5"])]
+#[case::integer(BuiltinType::Integer, &["-12", "+0", "123456789012345678901234567890"], &["1.0", "-", ""])]
+#[case::non_negative_integer(BuiltinType::NonNegativeInteger, &["0", "-0", "7"], &["-1"])]
+#[case::double(BuiltinType::Double, &["1.5E3", "INF", "-INF", "NaN", "1e-7"], &["inf", "nan", "1e", "1.5 E3"])]
+fn builtin_types(#[case] base: BuiltinType, #[case] valid: &[&str], #[case] invalid: &[&str]) {
+    let schema = Schema::new().with_element(ElementDecl::new(name("v"), SimpleType::new(base)));
+    let value = |text: &str| parse(&format!("<v>{text}</v>"));
+
+    for text in valid {
+        assert!(schema.validate(&value(text)).is_ok(), "{text:?} is valid");
+    }
+    for text in invalid {
+        assert!(
+            schema.validate(&value(text)).is_err(),
+            "{text:?} is invalid"
+        );
+    }
+}
+
+#[rstest]
+#[case::alternation("cat|dog", &["cat", "dog"], &["cow", "catdog"])]
+#[case::quantities("a{2,3}b?", &["aa", "aaab"], &["a", "aaaa"])]
+#[case::classes("[A-Z][a-z-[aeiou]]*", &["Xyz", "B"], &["Xa", "xy"])]
+#[case::negated_class("[^0-9]+", &["abc"], &["a1"])]
+#[case::multi_char_escapes(r"\i\c*\s\d+", &["_a.b 42"], &["1a 4", "ab 4a"])]
+#[case::groups("(ab)+(\\.c)?", &["abab", "ab.c"], &["aba", "ab."])]
+#[case::dot(".*", &["anything goes"], &["line\nbreak"])]
+#[case::large_bounds("a{4294967295}|(b?){4294967295}", &["", "bb"], &["a", "c"])]
+fn patterns(#[case] pattern: &str, #[case] matches: &[&str], #[case] mismatches: &[&str]) {
+    let pattern = Pattern::new(pattern).unwrap();
+
+    for value in matches {
+        assert!(pattern.is_match(value), "{pattern} matches {value:?}");
+    }
+    for value in mismatches {
+        assert!(
+            !pattern.is_match(value),
+            "{pattern} does not match {value:?}"
+        );
+    }
+}
+
+#[rstest]
+#[case::unclosed_group("(ab")]
+#[case::unmatched_paren("ab)")]
+#[case::dangling_quantifier("*a")]
+#[case::reversed_range("[z-a]")]
+#[case::category_escape(r"\p{Lu}")]
+#[case::unknown_escape(r"\q")]
+fn invalid_patterns(#[case] pattern: &str) {
+    assert!(Pattern::new(pattern).is_err());
+}
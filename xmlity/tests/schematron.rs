@@ -0,0 +1,126 @@
+#![cfg(feature = "schematron")]
+use pretty_assertions::assert_eq;
+
+mod common;
+use common::schema::issues;
+
+use xmlity::value::{
+    query::Query,
+    schema::{
+        schematron::{Rule, Schema},
+        SchemaIssueKind,
+    },
+    XmlValue,
+};
+
+fn query(source: &str) -> Query {
+    Query::new(source).unwrap()
+}
+
+fn issue(path: &str, message: &str) -> (String, SchemaIssueKind) {
+    (
+        path.to_string(),
+        SchemaIssueKind::FailedAssertion {
+            message: message.to_string(),
+        },
+    )
+}
+
+const INVOICE: &str = r#"<?xml version="1.0"?>
+<invoice number="INV-1" currency="EUR">
+  <line sku="A" quantity="2" price="10"/>
+  <line sku="B" quantity="1" price="5.5"/>
+  <total>15.5</total>
+</invoice>
+"#;
+
+fn invoice() -> Schema {
+    Schema::new()
+        .with_rule(
+            Rule::new(query("/invoice"))
+                .with_assert(
+                    query("starts-with(@number, 'INV-')"),
+                    "invoice numbers start with INV-",
+                )
+                .with_assert(
+                    query("@currency = 'EUR' or @currency = 'USD'"),
+                    "unsupported currency",
+                )
+                .with_assert(query("count(line) >= 1"), "an invoice has lines")
+                .with_assert(
+                    query("number(total) = sum(line/@price)"),
+                    "the total is the sum of the line prices",
+                ),
+        )
+        .with_rule(
+            Rule::new(query("line"))
+                .with_assert(query("@quantity > 0"), "quantities are positive")
+                .with_report(
+                    query("@sku = preceding-sibling::line/@sku"),
+                    "skus are unique",
+                ),
+        )
+}
+
+#[test]
+fn valid_document() {
+    assert_eq!(issues(&invoice(), INVOICE), Vec::new());
+}
+
+#[test]
+fn failed_assertions_are_reported_in_document_order() {
+    let xml = r#"<invoice number="X-1" currency="GBP"><line sku="A" quantity="0" price="1"/><line sku="A" quantity="1" price="2"/><total>4</total></invoice>"#;
+
+    assert_eq!(
+        issues(&invoice(), xml),
+        vec![
+            issue("/invoice", "invoice numbers start with INV-"),
+            issue("/invoice", "unsupported currency"),
+            issue("/invoice", "the total is the sum of the line prices"),
+            issue("/invoice/line[1]", "quantities are positive"),
+            issue("/invoice/line[2]", "skus are unique"),
+        ]
+    );
+}
+
+#[test]
+fn issues_display_their_messages() {
+    let schema = Schema::new()
+        .with_rule(Rule::new(query("@quantity")).with_assert(query(". > 0"), "must be positive"));
+    let value: XmlValue = INVOICE
+        .replace(r#"quantity="1""#, r#"quantity="-1""#)
+        .parse()
+        .unwrap();
+
+    assert_eq!(
+        schema.validate(&value).unwrap_err().to_string(),
+        "must be positive at /invoice/line[2]/@quantity"
+    );
+}
+
+#[test]
+fn contexts_without_matches_check_nothing() {
+    let schema = Schema::new()
+        .with_rule(Rule::new(query("receipt")).with_assert(query("false()"), "never checked"));
+
+    assert_eq!(issues(&schema, INVOICE), Vec::new());
+}
+
+#[test]
+fn namespaced_names() {
+    let schema = Schema::new().with_rule(
+        Rule::new(query("{http://example.com/ns}*"))
+            .with_assert(query("@{http://example.com/ns}id"), "has a namespaced id"),
+    );
+
+    assert_eq!(
+        issues(
+            &schema,
+            r#"<n:a xmlns:n="http://example.com/ns" n:id="1"><n:b id="2"/><c/></n:a>"#
+        ),
+        vec![issue(
+            "/{http://example.com/ns}a/{http://example.com/ns}b",
+            "has a namespaced id"
+        )]
+    );
+}
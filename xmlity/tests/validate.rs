@@ -0,0 +1,221 @@
+use pretty_assertions::assert_eq;
+use xmlity::{
+    ser::{validate, SerializeElement, ValidationIssueKind},
+    value::{InvalidContentError, XmlCData, XmlComment, XmlProcessingInstruction},
+    ExpandedNameBuf, LocalNameBuf, PrefixBuf, Serialize, XmlNamespace,
+};
+
+#[derive(Serialize)]
+#[xelement(name = "item")]
+struct Item {
+    #[xattribute(name = "id")]
+    id: String,
+    #[xvalue]
+    text: String,
+}
+
+#[derive(Serialize)]
+#[xelement(name = "list")]
+struct List {
+    name: Item,
+    items: Vec<Item>,
+}
+
+fn item(id: &str, text: &str) -> Item {
+    Item {
+        id: id.to_string(),
+        text: text.to_string(),
+    }
+}
+
+fn issues<T: Serialize>(value: &T) -> Vec<(String, ValidationIssueKind)> {
+    validate(value)
+        .unwrap_err()
+        .into_issues()
+        .into_iter()
+        .map(|issue| (issue.path().to_string(), issue.kind().clone()))
+        .collect()
+}
+
+#[test]
+fn valid_value() {
+    let list = List {
+        name: item("a", "Fish & chips <3"),
+        items: vec![item("b", "Tab\tand\nnewline")],
+    };
+
+    assert_eq!(validate(&list), Ok(()));
+}
+
+#[test]
+fn invalid_chars() {
+    let list = List {
+        name: item("a", "Name"),
+        items: vec![item("b", "Bell\u{7}"), item("c\u{0}", "Fine")],
+    };
+
+    assert_eq!(
+        issues(&list),
+        vec![
+            (
+                "/list/item[2]".to_string(),
+                ValidationIssueKind::InvalidChar { character: '\u{7}' }
+            ),
+            (
+                "/list/item[3]/@id".to_string(),
+                ValidationIssueKind::InvalidChar { character: '\u{0}' }
+            ),
+        ]
+    );
+}
+
+#[test]
+fn positions_only_for_repeated_names() {
+    let list = List {
+        name: item("a", "\u{1}"),
+        items: Vec::new(),
+    };
+
+    assert_eq!(
+        issues(&list),
+        vec![(
+            "/list/item".to_string(),
+            ValidationIssueKind::InvalidChar { character: '\u{1}' }
+        )]
+    );
+}
+
+#[test]
+fn invalid_content() {
+    assert_eq!(
+        issues(&XmlComment::new("a -- b")),
+        vec![(
+            String::new(),
+            ValidationIssueKind::InvalidContent(InvalidContentError::DoubleHyphenInComment)
+        )]
+    );
+
+    assert_eq!(
+        issues(&XmlProcessingInstruction::new("xml", "a ?> b")),
+        vec![
+            (
+                String::new(),
+                ValidationIssueKind::InvalidPiTarget {
+                    target: "xml".to_string()
+                }
+            ),
+            (
+                String::new(),
+                ValidationIssueKind::InvalidContent(InvalidContentError::PiTerminator)
+            ),
+        ]
+    );
+}
+
+#[test]
+fn cdata_terminator_is_split() {
+    assert_eq!(validate(&XmlCData::new("a]]>b")), Ok(()));
+
+    assert_eq!(
+        issues(&XmlCData::new("a]]>\u{0}")),
+        vec![(
+            String::new(),
+            ValidationIssueKind::InvalidChar { character: '\u{0}' }
+        )]
+    );
+}
+
+#[derive(Serialize)]
+#[xelement(name = "root")]
+struct Duplicate {
+    #[xattribute(name = "id")]
+    first: String,
+    #[xattribute(name = "id")]
+    second: String,
+}
+
+#[test]
+fn duplicate_attribute() {
+    let value = Duplicate {
+        first: "a".to_string(),
+        second: "b".to_string(),
+    };
+
+    assert_eq!(
+        issues(&value),
+        vec![(
+            "/root/@id".to_string(),
+            ValidationIssueKind::DuplicateAttribute {
+                name: ExpandedNameBuf::new(LocalNameBuf::new("id".to_string()).unwrap(), None)
+            }
+        )]
+    );
+}
+
+#[derive(Serialize)]
+#[xelement(
+    name = "root",
+    namespace = "http://example.com",
+    preferred_prefix = "xmlns"
+)]
+struct ReservedPrefix;
+
+#[derive(Serialize)]
+#[xelement(name = "root", namespace = "http://www.w3.org/2000/xmlns/")]
+struct ReservedNamespace;
+
+#[test]
+fn reserved_names() {
+    assert_eq!(
+        issues(&ReservedPrefix),
+        vec![(
+            "/{http://example.com}root".to_string(),
+            ValidationIssueKind::ReservedPrefix {
+                prefix: PrefixBuf::new("xmlns".to_string()).unwrap()
+            }
+        )]
+    );
+
+    assert_eq!(
+        issues(&ReservedNamespace),
+        vec![(
+            "/{http://www.w3.org/2000/xmlns/}root".to_string(),
+            ValidationIssueKind::ReservedNamespace {
+                namespace: XmlNamespace::XMLNS.to_owned()
+            }
+        )]
+    );
+}
+
+struct ElementInAttribute;
+
+impl Serialize for ElementInAttribute {
+    fn serialize<S: xmlity::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer
+            .serialize_element(&xmlity::ExpandedName::new(
+                xmlity::LocalName::new("a").unwrap(),
+                None,
+            ))?
+            .end()
+    }
+}
+
+#[derive(Serialize)]
+#[xelement(name = "root")]
+struct WithElementInAttribute {
+    #[xattribute(name = "value")]
+    value: ElementInAttribute,
+}
+
+#[test]
+fn unexpected_ends_validation() {
+    let report = validate(&WithElementInAttribute {
+        value: ElementInAttribute,
+    })
+    .unwrap_err();
+
+    assert_eq!(
+        report.to_string(),
+        "unexpected element a at /root/@value".to_string()
+    );
+}
@@ -0,0 +1,236 @@
+use std::str::FromStr;
+
+use pretty_assertions::assert_eq;
+
+mod common;
+use common::name;
+
+use xmlity::value::{
+    XmlAttribute, XmlCData, XmlChild, XmlComment, XmlDecl, XmlDoctype, XmlElement,
+    XmlProcessingInstruction, XmlSeq, XmlText, XmlValue, XmlValueParseError,
+};
+
+#[test]
+fn nested_elements() {
+    let actual = XmlValue::from_str(
+        r#"
+        <note id="1">
+          <to>Tove</to>
+          <body><![CDATA[<b>Hi</b>]]> &amp; bye<!-- draft --></body>
+        </note>
+        "#,
+    )
+    .unwrap();
+
+    let expected = XmlElement::new(name("note"))
+        .with_attribute(XmlAttribute::new(name("id"), "1"))
+        .with_child(XmlElement::new(name("to")).with_child(XmlText::new("Tove")))
+        .with_child(XmlElement::new(name("body")).with_children([
+            XmlChild::CData(XmlCData::new("<b>Hi</b>")),
+            XmlChild::Text(XmlText::new(" & bye")),
+            XmlChild::Comment(XmlComment::new(" draft ")),
+        ]));
+
+    assert_eq!(actual, XmlValue::Element(expected));
+}
+
+#[test]
+fn namespaces() {
+    let actual = XmlValue::from_str(
+        r#"<root xmlns="http://example.com/a" xmlns:b="http://example.com/b" b:attr="x" plain="y"><b:child/><child xmlns=""/></root>"#,
+    )
+    .unwrap();
+
+    let expected = XmlElement::new(name("{http://example.com/a}root"))
+        .with_attribute(XmlAttribute::new(name("{http://example.com/b}attr"), "x"))
+        .with_attribute(XmlAttribute::new(name("plain"), "y"))
+        .with_child(XmlElement::new(name("{http://example.com/b}child")))
+        .with_child(XmlElement::new(name("child")));
+
+    assert_eq!(actual, XmlValue::Element(expected));
+}
+
+#[test]
+fn document() {
+    let actual = XmlValue::from_str(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE note [<!ELEMENT note (#PCDATA)>]>
+<?xml-stylesheet href="style.css"?>
+<note>&#72;&#x69;</note>
+"#,
+    )
+    .unwrap();
+
+    let expected = XmlValue::Seq(XmlSeq::from_iter([
+        XmlValue::Decl(XmlDecl::new("1.0", Some("UTF-8"), None)),
+        XmlValue::Doctype(XmlDoctype::new("note [<!ELEMENT note (#PCDATA)>]")),
+        XmlValue::PI(XmlProcessingInstruction::new(
+            "xml-stylesheet",
+            r#"href="style.css""#,
+        )),
+        XmlValue::Element(XmlElement::new(name("note")).with_child(XmlText::new("Hi"))),
+    ]));
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn text_and_empty() {
+    assert_eq!(
+        XmlValue::from_str("Hello").unwrap(),
+        XmlValue::Text(XmlText::new("Hello"))
+    );
+    assert_eq!(XmlValue::from_str("  \n ").unwrap(), XmlValue::None);
+}
+
+#[test]
+fn partial_eq_str() {
+    let value = XmlValue::Element(
+        XmlElement::new(name("to"))
+            .with_attribute(XmlAttribute::new(name("lang"), "en"))
+            .with_child(XmlText::new("Tove")),
+    );
+
+    assert_eq!(value, r#"<to lang='en'>Tove</to>"#);
+    assert_ne!(value, "<to>Jani</to>");
+    assert_ne!(value, "<to>");
+}
+
+#[test]
+fn errors() {
+    assert_eq!(
+        XmlValue::from_str("<a></b>").unwrap_err(),
+        XmlValueParseError::MismatchedEndTag {
+            position: 3,
+            expected: "a".to_string(),
+            found: "b".to_string(),
+        }
+    );
+    assert_eq!(
+        XmlValue::from_str("<a><x:b/></a>").unwrap_err(),
+        XmlValueParseError::UnboundPrefix {
+            position: 4,
+            prefix: "x".to_string(),
+        }
+    );
+    assert_eq!(
+        XmlValue::from_str("<a>&nbsp;</a>").unwrap_err(),
+        XmlValueParseError::UnknownEntity {
+            position: 3,
+            name: "nbsp".to_string(),
+        }
+    );
+    assert_eq!(
+        XmlValue::from_str("<a>").unwrap_err(),
+        XmlValueParseError::UnexpectedEof
+    );
+    assert_eq!(
+        XmlValue::from_str("<a><?xml version=\"1.0\"?></a>").unwrap_err(),
+        XmlValueParseError::MisplacedNode {
+            position: 3,
+            node: "XML declaration",
+        }
+    );
+}
+
+#[test]
+fn not_well_formed() {
+    assert_eq!(
+        XmlValue::from_str("<a x='1' x='2'/>").unwrap_err(),
+        XmlValueParseError::DuplicateAttribute {
+            position: 9,
+            name: "x".to_string(),
+        }
+    );
+    assert_eq!(
+        XmlValue::from_str(
+            r#"<a xmlns:p="http://example.com" xmlns:q="http://example.com" p:x="1" q:x="2"/>"#
+        )
+        .unwrap_err(),
+        XmlValueParseError::DuplicateAttribute {
+            position: 69,
+            name: "q:x".to_string(),
+        }
+    );
+    assert_eq!(
+        XmlValue::from_str("<a>\0</a>").unwrap_err(),
+        XmlValueParseError::UnexpectedChar {
+            position: 3,
+            found: '\0',
+        }
+    );
+    assert_eq!(
+        XmlValue::from_str("<a>&#0;</a>").unwrap_err(),
+        XmlValueParseError::UnknownEntity {
+            position: 3,
+            name: "#0".to_string(),
+        }
+    );
+    assert_eq!(
+        XmlValue::from_str("<a>b]]>c</a>").unwrap_err(),
+        XmlValueParseError::UnexpectedChar {
+            position: 6,
+            found: '>',
+        }
+    );
+    assert_eq!(
+        XmlValue::from_str("<!-- a -- b -->").unwrap_err(),
+        XmlValueParseError::UnexpectedChar {
+            position: 8,
+            found: '-',
+        }
+    );
+    assert_eq!(
+        XmlValue::from_str("<!-- a --->").unwrap_err(),
+        XmlValueParseError::UnexpectedChar {
+            position: 8,
+            found: '-',
+        }
+    );
+    assert_eq!(
+        XmlValue::from_str("<a x='<'/>").unwrap_err(),
+        XmlValueParseError::UnexpectedChar {
+            position: 6,
+            found: '<',
+        }
+    );
+}
+
+#[test]
+fn split_cdata() {
+    assert_eq!(
+        XmlValue::from_str("<![CDATA[a]]]]><![CDATA[>b]]]]><![CDATA[>]]>").unwrap(),
+        XmlValue::CData(XmlCData::new("a]]>b]]>"))
+    );
+
+    // Only sections that were split at `]]>` are joined.
+    assert_eq!(
+        XmlValue::from_str("<a><![CDATA[a]]><![CDATA[>b]]><![CDATA[c]]]]><![CDATA[d]]></a>")
+            .unwrap(),
+        XmlValue::Element(XmlElement::new(name("a")).with_children([
+            XmlCData::new("a"),
+            XmlCData::new(">b"),
+            XmlCData::new("c]]"),
+            XmlCData::new("d"),
+        ]))
+    );
+}
+
+#[test]
+fn recursion_limit() {
+    let nested = |depth: usize| format!("{}{}", "<a>".repeat(depth), "</a>".repeat(depth));
+
+    assert!(XmlValue::from_str(&nested(XmlValue::DEFAULT_RECURSION_LIMIT)).is_ok());
+    assert_eq!(
+        XmlValue::from_str(&nested(200_000)).unwrap_err(),
+        XmlValueParseError::RecursionLimitExceeded {
+            position: 3 * XmlValue::DEFAULT_RECURSION_LIMIT,
+            limit: XmlValue::DEFAULT_RECURSION_LIMIT,
+        }
+    );
+    assert!(XmlValue::from_str_with_recursion_limit(
+        &nested(XmlValue::DEFAULT_RECURSION_LIMIT + 1),
+        None
+    )
+    .is_ok());
+}